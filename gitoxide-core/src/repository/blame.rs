@@ -6,6 +6,7 @@ pub fn blame_file(
     mut repo: gix::Repository,
     file: &OsStr,
     options: gix::blame::Options,
+    mut progress: impl gix::Progress,
     out: impl std::io::Write,
     err: Option<&mut dyn std::io::Write>,
 ) -> anyhow::Result<()> {
@@ -38,13 +39,17 @@ pub fn blame_file(
     let suspect: gix::ObjectId = repo.head()?.into_peeled_id()?.into();
     let cache: Option<gix::commitgraph::Graph> = repo.commit_graph_if_enabled()?;
     let mut resource_cache = repo.diff_resource_cache_for_tree_diff()?;
-    let outcome = gix::blame::file(
+    progress.set_name("traverse commits".into());
+    let outcome = gix::blame::file_incremental(
         &repo.objects,
         suspect,
         cache,
         &mut resource_cache,
         file.as_bstr(),
         options,
+        |_entry| {},
+        &mut progress,
+        &gix::interrupt::IS_INTERRUPTED,
     )?;
     let statistics = outcome.statistics;
     show_blame_entries(out, outcome, file)?;
@@ -15,10 +15,12 @@ pub fn commit(
     err: &mut dyn std::io::Write,
     ours: BString,
     theirs: BString,
+    strategy: gix::merge::commit::Strategy,
     Options {
         format,
         file_favor,
         tree_favor,
+        strategy_options,
         in_memory,
         debug,
         message: _,
@@ -35,10 +37,13 @@ pub fn commit(
     let (ours_ref, ours_id) = refname_and_commit(&repo, ours)?;
     let (theirs_ref, theirs_id) = refname_and_commit(&repo, theirs)?;
 
-    let options = repo
+    let mut options = repo
         .tree_merge_options()?
         .with_file_favor(file_favor)
         .with_tree_favor(tree_favor);
+    for option in &strategy_options {
+        options = options.with_strategy_option(gix::merge::tree::StrategyOption::try_from(option.as_bstr())?);
+    }
     let ours_id_str = ours_id.to_string();
     let theirs_id_str = theirs_id.to_string();
     let labels = gix::merge::blob::builtin_driver::text::Labels {
@@ -53,7 +58,12 @@ pub fn commit(
             .into(),
     };
     let res = repo
-        .merge_commits(ours_id, theirs_id, labels, options.into())?
+        .merge_commits(
+            ours_id,
+            theirs_id,
+            labels,
+            gix::merge::commit::Options::from(options).with_strategy(strategy),
+        )?
         .tree_merge;
     let has_conflicts = res.conflicts.is_empty();
     let has_unresolved_conflicts = res.has_unresolved_conflicts(TreatAsUnresolved::default());
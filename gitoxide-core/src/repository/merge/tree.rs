@@ -4,6 +4,7 @@ pub struct Options {
     pub format: OutputFormat,
     pub file_favor: Option<gix::merge::tree::FileFavor>,
     pub tree_favor: Option<gix::merge::tree::TreeFavor>,
+    pub strategy_options: Vec<gix::bstr::BString>,
     pub in_memory: bool,
     pub debug: bool,
     pub message: Option<String>,
@@ -36,6 +37,7 @@ pub(super) mod function {
             format,
             file_favor,
             tree_favor,
+            strategy_options,
             in_memory,
             debug,
             message,
@@ -59,10 +61,13 @@ pub(super) mod function {
         let (ours_ref, ours_id) = refname_and_tree(&repo, ours)?;
         let (theirs_ref, theirs_id) = refname_and_tree(&repo, theirs)?;
 
-        let options = repo
+        let mut options = repo
             .tree_merge_options()?
             .with_file_favor(file_favor)
             .with_tree_favor(tree_favor);
+        for option in &strategy_options {
+            options = options.with_strategy_option(gix::merge::tree::StrategyOption::try_from(option.as_bstr())?);
+        }
         let base_id_str = base_id.to_string();
         let ours_id_str = ours_id.to_string();
         let theirs_id_str = theirs_id.to_string();
@@ -0,0 +1,420 @@
+/// Options for [`apply()`](crate::apply()).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Options {
+    /// If `true`, apply the patch in reverse, like `git apply --reverse` does.
+    pub reverse: bool,
+    /// If `Some(n)`, allow hunks to apply even if only `n` lines of leading and trailing context match,
+    /// like `git apply -C<n>` does.
+    /// Context is reduced one line at a time only if a hunk can't be applied with all of its context.
+    ///
+    /// If `None`, all context has to match.
+    pub min_context: Option<usize>,
+    /// What to do with whitespace errors on added lines, like `git apply --whitespace=<action>`.
+    pub whitespace: Whitespace,
+    /// If `true`, ignore changes in whitespace when matching context and removed lines,
+    /// like `git apply --ignore-whitespace` does.
+    /// Context lines are always taken from the file the patch is applied to.
+    pub ignore_whitespace: bool,
+    /// The kind of hash used by the repository, to verify that binary patches with an
+    /// [`old_id`](crate::FilePatch::old_id) are applied to the version of the file they were created from, like Git does.
+    ///
+    /// If `None`, binary patches are applied without verification.
+    pub object_hash: Option<gix_hash::Kind>,
+}
+
+/// What to do with whitespace errors on added lines, like the action of `git apply --whitespace=<action>`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Whitespace {
+    /// Don't check for whitespace errors.
+    Nowarn,
+    /// Report whitespace errors in the [outcome](Outcome::whitespace_errors), but apply the patch as is.
+    #[default]
+    Warn,
+    /// Fix whitespace errors on added lines, and report what was fixed in the [outcome](Outcome::whitespace_errors).
+    /// Lines are also compared with trailing whitespace removed to find where hunks apply.
+    Fix,
+    /// Fail on the first whitespace error.
+    Error,
+    /// Fail if there are whitespace errors, but report all of them.
+    ErrorAll,
+}
+
+/// The kind of a [`WhitespaceError`], matching the default whitespace checks of Git.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WhitespaceErrorKind {
+    /// There are spaces or tabs at the end of the line.
+    BlankAtEol,
+    /// A space precedes a tab in the indentation of the line.
+    SpaceBeforeTab,
+    /// Blank lines were added at the end of the file.
+    BlankAtEof,
+}
+
+/// A whitespace error on an added line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WhitespaceError {
+    /// The index of the hunk that added the line.
+    pub hunk: usize,
+    /// The one-based line number of the added line in the patched file,
+    /// or the line at which blank lines would have been added if they were removed to fix the error.
+    pub line: usize,
+    /// The kind of the error.
+    pub kind: WhitespaceErrorKind,
+}
+
+/// Information about how a hunk was applied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HunkOutcome {
+    /// The amount of lines between the position the hunk was applied at and the position stated in its header.
+    pub offset: isize,
+    /// The amount of context lines that were ignored to make the hunk apply.
+    pub context_reduced: usize,
+}
+
+/// The outcome of [`apply()`](crate::apply()).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The content of the file after applying the patch, which is empty if the file is deleted.
+    pub content: Vec<u8>,
+    /// Information about each hunk of a text patch in order, or nothing for binary patches.
+    pub hunks: Vec<HunkOutcome>,
+    /// Whitespace errors on added lines, which are fixed if [`Whitespace::Fix`] is used.
+    pub whitespace_errors: Vec<WhitespaceError>,
+}
+
+/// The error returned by [`apply()`](crate::apply()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Hunk #{} starting at line {line} does not apply", hunk + 1)]
+    HunkMismatch { hunk: usize, line: u32 },
+    #[error("The file still has content after applying a patch that deletes it")]
+    DeletedFileNotEmpty,
+    #[error("The binary patch has no data that could be applied")]
+    BinaryWithoutData,
+    #[error("The binary patch was created for version {expected} of the file, but it is at {actual}")]
+    BinaryPreimageMismatch {
+        expected: gix_hash::Prefix,
+        actual: gix_hash::ObjectId,
+    },
+    #[error("The binary delta does not apply to the file")]
+    BinaryDelta,
+    #[error(transparent)]
+    Hash(#[from] gix_hash::hasher::Error),
+    #[error("Found {} whitespace error(s) in added lines", errors.len())]
+    WhitespaceErrors { errors: Vec<WhitespaceError> },
+}
+
+pub(super) mod function {
+    use std::borrow::Cow;
+
+    use bstr::{BString, ByteSlice};
+
+    use super::{Error, HunkOutcome, Options, Outcome, Whitespace, WhitespaceError, WhitespaceErrorKind};
+    use crate::{Binary, BinaryData, Body, FilePatch, Hunk, Line, Operation, binary};
+
+    /// Apply `patch` to `content`, the content of the file before the change, configured by `options`.
+    ///
+    /// Hunks are searched for near the line stated in their header, and may apply with an offset if lines were
+    /// added or removed elsewhere, just like Git does.
+    /// Note that the caller is responsible for checking that files to create don't exist yet,
+    /// and for handling changes to paths and modes.
+    pub fn apply(content: &[u8], patch: &FilePatch, options: Options) -> Result<Outcome, Error> {
+        let reversed;
+        let patch = if options.reverse {
+            reversed = patch.reversed();
+            &reversed
+        } else {
+            patch
+        };
+        let outcome = match &patch.body {
+            Body::Text(hunks) => apply_hunks(content, hunks, options)?,
+            Body::Binary(binary) => Outcome {
+                content: apply_binary(content, patch, binary.as_ref(), options)?,
+                hunks: Vec::new(),
+                whitespace_errors: Vec::new(),
+            },
+        };
+        if patch.operation == Operation::Delete && !outcome.content.is_empty() {
+            return Err(Error::DeletedFileNotEmpty);
+        }
+        Ok(outcome)
+    }
+
+    fn apply_binary(
+        content: &[u8],
+        patch: &FilePatch,
+        binary: Option<&Binary>,
+        options: Options,
+    ) -> Result<Vec<u8>, Error> {
+        let binary = binary.ok_or(Error::BinaryWithoutData)?;
+        if let Some((expected, object_hash)) = patch.old_id.zip(options.object_hash) {
+            let actual = gix_object::compute_hash(object_hash, gix_object::Kind::Blob, content)?;
+            if expected.cmp_oid(&actual).is_ne() {
+                return Err(Error::BinaryPreimageMismatch { expected, actual });
+            }
+        }
+        match &binary.forward {
+            BinaryData::Literal(data) => Ok(data.clone()),
+            BinaryData::Delta(delta) => binary::apply_delta(content, delta).ok_or(Error::BinaryDelta),
+        }
+    }
+
+    fn apply_hunks(content: &[u8], hunks: &[Hunk], options: Options) -> Result<Outcome, Error> {
+        let mut image: Vec<BString> = content.lines_with_terminator().map(Into::into).collect();
+        let mut outcomes = Vec::with_capacity(hunks.len());
+        let mut whitespace_errors = Vec::new();
+        for (hunk_index, hunk) in hunks.iter().enumerate() {
+            let (pos, lines, context_reduced) = find_hunk(&image, hunk, options).ok_or(Error::HunkMismatch {
+                hunk: hunk_index,
+                line: hunk.old_start,
+            })?;
+            let expected_pos = hunk.new_start.saturating_sub(1) as usize;
+            outcomes.push(HunkOutcome {
+                offset: pos as isize - expected_pos as isize,
+                context_reduced,
+            });
+
+            let preimage_len = lines.iter().filter(|line| !matches!(line, Line::Add(_))).count();
+            let at_end = pos + preimage_len == image.len();
+            let mut postimage = Vec::with_capacity(lines.len());
+            let mut image_lines = image[pos..pos + preimage_len].iter();
+            for line in lines {
+                match line {
+                    Line::Context(_) => postimage.push(image_lines.next().expect("matched").clone()),
+                    Line::Remove(_) => {
+                        image_lines.next();
+                    }
+                    Line::Add(added) => {
+                        let mut added = Cow::Borrowed(added);
+                        if options.whitespace != Whitespace::Nowarn {
+                            for kind in whitespace_errors_of(&added) {
+                                whitespace_errors.push(WhitespaceError {
+                                    hunk: hunk_index,
+                                    line: pos + postimage.len() + 1,
+                                    kind,
+                                });
+                                if options.whitespace == Whitespace::Error {
+                                    return Err(Error::WhitespaceErrors {
+                                        errors: whitespace_errors,
+                                    });
+                                }
+                            }
+                            if options.whitespace == Whitespace::Fix {
+                                added = Cow::Owned(fix_whitespace(&added));
+                            }
+                        }
+                        postimage.push(added.into_owned());
+                    }
+                }
+            }
+
+            let adds_at_end = at_end && matches!(lines.last(), Some(Line::Add(_)));
+            if adds_at_end && options.whitespace != Whitespace::Nowarn {
+                let num_blank = postimage
+                    .iter()
+                    .rev()
+                    .take_while(|line| line.trim_with(|c| c.is_ascii_whitespace()).is_empty())
+                    .count()
+                    .min(
+                        lines
+                            .iter()
+                            .rev()
+                            .take_while(|line| matches!(line, Line::Add(_)))
+                            .count(),
+                    );
+                if num_blank > 0 {
+                    let first_blank = postimage.len() - num_blank;
+                    whitespace_errors.push(WhitespaceError {
+                        hunk: hunk_index,
+                        line: pos + first_blank + 1,
+                        kind: WhitespaceErrorKind::BlankAtEof,
+                    });
+                    match options.whitespace {
+                        Whitespace::Error => {
+                            return Err(Error::WhitespaceErrors {
+                                errors: whitespace_errors,
+                            });
+                        }
+                        Whitespace::Fix => postimage.truncate(first_blank),
+                        Whitespace::Nowarn | Whitespace::Warn | Whitespace::ErrorAll => {}
+                    }
+                }
+            }
+            image.splice(pos..pos + preimage_len, postimage);
+        }
+
+        if options.whitespace == Whitespace::ErrorAll && !whitespace_errors.is_empty() {
+            return Err(Error::WhitespaceErrors {
+                errors: whitespace_errors,
+            });
+        }
+        Ok(Outcome {
+            content: image.concat(),
+            hunks: outcomes,
+            whitespace_errors,
+        })
+    }
+
+    /// Find the position at which `hunk` applies to `image`, and return it along with the lines of the hunk to apply,
+    /// which may have less context than the hunk, and the amount of removed context lines.
+    ///
+    /// This follows what `git apply` does: hunks that start at the first line have to match at the beginning,
+    /// and hunks without trailing context have to match at the end, unless that fails.
+    /// Then context is reduced, down to the minimum allowed by `options`.
+    fn find_hunk<'a>(image: &[BString], hunk: &'a Hunk, options: Options) -> Option<(usize, &'a [Line], usize)> {
+        let mut lines = hunk.lines.as_slice();
+        let mut leading = lines.iter().take_while(|line| matches!(line, Line::Context(_))).count();
+        let mut trailing = if leading == lines.len() {
+            0
+        } else {
+            lines
+                .iter()
+                .rev()
+                .take_while(|line| matches!(line, Line::Context(_)))
+                .count()
+        };
+        let mut match_beginning = hunk.old_start <= 1;
+        let mut match_end = trailing == 0;
+        let mut pos = hunk.new_start.saturating_sub(1) as usize;
+        let mut context_reduced = 0;
+        let min_context = options.min_context.unwrap_or(usize::MAX);
+
+        loop {
+            let preimage: Vec<_> = lines
+                .iter()
+                .filter_map(|line| match line {
+                    Line::Context(line) | Line::Remove(line) => Some(line),
+                    Line::Add(_) => None,
+                })
+                .collect();
+            if let Some(found) = find_position(image, &preimage, pos, match_beginning, match_end, options) {
+                return Some((found, lines, context_reduced));
+            }
+            if leading <= min_context && trailing <= min_context {
+                return None;
+            }
+            if match_beginning || match_end {
+                match_beginning = false;
+                match_end = false;
+                continue;
+            }
+            if leading >= trailing {
+                lines = &lines[1..];
+                pos = pos.saturating_sub(1);
+                leading -= 1;
+                context_reduced += 1;
+            }
+            if trailing > leading {
+                lines = &lines[..lines.len() - 1];
+                trailing -= 1;
+                context_reduced += 1;
+            }
+        }
+    }
+
+    /// Search for `preimage` in `image`, starting at `pos` and moving outward in both directions.
+    fn find_position(
+        image: &[BString],
+        preimage: &[&BString],
+        pos: usize,
+        match_beginning: bool,
+        match_end: bool,
+        options: Options,
+    ) -> Option<usize> {
+        let last_pos = image.len().checked_sub(preimage.len())?;
+        let matches_at = |pos: usize| {
+            (!match_end || pos == last_pos)
+                && image[pos..pos + preimage.len()]
+                    .iter()
+                    .zip(preimage)
+                    .all(|(actual, expected)| lines_match(actual, expected, options))
+        };
+        if match_beginning {
+            return matches_at(0).then_some(0);
+        }
+        if match_end {
+            return matches_at(last_pos).then_some(last_pos);
+        }
+
+        let pos = pos.min(last_pos);
+        (0..=pos.max(last_pos - pos))
+            .flat_map(|distance| {
+                let backward = pos.checked_sub(distance);
+                let forward = (distance != 0).then(|| pos + distance).filter(|pos| *pos <= last_pos);
+                backward.into_iter().chain(forward)
+            })
+            .find(|pos| matches_at(*pos))
+    }
+
+    fn lines_match(actual: &[u8], expected: &[u8], options: Options) -> bool {
+        if actual == expected {
+            return true;
+        }
+        if options.ignore_whitespace {
+            let words = |line: &[u8]| {
+                line.split(u8::is_ascii_whitespace)
+                    .filter(|word| !word.is_empty())
+                    .map(<[u8]>::to_owned)
+                    .collect::<Vec<_>>()
+            };
+            return words(actual) == words(expected);
+        }
+        if options.whitespace == Whitespace::Fix {
+            let (actual, actual_eol) = split_eol(actual);
+            let (expected, expected_eol) = split_eol(expected);
+            return actual_eol == expected_eol
+                && actual.trim_end_with(|c| c == ' ' || c == '\t')
+                    == expected.trim_end_with(|c| c == ' ' || c == '\t');
+        }
+        false
+    }
+
+    /// Split `line` into its content and its line ending.
+    fn split_eol(line: &[u8]) -> (&[u8], &[u8]) {
+        let content_len = if line.ends_with(b"\r\n") {
+            line.len() - 2
+        } else if line.ends_with(b"\n") {
+            line.len() - 1
+        } else {
+            line.len()
+        };
+        line.split_at(content_len)
+    }
+
+    /// The length of the indentation of `line`.
+    fn indent_len(line: &[u8]) -> usize {
+        line.iter().take_while(|b| **b == b' ' || **b == b'\t').count()
+    }
+
+    fn whitespace_errors_of(line: &[u8]) -> impl Iterator<Item = WhitespaceErrorKind> {
+        let (content, _eol) = split_eol(line);
+        let blank_at_eol = content.ends_with(b" ") || content.ends_with(b"\t");
+        let space_before_tab = content[..indent_len(content)].find(b" \t").is_some();
+        blank_at_eol
+            .then_some(WhitespaceErrorKind::BlankAtEol)
+            .into_iter()
+            .chain(space_before_tab.then_some(WhitespaceErrorKind::SpaceBeforeTab))
+    }
+
+    /// Remove trailing whitespace, and turn indentation up to the last tab into tabs like `git apply --whitespace=fix`.
+    fn fix_whitespace(line: &[u8]) -> BString {
+        let (content, eol) = split_eol(line);
+        let content = content.trim_end_with(|c| c == ' ' || c == '\t');
+        let indent = &content[..indent_len(content)];
+        let mut out = BString::default();
+        match indent.rfind_byte(b'\t').filter(|_| indent.find(b" \t").is_some()) {
+            Some(last_tab) => {
+                let mut column = 0;
+                for b in &indent[..=last_tab] {
+                    column = if *b == b'\t' { (column / 8 + 1) * 8 } else { column + 1 };
+                }
+                out.extend(std::iter::repeat_n(b'\t', column / 8));
+                out.extend_from_slice(&content[last_tab + 1..]);
+            }
+            None => out.extend_from_slice(content),
+        }
+        out.extend_from_slice(eol);
+        out
+    }
+}
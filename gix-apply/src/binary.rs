@@ -0,0 +1,89 @@
+/// The alphabet Git uses for base85 encoding in binary patches.
+const BASE85: &[u8; 85] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz!#$%&()*+-;<=>?@^_`{|}~";
+
+/// Decode a single line of base85 data of a binary patch, without its line ending, and append the decoded bytes to `out`.
+///
+/// The first character encodes the amount of decoded bytes, `A` to `Z` for 1 to 26 and `a` to `z` for 27 to 52.
+pub(crate) fn decode_base85_line(line: &[u8], out: &mut Vec<u8>) -> Option<()> {
+    let (&len, data) = line.split_first()?;
+    let len = match len {
+        b'A'..=b'Z' => len - b'A' + 1,
+        b'a'..=b'z' => len - b'a' + 27,
+        _ => return None,
+    } as usize;
+    if data.len() != len.div_ceil(4) * 5 {
+        return None;
+    }
+    let start = out.len();
+    for group in data.chunks(5) {
+        let mut value = 0u32;
+        for &c in group {
+            let digit = BASE85.iter().position(|&b| b == c)? as u32;
+            value = value.checked_mul(85)?.checked_add(digit)?;
+        }
+        out.extend_from_slice(&value.to_be_bytes());
+    }
+    out.truncate(start + len);
+    Some(())
+}
+
+/// Inflate the zlib-compressed `data`, expecting exactly `size` bytes.
+pub(crate) fn inflate(data: &[u8], size: usize) -> Option<Vec<u8>> {
+    let mut out = vec![0; size];
+    let mut inflate = gix_zlib::Inflate::default();
+    let (status, consumed, written) = inflate.once(data, &mut out).ok()?;
+    (status == gix_zlib::Status::StreamEnd && consumed == data.len() && written == size).then_some(out)
+}
+
+/// Apply a `delta` in the format Git uses in packs to `base`, or return `None` if it is malformed or doesn't fit `base`.
+pub(crate) fn apply_delta(base: &[u8], delta: &[u8]) -> Option<Vec<u8>> {
+    fn size(delta: &mut &[u8]) -> Option<usize> {
+        let mut out = 0usize;
+        let mut shift = 0;
+        loop {
+            let (&byte, rest) = delta.split_first()?;
+            *delta = rest;
+            out |= ((byte & 0x7f) as usize).checked_shl(shift)?;
+            shift += 7;
+            if byte & 0x80 == 0 {
+                return Some(out);
+            }
+        }
+    }
+
+    let mut delta = delta;
+    if size(&mut delta)? != base.len() {
+        return None;
+    }
+    let result_size = size(&mut delta)?;
+    let mut out = Vec::with_capacity(result_size);
+    while let Some((&cmd, rest)) = delta.split_first() {
+        delta = rest;
+        if cmd & 0x80 != 0 {
+            let mut read = |bits: std::ops::Range<u8>| -> Option<usize> {
+                let mut value = 0usize;
+                for (shift, bit) in bits.clone().enumerate() {
+                    if cmd & (1 << bit) != 0 {
+                        let (&byte, rest) = delta.split_first()?;
+                        delta = rest;
+                        value |= (byte as usize) << (shift * 8);
+                    }
+                }
+                Some(value)
+            };
+            let offset = read(0..4)?;
+            let size = match read(4..7)? {
+                0 => 0x10000,
+                size => size,
+            };
+            out.extend_from_slice(base.get(offset..offset.checked_add(size)?)?);
+        } else if cmd != 0 {
+            let (data, rest) = delta.split_at_checked(cmd as usize)?;
+            out.extend_from_slice(data);
+            delta = rest;
+        } else {
+            return None;
+        }
+    }
+    (out.len() == result_size).then_some(out)
+}
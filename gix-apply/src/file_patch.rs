@@ -0,0 +1,70 @@
+use bstr::BStr;
+
+use crate::{Binary, Body, FilePatch, Hunk, Line, Operation};
+
+impl FilePatch {
+    /// Return the path of the file after the change, or before the change if it is deleted.
+    pub fn path(&self) -> &BStr {
+        self.new_path
+            .as_ref()
+            .or(self.old_path.as_ref())
+            .map(AsRef::as_ref)
+            .expect("parsing assures there is at least one path")
+    }
+
+    /// Return a patch that undoes this patch, like `git apply --reverse` would apply it.
+    ///
+    /// Binary patches can only be reversed if they contain [reverse data](Binary::reverse), and will have no data otherwise.
+    /// Note that copies can't be undone and turn into creations of the copied file.
+    pub fn reversed(&self) -> FilePatch {
+        let operation = match self.operation {
+            Operation::Modify => Operation::Modify,
+            Operation::Create | Operation::Copy => Operation::Delete,
+            Operation::Delete => Operation::Create,
+            Operation::Rename => Operation::Rename,
+        };
+        let (old_path, new_path) = match self.operation {
+            Operation::Copy => (self.new_path.clone(), None),
+            _ => (self.new_path.clone(), self.old_path.clone()),
+        };
+        let body = match &self.body {
+            Body::Text(hunks) => Body::Text(
+                hunks
+                    .iter()
+                    .map(|hunk| Hunk {
+                        old_start: hunk.new_start,
+                        old_lines: hunk.new_lines,
+                        new_start: hunk.old_start,
+                        new_lines: hunk.old_lines,
+                        lines: hunk
+                            .lines
+                            .iter()
+                            .map(|line| match line {
+                                Line::Context(line) => Line::Context(line.clone()),
+                                Line::Remove(line) => Line::Add(line.clone()),
+                                Line::Add(line) => Line::Remove(line.clone()),
+                            })
+                            .collect(),
+                    })
+                    .collect(),
+            ),
+            Body::Binary(binary) => Body::Binary(binary.as_ref().and_then(|binary| {
+                binary.reverse.clone().map(|reverse| Binary {
+                    forward: reverse,
+                    reverse: Some(binary.forward.clone()),
+                })
+            })),
+        };
+        FilePatch {
+            old_path,
+            new_path,
+            old_mode: self.new_mode,
+            new_mode: self.old_mode.filter(|_| operation != Operation::Delete),
+            operation,
+            similarity: self.similarity,
+            old_id: self.new_id,
+            new_id: self.old_id.filter(|_| operation != Operation::Delete),
+            body,
+        }
+    }
+}
@@ -0,0 +1,134 @@
+//! Parse and apply patches like [`git apply`](https://git-scm.com/docs/git-apply) does.
+//!
+//! * [`parse()`] turns unified diffs, including those with Git extended headers and binary patches, into [`FilePatch`]es.
+//! * [`apply()`] applies a [`FilePatch`] to the content of a file, with support for reversing patches,
+//!   reducing context and handling whitespace errors.
+//! * [`three_way()`] applies a [`FilePatch`] to the version of a file it was created from and merges the result
+//!   with the current version of the file, for when it doesn't apply cleanly.
+//!
+//! Applying patches to the worktree, the index or trees is left to the caller, as this crate only deals with file contents.
+//!
+//! ## Feature Flags
+#![cfg_attr(
+    all(doc, feature = "document-features"),
+    doc = ::document_features::document_features!()
+)]
+#![cfg_attr(all(doc, feature = "document-features"), feature(doc_cfg))]
+#![deny(missing_docs, rust_2018_idioms)]
+#![forbid(unsafe_code)]
+
+use bstr::BString;
+use gix_object::tree::EntryKind;
+
+/// The changes to a single file as described by a patch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilePatch {
+    /// The path of the file before the change, or `None` if the file is created.
+    pub old_path: Option<BString>,
+    /// The path of the file after the change, or `None` if the file is deleted.
+    pub new_path: Option<BString>,
+    /// The mode of the file before the change, if known from the extended header.
+    pub old_mode: Option<EntryKind>,
+    /// The mode of the file after the change, if known from the extended header.
+    pub new_mode: Option<EntryKind>,
+    /// What happens to the file.
+    pub operation: Operation,
+    /// The similarity of the source and destination of a rename or copy in percent, as stated in the extended header.
+    pub similarity: Option<u8>,
+    /// The possibly abbreviated id of the blob before the change as stated in the `index` line of the extended header,
+    /// or `None` if it wasn't stated or if the file didn't exist.
+    pub old_id: Option<gix_hash::Prefix>,
+    /// The possibly abbreviated id of the blob after the change as stated in the `index` line of the extended header,
+    /// or `None` if it wasn't stated or if the file doesn't exist anymore.
+    pub new_id: Option<gix_hash::Prefix>,
+    /// The changes to the content of the file.
+    pub body: Body,
+}
+
+/// What happens to a file in a [`FilePatch`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    /// The file is changed in place, or only its mode changes.
+    Modify,
+    /// The file is created.
+    Create,
+    /// The file is deleted.
+    Delete,
+    /// The file is moved from [`old_path`](FilePatch::old_path) to [`new_path`](FilePatch::new_path),
+    /// and possibly changed.
+    Rename,
+    /// The file at [`old_path`](FilePatch::old_path) is copied to [`new_path`](FilePatch::new_path),
+    /// and possibly changed.
+    Copy,
+}
+
+/// The changes to the content of a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Body {
+    /// Changes to a text file as hunks, which may be empty if only the mode or name of the file changes,
+    /// or if an empty file is created or deleted.
+    Text(Vec<Hunk>),
+    /// Changes to a binary file, or `None` if the patch only states that binary files differ without providing the data.
+    Binary(Option<Binary>),
+}
+
+/// A contiguous set of changes to a text file, along with surrounding context lines.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hunk {
+    /// The one-based number of the first line of the hunk in the old file, or `0` if the old file is empty.
+    pub old_start: u32,
+    /// The number of context and removed lines.
+    pub old_lines: u32,
+    /// The one-based number of the first line of the hunk in the new file, or `0` if the new file is empty.
+    pub new_start: u32,
+    /// The number of context and added lines.
+    pub new_lines: u32,
+    /// All lines of the hunk in order.
+    pub lines: Vec<Line>,
+}
+
+/// A line of a [`Hunk`], including its line ending unless it's the last line of a file without newline at the end.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Line {
+    /// A line that is the same before and after the change.
+    Context(BString),
+    /// A line that exists only before the change.
+    Remove(BString),
+    /// A line that exists only after the change.
+    Add(BString),
+}
+
+/// The changes to a binary file, as stored in a `GIT binary patch`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Binary {
+    /// The data to turn the old version into the new version.
+    pub forward: BinaryData,
+    /// The data to turn the new version into the old version, which Git always provides, but which may be missing otherwise.
+    pub reverse: Option<BinaryData>,
+}
+
+/// Decoded data of a binary patch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BinaryData {
+    /// The entire content of the resulting file.
+    Literal(Vec<u8>),
+    /// Instructions to produce the resulting file by copying ranges of the source file and inserting new data,
+    /// in the format Git uses in packs.
+    Delta(Vec<u8>),
+}
+
+mod file_patch;
+
+///
+pub mod parse;
+pub use parse::function::parse;
+
+///
+pub mod apply;
+pub use apply::function::apply;
+
+///
+pub mod three_way;
+pub use three_way::function::three_way;
+
+mod binary;
@@ -0,0 +1,464 @@
+use bstr::BString;
+
+/// Options for [`parse()`](crate::parse()).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Options {
+    /// The number of leading path components to remove from the paths in diff headers, like `git apply -p<n>` does.
+    ///
+    /// It defaults to `1`, which removes the `a/` and `b/` prefixes of paths in diffs produced by Git.
+    /// Paths in `rename` and `copy` lines of Git extended headers are never stripped, as they never have a prefix.
+    pub strip: usize,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options { strip: 1 }
+    }
+}
+
+/// The error returned by [`parse()`](crate::parse()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Invalid hunk header at line {line}")]
+    HunkHeader { line: usize },
+    #[error("The hunk starting at line {line} is truncated or has an invalid line")]
+    TruncatedHunk { line: usize },
+    #[error("Invalid file mode at line {line}")]
+    Mode { line: usize },
+    #[error("Invalid object id at line {line}")]
+    ObjectId { line: usize },
+    #[error("Invalid similarity index at line {line}")]
+    Similarity { line: usize },
+    #[error("Could not unquote the path at line {line}")]
+    Unquote {
+        line: usize,
+        source: gix_quote::ansi_c::undo::Error,
+    },
+    #[error("The patch starting at line {line} lacks filename information")]
+    MissingPath { line: usize },
+    #[error("Cannot remove {strip} leading path components from '{path}' at line {line}")]
+    Strip { line: usize, path: BString, strip: usize },
+    #[error("Invalid binary patch at line {line}")]
+    Binary { line: usize },
+}
+
+pub(super) mod function {
+    use bstr::{BString, ByteSlice};
+
+    use super::{Error, Options};
+    use crate::{Binary, BinaryData, Body, FilePatch, Hunk, Line, Operation, binary};
+
+    /// Parse all patches for individual files in `input`, which is a unified diff possibly with Git extended headers
+    /// and binary patches, as produced by `git diff`, `git format-patch` or `diff -u`, and configured by `options`.
+    ///
+    /// Everything that isn't part of a patch, like the commit message of an email, is ignored.
+    pub fn parse(input: &[u8], options: Options) -> Result<Vec<FilePatch>, Error> {
+        let mut parser = Parser {
+            lines: input.lines_with_terminator().collect(),
+            pos: 0,
+            strip: options.strip,
+        };
+        let mut out = Vec::new();
+        while let Some(line) = parser.peek() {
+            if let Some(names) = line.strip_prefix(b"diff --git ") {
+                out.push(parser.git_patch(names)?);
+            } else if line.starts_with(b"--- ")
+                && parser.peek_at(1).is_some_and(|line| line.starts_with(b"+++ "))
+                && parser.peek_at(2).is_some_and(|line| line.starts_with(b"@@ -"))
+            {
+                out.push(parser.traditional_patch()?);
+            } else {
+                parser.pos += 1;
+            }
+        }
+        Ok(out)
+    }
+
+    struct Parser<'a> {
+        lines: Vec<&'a [u8]>,
+        pos: usize,
+        strip: usize,
+    }
+
+    impl<'a> Parser<'a> {
+        fn peek(&self) -> Option<&'a [u8]> {
+            self.peek_at(0)
+        }
+
+        fn peek_at(&self, offset: usize) -> Option<&'a [u8]> {
+            self.lines.get(self.pos + offset).copied()
+        }
+
+        /// The one-based number of the current line.
+        fn line_number(&self) -> usize {
+            self.pos + 1
+        }
+
+        fn git_patch(&mut self, names: &[u8]) -> Result<FilePatch, Error> {
+            let line = self.line_number();
+            let (old_path, new_path) = self.git_header_names(trim_eol(names), line)?;
+            self.pos += 1;
+            let mut patch = FilePatch {
+                old_path,
+                new_path,
+                old_mode: None,
+                new_mode: None,
+                operation: Operation::Modify,
+                similarity: None,
+                old_id: None,
+                new_id: None,
+                body: Body::Text(Vec::new()),
+            };
+
+            while let Some(header) = self.peek().map(trim_eol) {
+                let line = self.line_number();
+                if let Some(mode) = header.strip_prefix(b"old mode ") {
+                    patch.old_mode = Some(parse_mode(mode, line)?);
+                } else if let Some(mode) = header.strip_prefix(b"new mode ") {
+                    patch.new_mode = Some(parse_mode(mode, line)?);
+                } else if let Some(mode) = header.strip_prefix(b"deleted file mode ") {
+                    patch.old_mode = Some(parse_mode(mode, line)?);
+                    patch.operation = Operation::Delete;
+                } else if let Some(mode) = header.strip_prefix(b"new file mode ") {
+                    patch.new_mode = Some(parse_mode(mode, line)?);
+                    patch.operation = Operation::Create;
+                } else if let Some(path) = header
+                    .strip_prefix(b"rename from ")
+                    .or_else(|| header.strip_prefix(b"rename old "))
+                {
+                    patch.old_path = Some(unquote(path, line)?);
+                    patch.operation = Operation::Rename;
+                } else if let Some(path) = header
+                    .strip_prefix(b"rename to ")
+                    .or_else(|| header.strip_prefix(b"rename new "))
+                {
+                    patch.new_path = Some(unquote(path, line)?);
+                    patch.operation = Operation::Rename;
+                } else if let Some(path) = header.strip_prefix(b"copy from ") {
+                    patch.old_path = Some(unquote(path, line)?);
+                    patch.operation = Operation::Copy;
+                } else if let Some(path) = header.strip_prefix(b"copy to ") {
+                    patch.new_path = Some(unquote(path, line)?);
+                    patch.operation = Operation::Copy;
+                } else if let Some(similarity) = header.strip_prefix(b"similarity index ") {
+                    patch.similarity = Some(
+                        similarity
+                            .strip_suffix(b"%")
+                            .and_then(|value| value.to_str().ok()?.parse().ok())
+                            .filter(|value| *value <= 100)
+                            .ok_or(Error::Similarity { line })?,
+                    );
+                } else if header.starts_with(b"dissimilarity index ") {
+                } else if let Some(index) = header.strip_prefix(b"index ") {
+                    let (ids, mode) = match index.split_once_str(b" ") {
+                        Some((ids, mode)) => (ids, Some(parse_mode(mode, line)?)),
+                        None => (index, None),
+                    };
+                    let (old_id, new_id) = ids.split_once_str(b"..").ok_or(Error::ObjectId { line })?;
+                    patch.old_id = parse_id(old_id, line)?;
+                    patch.new_id = parse_id(new_id, line)?;
+                    if let Some(mode) = mode {
+                        patch.old_mode.get_or_insert(mode);
+                        patch.new_mode.get_or_insert(mode);
+                    }
+                } else {
+                    break;
+                }
+                self.pos += 1;
+            }
+
+            match self.peek() {
+                Some(line) if line.starts_with(b"--- ") => {
+                    let (old_path, new_path) = self.old_and_new_names()?;
+                    if patch.operation != Operation::Rename && patch.operation != Operation::Copy {
+                        patch.old_path = old_path.or(patch.old_path);
+                        patch.new_path = new_path.or(patch.new_path);
+                    }
+                    patch.body = Body::Text(self.hunks()?);
+                }
+                Some(line) if trim_eol(line) == b"GIT binary patch" => {
+                    self.pos += 1;
+                    let forward = self.binary_data()?.ok_or(Error::Binary {
+                        line: self.line_number(),
+                    })?;
+                    let reverse = self.binary_data()?;
+                    patch.body = Body::Binary(Some(Binary { forward, reverse }));
+                }
+                Some(line) if line.starts_with(b"Binary files ") => {
+                    self.pos += 1;
+                    patch.body = Body::Binary(None);
+                }
+                _ => {}
+            }
+
+            match patch.operation {
+                Operation::Create => patch.old_path = None,
+                Operation::Delete => patch.new_path = None,
+                Operation::Modify | Operation::Rename | Operation::Copy => {
+                    if patch.old_path.is_none() || patch.new_path.is_none() {
+                        return Err(Error::MissingPath { line });
+                    }
+                }
+            }
+            if patch.old_path.is_none() && patch.new_path.is_none() {
+                return Err(Error::MissingPath { line });
+            }
+            Ok(patch)
+        }
+
+        fn traditional_patch(&mut self) -> Result<FilePatch, Error> {
+            let line = self.line_number();
+            let (old_path, new_path) = self.old_and_new_names()?;
+            let operation = match (&old_path, &new_path) {
+                (None, Some(_)) => Operation::Create,
+                (Some(_), None) => Operation::Delete,
+                (Some(_), Some(_)) => Operation::Modify,
+                (None, None) => return Err(Error::MissingPath { line }),
+            };
+            Ok(FilePatch {
+                old_path,
+                new_path,
+                old_mode: None,
+                new_mode: None,
+                operation,
+                similarity: None,
+                old_id: None,
+                new_id: None,
+                body: Body::Text(self.hunks()?),
+            })
+        }
+
+        /// Parse the paths on the first line of a Git diff header, which are only unambiguous if they are quoted
+        /// or if they are the same.
+        fn git_header_names(&self, names: &[u8], line: usize) -> Result<(Option<BString>, Option<BString>), Error> {
+            if names.starts_with(b"\"") {
+                let (old, consumed) =
+                    gix_quote::ansi_c::undo(names.as_bstr()).map_err(|source| Error::Unquote { line, source })?;
+                let new = names[consumed..].trim_start();
+                let new = if new.starts_with(b"\"") {
+                    unquote(new, line)?
+                } else {
+                    new.into()
+                };
+                return Ok((Some(self.strip(&old, line)?), Some(self.strip(&new, line)?)));
+            }
+            if let Some(pos) = names.find(b" \"") {
+                let new = unquote(&names[pos + 1..], line)?;
+                return Ok((Some(self.strip(&names[..pos], line)?), Some(self.strip(&new, line)?)));
+            }
+            for pos in names.find_iter(b" ") {
+                let (Ok(old), Ok(new)) = (self.strip(&names[..pos], line), self.strip(&names[pos + 1..], line)) else {
+                    continue;
+                };
+                if old == new {
+                    return Ok((Some(old), Some(new)));
+                }
+            }
+            Ok((None, None))
+        }
+
+        /// Parse the `---` and `+++` lines, returning `None` for `/dev/null`.
+        fn old_and_new_names(&mut self) -> Result<(Option<BString>, Option<BString>), Error> {
+            let mut name = |prefix: &[u8]| -> Result<Option<BString>, Error> {
+                let line = self.line_number();
+                let name = self
+                    .peek()
+                    .and_then(|name| name.strip_prefix(prefix))
+                    .map(trim_eol)
+                    .ok_or(Error::MissingPath { line })?;
+                self.pos += 1;
+                let name = if name.starts_with(b"\"") {
+                    unquote(name, line)?
+                } else {
+                    // Traditional diffs may have a timestamp after a tab, and Git adds a tab after names with spaces.
+                    name.split_once_str(b"\t").map_or(name, |(name, _)| name).into()
+                };
+                if name == "/dev/null" {
+                    return Ok(None);
+                }
+                self.strip(&name, line).map(Some)
+            };
+            Ok((name(b"--- ")?, name(b"+++ ")?))
+        }
+
+        fn strip(&self, path: &[u8], line: usize) -> Result<BString, Error> {
+            let mut stripped = path;
+            for _ in 0..self.strip {
+                stripped = stripped
+                    .find_byte(b'/')
+                    .map(|pos| stripped[pos + 1..].trim_start_with(|c| c == '/'))
+                    .ok_or_else(|| Error::Strip {
+                        line,
+                        path: path.into(),
+                        strip: self.strip,
+                    })?;
+            }
+            Ok(stripped.into())
+        }
+
+        fn hunks(&mut self) -> Result<Vec<Hunk>, Error> {
+            let mut hunks = Vec::new();
+            while self.peek().is_some_and(|line| line.starts_with(b"@@ -")) {
+                hunks.push(self.hunk()?);
+            }
+            Ok(hunks)
+        }
+
+        fn hunk(&mut self) -> Result<Hunk, Error> {
+            let line = self.line_number();
+            let header = self.peek().map(trim_eol).expect("checked by caller");
+            let (old_start, old_lines, new_start, new_lines) =
+                parse_hunk_header(header).ok_or(Error::HunkHeader { line })?;
+            self.pos += 1;
+
+            let truncated = || Error::TruncatedHunk { line };
+            let (mut old_left, mut new_left) = (old_lines, new_lines);
+            let mut lines = Vec::new();
+            while old_left > 0 || new_left > 0 {
+                let Some(content) = self.peek() else {
+                    return Err(truncated());
+                };
+                match content.first() {
+                    Some(b' ') => {
+                        (old_left, new_left) = old_left
+                            .checked_sub(1)
+                            .zip(new_left.checked_sub(1))
+                            .ok_or_else(truncated)?;
+                        lines.push(Line::Context(content[1..].into()));
+                    }
+                    // Some tools strip trailing whitespace, which turns empty context lines into empty lines.
+                    Some(b'\n' | b'\r') if trim_eol(content).is_empty() => {
+                        (old_left, new_left) = old_left
+                            .checked_sub(1)
+                            .zip(new_left.checked_sub(1))
+                            .ok_or_else(truncated)?;
+                        lines.push(Line::Context(content.into()));
+                    }
+                    Some(b'-') => {
+                        old_left = old_left.checked_sub(1).ok_or_else(truncated)?;
+                        lines.push(Line::Remove(content[1..].into()));
+                    }
+                    Some(b'+') => {
+                        new_left = new_left.checked_sub(1).ok_or_else(truncated)?;
+                        lines.push(Line::Add(content[1..].into()));
+                    }
+                    Some(b'\\') => remove_newline(lines.last_mut()),
+                    _ => return Err(truncated()),
+                }
+                self.pos += 1;
+            }
+            if self.peek().is_some_and(|line| line.starts_with(b"\\")) {
+                remove_newline(lines.last_mut());
+                self.pos += 1;
+            }
+            Ok(Hunk {
+                old_start,
+                old_lines,
+                new_start,
+                new_lines,
+                lines,
+            })
+        }
+
+        /// Parse a `literal` or `delta` block of a binary patch, or return `None` if there is none.
+        fn binary_data(&mut self) -> Result<Option<BinaryData>, Error> {
+            let line = self.line_number();
+            let Some(header) = self.peek().map(trim_eol) else {
+                return Ok(None);
+            };
+            let (is_literal, size) = if let Some(size) = header.strip_prefix(b"literal ") {
+                (true, size)
+            } else if let Some(size) = header.strip_prefix(b"delta ") {
+                (false, size)
+            } else {
+                return Ok(None);
+            };
+            let size: usize = size
+                .to_str()
+                .ok()
+                .and_then(|size| size.parse().ok())
+                .ok_or(Error::Binary { line })?;
+            self.pos += 1;
+
+            let mut compressed = Vec::new();
+            while let Some(data) = self.peek().map(trim_eol) {
+                self.pos += 1;
+                if data.is_empty() {
+                    break;
+                }
+                binary::decode_base85_line(data, &mut compressed).ok_or(Error::Binary { line: self.pos })?;
+            }
+            let data = binary::inflate(&compressed, size).ok_or(Error::Binary { line })?;
+            Ok(Some(if is_literal {
+                BinaryData::Literal(data)
+            } else {
+                BinaryData::Delta(data)
+            }))
+        }
+    }
+
+    fn trim_eol(line: &[u8]) -> &[u8] {
+        let line = line.strip_suffix(b"\n").unwrap_or(line);
+        line.strip_suffix(b"\r").unwrap_or(line)
+    }
+
+    /// Remove the newline of `line`, as the following line said there is no newline at the end of the file.
+    fn remove_newline(line: Option<&mut Line>) {
+        if let Some(Line::Context(line) | Line::Remove(line) | Line::Add(line)) = line {
+            if line.ends_with(b"\n") {
+                line.pop();
+            }
+        }
+    }
+
+    fn unquote(path: &[u8], line: usize) -> Result<BString, Error> {
+        gix_quote::ansi_c::undo(path.as_bstr())
+            .map(|(path, _)| path.into_owned())
+            .map_err(|source| Error::Unquote { line, source })
+    }
+
+    fn parse_mode(mode: &[u8], line: usize) -> Result<gix_object::tree::EntryKind, Error> {
+        use gix_object::tree::EntryKind;
+        let mode = mode
+            .to_str()
+            .ok()
+            .and_then(|mode| u32::from_str_radix(mode.trim(), 8).ok())
+            .ok_or(Error::Mode { line })?;
+        Ok(match mode & 0o170000 {
+            0o100000 if mode & 0o111 != 0 => EntryKind::BlobExecutable,
+            0o100000 => EntryKind::Blob,
+            0o120000 => EntryKind::Link,
+            0o160000 => EntryKind::Commit,
+            _ => return Err(Error::Mode { line }),
+        })
+    }
+
+    fn parse_id(id: &[u8], line: usize) -> Result<Option<gix_hash::Prefix>, Error> {
+        if !id.is_empty() && id.iter().all(|b| *b == b'0') {
+            return Ok(None);
+        }
+        id.to_str()
+            .ok()
+            .and_then(|id| gix_hash::Prefix::from_hex_nonempty(id).ok())
+            .map(Some)
+            .ok_or(Error::ObjectId { line })
+    }
+
+    /// Parse `@@ -a,b +c,d @@`, where the line counts default to `1` if omitted.
+    fn parse_hunk_header(header: &[u8]) -> Option<(u32, u32, u32, u32)> {
+        let header = header.strip_prefix(b"@@ -")?;
+        let end = header.find(b" @@")?;
+        let (old, new) = header[..end].split_once_str(b" +")?;
+        let range = |range: &[u8]| -> Option<(u32, u32)> {
+            let range = range.to_str().ok()?;
+            Some(match range.split_once(',') {
+                Some((start, lines)) => (start.parse().ok()?, lines.parse().ok()?),
+                None => (range.parse().ok()?, 1),
+            })
+        };
+        let (old_start, old_lines) = range(old)?;
+        let (new_start, new_lines) = range(new)?;
+        Some((old_start, old_lines, new_start, new_lines))
+    }
+}
@@ -0,0 +1,64 @@
+use gix_merge::blob::builtin_driver::text;
+
+/// Options for [`three_way()`](crate::three_way()).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Options<'a> {
+    /// How to apply the patch to the version of the file it was created from.
+    pub apply: crate::apply::Options,
+    /// The labels to annotate conflict markers with, where *ancestor* is the version the patch was created from,
+    /// *current* is the version the patch should be applied to and *other* is the patched version.
+    pub labels: text::Labels<'a>,
+    /// How to merge the patched version into the current version.
+    pub merge: text::Options,
+}
+
+/// The outcome of [`three_way()`](crate::three_way()).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The merged content, which contains conflict markers if there are conflicts that were kept.
+    pub content: Vec<u8>,
+    /// The outcome of applying the patch to the version of the file it was created from.
+    pub apply: crate::apply::Outcome,
+    /// If `true`, the merge had conflicts that remain in [`content`](Self::content).
+    pub conflict: bool,
+}
+
+/// The error returned by [`three_way()`](crate::three_way()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The patch could not be applied to the version of the file it was created from")]
+    Apply(#[from] crate::apply::Error),
+}
+
+pub(super) mod function {
+    use gix_merge::blob::{Resolution, builtin_driver};
+
+    use super::{Error, Options, Outcome};
+    use crate::FilePatch;
+
+    /// Apply `patch` to `base`, the version of the file the patch was created from, and merge the result into `current`,
+    /// the version of the file the patch should be applied to, like `git apply --3way` does if a patch doesn't apply cleanly.
+    ///
+    /// `base` is typically obtained from the object database using [`FilePatch::old_id`], or [`FilePatch::new_id`]
+    /// if the patch is applied in reverse.
+    pub fn three_way(base: &[u8], current: &[u8], patch: &FilePatch, options: Options<'_>) -> Result<Outcome, Error> {
+        let apply = crate::apply(base, patch, options.apply)?;
+        let mut content = Vec::new();
+        let mut input = imara_diff::InternedInput::new(&[][..], &[]);
+        let resolution = builtin_driver::text(
+            &mut content,
+            &mut input,
+            options.labels,
+            current,
+            base,
+            &apply.content,
+            options.merge,
+        );
+        Ok(Outcome {
+            content,
+            apply,
+            conflict: resolution == Resolution::Conflict,
+        })
+    }
+}
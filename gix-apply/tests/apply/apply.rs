@@ -0,0 +1,185 @@
+use gix_apply::{
+    apply,
+    apply::{Whitespace, WhitespaceError, WhitespaceErrorKind},
+};
+
+use crate::{patches, read};
+
+fn path_in(dir: &str, path: Option<&gix_object::bstr::BString>) -> String {
+    path.map_or_else(|| format!("{dir}/does-not-exist"), |path| format!("{dir}/{path}"))
+}
+
+#[test]
+fn all_changes_apply_like_git_applies_them() -> crate::Result {
+    let options = apply::Options {
+        object_hash: Some(gix_testtools::object_hash()),
+        ..Default::default()
+    };
+    for patch in patches("all.patch")? {
+        let before = read(&path_in("before", patch.old_path.as_ref()))?;
+        let after = read(&path_in("after", patch.new_path.as_ref()))?;
+        let outcome = gix_apply::apply(&before, &patch, options)?;
+        assert_eq!(outcome.content, after, "{}", patch.path());
+        assert!(
+            outcome
+                .hunks
+                .iter()
+                .all(|hunk| hunk.offset == 0 && hunk.context_reduced == 0)
+        );
+        assert!(outcome.whitespace_errors.is_empty());
+
+        let outcome = gix_apply::apply(
+            &after,
+            &patch,
+            apply::Options {
+                reverse: true,
+                ..options
+            },
+        )?;
+        assert_eq!(outcome.content, before, "{} can be reversed", patch.path());
+    }
+    Ok(())
+}
+
+#[test]
+fn hunks_apply_at_an_offset() -> crate::Result {
+    let patch = &patches("a.patch")?[0];
+    let outcome = gix_apply::apply(&read("shifted/a")?, patch, Default::default())?;
+    assert_eq!(outcome.content, read("shifted-expected/a")?);
+    let offsets: Vec<_> = outcome.hunks.iter().map(|hunk| hunk.offset).collect();
+    assert_eq!(offsets, [0, 3]);
+    Ok(())
+}
+
+#[test]
+fn context_is_reduced_only_if_allowed() -> crate::Result {
+    let patch = &patches("a.patch")?[0];
+    let content = read("fuzzy/a")?;
+    assert!(matches!(
+        gix_apply::apply(&content, patch, Default::default()),
+        Err(apply::Error::HunkMismatch { hunk: 0, line: 1 })
+    ));
+
+    let outcome = gix_apply::apply(
+        &content,
+        patch,
+        apply::Options {
+            min_context: Some(1),
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(outcome.content, read("fuzzy-expected/a")?);
+    assert!(outcome.hunks[0].context_reduced > 0);
+    assert_eq!(outcome.hunks[1].context_reduced, 0);
+    Ok(())
+}
+
+#[test]
+fn whitespace_errors_are_reported_or_fixed() -> crate::Result {
+    let patch = &patches("whitespace.patch")?[0];
+    let content = b"1\n2\n3\n4\n5\n";
+
+    let outcome = gix_apply::apply(content, patch, Default::default())?;
+    let error = |line, kind| WhitespaceError { hunk: 0, line, kind };
+    let expected = [
+        error(6, WhitespaceErrorKind::BlankAtEol),
+        error(7, WhitespaceErrorKind::SpaceBeforeTab),
+        error(8, WhitespaceErrorKind::BlankAtEof),
+    ];
+    assert_eq!(outcome.whitespace_errors, expected);
+    assert_eq!(outcome.content, b"1\n2\n3\n4\n5\ntrailing \n \tspace before tab\n\n\n");
+
+    let outcome = gix_apply::apply(
+        content,
+        patch,
+        apply::Options {
+            whitespace: Whitespace::Fix,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(
+        outcome.content,
+        read("whitespace-fixed")?,
+        "fixes are the same as Git's"
+    );
+    assert_eq!(outcome.whitespace_errors, expected);
+
+    let outcome = gix_apply::apply(
+        content,
+        patch,
+        apply::Options {
+            whitespace: Whitespace::Nowarn,
+            ..Default::default()
+        },
+    )?;
+    assert!(outcome.whitespace_errors.is_empty());
+
+    for (whitespace, num_errors) in [(Whitespace::Error, 1), (Whitespace::ErrorAll, 3)] {
+        let err = gix_apply::apply(
+            content,
+            patch,
+            apply::Options {
+                whitespace,
+                ..Default::default()
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, apply::Error::WhitespaceErrors { errors } if errors.len() == num_errors));
+    }
+    Ok(())
+}
+
+#[test]
+fn whitespace_changes_can_be_ignored() -> crate::Result {
+    let patch = &gix_apply::parse(
+        b"--- a/a\n+++ b/a\n@@ -1,2 +1,2 @@\n a  b\n-c\n+d\n",
+        Default::default(),
+    )?[0];
+    let content = b"a\tb \nc\n";
+    assert!(gix_apply::apply(content, patch, Default::default()).is_err());
+    let outcome = gix_apply::apply(
+        content,
+        patch,
+        apply::Options {
+            ignore_whitespace: true,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(outcome.content, b"a\tb \nd\n", "context lines are kept as they are");
+    Ok(())
+}
+
+#[test]
+fn binary_patches_check_their_preimage() -> crate::Result {
+    let patch = patches("all.patch")?
+        .into_iter()
+        .find(|patch| patch.path() == "bin")
+        .expect("present");
+    assert!(matches!(
+        gix_apply::apply(
+            b"other content",
+            &patch,
+            apply::Options {
+                object_hash: Some(gix_testtools::object_hash()),
+                ..Default::default()
+            }
+        ),
+        Err(apply::Error::BinaryPreimageMismatch { .. })
+    ));
+    assert!(matches!(
+        gix_apply::apply(b"other content", &patch, Default::default()),
+        Err(apply::Error::BinaryDelta)
+    ));
+    Ok(())
+}
+
+#[test]
+fn deleting_requires_all_content_to_be_removed() -> crate::Result {
+    let patch = &gix_apply::parse(b"--- a/a\n+++ /dev/null\n@@ -1 +0,0 @@\n-a\n", Default::default())?[0];
+    assert!(gix_apply::apply(b"a\n", patch, Default::default())?.content.is_empty());
+    assert!(matches!(
+        gix_apply::apply(b"a\nb\n", patch, Default::default()),
+        Err(apply::Error::HunkMismatch { .. })
+    ));
+    Ok(())
+}
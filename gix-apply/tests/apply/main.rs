@@ -0,0 +1,27 @@
+use std::path::PathBuf;
+
+pub use gix_testtools::Result;
+
+/// The directory with `before/` and `after/` along with `all.patch` between them, and more patches with the results
+/// of applying them using `git apply`.
+pub fn fixture_dir() -> Result<PathBuf> {
+    gix_testtools::scripted_fixture_read_only("apply.sh")
+}
+
+/// Read `rela_path` in the fixture directory, or return an empty buffer if it doesn't exist.
+pub fn read(rela_path: &str) -> Result<Vec<u8>> {
+    match std::fs::read(fixture_dir()?.join(rela_path)) {
+        Ok(data) => Ok(data),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Parse the patch at `rela_path` in the fixture directory with default options.
+pub fn patches(rela_path: &str) -> Result<Vec<gix_apply::FilePatch>> {
+    Ok(gix_apply::parse(&read(rela_path)?, Default::default())?)
+}
+
+mod apply;
+mod parse;
+mod three_way;
@@ -0,0 +1,161 @@
+use gix_apply::{Binary, BinaryData, Body, Hunk, Line, Operation, parse};
+use gix_object::tree::EntryKind;
+
+use crate::patches;
+
+#[test]
+fn all_kinds_of_changes_made_by_git() -> crate::Result {
+    let patches = patches("all.patch")?;
+    let summary: Vec<_> = patches
+        .iter()
+        .map(|patch| {
+            (
+                patch.old_path.as_ref().map(ToString::to_string),
+                patch.new_path.as_ref().map(ToString::to_string),
+                patch.operation,
+            )
+        })
+        .collect();
+    let path = |path: &str| Some(path.to_owned());
+    assert_eq!(
+        summary,
+        [
+            (path("a"), path("a"), Operation::Modify),
+            (path("bin"), path("bin"), Operation::Modify),
+            (path("b"), path("c"), Operation::Rename),
+            (path("gone"), None, Operation::Delete),
+            (None, path("new"), Operation::Create),
+            (None, path("new-bin"), Operation::Create),
+            (path("no-eol"), path("no-eol"), Operation::Modify),
+            (path("script"), path("script"), Operation::Modify),
+        ]
+    );
+
+    let a = &patches[0];
+    assert_eq!((a.old_mode, a.new_mode), (Some(EntryKind::Blob), Some(EntryKind::Blob)));
+    assert_eq!(a.old_id.expect("set").hex_len(), 7, "ids are abbreviated");
+    let Body::Text(hunks) = &a.body else {
+        panic!("text patch")
+    };
+    assert_eq!(hunks.len(), 2);
+    assert_eq!(
+        (
+            hunks[1].old_start,
+            hunks[1].old_lines,
+            hunks[1].new_start,
+            hunks[1].new_lines
+        ),
+        (12, 7, 12, 7)
+    );
+    assert_eq!(hunks[1].lines[3], Line::Remove("15\n".into()));
+    assert_eq!(hunks[1].lines[4], Line::Add("fifteen\n".into()));
+
+    assert!(
+        matches!(
+            &patches[1].body,
+            Body::Binary(Some(Binary {
+                forward: BinaryData::Delta(_),
+                reverse: Some(BinaryData::Delta(_))
+            }))
+        ),
+        "small changes to large binary files are deltas"
+    );
+    assert_eq!(patches[2].similarity, Some(79));
+
+    assert_eq!(patches[3].old_mode, Some(EntryKind::Blob));
+    assert_eq!(patches[3].new_id, None, "all-zero ids mean there is no file");
+    assert_eq!(patches[4].old_id, None);
+    assert_eq!(
+        patches[5].body,
+        Body::Binary(Some(Binary {
+            forward: BinaryData::Literal(vec![0, 1, 2]),
+            reverse: Some(BinaryData::Literal(Vec::new())),
+        }))
+    );
+
+    let Body::Text(hunks) = &patches[6].body else {
+        panic!("text patch")
+    };
+    assert_eq!(
+        hunks[0].lines,
+        [
+            Line::Context("1\n".into()),
+            Line::Context("2\n".into()),
+            Line::Remove("3".into()),
+            Line::Add("3\n".into()),
+            Line::Add("4".into()),
+        ],
+        "the line before a 'No newline' marker has no newline"
+    );
+
+    let script = &patches[7];
+    assert_eq!(
+        (script.old_mode, script.new_mode),
+        (Some(EntryKind::Blob), Some(EntryKind::BlobExecutable))
+    );
+    assert_eq!(script.body, Body::Text(Vec::new()), "only the mode changed");
+    Ok(())
+}
+
+#[test]
+fn traditional_diffs_with_custom_strip_and_quoted_names() -> crate::Result {
+    let patches = parse(
+        b"Some text before\n\
+--- old/dir/a.txt\t2024-01-01 00:00:00\n\
++++ new/dir/a.txt\t2024-01-01 00:00:01\n\
+@@ -1 +1 @@\n\
+-a\n\
++b\n",
+        parse::Options { strip: 2 },
+    )?;
+    assert_eq!(patches.len(), 1);
+    assert_eq!(patches[0].old_path.as_ref().expect("set"), "a.txt");
+    assert_eq!(patches[0].new_path.as_ref().expect("set"), "a.txt");
+    assert_eq!(
+        patches[0].body,
+        Body::Text(vec![Hunk {
+            old_start: 1,
+            old_lines: 1,
+            new_start: 1,
+            new_lines: 1,
+            lines: vec![Line::Remove("a\n".into()), Line::Add("b\n".into())],
+        }]),
+        "line counts default to 1"
+    );
+
+    let patches = parse(
+        b"diff --git \"a/with\\ttab\" \"b/with\\ttab\"\nnew file mode 100755\nindex 0000000..e69de29\n",
+        Default::default(),
+    )?;
+    assert_eq!(patches[0].new_path.as_ref().expect("set"), "with\ttab");
+    assert_eq!(patches[0].operation, Operation::Create);
+    assert_eq!(patches[0].new_mode, Some(EntryKind::BlobExecutable));
+    assert_eq!(patches[0].body, Body::Text(Vec::new()), "an empty file was created");
+
+    let patches = parse(
+        b"diff --git a/with space b/with space\nold mode 100644\nnew mode 120000\n",
+        Default::default(),
+    )?;
+    assert_eq!(
+        patches[0].path(),
+        "with space",
+        "names with spaces are found if they are the same"
+    );
+    Ok(())
+}
+
+#[test]
+fn invalid_patches() {
+    assert!(matches!(
+        parse(b"--- a/a\n+++ b/a\n@@ -1,2 +1,2 @@\n-a\n+b\n", Default::default()),
+        Err(parse::Error::TruncatedHunk { line: 3 })
+    ));
+    assert!(matches!(
+        parse(b"diff --git a/a b/a\nnew mode 12345x\n", Default::default()),
+        Err(parse::Error::Mode { line: 2 })
+    ));
+    assert!(matches!(
+        parse(b"--- a\n+++ b\n@@ -1 +1 @@\n-a\n+b\n", Default::default()),
+        Err(parse::Error::Strip { line: 1, .. })
+    ));
+}
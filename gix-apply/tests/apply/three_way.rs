@@ -0,0 +1,53 @@
+use gix_apply::three_way;
+
+use crate::patches;
+
+#[test]
+fn changes_merge_into_the_current_version() -> crate::Result {
+    let patch = &patches("a.patch")?[0];
+    let base: Vec<u8> = (1..=20).flat_map(|n| format!("{n}\n").into_bytes()).collect();
+    let current = String::from_utf8(base.clone())?
+        .replace("\n9\n", "\nnine\n")
+        .replace("\n4\n", "\n4a\n");
+    assert!(
+        gix_apply::apply(current.as_bytes(), patch, Default::default()).is_err(),
+        "the patch doesn't apply as its context changed"
+    );
+
+    let outcome = gix_apply::three_way(&base, current.as_bytes(), patch, Default::default())?;
+    assert!(!outcome.conflict);
+    let expected = String::from_utf8(base.clone())?
+        .replace("\n9\n", "\nnine\n")
+        .replace("\n4\n", "\n4a\n")
+        .replace("\n3\n", "\nthree\n")
+        .replace("\n15\n", "\nfifteen\n");
+    assert_eq!(outcome.content, expected.as_bytes());
+    Ok(())
+}
+
+#[test]
+fn conflicts_are_marked() -> crate::Result {
+    let patch = &patches("a.patch")?[0];
+    let base: Vec<u8> = (1..=20).flat_map(|n| format!("{n}\n").into_bytes()).collect();
+    let current = String::from_utf8(base.clone())?.replace("\n3\n", "\nTHREE\n");
+    let outcome = gix_apply::three_way(
+        &base,
+        current.as_bytes(),
+        patch,
+        three_way::Options {
+            labels: gix_merge::blob::builtin_driver::text::Labels {
+                ancestor: None,
+                current: Some("ours".into()),
+                other: Some("theirs".into()),
+            },
+            ..Default::default()
+        },
+    )?;
+    assert!(outcome.conflict);
+    assert!(
+        outcome
+            .content
+            .starts_with(b"1\n2\n<<<<<<< ours\nTHREE\n=======\nthree\n>>>>>>> theirs\n4\n")
+    );
+    Ok(())
+}
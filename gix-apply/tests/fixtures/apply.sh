@@ -0,0 +1,66 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+# Run `git apply` with the remaining arguments in directory `$1`, which is made a repository temporarily
+# so patches apply relative to it even if it's inside of another repository.
+function git_apply_in() {
+  local dir="$1"; shift
+  (cd "$dir"
+    git init -q
+    git apply "$@" 2>/dev/null
+    rm -rf .git
+  )
+}
+
+# `before/` and `after/` hold all files before and after the changes in `all.patch`, which was created with
+# `git diff --binary` and has all kinds of changes Git can express.
+git init -q repo
+(cd repo
+  seq 1 20 > a
+  seq 1 10 > b
+  echo "gone" > gone
+  echo "#!/bin/sh" > script
+  printf '1\n2\n3' > no-eol
+  { seq 1 500; printf '\0\n'; } > bin
+  git add . && git commit -q -m "base"
+  mkdir ../before && git archive HEAD | tar -x -C ../before
+
+  sed -i.bak -e 's/^3$/three/' -e 's/^15$/fifteen/' a
+  sed -i.bak 's/^5$/five/' b && git mv b c
+  rm gone
+  chmod +x script
+  printf '1\n2\n3\n4' > no-eol
+  sed -i.bak 's/^250$/two-hundred-fifty/' bin
+  printf 'new\n' > new
+  printf '\0\1\2' > new-bin
+  rm *.bak
+  git add -A
+  git diff --cached -M --binary > ../all.patch
+  git commit -q -m "changed"
+  mkdir ../after && git archive HEAD | tar -x -C ../after
+)
+
+# `shifted/a` has three lines more in the middle than `before/a`, so the second hunk applies at an offset.
+(cd repo && git diff HEAD~1 HEAD -- a) > a.patch
+mkdir shifted
+sed '8a x\ny\nz' before/a > shifted/a
+cp -R shifted shifted-expected
+git_apply_in shifted-expected ../a.patch
+
+# `fuzzy/a` has a changed context line, so the patch only applies with reduced context, which we let `git apply` do.
+mkdir fuzzy
+sed 's/^1$/one/' before/a > fuzzy/a
+cp -R fuzzy fuzzy-expected
+git_apply_in fuzzy-expected -C1 ../a.patch
+
+# `whitespace.patch` adds lines with whitespace errors to `a`, and `whitespace-fixed` is the result of `git apply --whitespace=fix`.
+git init -q ws
+(cd ws
+  seq 1 5 > a
+  git add a && git commit -q -m "base"
+  { seq 1 5; printf 'trailing \n \tspace before tab\n\n\n'; } > a
+  git diff > ../whitespace.patch
+  git checkout -q a
+  git apply --whitespace=fix ../whitespace.patch 2>/dev/null
+  cp a ../whitespace-fixed
+)
@@ -29,6 +29,8 @@ pub enum Error {
     DiffTree(#[from] gix_diff::tree::Error),
     #[error(transparent)]
     DiffTreeWithRewrites(#[from] gix_diff::tree_with_rewrites::Error),
+    #[error("Failed to list the files of a tree to find copied lines in")]
+    TraverseTree(#[from] gix_traverse::tree::breadthfirst::Error),
     #[error(
         "Invalid line range was given, line range is expected to be a 1-based inclusive range in the format '<start>,<end>'"
     )]
@@ -37,4 +39,6 @@ pub enum Error {
     DecodeCommit(#[from] gix_object::decode::Error),
     #[error("Failed to get parent from commitgraph during traversal")]
     GetParentFromCommitGraph(#[from] gix_error::Message),
+    #[error("The blame was interrupted")]
+    Interrupted,
 }
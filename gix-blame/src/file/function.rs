@@ -1,16 +1,20 @@
-use std::num::NonZeroU32;
+use std::{
+    collections::{HashMap, HashSet},
+    num::NonZeroU32,
+    sync::atomic::{AtomicBool, Ordering},
+};
 
 use gix_diff::{blob::TokenSource, tree::Visit};
 use gix_hash::ObjectId;
 use gix_object::{
     FindExt,
-    bstr::{BStr, BString},
+    bstr::{BStr, BString, ByteSlice},
 };
 use gix_traverse::commit::find as find_commit;
 use smallvec::SmallVec;
 
-use super::{Change, UnblamedHunk, process_changes};
-use crate::{BlameEntry, Error, Options, Outcome, Statistics, types::BlamePathEntry};
+use super::{Change, UnblamedHunk, ignore, moves, process_changes};
+use crate::{BlameEntry, Copies, Error, MovesAndCopies, Options, Outcome, Statistics, types::BlamePathEntry};
 
 /// Produce a list of consecutive [`BlameEntry`] instances to indicate in which commits the ranges of the file
 /// at `suspect:<file_path>` originated in.
@@ -21,7 +25,8 @@ use crate::{BlameEntry, Error, Options, Outcome, Statistics, types::BlamePathEnt
 ///    - Access to database objects, also for used for diffing.
 ///    - Should have an object cache for good diff performance.
 /// * `suspect`
-///    - The first commit to be responsible for parts of `file_path`.
+///    - The first commit to be responsible for parts of `file_path`, or the commit that the uncommitted
+///      changes in [`Options::uncommitted_blob`] are based on.
 /// * `cache`
 ///    - Optionally, the commitgraph cache.
 /// * `resource_cache`
@@ -67,77 +72,294 @@ pub fn file(
     resource_cache: &mut gix_diff::blob::Platform,
     file_path: &BStr,
     options: Options,
+) -> Result<Outcome, Error> {
+    file_incremental(
+        odb,
+        suspect,
+        cache,
+        resource_cache,
+        file_path,
+        options,
+        |_entry| {},
+        &mut gix_features::progress::Discard,
+        &AtomicBool::default(),
+    )
+}
+
+/// Like [`file()`], but call `on_entry` with each [`BlameEntry`] as soon as it is final, similar to
+/// `git blame --incremental`.
+///
+/// Entries are passed in the order they are found, which is the order in which the history is traversed,
+/// and aren't merged with adjacent entries of the same commit. The returned [`Outcome`] still contains
+/// all entries, merged and sorted like the ones returned by [`file()`].
+///
+/// Each traversed commit is counted in `progress`, and the traversal stops with [`Error::Interrupted`]
+/// once `should_interrupt` is set.
+#[allow(clippy::too_many_arguments)]
+pub fn file_incremental(
+    odb: impl gix_object::Find + gix_object::FindHeader,
+    suspect: ObjectId,
+    cache: Option<gix_commitgraph::Graph>,
+    resource_cache: &mut gix_diff::blob::Platform,
+    file_path: &BStr,
+    mut options: Options,
+    mut on_entry: impl FnMut(BlameEntry),
+    progress: &mut dyn gix_features::progress::Progress,
+    should_interrupt: &AtomicBool,
 ) -> Result<Outcome, Error> {
     let _span = gix_trace::coarse!("gix_blame::file()", ?file_path, ?suspect);
 
+    let uncommitted_blob = options.uncommitted_blob.take();
+    let mut outcomes = blame_files(
+        odb,
+        suspect,
+        cache,
+        resource_cache,
+        vec![(file_path.to_owned(), uncommitted_blob)],
+        &options,
+        |_file_index, entry| on_entry(entry),
+        progress,
+        should_interrupt,
+    )?;
+    Ok(outcomes.pop().expect("one outcome per file"))
+}
+
+/// Like [`file()`], but blame all files at `file_paths` in a single traversal of the history starting at `suspect`,
+/// sharing the work of walking the commit graph and diffing trees between them.
+///
+/// Return one [`Outcome`] per file, in the order of `file_paths`. The [`statistics`](Outcome::statistics) of each
+/// outcome describe the shared traversal.
+///
+/// Each file is blamed as a whole as it is in `suspect`, so [`Options::ranges`] and [`Options::uncommitted_blob`]
+/// are ignored.
+pub fn files<'a>(
+    odb: impl gix_object::Find + gix_object::FindHeader,
+    suspect: ObjectId,
+    cache: Option<gix_commitgraph::Graph>,
+    resource_cache: &mut gix_diff::blob::Platform,
+    file_paths: impl IntoIterator<Item = &'a BStr>,
+    options: Options,
+) -> Result<Vec<Outcome>, Error> {
+    let _span = gix_trace::coarse!("gix_blame::files()", ?suspect);
+
+    let options = Options {
+        ranges: Default::default(),
+        uncommitted_blob: None,
+        ..options
+    };
+    blame_files(
+        odb,
+        suspect,
+        cache,
+        resource_cache,
+        file_paths.into_iter().map(|path| (path.to_owned(), None)).collect(),
+        &options,
+        |_file_index, _entry| {},
+        &mut gix_features::progress::Discard,
+        &AtomicBool::default(),
+    )
+}
+
+/// A file to blame, along with the state of its blame while the history is traversed.
+struct BlamedFile {
+    /// The path to the file in the first suspect.
+    path: BString,
+    /// The content of the *Blamed File*.
+    blob: Vec<u8>,
+    hunks_to_blame: Vec<UnblamedHunk>,
+    out: Vec<BlameEntry>,
+    /// The amount of entries in `out` that were passed to the caller already.
+    num_entries_passed_on: usize,
+    /// The path and entry of the file in the first parent of the last processed suspect, to avoid looking it up again.
+    previous_entry: Option<(ObjectId, BString, ObjectId)>,
+    blame_path: Option<Vec<BlamePathEntry>>,
+}
+
+impl BlamedFile {
+    /// Call `on_entry` with all entries in `out` that it didn't see yet, with their marks applied like in the final [`Outcome`].
+    fn pass_on_new_entries(&mut self, options: &Options, mut on_entry: impl FnMut(BlameEntry)) {
+        for entry in &self.out[self.num_entries_passed_on..] {
+            on_entry(BlameEntry {
+                ignored: entry.ignored && options.mark_ignored_lines,
+                unblamable: entry.unblamable && options.mark_unblamable_lines,
+                ..entry.clone()
+            });
+        }
+        self.num_entries_passed_on = self.out.len();
+    }
+
+    fn into_outcome(mut self, options: &Options, statistics: Statistics) -> Outcome {
+        debug_assert_eq!(
+            self.hunks_to_blame,
+            vec![],
+            "only if there is no portion of the file left we have completed the blame"
+        );
+
+        // I don’t know yet whether it would make sense to use a data structure instead that preserves
+        // order on insertion.
+        self.out.sort_by_key(|a| a.start_in_blamed_file);
+        let mut entries = coalesce_blame_entries(self.out);
+        // Like `git`, entries with different marks are never merged, even if the marks aren't shown.
+        for entry in &mut entries {
+            entry.ignored &= options.mark_ignored_lines;
+            entry.unblamable &= options.mark_unblamable_lines;
+        }
+        Outcome {
+            entries,
+            blob: self.blob,
+            statistics,
+            blame_path: self.blame_path,
+        }
+    }
+}
+
+/// Blame all `files`, each with the path to the file in `suspect` and an optional uncommitted version of it,
+/// in a single traversal, and return one [`Outcome`] per file.
+///
+/// `on_entry` is called with the index of the file in `files` and each of its entries as soon as they are final.
+#[allow(clippy::too_many_arguments)]
+fn blame_files(
+    odb: impl gix_object::Find + gix_object::FindHeader,
+    suspect: ObjectId,
+    cache: Option<gix_commitgraph::Graph>,
+    resource_cache: &mut gix_diff::blob::Platform,
+    files: Vec<(BString, Option<Vec<u8>>)>,
+    options: &Options,
+    mut on_entry: impl FnMut(usize, BlameEntry),
+    progress: &mut dyn gix_features::progress::Progress,
+    should_interrupt: &AtomicBool,
+) -> Result<Vec<Outcome>, Error> {
     let mut stats = Statistics::default();
     let (mut buf, mut buf2, mut buf3) = (Vec::new(), Vec::new(), Vec::new());
-    let blamed_file_entry_id = find_path_entry_in_commit(
-        &odb,
-        &suspect,
-        file_path,
-        cache.as_ref(),
-        &mut buf,
-        &mut buf2,
-        &mut stats,
-    )?
-    .ok_or_else(|| Error::FileMissing {
-        file_path: file_path.to_owned(),
-        commit_id: suspect,
-    })?;
-    let blamed_file_blob = odb.find_blob(&blamed_file_entry_id, &mut buf)?.data.to_vec();
-    let num_lines_in_blamed = tokens_for_diffing(&blamed_file_blob).tokenize().count() as u32;
-
-    // Binary or otherwise empty?
-    if num_lines_in_blamed == 0 {
-        return Ok(Outcome::default());
+
+    let mut blamed_files = Vec::with_capacity(files.len());
+    for (file_path, uncommitted_blob) in files {
+        let suspect_entry_id = find_path_entry_in_commit(
+            &odb,
+            &suspect,
+            file_path.as_ref(),
+            cache.as_ref(),
+            &mut buf,
+            &mut buf2,
+            &mut stats,
+        )?;
+        let has_uncommitted_blob = uncommitted_blob.is_some();
+        let blob = match uncommitted_blob {
+            Some(blob) => blob,
+            None => {
+                let blamed_file_entry_id = suspect_entry_id.ok_or_else(|| Error::FileMissing {
+                    file_path: file_path.clone(),
+                    commit_id: suspect,
+                })?;
+                odb.find_blob(&blamed_file_entry_id, &mut buf)?.data.to_vec()
+            }
+        };
+        let num_lines_in_blamed = tokens_for_diffing(&blob).tokenize().count() as u32;
+
+        let mut blamed_file = BlamedFile {
+            path: file_path,
+            blob: Vec::new(),
+            hunks_to_blame: Vec::new(),
+            out: Vec::new(),
+            num_entries_passed_on: 0,
+            previous_entry: None,
+            blame_path: options.debug_track_path.then(Vec::new),
+        };
+        // Binary or otherwise empty?
+        if num_lines_in_blamed == 0 {
+            blamed_file.blame_path = None;
+            blamed_files.push(blamed_file);
+            continue;
+        }
+
+        let ranges_to_blame = options.ranges.to_zero_based_exclusive_ranges(num_lines_in_blamed);
+        blamed_file.hunks_to_blame = ranges_to_blame
+            .into_iter()
+            .map(|range| UnblamedHunk::new(range, suspect))
+            .collect::<Vec<_>>();
+
+        if has_uncommitted_blob {
+            // The uncommitted state is a virtual commit on top of `suspect`, which is identified by the null id.
+            let uncommitted_id = suspect.kind().null();
+            pass_blame_from_to(suspect, uncommitted_id, &mut blamed_file.hunks_to_blame);
+            if let Some(suspect_entry_id) = suspect_entry_id {
+                let suspect_blob = odb.find_blob(&suspect_entry_id, &mut buf)?;
+                let changes = changes_between(suspect_blob.data, &blob, options.diff_algorithm, options.whitespace);
+                stats.blobs_diffed += 1;
+                blamed_file.hunks_to_blame =
+                    process_changes(blamed_file.hunks_to_blame, changes, uncommitted_id, suspect);
+            }
+            unblamed_to_out_is_done(&mut blamed_file.hunks_to_blame, &mut blamed_file.out, uncommitted_id);
+        }
+        blamed_file.blob = blob;
+        blamed_files.push(blamed_file);
     }
 
-    let ranges_to_blame = options.ranges.to_zero_based_exclusive_ranges(num_lines_in_blamed);
-    let mut hunks_to_blame = ranges_to_blame
-        .into_iter()
-        .map(|range| UnblamedHunk::new(range, suspect))
-        .collect::<Vec<_>>();
+    // When blaming in reverse, children take the place of parents, and the oldest commits are handled first.
+    let children = options
+        .reverse_until
+        .map(|until| collect_children(&odb, suspect, until))
+        .transpose()?;
+    let queue_key = |commit_time: gix_date::SecondsSinceUnixEpoch| {
+        if children.is_some() { -commit_time } else { commit_time }
+    };
 
     let (mut buf, mut buf2) = (Vec::new(), Vec::new());
     let commit = find_commit(cache.as_ref(), &odb, &suspect, &mut buf)?;
     let mut queue: gix_revwalk::PriorityQueue<gix_date::SecondsSinceUnixEpoch, ObjectId> =
         gix_revwalk::PriorityQueue::new();
-    queue.insert(commit.commit_time()?, suspect);
+    queue.insert(queue_key(commit.commit_time()?), suspect);
 
-    let mut out = Vec::new();
     let mut diff_state = gix_diff::tree::State::default();
-    let mut previous_entry: Option<(ObjectId, ObjectId)> = None;
-    let mut blame_path = if options.debug_track_path {
-        Some(Vec::new())
-    } else {
-        None
-    };
 
+    progress.init(None, gix_features::progress::count("commits"));
     'outer: while let Some(suspect) = queue.pop_value() {
+        for (file_index, blamed_file) in blamed_files.iter_mut().enumerate() {
+            blamed_file.pass_on_new_entries(options, |entry| on_entry(file_index, entry));
+        }
+        if should_interrupt.load(Ordering::Relaxed) {
+            return Err(Error::Interrupted);
+        }
         stats.commits_traversed += 1;
-        if hunks_to_blame.is_empty() {
+        progress.inc();
+        if all_done(&blamed_files) {
             break;
         }
 
-        let first_hunk_for_suspect = hunks_to_blame.iter().find(|hunk| hunk.has_suspect(&suspect));
-        let Some(first_hunk_for_suspect) = first_hunk_for_suspect else {
+        // All files with hunks associated with this `suspect`, along with the path of the file in it.
+        // Lines that were copied from other files are associated with more than one path.
+        let mut files_for_suspect: Vec<(usize, BString)> = Vec::new();
+        for (file_index, blamed_file) in blamed_files.iter().enumerate() {
+            for hunk in blamed_file
+                .hunks_to_blame
+                .iter()
+                .filter(|hunk| hunk.has_suspect(&suspect))
+            {
+                let current_file_path = hunk.source_file_name.as_ref().unwrap_or(&blamed_file.path);
+                if !files_for_suspect
+                    .iter()
+                    .any(|(index, path)| *index == file_index && path == current_file_path)
+                {
+                    files_for_suspect.push((file_index, current_file_path.clone()));
+                }
+            }
+        }
+        if files_for_suspect.is_empty() {
             // There are no `UnblamedHunk`s associated with this `suspect`, so we can continue with
             // the next one.
             continue 'outer;
-        };
-
-        let current_file_path = first_hunk_for_suspect
-            .source_file_name
-            .clone()
-            .unwrap_or_else(|| file_path.to_owned());
+        }
 
         let commit = find_commit(cache.as_ref(), &odb, &suspect, &mut buf)?;
         let commit_time = commit.commit_time()?;
 
         if let Some(since) = options.since {
             if commit_time < since.seconds {
-                if unblamed_to_out_is_done(&mut hunks_to_blame, &mut out, suspect) {
+                for (file_index, _) in &files_for_suspect {
+                    let blamed_file = &mut blamed_files[*file_index];
+                    unblamed_to_out_is_done(&mut blamed_file.hunks_to_blame, &mut blamed_file.out, suspect);
+                }
+                if all_done(&blamed_files) {
                     break 'outer;
                 }
 
@@ -145,33 +367,42 @@ pub fn file(
             }
         }
 
-        let parent_ids: ParentIds = collect_parents(commit, &odb, cache.as_ref(), &mut buf2)?;
+        let parent_ids: ParentIds = match &children {
+            Some(children) => children.get(&suspect).cloned().unwrap_or_default(),
+            None => collect_parents(commit, &odb, cache.as_ref(), &mut buf2)?,
+        };
 
         if parent_ids.is_empty() {
-            if queue.is_empty() {
+            // In reverse, only the last commit has no children, so what's left belongs to it.
+            if queue.is_empty() || children.is_some() {
                 // I’m not entirely sure if this is correct yet. `suspect`, at this point, is the
                 // `id` of the last `item` that was yielded by `queue`, so it makes sense to assign
                 // the remaining lines to it, even though we don’t explicitly check whether that is
                 // true here. We could perhaps use diff-tree-to-tree to compare `suspect` against
                 // an empty tree to validate this assumption.
-                if unblamed_to_out_is_done(&mut hunks_to_blame, &mut out, suspect) {
-                    if let Some(ref mut blame_path) = blame_path {
-                        let entry = previous_entry
-                            .take()
-                            .filter(|(id, _)| *id == suspect)
-                            .map(|(_, entry)| entry);
-
-                        let blame_path_entry = BlamePathEntry {
-                            source_file_path: current_file_path.clone(),
-                            previous_source_file_path: None,
-                            commit_id: suspect,
-                            blob_id: entry.unwrap_or(gix_hash::Kind::shortest().null()),
-                            previous_blob_id: gix_hash::Kind::shortest().null(),
-                            parent_index: 0,
-                        };
-                        blame_path.push(blame_path_entry);
-                    }
+                for (file_index, current_file_path) in &files_for_suspect {
+                    let blamed_file = &mut blamed_files[*file_index];
+                    if unblamed_to_out_is_done(&mut blamed_file.hunks_to_blame, &mut blamed_file.out, suspect) {
+                        if let Some(ref mut blame_path) = blamed_file.blame_path {
+                            let entry = blamed_file
+                                .previous_entry
+                                .take()
+                                .filter(|(id, path, _)| *id == suspect && path == current_file_path)
+                                .map(|(_, _, entry)| entry);
 
+                            let blame_path_entry = BlamePathEntry {
+                                source_file_path: current_file_path.clone(),
+                                previous_source_file_path: None,
+                                commit_id: suspect,
+                                blob_id: entry.unwrap_or(gix_hash::Kind::shortest().null()),
+                                previous_blob_id: gix_hash::Kind::shortest().null(),
+                                parent_index: 0,
+                            };
+                            blame_path.push(blame_path_entry);
+                        }
+                    }
+                }
+                if all_done(&blamed_files) {
                     break 'outer;
                 }
             }
@@ -179,89 +410,133 @@ pub fn file(
             continue;
         }
 
-        let mut entry = previous_entry
-            .take()
-            .filter(|(id, _)| *id == suspect)
-            .map(|(_, entry)| entry);
-        if entry.is_none() {
-            entry = find_path_entry_in_commit(
-                &odb,
-                &suspect,
-                current_file_path.as_ref(),
-                cache.as_ref(),
-                &mut buf,
-                &mut buf2,
-                &mut stats,
-            )?;
-        }
-
-        let Some(entry_id) = entry else {
-            continue;
+        let mut queued_parents = SmallVec::<[ObjectId; 2]>::new();
+        let mut queue_parent = |queue: &mut gix_revwalk::PriorityQueue<_, _>, parent_id: ObjectId, commit_time| {
+            if !queued_parents.contains(&parent_id) {
+                queued_parents.push(parent_id);
+                queue.insert(queue_key(commit_time), parent_id);
+            }
         };
 
-        // This block asserts that, for every `UnblamedHunk`, all lines in the *Blamed File* are
-        // identical to the corresponding lines in the *Source File*.
-        #[cfg(debug_assertions)]
-        {
-            let source_blob = odb.find_blob(&entry_id, &mut buf)?.data.to_vec();
-            let mut source_interner = gix_diff::blob::Interner::new(source_blob.len() / 100);
-            let source_lines_as_tokens: Vec<_> = tokens_for_diffing(&source_blob)
-                .tokenize()
-                .map(|token| source_interner.intern(token))
-                .collect();
-
-            let mut blamed_interner = gix_diff::blob::Interner::new(blamed_file_blob.len() / 100);
-            let blamed_lines_as_tokens: Vec<_> = tokens_for_diffing(&blamed_file_blob)
-                .tokenize()
-                .map(|token| blamed_interner.intern(token))
-                .collect();
-
-            for hunk in hunks_to_blame.iter() {
-                if let Some(range_in_suspect) = hunk.get_range(&suspect) {
-                    let range_in_blamed_file = hunk.range_in_blamed_file.clone();
-
-                    let source_lines = range_in_suspect
-                        .clone()
-                        .map(|i| BString::new(source_interner[source_lines_as_tokens[i as usize]].into()))
-                        .collect::<Vec<_>>();
-                    let blamed_lines = range_in_blamed_file
-                        .clone()
-                        .map(|i| BString::new(blamed_interner[blamed_lines_as_tokens[i as usize]].into()))
-                        .collect::<Vec<_>>();
-
-                    assert_eq!(source_lines, blamed_lines);
-                }
+        // All files that changed compared to each parent, each with the hunks associated with its path in `suspect`.
+        let mut files_to_diff = Vec::with_capacity(files_for_suspect.len());
+        'files: for (file_index, current_file_path) in files_for_suspect {
+            let blamed_file = &mut blamed_files[file_index];
+            let (mut hunks_to_blame, other_hunks): (Vec<_>, Vec<_>) = std::mem::take(&mut blamed_file.hunks_to_blame)
+                .into_iter()
+                .partition(|hunk| {
+                    hunk.has_suspect(&suspect)
+                        && *hunk.source_file_name.as_ref().unwrap_or(&blamed_file.path) == current_file_path
+                });
+            blamed_file.hunks_to_blame = other_hunks;
+            let mut entry = blamed_file
+                .previous_entry
+                .take()
+                .filter(|(id, path, _)| *id == suspect && *path == current_file_path)
+                .map(|(_, _, entry)| entry);
+            if entry.is_none() {
+                entry = find_path_entry_in_commit(
+                    &odb,
+                    &suspect,
+                    current_file_path.as_ref(),
+                    cache.as_ref(),
+                    &mut buf,
+                    &mut buf2,
+                    &mut stats,
+                )?;
             }
-        }
 
-        for (pid, (parent_id, parent_commit_time)) in parent_ids.iter().enumerate() {
-            if let Some(parent_entry_id) = find_path_entry_in_commit(
-                &odb,
-                parent_id,
-                current_file_path.as_ref(),
-                cache.as_ref(),
-                &mut buf,
-                &mut buf2,
-                &mut stats,
-            )? {
-                let no_change_in_entry = entry_id == parent_entry_id;
-                if pid == 0 {
-                    previous_entry = Some((*parent_id, parent_entry_id));
+            let Some(entry_id) = entry else {
+                blamed_file.hunks_to_blame.extend(hunks_to_blame);
+                continue;
+            };
+
+            // This block asserts that, for every `UnblamedHunk`, all lines in the *Blamed File* are
+            // identical to the corresponding lines in the *Source File*.
+            #[cfg(debug_assertions)]
+            {
+                let source_blob = odb.find_blob(&entry_id, &mut buf)?.data.to_vec();
+                let mut source_interner = gix_diff::blob::Interner::new(source_blob.len() / 100);
+                let source_lines_as_tokens: Vec<_> = tokens_for_diffing(&source_blob)
+                    .tokenize()
+                    .map(|token| source_interner.intern(token))
+                    .collect();
+
+                let mut blamed_interner = gix_diff::blob::Interner::new(blamed_file.blob.len() / 100);
+                let blamed_lines_as_tokens: Vec<_> = tokens_for_diffing(&blamed_file.blob)
+                    .tokenize()
+                    .map(|token| blamed_interner.intern(token))
+                    .collect();
+
+                // Lines passed on from ignored commits are only similar to the lines they were matched with.
+                for hunk in hunks_to_blame.iter().filter(|hunk| !hunk.ignored) {
+                    if let Some(range_in_suspect) = hunk.get_range(&suspect) {
+                        let range_in_blamed_file = hunk.range_in_blamed_file.clone();
+
+                        let source_lines = range_in_suspect
+                            .clone()
+                            .map(|i| BString::new(source_interner[source_lines_as_tokens[i as usize]].into()))
+                            .collect::<Vec<_>>();
+                        let blamed_lines = range_in_blamed_file
+                            .clone()
+                            .map(|i| BString::new(blamed_interner[blamed_lines_as_tokens[i as usize]].into()))
+                            .collect::<Vec<_>>();
+
+                        assert_eq!(source_lines, blamed_lines);
+                    }
                 }
-                if no_change_in_entry {
-                    pass_blame_from_to(suspect, *parent_id, &mut hunks_to_blame);
-                    queue.insert(*parent_commit_time, *parent_id);
-                    continue 'outer;
+            }
+
+            for (pid, (parent_id, parent_commit_time)) in parent_ids.iter().enumerate() {
+                if let Some(parent_entry_id) = find_path_entry_in_commit(
+                    &odb,
+                    parent_id,
+                    current_file_path.as_ref(),
+                    cache.as_ref(),
+                    &mut buf,
+                    &mut buf2,
+                    &mut stats,
+                )? {
+                    let no_change_in_entry = entry_id == parent_entry_id;
+                    if pid == 0 {
+                        blamed_file.previous_entry = Some((*parent_id, current_file_path.clone(), parent_entry_id));
+                    }
+                    if no_change_in_entry {
+                        pass_blame_from_to(suspect, *parent_id, &mut hunks_to_blame);
+                        blamed_file.hunks_to_blame.extend(hunks_to_blame);
+                        queue_parent(&mut queue, *parent_id, *parent_commit_time);
+                        continue 'files;
+                    }
                 }
             }
+            files_to_diff.push(FileToDiff {
+                file_index,
+                current_file_path,
+                entry_id,
+                hunks_to_blame,
+                diffs_to_ignore: Vec::new(),
+                versions_in_parents: Vec::new(),
+            });
         }
 
+        let is_ignored = options.ignore_revs.contains(&suspect);
+        let copies_enabled = options
+            .moves_and_copies
+            .is_some_and(|moves_and_copies| moves_and_copies.copies.is_some());
+        // The files lines may have been copied from, per parent and kind of search, shared by all files.
+        let mut copy_sources = HashMap::new();
         let more_than_one_parent = parent_ids.len() > 1;
         for (index, (parent_id, parent_commit_time)) in parent_ids.iter().enumerate() {
-            queue.insert(*parent_commit_time, *parent_id);
-            let changes_for_file_path = tree_diff_at_file_path(
+            if files_to_diff.is_empty() {
+                break;
+            }
+            queue_parent(&mut queue, *parent_id, *parent_commit_time);
+            let changes_for_file_paths = tree_diff_at_file_paths(
                 &odb,
-                current_file_path.as_ref(),
+                files_to_diff
+                    .iter()
+                    .filter(|file| !file.hunks_to_blame.is_empty())
+                    .map(|file| file.current_file_path.as_ref()),
                 suspect,
                 *parent_id,
                 cache.as_ref(),
@@ -273,147 +548,452 @@ pub fn file(
                 &mut buf3,
                 options.rewrites,
             )?;
-            let Some(modification) = changes_for_file_path else {
-                if more_than_one_parent {
-                    // None of the changes affected the file we’re currently blaming.
-                    // Copy blame to parent.
-                    for unblamed_hunk in &mut hunks_to_blame {
-                        unblamed_hunk.clone_blame(suspect, *parent_id);
-                    }
-                } else {
-                    pass_blame_from_to(suspect, *parent_id, &mut hunks_to_blame);
+            for file in &mut files_to_diff {
+                if file.hunks_to_blame.is_empty() {
+                    continue;
                 }
-                continue;
-            };
-
-            match modification {
-                TreeDiffChange::Addition { id } => {
+                let blamed_file = &mut blamed_files[file.file_index];
+                let current_file_path = &file.current_file_path;
+                let hunks_to_blame = &mut file.hunks_to_blame;
+                let Some(modification) = changes_for_file_paths.get(current_file_path.as_bstr()).cloned() else {
+                    file.versions_in_parents
+                        .push((*parent_id, file.entry_id, current_file_path.clone()));
                     if more_than_one_parent {
-                        // Do nothing under the assumption that this always (or almost always)
-                        // implies that the file comes from a different parent, compared to which
-                        // it was modified, not added.
-                    } else if unblamed_to_out_is_done(&mut hunks_to_blame, &mut out, suspect) {
-                        if let Some(ref mut blame_path) = blame_path {
-                            let blame_path_entry = BlamePathEntry {
-                                source_file_path: current_file_path.clone(),
-                                previous_source_file_path: None,
-                                commit_id: suspect,
-                                blob_id: id,
-                                previous_blob_id: gix_hash::Kind::shortest().null(),
-                                parent_index: index,
-                            };
-                            blame_path.push(blame_path_entry);
+                        // None of the changes affected the file we’re currently blaming.
+                        // Copy blame to parent.
+                        for unblamed_hunk in hunks_to_blame {
+                            unblamed_hunk.clone_blame(suspect, *parent_id);
                         }
-
-                        break 'outer;
+                    } else {
+                        pass_blame_from_to(suspect, *parent_id, hunks_to_blame);
                     }
-                }
-                TreeDiffChange::Deletion => {
-                    unreachable!("We already found file_path in suspect^{{tree}}, so it can't be deleted")
-                }
-                TreeDiffChange::Modification { previous_id, id } => {
-                    let changes = blob_changes(
-                        &odb,
-                        resource_cache,
-                        id,
-                        previous_id,
-                        file_path,
-                        file_path,
-                        options.diff_algorithm,
-                        &mut stats,
-                    )?;
-                    hunks_to_blame = process_changes(hunks_to_blame, changes.clone(), suspect, *parent_id);
-                    if let Some(ref mut blame_path) = blame_path {
-                        let has_blame_been_passed = hunks_to_blame.iter().any(|hunk| hunk.has_suspect(parent_id));
-
-                        if has_blame_been_passed {
-                            let blame_path_entry = BlamePathEntry {
-                                source_file_path: current_file_path.clone(),
-                                previous_source_file_path: Some(current_file_path.clone()),
-                                commit_id: suspect,
-                                blob_id: id,
-                                previous_blob_id: previous_id,
-                                parent_index: index,
-                            };
-                            blame_path.push(blame_path_entry);
+                    continue;
+                };
+
+                match modification {
+                    TreeDiffChange::Addition { id } => {
+                        if more_than_one_parent {
+                            // Do nothing under the assumption that this always (or almost always)
+                            // implies that the file comes from a different parent, compared to which
+                            // it was modified, not added.
+                        } else if copies_enabled {
+                            // The lines may have been copied from other files, which is checked below.
+                        } else if unblamed_to_out_is_done(hunks_to_blame, &mut blamed_file.out, suspect) {
+                            if let Some(ref mut blame_path) = blamed_file.blame_path {
+                                let blame_path_entry = BlamePathEntry {
+                                    source_file_path: current_file_path.clone(),
+                                    previous_source_file_path: None,
+                                    commit_id: suspect,
+                                    blob_id: id,
+                                    previous_blob_id: gix_hash::Kind::shortest().null(),
+                                    parent_index: index,
+                                };
+                                blame_path.push(blame_path_entry);
+                            }
                         }
                     }
-                }
-                TreeDiffChange::Rewrite {
-                    source_location,
-                    source_id,
-                    id,
-                } => {
-                    let changes = blob_changes(
-                        &odb,
-                        resource_cache,
-                        id,
+                    TreeDiffChange::Deletion => {
+                        unreachable!("We already found file_path in suspect^{{tree}}, so it can't be deleted")
+                    }
+                    TreeDiffChange::Modification { previous_id, id } => {
+                        let changes = blob_changes(
+                            &odb,
+                            resource_cache,
+                            id,
+                            previous_id,
+                            blamed_file.path.as_ref(),
+                            blamed_file.path.as_ref(),
+                            options.diff_algorithm,
+                            options.whitespace,
+                            &mut stats,
+                        )?;
+                        *hunks_to_blame =
+                            process_changes(std::mem::take(hunks_to_blame), changes.clone(), suspect, *parent_id);
+                        if is_ignored {
+                            file.diffs_to_ignore.push((*parent_id, previous_id, changes, None));
+                        }
+                        file.versions_in_parents
+                            .push((*parent_id, previous_id, current_file_path.clone()));
+                        if let Some(ref mut blame_path) = blamed_file.blame_path {
+                            let has_blame_been_passed = hunks_to_blame.iter().any(|hunk| hunk.has_suspect(parent_id));
+
+                            if has_blame_been_passed {
+                                let blame_path_entry = BlamePathEntry {
+                                    source_file_path: current_file_path.clone(),
+                                    previous_source_file_path: Some(current_file_path.clone()),
+                                    commit_id: suspect,
+                                    blob_id: id,
+                                    previous_blob_id: previous_id,
+                                    parent_index: index,
+                                };
+                                blame_path.push(blame_path_entry);
+                            }
+                        }
+                    }
+                    TreeDiffChange::Rewrite {
+                        source_location,
                         source_id,
-                        file_path,
-                        source_location.as_ref(),
-                        options.diff_algorithm,
-                        &mut stats,
-                    )?;
-                    hunks_to_blame = process_changes(hunks_to_blame, changes, suspect, *parent_id);
+                        id,
+                    } => {
+                        let changes = blob_changes(
+                            &odb,
+                            resource_cache,
+                            id,
+                            source_id,
+                            blamed_file.path.as_ref(),
+                            source_location.as_ref(),
+                            options.diff_algorithm,
+                            options.whitespace,
+                            &mut stats,
+                        )?;
+                        *hunks_to_blame =
+                            process_changes(std::mem::take(hunks_to_blame), changes.clone(), suspect, *parent_id);
+                        if is_ignored {
+                            file.diffs_to_ignore
+                                .push((*parent_id, source_id, changes, Some(source_location.clone())));
+                        }
+                        file.versions_in_parents
+                            .push((*parent_id, source_id, source_location.clone()));
 
-                    let mut has_blame_been_passed = false;
+                        let mut has_blame_been_passed = false;
 
-                    for hunk in hunks_to_blame.iter_mut() {
-                        if hunk.has_suspect(parent_id) {
-                            hunk.source_file_name = Some(source_location.clone());
+                        for hunk in hunks_to_blame.iter_mut() {
+                            if hunk.has_suspect(parent_id) {
+                                hunk.source_file_name = Some(source_location.clone());
 
-                            has_blame_been_passed = true;
+                                has_blame_been_passed = true;
+                            }
                         }
-                    }
 
-                    if has_blame_been_passed {
-                        if let Some(ref mut blame_path) = blame_path {
-                            let blame_path_entry = BlamePathEntry {
-                                source_file_path: current_file_path.clone(),
-                                previous_source_file_path: Some(source_location.clone()),
-                                commit_id: suspect,
-                                blob_id: id,
-                                previous_blob_id: source_id,
-                                parent_index: index,
-                            };
-                            blame_path.push(blame_path_entry);
+                        if has_blame_been_passed {
+                            if let Some(ref mut blame_path) = blamed_file.blame_path {
+                                let blame_path_entry = BlamePathEntry {
+                                    source_file_path: current_file_path.clone(),
+                                    previous_source_file_path: Some(source_location.clone()),
+                                    commit_id: suspect,
+                                    blob_id: id,
+                                    previous_blob_id: source_id,
+                                    parent_index: index,
+                                };
+                                blame_path.push(blame_path_entry);
+                            }
                         }
                     }
                 }
             }
+            if all_done(&blamed_files) && files_to_diff.iter().all(|file| file.hunks_to_blame.is_empty()) {
+                break 'outer;
+            }
         }
 
-        hunks_to_blame.retain_mut(|unblamed_hunk| {
-            if unblamed_hunk.suspects.len() == 1 {
-                if let Some(entry) = BlameEntry::from_unblamed_hunk(unblamed_hunk, suspect) {
-                    // At this point, we have copied blame for every hunk to a parent. Hunks
-                    // that have only `suspect` left in `suspects` have not passed blame to any
-                    // parent, and so they can be converted to a `BlameEntry` and moved to
-                    // `out`.
-                    out.push(entry);
-                    return false;
+        for FileToDiff {
+            file_index,
+            current_file_path,
+            entry_id,
+            mut hunks_to_blame,
+            diffs_to_ignore,
+            versions_in_parents,
+        } in files_to_diff
+        {
+            let blamed_file = &mut blamed_files[file_index];
+            let mut blob = None;
+            // Like `git`, pass what's left of an ignored commit to the parents it was diffed against,
+            // one after another, with each line going to the most similar line in the parent.
+            let has_unblamable_lines = !diffs_to_ignore.is_empty();
+            if has_unblamable_lines {
+                let blob = blob.insert(odb.find_blob(&entry_id, &mut buf)?.data.to_vec());
+                for (parent_id, previous_id, changes, source_file_name) in diffs_to_ignore {
+                    let previous_blob = odb.find_blob(&previous_id, &mut buf2)?;
+                    let line_matches = ignore::guess_line_matches(previous_blob.data, blob, &changes);
+                    hunks_to_blame = ignore::pass_ignored_lines(
+                        hunks_to_blame,
+                        suspect,
+                        parent_id,
+                        &line_matches,
+                        source_file_name.as_ref(),
+                    );
                 }
             }
-            unblamed_hunk.remove_blame(suspect);
-            true
-        });
+
+            if let Some(moves_and_copies) = options.moves_and_copies {
+                if has_lines_only_blamed_on(&hunks_to_blame, suspect) {
+                    let blob = match blob {
+                        Some(blob) => blob,
+                        None => odb.find_blob(&entry_id, &mut buf)?.data.to_vec(),
+                    };
+                    hunks_to_blame = pass_moved_and_copied_lines(
+                        &odb,
+                        hunks_to_blame,
+                        suspect,
+                        &parent_ids,
+                        &blob,
+                        blamed_file.path.as_ref(),
+                        current_file_path.as_ref(),
+                        &versions_in_parents,
+                        moves_and_copies,
+                        &mut copy_sources,
+                        options.diff_algorithm,
+                        options.whitespace,
+                        cache.as_ref(),
+                        &mut stats,
+                        &mut diff_state,
+                        &mut buf,
+                        &mut buf2,
+                        &mut buf3,
+                    )?;
+                }
+            }
+
+            let out = &mut blamed_file.out;
+            hunks_to_blame.retain_mut(|unblamed_hunk| {
+                if unblamed_hunk.suspects.len() == 1 {
+                    if let Some(mut entry) = BlameEntry::from_unblamed_hunk(unblamed_hunk, suspect) {
+                        entry.unblamable = has_unblamable_lines;
+                        // At this point, we have copied blame for every hunk to a parent. Hunks
+                        // that have only `suspect` left in `suspects` have not passed blame to any
+                        // parent, and so they can be converted to a `BlameEntry` and moved to
+                        // `out`.
+                        out.push(entry);
+                        return false;
+                    }
+                }
+                unblamed_hunk.remove_blame(suspect);
+                true
+            });
+            blamed_file.hunks_to_blame.extend(hunks_to_blame);
+        }
     }
 
-    debug_assert_eq!(
-        hunks_to_blame,
-        vec![],
-        "only if there is no portion of the file left we have completed the blame"
-    );
+    Ok(blamed_files
+        .into_iter()
+        .enumerate()
+        .map(|(file_index, mut blamed_file)| {
+            blamed_file.pass_on_new_entries(options, |entry| on_entry(file_index, entry));
+            blamed_file.into_outcome(options, stats)
+        })
+        .collect())
+}
+
+/// A file that changed between a suspect and its parents, along with the hunks associated with its path in the suspect
+/// while the suspect is processed.
+struct FileToDiff {
+    /// The index of the *Blamed File* the hunks belong to.
+    file_index: usize,
+    /// The path of the file in the suspect.
+    current_file_path: BString,
+    /// The id of the file in the suspect.
+    entry_id: ObjectId,
+    hunks_to_blame: Vec<UnblamedHunk>,
+    /// The parent, the id of the file in it and the changes to it, along with its path if it was renamed,
+    /// for passing on the lines of an ignored suspect.
+    diffs_to_ignore: Vec<(ObjectId, ObjectId, Vec<Change>, Option<BString>)>,
+    /// The parent, along with the id and path of the file in it, for each parent that has a version of the file.
+    versions_in_parents: Vec<(ObjectId, ObjectId, BString)>,
+}
 
-    // I don’t know yet whether it would make sense to use a data structure instead that preserves
-    // order on insertion.
-    out.sort_by_key(|a| a.start_in_blamed_file);
-    Ok(Outcome {
-        entries: coalesce_blame_entries(out),
-        blob: blamed_file_blob,
-        statistics: stats,
-        blame_path,
-    })
+/// Return `true` if some lines in `hunks_to_blame` are blamed on `suspect`, and on no other commit.
+fn has_lines_only_blamed_on(hunks_to_blame: &[UnblamedHunk], suspect: ObjectId) -> bool {
+    hunks_to_blame
+        .iter()
+        .any(|hunk| hunk.suspects.len() == 1 && hunk.has_suspect(&suspect))
+}
+
+/// Pass the lines in `hunks_to_blame` that are only blamed on `suspect` to the parent they were moved or copied from,
+/// like `git blame -M` and `-C`, and return what's left.
+///
+/// `blob` is the content of the file at `current_file_path` in `suspect`, whose `versions_in_parents` are searched for
+/// moved lines first. If enabled, other files in each of `parent_ids` are searched for copied lines next, with
+/// `copy_sources` keeping them for all files of `suspect`.
+#[allow(clippy::too_many_arguments)]
+fn pass_moved_and_copied_lines(
+    odb: &(impl gix_object::Find + gix_object::FindHeader),
+    mut hunks_to_blame: Vec<UnblamedHunk>,
+    suspect: ObjectId,
+    parent_ids: &ParentIds,
+    blob: &[u8],
+    blamed_file_path: &BStr,
+    current_file_path: &BStr,
+    versions_in_parents: &[(ObjectId, ObjectId, BString)],
+    moves_and_copies: MovesAndCopies,
+    copy_sources: &mut HashMap<(ObjectId, bool), Vec<(BString, ObjectId)>>,
+    diff_algorithm: gix_diff::blob::Algorithm,
+    whitespace: gix_diff::blob::Whitespace,
+    cache: Option<&gix_commitgraph::Graph>,
+    stats: &mut Statistics,
+    diff_state: &mut gix_diff::tree::State,
+    commit_buf: &mut Vec<u8>,
+    lhs_tree_buf: &mut Vec<u8>,
+    rhs_tree_buf: &mut Vec<u8>,
+) -> Result<Vec<UnblamedHunk>, Error> {
+    let source = |path: &BString, id: &ObjectId, buf: &mut Vec<u8>| -> Result<moves::Source, Error> {
+        Ok(moves::Source {
+            data: odb.find_blob(id, buf)?.data.to_vec(),
+            source_file_name: (path.as_bstr() != blamed_file_path).then(|| path.clone()),
+        })
+    };
+
+    for (parent_id, previous_id, path) in versions_in_parents {
+        if !has_lines_only_blamed_on(&hunks_to_blame, suspect) {
+            return Ok(hunks_to_blame);
+        }
+        let sources = [source(path, previous_id, commit_buf)?];
+        hunks_to_blame = moves::pass_moved_lines(
+            hunks_to_blame,
+            suspect,
+            *parent_id,
+            blob,
+            &sources,
+            moves_and_copies.move_score,
+            diff_algorithm,
+            whitespace,
+        );
+    }
+
+    let Some(copies) = moves_and_copies.copies else {
+        return Ok(hunks_to_blame);
+    };
+    for (parent_id, _) in parent_ids {
+        if !has_lines_only_blamed_on(&hunks_to_blame, suspect) {
+            break;
+        }
+        let path_in_parent = versions_in_parents
+            .iter()
+            .find(|(id, ..)| id == parent_id)
+            .map(|(_, _, path)| path.as_bstr());
+        let all_files = match copies {
+            Copies::ModifiedFiles => false,
+            Copies::AllFilesInCreatingCommit => path_in_parent != Some(current_file_path),
+            Copies::AllFiles => true,
+        };
+        let candidates = match copy_sources.entry((*parent_id, all_files)) {
+            std::collections::hash_map::Entry::Occupied(entry) => entry.into_mut(),
+            std::collections::hash_map::Entry::Vacant(entry) => entry.insert(files_to_copy_from(
+                odb,
+                suspect,
+                *parent_id,
+                all_files,
+                cache,
+                stats,
+                diff_state,
+                commit_buf,
+                lhs_tree_buf,
+                rhs_tree_buf,
+            )?),
+        };
+        // The version of the file in the parent was searched for moved lines already.
+        let sources = candidates
+            .iter()
+            .filter(|(path, _)| Some(path.as_bstr()) != path_in_parent)
+            .map(|(path, id)| source(path, id, commit_buf))
+            .collect::<Result<Vec<_>, _>>()?;
+        hunks_to_blame = moves::pass_moved_lines(
+            hunks_to_blame,
+            suspect,
+            *parent_id,
+            blob,
+            &sources,
+            moves_and_copies.copy_score,
+            diff_algorithm,
+            whitespace,
+        );
+    }
+    Ok(hunks_to_blame)
+}
+
+/// Return the path and id of the blobs in `parent_id` that lines in `suspect` may have been copied from, which
+/// are all of them if `all_files` is `true`, or only those that were modified or deleted in `suspect` otherwise.
+#[allow(clippy::too_many_arguments)]
+fn files_to_copy_from(
+    odb: &(impl gix_object::Find + gix_object::FindHeader),
+    suspect: ObjectId,
+    parent_id: ObjectId,
+    all_files: bool,
+    cache: Option<&gix_commitgraph::Graph>,
+    stats: &mut Statistics,
+    diff_state: &mut gix_diff::tree::State,
+    commit_buf: &mut Vec<u8>,
+    lhs_tree_buf: &mut Vec<u8>,
+    rhs_tree_buf: &mut Vec<u8>,
+) -> Result<Vec<(BString, ObjectId)>, Error> {
+    let parent_tree_id = find_commit(cache, odb, &parent_id, commit_buf)?.tree_id()?;
+    let parent_tree_iter = odb.find_tree_iter(&parent_tree_id, lhs_tree_buf)?;
+    stats.trees_decoded += 1;
+
+    if all_files {
+        let mut recorder = gix_traverse::tree::Recorder::default();
+        gix_traverse::tree::breadthfirst(
+            parent_tree_iter,
+            gix_traverse::tree::breadthfirst::State::default(),
+            odb,
+            &mut recorder,
+        )?;
+        return Ok(recorder
+            .records
+            .into_iter()
+            .filter(|entry| entry.mode.is_blob_or_symlink())
+            .map(|entry| (entry.filepath, entry.oid))
+            .collect());
+    }
+
+    let tree_id = find_commit(cache, odb, &suspect, commit_buf)?.tree_id()?;
+    let tree_iter = odb.find_tree_iter(&tree_id, rhs_tree_buf)?;
+    stats.trees_decoded += 1;
+
+    let mut recorder = gix_diff::tree::Recorder::default();
+    gix_diff::tree(parent_tree_iter, tree_iter, diff_state, odb, &mut recorder)?;
+    stats.trees_diffed += 1;
+    Ok(recorder
+        .records
+        .into_iter()
+        .filter_map(|change| match change {
+            gix_diff::tree::recorder::Change::Modification {
+                previous_entry_mode,
+                previous_oid,
+                path,
+                ..
+            }
+            | gix_diff::tree::recorder::Change::Deletion {
+                entry_mode: previous_entry_mode,
+                oid: previous_oid,
+                path,
+                ..
+            } => previous_entry_mode.is_blob_or_symlink().then_some((path, previous_oid)),
+            gix_diff::tree::recorder::Change::Addition { .. } => None,
+        })
+        .collect())
+}
+
+/// Return `true` if there is nothing left to blame in any of `blamed_files`.
+fn all_done(blamed_files: &[BlamedFile]) -> bool {
+    blamed_files
+        .iter()
+        .all(|blamed_file| blamed_file.hunks_to_blame.is_empty())
+}
+
+/// Return the children of all commits in the range `start..end`, along with `start` itself, and their commit times.
+fn collect_children(
+    odb: &impl gix_object::Find,
+    start: ObjectId,
+    end: ObjectId,
+) -> Result<gix_hashtable::HashMap<ObjectId, ParentIds>, Error> {
+    use gix_traverse::commit::{Simple, simple::CommitTimeOrder, simple::Sorting};
+
+    let mut children = gix_hashtable::HashMap::<ObjectId, ParentIds>::default();
+    let walk = Simple::new([end], odb)
+        .sorting(Sorting::ByCommitTime(CommitTimeOrder::NewestFirst))
+        .map_err(|err| Error::Traverse(err.into()))?
+        .hide([start])
+        .map_err(|err| Error::Traverse(err.into()))?;
+    for info in walk {
+        let info = info.map_err(|err| Error::Traverse(err.into()))?;
+        for parent_id in info.parent_ids {
+            children
+                .entry(parent_id)
+                .or_default()
+                .push((info.id, info.commit_time.unwrap_or_default()));
+        }
+    }
+    Ok(children)
 }
 
 /// Pass ownership of each unblamed hunk of `from` to `to`.
@@ -466,6 +1046,10 @@ fn coalesce_blame_entries(lines_blamed: Vec<BlameEntry>) -> Vec<BlameEntry> {
                 let previous_source_range = previous_entry.range_in_source_file();
                 let current_source_range = entry.range_in_source_file();
                 if previous_entry.commit_id == entry.commit_id
+                    // Lines copied from different files of the same commit stay apart.
+                    && previous_entry.source_file_name == entry.source_file_name
+                    && previous_entry.ignored == entry.ignored
+                    && previous_entry.unblamable == entry.unblamable
                     && previous_blamed_range.end == current_blamed_range.start
                     // As of 2024-09-19, the check below only is in `git`, but not in `libgit2`.
                     && previous_source_range.end == current_source_range.start
@@ -477,6 +1061,8 @@ fn coalesce_blame_entries(lines_blamed: Vec<BlameEntry>) -> Vec<BlameEntry> {
                             .expect("BUG: hunks are never zero-sized"),
                         commit_id: previous_entry.commit_id,
                         source_file_name: previous_entry.source_file_name.clone(),
+                        ignored: previous_entry.ignored,
+                        unblamable: previous_entry.unblamable,
                     };
 
                     acc.pop();
@@ -496,6 +1082,7 @@ fn coalesce_blame_entries(lines_blamed: Vec<BlameEntry>) -> Vec<BlameEntry> {
 
 /// The union of [`gix_diff::tree::recorder::Change`] and [`gix_diff::tree_with_rewrites::Change`],
 /// keeping only the blame-relevant information.
+#[derive(Clone)]
 enum TreeDiffChange {
     Addition {
         id: ObjectId,
@@ -549,10 +1136,12 @@ impl From<gix_diff::tree_with_rewrites::Change> for TreeDiffChange {
     }
 }
 
+/// Diff the trees of `parent_id` and `id` and return the changes to each of `file_paths`, keyed by path.
+/// Paths that didn't change are not part of the result.
 #[allow(clippy::too_many_arguments)]
-fn tree_diff_at_file_path(
+fn tree_diff_at_file_paths<'a>(
     odb: impl gix_object::Find + gix_object::FindHeader,
-    file_path: &BStr,
+    file_paths: impl IntoIterator<Item = &'a BStr>,
     id: ObjectId,
     parent_id: ObjectId,
     cache: Option<&gix_commitgraph::Graph>,
@@ -563,7 +1152,11 @@ fn tree_diff_at_file_path(
     lhs_tree_buf: &mut Vec<u8>,
     rhs_tree_buf: &mut Vec<u8>,
     rewrites: Option<gix_diff::Rewrites>,
-) -> Result<Option<TreeDiffChange>, Error> {
+) -> Result<HashMap<BString, TreeDiffChange>, Error> {
+    let file_paths: HashSet<BString> = file_paths.into_iter().map(ToOwned::to_owned).collect();
+    if file_paths.is_empty() {
+        return Ok(HashMap::default());
+    }
     let parent_tree_id = find_commit(cache, &odb, &parent_id, commit_buf)?.tree_id()?;
 
     let parent_tree_iter = odb.find_tree_iter(&parent_tree_id, lhs_tree_buf)?;
@@ -574,7 +1167,8 @@ fn tree_diff_at_file_path(
     let tree_iter = odb.find_tree_iter(&tree_id, rhs_tree_buf)?;
     stats.trees_decoded += 1;
 
-    let result = tree_diff_without_rewrites_at_file_path(&odb, file_path, stats, state, parent_tree_iter, tree_iter)?;
+    let mut result =
+        tree_diff_without_rewrites_at_file_paths(&odb, file_paths.clone(), stats, state, parent_tree_iter, tree_iter)?;
 
     // Here, we follow git’s behaviour. We return when we’ve found a `Modification`. We try a
     // second time with rename tracking when the change is either an `Addition` or a `Deletion`
@@ -582,16 +1176,20 @@ fn tree_diff_at_file_path(
     // TODO(perf): renames are usually rare enough to not care about the work duplication done here.
     //             But in theory, a rename tracker could be used by us, on demand, and we could stuff the
     //             changes in there and have it find renames, without repeating the diff.
-    if matches!(result, Some(TreeDiffChange::Modification { .. })) {
-        return Ok(result);
-    }
     let Some(rewrites) = rewrites else {
         return Ok(result);
     };
+    let file_paths_to_track: HashSet<BString> = file_paths
+        .into_iter()
+        .filter(|path| !matches!(result.get(path), Some(TreeDiffChange::Modification { .. })))
+        .collect();
+    if file_paths_to_track.is_empty() {
+        return Ok(result);
+    }
 
-    let result = tree_diff_with_rewrites_at_file_path(
+    let result_with_rewrites = tree_diff_with_rewrites_at_file_paths(
         &odb,
-        file_path,
+        &file_paths_to_track,
         stats,
         state,
         resource_cache,
@@ -599,39 +1197,42 @@ fn tree_diff_at_file_path(
         tree_iter,
         rewrites,
     )?;
+    for path in file_paths_to_track {
+        result.remove(&path);
+    }
+    result.extend(result_with_rewrites);
 
     Ok(result)
 }
 
-#[allow(clippy::too_many_arguments)]
-fn tree_diff_without_rewrites_at_file_path(
+fn tree_diff_without_rewrites_at_file_paths(
     odb: impl gix_object::Find + gix_object::FindHeader,
-    file_path: &BStr,
+    file_paths: HashSet<BString>,
     stats: &mut Statistics,
     state: &mut gix_diff::tree::State,
     parent_tree_iter: gix_object::TreeRefIter<'_>,
     tree_iter: gix_object::TreeRefIter<'_>,
-) -> Result<Option<TreeDiffChange>, Error> {
-    struct FindChangeToPath {
+) -> Result<HashMap<BString, TreeDiffChange>, Error> {
+    struct FindChangeToPaths {
         inner: gix_diff::tree::Recorder,
-        interesting_path: BString,
-        change: Option<gix_diff::tree::recorder::Change>,
+        interesting_paths: HashSet<BString>,
+        changes: HashMap<BString, TreeDiffChange>,
     }
 
-    impl FindChangeToPath {
-        fn new(interesting_path: BString) -> Self {
+    impl FindChangeToPaths {
+        fn new(interesting_paths: HashSet<BString>) -> Self {
             let inner =
                 gix_diff::tree::Recorder::default().track_location(Some(gix_diff::tree::recorder::Location::Path));
 
-            FindChangeToPath {
+            FindChangeToPaths {
                 inner,
-                interesting_path,
-                change: None,
+                interesting_paths,
+                changes: HashMap::default(),
             }
         }
     }
 
-    impl Visit for FindChangeToPath {
+    impl Visit for FindChangeToPaths {
         fn pop_front_tracked_path_and_set_current(&mut self) {
             self.inner.pop_front_tracked_path_and_set_current();
         }
@@ -651,42 +1252,46 @@ fn tree_diff_without_rewrites_at_file_path(
         fn visit(&mut self, change: gix_diff::tree::visit::Change) -> gix_diff::tree::visit::Action {
             use gix_diff::tree::visit::Change::*;
 
-            if self.inner.path() == self.interesting_path {
-                self.change = Some(match change {
-                    Deletion {
-                        entry_mode,
-                        oid,
-                        relation,
-                    } => gix_diff::tree::recorder::Change::Deletion {
-                        entry_mode,
-                        oid,
-                        path: self.inner.path_clone(),
-                        relation,
-                    },
-                    Addition {
-                        entry_mode,
-                        oid,
-                        relation,
-                    } => gix_diff::tree::recorder::Change::Addition {
-                        entry_mode,
-                        oid,
-                        path: self.inner.path_clone(),
-                        relation,
-                    },
-                    Modification {
-                        previous_entry_mode,
-                        previous_oid,
-                        entry_mode,
-                        oid,
-                    } => gix_diff::tree::recorder::Change::Modification {
-                        previous_entry_mode,
-                        previous_oid,
-                        entry_mode,
-                        oid,
-                        path: self.inner.path_clone(),
-                    },
-                });
+            if !self.interesting_paths.contains(self.inner.path()) {
+                return std::ops::ControlFlow::Continue(());
+            }
+            let change = match change {
+                Deletion {
+                    entry_mode,
+                    oid,
+                    relation,
+                } => gix_diff::tree::recorder::Change::Deletion {
+                    entry_mode,
+                    oid,
+                    path: self.inner.path_clone(),
+                    relation,
+                },
+                Addition {
+                    entry_mode,
+                    oid,
+                    relation,
+                } => gix_diff::tree::recorder::Change::Addition {
+                    entry_mode,
+                    oid,
+                    path: self.inner.path_clone(),
+                    relation,
+                },
+                Modification {
+                    previous_entry_mode,
+                    previous_oid,
+                    entry_mode,
+                    oid,
+                } => gix_diff::tree::recorder::Change::Modification {
+                    previous_entry_mode,
+                    previous_oid,
+                    entry_mode,
+                    oid,
+                    path: self.inner.path_clone(),
+                },
+            };
+            self.changes.insert(self.inner.path_clone(), change.into());
 
+            if self.changes.len() == self.interesting_paths.len() {
                 std::ops::ControlFlow::Break(())
             } else {
                 std::ops::ControlFlow::Continue(())
@@ -694,28 +1299,28 @@ fn tree_diff_without_rewrites_at_file_path(
         }
     }
 
-    let mut recorder = FindChangeToPath::new(file_path.into());
+    let mut recorder = FindChangeToPaths::new(file_paths);
     let result = gix_diff::tree(parent_tree_iter, tree_iter, state, &odb, &mut recorder);
     stats.trees_diffed += 1;
 
     match result {
-        Ok(_) | Err(gix_diff::tree::Error::Cancelled) => Ok(recorder.change.map(Into::into)),
+        Ok(_) | Err(gix_diff::tree::Error::Cancelled) => Ok(recorder.changes),
         Err(error) => Err(Error::DiffTree(error)),
     }
 }
 
 #[allow(clippy::too_many_arguments)]
-fn tree_diff_with_rewrites_at_file_path(
+fn tree_diff_with_rewrites_at_file_paths(
     odb: impl gix_object::Find + gix_object::FindHeader,
-    file_path: &BStr,
+    file_paths: &HashSet<BString>,
     stats: &mut Statistics,
     state: &mut gix_diff::tree::State,
     resource_cache: &mut gix_diff::blob::Platform,
     parent_tree_iter: gix_object::TreeRefIter<'_>,
     tree_iter: gix_object::TreeRefIter<'_>,
     rewrites: gix_diff::Rewrites,
-) -> Result<Option<TreeDiffChange>, Error> {
-    let mut change: Option<gix_diff::tree_with_rewrites::Change> = None;
+) -> Result<HashMap<BString, TreeDiffChange>, Error> {
+    let mut changes = HashMap::default();
 
     let options: gix_diff::tree_with_rewrites::Options = gix_diff::tree_with_rewrites::Options {
         location: Some(gix_diff::tree::recorder::Location::Path),
@@ -728,21 +1333,21 @@ fn tree_diff_with_rewrites_at_file_path(
         state,
         &odb,
         |change_ref| -> Result<_, std::convert::Infallible> {
-            if change_ref.location() == file_path {
-                change = Some(change_ref.into_owned());
-                Ok(std::ops::ControlFlow::Break(()))
-            } else {
-                Ok(std::ops::ControlFlow::Continue(()))
+            if file_paths.contains(change_ref.location()) {
+                let change = change_ref.into_owned();
+                changes.insert(change.location().to_owned(), change.into());
+                if changes.len() == file_paths.len() {
+                    return Ok(std::ops::ControlFlow::Break(()));
+                }
             }
+            Ok(std::ops::ControlFlow::Continue(()))
         },
         options,
     );
     stats.trees_diffed_with_rewrites += 1;
 
     match result {
-        Ok(_) | Err(gix_diff::tree_with_rewrites::Error::Diff(gix_diff::tree::Error::Cancelled)) => {
-            Ok(change.map(Into::into))
-        }
+        Ok(_) | Err(gix_diff::tree_with_rewrites::Error::Diff(gix_diff::tree::Error::Cancelled)) => Ok(changes),
         Err(error) => Err(Error::DiffTreeWithRewrites(error)),
     }
 }
@@ -756,10 +1361,9 @@ fn blob_changes(
     file_path: &BStr,
     previous_file_path: &BStr,
     diff_algorithm: gix_diff::blob::Algorithm,
+    whitespace: gix_diff::blob::Whitespace,
     stats: &mut Statistics,
 ) -> Result<Vec<Change>, Error> {
-    use gix_diff::blob::Hunk;
-
     resource_cache.set_resource(
         previous_oid,
         gix_object::tree::EntryKind::Blob,
@@ -776,13 +1380,29 @@ fn blob_changes(
     )?;
 
     let outcome = resource_cache.prepare_diff()?;
-    let input = gix_diff::blob::InternedInput::new(
+    let changes = changes_between(
         outcome.old.data.as_slice().unwrap_or_default(),
         outcome.new.data.as_slice().unwrap_or_default(),
+        diff_algorithm,
+        whitespace,
     );
 
-    let mut diff = gix_diff::blob::Diff::compute(diff_algorithm, &input);
-    diff.postprocess_lines(&input);
+    stats.blobs_diffed += 1;
+    Ok(changes)
+}
+
+/// Diff `old` and `new` line by line and return the changes that turn `old` into `new`, along with the regions in
+/// between that didn't change. Lines that only differ in whitespace ignored according to `whitespace` are unchanged.
+pub(super) fn changes_between(
+    old: &[u8],
+    new: &[u8],
+    diff_algorithm: gix_diff::blob::Algorithm,
+    whitespace: gix_diff::blob::Whitespace,
+) -> Vec<Change> {
+    use gix_diff::blob::Hunk;
+
+    let input = gix_diff::blob::InternedInput::new(old, new);
+    let diff = gix_diff::blob::diff_ignoring_whitespace(diff_algorithm, &input, whitespace);
 
     let mut last_seen_after_end = 0;
     let mut changes = diff.hunks().fold(Vec::new(), |mut hunks, hunk| {
@@ -815,9 +1435,7 @@ fn blob_changes(
     if input.after.len() > last_seen_after_end as usize {
         changes.push(Change::Unchanged(last_seen_after_end..total_number_of_lines));
     }
-
-    stats.blobs_diffed += 1;
-    Ok(changes)
+    changes
 }
 
 fn find_path_entry_in_commit(
@@ -0,0 +1,272 @@
+//! Support for ignoring commits, which requires guessing which line in a parent a changed line originated from.
+//!
+//! This follows the heuristic `git` uses when ignoring revisions: each line is reduced to a fingerprint, the multiset
+//! of pairs of adjacent characters, and lines in the *Blamed File* are matched to the most similar line in the parent
+//! close to their proportional position within a changed chunk. Matches are assigned in order of certainty, they
+//! never cross each other, and each match removes the pairs it shares from the parent line.
+use std::ops::Range;
+
+use gix_diff::blob::TokenSource;
+use gix_hash::ObjectId;
+use gix_object::bstr::BString;
+
+use super::function::tokens_for_diffing;
+use crate::types::{Change, UnblamedHunk};
+
+/// Only lines in the parent that are at most this many lines away from the line at the same proportional position
+/// in the chunk are considered as match.
+const MAX_SEARCH_DISTANCE: usize = 10;
+
+/// Return, for each line in `after`, the line in `before` it most likely originated from, based on `changes` which
+/// turn `before` into `after`.
+///
+/// Only lines within changed chunks are matched, all other lines are `None`.
+pub(super) fn guess_line_matches(before: &[u8], after: &[u8], changes: &[Change]) -> Vec<Option<u32>> {
+    let lines_before: Vec<_> = tokens_for_diffing(before).tokenize().collect();
+    let lines_after: Vec<_> = tokens_for_diffing(after).tokenize().collect();
+
+    let mut matches = vec![None; lines_after.len()];
+    let mut line_in_before = 0;
+    for change in changes {
+        match change {
+            Change::Unchanged(range) => line_in_before += range.len(),
+            Change::AddedOrReplaced(added, number_of_lines_deleted) => {
+                let deleted = line_in_before..line_in_before + *number_of_lines_deleted as usize;
+                let added = added.start as usize..added.end as usize;
+                if let (Some(lines_before), Some(lines_after)) =
+                    (lines_before.get(deleted.clone()), lines_after.get(added.clone()))
+                {
+                    let mut chunk = Chunk::new(lines_before, lines_after);
+                    chunk.find_matches(0..deleted.len(), 0..added.len(), &mut matches[added.clone()]);
+                    for line in matches[added].iter_mut().flatten() {
+                        *line += deleted.start as u32;
+                    }
+                }
+                line_in_before = deleted.end;
+            }
+            Change::Deleted(_, number_of_lines_deleted) => line_in_before += *number_of_lines_deleted as usize,
+        }
+    }
+    matches
+}
+
+/// Pass the lines of all hunks in `hunks_to_blame` that are only blamed on `suspect` to `parent`, if they have a
+/// match in `line_matches` as produced by [`guess_line_matches()`].
+///
+/// Lines passed to `parent` are marked as ignored, and are associated with `source_file_name` if the file had a
+/// different name in `parent`. Lines without a match remain with `suspect`.
+pub(super) fn pass_ignored_lines(
+    hunks_to_blame: Vec<UnblamedHunk>,
+    suspect: ObjectId,
+    parent: ObjectId,
+    line_matches: &[Option<u32>],
+    source_file_name: Option<&BString>,
+) -> Vec<UnblamedHunk> {
+    let mut new_hunks_to_blame = Vec::with_capacity(hunks_to_blame.len());
+    for hunk in hunks_to_blame {
+        let range_in_suspect = match hunk.suspects.as_slice() {
+            [(id, range)] if *id == suspect => range.clone(),
+            _ => {
+                new_hunks_to_blame.push(hunk);
+                continue;
+            }
+        };
+
+        let line_match = |line: u32| line_matches.get(line as usize).copied().flatten();
+        let mut run_start = range_in_suspect.start;
+        while run_start < range_in_suspect.end {
+            let first_match = line_match(run_start);
+            let mut run_end = run_start + 1;
+            while run_end < range_in_suspect.end
+                && match (first_match, line_match(run_end)) {
+                    (Some(first), Some(current)) => current == first + (run_end - run_start),
+                    (None, None) => true,
+                    _ => false,
+                }
+            {
+                run_end += 1;
+            }
+
+            let start_in_blamed_file = hunk.range_in_blamed_file.start + (run_start - range_in_suspect.start);
+            let range_in_blamed_file = start_in_blamed_file..start_in_blamed_file + (run_end - run_start);
+            new_hunks_to_blame.push(match first_match {
+                Some(line_in_parent) => UnblamedHunk {
+                    range_in_blamed_file,
+                    suspects: [(parent, line_in_parent..line_in_parent + (run_end - run_start))].into(),
+                    source_file_name: source_file_name.cloned().or_else(|| hunk.source_file_name.clone()),
+                    ignored: true,
+                },
+                None => UnblamedHunk {
+                    range_in_blamed_file,
+                    suspects: [(suspect, run_start..run_end)].into(),
+                    source_file_name: hunk.source_file_name.clone(),
+                    ignored: hunk.ignored,
+                },
+            });
+            run_start = run_end;
+        }
+    }
+    new_hunks_to_blame
+}
+
+/// The multiset of pairs of adjacent characters in a line, sorted for efficient comparison.
+///
+/// Letters are normalized to lower-case and whitespace is treated as a single kind of character. The line is
+/// considered to be surrounded by whitespace, and pairs of whitespace are ignored.
+struct Fingerprint(Vec<u16>);
+
+impl Fingerprint {
+    fn new(line: &[u8]) -> Self {
+        let normalize = |byte: &u8| {
+            if byte.is_ascii_whitespace() {
+                0
+            } else {
+                byte.to_ascii_lowercase()
+            }
+        };
+        let mut pairs = Vec::with_capacity(line.len() + 1);
+        let mut previous = 0u8;
+        for current in line.iter().map(normalize).chain(Some(0)) {
+            let pair = u16::from(previous) | (u16::from(current) << 8);
+            if pair != 0 {
+                pairs.push(pair);
+            }
+            previous = current;
+        }
+        pairs.sort_unstable();
+        Fingerprint(pairs)
+    }
+
+    /// Return the amount of pairs both fingerprints have in common.
+    fn similarity(&self, other: &Fingerprint) -> u32 {
+        let mut common = 0;
+        self.merge_with(other, |_| common += 1);
+        common
+    }
+
+    /// Remove all pairs that are also in `other`, so they can't be matched again.
+    fn subtract(&mut self, other: &Fingerprint) {
+        let mut common = Vec::new();
+        self.merge_with(other, |index| common.push(index));
+        for index in common.into_iter().rev() {
+            self.0.remove(index);
+        }
+    }
+
+    /// Call `on_common_pair` with the index of each pair in `self` that is also in `other`.
+    fn merge_with(&self, other: &Fingerprint, mut on_common_pair: impl FnMut(usize)) {
+        let (mut lhs, mut rhs) = (0, 0);
+        while let (Some(a), Some(b)) = (self.0.get(lhs), other.0.get(rhs)) {
+            match a.cmp(b) {
+                std::cmp::Ordering::Less => lhs += 1,
+                std::cmp::Ordering::Greater => rhs += 1,
+                std::cmp::Ordering::Equal => {
+                    on_common_pair(lhs);
+                    lhs += 1;
+                    rhs += 1;
+                }
+            }
+        }
+    }
+}
+
+/// A chunk of lines that were replaced by other lines, with `a` being the lines before and `b` being the lines after.
+struct Chunk {
+    a: Vec<Fingerprint>,
+    b: Vec<Fingerprint>,
+    max_search_distance: usize,
+    /// Cached similarities of each line in `b` with the lines in `a` within `max_search_distance` of its closest line,
+    /// along with the version of the line in `a` they were computed for.
+    similarities: Vec<Option<(u32, u32)>>,
+    /// The amount of times each line in `a` was changed by subtracting a matching line from it.
+    versions_of_a: Vec<u32>,
+}
+
+impl Chunk {
+    fn new(a: &[&[u8]], b: &[&[u8]]) -> Self {
+        let max_search_distance = MAX_SEARCH_DISTANCE.min(a.len().saturating_sub(1));
+        Chunk {
+            a: a.iter().map(|line| Fingerprint::new(line)).collect(),
+            b: b.iter().map(|line| Fingerprint::new(line)).collect(),
+            max_search_distance,
+            similarities: vec![None; b.len() * (max_search_distance * 2 + 1)],
+            versions_of_a: vec![0; a.len()],
+        }
+    }
+
+    /// Return the line in `a` that is at the same proportional position as `line_b` is in `b`.
+    fn closest_line_in_a(&self, line_b: usize) -> usize {
+        ((line_b * 2 + 1) * self.a.len()) / (self.b.len() * 2)
+    }
+
+    /// Return the similarity of `line_a` and `line_b`, scaled down by their distance to break ties in favor of
+    /// the line closest to `line_b`.
+    fn similarity(&mut self, line_a: usize, line_b: usize) -> u32 {
+        let closest = self.closest_line_in_a(line_b);
+        let index = line_b * (self.max_search_distance * 2 + 1) + line_a + self.max_search_distance - closest;
+        let version = self.versions_of_a[line_a];
+        match self.similarities[index] {
+            Some((cached_version, similarity)) if cached_version == version => similarity,
+            _ => {
+                let similarity = self.a[line_a].similarity(&self.b[line_b]) * (1000 - line_a.abs_diff(closest) as u32);
+                self.similarities[index] = Some((version, similarity));
+                similarity
+            }
+        }
+    }
+
+    /// Return the best match in `range_a` for `line_b` along with how certain we are about it,
+    /// or `None` if there is no similar line.
+    fn best_match(&mut self, line_b: usize, range_a: &Range<usize>) -> Option<(usize, u32)> {
+        let closest = self.closest_line_in_a(line_b);
+        let search_start = range_a.start.max(closest.saturating_sub(self.max_search_distance));
+        let search_end = range_a.end.min(closest + self.max_search_distance + 1);
+
+        let (mut best, mut best_similarity, mut second_best_similarity) = (None, 0, 0);
+        for line_a in search_start..search_end {
+            let similarity = self.similarity(line_a, line_b);
+            if similarity > best_similarity {
+                second_best_similarity = best_similarity;
+                best_similarity = similarity;
+                best = Some(line_a);
+            } else if similarity > second_best_similarity {
+                second_best_similarity = similarity;
+            }
+        }
+        // A line that matches two lines well is still preferred over a line that matches a single line poorly.
+        best.map(|line_a| (line_a, best_similarity * 2 - second_best_similarity))
+    }
+
+    /// Match lines in `range_b` with lines in `range_a`, starting with the most certain match, and write the
+    /// results into `matches`, which is indexed by lines in `b`.
+    ///
+    /// Lines before and after each match are matched independently, so matches never cross each other.
+    /// They may still share the matched line in `a`, but only with the parts of it that weren't matched yet.
+    fn find_matches(&mut self, range_a: Range<usize>, range_b: Range<usize>, matches: &mut [Option<u32>]) {
+        let mut ranges = vec![(range_a, range_b)];
+        while let Some((range_a, range_b)) = ranges.pop() {
+            if range_a.is_empty() || range_b.is_empty() {
+                continue;
+            }
+
+            let mut most_certain = None;
+            for line_b in range_b.clone() {
+                if let Some((line_a, certainty)) = self.best_match(line_b, &range_a) {
+                    if most_certain.is_none_or(|(_, _, most_certainty)| certainty > most_certainty) {
+                        most_certain = Some((line_a, line_b, certainty));
+                    }
+                }
+            }
+            let Some((line_a, line_b, _)) = most_certain else {
+                continue;
+            };
+
+            matches[line_b] = Some(line_a as u32);
+            self.a[line_a].subtract(&self.b[line_b]);
+            self.versions_of_a[line_a] += 1;
+            // Like `git`, finish the lines before the match first as they may take parts of the matched line.
+            ranges.push((line_a..range_a.end, line_b + 1..range_b.end));
+            ranges.push((range_a.start..line_a + 1, range_b.start..line_b));
+        }
+    }
+}
@@ -7,6 +7,8 @@ use gix_hash::ObjectId;
 use crate::types::{BlameEntry, Change, Either, LineRange, Offset, UnblamedHunk};
 
 pub(super) mod function;
+mod ignore;
+mod moves;
 
 /// Compare a section from a potential *Source File* (`hunk`) with a change from a diff and see if
 /// there is an intersection with `change`. Based on that intersection, we may generate a
@@ -394,12 +396,14 @@ impl UnblamedHunk {
                             ..(self.range_in_blamed_file.start + split_at_from_start),
                         suspects: new_suspects_before.collect(),
                         source_file_name: self.source_file_name.clone(),
+                        ignored: self.ignored,
                     };
                     let new_hunk_after = Self {
                         range_in_blamed_file: (self.range_in_blamed_file.start + split_at_from_start)
                             ..(self.range_in_blamed_file.end),
                         suspects: new_suspects_after.collect(),
                         source_file_name: self.source_file_name,
+                        ignored: self.ignored,
                     };
 
                     Either::Right((new_hunk_before, new_hunk_after))
@@ -448,6 +452,8 @@ impl BlameEntry {
             len: force_non_zero(range_in_source_file.len() as u32),
             commit_id,
             source_file_name: unblamed_hunk.source_file_name.clone(),
+            ignored: unblamed_hunk.ignored,
+            unblamable: false,
         })
     }
 }
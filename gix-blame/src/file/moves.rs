@@ -0,0 +1,172 @@
+//! Support for finding lines that were moved within a file or copied from other files, like `git blame -M` and `-C`.
+//!
+//! This follows `git`: each hunk that is still blamed on the suspect after diffing it with its parents is diffed
+//! against the whole of each candidate blob in a parent. The block of unchanged lines with the highest score, the
+//! amount of alphanumeric characters in it, is passed to the parent if the score is above a threshold. What's left
+//! of the hunk is searched again until nothing else can be found.
+use gix_diff::blob::TokenSource;
+use gix_hash::ObjectId;
+use gix_object::bstr::BString;
+
+use super::function::{changes_between, tokens_for_diffing};
+use crate::types::{Change, UnblamedHunk};
+
+/// A blob in a parent that may contain lines of the *Blamed File*.
+pub(super) struct Source {
+    /// The content of the blob.
+    pub(super) data: Vec<u8>,
+    /// The name to associate passed lines with, if it differs from the name of the *Blamed File*.
+    pub(super) source_file_name: Option<BString>,
+}
+
+/// The best match of the lines of a hunk in a [`Source`].
+struct Match {
+    /// The index of the source the lines were found in.
+    source: usize,
+    /// The range of the matched lines, relative to the start of the hunk.
+    lines_in_hunk: std::ops::Range<u32>,
+    /// The first line of the match in the source.
+    start_in_source: u32,
+    score: u32,
+}
+
+/// Pass the lines of all hunks in `hunks_to_blame` that are only blamed on `suspect` to `parent` if they can be found
+/// in one of `sources`, and if the matched lines contain more than `threshold` alphanumeric characters.
+///
+/// `suspect_blob` is the content of the *Blamed File* in `suspect`.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn pass_moved_lines(
+    mut hunks_to_blame: Vec<UnblamedHunk>,
+    suspect: ObjectId,
+    parent: ObjectId,
+    suspect_blob: &[u8],
+    sources: &[Source],
+    threshold: u32,
+    diff_algorithm: gix_diff::blob::Algorithm,
+    whitespace: gix_diff::blob::Whitespace,
+) -> Vec<UnblamedHunk> {
+    if sources.is_empty() {
+        return hunks_to_blame;
+    }
+    let suspect_lines: Vec<_> = tokens_for_diffing(suspect_blob).tokenize().collect();
+
+    loop {
+        let mut made_progress = false;
+        let mut new_hunks_to_blame = Vec::with_capacity(hunks_to_blame.len());
+        for hunk in hunks_to_blame {
+            let range_in_suspect = match hunk.suspects.as_slice() {
+                [(id, range)] if *id == suspect => range.clone(),
+                _ => {
+                    new_hunks_to_blame.push(hunk);
+                    continue;
+                }
+            };
+            let Some(lines) = suspect_lines.get(range_in_suspect.start as usize..range_in_suspect.end as usize) else {
+                new_hunks_to_blame.push(hunk);
+                continue;
+            };
+            // No part of the hunk can score higher than all of it.
+            if score(lines) <= threshold {
+                new_hunks_to_blame.push(hunk);
+                continue;
+            }
+
+            let best_match = best_match(lines, sources, diff_algorithm, whitespace).filter(|m| m.score > threshold);
+            let Some(Match {
+                source,
+                lines_in_hunk,
+                start_in_source,
+                score: _,
+            }) = best_match
+            else {
+                new_hunks_to_blame.push(hunk);
+                continue;
+            };
+
+            made_progress = true;
+            let piece = |lines: std::ops::Range<u32>| {
+                let range_in_blamed_file =
+                    hunk.range_in_blamed_file.start + lines.start..hunk.range_in_blamed_file.start + lines.end;
+                (
+                    range_in_blamed_file,
+                    range_in_suspect.start + lines.start..range_in_suspect.start + lines.end,
+                )
+            };
+            if lines_in_hunk.start > 0 {
+                let (range_in_blamed_file, range_in_suspect) = piece(0..lines_in_hunk.start);
+                new_hunks_to_blame.push(UnblamedHunk {
+                    range_in_blamed_file,
+                    suspects: [(suspect, range_in_suspect)].into(),
+                    source_file_name: hunk.source_file_name.clone(),
+                    ignored: hunk.ignored,
+                });
+            }
+            let (range_in_blamed_file, _) = piece(lines_in_hunk.clone());
+            new_hunks_to_blame.push(UnblamedHunk {
+                range_in_blamed_file,
+                suspects: [(parent, start_in_source..start_in_source + lines_in_hunk.len() as u32)].into(),
+                source_file_name: sources[source].source_file_name.clone(),
+                ignored: hunk.ignored,
+            });
+            let num_lines_in_hunk = range_in_suspect.len() as u32;
+            if lines_in_hunk.end < num_lines_in_hunk {
+                let (range_in_blamed_file, range_in_suspect) = piece(lines_in_hunk.end..num_lines_in_hunk);
+                new_hunks_to_blame.push(UnblamedHunk {
+                    range_in_blamed_file,
+                    suspects: [(suspect, range_in_suspect)].into(),
+                    source_file_name: hunk.source_file_name.clone(),
+                    ignored: hunk.ignored,
+                });
+            }
+        }
+        hunks_to_blame = new_hunks_to_blame;
+        if !made_progress {
+            break;
+        }
+    }
+    hunks_to_blame
+}
+
+/// Find the block of unchanged lines with the highest score when diffing each of `sources` with `lines`.
+/// Like `git`, later matches win if their score is the same.
+fn best_match(
+    lines: &[&[u8]],
+    sources: &[Source],
+    diff_algorithm: gix_diff::blob::Algorithm,
+    whitespace: gix_diff::blob::Whitespace,
+) -> Option<Match> {
+    let hunk_data = lines.concat();
+    let mut best: Option<Match> = None;
+    for (source_index, source) in sources.iter().enumerate() {
+        let mut line_in_source = 0;
+        for change in changes_between(&source.data, &hunk_data, diff_algorithm, whitespace) {
+            match change {
+                Change::Unchanged(range) => {
+                    let score = score(&lines[range.start as usize..range.end as usize]);
+                    if best.as_ref().is_none_or(|best| score >= best.score) {
+                        best = Some(Match {
+                            source: source_index,
+                            start_in_source: line_in_source,
+                            lines_in_hunk: range.clone(),
+                            score,
+                        });
+                    }
+                    line_in_source += range.len() as u32;
+                }
+                Change::AddedOrReplaced(_, number_of_lines_deleted) | Change::Deleted(_, number_of_lines_deleted) => {
+                    line_in_source += number_of_lines_deleted;
+                }
+            }
+        }
+    }
+    best
+}
+
+/// Like `git`, the score of lines is the amount of alphanumeric characters in them.
+fn score(lines: &[&[u8]]) -> u32 {
+    lines
+        .iter()
+        .flat_map(|line| line.iter())
+        .filter(|byte| byte.is_ascii_alphanumeric())
+        .count() as u32
+}
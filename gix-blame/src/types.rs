@@ -155,6 +155,11 @@ impl BlameRanges {
 pub struct Options {
     /// The algorithm to use for diffing.
     pub diff_algorithm: gix_diff::blob::Algorithm,
+    /// Which differences in whitespace to ignore when comparing lines, so that lines that only changed in whitespace
+    /// are attributed to the commit that changed them otherwise, similar to `git blame -w`.
+    ///
+    /// [`ignore_blank_lines`](gix_diff::blob::Whitespace::ignore_blank_lines) has no effect here.
+    pub whitespace: gix_diff::blob::Whitespace,
     /// The ranges to blame in the file.
     pub ranges: BlameRanges,
     /// Don't consider commits before the given date.
@@ -164,6 +169,71 @@ pub struct Options {
     /// Collect debug information whenever there's a diff or rename that affects the outcome of a
     /// blame.
     pub debug_track_path: bool,
+    /// Commits that should not be blamed, like those that only reformat code.
+    ///
+    /// Lines they changed are attributed to the most similar line in their parent instead, as determined by
+    /// `git`'s fuzzy line-matching heuristic. Lines without a match remain attributed to the ignored commit.
+    pub ignore_revs: gix_hashtable::HashSet<ObjectId>,
+    /// If `true`, set [`BlameEntry::ignored`] for lines that were passed on to a parent because they were
+    /// changed by one of the [ignored commits](Self::ignore_revs).
+    pub mark_ignored_lines: bool,
+    /// If `true`, set [`BlameEntry::unblamable`] for lines that were changed by one of the
+    /// [ignored commits](Self::ignore_revs), but couldn't be attributed to any of its parents.
+    pub mark_unblamable_lines: bool,
+    /// The content of the *Blamed File* as it is in the worktree or in the index, if it should be blamed instead of
+    /// the version in `suspect`.
+    ///
+    /// It acts like a commit on top of `suspect` which is identified by the null object id, similar to the lines
+    /// `git blame` shows as *Not Committed Yet*. The file doesn't have to exist in `suspect` for this to work.
+    ///
+    /// The content is expected to be in the form `git` would store it, i.e. after conversions like `eol` and filters
+    /// were applied.
+    pub uncommitted_blob: Option<Vec<u8>>,
+    /// If set, blame in reverse like `git blame --reverse`, with `suspect` being the oldest commit and this one being
+    /// the newest.
+    ///
+    /// Instead of the commit that introduced a line, each line is attributed to the last commit on the way from
+    /// `suspect` to this commit in which it still existed.
+    pub reverse_until: Option<ObjectId>,
+    /// If set, find lines that were moved within the *Blamed File* or copied from other files, and attribute them
+    /// to the commit they originally came from, similar to `git blame -M` and `-C`.
+    pub moves_and_copies: Option<MovesAndCopies>,
+}
+
+/// Options for finding lines that were moved or copied, see [`Options::moves_and_copies`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MovesAndCopies {
+    /// Where to look for lines that were copied from other files, or `None` to only look for lines that were moved
+    /// within the *Blamed File*, like `git blame -M`.
+    pub copies: Option<Copies>,
+    /// Moved lines are only found if they contain more than this many alphanumeric characters.
+    pub move_score: u32,
+    /// Copied lines are only found if they contain more than this many alphanumeric characters.
+    pub copy_score: u32,
+}
+
+impl Default for MovesAndCopies {
+    /// Only find moved lines, with the same thresholds as `git`.
+    fn default() -> Self {
+        MovesAndCopies {
+            copies: None,
+            move_score: 20,
+            copy_score: 40,
+        }
+    }
+}
+
+/// The files to search for lines that were copied from other files, see [`MovesAndCopies::copies`].
+///
+/// Lines that were moved within the *Blamed File* are always looked for as well.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Copies {
+    /// Look in files that were modified or deleted in the same commit, like `git blame -C`.
+    ModifiedFiles,
+    /// Also look in all other files of the parent if the *Blamed File* was created in a commit, like `git blame -C -C`.
+    AllFilesInCreatingCommit,
+    /// Always look in all files of the parent, like `git blame -C -C -C`.
+    AllFiles,
 }
 
 /// Represents a change during history traversal for blame. It is supposed to capture enough
@@ -325,6 +395,15 @@ pub struct BlameEntry {
     /// The *Source File*'s name, in case it differs from *Blamed File*'s name.
     /// This happens when the file was renamed.
     pub source_file_name: Option<BString>,
+    /// If `true`, the lines were changed by an ignored commit and attributed to a similar line in its parent instead.
+    ///
+    /// Only set if [`Options::mark_ignored_lines`] is `true`.
+    pub ignored: bool,
+    /// If `true`, the lines were changed by an ignored commit but could not be attributed to one of its parents,
+    /// so [`commit_id`](Self::commit_id) is the ignored commit.
+    ///
+    /// Only set if [`Options::mark_unblamable_lines`] is `true`.
+    pub unblamable: bool,
 }
 
 impl BlameEntry {
@@ -351,6 +430,8 @@ impl BlameEntry {
             len: NonZeroU32::new(range_in_blamed_file.len() as u32).expect("BUG: hunks are never empty"),
             commit_id,
             source_file_name,
+            ignored: false,
+            unblamable: false,
         }
     }
 }
@@ -389,6 +470,9 @@ pub struct UnblamedHunk {
     pub suspects: SmallVec<[(ObjectId, Range<u32>); 1]>,
     /// The *Source File*'s name, in case it differs from *Blamed File*'s name.
     pub source_file_name: Option<BString>,
+    /// If `true`, the lines of this hunk were passed on from an ignored commit, so they may differ from the
+    /// lines in the *Blamed File*.
+    pub ignored: bool,
 }
 
 impl UnblamedHunk {
@@ -400,6 +484,7 @@ impl UnblamedHunk {
             range_in_blamed_file: range_start..range_end,
             suspects: [(suspect, range_start..range_end)].into(),
             source_file_name: None,
+            ignored: false,
         }
     }
 
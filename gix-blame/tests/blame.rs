@@ -257,10 +257,17 @@ macro_rules! mktest {
                 source_file_name.as_ref(),
                 gix_blame::Options {
                     diff_algorithm: gix_diff::blob::Algorithm::Histogram,
+                    whitespace: Default::default(),
                     ranges: BlameRanges::default(),
                     since: None,
                     rewrites: Some(gix_diff::Rewrites::default()),
                     debug_track_path: false,
+                    ignore_revs: Default::default(),
+                    mark_ignored_lines: false,
+                    mark_unblamable_lines: false,
+                    uncommitted_blob: None,
+                    reverse_until: None,
+                    moves_and_copies: None,
                 },
             )?
             .entries;
@@ -329,6 +336,7 @@ fn diff_algorithm_parity() {
     for (case, diff_algorithm) in [
         ("empty-lines-myers", gix_diff::blob::Algorithm::Myers),
         ("empty-lines-histogram", gix_diff::blob::Algorithm::Histogram),
+        ("empty-lines-patience", gix_diff::blob::Algorithm::Patience),
     ] {
         let Fixture {
             odb,
@@ -346,10 +354,17 @@ fn diff_algorithm_parity() {
             source_file_name.as_ref(),
             gix_blame::Options {
                 diff_algorithm,
+                whitespace: Default::default(),
                 ranges: BlameRanges::default(),
                 since: None,
                 rewrites: Some(gix_diff::Rewrites::default()),
                 debug_track_path: false,
+                ignore_revs: Default::default(),
+                mark_ignored_lines: false,
+                mark_unblamable_lines: false,
+                uncommitted_blob: None,
+                reverse_until: None,
+                moves_and_copies: None,
             },
         )
         .unwrap()
@@ -413,12 +428,19 @@ fn since() -> gix_testtools::Result {
         source_file_name.as_ref(),
         gix_blame::Options {
             diff_algorithm: gix_diff::blob::Algorithm::Histogram,
+            whitespace: Default::default(),
             ranges: BlameRanges::default(),
             since: Some(
                 gix_date::parse("2025-01-31", None).expect("TODO: should be able to to retrieve inner from Exn"),
             ),
             rewrites: Some(gix_diff::Rewrites::default()),
             debug_track_path: false,
+            ignore_revs: Default::default(),
+            mark_ignored_lines: false,
+            mark_unblamable_lines: false,
+            uncommitted_blob: None,
+            reverse_until: None,
+            moves_and_copies: None,
         },
     )?
     .entries;
@@ -455,10 +477,17 @@ mod blame_ranges {
             source_file_name.as_ref(),
             gix_blame::Options {
                 diff_algorithm: gix_diff::blob::Algorithm::Histogram,
+                whitespace: Default::default(),
                 ranges: BlameRanges::from_one_based_inclusive_range(1..=2).unwrap(),
                 since: None,
                 rewrites: Some(gix_diff::Rewrites::default()),
                 debug_track_path: false,
+                ignore_revs: Default::default(),
+                mark_ignored_lines: false,
+                mark_unblamable_lines: false,
+                uncommitted_blob: None,
+                reverse_until: None,
+                moves_and_copies: None,
             },
         )?
         .entries;
@@ -498,10 +527,17 @@ mod blame_ranges {
             source_file_name.as_ref(),
             gix_blame::Options {
                 diff_algorithm: gix_diff::blob::Algorithm::Histogram,
+                whitespace: Default::default(),
                 ranges,
                 since: None,
                 rewrites: None,
                 debug_track_path: false,
+                ignore_revs: Default::default(),
+                mark_ignored_lines: false,
+                mark_unblamable_lines: false,
+                uncommitted_blob: None,
+                reverse_until: None,
+                moves_and_copies: None,
             },
         )?
         .entries;
@@ -539,10 +575,17 @@ mod blame_ranges {
             source_file_name.as_ref(),
             gix_blame::Options {
                 diff_algorithm: gix_diff::blob::Algorithm::Histogram,
+                whitespace: Default::default(),
                 ranges,
                 since: None,
                 rewrites: None,
                 debug_track_path: false,
+                ignore_revs: Default::default(),
+                mark_ignored_lines: false,
+                mark_unblamable_lines: false,
+                uncommitted_blob: None,
+                reverse_until: None,
+                moves_and_copies: None,
             },
         )?
         .entries;
@@ -585,10 +628,17 @@ mod rename_tracking {
             source_file_name.into(),
             gix_blame::Options {
                 diff_algorithm: gix_diff::blob::Algorithm::Histogram,
+                whitespace: Default::default(),
                 ranges: BlameRanges::default(),
                 since: None,
                 rewrites: Some(gix_diff::Rewrites::default()),
                 debug_track_path: false,
+                ignore_revs: Default::default(),
+                mark_ignored_lines: false,
+                mark_unblamable_lines: false,
+                uncommitted_blob: None,
+                reverse_until: None,
+                moves_and_copies: None,
             },
         )?
         .entries;
@@ -615,10 +665,17 @@ mod rename_tracking {
                 source_file_name.into(),
                 gix_blame::Options {
                     diff_algorithm: gix_diff::blob::Algorithm::Histogram,
+                    whitespace: Default::default(),
                     ranges: BlameRanges::default(),
                     since: None,
                     rewrites: Some(gix_diff::Rewrites::default()),
                     debug_track_path: false,
+                    ignore_revs: Default::default(),
+                    mark_ignored_lines: false,
+                    mark_unblamable_lines: false,
+                    uncommitted_blob: None,
+                    reverse_until: None,
+                    moves_and_copies: None,
                 },
             )?
             .entries;
@@ -634,6 +691,493 @@ mod rename_tracking {
     }
 }
 
+mod ignore_revs {
+    use gix_blame::BlameRanges;
+    use gix_hash::ObjectId;
+    use gix_object::bstr::ByteSlice;
+
+    use crate::{Baseline, Fixture};
+
+    fn options(ignore_revs: gix_hashtable::HashSet<ObjectId>, mark_lines: bool) -> gix_blame::Options {
+        gix_blame::Options {
+            diff_algorithm: gix_diff::blob::Algorithm::Histogram,
+            whitespace: Default::default(),
+            ranges: BlameRanges::default(),
+            since: None,
+            rewrites: Some(gix_diff::Rewrites::default()),
+            debug_track_path: false,
+            ignore_revs,
+            mark_ignored_lines: mark_lines,
+            mark_unblamable_lines: mark_lines,
+            uncommitted_blob: None,
+            reverse_until: None,
+            moves_and_copies: None,
+        }
+    }
+
+    fn fixture() -> gix_testtools::Result<(Fixture, std::path::PathBuf, gix_hashtable::HashSet<ObjectId>)> {
+        let worktree_path = gix_testtools::scripted_fixture_read_only("make_blame_ignore_revs_repo.sh")?;
+        let git_dir = worktree_path.join(".git");
+        let ignore_revs = std::fs::read(git_dir.join("ignore-revs"))?
+            .lines()
+            .map(ObjectId::from_hex)
+            .collect::<Result<_, _>>()?;
+        Ok((Fixture::for_worktree_path(worktree_path)?, git_dir, ignore_revs))
+    }
+
+    #[test]
+    fn lines_of_ignored_commits_are_attributed_to_similar_lines_in_parents() -> gix_testtools::Result {
+        let (mut fixture, git_dir, ignore_revs) = fixture()?;
+        for (case, number_of_lines) in [("ignore-revs", 6), ("ignore-revs-twice", 7)] {
+            let source_file_name = format!("{case}.txt");
+            let lines_blamed = fixture
+                .blame_file(source_file_name.as_str().into(), options(ignore_revs.clone(), false))?
+                .entries;
+
+            let baseline = Baseline::collect(git_dir.join(format!("{case}.baseline")), source_file_name.into())?;
+            assert_eq!(lines_blamed.len(), number_of_lines, "{case}");
+            pretty_assertions::assert_eq!(lines_blamed, baseline, "{case}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn ignored_and_unblamable_lines_can_be_marked() -> gix_testtools::Result {
+        let (mut fixture, _git_dir, ignore_revs) = fixture()?;
+        let entries = fixture
+            .blame_file("ignore-revs.txt".into(), options(ignore_revs.clone(), true))?
+            .entries;
+
+        let marks: Vec<_> = entries
+            .iter()
+            .map(|entry| (entry.start_in_blamed_file, entry.ignored, entry.unblamable))
+            .collect();
+        assert_eq!(
+            marks,
+            [
+                (0, false, false),
+                (1, true, false),
+                (2, false, false),
+                (3, false, true),
+                (4, true, false),
+                (5, false, false)
+            ],
+            "the reformatted lines are ignored, the new comment can't be attributed to anything before the reformat"
+        );
+        assert!(
+            ignore_revs.contains(&entries[3].commit_id),
+            "unblamable lines stay with the ignored commit"
+        );
+
+        let entries = fixture
+            .blame_file("ignore-revs.txt".into(), options(Default::default(), true))?
+            .entries;
+        assert!(
+            entries.iter().all(|entry| !entry.ignored && !entry.unblamable),
+            "nothing is marked if no commit is ignored"
+        );
+        Ok(())
+    }
+}
+
+mod uncommitted {
+    use gix_blame::BlameRanges;
+
+    use crate::{Baseline, Fixture, fixture_hash_kind};
+
+    fn options(uncommitted_blob: Vec<u8>) -> gix_blame::Options {
+        gix_blame::Options {
+            diff_algorithm: gix_diff::blob::Algorithm::Histogram,
+            whitespace: Default::default(),
+            ranges: BlameRanges::default(),
+            since: None,
+            rewrites: Some(gix_diff::Rewrites::default()),
+            debug_track_path: false,
+            ignore_revs: Default::default(),
+            mark_ignored_lines: false,
+            mark_unblamable_lines: false,
+            uncommitted_blob: Some(uncommitted_blob),
+            reverse_until: None,
+            moves_and_copies: None,
+        }
+    }
+
+    #[test]
+    fn changes_in_the_worktree_are_attributed_to_the_null_commit() -> gix_testtools::Result {
+        let worktree_path = gix_testtools::scripted_fixture_read_only("make_blame_uncommitted_repo.sh")?;
+        let mut fixture = Fixture::for_worktree_path(worktree_path.clone())?;
+
+        let blob = std::fs::read(worktree_path.join("uncommitted.txt"))?;
+        let outcome = fixture.blame_file("uncommitted.txt".into(), options(blob))?;
+
+        let baseline = Baseline::collect(
+            worktree_path.join(".git").join("uncommitted.baseline"),
+            "uncommitted.txt".into(),
+        )?;
+        assert_eq!(outcome.entries.len(), 5);
+        pretty_assertions::assert_eq!(outcome.entries, baseline);
+        Ok(())
+    }
+
+    #[test]
+    fn files_that_are_not_committed_yet_are_attributed_to_the_null_commit() -> gix_testtools::Result {
+        let worktree_path = gix_testtools::scripted_fixture_read_only("make_blame_uncommitted_repo.sh")?;
+        let mut fixture = Fixture::for_worktree_path(worktree_path.clone())?;
+
+        let blob = std::fs::read(worktree_path.join("untracked.txt"))?;
+        let outcome = fixture.blame_file("untracked.txt".into(), options(blob))?;
+
+        assert_eq!(outcome.entries.len(), 1);
+        let entry = &outcome.entries[0];
+        assert_eq!(entry.commit_id, fixture_hash_kind().null());
+        assert_eq!(entry.start_in_blamed_file, 0);
+        assert_eq!(entry.len.get(), 2);
+        Ok(())
+    }
+}
+
+mod incremental {
+    use std::sync::atomic::AtomicBool;
+
+    use gix_blame::BlameRanges;
+
+    use crate::Fixture;
+
+    fn options() -> gix_blame::Options {
+        gix_blame::Options {
+            diff_algorithm: gix_diff::blob::Algorithm::Histogram,
+            whitespace: Default::default(),
+            ranges: BlameRanges::default(),
+            since: None,
+            rewrites: Some(gix_diff::Rewrites::default()),
+            debug_track_path: false,
+            ignore_revs: Default::default(),
+            mark_ignored_lines: false,
+            mark_unblamable_lines: false,
+            uncommitted_blob: None,
+            reverse_until: None,
+            moves_and_copies: None,
+        }
+    }
+
+    #[test]
+    fn entries_are_passed_on_in_traversal_order() -> gix_testtools::Result {
+        let Fixture {
+            odb,
+            mut resource_cache,
+            suspect,
+        } = Fixture::new()?;
+
+        let mut entries = Vec::new();
+        let outcome = gix_blame::file_incremental(
+            &odb,
+            suspect,
+            None,
+            &mut resource_cache,
+            "simple.txt".into(),
+            options(),
+            |entry| entries.push(entry),
+            &mut gix_features::progress::Discard,
+            &AtomicBool::default(),
+        )?;
+
+        assert_eq!(
+            entries
+                .iter()
+                .map(|entry| entry.start_in_blamed_file)
+                .collect::<Vec<_>>(),
+            [3, 2, 1, 0],
+            "each line was added by its own commit, and the most recent ones are found first"
+        );
+        entries.sort_by_key(|entry| entry.start_in_blamed_file);
+        assert_eq!(entries, outcome.entries, "nothing is merged in this case");
+        Ok(())
+    }
+
+    #[test]
+    fn interrupt_stops_the_traversal() -> gix_testtools::Result {
+        let Fixture {
+            odb,
+            mut resource_cache,
+            suspect,
+        } = Fixture::new()?;
+
+        let mut entries = Vec::new();
+        let err = gix_blame::file_incremental(
+            &odb,
+            suspect,
+            None,
+            &mut resource_cache,
+            "simple.txt".into(),
+            options(),
+            |entry| entries.push(entry),
+            &mut gix_features::progress::Discard,
+            &AtomicBool::new(true),
+        )
+        .unwrap_err();
+
+        assert!(matches!(err, gix_blame::Error::Interrupted));
+        assert!(entries.is_empty());
+        Ok(())
+    }
+}
+
+mod reverse {
+    use gix_blame::BlameRanges;
+    use gix_hash::ObjectId;
+    use gix_object::bstr::ByteSlice;
+
+    use crate::{Baseline, Fixture};
+
+    #[test]
+    fn lines_are_attributed_to_the_last_commit_they_existed_in() -> gix_testtools::Result {
+        let worktree_path = gix_testtools::scripted_fixture_read_only("make_blame_reverse_repo.sh")?;
+        let git_dir = worktree_path.join(".git");
+        let start = ObjectId::from_hex(std::fs::read(git_dir.join("reverse-start"))?.trim())?;
+        let Fixture {
+            odb,
+            mut resource_cache,
+            suspect: end,
+        } = Fixture::for_worktree_path(worktree_path)?;
+
+        for (case, number_of_lines) in [("reverse", 5), ("deleted-later", 1)] {
+            let source_file_name = format!("{case}.txt");
+            let lines_blamed = gix_blame::file(
+                &odb,
+                start,
+                None,
+                &mut resource_cache,
+                source_file_name.as_str().into(),
+                gix_blame::Options {
+                    diff_algorithm: gix_diff::blob::Algorithm::Histogram,
+                    whitespace: Default::default(),
+                    ranges: BlameRanges::default(),
+                    since: None,
+                    rewrites: Some(gix_diff::Rewrites::default()),
+                    debug_track_path: false,
+                    ignore_revs: Default::default(),
+                    mark_ignored_lines: false,
+                    mark_unblamable_lines: false,
+                    uncommitted_blob: None,
+                    reverse_until: Some(end),
+                    moves_and_copies: None,
+                },
+            )?
+            .entries;
+
+            let baseline = Baseline::collect(git_dir.join(format!("{case}.baseline")), source_file_name.into())?;
+            assert_eq!(lines_blamed.len(), number_of_lines, "{case}");
+            pretty_assertions::assert_eq!(lines_blamed, baseline, "{case}");
+        }
+        Ok(())
+    }
+}
+
+mod moves_and_copies {
+    use gix_blame::{BlameRanges, Copies, MovesAndCopies};
+
+    use crate::{Baseline, Fixture};
+
+    #[test]
+    fn lines_are_attributed_to_the_commit_they_were_moved_or_copied_from() -> gix_testtools::Result {
+        let worktree_path = gix_testtools::scripted_fixture_read_only("make_blame_moves_repo.sh")?;
+        let git_dir = worktree_path.join(".git");
+        let Fixture {
+            odb,
+            mut resource_cache,
+            suspect,
+        } = Fixture::for_worktree_path(worktree_path)?;
+
+        for (case, copies, weaker_copies) in [
+            ("moved", None, None),
+            ("copied", Some(Copies::ModifiedFiles), None),
+            (
+                "copied-from-unmodified",
+                Some(Copies::AllFilesInCreatingCommit),
+                Some(Copies::ModifiedFiles),
+            ),
+            (
+                "existing",
+                Some(Copies::AllFiles),
+                Some(Copies::AllFilesInCreatingCommit),
+            ),
+        ] {
+            let source_file_name = format!("{case}.txt");
+            let mut blame = |moves_and_copies| {
+                gix_blame::file(
+                    &odb,
+                    suspect,
+                    None,
+                    &mut resource_cache,
+                    source_file_name.as_str().into(),
+                    gix_blame::Options {
+                        diff_algorithm: gix_diff::blob::Algorithm::Histogram,
+                        whitespace: Default::default(),
+                        ranges: BlameRanges::default(),
+                        since: None,
+                        rewrites: Some(gix_diff::Rewrites::default()),
+                        debug_track_path: false,
+                        ignore_revs: Default::default(),
+                        mark_ignored_lines: false,
+                        mark_unblamable_lines: false,
+                        uncommitted_blob: None,
+                        reverse_until: None,
+                        moves_and_copies,
+                    },
+                )
+                .map(|outcome| outcome.entries)
+            };
+            let lines_blamed = blame(Some(MovesAndCopies {
+                copies,
+                ..Default::default()
+            }))?;
+
+            let baseline = Baseline::collect(
+                git_dir.join(format!("{case}.baseline")),
+                source_file_name.clone().into(),
+            )?;
+            pretty_assertions::assert_eq!(lines_blamed, baseline, "{case}");
+
+            let weaker = if copies.is_some() {
+                Some(MovesAndCopies {
+                    copies: weaker_copies,
+                    ..Default::default()
+                })
+            } else {
+                None
+            };
+            assert_ne!(
+                blame(weaker)?,
+                baseline,
+                "{case}: a weaker search doesn't find the origin of all lines"
+            );
+
+            let lines_blamed = blame(Some(MovesAndCopies {
+                copies,
+                move_score: 1000,
+                copy_score: 1000,
+            }))?;
+            assert_eq!(
+                lines_blamed,
+                blame(None)?,
+                "{case}: nothing is found if the lines don't score high enough"
+            );
+        }
+        Ok(())
+    }
+}
+
+mod whitespace {
+    use gix_blame::BlameRanges;
+
+    use crate::{Baseline, Fixture};
+
+    #[test]
+    fn lines_that_only_changed_in_whitespace_keep_their_origin() -> gix_testtools::Result {
+        let worktree_path = gix_testtools::scripted_fixture_read_only("make_blame_whitespace_repo.sh")?;
+        let git_dir = worktree_path.join(".git");
+        let Fixture {
+            odb,
+            mut resource_cache,
+            suspect,
+        } = Fixture::for_worktree_path(worktree_path)?;
+
+        for (case, whitespace) in [
+            ("file", gix_diff::blob::Whitespace::default()),
+            (
+                "file-ignore-whitespace",
+                gix_diff::blob::Whitespace {
+                    ignore_all_space: true,
+                    ..Default::default()
+                },
+            ),
+        ] {
+            let lines_blamed = gix_blame::file(
+                &odb,
+                suspect,
+                None,
+                &mut resource_cache,
+                "file.txt".into(),
+                gix_blame::Options {
+                    diff_algorithm: gix_diff::blob::Algorithm::Histogram,
+                    whitespace,
+                    ranges: BlameRanges::default(),
+                    since: None,
+                    rewrites: Some(gix_diff::Rewrites::default()),
+                    debug_track_path: false,
+                    ignore_revs: Default::default(),
+                    mark_ignored_lines: false,
+                    mark_unblamable_lines: false,
+                    uncommitted_blob: None,
+                    reverse_until: None,
+                    moves_and_copies: None,
+                },
+            )?
+            .entries;
+
+            let baseline = Baseline::collect(git_dir.join(format!("{case}.baseline")), "file.txt".into())?;
+            pretty_assertions::assert_eq!(lines_blamed, baseline, "{case}");
+        }
+        Ok(())
+    }
+}
+
+mod files {
+    use gix_blame::BlameRanges;
+
+    use crate::{Baseline, Fixture, fixture_path};
+
+    #[test]
+    fn multiple_files_are_blamed_in_one_traversal() -> gix_testtools::Result {
+        let Fixture {
+            odb,
+            mut resource_cache,
+            suspect,
+        } = Fixture::new()?;
+
+        let cases = [
+            "simple",
+            "multiline-hunks",
+            "changed-lines",
+            "after-rename",
+            "after-rewrite",
+            "resolved-conflict",
+            "file-changed-in-two-branches",
+            "sub-directory/sub-directory",
+        ];
+        let file_paths: Vec<_> = cases.iter().map(|case| format!("{case}.txt")).collect();
+        let outcomes = gix_blame::files(
+            &odb,
+            suspect,
+            None,
+            &mut resource_cache,
+            file_paths.iter().map(|path| path.as_str().into()),
+            gix_blame::Options {
+                diff_algorithm: gix_diff::blob::Algorithm::Histogram,
+                whitespace: Default::default(),
+                ranges: BlameRanges::default(),
+                since: None,
+                rewrites: Some(gix_diff::Rewrites::default()),
+                debug_track_path: false,
+                ignore_revs: Default::default(),
+                mark_ignored_lines: false,
+                mark_unblamable_lines: false,
+                uncommitted_blob: None,
+                reverse_until: None,
+                moves_and_copies: None,
+            },
+        )?;
+
+        assert_eq!(outcomes.len(), cases.len());
+        let git_dir = fixture_path()?.join(".git");
+        for ((case, file_path), outcome) in cases.iter().zip(file_paths).zip(outcomes) {
+            let baseline = Baseline::collect(git_dir.join(format!("{case}.baseline")), file_path.into())?;
+            pretty_assertions::assert_eq!(outcome.entries, baseline, "{case}");
+        }
+        Ok(())
+    }
+}
+
 fn fixture_path() -> gix_testtools::Result<PathBuf> {
     gix_testtools::scripted_fixture_read_only("make_blame_repo.sh")
 }
@@ -0,0 +1,66 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git config --local diff.algorithm histogram
+
+cat > ignore-revs.txt <<'EOT'
+fn main() {
+let a = 1;
+let b = 2;
+println!("{a}");
+}
+EOT
+cat > ignore-revs-twice.txt <<'EOT'
+struct Point { x: u32, y: u32 }
+fn area(w: u32, h: u32) -> u32 { w * h }
+const ANSWER: u32 = 42;
+EOT
+git add ignore-revs.txt ignore-revs-twice.txt
+git commit -q -m c1
+
+sed -i 's/let b = 2;/let b = 3;/' ignore-revs.txt
+git add ignore-revs.txt
+git commit -q -m c2
+
+cat > ignore-revs.txt <<'EOT'
+fn main() {
+    let a = 1;
+    let b = 3;
+    // a new comment
+    println!("{a}");
+}
+EOT
+cat > ignore-revs-twice.txt <<'EOT'
+struct Point {
+    x: u32,
+    y: u32,
+}
+fn area(w: u32, h: u32) -> u32 { w * h }
+const ANSWER: u32 = 42;
+EOT
+git add ignore-revs.txt ignore-revs-twice.txt
+git commit -q -m "reformat"
+git rev-parse HEAD >> .git/ignore-revs
+
+cat > ignore-revs-twice.txt <<'EOT'
+struct Point {
+    x: u32,
+    y: u32,
+}
+fn area(w: u32, h: u32) -> u32 {
+    w * h
+}
+const ANSWER: u32 = 42;
+EOT
+git add ignore-revs-twice.txt
+git commit -q -m "reformat again"
+git rev-parse HEAD >> .git/ignore-revs
+
+sed -i 's/let b = 3;/let b = 4;/' ignore-revs.txt
+sed -i 's/42/43/' ignore-revs-twice.txt
+git add ignore-revs.txt ignore-revs-twice.txt
+git commit -q -m c4
+
+git blame --porcelain --ignore-revs-file .git/ignore-revs ignore-revs.txt > .git/ignore-revs.baseline
+git blame --porcelain --ignore-revs-file .git/ignore-revs ignore-revs-twice.txt > .git/ignore-revs-twice.baseline
@@ -0,0 +1,58 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git config --local diff.algorithm histogram
+
+cat > moved.txt <<'EOT'
+fn first_function_with_a_long_name() {
+    println!("the first function does something");
+}
+
+fn second_function_with_a_long_name() {
+    println!("the second function does something else");
+}
+EOT
+cat > source.txt <<'EOT'
+a line in the source file with plenty of alphanumeric characters
+another line in the source file with plenty of alphanumeric characters
+EOT
+cat > unmodified.txt <<'EOT'
+a line in the unmodified file with plenty of alphanumeric characters
+another line in the unmodified file with plenty of alphanumeric characters
+EOT
+cat > existing.txt <<'EOT'
+a line that was always in the existing file
+EOT
+git add moved.txt source.txt unmodified.txt existing.txt
+git commit -q -m c1
+
+cat > moved.txt <<'EOT'
+fn second_function_with_a_long_name() {
+    println!("the second function does something else");
+}
+
+fn first_function_with_a_long_name() {
+    println!("the first function does something");
+}
+EOT
+git commit -q -am c2
+
+cp source.txt copied.txt
+echo "a new line in the copy" >> copied.txt
+echo "a new line in the source file" >> source.txt
+git add copied.txt source.txt
+git commit -q -m c3
+
+cp unmodified.txt copied-from-unmodified.txt
+echo "a new line in the copy of the unmodified file" >> copied-from-unmodified.txt
+git add copied-from-unmodified.txt
+git commit -q -m c4
+
+cat unmodified.txt >> existing.txt
+git commit -q -am c5
+
+git blame --porcelain -M moved.txt > .git/moved.baseline
+git blame --porcelain -C copied.txt > .git/copied.baseline
+git blame --porcelain -C -C copied-from-unmodified.txt > .git/copied-from-unmodified.baseline
+git blame --porcelain -C -C -C existing.txt > .git/existing.baseline
@@ -0,0 +1,32 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git config --local diff.algorithm histogram
+
+cat > reverse.txt <<'EOT'
+line 1
+line 2
+line 3
+line 4
+line 5
+EOT
+cp reverse.txt deleted-later.txt
+git add reverse.txt deleted-later.txt
+git commit -q -m c1
+git rev-parse HEAD > .git/reverse-start
+
+sed -i 's/line 2/line 2 changed/' reverse.txt
+git add reverse.txt
+git commit -q -m c2
+
+sed -i '/line 4/d' reverse.txt
+git rm -q deleted-later.txt
+git commit -q -am c3
+
+sed -i '1i line 0' reverse.txt
+git add reverse.txt
+git commit -q -m c4
+
+git blame --porcelain --reverse "$(cat .git/reverse-start)..HEAD" reverse.txt > .git/reverse.baseline
+git blame --porcelain --reverse "$(cat .git/reverse-start)..HEAD" deleted-later.txt > .git/deleted-later.baseline
@@ -0,0 +1,31 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git config --local diff.algorithm histogram
+
+cat > uncommitted.txt <<'EOT'
+line 1
+line 2
+line 3
+line 4
+EOT
+git add uncommitted.txt
+git commit -q -m c1
+
+sed -i 's/line 3/line 3 changed/' uncommitted.txt
+git add uncommitted.txt
+git commit -q -m c2
+
+cat > uncommitted.txt <<'EOT'
+line 1
+a new line in the worktree
+line 2
+line 3 changed
+line 4 changed in the worktree
+EOT
+
+echo "untracked line 1" > untracked.txt
+echo "untracked line 2" >> untracked.txt
+
+git blame --porcelain uncommitted.txt > .git/uncommitted.baseline
@@ -0,0 +1,36 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git config --local diff.algorithm histogram
+
+cat > file.txt <<'EOT'
+fn main() {
+    let a = 1;
+    let b = 2;
+    println!("{}", a + b);
+}
+EOT
+git add file.txt
+git commit -q -m c1
+
+cat > file.txt <<'EOT'
+fn main() {
+	let a = 1;
+    let  b = 2;
+    println!("{}", a + b);   
+}
+EOT
+git commit -q -am c2
+
+cat > file.txt <<'EOT'
+fn main() {
+	let a = 1;
+    let  b = 3;
+    println!("{}", a + b);   
+}
+EOT
+git commit -q -am c3
+
+git blame --porcelain file.txt > .git/file.baseline
+git blame --porcelain -w file.txt > .git/file-ignore-whitespace.baseline
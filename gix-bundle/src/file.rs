@@ -0,0 +1,81 @@
+use std::{
+    io::{BufReader, Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+};
+
+use crate::{File, Header, Version};
+
+///
+pub mod init {
+    use std::path::PathBuf;
+
+    /// The error returned by [File::at()](crate::File::at()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not open bundle file at '{}'", path.display())]
+        Io { source: std::io::Error, path: PathBuf },
+        #[error("Could not decode the header of bundle file at '{}'", path.display())]
+        Header {
+            source: crate::header::decode::Error,
+            path: PathBuf,
+        },
+    }
+}
+
+/// Instantiation
+impl File {
+    /// Open the bundle at `path` and decode its header, without touching the pack that follows it.
+    pub fn at(path: impl Into<PathBuf>) -> Result<Self, init::Error> {
+        let path = path.into();
+        let io_err = |source| init::Error::Io {
+            source,
+            path: path.clone(),
+        };
+        let mut read = BufReader::new(std::fs::File::open(&path).map_err(io_err)?);
+        let header = Header::from_read(&mut read).map_err(|source| init::Error::Header {
+            source,
+            path: path.clone(),
+        })?;
+        let pack_offset = read.stream_position().map_err(io_err)?;
+        Ok(File {
+            header,
+            path,
+            pack_offset,
+        })
+    }
+}
+
+/// Access
+impl File {
+    /// The path from which the bundle was opened.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The offset in bytes at which the pack starts, right after the header.
+    pub fn pack_offset(&self) -> u64 {
+        self.pack_offset
+    }
+
+    /// Open the bundle file once more and position it at the beginning of the pack, so that reading it to the end
+    /// yields the complete pack data.
+    pub fn open_pack(&self) -> std::io::Result<std::fs::File> {
+        let mut file = std::fs::File::open(&self.path)?;
+        file.seek(SeekFrom::Start(self.pack_offset))?;
+        Ok(file)
+    }
+}
+
+/// Return `true` if the file at `path` starts with the signature of a bundle of any supported [version](Version).
+///
+/// This is useful to tell bundle files apart from repositories, and any IO error is treated as `false`.
+pub fn is_bundle(path: &Path) -> bool {
+    let mut signature = [0u8; 16];
+    if !path.is_file() {
+        return false;
+    }
+    std::fs::File::open(path)
+        .and_then(|mut file| file.read_exact(&mut signature))
+        .is_ok_and(|()| [Version::V2, Version::V3].iter().any(|v| v.signature() == signature))
+}
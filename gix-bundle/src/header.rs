@@ -0,0 +1,185 @@
+use std::io::{BufRead, Write};
+
+use bstr::{BString, ByteSlice};
+use gix_hash::ObjectId;
+
+use crate::{Capability, Header, Prerequisite, Ref, Version};
+
+///
+pub mod decode {
+    use bstr::BString;
+
+    /// The error returned by [Header::from_read()](crate::Header::from_read()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not read the bundle header")]
+        Io(#[from] std::io::Error),
+        #[error("Expected a bundle signature like '# v2 git bundle', but got {line:?}")]
+        InvalidSignature { line: BString },
+        #[error("Unknown bundle capability {name:?}")]
+        UnknownCapability { name: BString },
+        #[error("Unsupported object format {name:?}")]
+        UnsupportedObjectFormat { name: BString },
+        #[error("Could not parse the object id in line {line:?}")]
+        InvalidObjectId {
+            line: BString,
+            source: gix_hash::decode::Error,
+        },
+        #[error("The reference line {line:?} is missing a reference name")]
+        MissingRefName { line: BString },
+        #[error("The bundle header ended before the empty line that separates it from the pack")]
+        UnexpectedEof,
+    }
+}
+
+/// Decoding
+impl Header {
+    /// Read a bundle header from `read`, which is expected to be positioned at the very beginning of the bundle.
+    ///
+    /// After this call, `read` is positioned at the first byte of the pack that follows the header.
+    /// Just like `git`, unknown capabilities are an error as they might change the meaning of the bundle.
+    pub fn from_read(read: &mut dyn BufRead) -> Result<Self, decode::Error> {
+        let mut line = Vec::new();
+        read.read_until(b'\n', &mut line)?;
+        let version = [Version::V2, Version::V3]
+            .into_iter()
+            .find(|version| version.signature() == line.as_slice())
+            .ok_or_else(|| decode::Error::InvalidSignature {
+                line: line.trim_end().into(),
+            })?;
+
+        let mut object_hash = None;
+        let (mut capabilities, mut prerequisites, mut refs) = (Vec::new(), Vec::new(), Vec::new());
+        loop {
+            line.clear();
+            if read.read_until(b'\n', &mut line)? == 0 {
+                return Err(decode::Error::UnexpectedEof);
+            }
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+
+            if let Some(capability) = line.strip_prefix(b"@").filter(|_| version == Version::V3) {
+                let (name, value) = match capability.find_byte(b'=') {
+                    Some(pos) => (&capability[..pos], Some(&capability[pos + 1..])),
+                    None => (capability, None),
+                };
+                match (name, value) {
+                    (b"object-format", Some(value)) => object_hash = Some(object_hash_by_name(value)?),
+                    (b"filter", Some(_)) => {}
+                    _ => return Err(decode::Error::UnknownCapability { name: name.into() }),
+                }
+                capabilities.push(Capability {
+                    name: name.into(),
+                    value: value.map(Into::into),
+                });
+                continue;
+            }
+
+            let object_hash = match object_hash {
+                Some(kind) => kind,
+                None => *object_hash.insert(default_object_hash()?),
+            };
+            if let Some(prerequisite) = line.strip_prefix(b"-") {
+                let (id, comment) = parse_id_and_rest(prerequisite, line, object_hash)?;
+                prerequisites.push(Prerequisite {
+                    id,
+                    comment: comment.unwrap_or_default().into(),
+                });
+            } else {
+                let (id, name) = parse_id_and_rest(line, line, object_hash)?;
+                refs.push(Ref {
+                    id,
+                    name: name
+                        .filter(|name| !name.is_empty())
+                        .ok_or_else(|| decode::Error::MissingRefName { line: line.into() })?
+                        .into(),
+                });
+            }
+        }
+        Ok(Header {
+            version,
+            object_hash: object_hash.map_or_else(default_object_hash, Ok)?,
+            capabilities,
+            prerequisites,
+            refs,
+        })
+    }
+}
+
+/// Bundles without the `object-format` capability use SHA-1.
+pub(crate) fn default_object_hash() -> Result<gix_hash::Kind, decode::Error> {
+    object_hash_by_name(b"sha1")
+}
+
+fn object_hash_by_name(name: &[u8]) -> Result<gix_hash::Kind, decode::Error> {
+    name.to_str()
+        .ok()
+        .and_then(|name| name.parse::<gix_hash::Kind>().ok())
+        .filter(|kind| kind.to_string().as_bytes() == name)
+        .ok_or_else(|| decode::Error::UnsupportedObjectFormat { name: name.into() })
+}
+
+fn parse_id_and_rest<'a>(
+    input: &'a [u8],
+    line: &[u8],
+    object_hash: gix_hash::Kind,
+) -> Result<(ObjectId, Option<&'a [u8]>), decode::Error> {
+    let (hex, rest) = match input.find_byte(b' ') {
+        Some(pos) => (&input[..pos], Some(&input[pos + 1..])),
+        None => (input, None),
+    };
+    let invalid_id = |source| decode::Error::InvalidObjectId {
+        line: line.into(),
+        source,
+    };
+    if hex.len() != object_hash.len_in_hex() {
+        return Err(invalid_id(gix_hash::decode::Error::InvalidHexEncodingLength(hex.len())));
+    }
+    let id = ObjectId::from_hex(hex).map_err(invalid_id)?;
+    Ok((id, rest))
+}
+
+/// Encoding
+impl Header {
+    /// Write this header to `out`, including the empty line that separates it from the pack.
+    ///
+    /// Note that no validation is performed, so callers are responsible for only using
+    /// [capabilities](Self::capabilities) with [version 3](Version::V3).
+    pub fn write_to(&self, out: &mut dyn Write) -> std::io::Result<()> {
+        out.write_all(self.version.signature())?;
+        for Capability { name, value } in &self.capabilities {
+            out.write_all(b"@")?;
+            out.write_all(name)?;
+            if let Some(value) = value {
+                out.write_all(b"=")?;
+                out.write_all(value)?;
+            }
+            out.write_all(b"\n")?;
+        }
+        for Prerequisite { id, comment } in &self.prerequisites {
+            write!(out, "-{id}")?;
+            if !comment.is_empty() {
+                out.write_all(b" ")?;
+                out.write_all(comment)?;
+            }
+            out.write_all(b"\n")?;
+        }
+        for Ref { id, name } in &self.refs {
+            write!(out, "{id} ")?;
+            out.write_all(name)?;
+            out.write_all(b"\n")?;
+        }
+        out.write_all(b"\n")
+    }
+
+    /// Return the value of the first capability called `name`, or `None` if it doesn't exist or has no value.
+    pub fn capability(&self, name: &str) -> Option<&BString> {
+        self.capabilities
+            .iter()
+            .find(|c| c.name == name)
+            .and_then(|c| c.value.as_ref())
+    }
+}
@@ -0,0 +1,110 @@
+//! Read and write [git bundles](https://git-scm.com/docs/gitformat-bundle), which are packs prefixed with a header that
+//! lists the references they contain and the commits the receiving repository must already have.
+//!
+//! * [`File`] opens a bundle and provides access to its [`Header`] and pack data.
+//! * [`Header::verify_prerequisites()`] checks if a repository has all commits needed to make use of a bundle.
+//! * [`write_to()`] creates a bundle from references and excluded revisions.
+//! * [`transport`] makes bundles usable as remote to fetch or clone from, if the `blocking-transport` feature is enabled.
+//!
+//! ## Feature Flags
+#![cfg_attr(
+    all(doc, feature = "document-features"),
+    doc = ::document_features::document_features!()
+)]
+#![cfg_attr(all(doc, feature = "document-features"), feature(doc_cfg))]
+#![deny(missing_docs, rust_2018_idioms)]
+#![forbid(unsafe_code)]
+
+use std::path::PathBuf;
+
+use bstr::BString;
+use gix_hash::ObjectId;
+
+/// The version of a bundle file, as indicated by its signature line.
+#[derive(Default, PartialEq, Eq, Ord, PartialOrd, Debug, Hash, Clone, Copy)]
+pub enum Version {
+    /// The original format, which only supports SHA-1 and has no capabilities.
+    #[default]
+    V2 = 2,
+    /// The format which supports capabilities, like `object-format` and `filter`.
+    V3 = 3,
+}
+
+impl Version {
+    /// Return the signature line with which bundles of this version start, including the trailing newline.
+    pub fn signature(&self) -> &'static [u8] {
+        match self {
+            Version::V2 => b"# v2 git bundle\n",
+            Version::V3 => b"# v3 git bundle\n",
+        }
+    }
+}
+
+/// A capability of a [version 3](Version::V3) bundle, like `object-format=sha256`.
+#[derive(PartialEq, Eq, Debug, Hash, Clone)]
+pub struct Capability {
+    /// The name of the capability, like `object-format`.
+    pub name: BString,
+    /// The value of the capability, like `sha256`, if one was set.
+    pub value: Option<BString>,
+}
+
+/// A commit that isn't contained in the bundle, but that the receiving repository must have for the bundle to be usable.
+#[derive(PartialEq, Eq, Debug, Hash, Clone)]
+pub struct Prerequisite {
+    /// The id of the commit.
+    pub id: ObjectId,
+    /// A comment for use by humans, typically the subject line of the commit, which may be empty.
+    pub comment: BString,
+}
+
+/// A reference contained in the bundle.
+#[derive(PartialEq, Eq, Debug, Hash, Clone)]
+pub struct Ref {
+    /// The object the reference points to.
+    pub id: ObjectId,
+    /// The full name of the reference, like `refs/heads/main` or `HEAD`.
+    pub name: BString,
+}
+
+/// The header of a bundle file which precedes its pack.
+#[derive(PartialEq, Eq, Debug, Hash, Clone)]
+pub struct Header {
+    /// The version of the bundle.
+    pub version: Version,
+    /// The kind of hash used by all object ids in the bundle, as indicated by the `object-format` capability.
+    pub object_hash: gix_hash::Kind,
+    /// All capabilities, in order of occurrence, including `object-format` if it was specified.
+    pub capabilities: Vec<Capability>,
+    /// The commits that are needed to make use of the bundle, but which are not contained in it.
+    pub prerequisites: Vec<Prerequisite>,
+    /// The references contained in the bundle, in order of occurrence.
+    pub refs: Vec<Ref>,
+}
+
+/// A bundle file on disk, along with its parsed header.
+#[derive(Debug, Clone)]
+pub struct File {
+    /// The parsed header of the bundle.
+    pub header: Header,
+    path: PathBuf,
+    pack_offset: u64,
+}
+
+///
+pub mod header;
+
+///
+pub mod file;
+pub use file::is_bundle;
+
+///
+pub mod verify;
+
+///
+pub mod write;
+pub use write::function::write_to;
+
+///
+#[cfg(feature = "blocking-transport")]
+pub mod transport;
@@ -0,0 +1,225 @@
+//! A transport which serves a bundle file as if it was a remote repository.
+//!
+//! The transport emulates a server speaking protocol V1, which advertises the references of the bundle and answers
+//! all negotiation rounds with `NAK` until the client is done, to then send the pack of the bundle no matter which
+//! objects were actually wanted. This is what `git` does as well, as bundles can't produce packs on demand.
+use std::{
+    any::Any,
+    borrow::Cow,
+    io::{Cursor, Read},
+    path::PathBuf,
+};
+
+use bstr::{BStr, BString, ByteVec};
+use gix_transport::{
+    Protocol, Service,
+    client::{
+        self, MessageKind, TransportWithoutIO, WriteMode,
+        blocking_io::{RequestWriter, SetServiceResponse, Transport},
+        capabilities::blocking_recv::Handshake,
+    },
+    packetline::{
+        Channel, PacketLineRef,
+        blocking_io::{StreamingPeekableIter, encode},
+    },
+};
+
+use crate::File;
+
+/// The maximum amount of pack data in a single packet line, accounting for the byte that encodes the side-band channel.
+const MAX_BAND_DATA_LEN: usize = 65515;
+
+/// A connection to a bundle file, see the [module documentation](self) for details.
+pub struct Connection {
+    bundle: File,
+    url: BString,
+    line_provider: StreamingPeekableIter<Box<dyn Read + Send>>,
+    trace: bool,
+}
+
+/// Open the bundle at `path` to serve its content as if it was a remote repository.
+///
+/// If `trace` is `true`, all packetlines received or sent will be passed to the facilities of the `gix-trace` crate.
+pub fn connect(path: impl Into<PathBuf>, trace: bool) -> Result<Connection, crate::file::init::Error> {
+    Ok(Connection::new(File::at(path)?, trace))
+}
+
+impl Connection {
+    /// Serve the content of `bundle`, with `trace` controlling whether all packetlines are traced.
+    pub fn new(bundle: File, trace: bool) -> Self {
+        let mut url: BString = "file://".into();
+        url.push_str(gix_path::into_bstr(bundle.path()).as_ref());
+        Connection {
+            bundle,
+            url,
+            line_provider: new_line_provider(std::io::empty(), trace),
+            trace,
+        }
+    }
+
+    /// The bundle that is served by this connection.
+    pub fn bundle(&self) -> &File {
+        &self.bundle
+    }
+
+    /// Produce the references advertisement of a protocol V1 server, followed by a flush packet.
+    fn advertisement(&self) -> std::io::Result<Vec<u8>> {
+        let header = &self.bundle.header;
+        let mut capabilities = format!(
+            "multi_ack_detailed side-band-64k ofs-delta include-tag object-format={}",
+            header.object_hash
+        );
+        if let Some(head) = self.guess_head() {
+            capabilities.push_str(" symref=HEAD:");
+            capabilities.push_str(&head.to_string());
+        }
+
+        let mut out = Vec::new();
+        let mut refs = header.refs.iter();
+        let mut line: BString = match refs.next() {
+            Some(first) => format!("{} {}", first.id, first.name).into(),
+            None => format!("{} capabilities^{{}}", header.object_hash.null()).into(),
+        };
+        line.push(0);
+        line.push_str(capabilities);
+        line.push(b'\n');
+        encode::data_to_write(&line, &mut out)?;
+        for r in refs {
+            line.clear();
+            line.push_str(format!("{} {}\n", r.id, r.name));
+            encode::data_to_write(&line, &mut out)?;
+        }
+        encode::flush_to_write(&mut out)?;
+        Ok(out)
+    }
+
+    /// Bundles don't know which branch `HEAD` points to, so pick the first branch that points to the same commit, if there is one.
+    fn guess_head(&self) -> Option<&BStr> {
+        let refs = &self.bundle.header.refs;
+        let head = refs.iter().find(|r| r.name == "HEAD")?;
+        refs.iter()
+            .find(|r| r.id == head.id && r.name.starts_with(b"refs/heads/"))
+            .map(|r| r.name.as_ref())
+    }
+}
+
+fn new_line_provider(response: impl Read + Send + 'static, trace: bool) -> StreamingPeekableIter<Box<dyn Read + Send>> {
+    StreamingPeekableIter::new(Box::new(response), &[PacketLineRef::Flush], trace)
+}
+
+impl TransportWithoutIO for Connection {
+    fn to_url(&self) -> Cow<'_, BStr> {
+        Cow::Borrowed(self.url.as_ref())
+    }
+
+    fn connection_persists_across_multiple_requests(&self) -> bool {
+        true
+    }
+
+    fn configure(&mut self, _config: &dyn Any) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+        Ok(())
+    }
+
+    fn supported_protocol_versions(&self) -> &[Protocol] {
+        &[Protocol::V1]
+    }
+}
+
+impl Transport for Connection {
+    fn handshake<'a>(
+        &mut self,
+        service: Service,
+        _extra_parameters: &'a [(&'a str, Option<&'a str>)],
+    ) -> Result<SetServiceResponse<'_>, client::Error> {
+        if service != Service::UploadPack {
+            return Err(client::Error::Io(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                format!(
+                    "Bundles can only be fetched from, but {} was requested",
+                    service.as_str()
+                ),
+            )));
+        }
+        self.line_provider = new_line_provider(Cursor::new(self.advertisement()?), self.trace);
+        let Handshake {
+            capabilities,
+            refs,
+            protocol: actual_protocol,
+        } = Handshake::from_lines_with_version_detection(&mut self.line_provider)?;
+        Ok(SetServiceResponse {
+            actual_protocol,
+            capabilities,
+            refs,
+        })
+    }
+
+    fn request(
+        &mut self,
+        write_mode: WriteMode,
+        on_into_read: MessageKind,
+        trace: bool,
+    ) -> Result<RequestWriter<'_>, client::Error> {
+        let mut response = Vec::new();
+        encode::data_to_write(b"NAK\n", &mut response)?;
+        let client_is_done = matches!(on_into_read, MessageKind::Text(b"done"));
+        self.line_provider = if client_is_done {
+            new_line_provider(
+                Cursor::new(response).chain(SidebandPack::new(self.bundle.open_pack()?)),
+                self.trace,
+            )
+        } else {
+            new_line_provider(Cursor::new(response), self.trace)
+        };
+        Ok(RequestWriter::new_from_bufread(
+            std::io::sink(),
+            Box::new(self.line_provider.as_read_without_sidebands()),
+            write_mode,
+            on_into_read,
+            trace,
+        ))
+    }
+}
+
+/// Turn the pack of a bundle into side-band encoded packet lines, followed by a flush packet.
+struct SidebandPack {
+    pack: std::fs::File,
+    data: Vec<u8>,
+    buf: Vec<u8>,
+    pos: usize,
+    is_done: bool,
+}
+
+impl SidebandPack {
+    fn new(pack: std::fs::File) -> Self {
+        SidebandPack {
+            pack,
+            data: vec![0; MAX_BAND_DATA_LEN],
+            buf: Vec::new(),
+            pos: 0,
+            is_done: false,
+        }
+    }
+}
+
+impl Read for SidebandPack {
+    fn read(&mut self, out: &mut [u8]) -> std::io::Result<usize> {
+        if self.pos == self.buf.len() {
+            if self.is_done {
+                return Ok(0);
+            }
+            let num_read = self.pack.read(&mut self.data)?;
+            self.buf.clear();
+            self.pos = 0;
+            if num_read == 0 {
+                encode::flush_to_write(&mut self.buf)?;
+                self.is_done = true;
+            } else {
+                encode::band_to_write(Channel::Data, &self.data[..num_read], &mut self.buf)?;
+            }
+        }
+        let num_bytes = out.len().min(self.buf.len() - self.pos);
+        out[..num_bytes].copy_from_slice(&self.buf[self.pos..][..num_bytes]);
+        self.pos += num_bytes;
+        Ok(num_bytes)
+    }
+}
@@ -0,0 +1,72 @@
+use gix_hash::ObjectId;
+
+use crate::Header;
+
+mod error {
+    use gix_hash::ObjectId;
+
+    /// The error returned by [Header::verify_prerequisites()](crate::Header::verify_prerequisites()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not look up prerequisite commit {id}")]
+        FindHeader {
+            id: ObjectId,
+            source: gix_object::find::Error,
+        },
+        #[error("The repository lacks {} prerequisite commit(s) of the bundle: {}", missing.len(), missing.iter().map(ToString::to_string).collect::<Vec<_>>().join(", "))]
+        MissingPrerequisites { missing: Vec<ObjectId> },
+        #[error("Prerequisite {id} of the bundle is a {actual}, but should be a commit")]
+        NotACommit { id: ObjectId, actual: gix_object::Kind },
+        #[error("The bundle uses {actual} object ids, but the repository uses {expected}")]
+        ObjectHashMismatch {
+            expected: gix_hash::Kind,
+            actual: gix_hash::Kind,
+        },
+    }
+}
+pub use error::Error;
+
+/// Verification
+impl Header {
+    /// Assure that all prerequisites of this bundle exist in `objects` and are commits, with `objects` being
+    /// the object database of the repository that is supposed to receive the bundle.
+    ///
+    /// Prerequisites are commits that objects in the bundle's pack may refer to, without these being contained in the pack.
+    /// Note that `git` additionally checks that prerequisites are reachable from the references of the receiving
+    /// repository, which isn't done here.
+    pub fn verify_prerequisites(
+        &self,
+        objects: &dyn gix_object::FindHeader,
+        object_hash: gix_hash::Kind,
+    ) -> Result<(), Error> {
+        if self.object_hash != object_hash {
+            return Err(Error::ObjectHashMismatch {
+                expected: object_hash,
+                actual: self.object_hash,
+            });
+        }
+        let mut missing = Vec::<ObjectId>::new();
+        for prerequisite in &self.prerequisites {
+            let id = prerequisite.id;
+            match objects
+                .try_header(&id)
+                .map_err(|source| Error::FindHeader { id, source })?
+            {
+                Some(header) if header.kind != gix_object::Kind::Commit => {
+                    return Err(Error::NotACommit {
+                        id,
+                        actual: header.kind,
+                    });
+                }
+                Some(_) => {}
+                None => missing.push(id),
+            }
+        }
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::MissingPrerequisites { missing })
+        }
+    }
+}
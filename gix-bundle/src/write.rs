@@ -0,0 +1,298 @@
+use gix_hash::ObjectId;
+
+use crate::{Header, Version};
+
+/// The error returned by [`write_to()`](crate::write_to()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Refusing to create an empty bundle")]
+    Empty,
+    #[error("Version {version:?} bundles can't hold {object_hash} object ids")]
+    UnsupportedObjectHash {
+        version: Version,
+        object_hash: gix_hash::Kind,
+    },
+    #[error(transparent)]
+    FindExisting(#[from] gix_object::find::existing::Error),
+    #[error(transparent)]
+    FindExistingObject(#[from] gix_object::find::existing_object::Error),
+    #[error(transparent)]
+    FindExistingIter(#[from] gix_object::find::existing_iter::Error),
+    #[error(transparent)]
+    Decode(#[from] gix_object::decode::Error),
+    #[error(transparent)]
+    Traverse(#[from] gix_traverse::commit::simple::Error),
+    #[error(transparent)]
+    Count(#[from] gix_pack::data::output::count::objects::Error),
+    #[error(transparent)]
+    WritePack(#[from] gix_pack::data::output::bytes::Error<gix_pack::data::output::entry::iter_from_counts::Error>),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("Interrupted")]
+    Interrupted,
+}
+
+/// Configuration for [`write_to()`](crate::write_to()).
+#[derive(Default, Debug, Clone, Copy)]
+pub struct Options {
+    /// The version of the bundle to write, or `None` to write [version 2](Version::V2) bundles if the object hash allows it,
+    /// and [version 3](Version::V3) bundles otherwise, just like `git` does by default.
+    pub version: Option<Version>,
+    /// The amount of threads to use when creating pack entries, or `None` to use all available logical cores.
+    pub thread_limit: Option<usize>,
+}
+
+/// The result of [`write_to()`](crate::write_to()).
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    /// The header that was written.
+    pub header: Header,
+    /// The trailing checksum of the pack that was written after the header.
+    pub pack_checksum: ObjectId,
+    /// The amount of objects in the pack.
+    pub num_objects: u32,
+}
+
+pub(super) mod function {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use gix_features::progress::{Count, DynNestedProgress, Progress};
+    use gix_hash::ObjectId;
+    use gix_hashtable::HashSet;
+    use gix_object::FindExt;
+    use gix_pack::data::output;
+
+    use super::{Error, Options, Outcome};
+    use crate::{Capability, Header, Prerequisite, Ref, Version};
+
+    /// Write a bundle to `out` that contains all `refs` along with all objects reachable from them, excluding the
+    /// ones reachable from the commits in `excluded`, reading all objects from `objects`.
+    ///
+    /// Tags in `refs` or `excluded` are peeled to learn about the commits to include or exclude.
+    /// The commits that objects in the bundle build upon without including them become the
+    /// [prerequisites](Header::prerequisites) of the bundle, and the objects reachable from them are not included in its pack.
+    /// The pack is self-contained in the sense that it doesn't contain deltas against objects outside of it.
+    ///
+    /// `progress` is used to report the steps of the pack creation, which can be interrupted through `should_interrupt`.
+    pub fn write_to<Find>(
+        refs: impl IntoIterator<Item = Ref>,
+        excluded: impl IntoIterator<Item = ObjectId>,
+        objects: Find,
+        out: &mut dyn std::io::Write,
+        progress: &mut dyn DynNestedProgress,
+        should_interrupt: &AtomicBool,
+        Options { version, thread_limit }: Options,
+    ) -> Result<Outcome, Error>
+    where
+        Find: gix_pack::Find + gix_object::Find + Send + Clone + 'static,
+    {
+        let refs: Vec<_> = refs.into_iter().collect();
+        let object_hash = refs.first().ok_or(Error::Empty)?.id.kind();
+        let is_sha1 = crate::header::default_object_hash().ok() == Some(object_hash);
+        let version = match version {
+            Some(Version::V2) if !is_sha1 => {
+                return Err(Error::UnsupportedObjectHash {
+                    version: Version::V2,
+                    object_hash,
+                });
+            }
+            Some(version) => version,
+            None if is_sha1 => Version::V2,
+            None => Version::V3,
+        };
+
+        let mut buf = Vec::new();
+        let mut ids = Vec::new();
+        let mut seen = HashSet::default();
+        let (mut tips, mut trees) = (Vec::new(), Vec::new());
+        for r in &refs {
+            peel(&objects, r.id, &mut buf, |id, kind| match kind {
+                gix_object::Kind::Commit => tips.push(id),
+                gix_object::Kind::Tree => trees.push(id),
+                gix_object::Kind::Blob | gix_object::Kind::Tag => {
+                    if seen.insert(id) {
+                        ids.push(id);
+                    }
+                }
+            })?;
+        }
+        let mut hidden = Vec::new();
+        for id in excluded {
+            peel(&objects, id, &mut buf, |id, kind| {
+                if kind == gix_object::Kind::Commit {
+                    hidden.push(id);
+                }
+            })?;
+        }
+
+        let mut commits = Vec::new();
+        {
+            let mut progress = progress.add_child("traversing".into());
+            progress.init(None, gix_features::progress::count("commits"));
+            for info in gix_traverse::commit::Simple::new(tips.iter().copied(), objects.clone()).hide(hidden)? {
+                if should_interrupt.load(Ordering::Relaxed) {
+                    return Err(Error::Interrupted);
+                }
+                commits.push(info?);
+                progress.inc();
+            }
+        }
+
+        let mut prerequisite_ids = Vec::new();
+        {
+            let included: HashSet<_> = commits.iter().map(|info| info.id).collect();
+            let mut is_new_prerequisite = HashSet::default();
+            for id in commits
+                .iter()
+                .flat_map(|info| info.parent_ids.iter().copied())
+                .chain(tips.iter().copied())
+            {
+                if !included.contains(&id) && is_new_prerequisite.insert(id) {
+                    prerequisite_ids.push(id);
+                }
+            }
+        }
+        let mut prerequisites = Vec::with_capacity(prerequisite_ids.len());
+        for id in prerequisite_ids {
+            let tree = {
+                let commit = objects.find_commit(&id, &mut buf)?;
+                prerequisites.push(Prerequisite {
+                    id,
+                    comment: commit.message_summary().into_owned(),
+                });
+                commit.tree()
+            };
+            add_tree(&objects, tree, &mut seen, None, &mut buf)?;
+        }
+
+        for info in &commits {
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err(Error::Interrupted);
+            }
+            seen.insert(info.id);
+            ids.push(info.id);
+            let tree = objects.find_commit_iter(&info.id, &mut buf)?.tree_id()?;
+            add_tree(&objects, tree, &mut seen, Some(&mut ids), &mut buf)?;
+        }
+        for tree in trees {
+            add_tree(&objects, tree, &mut seen, Some(&mut ids), &mut buf)?;
+        }
+        if ids.is_empty() {
+            return Err(Error::Empty);
+        }
+
+        let counts = {
+            let mut progress = progress.add_child("counting".into());
+            progress.init(Some(ids.len()), gix_features::progress::count("objects"));
+            let (counts, _outcome) = output::count::objects_unthreaded(
+                &objects,
+                &mut ids.into_iter().map(Ok),
+                &progress,
+                should_interrupt,
+                output::count::objects::ObjectExpansion::AsIs,
+            )?;
+            counts
+        };
+
+        let header = Header {
+            version,
+            object_hash,
+            capabilities: match version {
+                Version::V2 => Vec::new(),
+                Version::V3 => vec![Capability {
+                    name: "object-format".into(),
+                    value: Some(object_hash.to_string().into()),
+                }],
+            },
+            prerequisites,
+            refs,
+        };
+        header.write_to(out)?;
+
+        let num_objects = counts.len() as u32;
+        let entries = output::entry::iter_from_counts(
+            counts,
+            objects,
+            Box::new(progress.add_child("creating entries".into())),
+            output::entry::iter_from_counts::Options {
+                thread_limit,
+                mode: output::entry::iter_from_counts::Mode::PackCopyAndBaseObjects,
+                allow_thin_pack: false,
+                ..Default::default()
+            },
+        );
+        let mut write_progress = progress.add_child("writing".into());
+        write_progress.init(None, gix_features::progress::bytes());
+        let mut pack = output::bytes::FromEntriesIter::new(
+            gix_features::parallel::InOrderIter::from(entries),
+            out,
+            num_objects,
+            gix_pack::data::Version::V2,
+            object_hash,
+        );
+        for written in pack.by_ref() {
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err(Error::Interrupted);
+            }
+            write_progress.inc_by(written? as usize);
+        }
+        let pack_checksum = pack.digest().expect("iteration is done");
+
+        Ok(Outcome {
+            header,
+            pack_checksum,
+            num_objects,
+        })
+    }
+
+    /// Call `cb` with `id` and each object it points to if it's a tag, with the object kind of each.
+    fn peel(
+        objects: &dyn gix_object::Find,
+        mut id: ObjectId,
+        buf: &mut Vec<u8>,
+        mut cb: impl FnMut(ObjectId, gix_object::Kind),
+    ) -> Result<(), Error> {
+        loop {
+            let object = objects.find(&id, buf)?;
+            cb(id, object.kind);
+            if object.kind != gix_object::Kind::Tag {
+                return Ok(());
+            }
+            id = object.decode()?.into_tag().expect("tag").target();
+        }
+    }
+
+    /// Mark `tree` and all objects reachable from it as `seen`, and add those that weren't seen yet to `out` if set.
+    /// Submodule commits are ignored.
+    fn add_tree(
+        objects: &dyn gix_object::Find,
+        tree: ObjectId,
+        seen: &mut HashSet,
+        mut out: Option<&mut Vec<ObjectId>>,
+        buf: &mut Vec<u8>,
+    ) -> Result<(), Error> {
+        if !seen.insert(tree) {
+            return Ok(());
+        }
+        if let Some(out) = out.as_deref_mut() {
+            out.push(tree);
+        }
+        let mut trees = vec![tree];
+        while let Some(tree) = trees.pop() {
+            for entry in objects.find_tree_iter(&tree, buf)? {
+                let entry = entry?;
+                if entry.mode.is_commit() || !seen.insert(entry.oid.to_owned()) {
+                    continue;
+                }
+                if let Some(out) = out.as_deref_mut() {
+                    out.push(entry.oid.to_owned());
+                }
+                if entry.mode.is_tree() {
+                    trees.push(entry.oid.to_owned());
+                }
+            }
+        }
+        Ok(())
+    }
+}
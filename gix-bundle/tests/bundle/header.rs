@@ -0,0 +1,192 @@
+use bstr::ByteSlice;
+use gix_bundle::{Header, Prerequisite, Version};
+
+use crate::{fixture_dir, num_pack_objects, odb, rev_parse};
+
+fn ref_names(header: &Header) -> Vec<&str> {
+    header
+        .refs
+        .iter()
+        .map(|r| r.name.to_str().expect("valid UTF-8"))
+        .collect()
+}
+
+fn expected_default_version() -> Version {
+    if gix_testtools::object_hash().to_string() == "sha1" {
+        Version::V2
+    } else {
+        Version::V3
+    }
+}
+
+#[test]
+fn all_refs_without_prerequisites() -> crate::Result {
+    let dir = fixture_dir()?;
+    let repo = dir.join("repo");
+    let bundle = gix_bundle::File::at(dir.join("all.bundle"))?;
+    let header = &bundle.header;
+    assert_eq!(header.version, expected_default_version());
+    assert_eq!(header.object_hash, gix_testtools::object_hash());
+    assert!(header.prerequisites.is_empty());
+    assert_eq!(
+        ref_names(header),
+        ["refs/heads/feature", "refs/heads/main", "refs/tags/v1", "HEAD"]
+    );
+    for r in &header.refs {
+        assert_eq!(r.id, rev_parse(&repo, r.name.to_str()?)?);
+    }
+    assert_eq!(num_pack_objects(&bundle)?, 13, "4 commits, 4 trees, 4 blobs and a tag");
+    Ok(())
+}
+
+#[test]
+fn incremental_with_prerequisites() -> crate::Result {
+    let dir = fixture_dir()?;
+    let repo = dir.join("repo");
+    let bundle = gix_bundle::File::at(dir.join("incremental.bundle"))?;
+    let header = &bundle.header;
+    assert_eq!(
+        header.prerequisites,
+        [Prerequisite {
+            id: rev_parse(&repo, "v1^{commit}")?,
+            comment: "second".into(),
+        }]
+    );
+    assert_eq!(ref_names(header), ["refs/heads/main", "refs/heads/feature"]);
+    assert_eq!(num_pack_objects(&bundle)?, 6, "2 commits, 2 trees and 2 blobs");
+    Ok(())
+}
+
+#[test]
+fn v3_with_object_format_capability() -> crate::Result {
+    let bundle = gix_bundle::File::at(fixture_dir()?.join("v3.bundle"))?;
+    let header = &bundle.header;
+    assert_eq!(header.version, Version::V3);
+    assert_eq!(header.capabilities.len(), 1);
+    assert_eq!(
+        header.capability("object-format").map(ToString::to_string),
+        Some(gix_testtools::object_hash().to_string())
+    );
+    assert_eq!(ref_names(header), ["refs/heads/main"]);
+    Ok(())
+}
+
+#[test]
+fn write_to_reproduces_headers_written_by_git() -> crate::Result {
+    let dir = fixture_dir()?;
+    for name in ["all.bundle", "incremental.bundle", "v3.bundle"] {
+        let bundle = gix_bundle::File::at(dir.join(name))?;
+        let mut buf = Vec::new();
+        bundle.header.write_to(&mut buf)?;
+        let data = std::fs::read(bundle.path())?;
+        assert_eq!(buf.as_bstr(), data[..bundle.pack_offset() as usize].as_bstr(), "{name}");
+        assert_eq!(Header::from_read(&mut buf.as_slice())?, bundle.header);
+    }
+    Ok(())
+}
+
+#[test]
+fn is_bundle() -> crate::Result {
+    let dir = fixture_dir()?;
+    assert!(gix_bundle::is_bundle(&dir.join("all.bundle")));
+    assert!(gix_bundle::is_bundle(&dir.join("v3.bundle")));
+    assert!(!gix_bundle::is_bundle(&dir.join("repo")), "directories aren't bundles");
+    assert!(
+        !gix_bundle::is_bundle(&dir.join("repo").join("a")),
+        "neither are other files"
+    );
+    assert!(!gix_bundle::is_bundle(&dir.join("does-not-exist")));
+    Ok(())
+}
+
+#[test]
+fn verify_prerequisites() -> crate::Result {
+    let dir = fixture_dir()?;
+    let object_hash = gix_testtools::object_hash();
+    let bundle = gix_bundle::File::at(dir.join("incremental.bundle"))?;
+    bundle
+        .header
+        .verify_prerequisites(&odb(&dir.join("base"))?, object_hash)?;
+
+    let err = bundle
+        .header
+        .verify_prerequisites(&odb(&dir.join("empty"))?, object_hash)
+        .unwrap_err();
+    assert!(
+        matches!(&err, gix_bundle::verify::Error::MissingPrerequisites { missing } if *missing == [bundle.header.prerequisites[0].id]),
+        "{err:?}"
+    );
+
+    let all = gix_bundle::File::at(dir.join("all.bundle"))?;
+    all.header
+        .verify_prerequisites(&odb(&dir.join("empty"))?, object_hash)?;
+    Ok(())
+}
+
+mod decode_errors {
+    use gix_bundle::{Header, header::decode};
+
+    fn decode(input: &str) -> decode::Error {
+        Header::from_read(&mut input.as_bytes()).expect_err("invalid input")
+    }
+
+    const ID: &str = "37e7d9d8a25fff6030e28f64af56ad5d0be826c6";
+
+    #[test]
+    fn invalid_signature() {
+        assert!(matches!(
+            decode("# v4 git bundle\n\n"),
+            decode::Error::InvalidSignature { line } if line == "# v4 git bundle"
+        ));
+    }
+
+    #[test]
+    fn unknown_capability() {
+        assert!(matches!(
+            decode("# v3 git bundle\n@unknown=value\n\n"),
+            decode::Error::UnknownCapability { name } if name == "unknown"
+        ));
+        assert!(matches!(
+            decode("# v3 git bundle\n@object-format=md5\n\n"),
+            decode::Error::UnsupportedObjectFormat { name } if name == "md5"
+        ));
+    }
+
+    #[test]
+    fn capabilities_are_not_supported_in_v2() {
+        assert!(matches!(
+            decode("# v2 git bundle\n@object-format=sha1\n\n"),
+            decode::Error::InvalidObjectId { .. }
+        ));
+    }
+
+    #[test]
+    fn invalid_lines() {
+        assert!(matches!(
+            decode("# v2 git bundle\n-abc comment\n\n"),
+            decode::Error::InvalidObjectId { .. }
+        ));
+        assert!(matches!(
+            decode(&format!("# v2 git bundle\n{ID}\n\n")),
+            decode::Error::MissingRefName { .. }
+        ));
+    }
+
+    #[test]
+    fn missing_separator() {
+        assert!(matches!(
+            decode(&format!("# v2 git bundle\n{ID} refs/heads/main\n")),
+            decode::Error::UnexpectedEof
+        ));
+    }
+}
+
+#[test]
+fn decode_leaves_reader_at_pack() -> crate::Result {
+    let input = b"# v2 git bundle\n\nPACK";
+    let mut read = input.as_slice();
+    let header = Header::from_read(&mut read)?;
+    assert!(header.refs.is_empty() && header.prerequisites.is_empty());
+    assert_eq!(read, b"PACK");
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use std::path::{Path, PathBuf};
+
+pub use gix_testtools::Result;
+
+/// The directory with all bundles created by `git`, along with the repositories `repo`, `base` and `empty`.
+pub fn fixture_dir() -> Result<PathBuf> {
+    gix_testtools::scripted_fixture_read_only("make_bundles.sh")
+}
+
+pub fn odb(repo: &Path) -> Result<gix_odb::HandleArc> {
+    Ok(gix_odb::at_opts(
+        repo.join(".git").join("objects"),
+        Vec::new(),
+        gix_odb::store::init::Options {
+            object_hash: gix_testtools::object_hash(),
+            ..Default::default()
+        },
+    )?
+    .into_arc()?)
+}
+
+/// Return the object id `rev` resolves to in `repo`, according to `git`.
+pub fn rev_parse(repo: &Path, rev: &str) -> Result<gix_hash::ObjectId> {
+    Ok(gix_hash::ObjectId::from_hex(
+        gix_testtools::git(repo, &format!("rev-parse {rev}"))?.trim().as_bytes(),
+    )?)
+}
+
+/// The amount of objects in the pack of `bundle`, as stored in the pack header.
+pub fn num_pack_objects(bundle: &gix_bundle::File) -> Result<u32> {
+    use std::io::Read;
+    let mut header = [0u8; 12];
+    bundle.open_pack()?.read_exact(&mut header)?;
+    assert_eq!(&header[..4], b"PACK", "the pack follows the bundle header");
+    Ok(u32::from_be_bytes(header[8..].try_into()?))
+}
+
+mod header;
+mod write;
@@ -0,0 +1,132 @@
+use std::{path::Path, sync::atomic::AtomicBool};
+
+use gix_bundle::{Ref, Version, write};
+
+use crate::{fixture_dir, odb, rev_parse};
+
+fn refs(repo: &Path, names: &[&str]) -> crate::Result<Vec<Ref>> {
+    names
+        .iter()
+        .map(|name| {
+            Ok(Ref {
+                id: rev_parse(repo, name)?,
+                name: (*name).into(),
+            })
+        })
+        .collect()
+}
+
+fn write_bundle(
+    repo: &Path,
+    refs: Vec<Ref>,
+    excluded: Vec<gix_hash::ObjectId>,
+    out: &Path,
+    options: write::Options,
+) -> Result<write::Outcome, write::Error> {
+    let mut file = std::fs::File::create(out)?;
+    gix_bundle::write_to(
+        refs,
+        excluded,
+        odb(repo).expect("valid object database"),
+        &mut file,
+        &mut gix_features::progress::Discard,
+        &AtomicBool::default(),
+        options,
+    )
+}
+
+#[test]
+fn all_refs() -> crate::Result {
+    let dir = fixture_dir()?;
+    let repo = dir.join("repo");
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let path = tmp.path().join("all.bundle");
+
+    let expected = gix_bundle::File::at(dir.join("all.bundle"))?;
+    let outcome = write_bundle(
+        &repo,
+        expected.header.refs.clone(),
+        Vec::new(),
+        &path,
+        Default::default(),
+    )?;
+    assert_eq!(
+        outcome.header, expected.header,
+        "the header is the same as the one written by git"
+    );
+    assert_eq!(outcome.num_objects, crate::num_pack_objects(&expected)?);
+
+    let actual = gix_bundle::File::at(&path)?;
+    assert_eq!(actual.header, outcome.header);
+    assert_eq!(crate::num_pack_objects(&actual)?, outcome.num_objects);
+    gix_testtools::git(&repo, &format!("bundle verify -q {}", path.display()))?;
+    Ok(())
+}
+
+#[test]
+fn incremental_with_excluded_tag() -> crate::Result {
+    let dir = fixture_dir()?;
+    let repo = dir.join("repo");
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let path = tmp.path().join("incremental.bundle");
+
+    let expected = gix_bundle::File::at(dir.join("incremental.bundle"))?;
+    let outcome = write_bundle(
+        &repo,
+        refs(&repo, &["refs/heads/main", "refs/heads/feature"])?,
+        vec![rev_parse(&repo, "v1")?],
+        &path,
+        Default::default(),
+    )?;
+    assert_eq!(
+        outcome.header.prerequisites, expected.header.prerequisites,
+        "the annotated tag is peeled to the commit it points to"
+    );
+    assert_eq!(outcome.num_objects, crate::num_pack_objects(&expected)?);
+
+    gix_testtools::git(dir.join("base"), &format!("bundle verify -q {}", path.display()))?;
+    assert!(
+        gix_testtools::git(dir.join("empty"), &format!("bundle verify -q {}", path.display())).is_err(),
+        "the prerequisites are missing there"
+    );
+    Ok(())
+}
+
+#[test]
+fn v3_with_object_format_capability() -> crate::Result {
+    let dir = fixture_dir()?;
+    let repo = dir.join("repo");
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let path = tmp.path().join("v3.bundle");
+
+    let outcome = write_bundle(
+        &repo,
+        refs(&repo, &["refs/heads/main"])?,
+        Vec::new(),
+        &path,
+        write::Options {
+            version: Some(Version::V3),
+            ..Default::default()
+        },
+    )?;
+    let expected = gix_bundle::File::at(dir.join("v3.bundle"))?;
+    assert_eq!(outcome.header, expected.header);
+    gix_testtools::git(&repo, &format!("bundle verify -q {}", path.display()))?;
+    Ok(())
+}
+
+#[test]
+fn nothing_to_include_is_an_error() -> crate::Result {
+    let dir = fixture_dir()?;
+    let repo = dir.join("repo");
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let path = tmp.path().join("empty.bundle");
+
+    let main = refs(&repo, &["refs/heads/main"])?;
+    let err = write_bundle(&repo, main.clone(), vec![main[0].id], &path, Default::default()).unwrap_err();
+    assert!(matches!(err, write::Error::Empty), "{err:?}");
+
+    let err = write_bundle(&repo, Vec::new(), Vec::new(), &path, Default::default()).unwrap_err();
+    assert!(matches!(err, write::Error::Empty), "{err:?}");
+    Ok(())
+}
@@ -0,0 +1,23 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q repo
+(cd repo
+  git checkout -q -b main
+  echo a > a && git add a && git commit -q -m "first"
+  echo b > b && git add b && git commit -q -m "second"
+  git tag -a v1 -m "annotated tag"
+  git checkout -q -b feature
+  echo c > c && git add c && git commit -q -m "third on feature"
+  git checkout -q main
+  echo d > d && git add d && git commit -q -m "fourth"
+
+  git bundle create -q ../all.bundle --all
+  git bundle create -q ../incremental.bundle main feature ^v1
+  git bundle create -q --version=3 ../v3.bundle main
+)
+
+git init -q empty
+
+git init -q base
+git -C base fetch -q ../repo refs/tags/v1:refs/tags/v1
@@ -0,0 +1,409 @@
+//! Find the lines that start a function, like `git` does with `diff.<driver>.xfuncname`, to show them in the header of
+//! each hunk and to extend hunks to the functions they change.
+use std::fmt::Write;
+
+use bstr::{BStr, BString, ByteSlice};
+use imara_diff::InternedInput;
+
+use super::unified_diff::HunkHeader;
+
+/// Regular expressions to find lines that start a function, as configured with `diff.<driver>.xfuncname` or
+/// `diff.<driver>.funcname`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Pattern {
+    /// One regular expression per line, which are tried in order until one matches.
+    ///
+    /// If the matching expression starts with `!`, the line doesn't start a function. Otherwise, its first capture group,
+    /// or the whole match if there is none, is the name of the function.
+    pub value: BString,
+    /// If `true`, the expressions are POSIX extended regular expressions like in `xfuncname`, otherwise they are
+    /// basic ones like in `funcname`.
+    pub extended: bool,
+    /// If `true`, letters match regardless of their case.
+    pub ignore_case: bool,
+}
+
+/// The built-in patterns of `git`, as `(driver name, ignore case, extended regular expressions)`.
+const BUILTIN: &[(&str, bool, &str)] = &[
+    (
+        "ada",
+        true,
+        "!^(.*[ \t])?(is[ \t]+new|renames|is[ \t]+separate)([ \t].*)?$\n!^[ \t]*with[ \t].*$\n^[ \t]*((procedure|function)[ \t]+.*)$\n^[ \t]*((package|protected|task)[ \t]+.*)$",
+    ),
+    (
+        "bash",
+        false,
+        "^[ \t]*(([a-zA-Z_][a-zA-Z0-9_]*[ \t]*\\([ \t]*\\))|(function[ \t]+[a-zA-Z_][a-zA-Z0-9_]*(([ \t]*\\([ \t]*\\))|([ \t]+)))[ \t]*(\\{|\\(\\(?|\\[\\[))",
+    ),
+    (
+        "bibtex",
+        false,
+        "(@[a-zA-Z]{1,}[ \t]*\\{{0,1}[ \t]*[^ \t\"@',\\#}{~%]*).*$",
+    ),
+    (
+        "cpp",
+        false,
+        "!^[ \t]*[A-Za-z_][A-Za-z_0-9]*:[[:space:]]*($|/[/*])\n^((::[[:space:]]*)?[A-Za-z_].*)$",
+    ),
+    (
+        "csharp",
+        false,
+        "!^[ \t]*(do|while|for|if|else|instanceof|new|return|switch|case|throw|catch|using)\n^[ \t]*(((static|public|internal|private|protected|new|virtual|sealed|override|unsafe|async)[ \t]+)*[][<>@.~_[:alnum:]]+[ \t]+[<>@._[:alnum:]]+[ \t]*\\(.*\\))[ \t]*$\n^[ \t]*(((static|public|internal|private|protected|new|virtual|sealed|override|unsafe)[ \t]+)*[][<>@.~_[:alnum:]]+[ \t]+[@._[:alnum:]]+)[ \t]*$\n^[ \t]*(((static|public|internal|private|protected|new|unsafe|sealed|abstract|partial)[ \t]+)*(class|enum|interface|struct|record)[ \t]+.*)$\n^[ \t]*(namespace[ \t]+.*)$",
+    ),
+    ("css", true, "![:;][[:space:]]*$\n^[:[@.#]?[_a-z0-9].*$"),
+    ("dts", false, "!;\n!=\n^[ \t]*((/[ \t]*\\{|&?[a-zA-Z_]).*)"),
+    (
+        "elixir",
+        false,
+        "^[ \t]*((def(macro|module|impl|protocol|p)?|test)[ \t].*)$",
+    ),
+    (
+        "fortran",
+        true,
+        "!^([C*]|[ \t]*!)\n!^[ \t]*MODULE[ \t]+PROCEDURE[ \t]\n^[ \t]*((END[ \t]+)?(PROGRAM|MODULE|BLOCK[ \t]+DATA|([^!'\" \t]+[ \t]+)*(SUBROUTINE|FUNCTION))[ \t]+[A-Z].*)$",
+    ),
+    ("fountain", true, "^((\\.[^.]|(int|ext|est|int\\.?/ext|i/e)[. ]).*)$"),
+    (
+        "golang",
+        false,
+        "^[ \t]*(func[ \t]*.*(\\{[ \t]*)?)\n^[ \t]*(type[ \t].*(struct|interface)[ \t]*(\\{[ \t]*)?)",
+    ),
+    ("html", false, "^[ \t]*(<[Hh][1-6]([ \t].*)?>.*)$"),
+    (
+        "java",
+        false,
+        "!^[ \t]*(catch|do|for|if|instanceof|new|return|switch|throw|while)\n^[ \t]*(([a-z]+[ \t]+)*(class|enum|interface)[ \t]+[A-Za-z][A-Za-z0-9_$]*[ \t]+.*)$\n^[ \t]*(([A-Za-z_<>&][][?&<>.,A-Za-z_0-9]*[ \t]+)+[A-Za-z_][A-Za-z_0-9]*[ \t]*\\([^;]*)$",
+    ),
+    (
+        "kotlin",
+        false,
+        "^[ \t]*(([a-z]+[ \t]+)*(fun|class|interface)[ \t]+.*)$",
+    ),
+    ("markdown", false, "^ {0,3}#{1,6}[ \t].*"),
+    (
+        "matlab",
+        false,
+        "^[[:space:]]*((classdef|function)[[:space:]].*)$|^(%%%?|##)[[:space:]].*$",
+    ),
+    (
+        "objc",
+        false,
+        "!^[ \t]*(do|for|if|else|return|switch|while)\n^[ \t]*([-+][ \t]*\\([ \t]*[A-Za-z_][A-Za-z_0-9* \t]*\\)[ \t]*[A-Za-z_].*)$\n^[ \t]*(([A-Za-z_][A-Za-z_0-9]*[ \t]+)+[A-Za-z_][A-Za-z_0-9]*[ \t]*\\([^;]*)$\n^(@(implementation|interface|protocol)[ \t].*)$",
+    ),
+    (
+        "pascal",
+        false,
+        "^(((class[ \t]+)?(procedure|function)|constructor|destructor|interface|implementation|initialization|finalization)[ \t]*.*)$\n^(.*=[ \t]*(class|record).*)$",
+    ),
+    (
+        "perl",
+        false,
+        "^package .*\n^sub [[:alnum:]_':]+[ \t]*(\\([^)]*\\)[ \t]*)?(:[^;#]*)?(\\{[ \t]*)?(#.*)?$\n^(BEGIN|END|INIT|CHECK|UNITCHECK|AUTOLOAD|DESTROY)[ \t]*(\\{[ \t]*)?(#.*)?$\n^=head[0-9] .*",
+    ),
+    (
+        "php",
+        false,
+        "^[\t ]*(((public|protected|private|static|abstract|final)[\t ]+)*function.*)$\n^[\t ]*((((final|abstract)[\t ]+)?class|enum|interface|trait).*)$",
+    ),
+    ("python", false, "^[ \t]*((class|(async[ \t]+)?def)[ \t].*)$"),
+    ("ruby", false, "^[ \t]*((class|module|def)[ \t].*)$"),
+    (
+        "rust",
+        false,
+        "^[\t ]*((pub(\\([^\\)]+\\))?[\t ]+)?((async|const|unsafe|extern([\t ]+\"[^\"]+\"))[\t ]+)?(struct|enum|union|mod|trait|fn|impl|macro_rules!)[< \t]+[^;]*)$",
+    ),
+    (
+        "scheme",
+        false,
+        "^[\t ]*(\\(((define|def(struct|syntax|class|method|rules|record|proto|alias)?)[-*/ \t]|(library|module|struct|class)[*+ \t]).*)$",
+    ),
+    ("tex", false, "^(\\\\((sub)*section|chapter|part)\\*{0,1}\\{.*)$"),
+];
+
+/// Instantiation
+impl Pattern {
+    /// Create a pattern from the value of `diff.<driver>.xfuncname`, which holds POSIX extended regular expressions.
+    pub fn extended(value: impl Into<BString>) -> Self {
+        Pattern {
+            value: value.into(),
+            extended: true,
+            ignore_case: false,
+        }
+    }
+
+    /// Create a pattern from the value of `diff.<driver>.funcname`, which holds POSIX basic regular expressions.
+    pub fn basic(value: impl Into<BString>) -> Self {
+        Pattern {
+            value: value.into(),
+            extended: false,
+            ignore_case: false,
+        }
+    }
+
+    /// Return the pattern of the diff driver called `name` that is built into `git`, like `rust`, `cpp` or `python`,
+    /// or `None` if there is no such driver.
+    pub fn builtin(name: &BStr) -> Option<Self> {
+        BUILTIN
+            .iter()
+            .find(|(builtin, _, _)| name == *builtin)
+            .map(|(_, ignore_case, value)| Pattern {
+                value: (*value).into(),
+                extended: true,
+                ignore_case: *ignore_case,
+            })
+    }
+
+    /// Return the names of all diff drivers that are built into `git` and have a pattern.
+    pub fn builtin_names() -> impl Iterator<Item = &'static str> {
+        BUILTIN.iter().map(|(name, _, _)| *name)
+    }
+}
+
+///
+pub mod matcher {
+    use bstr::BString;
+
+    /// The error returned by [`Matcher::new()`](super::Matcher::new()).
+    #[derive(Debug, thiserror::Error)]
+    #[error("The function name pattern {pattern:?} could not be compiled")]
+    pub struct Error {
+        /// The expression that failed to compile.
+        pub pattern: BString,
+        /// The reason for the failure.
+        pub source: regex::Error,
+    }
+}
+
+/// Find lines that start a function and obtain the function name from them.
+///
+/// The [default](Matcher::default()) considers all lines that start with a letter, `_` or `$` a function line,
+/// which is what `git` does if there is no [`Pattern`].
+#[derive(Default, Debug, Clone)]
+pub struct Matcher {
+    /// The compiled expressions along with `true` if they are negated, or `None` to use the default.
+    regexes: Option<Vec<(regex::bytes::Regex, bool)>>,
+}
+
+/// The maximum length of a function name in bytes, as `git` truncates it to this length in hunk headers.
+const MAX_NAME_LEN: usize = 80;
+
+/// Instantiation
+impl Matcher {
+    /// Compile all regular expressions in `pattern`, accepting the same syntax as `git`.
+    pub fn new(pattern: &Pattern) -> Result<Self, matcher::Error> {
+        let regexes = pattern
+            .value
+            .split_str("\n")
+            .map(|line| {
+                let (line, negated) = match line.strip_prefix(b"!") {
+                    Some(line) => (line, true),
+                    None => (line, false),
+                };
+                regex::bytes::RegexBuilder::new(&to_rust_syntax(line, pattern.extended))
+                    .unicode(false)
+                    .case_insensitive(pattern.ignore_case)
+                    .build()
+                    .map(|regex| (regex, negated))
+                    .map_err(|source| matcher::Error {
+                        pattern: line.into(),
+                        source,
+                    })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Matcher { regexes: Some(regexes) })
+    }
+}
+
+/// Access
+impl Matcher {
+    /// Return the name of the function that `line` starts, or `None` if it doesn't start a function.
+    ///
+    /// Like in `git`, the name is truncated to 80 bytes, and trailing whitespace is removed, so it may be empty.
+    pub fn function_name<'a>(&self, line: &'a [u8]) -> Option<&'a [u8]> {
+        let line = line
+            .strip_suffix(b"\n")
+            .map_or(line, |line| line.strip_suffix(b"\r").unwrap_or(line));
+        let name = match &self.regexes {
+            None => line
+                .first()
+                .is_some_and(|b| b.is_ascii_alphabetic() || *b == b'_' || *b == b'$')
+                .then_some(line)?,
+            Some(regexes) => regexes.iter().find_map(|(regex, negated)| {
+                let captures = regex.captures(line)?;
+                Some((!negated).then(|| {
+                    let name = captures.get(1).or_else(|| captures.get(0)).expect("matched");
+                    name.as_bytes()
+                }))
+            })??,
+        };
+        let name = &name[..name.len().min(MAX_NAME_LEN)];
+        let end = name
+            .iter()
+            .rposition(|b| !matches!(b, b' ' | b'\t' | b'\n' | b'\r'))
+            .map_or(0, |pos| pos + 1);
+        Some(&name[..end])
+    }
+
+    /// Return `true` if `line` starts a function.
+    pub fn is_function_line(&self, line: &[u8]) -> bool {
+        self.function_name(line).is_some()
+    }
+
+    /// Return the first line that starts a function when looking at the lines from `start` towards `limit`, which is
+    /// excluded, along with the name of the function.
+    /// `line(idx)` returns the line at `idx`, where `idx` is smaller than `num_lines`.
+    ///
+    /// This is a port of `get_func_line()` in `git`.
+    pub(crate) fn find_function_line<'a>(
+        &self,
+        line: impl Fn(usize) -> &'a [u8],
+        num_lines: usize,
+        start: isize,
+        limit: isize,
+    ) -> Option<(usize, &'a [u8])> {
+        let step = if start > limit { -1 } else { 1 };
+        let mut idx = start;
+        while idx != limit && idx >= 0 && (idx as usize) < num_lines {
+            if let Some(name) = self.function_name(line(idx as usize)) {
+                return Some((idx as usize, name));
+            }
+            idx += step;
+        }
+        None
+    }
+}
+
+/// Find the name of the function to show in the header of each hunk, which is the closest line before the hunk that
+/// starts a function, like `git` does.
+///
+/// Hunks must be passed in order, as lines are only searched until the start of the previous hunk.
+pub struct FunctionNames<'a> {
+    matcher: &'a Matcher,
+    before: Vec<&'a [u8]>,
+    /// The line up to which lines were already searched, excluding itself.
+    searched_until: isize,
+    name: &'a [u8],
+}
+
+/// Instantiation
+impl<'a> FunctionNames<'a> {
+    /// Create a new instance to find function lines using `matcher` in the old version of `input`.
+    pub fn new<T>(matcher: &'a Matcher, input: &'a InternedInput<T>) -> Self
+    where
+        T: AsRef<[u8]> + std::hash::Hash + Eq,
+    {
+        FunctionNames {
+            matcher,
+            before: input
+                .before
+                .iter()
+                .map(|token| input.interner[*token].as_ref())
+                .collect(),
+            searched_until: -1,
+            name: &[],
+        }
+    }
+}
+
+/// Access
+impl<'a> FunctionNames<'a> {
+    /// Return the function name to show in the header of the hunk described by `header`, which is empty if no line
+    /// before the hunk starts a function.
+    pub fn for_hunk(&mut self, header: &HunkHeader) -> &'a [u8] {
+        let start = header.before_hunk_start as isize - 2;
+        if let Some((_, name)) =
+            self.matcher
+                .find_function_line(|idx| self.before[idx], self.before.len(), start, self.searched_until)
+        {
+            self.name = name;
+        }
+        self.searched_until = start;
+        self.name
+    }
+}
+
+/// Turn the POSIX regular expression `pattern` into one with the syntax of the `regex` crate, as extended expression if
+/// `extended` is `true`, or as basic one otherwise, with the GNU extensions that `git` usually has available.
+fn to_rust_syntax(pattern: &[u8], extended: bool) -> String {
+    fn push_byte(out: &mut String, b: u8) {
+        if b.is_ascii() {
+            out.push(b as char);
+        } else {
+            write!(out, "\\x{b:02x}").expect("writing to a String never fails");
+        }
+    }
+    fn is_interval(rest: &[u8]) -> bool {
+        let Some(end) = rest.find_byte(b'}') else {
+            return false;
+        };
+        let interval = &rest[..end];
+        !interval.is_empty()
+            && interval[0].is_ascii_digit()
+            && interval.iter().filter(|b| **b == b',').count() <= 1
+            && interval.iter().all(|b| b.is_ascii_digit() || *b == b',')
+    }
+
+    let mut out = String::with_capacity(pattern.len() * 2);
+    let mut idx = 0;
+    while idx < pattern.len() {
+        let b = pattern[idx];
+        idx += 1;
+        match b {
+            b'\\' if idx < pattern.len() => {
+                let escaped = pattern[idx];
+                idx += 1;
+                match escaped {
+                    b'(' | b')' | b'{' | b'}' | b'|' | b'+' | b'?' if !extended => out.push(escaped as char),
+                    b'`' => out.push_str("\\A"),
+                    b'\'' => out.push_str("\\z"),
+                    b if b.is_ascii_alphanumeric() || b.is_ascii_punctuation() => {
+                        out.push('\\');
+                        out.push(b as char);
+                    }
+                    b => push_byte(&mut out, b),
+                }
+            }
+            b'(' | b')' | b'|' | b'+' | b'?' if !extended => {
+                out.push('\\');
+                out.push(b as char);
+            }
+            b'{' if !extended || !is_interval(&pattern[idx..]) => out.push_str("\\{"),
+            b'}' if !extended => out.push_str("\\}"),
+            b'[' => {
+                out.push('[');
+                if pattern.get(idx) == Some(&b'^') {
+                    out.push('^');
+                    idx += 1;
+                }
+                if pattern.get(idx) == Some(&b']') {
+                    out.push_str("\\]");
+                    idx += 1;
+                }
+                while idx < pattern.len() {
+                    let b = pattern[idx];
+                    idx += 1;
+                    match b {
+                        b']' => break,
+                        b'[' if pattern.get(idx) == Some(&b':') => {
+                            let end = pattern[idx..].find(":]").map_or(pattern.len(), |end| idx + end + 2);
+                            out.push('[');
+                            for b in &pattern[idx..end] {
+                                push_byte(&mut out, *b);
+                            }
+                            idx = end;
+                        }
+                        b'[' | b'\\' | b'&' | b'~' => {
+                            out.push('\\');
+                            out.push(b as char);
+                        }
+                        b'-' if pattern.get(idx) == Some(&b'-') => out.push_str("\\-"),
+                        b => push_byte(&mut out, b),
+                    }
+                }
+                out.push(']');
+            }
+            b => push_byte(&mut out, b),
+        }
+    }
+    out
+}
@@ -1,6 +1,6 @@
 //! For using text diffs, please have a look at the [`imara-diff` documentation](https://docs.rs/imara-diff),
 //! maintained by [Pascal Kuthe](https://github.com/pascalkuthe).
-use std::{collections::HashMap, path::PathBuf};
+use std::{borrow::Cow, collections::HashMap, path::PathBuf};
 
 use bstr::BString;
 pub use imara_diff::*;
@@ -9,6 +9,10 @@ pub use imara_diff::*;
 pub mod unified_diff;
 pub use unified_diff::impls::UnifiedDiff;
 
+pub mod word_diff;
+
+pub mod funcname;
+
 /// Compute a diff with Git's slider heuristics to produce more intuitive diffs.
 ///
 /// This function uses [`Diff`] from `imara-diff`
@@ -37,6 +41,94 @@ pub fn diff_with_slider_heuristics<T: AsRef<[u8]>>(algorithm: Algorithm, input:
     diff
 }
 
+/// Like [`diff_with_slider_heuristics()`], but lines are compared according to `whitespace`, so lines that
+/// only differ in ignored whitespace are considered unchanged.
+///
+/// Note that [`Whitespace::ignore_blank_lines`] isn't used here as it only affects which changes are shown,
+/// see [`UnifiedDiff::with_whitespace()`].
+///
+/// # Examples
+///
+/// ```
+/// use gix_diff::blob::{diff_ignoring_whitespace, Algorithm, InternedInput, Whitespace};
+///
+/// let before = "fn foo() {\n    let x = 1;\n}\n";
+/// let after = "fn foo() {\n  let x = 1; \n}\n";
+///
+/// let input = InternedInput::new(before, after);
+/// let whitespace = Whitespace {
+///     ignore_space_change: true,
+///     ..Default::default()
+/// };
+/// assert_eq!(diff_ignoring_whitespace(Algorithm::Histogram, &input, whitespace).count_additions(), 0);
+/// ```
+pub fn diff_ignoring_whitespace<T: AsRef<[u8]>>(
+    algorithm: Algorithm,
+    input: &InternedInput<T>,
+    whitespace: Whitespace,
+) -> Diff {
+    if !whitespace.affects_comparison() {
+        return diff_with_slider_heuristics(algorithm, input);
+    }
+    diff_with_equivalent_tokens(input, whitespace, |diff, before, after| {
+        diff.compute_with(algorithm, before, after, input.interner.num_tokens());
+    })
+}
+
+/// Like [`diff_ignoring_whitespace()`], but the [patience](Algorithm::Patience) algorithm is used to keep lines
+/// that start with any of the `anchors` unchanged if they occur exactly once in both versions, similar to
+/// `git diff --anchored=<text>`.
+///
+/// # Examples
+///
+/// ```
+/// use gix_diff::blob::{diff_with_anchors, InternedInput};
+///
+/// let before = "a\nb\nc\n";
+/// let after = "c\na\nb\n";
+///
+/// let input = InternedInput::new(before, after);
+/// let diff = diff_with_anchors(&input, Default::default(), &["c".into()]);
+/// assert_eq!(diff.count_removals(), 2, "`a` and `b` move around the anchored `c`");
+/// ```
+pub fn diff_with_anchors<T: AsRef<[u8]>>(
+    input: &InternedInput<T>,
+    whitespace: Whitespace,
+    anchors: &[BString],
+) -> Diff {
+    diff_with_equivalent_tokens(input, whitespace, |diff, before, after| {
+        diff.compute_anchored_with(before, after, |token| {
+            let line = input.interner[token].as_ref();
+            anchors.iter().any(|anchor| line.starts_with(anchor))
+        });
+    })
+}
+
+/// Compute a diff with `compute` on the tokens of `input` after mapping them to equivalent tokens according to
+/// `whitespace`, and apply slider heuristics.
+fn diff_with_equivalent_tokens<T: AsRef<[u8]>>(
+    input: &InternedInput<T>,
+    whitespace: Whitespace,
+    compute: impl FnOnce(&mut Diff, &[Token], &[Token]),
+) -> Diff {
+    let (before, after): (Cow<'_, [Token]>, Cow<'_, [Token]>) = if whitespace.affects_comparison() {
+        let equivalent = whitespace.equivalent_tokens(&input.interner);
+        let map = |tokens: &[Token]| tokens.iter().map(|token| equivalent[token.0 as usize]).collect();
+        (map(&input.before), map(&input.after))
+    } else {
+        (input.before.as_slice().into(), input.after.as_slice().into())
+    };
+
+    let mut diff = Diff::default();
+    compute(&mut diff, &before, &after);
+    diff.postprocess_with(
+        &before,
+        &after,
+        IndentHeuristic::new(|token| IndentLevel::for_ascii_line(input.interner[token].as_ref().iter().copied(), 8)),
+    );
+    diff
+}
+
 ///
 pub mod pipeline;
 
@@ -60,6 +152,30 @@ pub struct DiffLineStats {
     pub similarity: f32,
 }
 
+/// Settings to decide which differences in whitespace to ignore when comparing lines,
+/// similar to the respective options of `git diff`.
+///
+/// Note that all settings that ignore whitespace at the end of a line also ignore the newline itself,
+/// so a missing newline at the end of a file isn't considered a change either.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Whitespace {
+    /// Ignore changes in the amount of whitespace, so all runs of whitespace compare equal to a single space,
+    /// and whitespace at the end of a line is ignored, like `--ignore-space-change`.
+    pub ignore_space_change: bool,
+    /// Ignore all whitespace when comparing lines, even if only one of them has whitespace in a particular position,
+    /// like `--ignore-all-space`.
+    pub ignore_all_space: bool,
+    /// Ignore whitespace at the end of a line, like `--ignore-space-at-eol`.
+    pub ignore_space_at_eol: bool,
+    /// Ignore a carriage-return at the end of a line, like `--ignore-cr-at-eol`.
+    pub ignore_cr_at_eol: bool,
+    /// Don't show changes whose lines are all blank, like `--ignore-blank-lines`.
+    ///
+    /// If any of the other settings is enabled, lines that only consist of whitespace are blank, otherwise only empty lines are.
+    /// Such changes are still shown if they are close enough to other changes.
+    pub ignore_blank_lines: bool,
+}
+
 /// A way to classify a resource suitable for diffing.
 #[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
 pub enum ResourceKind {
@@ -94,6 +210,15 @@ pub struct Driver {
     /// If `Some(false)`, it won't be considered binary, and the its data will not be sampled for the null-byte either.
     /// Leaving it to `None` means binary detection is automatic, and is based on the presence of the `0` byte in the first 8kB of the buffer.
     pub is_binary: Option<bool>,
+    /// The regular expression to match words with when showing changed words, like `diff.<driver>.wordRegex`.
+    ///
+    /// Use [`word_diff::Tokenizer::from_regex()`] to compile it.
+    pub word_regex: Option<BString>,
+    /// The regular expressions to find lines that start a function, like `diff.<driver>.xfuncname` or `diff.<driver>.funcname`,
+    /// to show them in hunk headers.
+    ///
+    /// Use [`funcname::Matcher::new()`] to compile it.
+    pub funcname: Option<funcname::Pattern>,
 }
 
 /// A conversion pipeline to take an object or path from what's stored in `git` to what can be diffed, while
@@ -115,6 +240,8 @@ pub struct Pipeline {
     pub options: pipeline::Options,
     /// Drivers to help customize the conversion behaviour depending on the location of items.
     drivers: Vec<Driver>,
+    /// The amount of drivers that were configured, which come before the built-in ones in `drivers`.
+    num_configured_drivers: usize,
     /// Pre-configured attributes to obtain additional diff-related information.
     attrs: gix_filter::attributes::search::Outcome,
     /// A buffer to manipulate paths
@@ -153,6 +280,8 @@ pub struct Platform {
     free_list: Vec<Vec<u8>>,
 }
 
+mod whitespace;
+
 mod impls {
     use crate::blob::ResourceKind;
 
@@ -173,17 +173,39 @@ impl Pipeline {
     /// Create a new instance of a pipeline which produces blobs suitable for diffing. `roots` allow to read worktree files directly, otherwise
     /// `worktree_filter` is used to transform object database data directly. `drivers` further configure individual paths.
     /// `options` are used to further configure the way we act..
+    ///
+    /// Like in `git`, built-in drivers like `rust` are available even if they aren't configured, and configured drivers
+    /// with a built-in name use its [`funcname`](super::Driver::funcname) unless they set their own.
     pub fn new(
         roots: WorktreeRoots,
         worktree_filter: gix_filter::Pipeline,
         mut drivers: Vec<super::Driver>,
         options: Options,
     ) -> Self {
+        for driver in &mut drivers {
+            if driver.funcname.is_none() {
+                driver.funcname = super::funcname::Pattern::builtin(driver.name.as_ref());
+            }
+        }
         drivers.sort_by(|a, b| a.name.cmp(&b.name));
+        let num_configured_drivers = drivers.len();
+        for name in super::funcname::Pattern::builtin_names() {
+            if drivers[..num_configured_drivers]
+                .binary_search_by(|driver| driver.name.as_bstr().cmp(name.into()))
+                .is_err()
+            {
+                drivers.push(super::Driver {
+                    name: name.into(),
+                    funcname: super::funcname::Pattern::builtin(name.into()),
+                    ..Default::default()
+                });
+            }
+        }
         Pipeline {
             roots,
             worktree_filter,
             drivers,
+            num_configured_drivers,
             options,
             attrs: {
                 let mut out = gix_filter::attributes::search::Outcome::default();
@@ -197,12 +219,20 @@ impl Pipeline {
 
 /// Access
 impl Pipeline {
-    /// Return all drivers that this instance was initialized with.
+    /// Return all drivers that this instance was initialized with, followed by the drivers that are built into `git`
+    /// like `rust` or `python` unless one with the same name was configured.
     ///
-    /// They are sorted by [`name`](Driver::name) to support binary searches.
+    /// Both parts are sorted by [`name`](Driver::name) to support binary searches.
     pub fn drivers(&self) -> &[super::Driver] {
         &self.drivers
     }
+
+    /// Return the index of the driver called `name`, preferring configured drivers over built-in ones.
+    fn driver_index(&self, name: &BStr) -> Option<usize> {
+        let (configured, builtin) = self.drivers.split_at(self.num_configured_drivers);
+        let find = |drivers: &[super::Driver]| drivers.binary_search_by(|d| d.name.as_bstr().cmp(name)).ok();
+        find(configured).or_else(|| find(builtin).map(|idx| configured.len() + idx))
+    }
 }
 
 /// Conversion
@@ -255,11 +285,7 @@ impl Pipeline {
         out.clear();
         attributes(rela_path, &mut self.attrs);
         let attr = self.attrs.iter_selected().next().expect("pre-initialized with 'diff'");
-        let driver_index = attr
-            .assignment
-            .state
-            .as_bstr()
-            .and_then(|name| self.drivers.binary_search_by(|d| d.name.as_bstr().cmp(name)).ok());
+        let driver_index = attr.assignment.state.as_bstr().and_then(|name| self.driver_index(name));
         let driver = driver_index.map(|idx| &self.drivers[idx]);
         let mut is_binary = if let Some(driver) = driver {
             driver
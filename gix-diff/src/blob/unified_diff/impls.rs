@@ -1,9 +1,10 @@
 use bstr::{BString, ByteSlice, ByteVec};
-use imara_diff::{Diff, InternedInput, Interner, Token};
+use imara_diff::{Diff, Hunk, InternedInput, Interner, Token};
 use std::fmt::Write;
 use std::{hash::Hash, ops::Range};
 
 use super::{ConsumeBinaryHunk, ConsumeBinaryHunkDelegate, ConsumeHunk, ContextSize, DiffLineKind, HunkHeader};
+use crate::blob::{Whitespace, funcname::Matcher};
 
 /// A helper that renders a [`Diff`] as unified diff output.
 /// It can be used to create a textual diff in the format typically output by `git`
@@ -29,9 +30,17 @@ where
     // An index into `before` and the context line to print next,
     // or `None` if this value was never computed to be the correct starting point for an accumulated hunk.
     ctx_pos: Option<u32>,
+    /// The index into `after` that corresponds to `ctx_pos`, as context lines are printed as they are in `after`.
+    after_ctx_pos: u32,
 
     /// Symmetrical context before and after the changed hunk.
     ctx_size: u32,
+    /// The amount of lines between the context of two changes up to which they are kept in the same hunk.
+    inter_hunk_ctx_size: u32,
+    /// Used to determine which lines are blank, if changes of blank lines should be skipped.
+    whitespace: Whitespace,
+    /// If set, hunks are extended to the whole functions they change, with function lines found by this matcher.
+    function_context: Option<&'a Matcher>,
 
     buffer: Vec<(DiffLineKind, &'a [u8])>,
 
@@ -61,8 +70,12 @@ where
             after_hunk_len: 0,
             after_hunk_start: 0,
             ctx_pos: None,
+            after_ctx_pos: 0,
 
             ctx_size: context_size.symmetrical,
+            inter_hunk_ctx_size: context_size.inter_hunk,
+            whitespace: Whitespace::default(),
+            function_context: None,
 
             buffer: Vec::with_capacity(8),
             delegate: consume_hunk,
@@ -71,6 +84,22 @@ where
         }
     }
 
+    /// Skip changes that only consist of blank lines if [`Whitespace::ignore_blank_lines`] is set in `whitespace`,
+    /// unless they are close to other changes, like `git` does.
+    /// Other settings in `whitespace` only affect which lines are considered blank, and should match the ones
+    /// used to compute the diff.
+    pub fn with_whitespace(mut self, whitespace: Whitespace) -> Self {
+        self.whitespace = whitespace;
+        self
+    }
+
+    /// Extend each hunk to show the whole functions that it changes, with the lines that start a function found by `matcher`,
+    /// similar to `git diff --function-context`.
+    pub fn with_function_context(mut self, matcher: &'a Matcher) -> Self {
+        self.function_context = Some(matcher);
+        self
+    }
+
     fn print_tokens(&mut self, tokens: &[Token], line_type: DiffLineKind) {
         for &token in tokens {
             let content = self.interner[token].as_ref();
@@ -85,7 +114,7 @@ where
 
         let ctx_pos = self.ctx_pos.expect("has been set if we started a hunk");
         let end = (ctx_pos + self.ctx_size).min(self.before.len() as u32);
-        self.print_context_and_update_pos(ctx_pos..end, end);
+        self.print_context_and_update_pos(ctx_pos..end, self.after_ctx_pos, end);
 
         let hunk_start = self.before_hunk_start + 1;
         let hunk_end = self.after_hunk_start + 1;
@@ -103,13 +132,14 @@ where
         Ok(())
     }
 
-    fn print_context_and_update_pos(&mut self, print: Range<u32>, move_to: u32) {
+    /// Print the context lines in `print`, a range in `before`, as they are in `after` starting at `after_start`.
+    fn print_context_and_update_pos(&mut self, print: Range<u32>, after_start: u32, move_to: u32) {
+        let len = print.end - print.start;
         self.print_tokens(
-            &self.before[print.start as usize..print.end as usize],
+            &self.after[after_start as usize..(after_start + len) as usize],
             DiffLineKind::Context,
         );
 
-        let len = print.end - print.start;
         self.ctx_pos = Some(move_to);
         self.before_hunk_len += len;
         self.after_hunk_len += len;
@@ -131,7 +161,7 @@ where
         }
         let start_next_hunk = self
             .ctx_pos
-            .is_some_and(|ctx_pos| before.start - ctx_pos > 2 * self.ctx_size);
+            .is_some_and(|ctx_pos| before.start - ctx_pos > 2 * self.ctx_size + self.inter_hunk_ctx_size);
         if start_next_hunk {
             if let Err(err) = self.flush_accumulated_hunk() {
                 self.err = Some(err);
@@ -152,7 +182,12 @@ where
             }
             Some(pos) => pos,
         };
-        self.print_context_and_update_pos(ctx_pos..before.start, before.end);
+        self.print_context_and_update_pos(
+            ctx_pos..before.start,
+            after.start - (before.start - ctx_pos),
+            before.end,
+        );
+        self.after_ctx_pos = after.end;
         self.before_hunk_len += before.end - before.start;
         self.after_hunk_len += after.end - after.start;
 
@@ -165,11 +200,29 @@ where
 
     /// Consume all hunks from `diff` and return the delegate's final output.
     pub fn consume(mut self) -> std::io::Result<D::Out> {
-        for hunk in self.diff.hunks() {
-            self.process_change(hunk.before, hunk.after);
-        }
-        if let Err(err) = self.flush_accumulated_hunk() {
-            self.err = Some(err);
+        if let Some(matcher) = self.function_context {
+            let changes = self.changes_with_ignore_flag();
+            self.consume_with_function_context(&changes, matcher)?;
+        } else if self.whitespace.ignore_blank_lines {
+            let changes = self.changes_with_ignore_flag();
+            let mut start = 0;
+            while let Some(group) = self.next_group_with_changes_to_show(&changes, start) {
+                start = group.end;
+                for (hunk, _ignore) in &changes[group] {
+                    self.process_change(hunk.before.clone(), hunk.after.clone());
+                }
+                if let Err(err) = self.flush_accumulated_hunk() {
+                    self.err = Some(err);
+                }
+                self.ctx_pos = None;
+            }
+        } else {
+            for hunk in self.diff.hunks() {
+                self.process_change(hunk.before, hunk.after);
+            }
+            if let Err(err) = self.flush_accumulated_hunk() {
+                self.err = Some(err);
+            }
         }
         if let Some(err) = self.err {
             return Err(err);
@@ -178,6 +231,212 @@ where
     }
 }
 
+impl<T, D> UnifiedDiff<'_, T, D>
+where
+    T: Hash + Eq + AsRef<[u8]>,
+    D: ConsumeHunk,
+{
+    /// Return all changes of `diff`, each paired with `true` if it is to be ignored as it only changes blank lines.
+    fn changes_with_ignore_flag(&self) -> Vec<(Hunk, bool)> {
+        self.diff
+            .hunks()
+            .map(|hunk| {
+                let is_blank = |tokens: &[Token], range: &Range<u32>| {
+                    tokens[range.start as usize..range.end as usize]
+                        .iter()
+                        .all(|token| self.whitespace.is_blank(self.interner[*token].as_ref()))
+                };
+                let ignore = self.whitespace.ignore_blank_lines
+                    && is_blank(self.before, &hunk.before)
+                    && is_blank(self.after, &hunk.after);
+                (hunk, ignore)
+            })
+            .collect()
+    }
+
+    /// Pass all hunks to the delegate after extending them to the functions they change, using `matcher` to find
+    /// the lines that start a function.
+    ///
+    /// This is a port of `xdl_emit_diff()` in `git` with `XDL_EMIT_FUNCCONTEXT` set.
+    fn consume_with_function_context(&mut self, changes: &[(Hunk, bool)], matcher: &Matcher) -> std::io::Result<()> {
+        let (num_before, num_after) = (self.before.len() as isize, self.after.len() as isize);
+        let ctx = self.ctx_size as isize;
+        let before_line = |idx: usize| self.interner[self.before[idx]].as_ref();
+        let after_line = |idx: usize| self.interner[self.after[idx]].as_ref();
+        let is_empty = |idx: isize| {
+            before_line(idx as usize)
+                .iter()
+                .all(|b| matches!(b, b' ' | b'\t' | b'\n' | b'\r'))
+        };
+        let find_function_line = |start: isize, limit: isize| {
+            matcher
+                .find_function_line(before_line, self.before.len(), start, limit)
+                .map(|(idx, _name)| idx as isize)
+        };
+
+        let mut start = 0;
+        let mut hunks = Vec::new();
+        while let Some(group) = self.next_group_with_changes_to_show(changes, start) {
+            let (mut first, mut last) = (group.start, group.end - 1);
+            let mut previous = start;
+
+            let (mut s1, mut s2);
+            loop {
+                let change = &changes[first].0;
+                s1 = (change.before.start as isize - ctx).max(0);
+                s2 = (change.after.start as isize - ctx).max(0);
+
+                let mut i1 = change.before.start as isize;
+                if i1 >= num_before {
+                    // No more context is needed if a whole function was added.
+                    if (change.after.start as usize..self.after.len())
+                        .any(|idx| matcher.is_function_line(after_line(idx)))
+                    {
+                        break;
+                    }
+                    i1 = num_before - 1;
+                }
+
+                let mut fs1 = find_function_line(i1, -1).unwrap_or(-1);
+                while fs1 > 0 && !is_empty(fs1 - 1) && !matcher.is_function_line(before_line(fs1 as usize - 1)) {
+                    fs1 -= 1;
+                }
+                let fs1 = fs1.max(0);
+                if fs1 < s1 {
+                    s2 = (s2 - (s1 - fs1)).max(0);
+                    s1 = fs1;
+
+                    // Show ignored changes after all if the context now reaches them.
+                    while previous != first
+                        && changes[previous].0.before.end as isize <= s1
+                        && changes[previous].0.after.end as isize <= s2
+                    {
+                        previous += 1;
+                    }
+                    if previous != first {
+                        first = previous;
+                        continue;
+                    }
+                }
+                break;
+            }
+
+            let (mut e1, mut e2);
+            loop {
+                let change = &changes[last].0;
+                let (end1, end2) = (change.before.end as isize, change.after.end as isize);
+                let lctx = ctx.min(num_before - end1).min(num_after - end2);
+                e1 = end1 + lctx;
+                e2 = end2 + lctx;
+
+                let mut fe1 = find_function_line(end1, num_before).unwrap_or(-1);
+                while fe1 > 0 && is_empty(fe1 - 1) {
+                    fe1 -= 1;
+                }
+                if fe1 < 0 {
+                    fe1 = num_before;
+                }
+                if fe1 > e1 {
+                    e2 = (e2 + (fe1 - e1)).min(num_after);
+                    e1 = fe1;
+                }
+
+                // Include the next change if it overlaps with this hunk, and find the new end.
+                if let Some((next, _)) = changes.get(last + 1) {
+                    let l = (next.before.start as isize).min(num_before - 1);
+                    if l - ctx <= e1 || find_function_line(l, e1).is_none() {
+                        last += 1;
+                        continue;
+                    }
+                }
+                break;
+            }
+
+            hunks.push((first..last + 1, [s1 as u32, e1 as u32, s2 as u32, e2 as u32]));
+            start = last + 1;
+        }
+
+        for (group, [s1, e1, s2, e2]) in hunks {
+            let mut after_pos = s2;
+            for (change, _ignore) in &changes[group] {
+                self.print_tokens(
+                    &self.after[after_pos as usize..change.after.start as usize],
+                    DiffLineKind::Context,
+                );
+                self.print_tokens(
+                    &self.before[change.before.start as usize..change.before.end as usize],
+                    DiffLineKind::Remove,
+                );
+                self.print_tokens(
+                    &self.after[change.after.start as usize..change.after.end as usize],
+                    DiffLineKind::Add,
+                );
+                after_pos = change.after.end;
+            }
+            self.print_tokens(&self.after[after_pos as usize..e2 as usize], DiffLineKind::Context);
+
+            let header = HunkHeader {
+                before_hunk_start: s1 + 1,
+                before_hunk_len: e1 - s1,
+                after_hunk_start: s2 + 1,
+                after_hunk_len: e2 - s2,
+            };
+            self.delegate.consume_hunk(header, &self.buffer)?;
+            self.buffer.clear();
+        }
+        Ok(())
+    }
+
+    /// Return the range of `changes` starting at or after `start` that are to be shown in the same hunk, skipping
+    /// changes that are ignored as they are too far away from changes that aren't.
+    /// Each change is paired with `true` if it is to be ignored.
+    ///
+    /// This is a port of `xdl_get_hunk()` in `git`.
+    fn next_group_with_changes_to_show(&self, changes: &[(Hunk, bool)], mut start: usize) -> Option<Range<usize>> {
+        let max_common = 2 * self.ctx_size + self.inter_hunk_ctx_size;
+        let max_ignorable = self.ctx_size;
+
+        let mut idx = start;
+        while let Some((hunk, true)) = changes.get(idx) {
+            idx += 1;
+            if changes
+                .get(idx)
+                .is_none_or(|(next, _)| next.before.start - hunk.before.end >= max_ignorable)
+            {
+                start = idx;
+            }
+        }
+        if start >= changes.len() {
+            return None;
+        }
+
+        let mut last = start;
+        let mut ignored = 0;
+        for idx in start + 1..changes.len() {
+            let (prev, _) = &changes[idx - 1];
+            let (hunk, ignore) = &changes[idx];
+            let distance = hunk.before.start - prev.before.end;
+            if distance > max_common {
+                break;
+            }
+            if distance < max_ignorable && (!ignore || last == idx - 1) {
+                last = idx;
+                ignored = 0;
+            } else if distance < max_ignorable && *ignore {
+                ignored += hunk.after.len() as u32;
+            } else if last != idx - 1 && hunk.before.start + ignored - changes[last].0.before.end > max_common {
+                break;
+            } else if !ignore {
+                last = idx;
+                ignored = 0;
+            } else {
+                ignored += hunk.after.len() as u32;
+            }
+        }
+        Some(start..last + 1)
+    }
+}
+
 /// An implementation that fails if the input isn't UTF-8.
 impl<D> ConsumeHunk for ConsumeBinaryHunk<'_, D>
 where
@@ -265,6 +524,34 @@ impl DiffLineKind {
     }
 }
 
+impl HunkHeader {
+    /// Return a value to display this header like `git` does, which omits lengths of `1` and
+    /// starts empty ranges at the line before them.
+    pub(crate) fn display_like_git(&self) -> impl std::fmt::Display + '_ {
+        GitHunkHeader(self)
+    }
+}
+
+struct GitHunkHeader<'a>(&'a HunkHeader);
+
+impl std::fmt::Display for GitHunkHeader<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fn range(f: &mut std::fmt::Formatter<'_>, start: u32, len: u32) -> std::fmt::Result {
+            match len {
+                0 => write!(f, "{},0", start - 1),
+                1 => write!(f, "{start}"),
+                len => write!(f, "{start},{len}"),
+            }
+        }
+        let header = self.0;
+        f.write_str("@@ -")?;
+        range(f, header.before_hunk_start, header.before_hunk_len)?;
+        f.write_str(" +")?;
+        range(f, header.after_hunk_start, header.after_hunk_len)?;
+        f.write_str(" @@")
+    }
+}
+
 impl std::fmt::Display for HunkHeader {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
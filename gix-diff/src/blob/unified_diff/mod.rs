@@ -10,6 +10,8 @@
 pub struct ContextSize {
     /// Defines the size of the context printed before and after each change.
     symmetrical: u32,
+    /// The amount of lines between the context of two changes up to which they are shown in the same hunk.
+    inter_hunk: u32,
 }
 
 impl Default for ContextSize {
@@ -22,7 +24,17 @@ impl Default for ContextSize {
 impl ContextSize {
     /// Create a symmetrical context with `n` lines before and after a changed hunk.
     pub fn symmetrical(n: u32) -> Self {
-        ContextSize { symmetrical: n }
+        ContextSize {
+            symmetrical: n,
+            inter_hunk: 0,
+        }
+    }
+
+    /// Also show changes in the same hunk if there are no more than `n` lines between their context,
+    /// similar to the `--inter-hunk-context` option or `diff.interHunkContext` in git.
+    pub fn with_inter_hunk_lines(mut self, n: u32) -> Self {
+        self.inter_hunk = n;
+        self
     }
 }
 
@@ -0,0 +1,100 @@
+use std::hash::{Hash, Hasher};
+
+use imara_diff::{Interner, Token};
+
+use crate::blob::Whitespace;
+
+impl Whitespace {
+    /// Return `true` if lines aren't compared by their exact content anymore.
+    pub fn affects_comparison(&self) -> bool {
+        self.ignore_space_change || self.ignore_all_space || self.ignore_space_at_eol || self.ignore_cr_at_eol
+    }
+
+    /// Return `true` if `line` is blank, which is the case if it consists only of whitespace if any whitespace
+    /// is [ignored](Self::affects_comparison()), or if it's empty otherwise.
+    pub fn is_blank(&self, line: &[u8]) -> bool {
+        if self.affects_comparison() {
+            line.iter().copied().all(is_space)
+        } else {
+            line.is_empty() || line == b"\n"
+        }
+    }
+
+    /// Return `true` if lines `a` and `b` are equal when ignoring whitespace according to our settings.
+    pub fn lines_equal(&self, a: &[u8], b: &[u8]) -> bool {
+        Line::new(a, *self) == Line::new(b, *self)
+    }
+
+    /// Return one token for each token in `interner`, which is the first token whose line is equal to it
+    /// when ignoring whitespace according to our settings.
+    ///
+    /// See [`Interner::equivalent_tokens()`] for how to use it.
+    pub fn equivalent_tokens<T: AsRef<[u8]>>(&self, interner: &Interner<T>) -> Vec<Token> {
+        interner.equivalent_tokens(|line| Line::new(line.as_ref(), *self))
+    }
+}
+
+/// A line whose equality and hash ignore whitespace as configured.
+struct Line<'a> {
+    /// The line, with the parts that are always ignored already removed.
+    data: &'a [u8],
+    skip_spaces: bool,
+    collapse_spaces: bool,
+}
+
+impl<'a> Line<'a> {
+    fn new(line: &'a [u8], ws: Whitespace) -> Self {
+        let data = if ws.ignore_all_space || ws.ignore_space_change || ws.ignore_space_at_eol {
+            let end = line.iter().rposition(|b| !is_space(*b)).map_or(0, |pos| pos + 1);
+            &line[..end]
+        } else if ws.ignore_cr_at_eol {
+            let line = line.strip_suffix(b"\n").unwrap_or(line);
+            line.strip_suffix(b"\r").unwrap_or(line)
+        } else {
+            line
+        };
+        Line {
+            data,
+            skip_spaces: ws.ignore_all_space,
+            collapse_spaces: ws.ignore_space_change,
+        }
+    }
+
+    fn bytes(&self) -> impl Iterator<Item = u8> + '_ {
+        let mut prev_is_space = false;
+        self.data.iter().copied().filter_map(move |b| {
+            if !is_space(b) {
+                prev_is_space = false;
+                return Some(b);
+            }
+            let first_space = !prev_is_space;
+            prev_is_space = true;
+            (!self.skip_spaces && (first_space || !self.collapse_spaces)).then_some(if self.collapse_spaces {
+                b' '
+            } else {
+                b
+            })
+        })
+    }
+}
+
+impl PartialEq for Line<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.bytes().eq(other.bytes())
+    }
+}
+
+impl Eq for Line<'_> {}
+
+impl Hash for Line<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for b in self.bytes() {
+            state.write_u8(b);
+        }
+    }
+}
+
+/// Like `isspace()` in C, which is what `git` uses.
+fn is_space(b: u8) -> bool {
+    matches!(b, b' ' | b'\t' | b'\n' | b'\x0b' | b'\x0c' | b'\r')
+}
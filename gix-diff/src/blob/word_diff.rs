@@ -0,0 +1,409 @@
+//! Show which words changed within changed lines, similar to `git diff --word-diff`.
+//!
+//! Lines are diffed as usual, and each run of removed and added lines is split into words
+//! which are then diffed against each other to learn which words changed.
+use std::{fmt::Write, ops::Range};
+
+use bstr::{BStr, ByteSlice};
+use imara_diff::{Algorithm, Diff, InternedInput, TokenSource};
+
+use super::{
+    funcname::FunctionNames,
+    unified_diff::{ConsumeBinaryHunkDelegate, ConsumeHunk, DiffLineKind, HunkHeader},
+};
+
+/// Decide what a word is when computing changes between words.
+#[derive(Default, Debug, Clone)]
+pub enum Tokenizer {
+    /// Words are runs of characters that aren't whitespace, which is what `git` does by default.
+    #[default]
+    NonWhitespace,
+    /// Each character is a word, except for newlines, similar to `--word-diff-regex=.`.
+    Characters,
+    /// Words are the non-overlapping matches of a regular expression, like `diff.wordRegex` or `--word-diff-regex`.
+    /// Anything between matches is ignored when comparing words, and matches end at the first newline.
+    ///
+    /// Use [`Tokenizer::from_regex()`] to create it with the settings that `git` would use.
+    Regex(regex::bytes::Regex),
+}
+
+impl PartialEq for Tokenizer {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Tokenizer::NonWhitespace, Tokenizer::NonWhitespace) | (Tokenizer::Characters, Tokenizer::Characters) => {
+                true
+            }
+            (Tokenizer::Regex(a), Tokenizer::Regex(b)) => a.as_str() == b.as_str(),
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Tokenizer {}
+
+///
+pub mod tokenizer {
+    use bstr::BString;
+
+    /// The error returned by [`Tokenizer::from_regex()`](super::Tokenizer::from_regex()).
+    #[derive(Debug, thiserror::Error)]
+    #[error("The word regex {pattern:?} could not be compiled")]
+    pub struct Error {
+        /// The pattern that failed to compile.
+        pub pattern: BString,
+        /// The reason for the failure.
+        pub source: regex::Error,
+    }
+}
+
+/// Instantiation
+impl Tokenizer {
+    /// Create a tokenizer whose words are matched by the regular expression `pattern`, where `^` and `$` match at line boundaries
+    /// just like in `git`.
+    pub fn from_regex(pattern: &BStr) -> Result<Self, tokenizer::Error> {
+        regex::bytes::RegexBuilder::new(pattern.to_str_lossy().as_ref())
+            .multi_line(true)
+            .build()
+            .map(Tokenizer::Regex)
+            .map_err(|source| tokenizer::Error {
+                pattern: pattern.into(),
+                source,
+            })
+    }
+}
+
+/// Access
+impl Tokenizer {
+    /// Return an iterator over the byte ranges of all words in `text`.
+    pub fn word_ranges<'a>(&'a self, text: &'a [u8]) -> WordRanges<'a> {
+        WordRanges {
+            tokenizer: self,
+            text,
+            pos: 0,
+        }
+    }
+
+    /// Return a [`TokenSource`] of all words in `text`, for use in an [`InternedInput`].
+    pub fn words<'a>(&'a self, text: &'a [u8]) -> Words<'a> {
+        Words(self.word_ranges(text))
+    }
+}
+
+/// An iterator over the byte ranges of the words in a text, as returned by [`Tokenizer::word_ranges()`].
+#[derive(Clone)]
+pub struct WordRanges<'a> {
+    tokenizer: &'a Tokenizer,
+    text: &'a [u8],
+    pos: usize,
+}
+
+impl Iterator for WordRanges<'_> {
+    type Item = Range<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let text = self.text;
+        let rest = &text[self.pos..];
+        let word = match self.tokenizer {
+            Tokenizer::NonWhitespace => {
+                let start = self.pos + rest.iter().position(|b| !is_space(*b))?;
+                let end = text[start..]
+                    .iter()
+                    .position(|b| is_space(*b))
+                    .map_or(text.len(), |len| start + len);
+                start..end
+            }
+            Tokenizer::Characters => {
+                let start = self.pos + rest.iter().position(|b| *b != b'\n')?;
+                let (_, end, _) = text[start..].char_indices().next()?;
+                start..start + end
+            }
+            Tokenizer::Regex(regex) => {
+                let m = regex.find(rest)?;
+                let start = self.pos + m.start();
+                let end = text[start..self.pos + m.end()]
+                    .find_byte(b'\n')
+                    .map_or(self.pos + m.end(), |len| start + len);
+                if start >= end {
+                    // Like `git`, stop at the first empty match.
+                    self.pos = text.len();
+                    return None;
+                }
+                start..end
+            }
+        };
+        self.pos = word.end;
+        Some(word)
+    }
+}
+
+/// A [`TokenSource`] of the words in a text, as returned by [`Tokenizer::words()`].
+#[derive(Clone)]
+pub struct Words<'a>(WordRanges<'a>);
+
+impl<'a> Iterator for Words<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let word = self.0.next()?;
+        Some(&self.0.text[word])
+    }
+}
+
+impl<'a> TokenSource for Words<'a> {
+    type Token = &'a [u8];
+    type Tokenizer = Self;
+
+    fn tokenize(&self) -> Self::Tokenizer {
+        self.clone()
+    }
+
+    fn estimate_tokens(&self) -> u32 {
+        (self.0.text.len() / 4) as u32
+    }
+}
+
+/// Determine how changed words are shown, similar to `git diff --word-diff=<mode>`.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Mode {
+    /// Show removed words as `[-word-]` and added words as `{+word+}`.
+    #[default]
+    Plain,
+    /// Show each removed, added and unchanged part of a line on its own line, prefixed with `-`, `+` or a space respectively,
+    /// and show the end of each line as `~` on its own line.
+    ///
+    /// This format is meant to be parsed.
+    Porcelain,
+    /// Show removed words in red and added words in green using ANSI escape codes, and the hunk header in cyan.
+    Color,
+}
+
+const RESET: &str = "\x1b[m";
+
+impl Mode {
+    /// Return `(color, prefix, suffix)` of words of the given `kind`.
+    fn style(self, kind: DiffLineKind) -> (&'static str, &'static str, &'static str) {
+        match (self, kind) {
+            (Mode::Plain, DiffLineKind::Context) => ("", "", ""),
+            (Mode::Plain, DiffLineKind::Remove) => ("", "[-", "-]"),
+            (Mode::Plain, DiffLineKind::Add) => ("", "{+", "+}"),
+            (Mode::Porcelain, kind) => ("", kind.to_prefix_str(), "\n"),
+            (Mode::Color, DiffLineKind::Context) => ("", "", ""),
+            (Mode::Color, DiffLineKind::Remove) => ("\x1b[31m", "", ""),
+            (Mode::Color, DiffLineKind::Add) => ("\x1b[32m", "", ""),
+        }
+    }
+
+    /// Return what to write for a newline in the text.
+    fn newline(self) -> &'static str {
+        match self {
+            Mode::Plain | Mode::Color => "\n",
+            Mode::Porcelain => "~\n",
+        }
+    }
+}
+
+impl DiffLineKind {
+    fn to_prefix_str(self) -> &'static str {
+        match self {
+            DiffLineKind::Context => " ",
+            DiffLineKind::Add => "+",
+            DiffLineKind::Remove => "-",
+        }
+    }
+}
+
+/// An adapter with [`ConsumeHunk`] implementation which shows the words that changed within each hunk
+/// instead of its changed lines, and passes the result to a delegate just like
+/// [`ConsumeBinaryHunk`](super::unified_diff::ConsumeBinaryHunk) does.
+///
+/// Context lines are shown as they are, and missing newlines at the end of a file are added.
+pub struct ConsumeWordDiffHunk<'a, D> {
+    /// The delegate to receive the header and changed words of each hunk.
+    pub delegate: D,
+
+    tokenizer: &'a Tokenizer,
+    mode: Mode,
+    function_names: Option<FunctionNames<'a>>,
+
+    header_buf: String,
+    hunk_buf: Vec<u8>,
+    removed: Vec<u8>,
+    added: Vec<u8>,
+}
+
+impl<'a, D> ConsumeWordDiffHunk<'a, D>
+where
+    D: ConsumeBinaryHunkDelegate,
+{
+    /// Create a new instance that uses `tokenizer` to split lines into words, and writes each hunk in the given `mode`
+    /// to `delegate`.
+    pub fn new(delegate: D, tokenizer: &'a Tokenizer, mode: Mode) -> Self {
+        ConsumeWordDiffHunk {
+            delegate,
+            tokenizer,
+            mode,
+            function_names: None,
+            header_buf: String::new(),
+            hunk_buf: Vec::with_capacity(128),
+            removed: Vec::new(),
+            added: Vec::new(),
+        }
+    }
+
+    /// Show the name of the function that each hunk is in after its header, as found by `function_names`.
+    ///
+    /// Note that names that aren't valid UTF-8 are shown with replacement characters.
+    pub fn with_function_names(mut self, function_names: FunctionNames<'a>) -> Self {
+        self.function_names = Some(function_names);
+        self
+    }
+}
+
+impl<D> ConsumeHunk for ConsumeWordDiffHunk<'_, D>
+where
+    D: ConsumeBinaryHunkDelegate,
+{
+    type Out = D;
+
+    fn consume_hunk(&mut self, header: HunkHeader, lines: &[(DiffLineKind, &[u8])]) -> std::io::Result<()> {
+        self.header_buf.clear();
+        let (color, reset) = match self.mode {
+            Mode::Color => ("\x1b[36m", RESET),
+            Mode::Plain | Mode::Porcelain => ("", ""),
+        };
+        write!(self.header_buf, "{color}{}{reset}", header.display_like_git()).map_err(std::io::Error::other)?;
+        let function_name = self
+            .function_names
+            .as_mut()
+            .map(|names| names.for_hunk(&header))
+            .unwrap_or_default();
+        if !function_name.is_empty() {
+            write!(self.header_buf, " {reset}{}{reset}", function_name.to_str_lossy())
+                .map_err(std::io::Error::other)?;
+        }
+        self.header_buf.push('\n');
+
+        self.hunk_buf.clear();
+        self.removed.clear();
+        self.added.clear();
+        for &(kind, line) in lines {
+            match kind {
+                DiffLineKind::Remove => push_line(&mut self.removed, line),
+                DiffLineKind::Add => push_line(&mut self.added, line),
+                DiffLineKind::Context => {
+                    write_changed_words(
+                        &mut self.hunk_buf,
+                        self.tokenizer,
+                        self.mode,
+                        &self.removed,
+                        &self.added,
+                    );
+                    self.removed.clear();
+                    self.added.clear();
+                    write_context_line(&mut self.hunk_buf, self.mode, line);
+                }
+            }
+        }
+        write_changed_words(
+            &mut self.hunk_buf,
+            self.tokenizer,
+            self.mode,
+            &self.removed,
+            &self.added,
+        );
+
+        self.delegate
+            .consume_binary_hunk(header, &self.header_buf, &self.hunk_buf)
+    }
+
+    fn finish(self) -> Self::Out {
+        self.delegate
+    }
+}
+
+fn push_line(out: &mut Vec<u8>, line: &[u8]) {
+    out.extend_from_slice(line);
+    if line.last() != Some(&b'\n') {
+        out.push(b'\n');
+    }
+}
+
+fn write_context_line(out: &mut Vec<u8>, mode: Mode, line: &[u8]) {
+    let line = line.strip_suffix(b"\n").unwrap_or(line);
+    let (line, cr) = line.strip_suffix(b"\r").map_or((line, ""), |line| (line, "\r"));
+    if mode == Mode::Porcelain {
+        out.push(b' ');
+    }
+    out.extend_from_slice(line);
+    // Like `git`, don't reset colors on empty lines.
+    if mode == Mode::Color && !line.is_empty() {
+        out.extend_from_slice(RESET.as_bytes());
+    }
+    out.extend_from_slice(cr.as_bytes());
+    out.push(b'\n');
+    if mode == Mode::Porcelain {
+        out.extend_from_slice(mode.newline().as_bytes());
+    }
+}
+
+/// Write the words that changed between the `removed` and `added` lines to `out`, along with all unchanged text of `added`.
+///
+/// This is a port of `diff_words_show()` in `git`.
+fn write_changed_words(out: &mut Vec<u8>, tokenizer: &Tokenizer, mode: Mode, removed: &[u8], added: &[u8]) {
+    if added.is_empty() {
+        write_words(out, mode, DiffLineKind::Remove, removed);
+        return;
+    }
+    let removed_words: Vec<_> = tokenizer.word_ranges(removed).collect();
+    let added_words: Vec<_> = tokenizer.word_ranges(added).collect();
+    let mut input = InternedInput::default();
+    input.update_before(removed_words.iter().map(|word| &removed[word.clone()]));
+    input.update_after(added_words.iter().map(|word| &added[word.clone()]));
+    let mut diff = Diff::compute(Algorithm::Myers, &input);
+    diff.postprocess_no_heuristic(&input);
+
+    // Empty ranges are positioned right after the previous word.
+    let position = |words: &[Range<usize>], range: Range<u32>| -> Range<usize> {
+        if range.is_empty() {
+            let pos = range.start.checked_sub(1).map_or(0, |prev| words[prev as usize].end);
+            pos..pos
+        } else {
+            words[range.start as usize].start..words[range.end as usize - 1].end
+        }
+    };
+    let mut added_pos = 0;
+    for hunk in diff.hunks() {
+        let removed_range = position(&removed_words, hunk.before);
+        let added_range = position(&added_words, hunk.after);
+        write_words(out, mode, DiffLineKind::Context, &added[added_pos..added_range.start]);
+        write_words(out, mode, DiffLineKind::Remove, &removed[removed_range]);
+        write_words(out, mode, DiffLineKind::Add, &added[added_range.clone()]);
+        added_pos = added_range.end;
+    }
+    write_words(out, mode, DiffLineKind::Context, &added[added_pos..]);
+}
+
+/// Write `text` in the style of `kind` for `mode`, with each of its lines styled individually.
+fn write_words(out: &mut Vec<u8>, mode: Mode, kind: DiffLineKind, text: &[u8]) {
+    let (color, prefix, suffix) = mode.style(kind);
+    let mut lines = text.split(|b| *b == b'\n').peekable();
+    while let Some(line) = lines.next() {
+        if !line.is_empty() {
+            out.extend_from_slice(color.as_bytes());
+            out.extend_from_slice(prefix.as_bytes());
+            out.extend_from_slice(line);
+            out.extend_from_slice(suffix.as_bytes());
+            if !color.is_empty() {
+                out.extend_from_slice(RESET.as_bytes());
+            }
+        }
+        if lines.peek().is_some() {
+            out.extend_from_slice(mode.newline().as_bytes());
+        }
+    }
+}
+
+/// Like `isspace()` in `git`.
+fn is_space(b: u8) -> bool {
+    matches!(b, b' ' | b'\t' | b'\n' | b'\r')
+}
@@ -58,6 +58,10 @@ pub mod tree_with_rewrites;
 #[cfg(feature = "blob")]
 pub use tree_with_rewrites::function::diff as tree_with_rewrites;
 
+///
+#[cfg(feature = "blob")]
+pub mod patch;
+
 ///
 #[cfg(feature = "index")]
 pub mod index;
@@ -0,0 +1,394 @@
+//! Produce the `GIT binary patch` sections of a patch, just like `git diff --binary` would.
+use std::io::Write;
+
+/// Write the forward and the reverse body of a binary patch from `old` to `new` into `out`.
+///
+/// Each body is either a compressed literal copy of its target, or a compressed delta against its source,
+/// whichever is smaller.
+pub(super) fn write_bodies(out: &mut impl Write, old: &[u8], new: &[u8]) -> std::io::Result<()> {
+    out.write_all(b"GIT binary patch\n")?;
+    write_body(out, old, new)?;
+    write_body(out, new, old)
+}
+
+fn write_body(out: &mut impl Write, source: &[u8], target: &[u8]) -> std::io::Result<()> {
+    let literal = deflate(target)?;
+    let delta = if !source.is_empty() && !target.is_empty() {
+        delta::create(source, target, literal.len())
+            .map(|delta| deflate(&delta).map(|deflated| (delta.len(), deflated)))
+            .transpose()?
+    } else {
+        None
+    };
+
+    let data = match delta {
+        Some((delta_size, deflated)) if deflated.len() < literal.len() => {
+            writeln!(out, "delta {delta_size}")?;
+            deflated
+        }
+        _ => {
+            writeln!(out, "literal {}", target.len())?;
+            literal
+        }
+    };
+
+    let mut line = Vec::with_capacity(72);
+    for chunk in data.chunks(52) {
+        line.clear();
+        let len = chunk.len() as u8;
+        line.push(if len <= 26 { b'A' + len - 1 } else { b'a' + len - 27 });
+        base85::encode(chunk, &mut line);
+        line.push(b'\n');
+        out.write_all(&line)?;
+    }
+    out.write_all(b"\n")
+}
+
+/// Compress `data` with the fastest compression level, which is what `git` uses by default.
+fn deflate(data: &[u8]) -> std::io::Result<Vec<u8>> {
+    let mut out = gix_zlib::stream::deflate::Write::new(Vec::new());
+    out.write_all(data)?;
+    out.flush()?;
+    Ok(out.into_inner())
+}
+
+mod base85 {
+    const ALPHABET: &[u8; 85] =
+        b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz!#$%&()*+-;<=>?@^_`{|}~";
+
+    /// Encode `data` into `out`, producing 5 characters for every (possibly incomplete) group of 4 bytes.
+    pub fn encode(data: &[u8], out: &mut Vec<u8>) {
+        for group in data.chunks(4) {
+            let mut acc = group
+                .iter()
+                .enumerate()
+                .fold(0u32, |acc, (idx, byte)| acc | u32::from(*byte) << (24 - idx * 8));
+            let mut encoded = [0u8; 5];
+            for char in encoded.iter_mut().rev() {
+                *char = ALPHABET[(acc % 85) as usize];
+                acc /= 85;
+            }
+            out.extend_from_slice(&encoded);
+        }
+    }
+}
+
+/// A port of `diff-delta.c` from `git`, which is needed to produce the very same deltas.
+mod delta {
+    const RABIN_SHIFT: u32 = 23;
+    const RABIN_WINDOW: usize = 16;
+    /// The maximum amount of entries in a hash bucket before it gets culled.
+    const HASH_LIMIT: usize = 64;
+    /// The maximum size of a single operation in the delta.
+    const MAX_OP_SIZE: usize = 5 + 5 + 1 + RABIN_WINDOW + 7;
+    /// The size of a match that is considered good enough to stop looking for better ones.
+    const GOOD_ENOUGH_MATCH: usize = 4096;
+
+    /// The polynomial used for the rolling hash.
+    const POLYNOMIAL: u64 = (1 << 31) | 0x2b59_b4d1;
+
+    /// Compute `value` modulo the polynomial, in GF(2).
+    const fn modulo(mut value: u128) -> u32 {
+        let mut bit = 127;
+        while bit >= 31 {
+            if (value >> bit) & 1 == 1 {
+                value ^= (POLYNOMIAL as u128) << (bit - 31);
+            }
+            bit -= 1;
+        }
+        value as u32
+    }
+
+    /// The table to roll a byte into the hash.
+    const T: [u32; 256] = {
+        let mut table = [0u32; 256];
+        let mut idx = 0;
+        while idx < 256 {
+            let shifted = (idx as u128) << 31;
+            table[idx] = (shifted as u32) ^ modulo(shifted);
+            idx += 1;
+        }
+        table
+    };
+
+    /// The table to roll a byte out of the hash once it leaves the window.
+    const U: [u32; 256] = {
+        let mut table = [0u32; 256];
+        let mut idx = 0;
+        while idx < 256 {
+            table[idx] = modulo((idx as u128) << ((RABIN_WINDOW - 1) * 8));
+            idx += 1;
+        }
+        table
+    };
+
+    fn roll(val: u32, byte: u8) -> u32 {
+        ((val << 8) | u32::from(byte)) ^ T[(val >> RABIN_SHIFT) as usize]
+    }
+
+    #[derive(Clone, Copy)]
+    struct Entry {
+        /// The offset into the source just past the block that was hashed.
+        offset: usize,
+        val: u32,
+    }
+
+    struct Index {
+        hash_mask: u32,
+        /// All entries, grouped by bucket and ordered by offset within each bucket.
+        entries: Vec<Entry>,
+        /// The start of each bucket in `entries`, with one more item to mark the end of the last bucket.
+        buckets: Vec<usize>,
+    }
+
+    impl Index {
+        fn new(source: &[u8]) -> Self {
+            let mut num_entries = (source.len() - 1) / RABIN_WINDOW;
+            let mut hsize_bits = 4;
+            while (1usize << hsize_bits) < num_entries / 4 {
+                hsize_bits += 1;
+            }
+            let hsize = 1usize << hsize_bits;
+            let hash_mask = (hsize - 1) as u32;
+
+            let mut hash: Vec<Vec<Entry>> = vec![Vec::new(); hsize];
+            let mut last_bucket = None;
+            let mut prev_val = !0;
+            for block_start in (0..num_entries).rev().map(|idx| idx * RABIN_WINDOW) {
+                let val = source[block_start + 1..=block_start + RABIN_WINDOW]
+                    .iter()
+                    .fold(0, |val, byte| roll(val, *byte));
+                let offset = block_start + RABIN_WINDOW;
+                if val == prev_val {
+                    // keep the lowest of consecutive identical blocks
+                    let bucket: &mut Vec<Entry> = &mut hash[last_bucket.expect("set when prev_val was set")];
+                    bucket.last_mut().expect("just pushed").offset = offset;
+                    num_entries -= 1;
+                } else {
+                    prev_val = val;
+                    let bucket = (val & hash_mask) as usize;
+                    hash[bucket].push(Entry { offset, val });
+                    last_bucket = Some(bucket);
+                }
+            }
+
+            let mut entries = Vec::with_capacity(num_entries);
+            let mut buckets = Vec::with_capacity(hsize + 1);
+            for bucket in &mut hash {
+                buckets.push(entries.len());
+                bucket.reverse();
+                if bucket.len() <= HASH_LIMIT {
+                    entries.extend_from_slice(bucket);
+                    continue;
+                }
+                // Cull the entries uniformly to leave exactly `HASH_LIMIT` of them, to guard against
+                // pathological data sets that would make the search quadratic.
+                num_entries -= bucket.len() - HASH_LIMIT;
+                let excess = (bucket.len() - HASH_LIMIT) as isize;
+                let mut acc = 0isize;
+                let mut idx = 0;
+                while idx < bucket.len() {
+                    entries.push(bucket[idx]);
+                    acc += excess;
+                    while acc > 0 {
+                        idx += 1;
+                        acc -= HASH_LIMIT as isize;
+                    }
+                    idx += 1;
+                }
+            }
+            buckets.push(entries.len());
+            debug_assert_eq!(entries.len(), num_entries);
+            Index {
+                hash_mask,
+                entries,
+                buckets,
+            }
+        }
+
+        fn bucket(&self, val: u32) -> &[Entry] {
+            let bucket = (val & self.hash_mask) as usize;
+            &self.entries[self.buckets[bucket]..self.buckets[bucket + 1]]
+        }
+    }
+
+    fn write_size(out: &mut Vec<u8>, mut size: usize) {
+        while size >= 0x80 {
+            out.push((size as u8) | 0x80);
+            size >>= 7;
+        }
+        out.push(size as u8);
+    }
+
+    /// Create a delta that turns `source` into `target`, or return `None` if it would be larger than `max_size`.
+    ///
+    /// Both `source` and `target` must not be empty.
+    pub(crate) fn create(source: &[u8], target: &[u8], max_size: usize) -> Option<Vec<u8>> {
+        debug_assert!(!source.is_empty() && !target.is_empty());
+        let index = Index::new(source);
+
+        let mut out_size = 8192;
+        if out_size >= max_size {
+            out_size = max_size + MAX_OP_SIZE + 1;
+        }
+        let mut out = Vec::with_capacity(out_size);
+        write_size(&mut out, source.len());
+        write_size(&mut out, target.len());
+
+        // The slot for the amount of inserted bytes that follow.
+        out.push(0);
+        let mut val = 0;
+        let mut pos = 0;
+        while pos < RABIN_WINDOW && pos < target.len() {
+            out.push(target[pos]);
+            val = roll(val, target[pos]);
+            pos += 1;
+        }
+        let mut insert_count = pos as isize;
+
+        let mut match_offset = 0usize;
+        let mut match_size = 0usize;
+        while pos < target.len() {
+            if match_size < GOOD_ENOUGH_MATCH {
+                val ^= U[target[pos - RABIN_WINDOW] as usize];
+                val = roll(val, target[pos]);
+                for entry in index.bucket(val) {
+                    if entry.val != val {
+                        continue;
+                    }
+                    let ref_size = (source.len() - entry.offset).min(target.len() - pos);
+                    if ref_size <= match_size {
+                        break;
+                    }
+                    let size = source[entry.offset..][..ref_size]
+                        .iter()
+                        .zip(&target[pos..])
+                        .take_while(|(a, b)| a == b)
+                        .count();
+                    if match_size < size {
+                        match_size = size;
+                        match_offset = entry.offset;
+                        if match_size >= GOOD_ENOUGH_MATCH {
+                            break;
+                        }
+                    }
+                }
+            }
+
+            if match_size < 4 {
+                if insert_count == 0 {
+                    out.push(0);
+                }
+                out.push(target[pos]);
+                pos += 1;
+                insert_count += 1;
+                if insert_count == 0x7f {
+                    let slot = out.len() - insert_count as usize - 1;
+                    out[slot] = insert_count as u8;
+                    insert_count = 0;
+                }
+                match_size = 0;
+            } else {
+                if insert_count != 0 {
+                    while match_offset != 0 && source[match_offset - 1] == target[pos - 1] {
+                        // we can match one byte back
+                        match_size += 1;
+                        match_offset -= 1;
+                        pos -= 1;
+                        out.pop();
+                        insert_count -= 1;
+                        if insert_count == 0 {
+                            // remove the count slot
+                            out.pop();
+                            insert_count = -1;
+                            break;
+                        }
+                    }
+                    if insert_count > 0 {
+                        let slot = out.len() - insert_count as usize - 1;
+                        out[slot] = insert_count as u8;
+                    }
+                    insert_count = 0;
+                }
+
+                // A copy operation is limited to 64KB.
+                let left = match_size.saturating_sub(0x10000);
+                match_size -= left;
+
+                let op_pos = out.len();
+                out.push(0);
+                let mut op = 0x80u8;
+                for (byte_idx, flag) in [0x01, 0x02, 0x04, 0x08].into_iter().enumerate() {
+                    let byte = (match_offset >> (byte_idx * 8)) as u8;
+                    if byte != 0 {
+                        out.push(byte);
+                        op |= flag;
+                    }
+                }
+                for (byte_idx, flag) in [0x10, 0x20].into_iter().enumerate() {
+                    let byte = (match_size >> (byte_idx * 8)) as u8;
+                    if byte != 0 {
+                        out.push(byte);
+                        op |= flag;
+                    }
+                }
+                out[op_pos] = op;
+
+                pos += match_size;
+                match_offset += match_size;
+                match_size = left;
+
+                if match_offset > 0xffff_ffff {
+                    match_size = 0;
+                }
+                if match_size < GOOD_ENOUGH_MATCH {
+                    val = target[pos - RABIN_WINDOW..pos]
+                        .iter()
+                        .fold(0, |val, byte| roll(val, *byte));
+                }
+            }
+
+            if out.len() >= out_size - MAX_OP_SIZE {
+                out_size = out_size * 3 / 2;
+                if out_size >= max_size {
+                    out_size = max_size + MAX_OP_SIZE + 1;
+                }
+                if out.len() > max_size {
+                    break;
+                }
+            }
+        }
+
+        if insert_count > 0 {
+            let slot = out.len() - insert_count as usize - 1;
+            out[slot] = insert_count as u8;
+        }
+
+        (out.len() <= max_size).then_some(out)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::{T, U, create};
+
+        #[test]
+        fn tables_match_the_ones_in_git() {
+            assert_eq!(&T[..3], &[0x0000_0000, 0xab59_b4d1, 0x56b3_69a2]);
+            assert_eq!(&U[..4], &[0x0000_0000, 0x7eb5_200d, 0x5633_f4cb, 0x2886_d4c6]);
+        }
+
+        #[test]
+        fn repetitive_sources_are_indexed_without_overflowing_buckets() {
+            let source: Vec<u8> = std::iter::repeat_n(0u8, 100_000)
+                .chain((0..100_000u32).flat_map(|n| (n % 7).to_le_bytes()))
+                .collect();
+            let mut target = source.clone();
+            target[50_000] = 1;
+            let delta = create(&source, &target, source.len()).expect("small delta");
+            assert!(
+                delta.len() < 100,
+                "the delta consists of only a few copies and one insert"
+            );
+        }
+    }
+}
@@ -0,0 +1,520 @@
+use std::io::Write;
+
+use bstr::{BStr, ByteSlice};
+use gix_object::{FindExt, tree::EntryMode};
+
+use super::{Options, binary, write::Error};
+use crate::{
+    blob::{
+        ResourceKind, UnifiedDiff, diff_ignoring_whitespace, diff_with_anchors,
+        funcname::{FunctionNames, Matcher},
+        platform::prepare_diff::Operation,
+        unified_diff::{ConsumeHunk, DiffLineKind, HunkHeader},
+        word_diff::{self, ConsumeWordDiffHunk, Tokenizer},
+    },
+    tree_with_rewrites::ChangeRef,
+};
+
+/// One side of a change.
+#[derive(Clone, Copy)]
+struct Side<'a> {
+    location: &'a BStr,
+    mode: EntryMode,
+    id: &'a gix_hash::oid,
+}
+
+/// Information about a rename or a copy.
+#[derive(Clone, Copy)]
+struct Rewrite {
+    copy: bool,
+    /// The similarity in percent.
+    similarity: u32,
+}
+
+/// Write `change` as patch in the format of `git diff` to `out`, using `resource_cache` to obtain the diffable content of blobs
+/// and `objects` to obtain the data of binary blobs.
+/// Use `options` to configure the patch.
+///
+/// Trees are skipped as `git` doesn't show them either, and [modifications](ChangeRef::Modification) that change the type of an entry,
+/// like turning a file into a symlink, are written as a deletion followed by an addition.
+/// Submodules are shown by the commit they point to.
+///
+/// Note that nothing is written if a change has no effect on the content and doesn't need to be shown for other reasons.
+/// Also note that `resource_cache` is configured by the caller, so it's possible to obtain patches of content that was
+/// converted for use in the worktree, or that was turned into text.
+///
+/// ### Deviation
+///
+/// The similarity of [rewrites](ChangeRef::Rewrite) is based on an actual diff of the content, whereas `git` uses an estimate
+/// based on chunks of content, so the `similarity index` may differ slightly.
+/// Further, the compressed data of binary patches isn't necessarily the same as the one produced by `git`
+/// as a different implementation of `zlib` is used, even though it applies the same way.
+pub fn write(
+    out: &mut impl Write,
+    change: ChangeRef<'_>,
+    resource_cache: &mut crate::blob::Platform,
+    objects: &impl gix_object::FindObjectOrHeader,
+    options: &Options,
+) -> Result<(), Error> {
+    match change {
+        ChangeRef::Addition {
+            location,
+            entry_mode,
+            id,
+            relation: _,
+        } => {
+            let new = Side {
+                location,
+                mode: entry_mode,
+                id: &id,
+            };
+            write_pair(out, None, Some(new), None, resource_cache, objects, options)
+        }
+        ChangeRef::Deletion {
+            location,
+            entry_mode,
+            id,
+            relation: _,
+        } => {
+            let old = Side {
+                location,
+                mode: entry_mode,
+                id: &id,
+            };
+            write_pair(out, Some(old), None, None, resource_cache, objects, options)
+        }
+        ChangeRef::Modification {
+            location,
+            previous_entry_mode,
+            previous_id,
+            entry_mode,
+            id,
+        } => {
+            let old = Side {
+                location,
+                mode: previous_entry_mode,
+                id: &previous_id,
+            };
+            let new = Side {
+                location,
+                mode: entry_mode,
+                id: &id,
+            };
+            if is_type_change(old.mode, new.mode) {
+                write_pair(out, Some(old), None, None, resource_cache, objects, options)?;
+                write_pair(out, None, Some(new), None, resource_cache, objects, options)
+            } else {
+                write_pair(out, Some(old), Some(new), None, resource_cache, objects, options)
+            }
+        }
+        ChangeRef::Rewrite {
+            source_location,
+            source_entry_mode,
+            source_id,
+            diff,
+            entry_mode,
+            id,
+            location,
+            copy,
+            source_relation: _,
+            relation: _,
+        } => {
+            let old = Side {
+                location: source_location,
+                mode: source_entry_mode,
+                id: &source_id,
+            };
+            let new = Side {
+                location,
+                mode: entry_mode,
+                id: &id,
+            };
+            let rewrite = Rewrite {
+                copy,
+                similarity: diff.map_or(100, |diff| (diff.similarity * 100.0) as u32),
+            };
+            write_pair(
+                out,
+                Some(old),
+                Some(new),
+                Some(rewrite),
+                resource_cache,
+                objects,
+                options,
+            )
+        }
+    }
+}
+
+enum Content<'a> {
+    Text {
+        old: &'a [u8],
+        new: &'a [u8],
+        algorithm: crate::blob::Algorithm,
+    },
+    Binary,
+}
+
+fn write_pair(
+    out: &mut impl Write,
+    old: Option<Side<'_>>,
+    new: Option<Side<'_>>,
+    rewrite: Option<Rewrite>,
+    resource_cache: &mut crate::blob::Platform,
+    objects: &impl gix_object::FindObjectOrHeader,
+    options: &Options,
+) -> Result<(), Error> {
+    let any = new.or(old).expect("at least one side is set");
+    if old.is_some_and(|side| side.mode.is_tree()) || new.is_some_and(|side| side.mode.is_tree()) {
+        return Ok(());
+    }
+    if let (Some(old), Some(new)) = (old, new) {
+        if is_type_change(old.mode, new.mode) {
+            // `git` doesn't show anything for rewrites across types.
+            return Ok(());
+        }
+    }
+
+    let (old_submodule, new_submodule);
+    let outcome;
+    let mut driver_word_regex = None;
+    let mut driver_funcname = None;
+    let content = if any.mode.is_commit() {
+        old_submodule = old.map(submodule_text).unwrap_or_default();
+        new_submodule = new.map(submodule_text).unwrap_or_default();
+        Content::Text {
+            old: old_submodule.as_bytes(),
+            new: new_submodule.as_bytes(),
+            algorithm: resource_cache.options.algorithm.unwrap_or_default(),
+        }
+    } else {
+        for (side, kind) in [(old, ResourceKind::OldOrSource), (new, ResourceKind::NewOrDestination)] {
+            let side_or_any = side.unwrap_or(any);
+            resource_cache.set_resource(
+                side.map_or_else(|| any.id.kind().null(), |side| side.id.to_owned()),
+                side_or_any.mode.kind(),
+                side_or_any.location,
+                kind,
+                objects,
+            )?;
+        }
+        if options
+            .word_diff
+            .as_ref()
+            .is_some_and(|word_diff| word_diff.tokenizer.is_none())
+        {
+            driver_word_regex = resource_cache.resources().and_then(|(old, new)| {
+                [old, new].into_iter().find_map(|resource| {
+                    resource
+                        .driver_index
+                        .and_then(|idx| resource_cache.filter.drivers()[idx].word_regex.clone())
+                })
+            });
+        }
+        driver_funcname = resource_cache.resources().and_then(|(old, new)| {
+            [old, new].into_iter().find_map(|resource| {
+                resource
+                    .driver_index
+                    .and_then(|idx| resource_cache.filter.drivers()[idx].funcname.clone())
+            })
+        });
+        let default_algorithm = resource_cache.options.algorithm.unwrap_or_default();
+        outcome = resource_cache.prepare_diff()?;
+        match outcome.operation {
+            Operation::InternalDiff { algorithm } => Content::Text {
+                old: outcome.old.data.as_slice().unwrap_or_default(),
+                new: outcome.new.data.as_slice().unwrap_or_default(),
+                algorithm,
+            },
+            Operation::ExternalCommand { .. } => Content::Text {
+                old: outcome.old.data.as_slice().unwrap_or_default(),
+                new: outcome.new.data.as_slice().unwrap_or_default(),
+                algorithm: default_algorithm,
+            },
+            Operation::SourceOrDestinationIsBinary => Content::Binary,
+        }
+    };
+
+    let name_a = old.unwrap_or(any).location;
+    let name_b = any.location;
+    let mut label_a = Vec::new();
+    quote_into(&mut label_a, &[options.old_prefix.as_ref(), name_a]);
+    let mut label_b = Vec::new();
+    quote_into(&mut label_b, &[options.new_prefix.as_ref(), name_b]);
+
+    let mut header = Vec::with_capacity(256);
+    header.extend_from_slice(b"diff --git ");
+    header.extend_from_slice(&label_a);
+    header.push(b' ');
+    header.extend_from_slice(&label_b);
+    header.push(b'\n');
+    let must_show_header = match (old, new) {
+        (None, Some(new)) => {
+            writeln!(header, "new file mode {}", new.mode.kind().as_octal_str())?;
+            true
+        }
+        (Some(old), None) => {
+            writeln!(header, "deleted file mode {}", old.mode.kind().as_octal_str())?;
+            true
+        }
+        (Some(old), Some(new)) => {
+            let mode_changed = old.mode.kind() != new.mode.kind();
+            if mode_changed {
+                writeln!(header, "old mode {}", old.mode.kind().as_octal_str())?;
+                writeln!(header, "new mode {}", new.mode.kind().as_octal_str())?;
+            }
+            mode_changed || rewrite.is_some()
+        }
+        (None, None) => unreachable!("at least one side is set"),
+    };
+    if let Some(rewrite) = rewrite {
+        let kind = if rewrite.copy { "copy" } else { "rename" };
+        writeln!(header, "similarity index {}%", rewrite.similarity)?;
+        write!(header, "{kind} from ")?;
+        quote_into(&mut header, &[name_a]);
+        write!(header, "\n{kind} to ")?;
+        quote_into(&mut header, &[name_b]);
+        header.push(b'\n');
+    }
+    let null = any.id.kind().null();
+    let old_id = old.map_or(null.as_ref(), |side| side.id);
+    let new_id = new.map_or(null.as_ref(), |side| side.id);
+    if old_id != new_id {
+        let hex_len = if options.binary && matches!(content, Content::Binary) {
+            any.id.kind().len_in_hex()
+        } else {
+            options.abbrev
+        };
+        write!(
+            header,
+            "index {}..{}",
+            old_id.to_hex_with_len(hex_len),
+            new_id.to_hex_with_len(hex_len)
+        )?;
+        if let (Some(old), Some(new)) = (old, new) {
+            if old.mode.kind() == new.mode.kind() {
+                write!(header, " {}", new.mode.kind().as_octal_str())?;
+            }
+        }
+        header.push(b'\n');
+    }
+
+    let color = options
+        .word_diff
+        .as_ref()
+        .is_some_and(|word_diff| word_diff.mode == word_diff::Mode::Color);
+    let label = |side: Option<Side<'_>>, label: Vec<u8>| if side.is_some() { label } else { b"/dev/null".into() };
+    let (label_a, label_b) = (label(old, label_a), label(new, label_b));
+    match content {
+        Content::Text { old, new, algorithm } => {
+            let input = crate::blob::InternedInput::new(
+                crate::blob::sources::byte_lines(old),
+                crate::blob::sources::byte_lines(new),
+            );
+            let diff = if options.anchors.is_empty() {
+                diff_ignoring_whitespace(algorithm, &input, options.whitespace)
+            } else {
+                diff_with_anchors(&input, options.whitespace, &options.anchors)
+            };
+            let matcher = driver_funcname
+                .as_ref()
+                .map(Matcher::new)
+                .transpose()?
+                .unwrap_or_default();
+            let function_names = FunctionNames::new(&matcher, &input);
+            let hunks = match &options.word_diff {
+                None => {
+                    let hunks = Hunks {
+                        out: Vec::new(),
+                        function_names,
+                    };
+                    let unified = UnifiedDiff::new(&diff, &input, hunks, options.context);
+                    with_function_context(unified, options, &matcher)
+                        .with_whitespace(options.whitespace)
+                        .consume()?
+                }
+                Some(word_diff) => {
+                    let from_driver;
+                    let tokenizer = match (&word_diff.tokenizer, driver_word_regex) {
+                        (Some(tokenizer), _) => tokenizer,
+                        (None, Some(pattern)) => {
+                            from_driver = Tokenizer::from_regex(pattern.as_ref())?;
+                            &from_driver
+                        }
+                        (None, None) => &word_diff.default_tokenizer,
+                    };
+                    let hunks = ConsumeWordDiffHunk::new(Vec::new(), tokenizer, word_diff.mode)
+                        .with_function_names(function_names);
+                    let unified = UnifiedDiff::new(&diff, &input, hunks, options.context);
+                    with_function_context(unified, options, &matcher)
+                        .with_whitespace(options.whitespace)
+                        .consume()?
+                }
+            };
+            if hunks.is_empty() {
+                if must_show_header {
+                    write_header(out, &header, color)?;
+                }
+                return Ok(());
+            }
+            header.extend_from_slice(b"--- ");
+            header.extend_from_slice(&label_a);
+            if name_a.contains(&b' ') {
+                header.push(b'\t');
+            }
+            header.extend_from_slice(b"\n+++ ");
+            header.extend_from_slice(&label_b);
+            if name_b.contains(&b' ') {
+                header.push(b'\t');
+            }
+            header.push(b'\n');
+            write_header(out, &header, color)?;
+            out.write_all(&hunks)?;
+        }
+        Content::Binary if !options.binary => {
+            if old_id == new_id {
+                if must_show_header {
+                    write_header(out, &header, color)?;
+                }
+                return Ok(());
+            }
+            write_header(out, &header, color)?;
+            out.write_all(b"Binary files ")?;
+            out.write_all(&label_a)?;
+            out.write_all(b" and ")?;
+            out.write_all(&label_b)?;
+            out.write_all(b" differ\n")?;
+        }
+        Content::Binary => {
+            let (mut old_buf, mut new_buf) = (Vec::new(), Vec::new());
+            let old_data = match old {
+                Some(side) => objects.find_blob(side.id, &mut old_buf)?.data,
+                None => &[],
+            };
+            let new_data = match new {
+                Some(side) => objects.find_blob(side.id, &mut new_buf)?.data,
+                None => &[],
+            };
+            if old_data == new_data {
+                if must_show_header {
+                    write_header(out, &header, color)?;
+                }
+                return Ok(());
+            }
+            write_header(out, &header, color)?;
+            binary::write_bodies(out, old_data, new_data)?;
+        }
+    }
+    Ok(())
+}
+
+/// Extend the hunks of `unified` to whole functions if `options` ask for it.
+fn with_function_context<'a, D: ConsumeHunk>(
+    unified: UnifiedDiff<'a, &'a [u8], D>,
+    options: &Options,
+    matcher: &'a Matcher,
+) -> UnifiedDiff<'a, &'a [u8], D> {
+    if options.function_context {
+        unified.with_function_context(matcher)
+    } else {
+        unified
+    }
+}
+
+/// Write the extended `header`, with each line in bold if `color` is set.
+fn write_header(out: &mut impl Write, header: &[u8], color: bool) -> std::io::Result<()> {
+    if !color {
+        return out.write_all(header);
+    }
+    for line in header.lines() {
+        out.write_all(b"\x1b[1m")?;
+        out.write_all(line)?;
+        out.write_all(b"\x1b[m\n")?;
+    }
+    Ok(())
+}
+
+/// Return `true` if `old` and `new` are of a different type, like blobs and symlinks, which `git` never diffs against each other.
+fn is_type_change(old: EntryMode, new: EntryMode) -> bool {
+    const IFMT: u16 = 0o170000;
+    old.value() & IFMT != new.value() & IFMT
+}
+
+/// The content `git` shows for submodules.
+fn submodule_text(side: Side<'_>) -> String {
+    format!("Subproject commit {}\n", side.id)
+}
+
+/// Write all `parts` into `out` as one string, with quotes and escapes like `git` if one of them contains unusual characters.
+fn quote_into(out: &mut Vec<u8>, parts: &[&[u8]]) {
+    fn needs_quoting(byte: u8) -> bool {
+        byte < 0x20 || byte == b'"' || byte == b'\\' || byte >= 0x7f
+    }
+    if !parts
+        .iter()
+        .flat_map(|part| part.iter())
+        .any(|byte| needs_quoting(*byte))
+    {
+        for part in parts {
+            out.extend_from_slice(part);
+        }
+        return;
+    }
+    out.push(b'"');
+    for byte in parts.iter().flat_map(|part| part.iter()).copied() {
+        let escaped = match byte {
+            0x07 => b'a',
+            0x08 => b'b',
+            b'\t' => b't',
+            b'\n' => b'n',
+            0x0b => b'v',
+            0x0c => b'f',
+            b'\r' => b'r',
+            b'"' => b'"',
+            b'\\' => b'\\',
+            byte if needs_quoting(byte) => {
+                write!(out, "\\{byte:03o}").expect("writing to a Vec never fails");
+                continue;
+            }
+            byte => {
+                out.push(byte);
+                continue;
+            }
+        };
+        out.extend_from_slice(&[b'\\', escaped]);
+    }
+    out.push(b'"');
+}
+
+/// Collect hunks in the format used by `git`.
+struct Hunks<'a> {
+    out: Vec<u8>,
+    function_names: FunctionNames<'a>,
+}
+
+impl ConsumeHunk for Hunks<'_> {
+    type Out = Vec<u8>;
+
+    fn consume_hunk(&mut self, header: HunkHeader, lines: &[(DiffLineKind, &[u8])]) -> std::io::Result<()> {
+        let out = &mut self.out;
+        write!(out, "{}", header.display_like_git())?;
+        let function_name = self.function_names.for_hunk(&header);
+        if !function_name.is_empty() {
+            out.push(b' ');
+            out.extend_from_slice(function_name);
+        }
+        out.push(b'\n');
+        for (kind, line) in lines {
+            out.push(kind.to_prefix() as u8);
+            out.extend_from_slice(line);
+            if line.last_byte() != Some(b'\n') {
+                out.extend_from_slice(b"\n\\ No newline at end of file\n");
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> Self::Out {
+        self.out
+    }
+}
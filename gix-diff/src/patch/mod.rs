@@ -0,0 +1,100 @@
+//! Write changes as produced by [`tree_with_rewrites()`](crate::tree_with_rewrites()) as patches in the format of `git diff`,
+//! suitable for `git apply`.
+use bstr::BString;
+
+use crate::blob::{Whitespace, unified_diff::ContextSize, word_diff};
+
+/// Options for use in [`write()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// The amount of context lines around each change, and the amount of lines between changes up to which they are
+    /// merged into the same hunk.
+    ///
+    /// This is what `diff.context` and `diff.interHunkContext` configure in `git`.
+    pub context: ContextSize,
+    /// The amount of hexadecimal characters to use when displaying object ids in the `index` line.
+    ///
+    /// Note that the abbreviated ids aren't checked for uniqueness.
+    pub abbrev: usize,
+    /// If `true`, binary files are written as `GIT binary patch` which can be applied with `git apply`, similar to `git diff --binary`.
+    /// Otherwise only a notice is written that the binary files differ.
+    ///
+    /// If `true`, full object ids are written in the `index` line if one of the sides is binary, as `git apply` needs them.
+    pub binary: bool,
+    /// Which differences in whitespace to ignore when comparing lines.
+    ///
+    /// Note that patches that ignore whitespace may not apply anymore as changed lines may be shown as context.
+    pub whitespace: Whitespace,
+    /// If set, show which words changed within changed lines instead of showing changed lines, similar to `git diff --word-diff`.
+    ///
+    /// Note that such patches can't be applied.
+    pub word_diff: Option<WordDiff>,
+    /// Lines starting with any of these are kept unchanged if they occur exactly once in both versions,
+    /// similar to `git diff --anchored=<text>`.
+    ///
+    /// If not empty, the [patience](crate::blob::Algorithm::Patience) algorithm is used instead of the configured one.
+    pub anchors: Vec<BString>,
+    /// If `true`, hunks are extended to show the whole functions they change, similar to `git diff --function-context`.
+    ///
+    /// Lines that start a function are found with the [pattern of the diff driver](crate::blob::Driver::funcname),
+    /// which is also used to show the name of the function in the header of each hunk.
+    pub function_context: bool,
+    /// The prefix of the path of the old version, like `a/`.
+    pub old_prefix: BString,
+    /// The prefix of the path of the new version, like `b/`.
+    pub new_prefix: BString,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            context: ContextSize::default(),
+            abbrev: 7,
+            binary: false,
+            whitespace: Whitespace::default(),
+            word_diff: None,
+            anchors: Vec::new(),
+            function_context: false,
+            old_prefix: "a/".into(),
+            new_prefix: "b/".into(),
+        }
+    }
+}
+
+/// Options to show changed words instead of changed lines, for use in [`Options::word_diff`].
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct WordDiff {
+    /// How to show changed words.
+    pub mode: word_diff::Mode,
+    /// If set, this is how all lines are split into words, like `--word-diff-regex`, even if the
+    /// [diff driver](crate::blob::Driver::word_regex) of a file configures a different way.
+    pub tokenizer: Option<word_diff::Tokenizer>,
+    /// The way to split lines into words if neither [`tokenizer`](Self::tokenizer) is set nor a diff driver
+    /// configures it, like `diff.wordRegex`.
+    pub default_tokenizer: word_diff::Tokenizer,
+}
+
+///
+pub mod write {
+    /// The error returned by [`write()`](super::write()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not write the patch")]
+        Io(#[from] std::io::Error),
+        #[error(transparent)]
+        SetResource(#[from] crate::blob::platform::set_resource::Error),
+        #[error(transparent)]
+        PrepareDiff(#[from] crate::blob::platform::prepare_diff::Error),
+        #[error("Could not obtain the binary data of a blob to write a binary patch")]
+        FindObject(#[from] gix_object::find::existing_object::Error),
+        #[error(transparent)]
+        WordRegex(#[from] crate::blob::word_diff::tokenizer::Error),
+        #[error(transparent)]
+        FunctionName(#[from] crate::blob::funcname::matcher::Error),
+    }
+}
+
+mod binary;
+mod function;
+pub use function::write;
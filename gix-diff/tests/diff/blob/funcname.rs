@@ -0,0 +1,135 @@
+use bstr::ByteSlice;
+use gix_diff::blob::funcname::{Matcher, Pattern};
+
+fn builtin(name: &str) -> Matcher {
+    Matcher::new(&Pattern::builtin(name.into()).expect("exists")).expect("valid")
+}
+
+fn name<'a>(matcher: &Matcher, line: &'a str) -> Option<&'a str> {
+    matcher
+        .function_name(line.as_bytes())
+        .map(|name| name.to_str().expect("valid UTF-8"))
+}
+
+#[test]
+fn all_builtin_patterns_compile() {
+    let mut count = 0;
+    for driver in Pattern::builtin_names() {
+        let pattern = Pattern::builtin(driver.into()).expect("listed drivers exist");
+        assert!(
+            pattern.extended,
+            "{driver}: all built-in patterns are extended regular expressions"
+        );
+        Matcher::new(&pattern).unwrap_or_else(|err| panic!("{driver}: {err}"));
+        count += 1;
+    }
+    assert_eq!(count, 25);
+    assert!(Pattern::builtin("unknown".into()).is_none());
+}
+
+#[test]
+fn builtin_patterns_find_functions_like_git() {
+    for (driver, line, expected) in [
+        (
+            "rust",
+            "pub(crate) async fn run() {\n",
+            Some("pub(crate) async fn run() {"),
+        ),
+        ("rust", "    impl<T> Trait for T {\n", Some("impl<T> Trait for T {")),
+        ("rust", "    let x = 1;\n", None),
+        ("python", "    async def f(x):\n", Some("async def f(x):")),
+        ("python", "    pass\n", None),
+        ("cpp", "int main(void)\n", Some("int main(void)")),
+        ("cpp", "label:\n", None),
+        ("cpp", "public: // comment\n", None),
+        (
+            "java",
+            "    public static void main(String[] args) {\n",
+            Some("public static void main(String[] args) {"),
+        ),
+        ("java", "        if (x) {\n", None),
+        (
+            "golang",
+            "func (s *S) Run() error {\n",
+            Some("func (s *S) Run() error {"),
+        ),
+        ("bash", "foo() {\n", Some("foo()")),
+        ("tex", "\\section{Intro}\n", Some("\\section{Intro}")),
+        ("csharp", "    public int[] Values\n", Some("public int[] Values")),
+        ("fortran", "      subroutine foo(x)\n", Some("subroutine foo(x)")),
+        ("fortran", "C     a comment\n", None),
+        ("css", "A.link:hover {\n", Some("A.link:hover {")),
+        ("markdown", "## Heading\n", Some("## Heading")),
+    ] {
+        assert_eq!(name(&builtin(driver), line), expected, "{driver}: {line:?}");
+    }
+}
+
+#[test]
+fn default_matcher_finds_lines_starting_with_an_identifier() {
+    let matcher = Matcher::default();
+    assert_eq!(name(&matcher, "fn main() {\r\n"), Some("fn main() {"));
+    assert_eq!(name(&matcher, "_private \t\n"), Some("_private"));
+    assert_eq!(name(&matcher, "$var\n"), Some("$var"));
+    assert_eq!(name(&matcher, "  indented\n"), None);
+    assert_eq!(name(&matcher, "1 digit\n"), None);
+    assert_eq!(name(&matcher, "\n"), None);
+
+    let long = format!("{} {}", "a".repeat(79), "b".repeat(20));
+    assert_eq!(
+        name(&matcher, &long),
+        Some("a".repeat(79).as_str()),
+        "names are cut off after 80 bytes, and trailing whitespace is removed after that"
+    );
+}
+
+#[test]
+fn negations_and_capture_groups() -> crate::Result {
+    let matcher = Matcher::new(&Pattern::extended("!^skip\n^section (.*)$\n^(x*)y"))?;
+    assert_eq!(
+        name(&matcher, "section one\n"),
+        Some("one"),
+        "the first group is the name"
+    );
+    assert_eq!(
+        name(&matcher, "skip section two\n"),
+        None,
+        "negated patterns win if they match first"
+    );
+    assert_eq!(name(&matcher, "y\n"), Some(""), "empty names still mark function lines");
+    assert!(matcher.is_function_line(b"y"));
+    assert!(!matcher.is_function_line(b"other"));
+
+    let matcher = Matcher::new(&Pattern {
+        ignore_case: true,
+        ..Pattern::extended("^section .*")
+    })?;
+    assert_eq!(name(&matcher, "SECTION one\n"), Some("SECTION one"));
+    Ok(())
+}
+
+#[test]
+fn posix_syntax() -> crate::Result {
+    let matcher = Matcher::new(&Pattern::basic(r"^a+\(b\|c\)\{2\}$"))?;
+    assert_eq!(
+        name(&matcher, "a+bc"),
+        Some("c"),
+        "basic expressions escape groups and intervals"
+    );
+    assert_eq!(name(&matcher, "aabc"), None, "'+' is literal");
+
+    let matcher = Matcher::new(&Pattern::extended(r"^[]\[]+ [^\]+ [a&&b~-]+$"))?;
+    assert_eq!(name(&matcher, "][ ab a&~-"), Some("][ ab a&~-"));
+    assert_eq!(name(&matcher, r"][ a\b a"), None, "backslashes are literal in brackets");
+
+    let matcher = Matcher::new(&Pattern::extended("^{x}$"))?;
+    assert_eq!(
+        name(&matcher, "{x}"),
+        Some("{x}"),
+        "braces are literal if they aren't an interval"
+    );
+
+    let err = Matcher::new(&Pattern::extended("ok\n(unclosed")).unwrap_err();
+    assert_eq!(err.pattern, "(unclosed");
+    Ok(())
+}
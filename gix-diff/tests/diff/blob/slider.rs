@@ -33,6 +33,7 @@ fn baseline() -> gix_testtools::Result {
                 Algorithm::Myers => Algorithm::Myers,
                 Algorithm::Histogram => Algorithm::Histogram,
                 Algorithm::MyersMinimal => Algorithm::MyersMinimal,
+                Algorithm::Patience => Algorithm::Patience,
             },
             &input,
         );
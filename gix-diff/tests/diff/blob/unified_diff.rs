@@ -98,6 +98,68 @@ fn removed_modified_added() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn changes_separated_by_inter_hunk_lines_share_a_hunk() -> crate::Result {
+    let a = "1\n2\n3\n4\n5\n6\n7\n8\n9\n10";
+    let b = "2\n3\n4\n5\nsix\n7\n8\n9\n10\neleven\ntwelve";
+
+    let interner = gix_diff::blob::InternedInput::new(
+        gix_diff::blob::platform::resource::ByteLinesWithoutTerminator::new(a.as_bytes()),
+        gix_diff::blob::platform::resource::ByteLinesWithoutTerminator::new(b.as_bytes()),
+    );
+    let actual = render(
+        Algorithm::Myers,
+        &interner,
+        Recorder::new("\n"),
+        ContextSize::symmetrical(0).with_inter_hunk_lines(3),
+    )?;
+    assert_eq!(
+        actual,
+        &[
+            ((1, 1), (1, 0), "@@ -1,1 +1,0 @@\n".to_string()),
+            ((6, 1), (5, 1), "@@ -6,1 +5,1 @@\n".into()),
+            ((11, 0), (10, 2), "@@ -11,0 +10,2 @@\n".into())
+        ],
+        "four unchanged lines between changes are too many"
+    );
+
+    let actual = render(
+        Algorithm::Myers,
+        &interner,
+        ConsumeBinaryHunk::new(String::new(), "\n"),
+        ContextSize::symmetrical(0).with_inter_hunk_lines(4),
+    )?;
+    insta::assert_snapshot!(actual, @r"
+    @@ -1,10 +1,11 @@
+    -1
+     2
+     3
+     4
+     5
+    -6
+    +six
+     7
+     8
+     9
+     10
+    +eleven
+    +twelve
+    ");
+
+    let actual = render(
+        Algorithm::Myers,
+        &interner,
+        Recorder::new("\n"),
+        ContextSize::symmetrical(1).with_inter_hunk_lines(2),
+    )?;
+    assert_eq!(
+        actual,
+        &[((1, 10), (1, 11), "@@ -1,10 +1,11 @@\n".to_string())],
+        "the inter-hunk lines are added to the context of both changes"
+    );
+    Ok(())
+}
+
 #[test]
 fn context_overlap_by_one_line_move_up() -> crate::Result {
     let a = "2\n3\n4\n5\n6\n7\n";
@@ -0,0 +1,80 @@
+use gix_diff::blob::{
+    Algorithm, InternedInput, UnifiedDiff, diff_with_slider_heuristics,
+    unified_diff::ContextSize,
+    word_diff::{ConsumeWordDiffHunk, Mode, Tokenizer},
+};
+use gix_object::bstr::ByteSlice;
+
+#[test]
+fn tokenizers() {
+    let text = b"fn  main() {\n\tx\r\n}";
+    let words = |tokenizer: &Tokenizer| -> Vec<String> {
+        tokenizer
+            .words(text)
+            .map(|word| word.to_str_lossy().into_owned())
+            .collect()
+    };
+    assert_eq!(
+        words(&Tokenizer::NonWhitespace),
+        ["fn", "main()", "{", "x", "}"],
+        "whitespace separates words, and is ignored"
+    );
+    assert_eq!(
+        words(&Tokenizer::Characters).concat(),
+        "fn  main() {\tx\r}",
+        "each character is a word, except for newlines"
+    );
+    assert_eq!(
+        words(&Tokenizer::from_regex("[a-z]+|[^[:space:]]".into()).expect("valid")),
+        ["fn", "main", "(", ")", "{", "x", "}"]
+    );
+    assert_eq!(
+        words(&Tokenizer::from_regex("x\\s*".into()).expect("valid")),
+        ["x\r"],
+        "matches end at newlines"
+    );
+    assert_eq!(
+        words(&Tokenizer::from_regex("^[a-z]+|\\}".into()).expect("valid")),
+        ["fn", "}"],
+        "`^` matches at the beginning of lines"
+    );
+    assert_eq!(
+        words(&Tokenizer::from_regex("m?".into()).expect("valid")),
+        Vec::<String>::new(),
+        "like `git`, words end at the first empty match"
+    );
+    assert!(Tokenizer::from_regex("(".into()).is_err());
+}
+
+#[test]
+fn modes() -> crate::Result {
+    let input = InternedInput::new(&b"a b c\nkeep\nd\n"[..], &b"a x c\nkeep\nd e\n"[..]);
+    let diff = diff_with_slider_heuristics(Algorithm::Histogram, &input);
+    let render = |mode| -> std::io::Result<String> {
+        let tokenizer = Tokenizer::default();
+        UnifiedDiff::new(
+            &diff,
+            &input,
+            ConsumeWordDiffHunk::new(String::new(), &tokenizer, mode),
+            ContextSize::symmetrical(0),
+        )
+        .consume()
+    };
+
+    insta::assert_snapshot!(render(Mode::Plain)?, @r"
+    @@ -1 +1 @@
+    a [-b-]{+x+} c
+    @@ -3 +3 @@
+    d {+e+}
+    ");
+    assert_eq!(
+        render(Mode::Porcelain)?,
+        "@@ -1 +1 @@\n a \n-b\n+x\n  c\n~\n@@ -3 +3 @@\n d \n+e\n~\n",
+        "unchanged text keeps its whitespace"
+    );
+    assert_eq!(
+        render(Mode::Color)?,
+        "\x1b[36m@@ -1 +1 @@\x1b[m\na \x1b[31mb\x1b[m\x1b[32mx\x1b[m c\n\x1b[36m@@ -3 +3 @@\x1b[m\nd \x1b[32me\x1b[m\n"
+    );
+    Ok(())
+}
@@ -76,6 +76,7 @@ fn assert_hash_agnostic_patch_eq(actual: &str, expected: &str) {
 
 mod blob;
 mod index;
+mod patch;
 mod rewrites;
 mod tree;
 mod tree_with_rewrites;
@@ -0,0 +1,448 @@
+use std::{convert::Infallible, path::Path};
+
+use bstr::ByteSlice;
+use gix_diff::{
+    Rewrites,
+    blob::{
+        Algorithm, Whitespace,
+        funcname::Pattern,
+        unified_diff::ContextSize,
+        word_diff::{Mode, Tokenizer},
+    },
+    patch,
+    rewrites::{Copies, CopySource},
+    tree::recorder::Location,
+    tree_with_rewrites::{Change, Options},
+};
+use gix_object::{FindExt, TreeRefIter};
+
+#[test]
+fn modifications_additions_deletions_and_type_changes() -> crate::Result {
+    let root = fixture_root()?;
+    let actual = patch_between(&root, "modifications", None, &binary_options())?;
+    let expected = baseline(&root, "modifications")?;
+    assert_eq!(
+        without_binary_data(&actual),
+        without_binary_data(&expected),
+        "everything but the compressed data is the same"
+    );
+    assert_applies_to_from_tree("modifications", &actual)?;
+    Ok(())
+}
+
+#[test]
+fn binary_files_without_binary_patches() -> crate::Result {
+    let root = fixture_root()?;
+    let actual = patch_between(&root, "modifications", None, &patch::Options::default())?;
+    let actual = String::from_utf8(actual)?;
+    let hex_len = crate::fixture_hash_kind().len_in_hex();
+    assert!(
+        actual.contains("\nBinary files a/binary and b/binary differ\n"),
+        "binary files are only mentioned"
+    );
+    assert!(actual.contains("\nBinary files a/deleted-binary and /dev/null differ\n"));
+    assert!(actual.contains("\nBinary files /dev/null and b/added-binary differ\n"));
+    assert!(
+        !actual.contains("GIT binary patch"),
+        "these are only written if enabled"
+    );
+    assert!(
+        actual
+            .lines()
+            .filter_map(|line| line.strip_prefix("index "))
+            .all(|range| range.len() < hex_len),
+        "object ids are always abbreviated"
+    );
+    Ok(())
+}
+
+#[test]
+fn renames_copies_and_mode_changes() -> crate::Result {
+    let root = fixture_root()?;
+    let rewrites = Rewrites {
+        copies: Some(Copies {
+            source: CopySource::FromSetOfModifiedFilesAndAllSources,
+            percentage: Some(0.5),
+        }),
+        ..Default::default()
+    };
+    let actual = patch_between(&root, "rewrites", Some(rewrites), &binary_options())?;
+    let expected = baseline(&root, "rewrites")?;
+    assert_eq!(actual.as_bstr(), expected.as_bstr());
+    assert_applies_to_from_tree("rewrites", &actual)?;
+    Ok(())
+}
+
+#[test]
+fn inter_hunk_context() -> crate::Result {
+    let root = fixture_root()?;
+    for (name, context) in [
+        ("inter-hunk-0", ContextSize::symmetrical(3)),
+        ("inter-hunk-1", ContextSize::symmetrical(3).with_inter_hunk_lines(1)),
+        ("inter-hunk-2", ContextSize::symmetrical(3).with_inter_hunk_lines(2)),
+        (
+            "inter-hunk-context-1",
+            ContextSize::symmetrical(1).with_inter_hunk_lines(5),
+        ),
+    ] {
+        let options = patch::Options {
+            context,
+            ..Default::default()
+        };
+        let actual = patch_between(&root, name, None, &options)?;
+        let expected = baseline(&root, name)?;
+        assert_eq!(actual.as_bstr(), expected.as_bstr(), "{name}");
+    }
+    let num_hunks = |name| -> crate::Result<usize> {
+        Ok(baseline(&root, name)?
+            .lines()
+            .filter(|line| line.starts_with(b"@@ "))
+            .count())
+    };
+    assert_eq!(
+        num_hunks("inter-hunk-0")?,
+        3,
+        "without inter-hunk context, all changes are separate"
+    );
+    assert_eq!(num_hunks("inter-hunk-1")?, 2, "the first two changes are close enough");
+    assert_eq!(num_hunks("inter-hunk-2")?, 1, "all changes are close enough");
+    assert_eq!(
+        num_hunks("inter-hunk-context-1")?,
+        2,
+        "the inter-hunk context is added to the regular context"
+    );
+    Ok(())
+}
+
+#[test]
+fn whitespace() -> crate::Result {
+    let root = fixture_root()?;
+    let none = Whitespace::default();
+    for (name, whitespace, context) in [
+        ("whitespace-none", none, ContextSize::default()),
+        (
+            "whitespace-space-change",
+            Whitespace {
+                ignore_space_change: true,
+                ..none
+            },
+            ContextSize::default(),
+        ),
+        (
+            "whitespace-all-space",
+            Whitespace {
+                ignore_all_space: true,
+                ..none
+            },
+            ContextSize::default(),
+        ),
+        (
+            "whitespace-space-at-eol",
+            Whitespace {
+                ignore_space_at_eol: true,
+                ..none
+            },
+            ContextSize::default(),
+        ),
+        (
+            "whitespace-cr-at-eol",
+            Whitespace {
+                ignore_cr_at_eol: true,
+                ..none
+            },
+            ContextSize::default(),
+        ),
+        (
+            "whitespace-blank-lines",
+            Whitespace {
+                ignore_blank_lines: true,
+                ..none
+            },
+            ContextSize::default(),
+        ),
+        (
+            "whitespace-all-space-and-blank-lines",
+            Whitespace {
+                ignore_all_space: true,
+                ignore_blank_lines: true,
+                ..none
+            },
+            ContextSize::symmetrical(1),
+        ),
+    ] {
+        let options = patch::Options {
+            context,
+            whitespace,
+            ..Default::default()
+        };
+        let actual = patch_between(&root, name, None, &options)?;
+        let expected = baseline(&root, name)?;
+        assert_eq!(actual.as_bstr(), expected.as_bstr(), "{name}");
+    }
+    Ok(())
+}
+
+#[test]
+fn word_diff() -> crate::Result {
+    let root = fixture_root()?;
+    let regex = |pattern: &str| Tokenizer::from_regex(pattern.into()).expect("valid");
+    for (name, mode, tokenizer) in [
+        ("words-plain", Mode::Plain, None),
+        ("words-porcelain", Mode::Porcelain, None),
+        ("words-color", Mode::Color, None),
+        ("words-chars", Mode::Plain, Some(Tokenizer::Characters)),
+        ("words-chars", Mode::Plain, Some(regex("."))),
+        ("words-regex", Mode::Plain, Some(regex("[a-z]+"))),
+    ] {
+        let options = patch::Options {
+            word_diff: Some(patch::WordDiff {
+                mode,
+                tokenizer,
+                default_tokenizer: Tokenizer::NonWhitespace,
+            }),
+            ..Default::default()
+        };
+        let actual = patch_between(&root, name, None, &options)?;
+        let expected = baseline(&root, name)?;
+        assert_eq!(actual.as_bstr(), expected.as_bstr(), "{name}");
+    }
+    Ok(())
+}
+
+#[test]
+fn patience_and_anchors() -> crate::Result {
+    let root = fixture_root()?;
+    for (name, algorithm, anchors, whitespace) in [
+        (
+            "patience-histogram",
+            Algorithm::Histogram,
+            vec![],
+            Whitespace::default(),
+        ),
+        ("patience", Algorithm::Patience, vec![], Whitespace::default()),
+        (
+            "patience-anchored",
+            Algorithm::Histogram,
+            vec!["b".into()],
+            Whitespace::default(),
+        ),
+        (
+            "patience-anchored-with-whitespace",
+            Algorithm::Myers,
+            vec!["b".into()],
+            Whitespace {
+                ignore_all_space: true,
+                ..Default::default()
+            },
+        ),
+    ] {
+        let options = patch::Options {
+            anchors,
+            whitespace,
+            ..Default::default()
+        };
+        let actual = patch_between_with_algorithm(&root, name, None, Some(algorithm), &options)?;
+        let expected = baseline(&root, name)?;
+        assert_eq!(actual.as_bstr(), expected.as_bstr(), "{name}");
+    }
+    assert_ne!(
+        baseline(&root, "patience")?,
+        baseline(&root, "patience-anchored")?,
+        "anchors make a difference"
+    );
+    assert_ne!(baseline(&root, "patience")?, baseline(&root, "patience-histogram")?);
+    Ok(())
+}
+
+#[test]
+fn function_names_and_context() -> crate::Result {
+    let root = fixture_root()?;
+    for (name, function_context, context, word_diff) in [
+        ("funcname", false, ContextSize::default(), None),
+        ("funcname-context", true, ContextSize::default(), None),
+        ("funcname-context-1", true, ContextSize::symmetrical(1), None),
+        (
+            "funcname-words-color",
+            false,
+            ContextSize::default(),
+            Some(patch::WordDiff {
+                mode: Mode::Color,
+                tokenizer: Some(Tokenizer::from_regex("[^[:space:]]+".into()).expect("valid")),
+                ..Default::default()
+            }),
+        ),
+    ] {
+        let options = patch::Options {
+            function_context,
+            context,
+            word_diff,
+            ..Default::default()
+        };
+        let actual = patch_between(&root, name, None, &options)?;
+        let expected = baseline(&root, name)?;
+        assert_eq!(actual.as_bstr(), expected.as_bstr(), "{name}");
+    }
+    Ok(())
+}
+
+fn binary_options() -> patch::Options {
+    patch::Options {
+        binary: true,
+        ..Default::default()
+    }
+}
+
+fn fixture_root() -> crate::Result<std::path::PathBuf> {
+    crate::scripted_fixture_read_only("make_diff_for_patches_repo.sh")
+}
+
+fn baseline(root: &Path, name: &str) -> crate::Result<Vec<u8>> {
+    Ok(std::fs::read(root.join(name).with_extension("patch"))?)
+}
+
+fn tree_id(root: &Path, name: &str, side: &str) -> crate::Result<gix_hash::ObjectId> {
+    let hex = std::fs::read_to_string(root.join(format!("{name}.{side}-tree")))?;
+    Ok(gix_hash::ObjectId::from_hex(hex.trim().as_bytes())?)
+}
+
+/// Write all changes between the trees of the baseline `name` as patch, in the order `git` would use.
+fn patch_between(
+    root: &Path,
+    name: &str,
+    rewrites: Option<Rewrites>,
+    options: &patch::Options,
+) -> crate::Result<Vec<u8>> {
+    patch_between_with_algorithm(root, name, rewrites, None, options)
+}
+
+/// Like [`patch_between()`], but configure the diff `algorithm` to use.
+fn patch_between_with_algorithm(
+    root: &Path,
+    name: &str,
+    rewrites: Option<Rewrites>,
+    algorithm: Option<Algorithm>,
+    options: &patch::Options,
+) -> crate::Result<Vec<u8>> {
+    let odb = crate::open_odb(root.join("repo/.git/objects"))?;
+    let (mut from, mut to) = (Vec::new(), Vec::new());
+    odb.find_tree(&tree_id(root, name, "from")?, &mut from)?;
+    odb.find_tree(&tree_id(root, name, "to")?, &mut to)?;
+
+    // The diff drivers configured in the fixture.
+    let drivers = vec![
+        gix_diff::blob::Driver {
+            name: "digits".into(),
+            word_regex: Some("[0-9]".into()),
+            ..Default::default()
+        },
+        gix_diff::blob::Driver {
+            name: "custom".into(),
+            funcname: Some(Pattern::extended("!^skip\n^section (.*)$")),
+            ..Default::default()
+        },
+        gix_diff::blob::Driver {
+            name: "basic".into(),
+            funcname: Some(Pattern::basic(r"^begin \([a-z]*\)$")),
+            ..Default::default()
+        },
+    ];
+    let mut cache = gix_diff::blob::Platform::new(
+        gix_diff::blob::platform::Options {
+            algorithm,
+            ..Default::default()
+        },
+        gix_diff::blob::Pipeline::new(Default::default(), Default::default(), drivers, Default::default()),
+        gix_diff::blob::pipeline::Mode::ToGit,
+        gix_worktree::Stack::new(
+            root.join("repo"),
+            gix_worktree::stack::State::AttributesStack(gix_worktree::stack::state::Attributes::new(
+                Default::default(),
+                None,
+                gix_worktree::stack::state::attributes::Source::WorktreeThenIdMapping,
+                Default::default(),
+            )),
+            Default::default(),
+            Vec::new(),
+            Vec::new(),
+        ),
+    );
+    let mut changes = Vec::new();
+    let hash_kind = crate::fixture_hash_kind();
+    gix_diff::tree_with_rewrites(
+        TreeRefIter::from_bytes(&from, hash_kind),
+        TreeRefIter::from_bytes(&to, hash_kind),
+        &mut cache,
+        &mut Default::default(),
+        &odb,
+        |change| -> Result<_, Infallible> {
+            changes.push(change.into_owned());
+            Ok(std::ops::ControlFlow::Continue(()))
+        },
+        Options {
+            location: Some(Location::Path),
+            rewrites,
+        },
+    )?;
+    changes.sort_by(|a: &Change, b: &Change| a.location().cmp(b.location()));
+
+    let mut out = Vec::new();
+    for change in &changes {
+        patch::write(&mut out, change.to_ref(), &mut cache, &odb, options)?;
+    }
+    Ok(out)
+}
+
+/// Remove the compressed data of binary patches, as it depends on the implementation of zlib.
+fn without_binary_data(patch: &[u8]) -> String {
+    let mut in_binary_patch = false;
+    patch
+        .lines()
+        .filter(|line| {
+            if line.starts_with(b"diff --git ") {
+                in_binary_patch = false;
+            } else if *line == b"GIT binary patch" {
+                in_binary_patch = true;
+            }
+            !in_binary_patch || line.starts_with(b"literal ") || line.starts_with(b"delta ") || line.is_empty()
+        })
+        .map(|line| line.to_str_lossy().into_owned() + "\n")
+        .collect()
+}
+
+/// Apply `patch` to the index of the `from` tree of the baseline `name` with `git apply` and assert it produces the `to` tree.
+fn assert_applies_to_from_tree(name: &str, patch: &[u8]) -> crate::Result {
+    let tmp = gix_testtools::scripted_fixture_writable("make_diff_for_patches_repo.sh")?;
+    let repo = tmp.path().join("repo");
+    let index = tmp.path().join("patch-test-index");
+    let git = |args: &[&str], stdin: Option<&[u8]>| -> crate::Result<String> {
+        use std::io::Write;
+        let mut child = std::process::Command::new("git")
+            .args(args)
+            .current_dir(&repo)
+            .env("GIT_INDEX_FILE", &index)
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .spawn()?;
+        child
+            .stdin
+            .take()
+            .expect("piped")
+            .write_all(stdin.unwrap_or_default())?;
+        let out = child.wait_with_output()?;
+        assert!(
+            out.status.success(),
+            "git {args:?} failed: {}",
+            out.stderr.to_str_lossy()
+        );
+        Ok(out.stdout.to_str_lossy().trim().to_owned())
+    };
+    git(&["read-tree", &tree_id(tmp.path(), name, "from")?.to_string()], None)?;
+    git(&["apply", "--cached", "-"], Some(patch))?;
+    assert_eq!(
+        git(&["write-tree"], None)?,
+        tree_id(tmp.path(), name, "to")?.to_string(),
+        "applying the patch leads to the expected tree"
+    );
+    Ok(())
+}
@@ -0,0 +1,241 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+function baseline() {
+  local name="${1:?the name of the baseline}"
+  local from="${2:?the revision to diff from}"
+  local to="${3:?the revision to diff to}"
+  shift 3
+  git rev-parse "$from^{tree}" > "../$name.from-tree"
+  git rev-parse "$to^{tree}" > "../$name.to-tree"
+  git diff --no-color --binary "$@" "$from" "$to" -- > "../$name.patch"
+}
+
+mkdir repo
+cd repo
+git init -q
+git config diff.renames false
+
+seq 1 20 > numbers
+seq 101 130 > moved-numbers
+seq 201 230 > copy-source
+printf '1\n2\n3' > no-newline
+printf '1\n2\n3\n' > gains-no-newline
+echo "content" > "with space"
+echo "executable" > exe
+echo "to-link" > will-be-link
+ln -s numbers link-to-numbers
+echo "deleted" > deleted
+printf 'binary\0content\n' > binary
+{
+  printf 'large\0binary\n'
+  seq 1 500
+} > large-binary
+printf 'will be deleted\0\n' > deleted-binary
+seq 1 10 > "quoted\"name"
+git add .
+git update-index --add --cacheinfo "160000,$(echo first | git hash-object --stdin),submodule"
+git commit -q -m "base"
+git tag base
+
+sed -i.bak -e 's/^2$/two/' -e 's/^18$/eighteen/' numbers
+sed -i.bak -e 's/^3$/three/' no-newline
+printf '1\n2\n3' > gains-no-newline
+echo "more content" >> "with space"
+chmod +x exe
+rm will-be-link && ln -s numbers will-be-link
+git rm -q deleted deleted-binary
+echo "added" > added
+seq 1 10 | sed 's/^5$/five/' > "quoted\"name"
+rm -f ./*.bak
+{
+  printf 'binary\0content\n'
+  seq 1 200
+} > binary
+{
+  printf 'large\0binary\n'
+  seq 1 500 | sed 's/^250$/two-hundred-fifty/'
+} > large-binary
+printf 'added\0binary\n' > added-binary
+git add .
+git update-index --add --cacheinfo "160000,$(echo second | git hash-object --stdin),submodule"
+git commit -q -m "modifications"
+git tag modifications
+
+baseline modifications base modifications
+
+git mv moved-numbers renamed-numbers
+sed -i.bak -e 's/^129$/one-hundred-twenty-nine/' renamed-numbers
+cp copy-source copied
+echo 231 >> copied
+git mv exe renamed-exe
+chmod -x renamed-exe
+rm -f ./*.bak
+git add .
+git commit -q -m "rewrites"
+git tag rewrites
+
+baseline rewrites modifications rewrites --find-copies-harder
+
+seq 1 30 > inter-hunk
+git add inter-hunk && git commit -q -m "inter-hunk base"
+git tag inter-hunk-base
+seq 1 30 | sed -e 's/^5$/five/' -e 's/^13$/thirteen/' -e 's/^22$/twenty-two/' > inter-hunk
+git commit -q -am "inter-hunk change"
+git tag inter-hunk-change
+
+baseline inter-hunk-0 inter-hunk-base inter-hunk-change
+baseline inter-hunk-1 inter-hunk-base inter-hunk-change --inter-hunk-context=1
+baseline inter-hunk-2 inter-hunk-base inter-hunk-change --inter-hunk-context=2
+baseline inter-hunk-context-1 inter-hunk-base inter-hunk-change -U1 --inter-hunk-context=5
+
+{
+  printf '1\n2 a  b\n3\n4 trailing\n5\n6 crlf\r\n7\n8 ab\n'
+  seq 9 37
+  printf '\n'
+  seq 38 40
+} > whitespace
+git add whitespace && git commit -q -m "whitespace base"
+git tag whitespace-base
+{
+  printf '1\n2 a b\n3\n4 trailing \t\n5\n6 crlf\n7\n8 a b\n'
+  seq 9 20
+  printf '\n'
+  seq 21 30
+  printf '31 thirty-one\n\n'
+  seq 32 40
+} > whitespace
+git commit -q -am "whitespace change"
+git tag whitespace-change
+
+baseline whitespace-none whitespace-base whitespace-change
+baseline whitespace-space-change whitespace-base whitespace-change --ignore-space-change
+baseline whitespace-all-space whitespace-base whitespace-change --ignore-all-space
+baseline whitespace-space-at-eol whitespace-base whitespace-change --ignore-space-at-eol
+baseline whitespace-cr-at-eol whitespace-base whitespace-change --ignore-cr-at-eol
+baseline whitespace-blank-lines whitespace-base whitespace-change --ignore-blank-lines
+baseline whitespace-all-space-and-blank-lines whitespace-base whitespace-change --ignore-all-space --ignore-blank-lines -U1
+
+echo "*.num diff=digits" > .gitattributes
+git config diff.digits.wordRegex '[0-9]'
+printf '1 one two three\n2 keep\n3 x y\n4 gone line\n5 same\n6 a b\r\n7 end' > words
+printf '10 20 30\n40 50\n' > words.num
+printf '1 removed\n2 entirely\n' > words-deleted
+git add . && git commit -q -m "words base"
+git tag words-base
+printf '1 one 2 three four\n2 keep\n3 x  z\n5 same\n6 a c\r\n7 added\n8 end' > words
+printf '10 21 30\n45 50 60\n' > words.num
+printf '1 new\n2 file\n' > words-added
+git rm -q words-deleted
+git add . && git commit -q -m "words change"
+git tag words-change
+
+baseline words-plain words-base words-change --word-diff=plain
+baseline words-porcelain words-base words-change --word-diff=porcelain
+baseline words-color words-base words-change --word-diff=color
+baseline words-chars words-base words-change --word-diff=plain --word-diff-regex=.
+baseline words-regex words-base words-change --word-diff=plain --word-diff-regex='[a-z]+'
+
+printf 'a\nx\nb anchored\nx\nc\nd\ne\n' > patience
+git add patience && git commit -q -m "patience base"
+git tag patience-base
+printf 'c\nd\nx\na\nx\nb anchored\ne\n' > patience
+git commit -q -am "patience change"
+git tag patience-change
+
+baseline patience-histogram patience-base patience-change --histogram
+baseline patience patience-base patience-change --patience
+baseline patience-anchored patience-base patience-change --anchored=b
+baseline patience-anchored-with-whitespace patience-base patience-change --anchored=b --ignore-all-space
+
+{
+  echo "*.rs diff=rust"
+  echo "*.py diff=python"
+  echo "*.c diff=cpp"
+  echo "*.custom diff=custom"
+  echo "*.basic diff=basic"
+} >> .gitattributes
+git config diff.custom.xfuncname $'!^skip\n^section (.*)$'
+git config diff.basic.funcname '^begin \([a-z]*\)$'
+cat <<'RUST' > funcname.rs
+use std::io;
+
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+impl Point {
+    fn new(x: i32, y: i32) -> Self {
+        Point { x, y }
+    }
+
+    fn sum(&self) -> i32 {
+        let a = self.x;
+        let b = self.y;
+        let c = 0;
+        let d = 0;
+        a + b + c + d
+    }
+}
+
+pub fn first() {
+    one();
+    two();
+    three();
+    four();
+    five();
+    six();
+}
+
+fn second() {
+    seven();
+    eight();
+}
+
+fn last() {
+    nine();
+}
+RUST
+cat <<'PYTHON' > funcname.py
+class Greeter:
+    def __init__(self, name):
+        self.name = name
+
+    def greet(self):
+        print("hello")
+        print(self.name)
+        print("bye")
+
+
+def main():
+    g = Greeter("x")
+    g.greet()
+    return 0
+PYTHON
+printf 'int main(void)\n{\n\tint a = 1;\nlabel:\n\ta++;\n\tif (a < 10)\n\t\tgoto label;\n\treturn a;\n}\n' > funcname.c
+printf 'section one\n  a\n  b\n  c\n  d\n  e\nskip this\n  f\n  g\n  h\n  i\nsection two\n  j\n' > funcname.custom
+printf 'begin first\n  a\n  b\n  c\n  d\nbegin Second\n  e\n  f\n  g\n  h\n' > funcname.basic
+{
+  printf 'A%078d tail that is cut off\n' 0
+  seq 1 8 | sed 's/^/  /'
+} > funcname.txt
+printf 'fn existing() {\n    body();\n}\n' > funcname-append.rs
+printf 'import os\n\n\ndef existing():\n    a()\n    b()\n    c()\n    d()\n    body()\n' > funcname-append.py
+git add . && git commit -q -m "funcname base"
+git tag funcname-base
+sed -i -e 's/let d = 0;/let d = 1;/' -e 's/five();/FIVE();/' -e 's/nine();/ten();/' funcname.rs
+sed -i -e 's/print(self.name)/print(self.name.upper())/' -e 's/return 0/return 1/' funcname.py
+sed -i 's/return a;/return a + 1;/' funcname.c
+sed -i 's/  i/  I/' funcname.custom
+sed -i 's/  h/  H/' funcname.basic
+sed -i 's/  8/  eight/' funcname.txt
+printf '\nfn added() {\n    more();\n}\n' >> funcname-append.rs
+printf '    more()\n' >> funcname-append.py
+git add . && git commit -q -m "funcname change"
+git tag funcname-change
+
+baseline funcname funcname-base funcname-change
+baseline funcname-context funcname-base funcname-change --function-context
+baseline funcname-context-1 funcname-base funcname-change --function-context -U1
+baseline funcname-words-color funcname-base funcname-change --word-diff=color --word-diff-regex='[^[:space:]]+'
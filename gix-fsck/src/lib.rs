@@ -3,10 +3,13 @@
 
 use std::collections::VecDeque;
 
-use gix_hash::ObjectId;
+use gix_hash::{ObjectId, oid};
 use gix_hashtable::HashSet;
 use gix_object::{Exists, FindExt, Kind, tree::EntryKind};
 
+/// A predicate to tell if an object was received from a promisor remote.
+type IsPromisorObjectFn = Box<dyn Fn(&oid) -> bool>;
+
 /// Perform a connectivity check.
 pub struct Connectivity<T, F>
 where
@@ -17,8 +20,13 @@ where
     db: T,
     /// Closure to invoke when a missing object is encountered
     missing_cb: F,
+    /// If set, a predicate to tell if an object was received from a promisor remote, so the objects it refers to may be missing.
+    is_promisor_object: Option<IsPromisorObjectFn>,
     /// Set of Object IDs already (or about to be) scanned during the check
     seen: HashSet,
+    /// Set of tree IDs already scanned while being referenced by promisor objects only, which allows them to be missing.
+    /// They are scanned again if a non-promisor object refers to them.
+    seen_by_promisor: HashSet,
     /// A buffer to keep a single object at a time.
     buf: Vec<u8>,
 }
@@ -33,18 +41,34 @@ where
         Connectivity {
             db,
             missing_cb,
+            is_promisor_object: None,
             seen: HashSet::default(),
+            seen_by_promisor: HashSet::default(),
             buf: Default::default(),
         }
     }
 
+    /// Use `is_promisor_object` to determine if an object was received from a promisor remote, as is the case in partial clones.
+    ///
+    /// Objects referenced by promisor objects are allowed to be missing, and won't be passed to the `missing_cb`.
+    /// With `gix_odb`, use `Handle::is_in_promisor_pack()` as predicate.
+    pub fn with_promisor_objects(mut self, is_promisor_object: impl Fn(&oid) -> bool + 'static) -> Self {
+        self.is_promisor_object = Some(Box::new(is_promisor_object));
+        self
+    }
+
+    fn is_promisor_object(&self, id: &oid) -> bool {
+        self.is_promisor_object.as_ref().is_some_and(|f| f(id))
+    }
+
     /// Run the connectivity check on the provided commit `oid`.
     ///
     /// ### Algorithm
     ///
     /// Walk the trees and blobs referenced by the commit and verify they exist in the ODB.
     /// Any objects previously encountered by this instance will be skipped silently.
-    /// Any referenced blobs that are not present in the ODB will result in a call to the  `missing_cb`.
+    /// Any referenced blobs that are not present in the ODB will result in a call to the  `missing_cb`,
+    /// unless they are referenced by a [promisor object](Self::with_promisor_objects()).
     /// Missing commits or trees will cause an error to be returned.
     ///     - TODO: consider how to handle a missing commit (invoke `missing_cb`, or possibly return a Result?)
     pub fn check_commit(&mut self, oid: &ObjectId) -> Result<(), gix_object::find::existing_object::Error> {
@@ -58,10 +82,15 @@ where
             commit.tree()
         };
 
-        let mut tree_ids = VecDeque::from_iter(Some(tree_id));
-        while let Some(tree_id) = tree_ids.pop_front() {
-            if self.seen.insert(tree_id) {
-                self.check_tree(&tree_id, &mut tree_ids);
+        let mut tree_ids = VecDeque::from_iter(Some((tree_id, self.is_promisor_object(oid))));
+        while let Some((tree_id, referenced_by_promisor)) = tree_ids.pop_front() {
+            let is_first_visit = if referenced_by_promisor {
+                !self.seen.contains(&tree_id) && self.seen_by_promisor.insert(tree_id)
+            } else {
+                self.seen.insert(tree_id)
+            };
+            if is_first_visit {
+                self.check_tree(&tree_id, referenced_by_promisor, &mut tree_ids);
             }
         }
 
@@ -70,9 +99,13 @@ where
 
     /// Blobs are checked right away, trees are stored in `tree_ids` for the parent to iterate them, and only
     /// if they have not been `seen` yet.
-    fn check_tree(&mut self, oid: &ObjectId, tree_ids: &mut VecDeque<ObjectId>) {
+    /// If `referenced_by_promisor` is `true`, the tree at `oid` is allowed to be missing.
+    fn check_tree(&mut self, oid: &ObjectId, referenced_by_promisor: bool, tree_ids: &mut VecDeque<(ObjectId, bool)>) {
+        let is_promisor = self.is_promisor_object(oid);
         let Ok(tree) = self.db.find_tree(oid, &mut self.buf) else {
-            (self.missing_cb)(oid, Kind::Tree);
+            if !referenced_by_promisor {
+                (self.missing_cb)(oid, Kind::Tree);
+            }
             return;
         };
 
@@ -80,11 +113,12 @@ where
             match entry_ref.mode.kind() {
                 EntryKind::Tree => {
                     let tree_id = entry_ref.oid.to_owned();
-                    tree_ids.push_back(tree_id);
+                    tree_ids.push_back((tree_id, is_promisor));
                 }
                 EntryKind::Blob | EntryKind::BlobExecutable | EntryKind::Link => {
                     let blob_id = entry_ref.oid.to_owned();
-                    if self.seen.insert(blob_id) {
+                    // Blobs of promisor trees aren't marked as seen, as other trees may still require them.
+                    if !is_promisor && self.seen.insert(blob_id) {
                         check_blob(&self.db, &blob_id, &mut self.missing_cb);
                     }
                 }
@@ -6,7 +6,28 @@ use std::sync::LazyLock;
 
 use crate::hex_to_id;
 
+type IsPromisorObject = Box<dyn Fn(&gix_hash::oid) -> bool>;
+
 fn check_missing<'a>(repo_name: &str, commits: impl IntoIterator<Item = &'a ObjectId>) -> HashMap<ObjectId, Kind> {
+    check_missing_with(repo_name, commits, |_db| None)
+}
+
+fn check_missing_with_promisor_objects<'a>(
+    repo_name: &str,
+    commits: impl IntoIterator<Item = &'a ObjectId>,
+) -> HashMap<ObjectId, Kind> {
+    check_missing_with(repo_name, commits, |db| {
+        let db = db.clone();
+        Some(Box::new(move |id| db.is_in_promisor_pack(id)))
+    })
+}
+
+/// Use `is_promisor_object(db)` to obtain the predicate to detect promisor objects with, if there should be one.
+fn check_missing_with<'a>(
+    repo_name: &str,
+    commits: impl IntoIterator<Item = &'a ObjectId>,
+    is_promisor_object: impl FnOnce(&gix_odb::Handle) -> Option<IsPromisorObject>,
+) -> HashMap<ObjectId, Kind> {
     let db = {
         let fixture_path = gix_testtools::scripted_fixture_read_only("make_test_repos.sh")
             .expect("fixture path")
@@ -23,7 +44,12 @@ fn check_missing<'a>(repo_name: &str, commits: impl IntoIterator<Item = &'a Obje
         missing.try_insert(*oid, kind).expect("no duplicate oid");
     };
 
-    let mut check = Connectivity::new(db, record_missing_and_assert_no_duplicate);
+    let mut check = match is_promisor_object(&db) {
+        Some(is_promisor_object) => {
+            Connectivity::new(db, record_missing_and_assert_no_duplicate).with_promisor_objects(is_promisor_object)
+        }
+        None => Connectivity::new(db, record_missing_and_assert_no_duplicate),
+    };
     for commit in commits.into_iter() {
         check.check_commit(commit).expect("commit is present");
     }
@@ -82,3 +108,54 @@ fn missing_trees() {
     );
     assert_eq!(check_missing("treeless", all_commits()), expected);
 }
+
+#[test]
+fn objects_promised_by_promisor_packs_are_not_missing() {
+    for repo_name in ["base", "blobless", "treeless"] {
+        assert_eq!(
+            check_missing_with_promisor_objects(repo_name, all_commits()),
+            HashMap::default(),
+            "{repo_name}: all objects were received from the promisor remote, which promised to provide the missing ones"
+        );
+    }
+}
+
+#[test]
+fn objects_missing_in_promisor_trees_are_still_missing_elsewhere() {
+    // The root tree of the second commit pretends to be a promisor object, so its missing blob `blob-1` is allowed to
+    // be missing there, but not in the root tree of the third commit.
+    let promisor_tree = hex_to_id("20317ffa7614f49b2702a057bf2833918ea9fd24");
+    let expected = hex_to_objects(
+        [
+            "4cdeaab5b01f9a9fbbb2fb6c08404cf12b7bdab1",
+            "c18147dc648481eeb65dc5e66628429a64843327",
+        ],
+        Kind::Blob,
+    );
+    assert_eq!(
+        check_missing_with("blobless", all_commits(), |_db| Some(Box::new(
+            move |id| id == promisor_tree
+        ))),
+        expected,
+        "`tree-1` isn't a promisor object, and `blob-1` is also referenced by the third commit"
+    );
+}
+
+#[test]
+fn trees_missing_in_promisor_trees_are_still_missing_elsewhere() {
+    // The tree `shared` is missing and referenced by the root trees of both commits, but only the root tree of the first
+    // commit pretends to be a promisor object.
+    let promisor_tree = hex_to_id("ffe80a6319c526d2f6c000070353755a0a1ee748");
+    let commits = hex_to_ids([
+        "c2420782ca6204dc98ce02eb50d4fe95ee22da91",
+        "9238b63841e342a4d4a41de3e29f5e0a2995a439",
+    ]);
+    let expected = hex_to_objects(["972653eefab2e73de381dc6b6ac661021d50c952"], Kind::Tree);
+    assert_eq!(
+        check_missing_with("shared-tree-missing", &commits, |_db| Some(Box::new(
+            move |id| id == promisor_tree
+        ))),
+        expected,
+        "the root tree of the second commit isn't a promisor object, so `shared` must not be missing there"
+    );
+}
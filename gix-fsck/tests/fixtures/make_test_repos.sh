@@ -32,3 +32,22 @@ git clone --no-local --no-hardlinks --filter=blob:none ./base blobless
 
 # Treeless (and blobless) clone
 git clone --no-local --no-hardlinks --filter=tree:0 ./base treeless
+
+# A repository whose tree `shared` is referenced by the root trees of two commits, but is missing
+git init shared-tree-missing
+(
+    cd shared-tree-missing
+
+    mkdir shared
+    echo "shared blob" > shared/blob
+    echo "blob 1" > blob-1
+    git add -A
+    git commit -m "commit 1"
+    git rm blob-1
+    echo "blob 2" > blob-2
+    git add -A
+    git commit -m "commit 2"
+
+    shared_tree=$(git rev-parse HEAD:shared)
+    rm ".git/objects/${shared_tree:0:2}/${shared_tree:2}"
+)
@@ -17,7 +17,7 @@ struct Input<'a> {
     after_str: &'a str,
 }
 
-/// Tests all three diff algorithms (Myers, Histogram, MyersMinimal) with:
+/// Tests all diff algorithms (Myers, Histogram, MyersMinimal, Patience) with:
 /// - Computing diffs on arbitrary string inputs
 /// - Postprocessing with no heuristic and line heuristic
 /// - Unified diff printing
@@ -34,11 +34,12 @@ fn do_fuzz(
     // Create interned input
     let input = InternedInput::new(before, after);
 
-    // Test all three diff algorithms
+    // Test all diff algorithms
     for algorithm in [
         Algorithm::Histogram,
         Algorithm::Myers,
         Algorithm::MyersMinimal,
+        Algorithm::Patience,
     ] {
         // Compute diff
         let mut diff = Diff::compute(algorithm, &input);
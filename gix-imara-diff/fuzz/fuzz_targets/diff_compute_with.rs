@@ -39,6 +39,7 @@ fn do_fuzz(data: &[u8]) {
         Algorithm::Histogram,
         Algorithm::Myers,
         Algorithm::MyersMinimal,
+        Algorithm::Patience,
     ] {
         let mut diff = Diff::default();
         diff.compute_with(algorithm, &before_tokens, &after_tokens, 256);
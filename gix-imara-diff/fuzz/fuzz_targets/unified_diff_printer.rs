@@ -32,6 +32,7 @@ fn do_fuzz(
         Algorithm::Histogram,
         Algorithm::Myers,
         Algorithm::MyersMinimal,
+        Algorithm::Patience,
     ] {
         let mut diff = Diff::compute(algorithm, &input);
 
@@ -186,6 +186,22 @@ impl<T> Interner<T> {
     pub fn num_tokens(&self) -> u32 {
         self.tokens.len() as u32
     }
+
+    /// Returns one token for each interned token, in the order they were interned, which is the first
+    /// interned token with the same `key`.
+    ///
+    /// This allows to consider tokens equal that aren't, for instance lines that only differ in whitespace,
+    /// by mapping `before` and `after` through the returned list and passing the result
+    /// to [`Diff::compute_with()`](crate::Diff::compute_with()).
+    /// As each returned token is also interned here, [`num_tokens()`](Self::num_tokens()) can still be used.
+    pub fn equivalent_tokens<'a, K: Hash + Eq>(&'a self, mut key: impl FnMut(&'a T) -> K) -> Vec<Token> {
+        let mut first_token_by_key = hashbrown::HashMap::with_capacity(self.tokens.len());
+        self.tokens
+            .iter()
+            .enumerate()
+            .map(|(idx, token)| *first_token_by_key.entry(key(token)).or_insert(Token(idx as u32)))
+            .collect()
+    }
 }
 
 impl<T: Hash + Eq> Interner<T> {
@@ -12,10 +12,11 @@
 //! not just lists and strings and even allows reusing large parts of the computation when
 //! comparing the same file to multiple different files.
 //!
-//! Imara-diff provides two diff algorithms:
+//! Imara-diff provides three diff algorithms:
 //!
 //! * The linear-space variant of the well known [**Myers** algorithm](http://www.xmailserver.org/diff2.pdf)
 //! * The **Histogram** algorithm which is a variant of the patience diff algorithm.
+//! * The **Patience** algorithm, which is what `git diff --patience` uses.
 //!
 //! Myers algorithm has been enhanced with preprocessing and multiple heuristics to ensure fast runtime in pathological
 //! cases to avoid quadratic time complexity and closely matches the behavior of gnu-diff and git.
@@ -160,6 +161,7 @@ pub use unified_diff::{BasicLineDiffPrinter, UnifiedDiff, UnifiedDiffConfig, Uni
 mod histogram;
 mod intern;
 mod myers;
+mod patience;
 mod postprocess;
 mod slider_heuristic;
 pub mod sources;
@@ -224,6 +226,16 @@ pub enum Algorithm {
     /// a minimal edit sequence.
     /// This can mean significant slowdown in pathological cases.
     MyersMinimal,
+    /// The [`patience` diff algorithm described by Bram Cohen's blog post](https://bramcohen.livejournal.com/73318.html),
+    /// which matches tokens that occur exactly once in both sequences and recurses into the sections between them.
+    ///
+    /// The implementation is ported from `git` and produces the same output as `git diff --patience`.
+    /// Sections without any unique tokens are diffed using Myers algorithm.
+    /// It is usually slower than the `Histogram` algorithm, which is why it should only be used
+    /// if compatibility with `git` is required.
+    ///
+    /// Use [`Diff::compute_anchored_with()`] to force certain tokens to remain unchanged.
+    Patience,
 }
 
 /// Represents the difference between two sequences of tokens.
@@ -257,20 +269,13 @@ impl Diff {
     /// Computes an edit-script that transforms `before` into `after` using
     /// the specified `algorithm`.
     pub fn compute_with(&mut self, algorithm: Algorithm, mut before: &[Token], mut after: &[Token], num_tokens: u32) {
-        assert!(
-            before.len() < i32::MAX as usize,
-            "imara-diff only supports up to {} tokens",
-            i32::MAX
-        );
-        assert!(
-            after.len() < i32::MAX as usize,
-            "imara-diff only supports up to {} tokens",
-            i32::MAX
-        );
-        self.removed.clear();
-        self.added.clear();
-        self.removed.resize(before.len(), false);
-        self.added.resize(after.len(), false);
+        assert_supported_len(before, after);
+        self.reset(before.len(), after.len());
+        if algorithm == Algorithm::Patience {
+            // Like `git`, look for unique tokens in the whole input, including the common prefix and postfix.
+            patience::diff(before, after, &mut self.removed, &mut self.added, &|_| false);
+            return;
+        }
         let common_prefix = strip_common_prefix(&mut before, &mut after) as usize;
         let common_postfix = strip_common_postfix(&mut before, &mut after);
         let range = common_prefix..self.removed.len() - common_postfix as usize;
@@ -281,9 +286,26 @@ impl Diff {
             Algorithm::Histogram => histogram::diff(before, after, removed, added, num_tokens),
             Algorithm::Myers => myers::diff(before, after, removed, added, false),
             Algorithm::MyersMinimal => myers::diff(before, after, removed, added, true),
+            Algorithm::Patience => unreachable!("handled above"),
         }
     }
 
+    /// Computes an edit-script that transforms `before` into `after` using the [`Patience`](Algorithm::Patience)
+    /// algorithm, while keeping all tokens for which `is_anchor` returns `true` unchanged if they occur exactly once
+    /// in both sequences, similar to `git diff --anchored=<text>`.
+    pub fn compute_anchored_with(&mut self, before: &[Token], after: &[Token], is_anchor: impl Fn(Token) -> bool) {
+        assert_supported_len(before, after);
+        self.reset(before.len(), after.len());
+        patience::diff(before, after, &mut self.removed, &mut self.added, &is_anchor);
+    }
+
+    fn reset(&mut self, before_len: usize, after_len: usize) {
+        self.removed.clear();
+        self.added.clear();
+        self.removed.resize(before_len, false);
+        self.added.resize(after_len, false);
+    }
+
     /// Returns the total number of tokens that were added in the second sequence.
     pub fn count_additions(&self) -> u32 {
         self.added.iter().map(|&added| u32::from(added)).sum()
@@ -478,6 +500,19 @@ impl Hunk {
     }
 }
 
+fn assert_supported_len(before: &[Token], after: &[Token]) {
+    assert!(
+        before.len() < i32::MAX as usize,
+        "imara-diff only supports up to {} tokens",
+        i32::MAX
+    );
+    assert!(
+        after.len() < i32::MAX as usize,
+        "imara-diff only supports up to {} tokens",
+        i32::MAX
+    );
+}
+
 /// Yields all [`Hunk`]s in a file in monotonically increasing order.
 /// Monotonically increasing means here that the following holds for any two
 /// consecutive [`Hunk`]s `x` and `y`:
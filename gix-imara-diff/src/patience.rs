@@ -0,0 +1,204 @@
+//! The patience diff algorithm, ported from `xdiff/xpatience.c` in `git`.
+
+use hashbrown::{HashMap, hash_map::Entry as MapEntry};
+
+use crate::intern::Token;
+use crate::myers;
+
+/// Computes a diff using the patience algorithm.
+///
+/// # Parameters
+///
+/// * `before` - The token sequence from the first file, before changes.
+/// * `after` - The token sequence from the second file, after changes.
+/// * `removed` - Output array marking removed tokens
+/// * `added` - Output array marking added tokens
+/// * `is_anchor` - Returns `true` for tokens that should remain unchanged if they are unique in both files.
+pub fn diff(
+    before: &[Token],
+    after: &[Token],
+    removed: &mut [bool],
+    added: &mut [bool],
+    is_anchor: &dyn Fn(Token) -> bool,
+) {
+    run(before, after, removed, added, is_anchor);
+}
+
+/// How often a token that is unique in `before` occurs in `after`.
+#[derive(Clone, Copy)]
+enum Occurrence {
+    /// The token doesn't occur in `after`.
+    None,
+    /// The token occurs exactly once in `after`, at the given position.
+    Unique(u32),
+    /// The token occurs more than once in `after`, or more than once in `before`.
+    NonUnique,
+}
+
+/// A token in `before`, along with its occurrences in `after`.
+struct Entry {
+    /// The position of the first occurrence of the token in `before`.
+    before: u32,
+    /// Where the token occurs in `after`.
+    after: Occurrence,
+    /// If `true`, the token must be part of the longest common sequence if it is unique in both files.
+    anchor: bool,
+    /// The index of the preceding entry in the longest common sequence.
+    previous: Option<usize>,
+}
+
+impl Entry {
+    fn unique_after(&self) -> Option<u32> {
+        match self.after {
+            Occurrence::Unique(pos) => Some(pos),
+            Occurrence::None | Occurrence::NonUnique => None,
+        }
+    }
+}
+
+fn run(before: &[Token], after: &[Token], removed: &mut [bool], added: &mut [bool], is_anchor: &dyn Fn(Token) -> bool) {
+    if before.is_empty() {
+        added.fill(true);
+        return;
+    } else if after.is_empty() {
+        removed.fill(true);
+        return;
+    }
+
+    let mut entries = Vec::<Entry>::new();
+    let mut entry_by_token = HashMap::<Token, usize>::with_capacity(before.len());
+    for (pos, &token) in before.iter().enumerate() {
+        match entry_by_token.entry(token) {
+            MapEntry::Occupied(entry) => entries[*entry.get()].after = Occurrence::NonUnique,
+            MapEntry::Vacant(entry) => {
+                entry.insert(entries.len());
+                entries.push(Entry {
+                    before: pos as u32,
+                    after: Occurrence::None,
+                    anchor: is_anchor(token),
+                    previous: None,
+                });
+            }
+        }
+    }
+    let mut has_matches = false;
+    for (pos, token) in after.iter().enumerate() {
+        if let Some(&idx) = entry_by_token.get(token) {
+            has_matches = true;
+            let entry = &mut entries[idx];
+            entry.after = match entry.after {
+                Occurrence::None => Occurrence::Unique(pos as u32),
+                Occurrence::Unique(_) | Occurrence::NonUnique => Occurrence::NonUnique,
+            };
+        }
+    }
+
+    if !has_matches {
+        removed.fill(true);
+        added.fill(true);
+        return;
+    }
+
+    match longest_common_sequence(&mut entries) {
+        Some(sequence) => walk_common_sequence(&entries, &sequence, before, after, removed, added, is_anchor),
+        None => myers::diff(before, after, removed, added, false),
+    }
+}
+
+/// Return the indices of the entries that form the longest sequence of tokens that are unique in both files and
+/// appear in the same order in both, or `None` if there is no such token.
+///
+/// Anchors are always part of the sequence, which is why entries that come before an anchor can't replace entries in it.
+fn longest_common_sequence(entries: &mut [Entry]) -> Option<Vec<usize>> {
+    // The entry that ends the currently longest sequence of each length.
+    let mut sequence = vec![0; entries.len()];
+    let mut longest = 0;
+    let mut anchor_pos = None;
+
+    for idx in 0..entries.len() {
+        let Some(after) = entries[idx].unique_after() else {
+            continue;
+        };
+        let pos = sequence[..longest]
+            .partition_point(|&other: &usize| entries[other].unique_after().expect("only unique entries") < after);
+        entries[idx].previous = pos.checked_sub(1).map(|prev| sequence[prev]);
+        if anchor_pos.is_some_and(|anchor_pos| pos <= anchor_pos) {
+            continue;
+        }
+        sequence[pos] = idx;
+        if entries[idx].anchor {
+            anchor_pos = Some(pos);
+            longest = pos + 1;
+        } else if pos == longest {
+            longest += 1;
+        }
+    }
+
+    if longest == 0 {
+        return None;
+    }
+    let mut out = Vec::with_capacity(longest);
+    let mut idx = Some(sequence[longest - 1]);
+    while let Some(current) = idx {
+        out.push(current);
+        idx = entries[current].previous;
+    }
+    out.reverse();
+    Some(out)
+}
+
+/// Match all tokens in `sequence`, along with equal tokens around them, and diff the sections between them recursively.
+fn walk_common_sequence(
+    entries: &[Entry],
+    sequence: &[usize],
+    before: &[Token],
+    after: &[Token],
+    removed: &mut [bool],
+    added: &mut [bool],
+    is_anchor: &dyn Fn(Token) -> bool,
+) {
+    let (mut pos1, mut pos2) = (0, 0);
+    let mut sequence = sequence.iter().map(|&idx| &entries[idx]).peekable();
+    loop {
+        let current = sequence.next();
+        // Grow the ranges of equal tokens around the next common token.
+        let (mut next1, mut next2) = match current {
+            Some(entry) => (
+                entry.before as usize,
+                entry.unique_after().expect("only unique entries") as usize,
+            ),
+            None => (before.len(), after.len()),
+        };
+        if current.is_some() {
+            while next1 > pos1 && next2 > pos2 && before[next1 - 1] == after[next2 - 1] {
+                next1 -= 1;
+                next2 -= 1;
+            }
+        }
+        while pos1 < next1 && pos2 < next2 && before[pos1] == after[pos2] {
+            pos1 += 1;
+            pos2 += 1;
+        }
+
+        if next1 > pos1 || next2 > pos2 {
+            run(
+                &before[pos1..next1],
+                &after[pos2..next2],
+                &mut removed[pos1..next1],
+                &mut added[pos2..next2],
+                is_anchor,
+            );
+        }
+
+        let Some(mut entry) = current else {
+            return;
+        };
+        while let Some(next) = sequence.next_if(|next| {
+            next.before == entry.before + 1 && next.unique_after() == entry.unique_after().map(|pos| pos + 1)
+        }) {
+            entry = next;
+        }
+        pos1 = entry.before as usize + 1;
+        pos2 = entry.unique_after().expect("only unique entries") as usize + 1;
+    }
+}
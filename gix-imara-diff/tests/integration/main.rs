@@ -9,7 +9,7 @@ use gix_imara_diff::InternedInput;
 use gix_imara_diff::sources::words;
 use gix_imara_diff::{Algorithm, Diff, UnifiedDiffConfig};
 
-const ALL_ALGORITHMS: [Algorithm; 2] = [Algorithm::Histogram, Algorithm::Myers];
+const ALL_ALGORITHMS: [Algorithm; 3] = [Algorithm::Histogram, Algorithm::Myers, Algorithm::Patience];
 
 mod fuzzed {
     use std::time::{Duration, Instant};
@@ -35,7 +35,12 @@ mod fuzzed {
     ) {
         let input = InternedInput::new(before, after);
 
-        for algorithm in [Algorithm::Histogram, Algorithm::Myers, Algorithm::MyersMinimal] {
+        for algorithm in [
+            Algorithm::Histogram,
+            Algorithm::Myers,
+            Algorithm::MyersMinimal,
+            Algorithm::Patience,
+        ] {
             let mut diff = Diff::compute(algorithm, &input);
 
             let _ = diff.count_additions();
@@ -94,6 +99,36 @@ fn words_tokenizer() {
     );
 }
 
+#[test]
+fn equivalent_tokens() {
+    let before = "a\nB\nc\n";
+    let after = "A\nb\nc\nd\n";
+    let input = InternedInput::new(before, after);
+    let equivalent = input.interner.equivalent_tokens(|line| line.to_ascii_lowercase());
+    assert_eq!(equivalent.len(), input.interner.num_tokens() as usize);
+
+    let map =
+        |tokens: &[gix_imara_diff::Token]| -> Vec<_> { tokens.iter().map(|t| equivalent[t.0 as usize]).collect() };
+    let (before_tokens, after_tokens) = (map(&input.before), map(&input.after));
+    assert_eq!(
+        before_tokens[..],
+        after_tokens[..3],
+        "tokens with the same key map to the token interned first"
+    );
+
+    for algorithm in ALL_ALGORITHMS {
+        let mut diff = Diff::default();
+        diff.compute_with(algorithm, &before_tokens, &after_tokens, input.interner.num_tokens());
+        assert_eq!(diff.count_removals(), 0, "{algorithm:?}");
+        assert_eq!(diff.count_additions(), 1, "{algorithm:?}: only the last line was added");
+        assert_eq!(
+            Diff::compute(algorithm, &input).count_additions(),
+            3,
+            "{algorithm:?}: without mapping, case matters"
+        );
+    }
+}
+
 #[test]
 fn replace() {
     let before = r#"fn foo() -> Bar{
@@ -140,6 +175,61 @@ fn foo() -> Bar{
     }
 }
 
+#[test]
+fn patience_prefers_unique_lines() {
+    let before = "a\nx\nb\nx\nc\nd\ne\n";
+    let after = "c\nd\nx\na\nx\nb\ne\n";
+    let input = InternedInput::new(before, after);
+    let unified = |diff: &Diff| {
+        diff.unified_diff(
+            &BasicLineDiffPrinter(&input.interner),
+            UnifiedDiffConfig::default(),
+            &input,
+        )
+        .to_string()
+    };
+
+    let diff = Diff::compute(Algorithm::Patience, &input);
+    expect![[r#"
+        @@ -1,7 +1,7 @@
+        -a
+        -x
+        -b
+        -x
+         c
+         d
+        +x
+        +a
+        +x
+        +b
+         e
+    "#]]
+    .assert_eq(&unified(&diff));
+
+    let mut anchored = Diff::default();
+    let b = input.after[5];
+    anchored.compute_anchored_with(&input.before, &input.after, |token| token == b);
+    expect![[r#"
+        @@ -1,7 +1,7 @@
+        +c
+        +d
+        +x
+         a
+         x
+         b
+        -x
+        -c
+        -d
+         e
+    "#]]
+    .assert_eq(&unified(&anchored));
+    assert_eq!(
+        unified(&Diff::compute(Algorithm::Histogram, &input)),
+        unified(&anchored),
+        "anchors force lines to stay unchanged, like `git diff --anchored=b`"
+    );
+}
+
 #[test]
 fn identical_files() {
     let file = r#"fn foo() -> Bar{
@@ -27,21 +27,60 @@ impl<'input, 'data> Merge<'input, 'data> {
         ancestor: &'data [u8],
         other: &'data [u8],
         diff_algorithm: imara_diff::Algorithm,
+    ) -> Merge<'input, 'data> {
+        Self::new_with_whitespace(input, current, ancestor, other, diff_algorithm, Default::default())
+    }
+
+    /// Like [`new()`](Self::new()), but compare lines while ignoring differences in `whitespace`.
+    ///
+    /// See [`Options::whitespace`] for details.
+    pub fn new_with_whitespace(
+        input: &'input mut imara_diff::InternedInput<&'data [u8]>,
+        current: &'data [u8],
+        ancestor: &'data [u8],
+        other: &'data [u8],
+        diff_algorithm: imara_diff::Algorithm,
+        whitespace: gix_diff::blob::Whitespace,
     ) -> Merge<'input, 'data> {
         input.update_before(tokens(ancestor));
         input.update_after(tokens(current));
-
-        let hunks = collect_hunks(diff_algorithm, input, Side::Current, Vec::new());
-
         let current_tokens = std::mem::take(&mut input.after);
         input.update_after(tokens(other));
 
-        let mut hunks = collect_hunks(diff_algorithm, input, Side::Other, hunks);
+        let equivalent = whitespace
+            .affects_comparison()
+            .then(|| whitespace.equivalent_tokens(&input.interner));
+        let hunks = collect_hunks(
+            diff_algorithm,
+            input,
+            &current_tokens,
+            equivalent.as_deref(),
+            Side::Current,
+            Vec::new(),
+        );
+        let mut hunks = collect_hunks(
+            diff_algorithm,
+            input,
+            &input.after,
+            equivalent.as_deref(),
+            Side::Other,
+            hunks,
+        );
+        // Like `git`, use our version of lines that didn't change, unless only their side changed.
+        let common_tokens = equivalent.is_some().then(|| {
+            let (tokens, side) = if hunks.iter().any(|hunk| hunk.side == Side::Current) {
+                (current_tokens.as_slice(), Side::Current)
+            } else {
+                (input.after.as_slice(), Side::Other)
+            };
+            common_tokens(&input.before, tokens, hunks.iter().filter(|hunk| hunk.side == side))
+        });
         hunks.sort_by_key(|a| a.before.start);
 
         Merge {
             input,
             current_tokens,
+            common_tokens,
             hunks,
         }
     }
@@ -65,8 +104,9 @@ impl<'input, 'data> Merge<'input, 'data> {
         out.clear();
         let input = self.input;
         let current_tokens = &self.current_tokens;
+        let common_tokens = self.common_tokens.as_deref().unwrap_or(&input.before);
         if self.hunks.is_empty() {
-            write_ancestor(input, 0, input.before.len(), out);
+            write_ancestor(input, common_tokens, 0, input.before.len(), out);
             return Resolution::Complete;
         }
 
@@ -78,7 +118,13 @@ impl<'input, 'data> Merge<'input, 'data> {
         while take_intersecting(&mut hunks, &mut current_hunks, &mut intersecting).is_some() {
             if intersecting.is_empty() {
                 let hunk = current_hunks.pop().expect("always pushed during intersection check");
-                write_ancestor(input, ancestor_integrated_until, hunk.before.start as usize, out);
+                write_ancestor(
+                    input,
+                    common_tokens,
+                    ancestor_integrated_until,
+                    hunk.before.start as usize,
+                    out,
+                );
                 ancestor_integrated_until = hunk.before.end;
                 write_hunks(std::slice::from_ref(&hunk), input, current_tokens, out);
                 continue;
@@ -111,7 +157,13 @@ impl<'input, 'data> Merge<'input, 'data> {
                     let (front_hunks, back_hunks) = hunks_front_and_back.split_at(num_hunks_front);
                     let first_hunk = first_hunk(front_hunks, our_hunks, their_hunks, back_hunks);
                     let last_hunk = last_hunk(front_hunks, our_hunks, their_hunks, back_hunks);
-                    write_ancestor(input, ancestor_integrated_until, first_hunk.before.start as usize, out);
+                    write_ancestor(
+                        input,
+                        common_tokens,
+                        ancestor_integrated_until,
+                        first_hunk.before.start as usize,
+                        out,
+                    );
                     write_hunks(front_hunks, input, current_tokens, out);
                     // DEVIATION: this makes tests (mostly) pass, but probably is very different from what Git does.
                     let hunk_storage;
@@ -187,7 +239,13 @@ impl<'input, 'data> Merge<'input, 'data> {
                         their_hunks
                     };
                     if let Some(first_hunk) = hunks_to_write.first() {
-                        write_ancestor(input, ancestor_integrated_until, first_hunk.before.start as usize, out);
+                        write_ancestor(
+                            input,
+                            common_tokens,
+                            ancestor_integrated_until,
+                            first_hunk.before.start as usize,
+                            out,
+                        );
                     }
                     write_hunks(hunks_to_write, input, current_tokens, out);
                     if let Some(last_hunk) = hunks_to_write.last() {
@@ -210,7 +268,13 @@ impl<'input, 'data> Merge<'input, 'data> {
                     }
                     let (front_hunks, back_hunks) = hunks_front_and_back.split_at(num_hunks_front);
                     let first_hunk = first_hunk(front_hunks, our_hunks, their_hunks, back_hunks);
-                    write_ancestor(input, ancestor_integrated_until, first_hunk.before.start as usize, out);
+                    write_ancestor(
+                        input,
+                        common_tokens,
+                        ancestor_integrated_until,
+                        first_hunk.before.start as usize,
+                        out,
+                    );
                     write_hunks(front_hunks, input, current_tokens, out);
                     assure_ends_with_nl(out, detect_line_ending_or_nl(front_hunks, input, current_tokens));
                     write_hunks(our_hunks, input, current_tokens, out);
@@ -225,7 +289,7 @@ impl<'input, 'data> Merge<'input, 'data> {
                 }
             }
         }
-        write_ancestor(input, ancestor_integrated_until, input.before.len(), out);
+        write_ancestor(input, common_tokens, ancestor_integrated_until, input.before.len(), out);
 
         resolution
     }
@@ -257,11 +321,12 @@ pub fn merge<'a>(
     other: &'a [u8],
     Options {
         diff_algorithm,
+        whitespace,
         conflict,
     }: Options,
 ) -> Resolution {
     out.clear();
-    let merge = Merge::new(input, current, ancestor, other, diff_algorithm);
+    let merge = Merge::new_with_whitespace(input, current, ancestor, other, diff_algorithm, whitespace);
     merge.run(
         out,
         Labels {
@@ -273,6 +338,25 @@ pub fn merge<'a>(
     )
 }
 
+/// Return `ancestor` with all tokens that are unchanged in `side` according to `side_hunks` replaced by
+/// their counterpart in `side`.
+fn common_tokens<'a>(
+    ancestor: &[imara_diff::Token],
+    side: &[imara_diff::Token],
+    side_hunks: impl Iterator<Item = &'a Hunk>,
+) -> Vec<imara_diff::Token> {
+    let mut out = Vec::with_capacity(ancestor.len());
+    let mut side_pos = 0;
+    for hunk in side_hunks {
+        let unchanged = hunk.before.start as usize - out.len();
+        out.extend_from_slice(&side[side_pos..][..unchanged]);
+        out.extend_from_slice(&ancestor[hunk.before.start as usize..hunk.before.end as usize]);
+        side_pos = hunk.after.end as usize;
+    }
+    out.extend_from_slice(&side[side_pos..]);
+    out
+}
+
 fn first_hunk<'a>(front: &'a [Hunk], ours: &'a [Hunk], theirs: &'a [Hunk], back: &'a [Hunk]) -> &'a Hunk {
     front
         .first()
@@ -72,6 +72,13 @@ pub struct Options {
     /// Determine of the diff will be performed.
     /// Defaults to [`imara_diff::Algorithm::Myers`].
     pub diff_algorithm: imara_diff::Algorithm,
+    /// Which differences in whitespace to ignore when comparing lines, like the `ignore-space-change`, `ignore-all-space`,
+    /// `ignore-space-at-eol` and `ignore-cr-at-eol` strategy options of `git merge`.
+    ///
+    /// Lines that only changed in ignored whitespace are considered unchanged, and lines that are unchanged on both sides
+    /// are taken from *our* side, unless only *their* side has changes.
+    /// [`ignore_blank_lines`](gix_diff::blob::Whitespace::ignore_blank_lines) has no effect here.
+    pub whitespace: gix_diff::blob::Whitespace,
     /// Decide what to do to automatically resolve conflicts, or to keep them.
     pub conflict: Conflict,
 }
@@ -81,6 +88,7 @@ impl Default for Options {
         Options {
             conflict: Default::default(),
             diff_algorithm: imara_diff::Algorithm::Myers,
+            whitespace: Default::default(),
         }
     }
 }
@@ -135,6 +143,9 @@ impl Default for Conflict {
 pub struct Merge<'input, 'data> {
     input: &'input imara_diff::InternedInput<&'data [u8]>,
     current_tokens: Vec<imara_diff::Token>,
+    /// The tokens of the ancestor, with lines that are unchanged replaced by the version of the side they are taken from,
+    /// if whitespace is ignored and these might differ.
+    common_tokens: Option<Vec<imara_diff::Token>>,
     hunks: Vec<utils::Hunk>,
 }
 
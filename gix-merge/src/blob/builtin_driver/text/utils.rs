@@ -1,4 +1,4 @@
-use std::{iter::Peekable, ops::Range};
+use std::{borrow::Cow, iter::Peekable, ops::Range};
 
 use bstr::{BStr, ByteSlice, ByteVec};
 
@@ -105,11 +105,18 @@ pub fn write_conflict_marker(out: &mut Vec<u8>, marker: u8, label: Option<&BStr>
     out.push_str(nl);
 }
 
-pub fn write_ancestor(input: &imara_diff::InternedInput<&[u8]>, from: u32, to: usize, out: &mut Vec<u8>) {
+/// Write the lines between `from` and `to` of the ancestor, as they are in `ancestor_tokens`.
+pub fn write_ancestor(
+    input: &imara_diff::InternedInput<&[u8]>,
+    ancestor_tokens: &[imara_diff::Token],
+    from: u32,
+    to: usize,
+    out: &mut Vec<u8>,
+) {
     if to < from as usize {
         return;
     }
-    if let Some(tokens) = input.before.get(from as usize..to) {
+    if let Some(tokens) = ancestor_tokens.get(from as usize..to) {
         write_tokens(&input.interner, tokens, out);
     }
 }
@@ -469,14 +476,33 @@ pub struct Hunk {
     pub side: Side,
 }
 
+/// Diff the ancestor in `input` with `after`, the tokens of `side`, and add the resulting hunks to `hunks`.
+/// If `equivalent` is set, tokens are mapped through it before diffing.
 pub fn collect_hunks(
     algorithm: imara_diff::Algorithm,
     input: &imara_diff::InternedInput<&[u8]>,
+    after: &[imara_diff::Token],
+    equivalent: Option<&[imara_diff::Token]>,
     side: Side,
     mut hunks: Vec<Hunk>,
 ) -> Vec<Hunk> {
-    let mut diff = imara_diff::Diff::compute(algorithm, input);
-    diff.postprocess_lines(input);
+    let map = |tokens: &[imara_diff::Token]| -> Vec<_> {
+        let equivalent = equivalent.expect("only called if set");
+        tokens.iter().map(|token| equivalent[token.0 as usize]).collect()
+    };
+    let (before, after) = match equivalent {
+        Some(_) => (Cow::Owned(map(&input.before)), Cow::Owned(map(after))),
+        None => (Cow::Borrowed(input.before.as_slice()), Cow::Borrowed(after)),
+    };
+    let mut diff = imara_diff::Diff::default();
+    diff.compute_with(algorithm, &before, &after, input.interner.num_tokens());
+    diff.postprocess_with(
+        &before,
+        &after,
+        imara_diff::IndentHeuristic::new(|token| {
+            imara_diff::IndentLevel::for_ascii_line(input.interner[token].iter().copied(), 8)
+        }),
+    );
     hunks.extend(diff.hunks().map(|hunk| Hunk {
         before: hunk.before,
         after: hunk.after,
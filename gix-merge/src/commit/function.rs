@@ -4,7 +4,7 @@ use gix_object::FindExt;
 
 use crate::{
     blob::builtin_driver,
-    commit::{Error, Options},
+    commit::{Error, Options, Strategy},
 };
 
 /// Like [`tree()`](crate::tree()), but it takes only two commits, `our_commit` and `their_commit` to automatically
@@ -33,6 +33,9 @@ use crate::{
 ///
 /// ### Notes
 ///
+/// With the [`ours` strategy](Strategy::Ours), *their* tree isn't looked at, and the tree of `our_commit` is returned
+/// without conflicts.
+///
 /// When merging merge-bases recursively, the options are adjusted automatically to act like Git, i.e. merge binary
 /// blobs and resolve with *ours*, while resorting to using the base/ancestor in case of unresolvable conflicts.
 ///
@@ -49,6 +52,7 @@ pub fn commit<'objects>(
     graph: &mut gix_revwalk::Graph<'_, '_, gix_revwalk::graph::Commit<gix_revision::merge_base::Flags>>,
     diff_resource_cache: &mut gix_diff::blob::Platform,
     blob_merge: &mut crate::blob::Platform,
+    submodules: &mut dyn crate::tree::submodule::Find,
     objects: &'objects (impl gix_object::FindObjectOrHeader + gix_object::Write),
     abbreviate_hash: &mut dyn FnMut(&gix_hash::oid) -> String,
     options: Options,
@@ -65,7 +69,7 @@ pub fn commit<'objects>(
             abbreviate_hash(base_commit.first()).into(),
         ),
         Some(base_commits) => {
-            let virtual_base_tree = if options.use_first_merge_base {
+            let virtual_base_tree = if options.use_first_merge_base || options.strategy == Strategy::Ours {
                 commit_to_tree(*base_commits.first())?
             } else {
                 let mut base_commits: Vec<_> = base_commits.into();
@@ -78,6 +82,7 @@ pub fn commit<'objects>(
                     graph,
                     diff_resource_cache,
                     blob_merge,
+                    submodules,
                     objects,
                     abbreviate_hash,
                     options.tree_merge.clone(),
@@ -105,6 +110,19 @@ pub fn commit<'objects>(
     }
 
     let our_tree_id = objects.find_commit(&our_commit, &mut state.buf1)?.tree();
+    if options.strategy == Strategy::Ours {
+        let our_tree = objects.find_tree(&our_tree_id, &mut state.buf1)?;
+        return Ok(super::Outcome {
+            tree_merge: crate::tree::Outcome {
+                tree: gix_object::tree::Editor::new(our_tree.to_owned(), objects, our_tree_id.kind()),
+                conflicts: Vec::new(),
+                failed_on_first_unresolved_conflict: false,
+            },
+            merge_bases,
+            merge_base_tree_id,
+            virtual_merge_bases,
+        });
+    }
     let their_tree_id = objects.find_commit(&their_commit, &mut state.buf1)?.tree();
 
     let outcome = crate::tree(
@@ -117,6 +135,7 @@ pub fn commit<'objects>(
         &mut state,
         diff_resource_cache,
         blob_merge,
+        submodules,
         options.tree_merge,
     )?;
 
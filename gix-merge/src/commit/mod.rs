@@ -30,6 +30,22 @@ pub struct Options {
     // TODO: test
     #[doc(alias = "no_recursive", alias = "git2")]
     pub use_first_merge_base: bool,
+    /// The way both commits are merged.
+    pub strategy: Strategy,
+}
+
+/// Determine how commits are merged, similar to `git merge -s <strategy>`, for use in [`Options::strategy`].
+#[derive(Default, Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Strategy {
+    /// Merge the trees of both commits, while merging multiple merge-bases into one if needed,
+    /// similar to the `ort` strategy.
+    #[default]
+    #[doc(alias = "recursive")]
+    Ort,
+    /// Ignore all changes of *their* commit and use *our* tree as the result of the merge, like the `ours` strategy.
+    ///
+    /// This is useful to record that a branch was merged while superseding all of its changes.
+    Ours,
 }
 
 /// The result of [`commit()`](crate::commit()).
@@ -39,7 +55,8 @@ pub struct Outcome<'a> {
     pub tree_merge: crate::tree::Outcome<'a>,
     /// The tree id of the base commit we used. This is either…
     /// * the single merge-base we found
-    /// * the first of multiple merge-bases if [`use_first_merge_base`](Options::use_first_merge_base) was `true`.
+    /// * the first of multiple merge-bases if [`use_first_merge_base`](Options::use_first_merge_base) was `true`,
+    ///   or if the [`ours` strategy](Strategy::Ours) was used.
     /// * the merged tree of all merge-bases, which then isn't linked to an actual commit.
     /// * an empty tree, if [`allow_missing_merge_base`](Options::allow_missing_merge_base) is enabled.
     pub merge_base_tree_id: gix_hash::ObjectId,
@@ -58,3 +75,7 @@ pub(super) mod function;
 ///
 pub mod virtual_merge_base;
 pub use virtual_merge_base::function::virtual_merge_base;
+
+///
+pub mod octopus;
+pub use octopus::function::octopus;
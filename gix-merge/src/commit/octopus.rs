@@ -0,0 +1,273 @@
+/// A way to configure [`commit::octopus()`](crate::commit::octopus()).
+#[derive(Default, Debug, Clone)]
+pub struct Options {
+    /// If `true`, merging unrelated commits is allowed, with the merge-base being assumed as empty tree.
+    pub allow_missing_merge_base: bool,
+    /// Options to define how trees should be merged.
+    pub tree_merge: crate::tree::Options,
+    /// If `true`, do not merge multiple merge-bases into one. Instead, just use the first one.
+    pub use_first_merge_base: bool,
+    /// Determine which conflicts make the octopus merge fail.
+    pub treat_as_unresolved: crate::tree::TreatAsUnresolved,
+    /// If `true`, *our* commit is always the first of the [parents](Outcome::parents), even if it is contained
+    /// in one of *their* commits, similar to `git merge --no-ff`.
+    pub no_fast_forward: bool,
+}
+
+/// The result of [`commit::octopus()`](crate::commit::octopus()).
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    /// The id of the written tree that contains the changes of all merged commits.
+    pub tree_id: gix_hash::ObjectId,
+    /// The commits to use as parents of the merge commit, in order.
+    ///
+    /// Like in Git, commits that are contained in other commits of the merge aren't listed, which also
+    /// removes *our* commit unless [`no_fast_forward`](Options::no_fast_forward) is set.
+    /// If only a single commit is left, no merge commit is needed as the result is the tree of this commit.
+    pub parents: Vec<gix_hash::ObjectId>,
+    /// What happened to each of *their* commits, in the order they were merged.
+    pub steps: Vec<Step>,
+    /// A list of virtual commits that were created to merge multiple merge-bases into one, across all steps.
+    /// As they are not reachable by anything they will be garbage collected.
+    pub virtual_merge_bases: Vec<gix_hash::ObjectId>,
+}
+
+/// What happened to one of *their* commits during an [octopus merge](crate::commit::octopus()).
+#[derive(Debug, Clone)]
+pub enum Step {
+    /// The commit was already contained in what was merged so far, so nothing was done.
+    AlreadyUpToDate {
+        /// The commit that was skipped.
+        commit_id: gix_hash::ObjectId,
+    },
+    /// The commit contained everything that was merged so far, so its tree was used as is.
+    ///
+    /// This can only happen before the first actual merge.
+    FastForward {
+        /// The commit that was fast-forwarded to.
+        commit_id: gix_hash::ObjectId,
+    },
+    /// The tree of the commit was merged with the tree of what was merged so far.
+    Merged {
+        /// The commit that was merged.
+        commit_id: gix_hash::ObjectId,
+        /// The tree that was used as merge-base.
+        merge_base_tree_id: gix_hash::ObjectId,
+        /// The conflicts that were encountered and resolved automatically.
+        conflicts: Vec<crate::tree::Conflict>,
+    },
+}
+
+/// The error returned by [`commit::octopus()`](crate::commit::octopus()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("An octopus merge needs at least one commit to merge")]
+    NothingToMerge,
+    #[error("Failed to obtain the merge base between the commits to be merged")]
+    MergeBase(#[from] gix_revision::merge_base::Error),
+    #[error(transparent)]
+    VirtualMergeBase(#[from] crate::commit::Error),
+    #[error(transparent)]
+    MergeTree(#[from] crate::tree::Error),
+    #[error("Failed to write the merged tree")]
+    WriteTree(#[from] gix_object::write::Error),
+    #[error("Could not find a commit to extract its tree")]
+    FindCommit(#[from] gix_object::find::existing_object::Error),
+    #[error("No common ancestor between {their_commit_id} and the commits merged before it")]
+    NoMergeBase { their_commit_id: gix_hash::ObjectId },
+    #[error(
+        "Merging {their_commit_id} caused conflicts that need manual resolution, which isn't possible in an octopus merge"
+    )]
+    Conflict {
+        /// The commit whose merge caused the conflicts.
+        their_commit_id: gix_hash::ObjectId,
+        /// All conflicts of the failed merge, resolved or not.
+        conflicts: Vec<crate::tree::Conflict>,
+    },
+}
+
+pub(super) mod function {
+    use gix_object::FindExt;
+
+    use super::{Error, Options, Outcome, Step};
+    use crate::blob::builtin_driver;
+
+    /// Merge all of `their_commits` into `our_commit` one after another, like `git merge -s octopus` does,
+    /// and return the merged tree along with the parents for the merge commit.
+    ///
+    /// Each commit is merged with the result of merging all commits before it, using the merge-bases between it and
+    /// all commits merged so far. Commits that are already contained are skipped, and as long as nothing was merged,
+    /// commits that contain everything so far are fast-forwarded to.
+    /// The merge fails if a merge has conflicts which are considered [unresolved](Options::treat_as_unresolved), as
+    /// an octopus merge is meant for combining many branches that don't need manual intervention.
+    ///
+    /// The `graph` is used to find merge-bases and to determine the parents, and can also act as cache
+    /// to speed up subsequent merge-base queries.
+    ///
+    /// `diff_resource_cache`, `blob_merge`, `submodules` and `objects` are passed to [`tree()`](crate::tree())
+    /// for each merge, and `objects` is also used to write the merged trees.
+    /// Use `abbreviate_hash(id)` to shorten the given `id` according to standard git shortening rules,
+    /// which is used for the labels of conflict markers.
+    ///
+    /// ### Deviation
+    ///
+    /// * Git merges the trees of an octopus merge without rename tracking, whereas renames are tracked here
+    ///   as configured in [`Options::tree_merge`], so more merges may succeed.
+    #[allow(clippy::too_many_arguments)]
+    pub fn octopus(
+        our_commit: gix_hash::ObjectId,
+        their_commits: &[gix_hash::ObjectId],
+        graph: &mut gix_revwalk::Graph<'_, '_, gix_revwalk::graph::Commit<gix_revision::merge_base::Flags>>,
+        diff_resource_cache: &mut gix_diff::blob::Platform,
+        blob_merge: &mut crate::blob::Platform,
+        submodules: &mut dyn crate::tree::submodule::Find,
+        objects: &(impl gix_object::FindObjectOrHeader + gix_object::Write),
+        abbreviate_hash: &mut dyn FnMut(&gix_hash::oid) -> String,
+        options: Options,
+    ) -> Result<Outcome, Error> {
+        if their_commits.is_empty() {
+            return Err(Error::NothingToMerge);
+        }
+        let mut state = gix_diff::tree::State::default();
+        let mut buf = Vec::new();
+        let mut commit_to_tree = |commit_id: &gix_hash::oid| objects.find_commit(commit_id, &mut buf).map(|c| c.tree());
+
+        let mut merged_commits = vec![our_commit];
+        let mut tree_id = commit_to_tree(&our_commit)?;
+        let mut steps = Vec::with_capacity(their_commits.len());
+        let mut virtual_merge_bases = Vec::new();
+        let mut did_merge = false;
+        for &their_commit in their_commits {
+            let merge_bases = gix_revision::merge_base(their_commit, &merged_commits, graph)?;
+            if merge_bases.as_ref().is_some_and(|bases| bases.contains(&their_commit)) {
+                steps.push(Step::AlreadyUpToDate {
+                    commit_id: their_commit,
+                });
+                continue;
+            }
+            if !did_merge
+                && merge_bases
+                    .as_ref()
+                    .is_some_and(|bases| bases.len() == 1 && *bases.first() == merged_commits[0])
+            {
+                merged_commits = vec![their_commit];
+                tree_id = commit_to_tree(&their_commit)?;
+                steps.push(Step::FastForward {
+                    commit_id: their_commit,
+                });
+                continue;
+            }
+
+            did_merge = true;
+            let (merge_base_tree_id, ancestor) = match merge_bases {
+                Some(base_commits) if base_commits.len() == 1 || options.use_first_merge_base => (
+                    commit_to_tree(base_commits.first())?,
+                    abbreviate_hash(base_commits.first()),
+                ),
+                Some(base_commits) => {
+                    let mut base_commits: Vec<_> = base_commits.into();
+                    let first = base_commits.pop().expect("at least two");
+                    let second = base_commits.pop().expect("at least one left");
+                    let out = crate::commit::virtual_merge_base(
+                        first,
+                        second,
+                        base_commits,
+                        graph,
+                        diff_resource_cache,
+                        blob_merge,
+                        submodules,
+                        objects,
+                        abbreviate_hash,
+                        options.tree_merge.clone(),
+                    )?;
+                    virtual_merge_bases.extend(out.virtual_merge_bases);
+                    (out.tree_id, "merged common ancestors".into())
+                }
+                None if options.allow_missing_merge_base => {
+                    (gix_hash::ObjectId::empty_tree(our_commit.kind()), "empty tree".into())
+                }
+                None => {
+                    return Err(Error::NoMergeBase {
+                        their_commit_id: their_commit,
+                    });
+                }
+            };
+
+            let their_tree_id = commit_to_tree(&their_commit)?;
+            let their_name = abbreviate_hash(&their_commit);
+            let labels = builtin_driver::text::Labels {
+                ancestor: Some(ancestor.as_str().into()),
+                current: Some("HEAD".into()),
+                other: Some(their_name.as_str().into()),
+            };
+            let mut outcome = crate::tree(
+                &merge_base_tree_id,
+                &tree_id,
+                &their_tree_id,
+                labels,
+                objects,
+                |buf| objects.write_buf(gix_object::Kind::Blob, buf),
+                &mut state,
+                diff_resource_cache,
+                blob_merge,
+                submodules,
+                options.tree_merge.clone(),
+            )?;
+            if outcome.has_unresolved_conflicts(options.treat_as_unresolved) {
+                return Err(Error::Conflict {
+                    their_commit_id: their_commit,
+                    conflicts: outcome.conflicts,
+                });
+            }
+            tree_id = outcome.tree.write(|tree| objects.write(tree))?;
+            merged_commits.push(their_commit);
+            steps.push(Step::Merged {
+                commit_id: their_commit,
+                merge_base_tree_id,
+                conflicts: outcome.conflicts,
+            });
+        }
+
+        let parents = parents(our_commit, their_commits, graph, options.no_fast_forward)?;
+        Ok(Outcome {
+            tree_id,
+            parents,
+            steps,
+            virtual_merge_bases,
+        })
+    }
+
+    /// Return `our_commit` and `their_commits` without duplicates and without the commits that are contained in others,
+    /// but keep `our_commit` as first parent if `no_fast_forward` is `true`, like Git does.
+    fn parents(
+        our_commit: gix_hash::ObjectId,
+        their_commits: &[gix_hash::ObjectId],
+        graph: &mut gix_revwalk::Graph<'_, '_, gix_revwalk::graph::Commit<gix_revision::merge_base::Flags>>,
+        no_fast_forward: bool,
+    ) -> Result<Vec<gix_hash::ObjectId>, gix_revision::merge_base::Error> {
+        let mut commits = vec![our_commit];
+        for commit in their_commits {
+            if !commits.contains(commit) {
+                commits.push(*commit);
+            }
+        }
+        let mut parents = Vec::with_capacity(commits.len());
+        for (idx, commit) in commits.iter().enumerate() {
+            let mut is_contained = false;
+            for (other_idx, other) in commits.iter().enumerate() {
+                if idx == other_idx {
+                    continue;
+                }
+                if gix_revision::merge_base(*commit, &[*other], graph)?.is_some_and(|bases| bases.contains(commit)) {
+                    is_contained = true;
+                    break;
+                }
+            }
+            if !is_contained || (idx == 0 && no_fast_forward) {
+                parents.push(*commit);
+            }
+        }
+        Ok(parents)
+    }
+}
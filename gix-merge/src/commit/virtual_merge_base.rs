@@ -42,7 +42,7 @@ pub(super) mod function {
     /// Note that `first_commit` and `second_commit` are expected to have been popped off `others`, so `first_commit`
     /// was the last provided merge-base of function that provides multiple merge-bases for a pair of commits.
     ///
-    /// The parameters `graph`, `diff_resource_cache`, `blob_merge`, `objects`, `abbreviate_hash` and `options` are passed
+    /// The parameters `graph`, `diff_resource_cache`, `blob_merge`, `submodules`, `objects`, `abbreviate_hash` and `options` are passed
     /// directly to [`tree()`](crate::tree()) for merging the trees of two merge-bases at a time.
     /// Note that most of `options` are overwritten to match the requirements of a merge-base merge.
     #[allow(clippy::too_many_arguments)]
@@ -53,6 +53,7 @@ pub(super) mod function {
         graph: &mut gix_revwalk::Graph<'_, '_, gix_revwalk::graph::Commit<gix_revision::merge_base::Flags>>,
         diff_resource_cache: &mut gix_diff::blob::Platform,
         blob_merge: &mut crate::blob::Platform,
+        submodules: &mut dyn crate::tree::submodule::Find,
         objects: &'objects (impl gix_object::FindObjectOrHeader + gix_object::Write),
         abbreviate_hash: &mut dyn FnMut(&gix_hash::oid) -> String,
         mut options: crate::tree::Options,
@@ -82,12 +83,14 @@ pub(super) mod function {
                 graph,
                 diff_resource_cache,
                 blob_merge,
+                submodules,
                 objects,
                 abbreviate_hash,
                 crate::commit::Options {
                     allow_missing_merge_base: false,
                     tree_merge: options.clone(),
                     use_first_merge_base: false,
+                    strategy: crate::commit::Strategy::Ort,
                 },
             )?;
             // This shouldn't happen, but if for some buggy reason it does, we rather bail.
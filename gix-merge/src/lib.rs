@@ -3,6 +3,7 @@
 //! * [blob-merges](blob) look at file content.
 //! * [tree-merges](mod@tree) look at trees and merge them structurally, triggering blob-merges as needed.
 //! * [commit-merges](mod@commit) are like tree merges, but compute or create the merge-base on the fly.
+//!   [Octopus merges](commit::octopus()) combine more than two commits this way.
 #![deny(missing_docs)]
 #![forbid(unsafe_code)]
 
@@ -11,7 +11,7 @@ use gix_object::{
 use crate::tree::{
     Conflict, ConflictIndexEntry, ConflictIndexEntryPathHint, ConflictMapping,
     ConflictMapping::{Original, Swapped},
-    ContentMerge, Error, Options, Outcome, Resolution, ResolutionFailure, ResolveWith,
+    ContentMerge, Error, Options, Outcome, Resolution, ResolutionFailure, ResolveWith, submodule,
     utils::{
         ChangeList, ChangeListRef, PossibleConflict, TrackedChange, TreeNodes, apply_change, perform_blob_merge,
         possibly_rewritten_location, rewrite_location_with_renamed_directory, to_components, track,
@@ -30,6 +30,8 @@ use crate::tree::{
 /// * `diff_resource_cache` is used for similarity checks.
 /// * `blob_merge` is a pre-configured platform to merge any content.
 ///     - Note that it shouldn't be allowed to read from the worktree, given that this is a tree-merge.
+/// * `submodules` provides access to the commits of submodules so that submodules changed on both sides can be merged.
+///     - Use `&mut ()` to treat all of these as conflicts.
 /// * `options` are used to affect how the merge is performed.
 ///
 /// ### Unbiased (Ours x Theirs == Theirs x Ours)
@@ -65,6 +67,7 @@ pub fn tree<'objects, E>(
     diff_state: &mut gix_diff::tree::State,
     diff_resource_cache: &mut gix_diff::blob::Platform,
     blob_merge: &mut crate::blob::Platform,
+    submodules: &mut dyn submodule::Find,
     options: Options,
 ) -> Result<Outcome<'objects>, Error>
 where
@@ -552,6 +555,62 @@ where
                                     break 'outer;
                                 }
                             }
+                            (
+                                Change::Modification {
+                                    location,
+                                    previous_id,
+                                    previous_entry_mode,
+                                    entry_mode: our_mode,
+                                    id: our_id,
+                                    ..
+                                },
+                                Change::Modification {
+                                    entry_mode: their_mode,
+                                    id: their_id,
+                                    ..
+                                },
+                            ) if our_mode.is_commit()
+                                && their_mode.is_commit()
+                                && previous_entry_mode.is_commit()
+                                && our_id != their_id =>
+                            {
+                                let index_entries = [
+                                    index_entry(previous_entry_mode, previous_id),
+                                    index_entry(our_mode, our_id),
+                                    index_entry(their_mode, their_id),
+                                ];
+                                let conflict = match submodule::merge(
+                                    submodules,
+                                    location.as_ref(),
+                                    (*previous_id, *our_id, *their_id),
+                                    !options.blob_merge.is_virtual_ancestor,
+                                )? {
+                                    Ok(commit_id) => {
+                                        editor.upsert(toc(location), EntryKind::Commit, commit_id)?;
+                                        Conflict::with_resolution(
+                                            Resolution::OursModifiedTheirsModifiedThenSubmoduleFastForward {
+                                                commit_id,
+                                            },
+                                            (ours, theirs, Original, outer_side),
+                                            index_entries,
+                                        )
+                                    }
+                                    Err(failure) => {
+                                        // Like Git, keep our commit, unless the ancestor is supposed to be kept.
+                                        if tree_conflicts != Some(ResolveWith::Ancestor) {
+                                            apply_our_resolution(ours, theirs, outer_side, &mut editor)?;
+                                        }
+                                        Conflict::without_resolution(
+                                            ResolutionFailure::OursModifiedTheirsModifiedSubmodule { failure },
+                                            (ours, theirs, Original, outer_side),
+                                            index_entries,
+                                        )
+                                    }
+                                };
+                                if should_fail_on_conflict(conflict) {
+                                    break 'outer;
+                                }
+                            }
                             (
                                 Change::Addition {
                                     location,
@@ -23,6 +23,8 @@ pub enum Error {
     WriteBlobToOdb(Box<dyn std::error::Error + Send + Sync + 'static>),
     #[error("The merge was performed, but the binary merge result couldn't be selected as it wasn't found")]
     MergeResourceNotFound,
+    #[error(transparent)]
+    Submodule(#[from] submodule::Error),
 }
 
 /// The outcome produced by [`tree()`](crate::tree()).
@@ -252,6 +254,7 @@ impl Conflict {
                         Resolution::OursModifiedTheirsModifiedThenBlobContentMerge { merged_blob } => {
                             content_merge_unresolved(merged_blob)
                         }
+                        Resolution::OursModifiedTheirsModifiedThenSubmoduleFastForward { .. } => false,
                     },
                     Err(_failure) => true,
                 }
@@ -294,6 +297,7 @@ impl Conflict {
             match failure {
                 ResolutionFailure::OursRenamedTheirsRenamedDifferently { merged_blob } => *merged_blob,
                 ResolutionFailure::Unknown
+                | ResolutionFailure::OursModifiedTheirsModifiedSubmodule { .. }
                 | ResolutionFailure::OursDirectoryTheirsNonDirectoryTheirsRenamed { .. }
                 | ResolutionFailure::OursModifiedTheirsDeleted
                 | ResolutionFailure::OursModifiedTheirsRenamedTypeMismatch
@@ -310,6 +314,7 @@ impl Conflict {
                 Resolution::SourceLocationAffectedByRename { .. } => None,
                 Resolution::OursModifiedTheirsRenamedAndChangedThenRename { merged_blob, .. } => *merged_blob,
                 Resolution::OursModifiedTheirsModifiedThenBlobContentMerge { merged_blob } => Some(*merged_blob),
+                Resolution::OursModifiedTheirsModifiedThenSubmoduleFastForward { .. } => None,
             },
             Err(failure) => failure_merged_blob(failure),
         }
@@ -349,6 +354,12 @@ pub enum Resolution {
         /// The outcome of the content merge.
         merged_blob: ContentMerge,
     },
+    /// *ours* and *theirs* changed the commit of a submodule, and one contains the other so the submodule
+    /// was fast-forwarded to it.
+    OursModifiedTheirsModifiedThenSubmoduleFastForward {
+        /// The commit of the submodule we fast-forwarded to.
+        commit_id: gix_hash::ObjectId,
+    },
     /// This is a resolution failure was forcefully turned into a usable resolution, i.e. [making a choice](ResolveWith)
     /// is turned into a valid resolution.
     Forced(ResolutionFailure),
@@ -392,6 +403,12 @@ pub enum ResolutionFailure {
     OursDeletedTheirsRenamed,
     /// *ours* was modified and *theirs* was deleted. We keep the modified one and ignore the deletion.
     OursModifiedTheirsDeleted,
+    /// *ours* and *theirs* changed the commit of a submodule, but they couldn't be merged.
+    /// Like in Git, *our* commit is kept in the tree.
+    OursModifiedTheirsModifiedSubmodule {
+        /// The reason the submodule couldn't be merged, possibly with suggestions for merge commits to use instead.
+        failure: submodule::Failure,
+    },
     /// *ours* and *theirs* are in an untested state so it can't be handled yet, and is considered a conflict
     /// without adding our *or* their side to the resulting tree.
     Unknown,
@@ -440,6 +457,86 @@ pub struct Options {
     /// the entries what would fit the index if no forced resolution was performed.
     /// It's up to the caller to handle that information mindfully.
     pub tree_conflicts: Option<ResolveWith>,
+    /// If `Some(mode)`, use it to convert blobs into a mergeable state instead of the
+    /// [mode of the merge platform](crate::blob::Platform::filter_mode).
+    ///
+    /// This is useful to [renormalize](crate::blob::pipeline::Mode::Renormalize) blobs for just this merge,
+    /// similar to `git merge -X renormalize`.
+    pub filter_mode: Option<crate::blob::pipeline::Mode>,
+}
+
+/// Mutation
+impl Options {
+    /// Adjust these options like Git would when passing `option` as `-X <option>` to `git merge`.
+    ///
+    /// Options that choose a side affect content merges, binaries and symlinks, but leave
+    /// [irreconcilable tree conflicts](Self::tree_conflicts) alone, just like in Git.
+    pub fn apply_strategy_option(&mut self, option: StrategyOption) {
+        use crate::blob::builtin_driver::{binary, text};
+        let whitespace = &mut self.blob_merge.text.whitespace;
+        match option {
+            StrategyOption::Ours | StrategyOption::Theirs => {
+                let (binary, text) = if option == StrategyOption::Ours {
+                    (binary::ResolveWith::Ours, text::Conflict::ResolveWithOurs)
+                } else {
+                    (binary::ResolveWith::Theirs, text::Conflict::ResolveWithTheirs)
+                };
+                self.blob_merge.text.conflict = text;
+                self.blob_merge.resolve_binary_with = Some(binary);
+                self.symlink_conflicts = Some(binary);
+            }
+            StrategyOption::IgnoreSpaceChange => whitespace.ignore_space_change = true,
+            StrategyOption::IgnoreAllSpace => whitespace.ignore_all_space = true,
+            StrategyOption::IgnoreSpaceAtEol => whitespace.ignore_space_at_eol = true,
+            StrategyOption::IgnoreCrAtEol => whitespace.ignore_cr_at_eol = true,
+            StrategyOption::Renormalize => self.filter_mode = Some(crate::blob::pipeline::Mode::Renormalize),
+            StrategyOption::NoRenormalize => self.filter_mode = Some(crate::blob::pipeline::Mode::ToGit),
+            StrategyOption::DiffAlgorithm(algorithm) => self.blob_merge.text.diff_algorithm = algorithm,
+            StrategyOption::FindRenames(percentage) => {
+                let rewrites = self.rewrites.get_or_insert_with(Default::default);
+                rewrites.percentage = percentage.or(Rewrites::default().percentage);
+            }
+            StrategyOption::NoRenames => self.rewrites = None,
+        }
+    }
+}
+
+/// An option to adjust how trees and their blobs are merged, similar to `git merge -X <option>`,
+/// for use in [`Options::apply_strategy_option()`].
+///
+/// Use `StrategyOption::try_from(option)` to parse it from the way it would be passed to Git.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum StrategyOption {
+    /// Resolve conflicting hunks, binaries and symlinks in favor of *our* side, like `ours`.
+    ///
+    /// Note that unlike the [`ours` strategy](crate::commit::Strategy::Ours), non-conflicting changes of
+    /// *their* side are still merged.
+    Ours,
+    /// Resolve conflicting hunks, binaries and symlinks in favor of *their* side, like `theirs`.
+    Theirs,
+    /// Consider lines equal if they only differ in the amount of whitespace, like `ignore-space-change`.
+    IgnoreSpaceChange,
+    /// Consider lines equal if they only differ in whitespace, like `ignore-all-space`.
+    IgnoreAllSpace,
+    /// Consider lines equal if they only differ in whitespace at the end of the line, like `ignore-space-at-eol`.
+    IgnoreSpaceAtEol,
+    /// Consider lines equal if they only differ in a carriage-return at the end of the line, like `ignore-cr-at-eol`.
+    IgnoreCrAtEol,
+    /// Convert all sides to the worktree and back before merging them, like `renormalize`.
+    Renormalize,
+    /// Don't renormalize blobs, even if the merge platform is configured to, like `no-renormalize`.
+    NoRenormalize,
+    /// Use the given algorithm for content merges, like `diff-algorithm=<name>` or `patience`.
+    DiffAlgorithm(gix_diff::blob::Algorithm),
+    /// Track renames, and if `Some(fraction)`, consider files renamed if they are at least this similar,
+    /// like `find-renames[=<n>]`.
+    ///
+    /// If `None`, the default similarity is used.
+    FindRenames(Option<f32>),
+    /// Don't track renames, like `no-renames`.
+    ///
+    /// Note that Git only respects this with the `recursive` strategy, as the `ort` strategy always tracks renames.
+    NoRenames,
 }
 
 /// Decide how to resolve tree-related conflicts, but only those that have [no way of being correct](ResolutionFailure).
@@ -454,7 +551,102 @@ pub enum ResolveWith {
     Ours,
 }
 
+///
+pub mod strategy_option {
+    use bstr::{BStr, BString, ByteSlice};
+
+    use super::StrategyOption;
+
+    /// The error returned by [`StrategyOption::try_from()`].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Unknown or unsupported strategy option: '{option}'")]
+        Unknown { option: BString },
+        #[error("Invalid similarity '{value}' in strategy option 'find-renames'")]
+        InvalidSimilarity { value: BString },
+        #[error("Unknown diff algorithm '{name}' in strategy option 'diff-algorithm'")]
+        UnknownDiffAlgorithm { name: BString },
+    }
+
+    impl TryFrom<&BStr> for StrategyOption {
+        type Error = Error;
+
+        /// Parse `option` as it would be passed to `git merge -X <option>`.
+        fn try_from(option: &BStr) -> Result<Self, Self::Error> {
+            use gix_diff::blob::Algorithm;
+            Ok(match option.as_bytes() {
+                b"ours" => StrategyOption::Ours,
+                b"theirs" => StrategyOption::Theirs,
+                b"ignore-space-change" => StrategyOption::IgnoreSpaceChange,
+                b"ignore-all-space" => StrategyOption::IgnoreAllSpace,
+                b"ignore-space-at-eol" => StrategyOption::IgnoreSpaceAtEol,
+                b"ignore-cr-at-eol" => StrategyOption::IgnoreCrAtEol,
+                b"renormalize" => StrategyOption::Renormalize,
+                b"no-renormalize" => StrategyOption::NoRenormalize,
+                b"patience" => StrategyOption::DiffAlgorithm(Algorithm::Patience),
+                b"find-renames" => StrategyOption::FindRenames(None),
+                b"no-renames" => StrategyOption::NoRenames,
+                _ => {
+                    if let Some(name) = option.strip_prefix(b"diff-algorithm=") {
+                        let algorithm = match name {
+                            b"myers" | b"default" => Algorithm::Myers,
+                            b"minimal" => Algorithm::MyersMinimal,
+                            b"histogram" => Algorithm::Histogram,
+                            b"patience" => Algorithm::Patience,
+                            _ => return Err(Error::UnknownDiffAlgorithm { name: name.into() }),
+                        };
+                        StrategyOption::DiffAlgorithm(algorithm)
+                    } else if let Some(value) = option
+                        .strip_prefix(b"find-renames=")
+                        .or_else(|| option.strip_prefix(b"rename-threshold="))
+                    {
+                        let similarity =
+                            parse_similarity(value).ok_or_else(|| Error::InvalidSimilarity { value: value.into() })?;
+                        // Like in Git, zero means the default similarity.
+                        StrategyOption::FindRenames((similarity > 0.0).then_some(similarity))
+                    } else {
+                        return Err(Error::Unknown { option: option.into() });
+                    }
+                }
+            })
+        }
+    }
+
+    /// Parse `value` like Git parses the similarity score of `-M<n>`, where `5`, `50` and `0.5` all mean `50%`,
+    /// while `5%` means exactly that.
+    fn parse_similarity(value: &[u8]) -> Option<f32> {
+        let (mut num, mut scale) = (0u64, 1u64);
+        let mut seen_dot = false;
+        let mut bytes = value.iter().peekable();
+        while let Some(&byte) = bytes.next() {
+            match byte {
+                b'.' if !seen_dot => {
+                    scale = 1;
+                    seen_dot = true;
+                }
+                b'%' => {
+                    scale = if seen_dot { scale * 100 } else { 100 };
+                    if bytes.peek().is_some() {
+                        return None;
+                    }
+                }
+                b'0'..=b'9' => {
+                    if scale < 100_000 {
+                        scale *= 10;
+                        num = num * 10 + u64::from(byte - b'0');
+                    }
+                }
+                _ => return None,
+            }
+        }
+        Some(if num >= scale { 1.0 } else { num as f32 / scale as f32 })
+    }
+}
+
 pub(super) mod function;
+///
+pub mod submodule;
 mod utils;
 ///
 pub mod apply_index_entries {
@@ -521,7 +713,8 @@ pub mod apply_index_entries {
                             final_location.as_ref().map(|p| p.as_bstr()),
                             conflict.changes_in_resolution().1.location(),
                         ),
-                        Resolution::OursModifiedTheirsModifiedThenBlobContentMerge { .. } => {
+                        Resolution::OursModifiedTheirsModifiedThenBlobContentMerge { .. }
+                        | Resolution::OursModifiedTheirsModifiedThenSubmoduleFastForward { .. } => {
                             (None, conflict.ours.location())
                         }
                     },
@@ -535,6 +728,7 @@ pub mod apply_index_entries {
                         ResolutionFailure::OursModifiedTheirsRenamedTypeMismatch
                         | ResolutionFailure::OursDeletedTheirsRenamed
                         | ResolutionFailure::OursModifiedTheirsDeleted
+                        | ResolutionFailure::OursModifiedTheirsModifiedSubmodule { .. }
                         | ResolutionFailure::Unknown => (None, conflict.ours.location()),
                         ResolutionFailure::OursModifiedTheirsDirectoryThenOursRenamed {
                             renamed_unique_path_to_modified_blob,
@@ -0,0 +1,183 @@
+use bstr::{BStr, BString};
+use gix_hash::{ObjectId, oid};
+
+/// A way to access the repositories of submodules to merge the commits they point to as part of [`tree()`](crate::tree()).
+///
+/// Use `()` if submodules can't be accessed, which makes every submodule that was changed on both sides a conflict.
+pub trait Find {
+    /// Return access to the submodule at `rela_path` in the superproject, or `None` if it isn't available,
+    /// for instance because it isn't checked out.
+    fn find_submodule(
+        &mut self,
+        rela_path: &BStr,
+    ) -> Result<Option<Access<'_>>, Box<dyn std::error::Error + Send + Sync + 'static>>;
+}
+
+impl Find for () {
+    fn find_submodule(
+        &mut self,
+        _rela_path: &BStr,
+    ) -> Result<Option<Access<'_>>, Box<dyn std::error::Error + Send + Sync + 'static>> {
+        Ok(None)
+    }
+}
+
+/// Access to the commits of a submodule, as returned by [`Find::find_submodule()`].
+pub struct Access<'a> {
+    /// The object database of the submodule.
+    pub objects: Box<dyn gix_object::Find + 'a>,
+    /// The commits that all references of the submodule point to.
+    ///
+    /// They are used to find merge commits that contain both sides, similar to `git rev-list --all`.
+    pub tips: Vec<ObjectId>,
+}
+
+/// The reason a submodule couldn't be merged, as part of a [resolution failure](super::ResolutionFailure::OursModifiedTheirsModifiedSubmodule).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Failure {
+    /// The submodule isn't available, typically because it isn't checked out.
+    NotAvailable,
+    /// At least one of the commits isn't present in the object database of the submodule.
+    CommitsMissing,
+    /// At least one side isn't a descendant of the commit the submodule pointed to in the common ancestor,
+    /// so it may have been rewound.
+    MayHaveRewinds,
+    /// Neither side contains the other, so they can't be fast-forwarded.
+    Diverged {
+        /// The merge commits in the submodule which contain both sides, without those which contain another candidate.
+        ///
+        /// If there is only one, it is likely to be the desired resolution, but needs to be confirmed by the user.
+        /// It is always empty when merging [virtual ancestors](crate::blob::platform::merge::Options::is_virtual_ancestor).
+        merge_candidates: Vec<ObjectId>,
+    },
+}
+
+/// The error returned when merging submodules as part of [`tree()`](crate::tree()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not access the submodule at '{rela_path}'")]
+    Find {
+        rela_path: BString,
+        source: Box<dyn std::error::Error + Send + Sync + 'static>,
+    },
+    #[error("Could not read a commit in the submodule at '{rela_path}'")]
+    FindCommit {
+        rela_path: BString,
+        source: gix_object::find::Error,
+    },
+    #[error("Could not find a merge-base in the submodule at '{rela_path}'")]
+    MergeBase {
+        rela_path: BString,
+        source: gix_revision::merge_base::Error,
+    },
+}
+
+/// Allow using the trait object of [`Access::objects`] where `impl Find` is needed.
+struct Objects<'a, 'b>(&'a (dyn gix_object::Find + 'b));
+
+impl gix_object::Find for Objects<'_, '_> {
+    fn try_find<'a>(
+        &self,
+        id: &oid,
+        buffer: &'a mut Vec<u8>,
+    ) -> Result<Option<gix_object::Data<'a>>, gix_object::find::Error> {
+        self.0.try_find(id, buffer)
+    }
+}
+
+/// Merge the submodule at `rela_path` which pointed to `base` and now points to `ours` and `theirs`, like Git does.
+///
+/// Return the commit to fast-forward to if one side contains the other, or the reason the merge failed.
+/// Only search for merge commits that contain both sides if `find_merge_candidates` is `true`.
+pub(super) fn merge(
+    submodules: &mut dyn Find,
+    rela_path: &BStr,
+    (base, ours, theirs): (ObjectId, ObjectId, ObjectId),
+    find_merge_candidates: bool,
+) -> Result<Result<ObjectId, Failure>, Error> {
+    let Some(access) = submodules.find_submodule(rela_path).map_err(|source| Error::Find {
+        rela_path: rela_path.to_owned(),
+        source,
+    })?
+    else {
+        return Ok(Err(Failure::NotAvailable));
+    };
+    let objects = Objects(&*access.objects);
+    let mut buf = Vec::new();
+    let find_commit = |id: &oid, buf: &mut Vec<u8>| -> Result<Option<Vec<ObjectId>>, Error> {
+        let data = gix_object::Find::try_find(&objects, id, buf).map_err(|source| Error::FindCommit {
+            rela_path: rela_path.to_owned(),
+            source,
+        })?;
+        Ok(data
+            .and_then(gix_object::Data::try_into_commit_iter)
+            .map(|commit| commit.parent_ids().collect()))
+    };
+    for id in [&base, &ours, &theirs] {
+        if find_commit(id, &mut buf)?.is_none() {
+            return Ok(Err(Failure::CommitsMissing));
+        }
+    }
+
+    let mut graph = gix_revwalk::Graph::new(Objects(&*access.objects), None);
+    let mut is_ancestor = |ancestor: ObjectId, descendant: ObjectId| -> Result<bool, Error> {
+        Ok(gix_revision::merge_base(ancestor, &[descendant], &mut graph)
+            .map_err(|source| Error::MergeBase {
+                rela_path: rela_path.to_owned(),
+                source,
+            })?
+            .is_some_and(|bases| bases.contains(&ancestor)))
+    };
+    if !is_ancestor(base, ours)? || !is_ancestor(base, theirs)? {
+        return Ok(Err(Failure::MayHaveRewinds));
+    }
+    if is_ancestor(ours, theirs)? {
+        return Ok(Ok(theirs));
+    }
+    if is_ancestor(theirs, ours)? {
+        return Ok(Ok(ours));
+    }
+    if !find_merge_candidates {
+        return Ok(Err(Failure::Diverged {
+            merge_candidates: Vec::new(),
+        }));
+    }
+
+    // Like `git rev-list --merges --ancestry-path --all ^ours`, but only keep merges that also contain `theirs`.
+    // All ancestors of a commit that doesn't descend from `ours` don't descend from it either, so we stop there.
+    let mut merges = Vec::new();
+    let mut seen = std::collections::HashSet::new();
+    let mut queue: std::collections::VecDeque<_> = access.tips.iter().copied().collect();
+    while let Some(id) = queue.pop_front() {
+        if id == ours || !seen.insert(id) {
+            continue;
+        }
+        // Tips may point to other objects than commits, which are skipped.
+        let Some(parents) = find_commit(&id, &mut buf)? else {
+            continue;
+        };
+        if !is_ancestor(ours, id)? {
+            continue;
+        }
+        if parents.len() > 1 && is_ancestor(theirs, id)? {
+            merges.push(id);
+        }
+        queue.extend(parents);
+    }
+
+    let mut merge_candidates = Vec::new();
+    for (idx, candidate) in merges.iter().enumerate() {
+        let mut contains_another = false;
+        for (other_idx, other) in merges.iter().enumerate() {
+            if idx != other_idx && is_ancestor(*other, *candidate)? {
+                contains_another = true;
+                break;
+            }
+        }
+        if !contains_another {
+            merge_candidates.push(*candidate);
+        }
+    }
+    Ok(Err(Failure::Diverged { merge_candidates }))
+}
@@ -130,21 +130,33 @@ where
         ConflictMapping::Original => (ResourceKind::CurrentOrOurs, ResourceKind::OtherOrTheirs),
         ConflictMapping::Swapped => (ResourceKind::OtherOrTheirs, ResourceKind::CurrentOrOurs),
     };
-    blob_merge.set_resource(our_id, our_mode.kind(), our_location.as_bstr(), our_kind, objects)?;
-    blob_merge.set_resource(
-        their_id,
-        their_mode.kind(),
-        their_location.as_bstr(),
-        their_kind,
-        objects,
-    )?;
-    blob_merge.set_resource(
-        previous_id,
-        previous_mode.kind(),
-        previous_location.as_bstr(),
-        ResourceKind::CommonAncestorOrBase,
-        objects,
-    )?;
+    let previous_filter_mode = options
+        .filter_mode
+        .map(|mode| std::mem::replace(&mut blob_merge.filter_mode, mode));
+    let res = blob_merge
+        .set_resource(our_id, our_mode.kind(), our_location.as_bstr(), our_kind, objects)
+        .and_then(|()| {
+            blob_merge.set_resource(
+                their_id,
+                their_mode.kind(),
+                their_location.as_bstr(),
+                their_kind,
+                objects,
+            )
+        })
+        .and_then(|()| {
+            blob_merge.set_resource(
+                previous_id,
+                previous_mode.kind(),
+                previous_location.as_bstr(),
+                ResourceKind::CommonAncestorOrBase,
+                objects,
+            )
+        });
+    if let Some(mode) = previous_filter_mode {
+        blob_merge.filter_mode = mode;
+    }
+    res?;
 
     fn combined(side: &BStr, location: &BString) -> BString {
         let mut buf = side.to_owned();
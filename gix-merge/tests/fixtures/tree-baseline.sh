@@ -61,10 +61,6 @@ function oid () {
     f801a62deed900f8a80ff35e3339474ad6352a93) echo 970d16592cccbb8ce3846be7a74ecb94bc3bf8d01bec1eeb15a57380aae5d5a3 ;;
     f89a08d1e226b9a319210641b63b07dcf0bd705f) echo 43d8308eb726995b8b6a3f826252dd21dc35d01386931b8eea4125723e36cc83 ;;
     fa49b077972391ad58037050f2a75f74e3671e92) echo 6d5fd291bb0f67444e99ab492f1bf1fcdf5dca09dab24cf331e05111b4cfc1a3 ;;
-    # gitlinks: submodule 'sub' commits (sub-root, sub-a, sub-b), deterministic from the fixed tick dates
-    e835c0c403c8e494c0ca98f3d25d0b8464c18d38) echo ed2d65b7fe3c3f869a21cfec7f54867db6c8eb2cc9b0bf671522d87599704d70 ;;
-    64466ebdff775ad618d9cc993cf52840e0af528c) echo df5d889015388598b724a741b53437e4dee9d67a5dbe0fa9f361fedd4c9ac6e8 ;;
-    ea6eb701e03c2497915c25a851f3da8f8e362ca0) echo c79d38d6c8161bf2aec16032f82127c7ac237eeab69e1490422c93b5c314ce9c ;;
     *) echo "oid(): no SHA-256 mapping for '$1'" >&2; return 1 ;;
   esac
 }
@@ -999,8 +995,6 @@ git init submodule-both-modify
 	tick
 	git commit -m root
 
-	git branch expected
-
 	git checkout -b A main
 	(cd sub
 	 echo A > file
@@ -1022,23 +1016,6 @@ git init submodule-both-modify
 	git add sub
 	tick
 	git commit -m b
-
-	# We cannot handle submodules yet and thus mark them as conflicted, always if they mismatch at least.
-	rm .git/index
-	git update-index --index-info <<EOF
-160000 $(oid e835c0c403c8e494c0ca98f3d25d0b8464c18d38) 1	sub
-160000 $(oid 64466ebdff775ad618d9cc993cf52840e0af528c) 2	sub
-160000 $(oid ea6eb701e03c2497915c25a851f3da8f8e362ca0) 3	sub
-EOF
-  make_conflict_index submodule-both-modify-A-B
-
-	rm .git/index
-	git update-index --index-info <<EOF
-160000 $(oid e835c0c403c8e494c0ca98f3d25d0b8464c18d38) 1	sub
-160000 $(oid ea6eb701e03c2497915c25a851f3da8f8e362ca0) 2	sub
-160000 $(oid 64466ebdff775ad618d9cc993cf52840e0af528c) 3	sub
-EOF
-  make_conflict_index submodule-both-modify-A-B-reversed
 )
 
 git init both-modify-union-attr
@@ -1275,7 +1252,7 @@ baseline added-file-changed-content-and-mode A-B A B "We improve on executable b
 
 baseline type-change-and-renamed A-B A B
 baseline change-and-delete A-B A B
-baseline submodule-both-modify A-B A B "We can't handle submodules yet and just mark them as conflicting. This is planned to be improved."
+baseline submodule-both-modify A-B A B
 baseline both-modify-union-attr A-B A B
 baseline both-modify-union-attr A-B-diff3 A B
 baseline both-modify-binary A-B A B
@@ -1723,31 +1700,6 @@ EOF
   make_resolve_tree ours B A
 )
 
-(cd submodule-both-modify
-  rm .git/index
-  # There is only one submodule. 'A' and 'B' change it in a fast-forwardable manner,
-  # but we can't handle this at all yet, and thus have to consider it irreconcilable.
-  # The 'ancestor' resolution just keeps what was.
-  git update-index --index-info <<EOF
-160000 commit $(oid e835c0c403c8e494c0ca98f3d25d0b8464c18d38)	sub
-EOF
-  make_resolve_tree ancestor A B
-  make_resolve_tree ancestor B A
-
-  rm .git/index
-  # Otherwise it's the state of 'A'.
-  git update-index --index-info <<EOF
-160000 commit $(oid 64466ebdff775ad618d9cc993cf52840e0af528c)	sub
-EOF
-  make_resolve_tree ours A B
-
-  rm .git/index
-  # Otherwise it's the state of 'B'.
-  git update-index --index-info <<EOF
-160000 commit $(oid ea6eb701e03c2497915c25a851f3da8f8e362ca0)	sub
-EOF
-  make_resolve_tree ours B A
-)
 
 (cd multiple-merge-bases
   rm .git/index
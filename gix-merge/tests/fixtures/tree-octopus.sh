@@ -0,0 +1,79 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+function tick () {
+  if test -z "${tick+set}"
+  then
+    tick=1112911993
+  else
+    tick=$(($tick + 60))
+  fi
+  GIT_COMMITTER_DATE="$tick -0700"
+  GIT_AUTHOR_DATE="$tick -0700"
+  export GIT_COMMITTER_DATE GIT_AUTHOR_DATE
+}
+
+function write_lines () {
+	printf "%s\n" "$@"
+}
+
+# Merge all given branches into `start` with the octopus strategy, and store the resulting tree
+# in `.git/<name>.tree` and the parents of the merge commit in `.git/<name>.parents`.
+function baseline () {
+  local name=${1:?the name of the case}
+  local start=${2:?the commit to merge into}
+  shift 2
+
+  git checkout -q --detach "$start"
+  tick
+  git merge -q --no-edit -s octopus "$@" >/dev/null
+  git rev-parse HEAD^{tree} > ".git/${name}.tree"
+  git log -1 --format=%P | tr ' ' '\n' > ".git/${name}.parents"
+}
+
+# Assert that merging all given branches into `start` with the octopus strategy fails.
+function conflicts () {
+  local start=${1:?the commit to merge into}
+  shift
+
+  git checkout -q --detach "$start"
+  if git merge -q --no-edit -s octopus "$@" >/dev/null 2>&1; then
+    echo "expected octopus merge of '$*' to fail" >&2
+    exit 1
+  fi
+  git reset -q --hard
+}
+
+tick
+git init octopus
+(cd octopus
+  write_lines 1 2 3 4 5 6 7 8 9 >a
+  echo b >b
+  git add . && git commit -q -m "init"
+  git branch old
+
+  git checkout -q -b topic-a
+  write_lines topic-a 2 3 4 5 6 7 8 9 >a
+  tick && git commit -q -am "topic-a changes the beginning of 'a'"
+
+  git checkout -q -b topic-b old
+  echo topic-b >b
+  tick && git commit -q -am "topic-b changes 'b'"
+
+  git checkout -q -b topic-c old
+  write_lines 1 2 3 4 5 6 7 8 topic-c >a
+  echo c >c
+  git add . && tick && git commit -q -m "topic-c changes the end of 'a' and adds 'c'"
+
+  git checkout -q -b conflicting old
+  write_lines conflicting 2 3 4 5 6 7 8 9 >a
+  tick && git commit -q -am "conflicting changes the beginning of 'a'"
+
+  git checkout -q main
+  echo d >d
+  git add . && tick && git commit -q -m "main adds 'd'"
+
+  baseline merged main topic-a topic-b topic-c old
+  baseline fast-forward old topic-a topic-b
+  conflicts main topic-a conflicting
+)
@@ -0,0 +1,152 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+function tick () {
+  if test -z "${tick+set}"
+  then
+    tick=1112911993
+  else
+    tick=$(($tick + 60))
+  fi
+  GIT_COMMITTER_DATE="$tick -0700"
+  GIT_AUTHOR_DATE="$tick -0700"
+  export GIT_COMMITTER_DATE GIT_AUTHOR_DATE
+}
+
+function write_lines () {
+	printf "%s\n" "$@"
+}
+
+# Merge `B` into `A` with `git merge` and the given strategy arguments, and store the
+# resulting tree in `.git/<name>.tree`. The merge must be free of conflicts.
+function baseline () {
+  local name=${1:?the name of the file to store the merged tree in}
+  shift
+
+  git checkout -q --detach A
+  git merge -q --no-edit "$@" B
+  git rev-parse HEAD^{tree} > ".git/${name}.tree"
+}
+
+# Assert that merging `B` into `A` with the given strategy arguments causes conflicts.
+function conflicts () {
+  git checkout -q --detach A
+  if git merge -q --no-edit "$@" B >/dev/null 2>&1; then
+    echo "expected merge with '$*' to conflict" >&2
+    exit 1
+  fi
+  git merge --abort
+}
+
+tick
+git init favor
+(cd favor
+  write_lines 1 2 3 4 5 6 7 8 9 >a
+  write_lines x y z >b
+  git add . && git commit -m "init"
+
+  git branch A
+  git branch B
+
+  git checkout A
+  write_lines 1 2 3 4 A 6 7 8 9 >a
+  write_lines x y z A >b
+  git commit -am "A changes 'a' and 'b'"
+
+  git checkout B
+  write_lines 1 B 3 4 B 6 7 8 9 >a
+  write_lines x y z B >b
+  git commit -am "B changes 'a' and 'b'"
+
+  conflicts
+  baseline ours -X ours
+  baseline theirs -X theirs
+  baseline strategy-ours -s ours
+)
+
+git init whitespace
+(cd whitespace
+  write_lines "one two" "three" "four" "five" "six" "seven" >a
+  git add . && git commit -m "init"
+
+  git branch A
+  git branch B
+
+  git checkout A
+  write_lines "one  two " "three" "four" "five" "six" "seven" >a
+  git commit -am "A changes whitespace in 'a'"
+
+  git checkout B
+  write_lines "one two three" "three" "four" "five" "six" "eight" >a
+  git commit -am "B changes 'a'"
+
+  conflicts
+  conflicts -X ignore-space-at-eol
+  baseline ignore-space-change -X ignore-space-change
+  baseline ignore-all-space -X ignore-all-space
+)
+
+git init space-at-eol
+(cd space-at-eol
+  write_lines "one two" "three" "four" "five" "six" "seven" >a
+  git add . && git commit -m "init"
+
+  git branch A
+  git branch B
+
+  git checkout A
+  write_lines "one two  " "three" "four" "five" "six" "seven" >a
+  git commit -am "A adds whitespace at the end of a line in 'a'"
+
+  git checkout B
+  write_lines "one two three" "three" "four" "five" "six" "eight" >a
+  git commit -am "B changes 'a'"
+
+  conflicts
+  baseline ignore-space-at-eol -X ignore-space-at-eol
+)
+
+git init renormalize
+(cd renormalize
+  printf "1\r\n2\r\n3\r\n4\r\n5\r\n" >a
+  git add . && git commit -m "init with CRLF"
+
+  git branch A
+  git branch B
+
+  git checkout A
+  echo "* text=auto" >.gitattributes
+  git add .gitattributes && git add --renormalize . && git commit -m "A normalizes line endings"
+
+  git checkout B
+  printf "1\r\n2\r\nB\r\n4\r\n5\r\n" >a
+  git commit -am "B changes 'a'"
+
+  conflicts
+  baseline renormalize -X renormalize
+  git checkout -q --detach A
+)
+
+git init renames
+(cd renames
+  write_lines 1 2 3 4 5 6 7 8 9 10 >a
+  git add . && git commit -m "init"
+
+  git branch A
+  git branch B
+
+  git checkout A
+  git mv a renamed
+  write_lines 1 2 3 4 5 6 7 A A A >renamed
+  git commit -am "A renames 'a' and changes it a little"
+
+  git checkout B
+  write_lines B 2 3 4 5 6 7 8 9 10 >a
+  git commit -am "B changes 'a'"
+
+  baseline renames
+  baseline find-renames -X find-renames=60%
+  conflicts -X find-renames=90
+  # 'ort' always detects renames, but 'recursive' respects this option.
+  conflicts -s recursive -X no-renames
+)
@@ -0,0 +1,92 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+function tick () {
+  if test -z "${tick+set}"
+  then
+    tick=1112911993
+  else
+    tick=$(($tick + 60))
+  fi
+  GIT_COMMITTER_DATE="$tick -0700"
+  GIT_AUTHOR_DATE="$tick -0700"
+  export GIT_COMMITTER_DATE GIT_AUTHOR_DATE
+}
+
+# Create a commit in the superproject on top of `parent` which points the submodule to the given commit.
+function commit_with_submodule () {
+  local branch=${1:?the branch to create}
+  local commit=${2:?the commit the submodule should point to}
+  local parent=${3:-main}
+
+  git checkout -q -b "$branch" "$parent"
+  git update-index --cacheinfo "160000,$commit,sub"
+  tick
+  git commit -q -m "$branch"
+}
+
+# Merge `theirs` into `ours` with `git merge-tree` and store the resulting tree in `.git/<name>.tree`.
+# The merge must conflict.
+function baseline () {
+  local name=${1:?the name of the case}
+  local ours=${2:?our side}
+  local theirs=${3:?their side}
+
+  git merge-tree --write-tree "$ours" "$theirs" > ".git/${name}.merge-info" && {
+    echo "expected merge of '$ours' and '$theirs' to conflict" >&2
+    exit 1
+  }
+  head -n 1 ".git/${name}.merge-info" > ".git/${name}.tree"
+}
+
+tick
+git init super
+(cd super
+  git init sub
+  (cd sub
+    echo root > file
+    git add file && tick && git commit -m "root"
+    git branch root
+
+    git checkout -b a root
+    echo a > file && tick && git commit -am "a"
+
+    git checkout -b b root
+    echo b > b && git add b && tick && git commit -m "b"
+
+    git checkout -b merged a
+    tick && git merge --no-edit b
+
+    git checkout -b other root
+    echo other > other && git add other && tick && git commit -m "other"
+
+    # This merge also contains 'a' and 'b', but isn't suggested as it contains 'merged'.
+    git checkout -b merged-more merged
+    tick && git merge --no-edit other
+
+    git checkout -b a-descendant a
+    echo a-descendant > file && tick && git commit -am "a-descendant"
+    git checkout -q root
+  )
+
+  git add sub && tick && git commit -m "init with submodule at 'root'"
+
+  commit_with_submodule diverged-A "$(git -C sub rev-parse a)"
+  commit_with_submodule diverged-B "$(git -C sub rev-parse b)"
+  git -C sub rev-parse merged > .git/diverged.candidates
+
+  commit_with_submodule rewound-base "$(git -C sub rev-parse a)"
+  commit_with_submodule rewound-A "$(git -C sub rev-parse b)" rewound-base
+  commit_with_submodule rewound-B "$(git -C sub rev-parse a-descendant)" rewound-base
+
+  commit_with_submodule fast-forward-A "$(git -C sub rev-parse a-descendant)"
+  commit_with_submodule fast-forward-B "$(git -C sub rev-parse a)"
+
+  commit_with_submodule missing-A "$(git -C sub rev-parse a)"
+  commit_with_submodule missing-B "$(git -C sub rev-parse a | tr '0-9a-f' '1-9a-f0')"
+
+  git checkout -q main
+  baseline diverged diverged-A diverged-B
+  baseline rewound rewound-A rewound-B
+  baseline missing missing-A missing-B
+)
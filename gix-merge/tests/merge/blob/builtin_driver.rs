@@ -125,6 +125,7 @@ mod text {
                 builtin_driver::text::Options {
                     conflict: Conflict::ResolveWithUnion,
                     diff_algorithm: imara_diff::Algorithm::Myers,
+                    whitespace: Default::default(),
                 },
             ),
             (
@@ -301,6 +302,118 @@ mod text {
         }
     }
 
+    #[test]
+    fn whitespace_changes_can_be_ignored() {
+        let base = "a\nx y\nc\nd\ne\nf\ng\nh\n";
+        let ignore_space_change = builtin_driver::text::Options {
+            whitespace: gix_diff::blob::Whitespace {
+                ignore_space_change: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        for (ours, theirs, expected, reason) in [
+            (
+                "a\nx  y\nc\nd\ne\nf\ng \nh\n",
+                "a\nx z\nc\nd\ne\nf\ng\nH\n",
+                "a\nx z\nc\nd\ne\nf\ng\nH\n",
+                "if we only changed whitespace, their version is used",
+            ),
+            (
+                "A\nx  y\nc\nd\ne\nf\ng\nh\n",
+                "a\nx y\nc\nd\ne\nf\ng \nH\n",
+                "A\nx  y\nc\nd\ne\nf\ng\nH\n",
+                "lines that only changed in whitespace are taken from our side",
+            ),
+        ] {
+            let mut input = imara_diff::InternedInput::default();
+            let mut out = Vec::new();
+            let actual = builtin_driver::text(
+                &mut out,
+                &mut input,
+                Default::default(),
+                ours.as_bytes(),
+                base.as_bytes(),
+                theirs.as_bytes(),
+                ignore_space_change,
+            );
+            assert_eq!(actual, Resolution::Complete, "{reason}");
+            assert_eq!(out.as_bstr(), expected, "{reason}");
+        }
+
+        let mut input = imara_diff::InternedInput::default();
+        let mut out = Vec::new();
+        let actual = builtin_driver::text(
+            &mut out,
+            &mut input,
+            Default::default(),
+            b"a\nx  y\nc\n",
+            b"a\nx y\nc\n",
+            b"a\nx z\nc\n",
+            Default::default(),
+        );
+        assert_eq!(actual, Resolution::Conflict, "whitespace changes conflict by default");
+
+        let mut input = imara_diff::InternedInput::default();
+        let mut out = Vec::new();
+        let actual = builtin_driver::text(
+            &mut out,
+            &mut input,
+            Default::default(),
+            b"a\r\nx y\r\nc\n",
+            b"a\nx y\nc\n",
+            b"a\nx y\nC\n",
+            builtin_driver::text::Options {
+                whitespace: gix_diff::blob::Whitespace {
+                    ignore_cr_at_eol: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
+        assert_eq!(actual, Resolution::Complete);
+        assert_eq!(
+            out.as_bstr(),
+            "a\nx y\nC\n",
+            "only carriage returns were added, so their version is used"
+        );
+    }
+
+    #[test]
+    fn diff_algorithm_is_honored() {
+        let base = b"a\nx\nb\nx\nc\nd\ne\n";
+        let ours = b"c\nd\nx\na\nx\nb\ne\n";
+        let theirs = b"a\ny\nb\nx\nc\nd\ne\n";
+        for (diff_algorithm, expected, reason) in [
+            (
+                imara_diff::Algorithm::Histogram,
+                Resolution::Complete,
+                "the first `x` is unchanged on our side as `c` and `d` are moved before it",
+            ),
+            (
+                imara_diff::Algorithm::Patience,
+                Resolution::Conflict,
+                "the first `x` is moved on our side as the unique lines `c` and `d` are kept",
+            ),
+        ] {
+            let mut input = imara_diff::InternedInput::default();
+            let mut out = Vec::new();
+            let actual = builtin_driver::text(
+                &mut out,
+                &mut input,
+                Default::default(),
+                ours,
+                base,
+                theirs,
+                builtin_driver::text::Options {
+                    diff_algorithm,
+                    ..Default::default()
+                },
+            );
+            assert_eq!(actual, expected, "{reason}");
+        }
+    }
+
     #[test]
     fn both_differ_partially_resolution_is_conflicting() {
         for (conflict, expected) in [
@@ -388,6 +501,7 @@ mod text {
             {
                 let options = builtin_driver::text::Options {
                     diff_algorithm: imara_diff::Algorithm::Histogram,
+                    whitespace: Default::default(),
                     conflict: Conflict::Keep {
                         style: builtin_driver::text::ConflictStyle::Merge,
                         marker_size: 7.try_into().unwrap(),
@@ -406,6 +520,7 @@ mod text {
             {
                 let options = builtin_driver::text::Options {
                     diff_algorithm: imara_diff::Algorithm::Myers,
+                    whitespace: Default::default(),
                     conflict: Conflict::Keep {
                         style: builtin_driver::text::ConflictStyle::Merge,
                         marker_size: 7.try_into().unwrap(),
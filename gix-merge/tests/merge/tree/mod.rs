@@ -66,6 +66,7 @@ fn run_baseline() -> crate::Result {
             &mut graph,
             &mut diff_resource_cache,
             &mut blob_merge,
+            &mut Submodules(&root),
             &odb,
             &mut |id| id.to_hex_with_len(7).to_string(),
             options.clone(),
@@ -173,6 +174,7 @@ fn run_baseline() -> crate::Result {
                 &mut graph,
                 &mut diff_resource_cache,
                 &mut blob_merge,
+                &mut Submodules(&root),
                 &odb,
                 &mut |id| id.to_hex_with_len(7).to_string(),
                 options.clone(),
@@ -206,7 +208,7 @@ fn run_baseline() -> crate::Result {
         "BUG: update this number, and don't forget to remove a filter in the end"
     );
     assert_eq!(
-        skipped_tree_resolve_cases, 102,
+        skipped_tree_resolve_cases, 106,
         "this is done when no case is skipped, and we don't want to accidentally skip them.\
         Some don't actually have conflicts.\
         The ones we skipped don't have irreconcilable conflicts"
@@ -215,6 +217,449 @@ fn run_baseline() -> crate::Result {
     Ok(())
 }
 
+mod strategy {
+    use std::path::Path;
+
+    use gix_merge::{
+        commit::Strategy,
+        tree::{StrategyOption, TreatAsUnresolved},
+    };
+    use gix_object::Write;
+
+    use crate::tree::{basic_merge_options, new_blob_merge_platform, new_diff_resource_cache};
+
+    fn merge_b_into_a(
+        root: &Path,
+        strategy_options: &[&str],
+        strategy: Strategy,
+    ) -> crate::Result<(gix_hash::ObjectId, bool)> {
+        let object_hash = gix_testtools::object_hash();
+        let odb = gix_odb::at_opts(
+            root.join(".git/objects"),
+            Vec::new(),
+            gix_odb::store::init::Options {
+                object_hash,
+                ..Default::default()
+            },
+        )?;
+        let odb = gix_odb::memory::Proxy::new(odb, object_hash);
+        let commit_id = |name: &str| -> crate::Result<gix_hash::ObjectId> {
+            let hex = std::fs::read_to_string(root.join(".git/refs/heads").join(name))?;
+            Ok(gix_hash::ObjectId::from_hex(hex.trim().as_bytes())?)
+        };
+
+        let mut options = basic_merge_options();
+        options.strategy = strategy;
+        for option in strategy_options {
+            options
+                .tree_merge
+                .apply_strategy_option(StrategyOption::try_from(bstr::BStr::new(option))?);
+        }
+        let mut outcome = gix_merge::commit(
+            commit_id("A")?,
+            commit_id("B")?,
+            Default::default(),
+            &mut gix_revwalk::Graph::new(&odb, None),
+            &mut new_diff_resource_cache(root),
+            &mut new_blob_merge_platform(root, None),
+            &mut (),
+            &odb,
+            &mut |id| id.to_hex_with_len(7).to_string(),
+            options,
+        )?
+        .tree_merge;
+        let has_conflicts = outcome.has_unresolved_conflicts(TreatAsUnresolved::git());
+        Ok((outcome.tree.write(|tree| odb.write(tree))?, has_conflicts))
+    }
+
+    fn expected_tree(root: &Path, name: &str) -> crate::Result<gix_hash::ObjectId> {
+        let hex = std::fs::read_to_string(root.join(".git").join(format!("{name}.tree")))?;
+        Ok(gix_hash::ObjectId::from_hex(hex.trim().as_bytes())?)
+    }
+
+    #[test]
+    fn options_match_git() -> crate::Result {
+        let root = gix_testtools::scripted_fixture_read_only("tree-strategy-options.sh")?;
+        for (repo, expected, strategy_options) in [
+            ("favor", "ours", &["ours"][..]),
+            ("favor", "theirs", &["theirs"]),
+            ("whitespace", "ignore-space-change", &["ignore-space-change"]),
+            ("whitespace", "ignore-all-space", &["ignore-all-space"]),
+            ("space-at-eol", "ignore-space-at-eol", &["ignore-space-at-eol"]),
+            ("renormalize", "renormalize", &["renormalize"]),
+            ("renames", "renames", &[]),
+            ("renames", "find-renames", &["find-renames=60%"]),
+        ] {
+            let repo_root = root.join(repo);
+            let (actual, has_conflicts) = merge_b_into_a(&repo_root, strategy_options, Strategy::Ort)?;
+            assert!(!has_conflicts, "{repo}: {strategy_options:?} resolves all conflicts");
+            assert_eq!(
+                actual,
+                expected_tree(&repo_root, expected)?,
+                "{repo}: {strategy_options:?} merges like Git"
+            );
+        }
+
+        for (repo, strategy_options) in [
+            ("favor", &[][..]),
+            ("whitespace", &[]),
+            ("whitespace", &["ignore-space-at-eol"]),
+            ("space-at-eol", &[]),
+            ("renormalize", &[]),
+            ("renormalize", &["renormalize", "no-renormalize"]),
+            ("renames", &["find-renames=90"]),
+            ("renames", &["no-renames"]),
+        ] {
+            let (_, has_conflicts) = merge_b_into_a(&root.join(repo), strategy_options, Strategy::Ort)?;
+            assert!(has_conflicts, "{repo}: {strategy_options:?} conflicts, like in Git");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn ours() -> crate::Result {
+        let root = gix_testtools::scripted_fixture_read_only("tree-strategy-options.sh")?.join("favor");
+        let (actual, has_conflicts) = merge_b_into_a(&root, &["theirs"], Strategy::Ours)?;
+        assert!(!has_conflicts);
+        assert_eq!(
+            actual,
+            expected_tree(&root, "strategy-ours")?,
+            "strategy options don't matter as their changes are ignored entirely"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn parse_options() -> crate::Result {
+        use gix_diff::blob::Algorithm;
+        let parse = |option: &str| StrategyOption::try_from(bstr::BStr::new(option));
+        for (option, expected) in [
+            ("ours", StrategyOption::Ours),
+            ("theirs", StrategyOption::Theirs),
+            ("ignore-space-change", StrategyOption::IgnoreSpaceChange),
+            ("ignore-all-space", StrategyOption::IgnoreAllSpace),
+            ("ignore-space-at-eol", StrategyOption::IgnoreSpaceAtEol),
+            ("ignore-cr-at-eol", StrategyOption::IgnoreCrAtEol),
+            ("renormalize", StrategyOption::Renormalize),
+            ("no-renormalize", StrategyOption::NoRenormalize),
+            ("patience", StrategyOption::DiffAlgorithm(Algorithm::Patience)),
+            (
+                "diff-algorithm=histogram",
+                StrategyOption::DiffAlgorithm(Algorithm::Histogram),
+            ),
+            (
+                "diff-algorithm=minimal",
+                StrategyOption::DiffAlgorithm(Algorithm::MyersMinimal),
+            ),
+            ("no-renames", StrategyOption::NoRenames),
+            ("find-renames", StrategyOption::FindRenames(None)),
+            ("find-renames=0", StrategyOption::FindRenames(None)),
+            ("find-renames=5", StrategyOption::FindRenames(Some(0.5))),
+            ("find-renames=75", StrategyOption::FindRenames(Some(0.75))),
+            ("find-renames=0.25", StrategyOption::FindRenames(Some(0.25))),
+            ("find-renames=5%", StrategyOption::FindRenames(Some(0.05))),
+            ("find-renames=200%", StrategyOption::FindRenames(Some(1.0))),
+            ("rename-threshold=50%", StrategyOption::FindRenames(Some(0.5))),
+        ] {
+            assert_eq!(parse(option)?, expected, "{option}");
+        }
+
+        for invalid in [
+            "subtree",
+            "Ours",
+            "diff-algorithm=unknown",
+            "find-renames=50%%",
+            "find-renames=a",
+        ] {
+            assert!(parse(invalid).is_err(), "{invalid}");
+        }
+        Ok(())
+    }
+}
+
+mod submodule {
+    use gix_merge::tree::{Conflict, Resolution, ResolutionFailure, TreatAsUnresolved, submodule::Failure};
+    use gix_object::Write;
+
+    use crate::tree::{Submodules, basic_merge_options, new_blob_merge_platform, new_diff_resource_cache};
+
+    fn id_at(path: &std::path::Path) -> crate::Result<gix_hash::ObjectId> {
+        Ok(gix_hash::ObjectId::from_hex(
+            std::fs::read_to_string(path)?.trim().as_bytes(),
+        )?)
+    }
+
+    fn merge(
+        root: &std::path::Path,
+        case: &str,
+        submodules: &mut dyn gix_merge::tree::submodule::Find,
+    ) -> crate::Result<(gix_hash::ObjectId, Vec<Conflict>, bool)> {
+        let object_hash = gix_testtools::object_hash();
+        let odb = gix_odb::at_opts(
+            root.join(".git/objects"),
+            Vec::new(),
+            gix_odb::store::init::Options {
+                object_hash,
+                ..Default::default()
+            },
+        )?;
+        let odb = gix_odb::memory::Proxy::new(odb, object_hash);
+        let mut outcome = gix_merge::commit(
+            id_at(&root.join(".git/refs/heads").join(format!("{case}-A")))?,
+            id_at(&root.join(".git/refs/heads").join(format!("{case}-B")))?,
+            Default::default(),
+            &mut gix_revwalk::Graph::new(&odb, None),
+            &mut new_diff_resource_cache(root),
+            &mut new_blob_merge_platform(root, None),
+            submodules,
+            &odb,
+            &mut |id| id.to_hex_with_len(7).to_string(),
+            basic_merge_options(),
+        )?
+        .tree_merge;
+        let has_unresolved_conflicts = outcome.has_unresolved_conflicts(TreatAsUnresolved::git());
+        Ok((
+            outcome.tree.write(|tree| odb.write(tree))?,
+            outcome.conflicts,
+            has_unresolved_conflicts,
+        ))
+    }
+
+    #[test]
+    fn conflicts_match_git() -> crate::Result {
+        let root = gix_testtools::scripted_fixture_read_only("tree-submodules.sh")?.join("super");
+        for (case, expected_failure) in [
+            (
+                "diverged",
+                Failure::Diverged {
+                    merge_candidates: vec![id_at(&root.join(".git/diverged.candidates"))?],
+                },
+            ),
+            ("rewound", Failure::MayHaveRewinds),
+            ("missing", Failure::CommitsMissing),
+        ] {
+            let (actual_tree, conflicts, has_unresolved_conflicts) = merge(&root, case, &mut Submodules(&root))?;
+            assert_eq!(
+                actual_tree,
+                id_at(&root.join(".git").join(format!("{case}.tree")))?,
+                "{case}: like Git, we keep our side of the submodule"
+            );
+            assert!(has_unresolved_conflicts);
+            assert_eq!(conflicts.len(), 1);
+            let Err(ResolutionFailure::OursModifiedTheirsModifiedSubmodule { failure }) = &conflicts[0].resolution
+            else {
+                panic!("{case}: unexpected resolution {:?}", conflicts[0].resolution)
+            };
+            assert_eq!(*failure, expected_failure, "{case}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn unavailable_submodules_conflict() -> crate::Result {
+        let root = gix_testtools::scripted_fixture_read_only("tree-submodules.sh")?.join("super");
+        let (actual_tree, conflicts, has_unresolved_conflicts) = merge(&root, "diverged", &mut ())?;
+        assert_eq!(actual_tree, id_at(&root.join(".git/diverged.tree"))?);
+        assert!(has_unresolved_conflicts);
+        assert!(matches!(
+            conflicts[0].resolution,
+            Err(ResolutionFailure::OursModifiedTheirsModifiedSubmodule {
+                failure: Failure::NotAvailable
+            })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn fast_forward() -> crate::Result {
+        let root = gix_testtools::scripted_fixture_read_only("tree-submodules.sh")?.join("super");
+        let (_, conflicts, has_unresolved_conflicts) = merge(&root, "fast-forward", &mut Submodules(&root))?;
+        assert!(!has_unresolved_conflicts);
+        let Ok(Resolution::OursModifiedTheirsModifiedThenSubmoduleFastForward { commit_id }) = conflicts[0].resolution
+        else {
+            panic!("unexpected resolution {:?}", conflicts[0].resolution)
+        };
+        assert_eq!(
+            commit_id,
+            id_at(&root.join("sub/.git/refs/heads/a-descendant"))?,
+            "the descendant is chosen"
+        );
+        Ok(())
+    }
+}
+
+/// Access submodules that are checked out in the worktree at `self.0`, without the need for `.gitmodules`.
+struct Submodules<'a>(&'a Path);
+
+impl gix_merge::tree::submodule::Find for Submodules<'_> {
+    fn find_submodule(
+        &mut self,
+        rela_path: &bstr::BStr,
+    ) -> Result<Option<gix_merge::tree::submodule::Access<'_>>, Box<dyn std::error::Error + Send + Sync + 'static>>
+    {
+        let git_dir = self.0.join(gix_path::from_bstr(rela_path)).join(".git");
+        if !git_dir.is_dir() {
+            return Ok(None);
+        }
+        let object_hash = gix_testtools::object_hash();
+        let objects = gix_odb::at_opts(
+            git_dir.join("objects"),
+            Vec::new(),
+            gix_odb::store::init::Options {
+                object_hash,
+                ..Default::default()
+            },
+        )?;
+        let mut tips = Vec::new();
+        for entry in std::fs::read_dir(git_dir.join("refs/heads"))? {
+            let hex = std::fs::read_to_string(entry?.path())?;
+            tips.push(gix_hash::ObjectId::from_hex(hex.trim().as_bytes())?);
+        }
+        Ok(Some(gix_merge::tree::submodule::Access {
+            objects: Box::new(objects),
+            tips,
+        }))
+    }
+}
+
+mod octopus {
+    use std::path::Path;
+
+    use gix_merge::commit::octopus::{Error, Outcome, Step};
+
+    use crate::tree::{basic_merge_options, new_blob_merge_platform, new_diff_resource_cache};
+
+    fn id_at(path: &Path) -> crate::Result<gix_hash::ObjectId> {
+        Ok(gix_hash::ObjectId::from_hex(
+            std::fs::read_to_string(path)?.trim().as_bytes(),
+        )?)
+    }
+
+    fn branch(root: &Path, name: &str) -> crate::Result<gix_hash::ObjectId> {
+        id_at(&root.join(".git/refs/heads").join(name))
+    }
+
+    fn octopus(
+        root: &Path,
+        ours: &str,
+        theirs: &[&str],
+        no_fast_forward: bool,
+    ) -> crate::Result<Result<Outcome, Error>> {
+        let object_hash = gix_testtools::object_hash();
+        let odb = gix_odb::at_opts(
+            root.join(".git/objects"),
+            Vec::new(),
+            gix_odb::store::init::Options {
+                object_hash,
+                ..Default::default()
+            },
+        )?;
+        let odb = gix_odb::memory::Proxy::new(odb, object_hash);
+        let their_commits = theirs
+            .iter()
+            .map(|name| branch(root, name))
+            .collect::<crate::Result<Vec<_>>>()?;
+        let options = basic_merge_options();
+        Ok(gix_merge::commit::octopus(
+            branch(root, ours)?,
+            &their_commits,
+            &mut gix_revwalk::Graph::new(&odb, None),
+            &mut new_diff_resource_cache(root),
+            &mut new_blob_merge_platform(root, None),
+            &mut (),
+            &odb,
+            &mut |id| id.to_hex_with_len(7).to_string(),
+            gix_merge::commit::octopus::Options {
+                allow_missing_merge_base: false,
+                tree_merge: options.tree_merge,
+                use_first_merge_base: false,
+                treat_as_unresolved: Default::default(),
+                no_fast_forward,
+            },
+        ))
+    }
+
+    fn expected(root: &Path, name: &str) -> crate::Result<(gix_hash::ObjectId, Vec<gix_hash::ObjectId>)> {
+        let tree_id = id_at(&root.join(".git").join(format!("{name}.tree")))?;
+        let parents = std::fs::read_to_string(root.join(".git").join(format!("{name}.parents")))?
+            .lines()
+            .map(|hex| gix_hash::ObjectId::from_hex(hex.as_bytes()))
+            .collect::<Result<_, _>>()?;
+        Ok((tree_id, parents))
+    }
+
+    #[test]
+    fn merges_like_git() -> crate::Result {
+        let root = gix_testtools::scripted_fixture_read_only("tree-octopus.sh")?.join("octopus");
+        let outcome = octopus(&root, "main", &["topic-a", "topic-b", "topic-c", "old"], false)??;
+        assert_eq!((outcome.tree_id, outcome.parents), expected(&root, "merged")?);
+        assert_eq!(outcome.steps.len(), 4);
+        assert!(
+            outcome.steps[..3]
+                .iter()
+                .all(|step| matches!(step, Step::Merged { .. }))
+        );
+        let Step::Merged { conflicts, .. } = &outcome.steps[2] else {
+            unreachable!("checked above")
+        };
+        assert!(
+            conflicts.len() == 1 && conflicts[0].resolution.is_ok(),
+            "'a' was changed in different places, which is resolved with a content merge"
+        );
+        assert!(
+            matches!(outcome.steps[3], Step::AlreadyUpToDate { commit_id } if commit_id == branch(&root, "old")?),
+            "'old' is contained in 'main' and thus isn't a parent"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn fast_forward_like_git() -> crate::Result {
+        let root = gix_testtools::scripted_fixture_read_only("tree-octopus.sh")?.join("octopus");
+        let outcome = octopus(&root, "old", &["topic-a", "topic-b"], false)??;
+        assert_eq!(
+            (outcome.tree_id, outcome.parents),
+            expected(&root, "fast-forward")?,
+            "'old' is contained in both, so it's not a parent"
+        );
+        assert!(matches!(outcome.steps[0], Step::FastForward { .. }));
+        assert!(matches!(outcome.steps[1], Step::Merged { .. }));
+
+        let outcome = octopus(&root, "old", &["topic-a", "topic-b"], true)??;
+        assert_eq!(
+            outcome.parents,
+            [
+                branch(&root, "old")?,
+                branch(&root, "topic-a")?,
+                branch(&root, "topic-b")?
+            ],
+            "without fast-forwards, our commit is always the first parent"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn refuses_to_merge_with_conflicts() -> crate::Result {
+        let root = gix_testtools::scripted_fixture_read_only("tree-octopus.sh")?.join("octopus");
+        let err = octopus(&root, "main", &["topic-a", "conflicting"], false)?.unwrap_err();
+        assert!(
+            matches!(&err, Error::Conflict { their_commit_id, conflicts } if *their_commit_id == branch(&root, "conflicting")? && conflicts.len() == 1),
+            "{err:?}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn needs_at_least_one_commit() -> crate::Result {
+        let root = gix_testtools::scripted_fixture_read_only("tree-octopus.sh")?.join("octopus");
+        assert!(matches!(
+            octopus(&root, "main", &[], false)?,
+            Err(Error::NothingToMerge)
+        ));
+        Ok(())
+    }
+}
+
 fn basic_merge_options() -> Options {
     gix_merge::commit::Options {
         allow_missing_merge_base: true,
@@ -232,7 +677,9 @@ fn basic_merge_options() -> Options {
             blob_merge_command_ctx: Default::default(),
             fail_on_conflict: None,
             marker_size_multiplier: 0,
+            filter_mode: None,
         },
+        strategy: gix_merge::commit::Strategy::Ort,
     }
 }
 
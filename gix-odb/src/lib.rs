@@ -165,6 +165,10 @@ pub struct Store {
 
     /// The amount of times we re-read the disk state to consolidate our in-memory representation.
     pub(crate) num_disk_state_consolidation: AtomicUsize,
+    /// Objects that failed to decode, along with the paths of the packs that contain their corrupt copies.
+    ///
+    /// These copies are skipped when looking up objects, so other copies in other packs, loose objects or alternates are used.
+    pub(crate) bad_packed_objects: parking_lot::RwLock<gix_hashtable::HashMap<gix_hash::ObjectId, Vec<PathBuf>>>,
     /// If true, we are allowed to use multi-pack indices and they must have the `object_hash` or be ignored.
     use_multi_pack_index: bool,
     /// The hash kind to use for some operations
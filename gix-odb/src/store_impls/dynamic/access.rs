@@ -22,3 +22,35 @@ impl Store {
         self.replacements.iter().copied()
     }
 }
+
+/// Handling of corrupt objects in packs
+impl Store {
+    /// Return `true` if the copy of the object `id` in the pack at `pack_path` was found to be corrupt before.
+    pub(crate) fn is_bad_packed_object(&self, pack_path: &std::path::Path, id: &gix_hash::oid) -> bool {
+        let bad = self.bad_packed_objects.read();
+        !bad.is_empty()
+            && bad
+                .get(id)
+                .is_some_and(|packs| packs.iter().any(|path| path == pack_path))
+    }
+
+    /// Remember that the copy of the object `id` in the pack at `pack_path` is corrupt as it failed to decode with `err`,
+    /// so that it's skipped from now on.
+    pub(crate) fn mark_bad_packed_object(
+        &self,
+        pack_path: &std::path::Path,
+        id: &gix_hash::oid,
+        _err: &dyn std::error::Error,
+    ) {
+        gix_features::trace::warn!(
+            err = ?_err,
+            "Object {id} in pack at '{}' is corrupt and will be looked up elsewhere",
+            pack_path.display()
+        );
+        let mut bad = self.bad_packed_objects.write();
+        let packs = bad.entry(id.to_owned()).or_default();
+        if !packs.iter().any(|path| path == pack_path) {
+            packs.push(pack_path.to_owned());
+        }
+    }
+}
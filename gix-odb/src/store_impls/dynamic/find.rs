@@ -28,6 +28,11 @@ pub(crate) mod error {
             /// The original object to lookup
             id: gix_hash::ObjectId,
         },
+        #[error("Object {id} is only available in packs in which it was found to be corrupt")]
+        CorruptPackedObject {
+            /// The object which couldn't be decoded
+            id: gix_hash::ObjectId,
+        },
         #[error("The base object {} could not be found but is required to decode {}", .base_id, .id)]
         DeltaBaseMissing {
             /// the id of the base object which failed to lookup
@@ -46,6 +51,19 @@ pub(crate) mod error {
         },
     }
 
+    impl Error {
+        /// Return `true` if this error is caused by a corrupt pack entry, either of the object itself or of one of its
+        /// delta bases, so other copies of the object should be tried.
+        pub(crate) fn is_corruption(&self) -> bool {
+            match self {
+                Error::Pack(err) => super::is_corruption(err),
+                Error::EntryType(_) | Error::CorruptPackedObject { .. } => true,
+                Error::DeltaBaseLookup { err, .. } => err.is_corruption(),
+                _ => false,
+            }
+        }
+    }
+
     #[derive(Copy, Clone)]
     pub(crate) struct DeltaBaseRecursion<'a> {
         pub depth: usize,
@@ -80,6 +98,15 @@ pub use error::Error;
 
 use crate::store::types::PackId;
 
+/// Return `true` if `err` indicates that the pack entry that failed to decode is corrupt, so other copies of it should be tried.
+///
+/// Other errors, like failing to allocate memory for an object, may also be caused by corrupt entry headers. These are detected
+/// by [`IntraPackLookup::crc32_mismatches()`](handle::IntraPackLookup::crc32_mismatches()) instead.
+pub(crate) fn is_corruption(err: &gix_pack::data::decode::Error) -> bool {
+    use gix_pack::data::decode::Error;
+    matches!(err, Error::ZlibInflate(_) | Error::EntryType(_) | Error::Delta(_))
+}
+
 impl<S> super::Handle<S>
 where
     S: Deref<Target = super::Store> + Clone,
@@ -110,6 +137,9 @@ where
             }
         }
 
+        let mut fetched_from_promisor = false;
+        // Set if only corrupt copies of the object were found so far, to report them if there is no other copy.
+        let mut corruption = None;
         'outer: loop {
             {
                 let marker = snapshot.marker;
@@ -139,13 +169,24 @@ where
                                             // nothing new in the index, kind of unexpected to not have a pack but to also
                                             // to have no new index yet. We set the new index before removing any slots, so
                                             // this should be observable.
-                                            return Ok(None);
+                                            return corruption.map_or(Ok(None), Err);
                                         }
                                     }
                                 }
                             },
                         };
-                        let entry = pack.entry(pack_offset)?;
+                        if self.store.is_bad_packed_object(pack.path(), id) {
+                            corruption.get_or_insert_with(|| Error::CorruptPackedObject { id: id.to_owned() });
+                            continue;
+                        }
+                        let entry = match pack.entry(pack_offset) {
+                            Ok(entry) => entry,
+                            Err(err) => {
+                                self.store.mark_bad_packed_object(pack.path(), id, &err);
+                                corruption = Some(err.into());
+                                continue;
+                            }
+                        };
                         let header_size = entry.header_size();
                         let res = pack.decode_entry(
                             entry,
@@ -185,28 +226,41 @@ where
                                 // The whole ordeal isn't as efficient as it could be due to memory allocation and
                                 // later mem-copying when trying again.
                                 let mut buf = Vec::new();
-                                let obj_kind = self
-                                    .try_find_cached_inner(
-                                        &base_id,
-                                        &mut buf,
-                                        inflate,
-                                        pack_cache,
-                                        snapshot,
-                                        recursion
-                                            .map(error::DeltaBaseRecursion::inc_depth)
-                                            .or_else(|| error::DeltaBaseRecursion::new(id).into()),
-                                    )
-                                    .map_err(|err| Error::DeltaBaseLookup {
-                                        err: Box::new(err),
-                                        base_id,
-                                        id: id.to_owned(),
-                                    })?
-                                    .ok_or_else(|| Error::DeltaBaseMissing {
-                                        base_id,
-                                        id: id.to_owned(),
-                                    })?
-                                    .0
-                                    .kind;
+                                let pack_path = pack.path().to_owned();
+                                let obj_kind = match self.try_find_cached_inner(
+                                    &base_id,
+                                    &mut buf,
+                                    inflate,
+                                    pack_cache,
+                                    snapshot,
+                                    recursion
+                                        .map(error::DeltaBaseRecursion::inc_depth)
+                                        .or_else(|| error::DeltaBaseRecursion::new(id).into()),
+                                ) {
+                                    Ok(Some((base, _location))) => base.kind,
+                                    Ok(None) => {
+                                        return Err(Error::DeltaBaseMissing {
+                                            base_id,
+                                            id: id.to_owned(),
+                                        });
+                                    }
+                                    Err(err) => {
+                                        let is_corruption = err.is_corruption();
+                                        let err = Error::DeltaBaseLookup {
+                                            err: Box::new(err),
+                                            base_id,
+                                            id: id.to_owned(),
+                                        };
+                                        if !is_corruption {
+                                            return Err(err);
+                                        }
+                                        // The base only has corrupt copies, so the object can't be decoded from this pack.
+                                        // As the snapshot may have changed, start over, skipping this copy.
+                                        self.store.mark_bad_packed_object(&pack_path, id, &err);
+                                        corruption = Some(err);
+                                        continue 'outer;
+                                    }
+                                };
                                 let handle::index_lookup::Outcome {
                                     object_index:
                                         handle::IndexForObjectInPack {
@@ -236,7 +290,7 @@ where
                                     .expect("pack to still be available like just now");
                                 let entry = pack.entry(pack_offset)?;
                                 let header_size = entry.header_size();
-                                pack.decode_entry(
+                                let res = pack.decode_entry(
                                     entry,
                                     buffer,
                                     inflate,
@@ -260,8 +314,15 @@ where
                                             })
                                     },
                                     pack_cache,
-                                )
-                                .map(move |r| {
+                                );
+                                if let Err(err) = &res {
+                                    if is_corruption(err) || index_file.crc32_mismatches(id, pack) {
+                                        self.store.mark_bad_packed_object(pack.path(), id, err);
+                                        corruption = res.err().map(Into::into);
+                                        continue 'outer;
+                                    }
+                                }
+                                res.map(move |r| {
                                     (
                                         gix_object::Data {
                                             kind: r.kind,
@@ -276,6 +337,11 @@ where
                                     )
                                 })
                             }
+                            Err(err) if is_corruption(&err) || index_file.crc32_mismatches(id, pack) => {
+                                self.store.mark_bad_packed_object(pack.path(), id, &err);
+                                corruption = Some(err.into());
+                                continue;
+                            }
                             Err(err) => Err(err),
                         }?;
 
@@ -302,7 +368,15 @@ where
                     *snapshot = new_snapshot;
                     self.clear_cache();
                 }
-                None => return Ok(None),
+                None => {
+                    if recursion.is_none() && !fetched_from_promisor {
+                        fetched_from_promisor = true;
+                        if self.fetch_promised_object(id, snapshot)? {
+                            continue 'outer;
+                        }
+                    }
+                    return corruption.map_or(Ok(None), Err);
+                }
             }
         }
     }
@@ -451,8 +525,8 @@ where
             let snapshot = self.snapshot.borrow();
             {
                 for index in &snapshot.indices {
-                    if let Some(iter) = index.iter(pack_id) {
-                        return Some(iter.map(|e| (e.pack_offset, e.oid)).collect());
+                    if let Some(entries) = index.pack_offsets_and_oid(pack_id) {
+                        return Some(entries);
                     }
                 }
             }
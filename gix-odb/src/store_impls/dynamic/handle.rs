@@ -14,10 +14,14 @@ pub(crate) enum SingleOrMultiIndex {
     Single {
         index: Arc<gix_pack::index::File>,
         data: Option<Arc<gix_pack::data::File>>,
+        /// `true` if the pack is a promisor pack.
+        promisor: bool,
     },
     Multi {
         index: Arc<gix_pack::multi_index::File>,
         data: Vec<Option<Arc<gix_pack::data::File>>>,
+        /// For each pack in `data`, `true` if it is a promisor pack.
+        promisor: Vec<bool>,
     },
 }
 
@@ -47,6 +51,27 @@ impl IntraPackLookup<'_> {
             }),
         }
     }
+
+    /// Return `true` if the CRC32 of the entry of `id` in `pack` doesn't match the one stored in the index, which indicates
+    /// that the entry is corrupt.
+    ///
+    /// This is always `false` for multi-pack indices or indices of version 1 as they don't store a CRC32 for their entries.
+    pub(crate) fn crc32_mismatches(&self, id: &oid, pack: &gix_pack::data::File) -> bool {
+        let IntraPackLookup::Single(index) = self else {
+            return false;
+        };
+        let Some(entry_index) = index.lookup(id) else {
+            return false;
+        };
+        let Some(expected) = index.crc32_at_index(entry_index) else {
+            return false;
+        };
+        // Entries end where the next entry begins, or at the trailing checksum of the pack.
+        let pack_offset = index.pack_offset_at_index(entry_index);
+        let entry_end = index.next_pack_offset(pack_offset).unwrap_or(pack.pack_end() as u64);
+        pack.entry_slice(pack_offset..entry_end)
+            .is_none_or(|entry| gix_features::hash::crc32(entry) != expected)
+    }
 }
 
 pub struct IndexLookup {
@@ -76,25 +101,46 @@ pub(crate) mod index_lookup {
     }
 
     impl handle::IndexLookup {
-        /// Return an iterator over the entries of the given pack. The `pack_id` is required to identify a pack uniquely within
-        /// a potential multi-pack index.
-        pub(crate) fn iter(
-            &self,
-            pack_id: types::PackId,
-        ) -> Option<Box<dyn Iterator<Item = gix_pack::index::Entry> + '_>> {
+        /// Return the pack offsets and object ids of all entries of the given pack, sorted by pack offset. The `pack_id` is
+        /// required to identify a pack uniquely within a potential multi-pack index.
+        ///
+        /// Reverse indices are used if present, so the entries don't need to be sorted.
+        pub(crate) fn pack_offsets_and_oid(&self, pack_id: types::PackId) -> Option<Vec<(u64, gix_hash::ObjectId)>> {
             (self.id == pack_id.index).then(|| match &self.file {
-                handle::SingleOrMultiIndex::Single { index, .. } => index.iter(),
+                handle::SingleOrMultiIndex::Single { index, .. } => index
+                    .entry_indices_sorted_by_pack_offset()
+                    .into_iter()
+                    .map(|entry_index| {
+                        (
+                            index.pack_offset_at_index(entry_index),
+                            index.oid_at_index(entry_index).to_owned(),
+                        )
+                    })
+                    .collect(),
                 handle::SingleOrMultiIndex::Multi { index, .. } => {
                     let pack_index = pack_id.multipack_index.expect(
                         "BUG: multi-pack index must be set if this is a multi-pack, pack-indices seem unstable",
                     );
-                    Box::new(index.iter().filter_map(move |e| {
-                        (e.pack_index == pack_index).then_some(gix_pack::index::Entry {
-                            oid: e.oid,
-                            pack_offset: e.pack_offset,
-                            crc32: None,
-                        })
-                    }))
+                    let mut entries: Vec<_> = if index.has_reverse_index() {
+                        (0..index.num_objects())
+                            .filter_map(|pack_position| index.entry_index_at_pack_position(pack_position))
+                            .filter(|entry_index| *entry_index < index.num_objects())
+                            .filter_map(|entry_index| {
+                                let (entry_pack_index, pack_offset) =
+                                    index.pack_id_and_pack_offset_at_index(entry_index);
+                                (entry_pack_index == pack_index)
+                                    .then(|| (pack_offset, index.oid_at_index(entry_index).to_owned()))
+                            })
+                            .collect()
+                    } else {
+                        index
+                            .iter()
+                            .filter_map(|e| (e.pack_index == pack_index).then_some((e.pack_offset, e.oid)))
+                            .collect()
+                    };
+                    // This is a no-op if the reverse index already provided the entries of the pack in order.
+                    entries.sort_by_key(|e| e.0);
+                    entries
                 }
             })
         }
@@ -119,6 +165,20 @@ pub(crate) mod index_lookup {
             }
         }
 
+        /// Return `Some(true)` if the given object id exists in this index and is stored in a promisor pack,
+        /// `Some(false)` if it exists in a regular pack, or `None` if it doesn't exist at all.
+        pub(crate) fn is_in_promisor_pack(&self, object_id: &oid) -> Option<bool> {
+            match &self.file {
+                handle::SingleOrMultiIndex::Single { index, promisor, .. } => {
+                    index.lookup(object_id).map(|_| *promisor)
+                }
+                handle::SingleOrMultiIndex::Multi { index, promisor, .. } => index.lookup(object_id).map(|idx| {
+                    let (pack_index, _pack_offset) = index.pack_id_and_pack_offset_at_index(idx);
+                    promisor[pack_index as usize]
+                }),
+            }
+        }
+
         /// Return true if the given object id exists in this index
         pub(crate) fn oid_at_index(&self, entry_index: u32) -> &gix_hash::oid {
             match &self.file {
@@ -165,18 +225,20 @@ pub(crate) mod index_lookup {
         pub(crate) fn lookup(&mut self, object_id: &oid) -> Option<Outcome<'_>> {
             let id = self.id;
             match &mut self.file {
-                handle::SingleOrMultiIndex::Single { index, data } => index.lookup(object_id).map(move |idx| Outcome {
-                    object_index: handle::IndexForObjectInPack {
-                        pack_id: types::PackId {
-                            index: id,
-                            multipack_index: None,
+                handle::SingleOrMultiIndex::Single { index, data, .. } => {
+                    index.lookup(object_id).map(move |idx| Outcome {
+                        object_index: handle::IndexForObjectInPack {
+                            pack_id: types::PackId {
+                                index: id,
+                                multipack_index: None,
+                            },
+                            pack_offset: index.pack_offset_at_index(idx),
                         },
-                        pack_offset: index.pack_offset_at_index(idx),
-                    },
-                    index_file: IntraPackLookup::Single(index),
-                    pack: data,
-                }),
-                handle::SingleOrMultiIndex::Multi { index, data } => index.lookup(object_id).map(move |idx| {
+                        index_file: IntraPackLookup::Single(index),
+                        pack: data,
+                    })
+                }
+                handle::SingleOrMultiIndex::Multi { index, data, .. } => index.lookup(object_id).map(move |idx| {
                     let (pack_index, pack_offset) = index.pack_id_and_pack_offset_at_index(idx);
                     Outcome {
                         object_index: handle::IndexForObjectInPack {
@@ -255,6 +317,7 @@ impl super::Store {
             store: self.clone(),
             refresh: RefreshMode::default(),
             ignore_replacements: false,
+            promisor: None,
             token: Some(token),
             inflate: RefCell::new(Default::default()),
             snapshot: RefCell::new(self.collect_snapshot()),
@@ -272,6 +335,7 @@ impl super::Store {
             store: self.clone(),
             refresh: Default::default(),
             ignore_replacements: false,
+            promisor: None,
             token: Some(token),
             inflate: RefCell::new(Default::default()),
             snapshot: RefCell::new(self.collect_snapshot()),
@@ -365,6 +429,7 @@ impl super::Handle<Rc<super::Store>> {
         let mut cache = store.to_handle_arc();
         cache.refresh = self.refresh;
         cache.max_recursion_depth = self.max_recursion_depth;
+        cache.promisor.clone_from(&self.promisor);
         Ok(cache)
     }
 }
@@ -385,6 +450,7 @@ where
             store: self.store.clone(),
             refresh: self.refresh,
             ignore_replacements: self.ignore_replacements,
+            promisor: self.promisor.clone(),
             token: {
                 let token = self.store.register_handle();
                 match self.token.as_ref().expect("token is always set here ") {
@@ -5,7 +5,10 @@ use gix_hash::oid;
 use super::find::Error;
 use crate::{
     find::Header,
-    store::{find::error::DeltaBaseRecursion, handle, load_index},
+    store::{
+        find::{error::DeltaBaseRecursion, is_corruption},
+        handle, load_index,
+    },
 };
 
 impl<S> super::Handle<S>
@@ -36,6 +39,9 @@ where
             }
         }
 
+        let mut fetched_from_promisor = false;
+        // Set if only corrupt copies of the object were found so far, to report them if there is no other copy.
+        let mut corruption = None;
         'outer: loop {
             {
                 let marker = snapshot.marker;
@@ -65,13 +71,24 @@ where
                                             // nothing new in the index, kind of unexpected to not have a pack but to also
                                             // to have no new index yet. We set the new index before removing any slots, so
                                             // this should be observable.
-                                            return Ok(None);
+                                            return corruption.map_or(Ok(None), Err);
                                         }
                                     }
                                 }
                             },
                         };
-                        let entry = pack.entry(pack_offset)?;
+                        if self.store.is_bad_packed_object(pack.path(), id) {
+                            corruption.get_or_insert_with(|| Error::CorruptPackedObject { id: id.to_owned() });
+                            continue;
+                        }
+                        let entry = match pack.entry(pack_offset) {
+                            Ok(entry) => entry,
+                            Err(err) => {
+                                self.store.mark_bad_packed_object(pack.path(), id, &err);
+                                corruption = Some(err.into());
+                                continue;
+                            }
+                        };
                         let res = match pack.decode_header(entry, inflate, &|id| {
                             index_file.pack_offset_by_id(id).and_then(|pack_offset| {
                                 pack.entry(pack_offset)
@@ -85,24 +102,39 @@ where
                                 // multi-pack. Otherwise this would constitute a thin pack which is only allowed in transit.
                                 // However, if we somehow end up with that, we will resolve it safely, even though we could
                                 // avoid handling this case and error instead.
-                                let hdr = self
-                                    .try_header_inner(
-                                        &base_id,
-                                        inflate,
-                                        snapshot,
-                                        recursion
-                                            .map(DeltaBaseRecursion::inc_depth)
-                                            .or_else(|| DeltaBaseRecursion::new(id).into()),
-                                    )
-                                    .map_err(|err| Error::DeltaBaseLookup {
-                                        err: Box::new(err),
-                                        base_id,
-                                        id: id.to_owned(),
-                                    })?
-                                    .ok_or_else(|| Error::DeltaBaseMissing {
-                                        base_id,
-                                        id: id.to_owned(),
-                                    })?;
+                                let pack_path = pack.path().to_owned();
+                                let hdr = match self.try_header_inner(
+                                    &base_id,
+                                    inflate,
+                                    snapshot,
+                                    recursion
+                                        .map(DeltaBaseRecursion::inc_depth)
+                                        .or_else(|| DeltaBaseRecursion::new(id).into()),
+                                ) {
+                                    Ok(Some(hdr)) => hdr,
+                                    Ok(None) => {
+                                        return Err(Error::DeltaBaseMissing {
+                                            base_id,
+                                            id: id.to_owned(),
+                                        });
+                                    }
+                                    Err(err) => {
+                                        let is_corruption = err.is_corruption();
+                                        let err = Error::DeltaBaseLookup {
+                                            err: Box::new(err),
+                                            base_id,
+                                            id: id.to_owned(),
+                                        };
+                                        if !is_corruption {
+                                            return Err(err);
+                                        }
+                                        // The base only has corrupt copies, so the object can't be decoded from this pack.
+                                        // As the snapshot may have changed, start over, skipping this copy.
+                                        self.store.mark_bad_packed_object(&pack_path, id, &err);
+                                        corruption = Some(err);
+                                        continue 'outer;
+                                    }
+                                };
                                 let handle::index_lookup::Outcome {
                                     object_index:
                                         handle::IndexForObjectInPack {
@@ -131,7 +163,7 @@ where
                                     .as_ref()
                                     .expect("pack to still be available like just now");
                                 let entry = pack.entry(pack_offset)?;
-                                pack.decode_header(entry, inflate, &|id| {
+                                let res = pack.decode_header(entry, inflate, &|id| {
                                     index_file
                                         .pack_offset_by_id(id)
                                         .and_then(|pack_offset| {
@@ -147,8 +179,20 @@ where
                                                 }
                                             })
                                         })
-                                })
-                                .map(Into::into)
+                                });
+                                if let Err(err) = &res {
+                                    if is_corruption(err) || index_file.crc32_mismatches(id, pack) {
+                                        self.store.mark_bad_packed_object(pack.path(), id, err);
+                                        corruption = res.err().map(Into::into);
+                                        continue 'outer;
+                                    }
+                                }
+                                res.map(Into::into)
+                            }
+                            Err(err) if is_corruption(&err) || index_file.crc32_mismatches(id, pack) => {
+                                self.store.mark_bad_packed_object(pack.path(), id, &err);
+                                corruption = Some(err.into());
+                                continue;
                             }
                             Err(err) => Err(err),
                         }?;
@@ -164,7 +208,10 @@ where
             for lodb in snapshot.loose_dbs.iter() {
                 // TODO: remove this double-lookup once the borrow checker allows it.
                 if lodb.contains(id) {
-                    return lodb.try_header(id).map(|opt| opt.map(Into::into)).map_err(Into::into);
+                    return lodb
+                        .try_header_with_inflate(id, inflate)
+                        .map(|opt| opt.map(Into::into))
+                        .map_err(Into::into);
                 }
             }
 
@@ -173,7 +220,15 @@ where
                     *snapshot = new_snapshot;
                     self.clear_cache();
                 }
-                None => return Ok(None),
+                None => {
+                    if recursion.is_none() && !fetched_from_promisor {
+                        fetched_from_promisor = true;
+                        if self.fetch_promised_object(id, snapshot)? {
+                            continue 'outer;
+                        }
+                    }
+                    return corruption.map_or(Ok(None), Err);
+                }
             }
         }
     }
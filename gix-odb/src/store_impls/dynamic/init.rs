@@ -141,6 +141,7 @@ impl Store {
             num_handles_stable: Default::default(),
             num_handles_unstable: Default::default(),
             num_disk_state_consolidation: Default::default(),
+            bad_packed_objects: Default::default(),
         })
     }
 }
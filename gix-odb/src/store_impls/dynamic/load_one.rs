@@ -149,10 +149,12 @@ impl super::Store {
             types::IndexAndPacks::Index(bundle) => handle::SingleOrMultiIndex::Single {
                 index: bundle.index.loaded()?.clone(),
                 data: bundle.data.loaded().cloned(),
+                promisor: bundle.promisor,
             },
             types::IndexAndPacks::MultiIndex(multi) => handle::SingleOrMultiIndex::Multi {
                 index: multi.multi_index.loaded()?.clone(),
                 data: multi.data.iter().map(|f| f.loaded().cloned()).collect(),
+                promisor: multi.promisor.clone(),
             },
         };
         handle::IndexLookup {
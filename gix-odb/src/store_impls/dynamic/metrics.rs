@@ -75,6 +75,7 @@ impl super::Store {
             loose_dbs: index.loose_dbs.len(),
             unreachable_indices,
             unreachable_packs,
+            num_bad_packed_objects: self.bad_packed_objects.read().len(),
         }
     }
 }
@@ -1,5 +1,5 @@
 //! The standard object store which should fit all needs.
-use std::{cell::RefCell, ops::Deref};
+use std::{cell::RefCell, ops::Deref, sync::Arc};
 
 use crate::Store;
 
@@ -22,6 +22,12 @@ where
     /// If true, replacements will not be performed even if these are available.
     pub ignore_replacements: bool,
 
+    /// If set, objects that couldn't be found will be requested from a promisor remote before giving up.
+    ///
+    /// This is what makes partial clones work, as these are allowed to lack objects that the promisor remote
+    /// can provide on demand.
+    pub promisor: Option<Arc<dyn promisor::Fetch>>,
+
     pub(crate) token: Option<handle::Mode>,
     snapshot: RefCell<load_index::Snapshot>,
     inflate: RefCell<gix_zlib::Inflate>,
@@ -56,6 +62,9 @@ pub mod prefix;
 
 mod header;
 
+///
+pub mod promisor;
+
 ///
 pub mod iter;
 
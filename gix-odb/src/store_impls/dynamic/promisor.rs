@@ -0,0 +1,142 @@
+//! Support for partial clones, whose object databases may lack objects which a *promisor remote* promised to provide on demand.
+//!
+//! Packs received from a promisor remote are marked with a `.promisor` file next to them, and objects contained in them
+//! may refer to objects that aren't present locally.
+use std::ops::Deref;
+
+use gix_hash::oid;
+use gix_pack::Find;
+
+use crate::store::load_index;
+
+/// The error returned by implementations of [`Fetch::fetch()`].
+pub type FetchError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+/// A way to obtain objects that are missing locally from a promisor remote.
+///
+/// Implementations are expected to write all received objects into the object database of the handle they are used with,
+/// typically as pack marked with a `.promisor` file, so they can be found once the object database was refreshed.
+pub trait Fetch: Send + Sync {
+    /// Obtain all objects identified by `ids` from a promisor remote and write them into the object database.
+    fn fetch(&self, ids: &[gix_hash::ObjectId]) -> Result<(), FetchError>;
+}
+
+impl<F> Fetch for F
+where
+    F: Fn(&[gix_hash::ObjectId]) -> Result<(), FetchError> + Send + Sync,
+{
+    fn fetch(&self, ids: &[gix_hash::ObjectId]) -> Result<(), FetchError> {
+        self(ids)
+    }
+}
+
+mod error {
+    /// The error returned by [`Handle::fetch_missing()`](crate::store::Handle::fetch_missing()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not fetch {num_objects} missing object(s) from the promisor remote")]
+        Fetch {
+            num_objects: usize,
+            source: super::FetchError,
+        },
+        #[error(transparent)]
+        LoadIndex(#[from] crate::store::load_index::Error),
+    }
+}
+pub use error::Error;
+
+impl<S> super::Handle<S>
+where
+    S: Deref<Target = super::Store> + Clone,
+{
+    /// Return `true` if `id` is contained in a pack that was received from a promisor remote, as indicated by
+    /// a `.promisor` file next to it.
+    ///
+    /// Objects referenced by such objects are allowed to be missing in partial clones as the promisor remote
+    /// promised to provide them on demand. Loose objects are never considered to be promisor objects.
+    pub fn is_in_promisor_pack(&self, id: &oid) -> bool {
+        let mut snapshot = self.snapshot.borrow_mut();
+        loop {
+            for (idx, index) in snapshot.indices.iter().enumerate() {
+                if let Some(is_promisor) = index.is_in_promisor_pack(id) {
+                    if idx != 0 {
+                        snapshot.indices.swap(0, idx);
+                    }
+                    return is_promisor;
+                }
+            }
+
+            match self.store.load_one_index(self.refresh, snapshot.marker) {
+                Ok(Some(new_snapshot)) => {
+                    *snapshot = new_snapshot;
+                    self.clear_cache();
+                }
+                Ok(None) | Err(_) => return false,
+            }
+        }
+    }
+
+    /// Fetch all objects in `ids` that don't exist locally from the [promisor remote](Self::promisor) in a single batch,
+    /// and refresh this handle so they can be found afterwards.
+    ///
+    /// This is more efficient than relying on each object lookup to fetch a single missing object, and should be
+    /// used whenever the set of needed objects is known ahead of time, as when checking out a tree.
+    ///
+    /// Return the amount of objects that were requested, which is `0` if all objects were already present
+    /// or if no promisor remote is configured.
+    pub fn fetch_missing(&self, ids: impl IntoIterator<Item = impl AsRef<oid>>) -> Result<usize, Error> {
+        let Some(promisor) = self.promisor.as_ref() else {
+            return Ok(0);
+        };
+        let missing: Vec<_> = ids
+            .into_iter()
+            .filter(|id| !self.contains(id.as_ref()))
+            .map(|id| id.as_ref().to_owned())
+            .collect();
+        if missing.is_empty() {
+            return Ok(0);
+        }
+        promisor.fetch(&missing).map_err(|err| Error::Fetch {
+            num_objects: missing.len(),
+            source: err,
+        })?;
+
+        let mut snapshot = self.snapshot.borrow_mut();
+        self.refresh_after_fetch(&mut snapshot)?;
+        Ok(missing.len())
+    }
+
+    /// Fetch the missing object `id` from our promisor remote, if there is one, and return `true` if the object lookup
+    /// should be retried.
+    ///
+    /// Failures are treated like the object is missing, just like Git does.
+    pub(crate) fn fetch_promised_object(
+        &self,
+        id: &oid,
+        snapshot: &mut load_index::Snapshot,
+    ) -> Result<bool, load_index::Error> {
+        let Some(promisor) = self.promisor.as_ref() else {
+            return Ok(false);
+        };
+        let _span = gix_features::trace::detail!("gix_odb::Handle::fetch_promised_object()");
+        if let Err(_err) = promisor.fetch(&[id.to_owned()]) {
+            gix_features::trace::error!(err=?_err, "Failed to fetch missing object from promisor remote");
+            return Ok(false);
+        }
+        self.refresh_after_fetch(snapshot)?;
+        Ok(true)
+    }
+
+    fn refresh_after_fetch(&self, snapshot: &mut load_index::Snapshot) -> Result<(), load_index::Error> {
+        // New packs were written by the promisor, so we have to see them even if refreshes are turned off otherwise.
+        if let Some(new_snapshot) = self
+            .store
+            .consolidate_with_disk_state(false /* needs init */, true /* load one new index */)?
+        {
+            *snapshot = new_snapshot;
+            self.clear_cache();
+        }
+        Ok(())
+    }
+}
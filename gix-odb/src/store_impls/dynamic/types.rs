@@ -233,12 +233,16 @@ impl<T: Clone> OnDiskFile<T> {
 pub(crate) struct IndexFileBundle {
     pub index: OnDiskFile<Arc<gix_pack::index::File>>,
     pub data: OnDiskFile<Arc<gix_pack::data::File>>,
+    /// If `true`, the pack is accompanied by a `.promisor` file, marking it as received from a promisor remote.
+    pub promisor: bool,
 }
 
 #[derive(Clone)]
 pub(crate) struct MultiIndexFileBundle {
     pub multi_index: OnDiskFile<Arc<gix_pack::multi_index::File>>,
     pub data: Vec<OnDiskFile<Arc<gix_pack::data::File>>>,
+    /// For each pack in `data`, `true` if it is accompanied by a `.promisor` file.
+    pub promisor: Vec<bool>,
 }
 
 #[derive(Clone)]
@@ -336,6 +340,7 @@ impl IndexAndPacks {
                 })?;
                 if let Some(multi_index) = bundle.multi_index.loaded() {
                     bundle.data = Self::index_names_to_pack_paths(multi_index);
+                    bundle.promisor = Self::promisor_markers(&bundle.data);
                 }
                 Ok(())
             }
@@ -344,6 +349,7 @@ impl IndexAndPacks {
 
     pub(crate) fn new_single(index_path: PathBuf, mtime: SystemTime) -> Self {
         let data_path = index_path.with_extension("pack");
+        let promisor = index_path.with_extension("promisor").is_file();
         Self::Index(IndexFileBundle {
             index: OnDiskFile {
                 path: index_path.into(),
@@ -355,11 +361,13 @@ impl IndexAndPacks {
                 state: OnDiskFileState::Unloaded,
                 mtime,
             },
+            promisor,
         })
     }
 
     pub(crate) fn new_multi_from_open_file(multi_index: Arc<gix_pack::multi_index::File>, mtime: SystemTime) -> Self {
         let data = Self::index_names_to_pack_paths(&multi_index);
+        let promisor = Self::promisor_markers(&data);
         Self::MultiIndex(MultiIndexFileBundle {
             multi_index: OnDiskFile {
                 path: Arc::new(multi_index.path().to_owned()),
@@ -367,9 +375,17 @@ impl IndexAndPacks {
                 mtime,
             },
             data,
+            promisor,
         })
     }
 
+    /// Return `true` for each pack in `data` that has a `.promisor` file next to it.
+    fn promisor_markers(data: &[OnDiskFile<Arc<gix_pack::data::File>>]) -> Vec<bool> {
+        data.iter()
+            .map(|pack| pack.path().with_extension("promisor").is_file())
+            .collect()
+    }
+
     fn index_names_to_pack_paths(
         multi_index: &gix_pack::multi_index::File,
     ) -> Vec<OnDiskFile<Arc<gix_pack::data::File>>> {
@@ -428,6 +444,13 @@ pub struct Metrics {
     ///
     /// There may be more than one if 'alternates' are used.
     pub loose_dbs: usize,
+    /// The amount of objects which were found to be corrupt in one or more packs.
+    ///
+    /// Their corrupt copies are skipped in favor of copies in other packs, loose objects or alternates.
+    /// Note that entries that fail to decode for reasons that don't clearly indicate corruption, like corrupt entry headers,
+    /// are only detected by their CRC32 if their pack has an index of version 2, as multi-pack indices and indices of
+    /// version 1 don't store it.
+    pub num_bad_packed_objects: usize,
 }
 
 #[cfg(test)]
@@ -115,13 +115,25 @@ impl Store {
     /// Return only the decompressed size of the object and its kind without fully reading it into memory as tuple of `(size, kind)`.
     /// Returns `None` if `id` does not exist in the database.
     pub fn try_header(&self, id: &gix_hash::oid) -> Result<Option<(u64, gix_object::Kind)>, Error> {
+        self.try_header_with_inflate(id, &mut gix_zlib::Inflate::default())
+    }
+
+    /// Like [`try_header()`](Self::try_header()), but uses `inflate` to decompress only the object header.
+    ///
+    /// `inflate` is reset before use, which allows to reuse it for looking up many headers without
+    /// allocating decompression state each time.
+    pub fn try_header_with_inflate(
+        &self,
+        id: &gix_hash::oid,
+        inflate: &mut gix_zlib::Inflate,
+    ) -> Result<Option<(u64, gix_object::Kind)>, Error> {
         let path = hash_path(id, self.path.clone());
         let map = match self.map_loose_object(&path)? {
             Some(map) => map,
             None => return Ok(None),
         };
         let mut header = [0_u8; HEADER_MAX_SIZE];
-        let mut inflate = gix_zlib::Inflate::default();
+        inflate.reset();
         let (status, _consumed_in, consumed_out) =
             inflate.once(&map, &mut header).map_err(|e| Error::DecompressFile {
                 source: e,
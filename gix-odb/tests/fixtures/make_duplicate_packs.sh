@@ -0,0 +1,15 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+echo "available in two packs" > file
+git add file
+git commit -qm "first"
+git repack -q -a -d
+
+echo "only in the second pack" > other
+git add other
+git commit -qm "second"
+# pack all objects once more, leaving the first pack in place so all of its objects are duplicated
+git rev-list --objects --all | git pack-objects -q .git/objects/pack/pack >/dev/null
+git prune-packed
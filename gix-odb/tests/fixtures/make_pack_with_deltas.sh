@@ -0,0 +1,13 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+seq 1000 > file
+git add file
+git commit -qm "first"
+
+seq 1001 > file
+git add file
+git commit -qm "second"
+# pack all objects so one version of the file is stored as delta of the other
+git repack -q -a -d -f
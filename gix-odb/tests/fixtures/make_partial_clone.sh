@@ -0,0 +1,12 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q base
+(cd base
+  git config uploadpack.allowFilter true
+  echo "first" > a
+  mkdir dir && echo "second" > dir/b
+  git add . && git commit -q -m "initial"
+)
+
+git clone -q --no-local --no-hardlinks --no-checkout --filter=blob:none ./base blobless
@@ -1,4 +1,7 @@
-use crate::{db, hex_to_id};
+use gix_object::FindExt;
+use gix_odb::Header;
+
+use crate::{db, db_small_packs, hex_to_id};
 
 fn find_header(db: impl gix_odb::Header, hex_id: &str) -> gix_odb::find::Header {
     db.try_header(&hex_to_id(hex_id))
@@ -39,3 +42,30 @@ fn pack_object() {
         })
     );
 }
+
+#[test]
+fn all_objects_match_their_fully_decoded_kind_and_size() -> crate::Result {
+    let mut num_deltified = 0;
+    for db in [db(), db_small_packs()] {
+        let mut buf = Vec::new();
+        for id in db.iter()? {
+            let id = id?;
+            let header = db.try_header(&id)?.expect("object exists");
+            let obj = db.find(&id, &mut buf)?;
+            assert_eq!(header.kind(), obj.kind, "{id}");
+            assert_eq!(
+                header.size(),
+                obj.data.len() as u64,
+                "{id}: the size is the one after applying all deltas"
+            );
+            if header.num_deltas().is_some_and(|n| n > 0) {
+                num_deltified += 1;
+            }
+        }
+    }
+    assert_ne!(
+        num_deltified, 0,
+        "there are deltified objects whose size must be resolved"
+    );
+    Ok(())
+}
@@ -0,0 +1,264 @@
+use std::{
+    io::{Seek, SeekFrom, Write as _},
+    path::Path,
+};
+
+use gix_hash::ObjectId;
+use gix_object::{Find, FindExt, Write};
+use gix_odb::Header;
+
+use crate::{fixture_options, odb_at, scripted_fixture_writable};
+
+const DUPLICATED_BLOB: &[u8] = b"available in two packs\n";
+
+fn duplicated_blob_id() -> crate::Result<ObjectId> {
+    Ok(gix_object::compute_hash(
+        fixture_options().object_hash,
+        gix_object::Kind::Blob,
+        DUPLICATED_BLOB,
+    )?)
+}
+
+/// The part of a pack entry to corrupt.
+#[derive(Copy, Clone)]
+enum Part {
+    /// The compressed data, which fails to decompress.
+    Data,
+    /// The header, which is changed to claim an object size so large that it can't be allocated.
+    Header,
+}
+
+/// Overwrite `part` of the entry of `id` in each pack whose position among all sorted pack indices is selected by `corrupt`,
+/// and return the amount of packs that contained the object.
+fn corrupt_object_in_packs(
+    objects_dir: &Path,
+    id: &ObjectId,
+    part: Part,
+    corrupt: impl Fn(usize) -> bool,
+) -> crate::Result<usize> {
+    let mut index_paths: Vec<_> = std::fs::read_dir(objects_dir.join("pack"))?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<Result<_, _>>()?;
+    index_paths.retain(|path| path.extension().is_some_and(|ext| ext == "idx"));
+    index_paths.sort();
+
+    let mut num_packs = 0;
+    for (pack_idx, index_path) in index_paths.iter().enumerate() {
+        let index = gix_pack::index::File::at(index_path, fixture_options().object_hash)?;
+        let Some(entry_index) = index.lookup(id) else {
+            continue;
+        };
+        num_packs += 1;
+        if !corrupt(pack_idx) {
+            continue;
+        }
+        let pack_offset = index.pack_offset_at_index(entry_index);
+        let pack_path = index_path.with_extension("pack");
+        match part {
+            Part::Data => {
+                let data_offset = {
+                    let pack = gix_pack::data::File::at(&pack_path, fixture_options().object_hash)?;
+                    pack.entry(pack_offset)?.data_offset
+                };
+                overwrite(&pack_path, data_offset, &[0xff; 4])?;
+            }
+            Part::Header => {
+                // A blob with a size of 2^63 bytes, with all continuation bytes but the last one being zero.
+                let mut header = [0x80; 10];
+                header[0] = 0b1011_0000;
+                header[9] = 0b0000_1000;
+                overwrite(&pack_path, pack_offset, &header)?;
+            }
+        }
+    }
+    Ok(num_packs)
+}
+
+fn overwrite(pack_path: &Path, offset: u64, bytes: &[u8]) -> std::io::Result<()> {
+    let mut permissions = std::fs::metadata(pack_path)?.permissions();
+    #[allow(clippy::permissions_set_readonly_false)]
+    permissions.set_readonly(false);
+    std::fs::set_permissions(pack_path, permissions)?;
+    let mut pack = std::fs::OpenOptions::new().write(true).open(pack_path)?;
+    pack.seek(SeekFrom::Start(offset))?;
+    pack.write_all(bytes)
+}
+
+#[test]
+fn corrupt_copies_are_skipped_in_favor_of_intact_ones_in_other_packs() -> crate::Result {
+    let id = duplicated_blob_id()?;
+    for corrupt_pack in 0..2 {
+        let dir = scripted_fixture_writable("make_duplicate_packs.sh")?;
+        let objects_dir = dir.path().join(".git/objects");
+        assert_eq!(
+            corrupt_object_in_packs(&objects_dir, &id, Part::Data, |pack_idx| pack_idx == corrupt_pack)?,
+            2,
+            "the blob is in both packs"
+        );
+
+        let odb = odb_at(&objects_dir)?;
+        let mut buf = Vec::new();
+        for _attempt in 0..2 {
+            let obj = odb.find(&id, &mut buf)?;
+            assert_eq!(obj.kind, gix_object::Kind::Blob);
+            assert_eq!(obj.data, DUPLICATED_BLOB, "the intact copy is used");
+            let header = odb.try_header(&id)?.expect("present");
+            assert_eq!(header.size(), DUPLICATED_BLOB.len() as u64);
+        }
+        assert!(
+            odb.store_ref().metrics().num_bad_packed_objects <= 1,
+            "the corrupt copy is only noticed if its pack is looked at first, and it's only marked once"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn loose_copies_are_used_if_all_packed_copies_are_corrupt() -> crate::Result {
+    let id = duplicated_blob_id()?;
+    let dir = scripted_fixture_writable("make_duplicate_packs.sh")?;
+    let objects_dir = dir.path().join(".git/objects");
+    assert_eq!(corrupt_object_in_packs(&objects_dir, &id, Part::Data, |_| true)?, 2);
+
+    let odb = odb_at(&objects_dir)?;
+    let mut buf = Vec::new();
+    assert!(odb.try_find(&id, &mut buf).is_err(), "there is no intact copy yet");
+    assert_eq!(
+        odb.store_ref().metrics().num_bad_packed_objects,
+        1,
+        "the object is marked as bad in both packs"
+    );
+
+    gix_odb::loose::Store::at(&objects_dir, fixture_options().object_hash, None)
+        .write_buf(gix_object::Kind::Blob, DUPLICATED_BLOB)?;
+    let obj = odb.find(&id, &mut buf)?;
+    assert_eq!(obj.data, DUPLICATED_BLOB, "the loose copy is used");
+    let header = odb.try_header(&id)?.expect("present");
+    assert_eq!(header.size(), DUPLICATED_BLOB.len() as u64);
+    Ok(())
+}
+
+#[test]
+fn objects_with_only_corrupt_copies_fail_to_be_found() -> crate::Result {
+    let id = duplicated_blob_id()?;
+    let dir = scripted_fixture_writable("make_duplicate_packs.sh")?;
+    let objects_dir = dir.path().join(".git/objects");
+    assert_eq!(corrupt_object_in_packs(&objects_dir, &id, Part::Data, |_| true)?, 2);
+
+    let odb = odb_at(&objects_dir)?;
+    let mut buf = Vec::new();
+    let err = odb.try_find(&id, &mut buf).expect_err("no intact copy");
+    assert!(
+        matches!(
+            err.downcast_ref::<gix_odb::store::find::Error>(),
+            Some(gix_odb::store::find::Error::Pack(
+                gix_pack::data::decode::Error::ZlibInflate(_)
+            ))
+        ),
+        "the decode error is reported at first: {err:?}"
+    );
+
+    let err = odb.try_find(&id, &mut buf).expect_err("still no intact copy");
+    assert!(
+        matches!(
+            err.downcast_ref::<gix_odb::store::find::Error>(),
+            Some(gix_odb::store::find::Error::CorruptPackedObject { id: actual }) if *actual == id
+        ),
+        "later the corrupt copies are skipped right away: {err:?}"
+    );
+    Ok(())
+}
+
+#[test]
+fn corrupt_entry_headers_are_detected_by_their_crc32() -> crate::Result {
+    let id = duplicated_blob_id()?;
+    for corrupt_pack in 0..2 {
+        let dir = scripted_fixture_writable("make_duplicate_packs.sh")?;
+        let objects_dir = dir.path().join(".git/objects");
+        assert_eq!(
+            corrupt_object_in_packs(&objects_dir, &id, Part::Header, |pack_idx| pack_idx == corrupt_pack)?,
+            2
+        );
+
+        let odb = odb_at(&objects_dir)?;
+        let mut buf = Vec::new();
+        let obj = odb.find(&id, &mut buf)?;
+        assert_eq!(
+            obj.data, DUPLICATED_BLOB,
+            "the object fails to be allocated, which is known to be corruption by its CRC32 mismatch"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn objects_with_corrupt_delta_bases_are_looked_up_elsewhere() -> crate::Result {
+    let dir = scripted_fixture_writable("make_pack_with_deltas.sh")?;
+    let objects_dir = dir.path().join(".git/objects");
+    let (delta_id, base_id) = corrupt_first_delta_base(&objects_dir)?;
+
+    let odb = odb_at(&objects_dir)?;
+    let mut buf = Vec::new();
+    let err = odb.try_find(&delta_id, &mut buf).expect_err("the base is corrupt");
+    assert!(
+        matches!(
+            err.downcast_ref::<gix_odb::store::find::Error>(),
+            Some(gix_odb::store::find::Error::Pack(
+                gix_pack::data::decode::Error::ZlibInflate(_)
+            ))
+        ),
+        "the base fails to decompress: {err:?}"
+    );
+    assert!(
+        odb.try_find(&base_id, &mut buf).is_err(),
+        "the base itself is corrupt as well"
+    );
+    assert_eq!(
+        odb.store_ref().metrics().num_bad_packed_objects,
+        2,
+        "both objects are marked as bad as neither can be decoded"
+    );
+
+    let loose = gix_odb::loose::Store::at(&objects_dir, fixture_options().object_hash, None);
+    for num_lines in [1000, 1001] {
+        let content = (1..=num_lines).map(|n| n.to_string()).collect::<Vec<_>>().join("\n") + "\n";
+        loose.write_buf(gix_object::Kind::Blob, content.as_bytes())?;
+    }
+    for id in [delta_id, base_id] {
+        let obj = odb.find(&id, &mut buf)?;
+        assert_eq!(obj.kind, gix_object::Kind::Blob, "the loose copy is used");
+        let header = odb.try_header(&id)?.expect("present");
+        assert_eq!(header.size(), obj.data.len() as u64);
+    }
+    Ok(())
+}
+
+/// Find the first object stored as delta in the only pack in `objects_dir`, corrupt the data of its base
+/// and return `(delta_id, base_id)`.
+fn corrupt_first_delta_base(objects_dir: &Path) -> crate::Result<(ObjectId, ObjectId)> {
+    let index_path = std::fs::read_dir(objects_dir.join("pack"))?
+        .map(|entry| entry.map(|e| e.path()))
+        .find(|path| {
+            path.as_ref()
+                .map_or(true, |path| path.extension().is_some_and(|ext| ext == "idx"))
+        })
+        .expect("one pack")?;
+    let index = gix_pack::index::File::at(&index_path, fixture_options().object_hash)?;
+    let pack_path = index_path.with_extension("pack");
+    let pack = gix_pack::data::File::at(&pack_path, fixture_options().object_hash)?;
+    for entry in index.iter() {
+        let pack_entry = pack.entry(entry.pack_offset)?;
+        let gix_pack::data::entry::Header::OfsDelta { base_distance } = pack_entry.header else {
+            continue;
+        };
+        let base_offset = pack_entry.base_pack_offset(base_distance);
+        let base_id = index
+            .iter()
+            .find(|e| e.pack_offset == base_offset)
+            .expect("base is in the same pack")
+            .oid;
+        overwrite(&pack_path, pack.entry(base_offset)?.data_offset, &[0xff; 4])?;
+        return Ok((entry.oid, base_id));
+    }
+    unreachable!("the fixture creates a pack with a delta")
+}
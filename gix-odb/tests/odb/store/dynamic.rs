@@ -67,6 +67,7 @@ fn db_with_all_object_sources() -> crate::Result<(gix_odb::Handle, gix_testtools
         &std::sync::atomic::AtomicBool::default(),
         gix_odb::pack::multi_index::write::Options {
             object_hash: gix_hash::Kind::Sha1,
+            write_reverse_index: false,
         },
     )?;
     Ok((gix_odb::at(objects_dir.path())?, objects_dir))
@@ -90,7 +91,8 @@ fn multi_index_access() -> crate::Result {
             unused_slots: 32,
             loose_dbs: 0,
             unreachable_indices: 0,
-            unreachable_packs: 0
+            unreachable_packs: 0,
+            num_bad_packed_objects: 0
         },
         "it starts out knowing nothing, it's completely lazy"
     );
@@ -122,7 +124,8 @@ fn multi_index_access() -> crate::Result {
             unused_slots: 31,
             loose_dbs: 1,
             unreachable_indices: 0,
-            unreachable_packs: 0
+            unreachable_packs: 0,
+            num_bad_packed_objects: 0
         },
         "it opened only a single multi-index and its pack - hard to see it's actually a multi-index as it's just one index anyway…"
     );
@@ -142,7 +145,8 @@ fn multi_index_access() -> crate::Result {
             unused_slots: 31,
             loose_dbs: 1,
             unreachable_indices: 0,
-            unreachable_packs: 0
+            unreachable_packs: 0,
+            num_bad_packed_objects: 0
         },
         "A miss means just another refresh with no other change"
     );
@@ -165,7 +169,8 @@ fn multi_index_access() -> crate::Result {
             unused_slots: 31,
             loose_dbs: 1,
             unreachable_indices: 0,
-            unreachable_packs: 0
+            unreachable_packs: 0,
+            num_bad_packed_objects: 0
         },
         "everything seems to remain as it was, even though we moved our multi-index to a new slot and removed the old one"
     );
@@ -235,7 +240,8 @@ fn multi_index_keep_open() -> crate::Result {
             unused_slots: 31,
             loose_dbs: 1,
             unreachable_indices: 0,
-            unreachable_packs: 0
+            unreachable_packs: 0,
+            num_bad_packed_objects: 0
         },
         "it opened the multi-pack index for iteration"
     );
@@ -264,7 +270,8 @@ fn multi_index_keep_open() -> crate::Result {
             unused_slots: 30,
             loose_dbs: 1,
             unreachable_indices: 1,
-            unreachable_packs: 1
+            unreachable_packs: 1,
+            num_bad_packed_objects: 0
         },
         "now there is an unreachable index and pack which is still loaded, but whose pack hasn't been loaded"
     );
@@ -418,7 +425,8 @@ fn contains() {
             unused_slots: 29,
             loose_dbs: 1,
             unreachable_indices: 0,
-            unreachable_packs: 0
+            unreachable_packs: 0,
+            num_bad_packed_objects: 0
         },
         "it only refreshed the file list, yielding the loose db to find this object, but no pack was opened yet"
     );
@@ -438,7 +446,8 @@ fn contains() {
             unused_slots: 29,
             loose_dbs: 1,
             unreachable_indices: 0,
-            unreachable_packs: 0
+            unreachable_packs: 0,
+            num_bad_packed_objects: 0
         },
         "it loaded the biggest back only, which is the first in the list"
     );
@@ -460,7 +469,8 @@ fn contains() {
             unused_slots: 29,
             loose_dbs: 1,
             unreachable_indices: 0,
-            unreachable_packs: 0
+            unreachable_packs: 0,
+            num_bad_packed_objects: 0
         },
         "when asking for an object in the smallest pack, all in between packs are also loaded."
     );
@@ -478,7 +488,8 @@ fn contains() {
             unused_slots: 29,
             loose_dbs: 1,
             unreachable_indices: 0,
-            unreachable_packs: 0
+            unreachable_packs: 0,
+            num_bad_packed_objects: 0
         },
         "trigger refreshes each time there is an object miss"
     );
@@ -497,7 +508,8 @@ fn contains() {
             unused_slots: 29,
             loose_dbs: 1,
             unreachable_indices: 0,
-            unreachable_packs: 0
+            unreachable_packs: 0,
+            num_bad_packed_objects: 0
         },
         "if no refreshes are allowed, there is no additional refresh"
     );
@@ -532,7 +544,8 @@ fn lookup() {
             unused_slots: 32,
             loose_dbs: 0,
             unreachable_indices: 0,
-            unreachable_packs: 0
+            unreachable_packs: 0,
+            num_bad_packed_objects: 0
         },
         "nothing happened yet, the store is totally lazy"
     );
@@ -553,6 +566,7 @@ fn lookup() {
         loose_dbs: 1,
         unreachable_indices: 0,
         unreachable_packs: 0,
+        num_bad_packed_objects: 0,
     };
     assert_eq!(
         handle.store_ref().metrics(),
@@ -599,7 +613,8 @@ fn assert_all_indices_loaded(handle: &gix_odb::Handle, num_refreshes: usize, ope
             unused_slots: 30,
             loose_dbs: 1,
             unreachable_indices: 0,
-            unreachable_packs: 0
+            unreachable_packs: 0,
+            num_bad_packed_objects: 0
         },
         "all indices must be loaded and searched to assure unambiguous object ids"
     );
@@ -832,7 +847,8 @@ fn missing_objects_triggers_everything_is_loaded() {
             unused_slots: 29,
             loose_dbs: 1,
             unreachable_indices: 0,
-            unreachable_packs: 0
+            unreachable_packs: 0,
+            num_bad_packed_objects: 0
         },
         "first refresh triggered by on-disk check, second refresh triggered to see if something changed, contains() only sees indices"
     );
@@ -852,7 +868,8 @@ fn missing_objects_triggers_everything_is_loaded() {
             unused_slots: 29,
             loose_dbs: 1,
             unreachable_indices: 0,
-            unreachable_packs: 0
+            unreachable_packs: 0,
+            num_bad_packed_objects: 0
         },
         "there are still no packs opened as no index contained the object"
     );
@@ -923,7 +940,8 @@ fn auto_refresh_with_and_without_id_stability() -> crate::Result {
             unused_slots: 31,
             loose_dbs: 1,
             unreachable_indices: 0,
-            unreachable_packs: 0
+            unreachable_packs: 0,
+            num_bad_packed_objects: 0
         },
         "one pack was opened"
     );
@@ -948,7 +966,8 @@ fn auto_refresh_with_and_without_id_stability() -> crate::Result {
             unused_slots: 31,
             loose_dbs: 1,
             unreachable_indices: 0,
-            unreachable_packs: 0
+            unreachable_packs: 0,
+            num_bad_packed_objects: 0
         },
         "the old pack was removed, the new was loaded"
     );
@@ -986,7 +1005,8 @@ fn auto_refresh_with_and_without_id_stability() -> crate::Result {
                 unused_slots: 30,
                 loose_dbs: 1,
                 unreachable_indices: 1,
-                unreachable_packs: 1
+                unreachable_packs: 1,
+                num_bad_packed_objects: 0
             },
             "the removed pack is still loaded"
         );
@@ -1021,7 +1041,8 @@ fn auto_refresh_with_and_without_id_stability() -> crate::Result {
             unused_slots: 30,
             loose_dbs: 1,
             unreachable_indices: 1,
-            unreachable_packs: 1
+            unreachable_packs: 1,
+            num_bad_packed_objects: 0
         },
         "garbaged slots aren't reclaimed until there is the need. Keeping indices open despite them not being accessible anymore."
     );
@@ -1076,7 +1097,8 @@ mod verify {
                 unused_slots: 29,
                 loose_dbs: 1,
                 unreachable_indices: 0,
-                unreachable_packs: 0
+                unreachable_packs: 0,
+                num_bad_packed_objects: 0
             },
             "verification only discovers files on disk but won't cause them to be opened permanently"
         );
@@ -451,12 +451,18 @@ cjHJZXWmV4CcRfmLsXzU8s2cR9A0DBvOxhPD1TlKC2JhBFXigjuL9U4Rbq9tdegB
         fn all() -> crate::Result {
             let db = ldb();
             let mut buf = Vec::new();
+            let mut inflate = gix_zlib::Inflate::default();
             for id in db.iter() {
                 let id = id?;
                 let expected = db.try_find(&id, &mut buf)?.expect("exists");
                 let (size, kind) = db.try_header(&id)?.expect("header exists");
                 assert_eq!(size, expected.data.len() as u64);
                 assert_eq!(kind, expected.kind);
+                assert_eq!(
+                    db.try_header_with_inflate(&id, &mut inflate)?,
+                    Some((size, kind)),
+                    "the same decompressor can be reused for all objects"
+                );
             }
             Ok(())
         }
@@ -1,4 +1,6 @@
 pub mod compound;
+pub mod corrupt;
 pub mod dynamic;
 pub mod linked;
 pub mod loose;
+pub mod promisor;
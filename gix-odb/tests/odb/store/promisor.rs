@@ -0,0 +1,145 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use gix_hash::ObjectId;
+use gix_object::{Exists, Find, FindExt, Write};
+use gix_odb::{Header, store::promisor};
+
+use crate::{fixture_options, odb_at, scripted_fixture_writable};
+
+/// The object ids of the promisor remote that the partial clone doesn't have, i.e. all blobs.
+fn missing_in(blobless: &gix_odb::Handle, base_objects: &Path) -> crate::Result<Vec<ObjectId>> {
+    let base = gix_odb::loose::Store::at(base_objects, fixture_options().object_hash, None);
+    let mut missing: Vec<_> = base
+        .iter()
+        .filter_map(Result::ok)
+        .filter(|id| !blobless.exists(id))
+        .collect();
+    missing.sort();
+    Ok(missing)
+}
+
+/// The ids passed to each call of the fetcher.
+type Calls = Arc<Mutex<Vec<Vec<ObjectId>>>>;
+
+/// A fetcher which copies the requested objects from `base_objects` into `objects_dir`, recording each call.
+fn fetcher(base_objects: PathBuf, objects_dir: PathBuf) -> (Arc<dyn promisor::Fetch>, Calls) {
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    let fetch = {
+        let calls = calls.clone();
+        move |ids: &[ObjectId]| -> Result<(), promisor::FetchError> {
+            calls.lock().expect("not poisoned").push(ids.to_vec());
+            let base = odb_at(&base_objects)?;
+            let out = gix_odb::loose::Store::at(&objects_dir, fixture_options().object_hash, None);
+            let mut buf = Vec::new();
+            for id in ids {
+                let obj = base.find(id, &mut buf)?;
+                out.write_buf(obj.kind, obj.data)?;
+            }
+            Ok(())
+        }
+    };
+    (Arc::new(fetch), calls)
+}
+
+#[test]
+fn objects_in_promisor_packs_are_detected() -> crate::Result {
+    let dir = scripted_fixture_writable("make_partial_clone.sh")?;
+    let blobless = odb_at(dir.path().join("blobless/.git/objects"))?;
+    let base_objects = dir.path().join("base/.git/objects");
+
+    let present: Vec<_> = blobless.iter()?.collect::<Result<_, _>>()?;
+    assert_eq!(present.len(), 3, "a commit and two trees");
+    for id in &present {
+        assert!(
+            blobless.is_in_promisor_pack(id),
+            "everything was received from the promisor remote"
+        );
+    }
+    for id in missing_in(&blobless, &base_objects)? {
+        assert!(!blobless.is_in_promisor_pack(&id), "missing objects aren't in any pack");
+    }
+
+    let base = odb_at(&base_objects)?;
+    assert!(
+        present.iter().all(|id| !base.is_in_promisor_pack(id)),
+        "loose objects are never promisor objects"
+    );
+    Ok(())
+}
+
+#[test]
+fn missing_objects_are_not_fetched_without_promisor() -> crate::Result {
+    let dir = scripted_fixture_writable("make_partial_clone.sh")?;
+    let blobless = odb_at(dir.path().join("blobless/.git/objects"))?;
+    let missing = missing_in(&blobless, &dir.path().join("base/.git/objects"))?;
+    assert_eq!(missing.len(), 2, "both blobs are missing");
+
+    let mut buf = Vec::new();
+    assert!(blobless.try_find(&missing[0], &mut buf)?.is_none());
+    assert!(blobless.try_header(&missing[0])?.is_none());
+    assert_eq!(blobless.fetch_missing(&missing)?, 0, "nothing to fetch from");
+    Ok(())
+}
+
+#[test]
+fn missing_objects_are_fetched_on_demand() -> crate::Result {
+    let dir = scripted_fixture_writable("make_partial_clone.sh")?;
+    let objects_dir = dir.path().join("blobless/.git/objects");
+    let base_objects = dir.path().join("base/.git/objects");
+    let mut blobless = odb_at(&objects_dir)?;
+    let missing = missing_in(&blobless, &base_objects)?;
+
+    let (fetch, calls) = fetcher(base_objects, objects_dir);
+    blobless.promisor = Some(fetch);
+
+    let mut buf = Vec::new();
+    let blob = blobless.find(&missing[0], &mut buf)?;
+    assert_eq!(blob.kind, gix_object::Kind::Blob);
+    assert_eq!(
+        calls.lock().expect("not poisoned").as_slice(),
+        &[vec![missing[0]]],
+        "the missing object was fetched"
+    );
+
+    let header = blobless.try_header(&missing[1])?.expect("fetched as well");
+    assert_eq!(header.kind(), gix_object::Kind::Blob);
+    assert_eq!(calls.lock().expect("not poisoned").len(), 2);
+
+    blobless.find(&missing[0], &mut buf)?;
+    assert_eq!(
+        calls.lock().expect("not poisoned").len(),
+        2,
+        "objects that are present aren't fetched again"
+    );
+    Ok(())
+}
+
+#[test]
+fn fetch_missing_requests_all_missing_objects_at_once() -> crate::Result {
+    let dir = scripted_fixture_writable("make_partial_clone.sh")?;
+    let objects_dir = dir.path().join("blobless/.git/objects");
+    let base_objects = dir.path().join("base/.git/objects");
+    let mut blobless = odb_at(&objects_dir)?;
+    let missing = missing_in(&blobless, &base_objects)?;
+    let present = blobless.iter()?.next().expect("at least one object")?;
+
+    let (fetch, calls) = fetcher(base_objects, objects_dir);
+    blobless.promisor = Some(fetch);
+
+    let ids = [present, missing[0], missing[1]];
+    assert_eq!(blobless.fetch_missing(ids)?, 2, "present objects aren't requested");
+    assert_eq!(
+        calls.lock().expect("not poisoned").as_slice(),
+        std::slice::from_ref(&missing)
+    );
+    for id in &missing {
+        assert!(blobless.exists(id), "the handle sees the fetched objects right away");
+    }
+
+    assert_eq!(blobless.fetch_missing(ids)?, 0, "nothing left to fetch");
+    assert_eq!(calls.lock().expect("not poisoned").len(), 1);
+    Ok(())
+}
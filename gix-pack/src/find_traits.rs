@@ -48,7 +48,7 @@ pub trait Find {
     /// _Note_ that this is always None if the object isn't packed even though it exists as loose object.
     fn location_by_oid(&self, id: &gix_hash::oid, buf: &mut Vec<u8>) -> Option<data::entry::Location>;
 
-    /// Obtain a vector of all offsets, in index order, along with their object id.
+    /// Obtain a vector of all offsets along with their object id, ideally sorted by offset with the help of a reverse index.
     fn pack_offsets_and_oid(&self, pack_id: u32) -> Option<Vec<(data::Offset, gix_hash::ObjectId)>>;
 
     /// Return the [`find::Entry`] for `location` if it is backed by a pack.
@@ -182,6 +182,76 @@ where
         ofs
     }
 
+    /// Return all entry indices ordered by the offset of their objects in the pack data file, which is the
+    /// order in which objects appear in the pack.
+    ///
+    /// They are read from the [reverse index](Self::reverse_index()) if there is one, or computed otherwise.
+    pub fn entry_indices_sorted_by_pack_offset(&self) -> Vec<EntryIndex> {
+        self.entry_indices_from_reverse_index()
+            .unwrap_or_else(|| self.compute_entry_indices_sorted_by_pack_offset())
+    }
+
+    /// Return the reverse index stored next to this index, i.e. `pack-<hash>.rev` for `pack-<hash>.idx`, if it exists
+    /// and belongs to the same pack.
+    ///
+    /// It's opened on first use and ignored if it belongs to another pack. Its content isn't validated to keep opening it
+    /// cheap, use [`verify_checksum()`](index::reverse::File::verify_checksum()) for that.
+    pub fn reverse_index(&self) -> Option<&index::reverse::File> {
+        self.reverse
+            .get_or_init(|| {
+                index::reverse::File::at(self.path.with_extension("rev"), self.object_hash)
+                    .ok()
+                    .filter(|rev| rev.is_for(self))
+            })
+            .as_ref()
+    }
+
+    /// Return the smallest pack offset of all entries that is larger than `pack_offset`, which is where the entry at
+    /// `pack_offset` ends, or `None` if there is no such entry as `pack_offset` belongs to the last entry of the pack.
+    ///
+    /// The [reverse index](Self::reverse_index()) is searched if there is one, otherwise all entries are looked at.
+    pub fn next_pack_offset(&self, pack_offset: data::Offset) -> Option<data::Offset> {
+        let Some(rev) = self.reverse_index() else {
+            return self
+                .iter()
+                .map(|entry| entry.pack_offset)
+                .filter(|offset| *offset > pack_offset)
+                .min();
+        };
+        let offset_at_pack_position = |pack_position| {
+            rev.index_entry_at_pack_position(pack_position)
+                .filter(|entry_index| *entry_index < self.num_objects)
+                .map(|entry_index| self.pack_offset_at_index(entry_index))
+        };
+        let (mut lower, mut upper) = (0, rev.num_objects());
+        while lower < upper {
+            let mid = lower + (upper - lower) / 2;
+            if offset_at_pack_position(mid)? <= pack_offset {
+                lower = mid + 1;
+            } else {
+                upper = mid;
+            }
+        }
+        offset_at_pack_position(lower)
+    }
+
+    /// Read all entry indices in pack order from our reverse index, or `None` if there is none or if it refers to
+    /// entries that don't exist.
+    pub(crate) fn entry_indices_from_reverse_index(&self) -> Option<Vec<EntryIndex>> {
+        self.reverse_index()?
+            .iter()
+            .map(|entry_index| (entry_index < self.num_objects).then_some(entry_index))
+            .collect()
+    }
+
+    pub(crate) fn compute_entry_indices_sorted_by_pack_offset(&self) -> Vec<EntryIndex> {
+        let mut entries: Vec<_> = (0..self.num_objects)
+            .map(|entry_index| (self.pack_offset_at_index(entry_index), entry_index))
+            .collect();
+        entries.sort_unstable();
+        entries.into_iter().map(|(_, entry_index)| entry_index).collect()
+    }
+
     #[inline]
     fn offset_crc32_v2(&self) -> usize {
         V2_HEADER_SIZE + self.num_objects as usize * self.hash_len
@@ -93,6 +93,7 @@ where
             fan,
             hash_len,
             object_hash,
+            reverse: Default::default(),
         })
     }
 }
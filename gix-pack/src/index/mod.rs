@@ -106,6 +106,8 @@ pub struct File<T = MMap> {
     fan: [u32; FAN_LEN],
     hash_len: usize,
     object_hash: gix_hash::Kind,
+    /// The reverse index next to this file, loaded on first use.
+    reverse: std::sync::OnceLock<Option<reverse::File>>,
 }
 
 /// Basic file information
@@ -138,6 +140,9 @@ pub mod init;
 pub(crate) mod access;
 pub use access::Entry;
 
+///
+pub mod reverse;
+
 pub(crate) mod encode;
 ///
 pub mod traverse;
@@ -0,0 +1,104 @@
+use std::path::{Path, PathBuf};
+
+use crate::index::reverse::{File, HEADER_LEN, N32_SIZE, SIGNATURE, Version};
+
+mod error {
+    /// The error returned by [File::at()][super::File::at()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not open pack reverse index file at '{path}'")]
+        Io {
+            source: std::io::Error,
+            path: std::path::PathBuf,
+        },
+        #[error("{message}")]
+        Corrupt { message: &'static str },
+        #[error("Unsupported reverse index version: {version})")]
+        UnsupportedVersion { version: u32 },
+        #[error("Unsupported hash kind: {kind})")]
+        UnsupportedObjectHash { kind: u32 },
+        #[error("The reverse index uses {actual} object ids, but {expected} was expected")]
+        ObjectHashMismatch {
+            expected: gix_hash::Kind,
+            actual: gix_hash::Kind,
+        },
+    }
+}
+
+pub use error::Error;
+
+/// Instantiation
+impl File<crate::MMap> {
+    /// Open the pack reverse index file at the given `path`, expecting it to use `object_hash` for the checksums it contains.
+    pub fn at(path: impl AsRef<Path>, object_hash: gix_hash::Kind) -> Result<Self, Error> {
+        Self::at_inner(path.as_ref(), object_hash)
+    }
+
+    fn at_inner(path: &Path, object_hash: gix_hash::Kind) -> Result<Self, Error> {
+        let data = crate::mmap::read_only(path).map_err(|source| Error::Io {
+            source,
+            path: path.to_owned(),
+        })?;
+        Self::from_data(data, path.to_owned(), object_hash)
+    }
+}
+
+impl<T> File<T>
+where
+    T: crate::FileData,
+{
+    /// Instantiate a reverse index file from `data` as assumed to be read or memory-mapped from `path`.
+    ///
+    /// Note that the amount of objects isn't stored in the file, but is derived from its size.
+    pub fn from_data(data: T, path: PathBuf, object_hash: gix_hash::Kind) -> Result<Self, Error> {
+        let hash_len = object_hash.len_in_bytes();
+        let footer_size = hash_len * 2;
+        if data.len() < HEADER_LEN + footer_size {
+            return Err(Error::Corrupt {
+                message: "Pack reverse index is truncated and too short",
+            });
+        }
+
+        let (signature, rest) = data.split_at(SIGNATURE.len());
+        if signature != SIGNATURE {
+            return Err(Error::Corrupt {
+                message: "Invalid signature",
+            });
+        }
+        let (version, rest) = rest.split_at(N32_SIZE);
+        match crate::read_u32(version) {
+            1 => Version::V1,
+            version => return Err(Error::UnsupportedVersion { version }),
+        };
+        let kind = crate::read_u32(&rest[..N32_SIZE]);
+        let actual = u8::try_from(kind)
+            .ok()
+            .and_then(|kind| gix_hash::Kind::try_from(kind).ok())
+            .ok_or(Error::UnsupportedObjectHash { kind })?;
+        if actual != object_hash {
+            return Err(Error::ObjectHashMismatch {
+                expected: object_hash,
+                actual,
+            });
+        }
+
+        let table_len = data.len() - HEADER_LEN - footer_size;
+        if table_len % N32_SIZE != 0 {
+            return Err(Error::Corrupt {
+                message: "Pack reverse index size doesn't match a whole number of entries",
+            });
+        }
+        let num_objects = u32::try_from(table_len / N32_SIZE).map_err(|_| Error::Corrupt {
+            message: "Pack reverse index has more entries than a pack can hold",
+        })?;
+
+        Ok(File {
+            data,
+            path,
+            num_objects,
+            hash_len,
+            object_hash,
+        })
+    }
+}
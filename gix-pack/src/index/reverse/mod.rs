@@ -0,0 +1,142 @@
+use std::path::PathBuf;
+
+use crate::{MMap, data, index::EntryIndex};
+
+/// The position of an object within its pack data file, with the first object at position 0.
+pub type PackPosition = u32;
+
+/// A representation of a pack reverse index file, typically named `pack-<hash>.rev`, which maps the position of
+/// an object in its pack data file to its entry in the pack index.
+///
+/// That way, entries can be enumerated in pack order and looked up by pack offset without sorting all offsets first.
+pub struct File<T = MMap> {
+    data: T,
+    path: PathBuf,
+    num_objects: u32,
+    hash_len: usize,
+    object_hash: gix_hash::Kind,
+}
+
+const SIGNATURE: &[u8] = b"RIDX";
+const HEADER_LEN: usize = 4 /*signature*/ + 4 /*version*/ + 4 /*object hash*/;
+const N32_SIZE: usize = std::mem::size_of::<u32>();
+
+/// The version of a reverse index file
+#[derive(Default, PartialEq, Eq, Ord, PartialOrd, Debug, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[allow(missing_docs)]
+pub enum Version {
+    #[default]
+    V1 = 1,
+}
+
+/// Basic file information
+impl<T> File<T>
+where
+    T: crate::FileData,
+{
+    /// The path of the opened reverse index file
+    pub fn path(&self) -> &std::path::Path {
+        &self.path
+    }
+    /// The amount of objects in the pack, as one past the highest pack position.
+    pub fn num_objects(&self) -> u32 {
+        self.num_objects
+    }
+    /// The kind of hash used by the pack and its index.
+    pub fn object_hash(&self) -> gix_hash::Kind {
+        self.object_hash
+    }
+    /// The checksum of the pack data file this reverse index belongs to.
+    pub fn pack_checksum(&self) -> gix_hash::ObjectId {
+        let from = self.data.len() - self.hash_len * 2;
+        gix_hash::ObjectId::from_bytes_or_panic(&self.data[from..][..self.hash_len])
+    }
+    /// The checksum over the entire content of the file (excluding the checksum itself).
+    pub fn checksum(&self) -> gix_hash::ObjectId {
+        gix_hash::ObjectId::from_bytes_or_panic(&self.data[self.data.len() - self.hash_len..])
+    }
+    /// Return `true` if this reverse index belongs to the pack of `index`, i.e. if both refer to the same pack and
+    /// have the same amount of objects.
+    pub fn is_for(&self, index: &crate::index::File<impl crate::FileData>) -> bool {
+        self.object_hash == index.object_hash()
+            && self.num_objects == index.num_objects()
+            && self.pack_checksum() == index.pack_checksum()
+    }
+}
+
+/// Verification
+impl<T> File<T>
+where
+    T: crate::FileData,
+{
+    /// Validate that our [`checksum()`](File::checksum()) matches the actual contents of this file.
+    pub fn verify_checksum(
+        &self,
+        progress: &mut dyn gix_features::progress::Progress,
+        should_interrupt: &std::sync::atomic::AtomicBool,
+    ) -> Result<gix_hash::ObjectId, crate::verify::checksum::Error> {
+        crate::verify::checksum_on_disk_or_mmap(
+            self.path(),
+            &self.data,
+            self.checksum(),
+            self.object_hash,
+            progress,
+            should_interrupt,
+        )
+    }
+}
+
+/// Access
+impl<T> File<T>
+where
+    T: crate::FileData,
+{
+    /// Return the entry index into the pack index of the object at `pack_position`, or `None` if the position is
+    /// out of bounds.
+    pub fn index_entry_at_pack_position(&self, pack_position: PackPosition) -> Option<EntryIndex> {
+        (pack_position < self.num_objects).then(|| {
+            let start = HEADER_LEN + pack_position as usize * N32_SIZE;
+            crate::read_u32(&self.data[start..][..N32_SIZE])
+        })
+    }
+
+    /// Return an iterator over all entry indices into the pack index, in the order in which their objects appear in the pack.
+    pub fn iter(&self) -> impl Iterator<Item = EntryIndex> + '_ {
+        self.data[HEADER_LEN..][..self.num_objects as usize * N32_SIZE]
+            .chunks_exact(N32_SIZE)
+            .map(crate::read_u32)
+    }
+
+    /// Find the entry index of the object starting at `pack_offset` in the pack of `index`, which must be
+    /// [the index this file belongs to](Self::is_for()), or `None` if there is no object at this offset.
+    ///
+    /// This is a binary search over pack positions, without the need to sort all offsets first.
+    pub fn lookup_pack_offset(
+        &self,
+        index: &crate::index::File<impl crate::FileData>,
+        pack_offset: data::Offset,
+    ) -> Option<EntryIndex> {
+        let (mut lower, mut upper) = (0, self.num_objects);
+        while lower < upper {
+            let mid = lower + (upper - lower) / 2;
+            let entry_index = self.index_entry_at_pack_position(mid)?;
+            if entry_index >= index.num_objects() {
+                return None;
+            }
+            match index.pack_offset_at_index(entry_index).cmp(&pack_offset) {
+                std::cmp::Ordering::Less => lower = mid + 1,
+                std::cmp::Ordering::Greater => upper = mid,
+                std::cmp::Ordering::Equal => return Some(entry_index),
+            }
+        }
+        None
+    }
+}
+
+///
+pub mod init;
+
+///
+pub mod write;
+pub use write::function::write_to;
@@ -0,0 +1,38 @@
+use crate::index::reverse::{File, SIGNATURE, Version};
+
+impl<T> File<T> {
+    fn write_header(out: &mut dyn std::io::Write, object_hash: gix_hash::Kind) -> std::io::Result<()> {
+        out.write_all(SIGNATURE)?;
+        out.write_all(&(Version::V1 as u32).to_be_bytes())?;
+        out.write_all(&(object_hash as u32).to_be_bytes())?;
+        Ok(())
+    }
+}
+
+pub(super) mod function {
+    use std::io::Write;
+
+    use crate::{MMap, index::reverse::File};
+
+    /// Write the reverse index of the pack belonging to `index` to `out`, and return its trailing checksum.
+    ///
+    /// The result is what `git` would write to the `pack-<hash>.rev` file next to the `pack-<hash>.idx` file of `index`.
+    pub fn write_to(
+        index: &crate::index::File<impl crate::FileData>,
+        out: &mut dyn std::io::Write,
+    ) -> Result<gix_hash::ObjectId, gix_hash::io::Error> {
+        let object_hash = index.object_hash();
+        let mut out = std::io::BufWriter::with_capacity(8 * 4096, gix_hash::io::Write::new(out, object_hash));
+        File::<MMap>::write_header(&mut out, object_hash)?;
+        for entry_index in index.compute_entry_indices_sorted_by_pack_offset() {
+            out.write_all(&entry_index.to_be_bytes())?;
+        }
+        out.write_all(index.pack_checksum().as_slice())?;
+
+        let out = out.into_inner().map_err(std::io::Error::from)?;
+        let checksum = out.hash.try_finalize()?;
+        out.inner.write_all(checksum.as_slice())?;
+        out.inner.flush()?;
+        Ok(checksum)
+    }
+}
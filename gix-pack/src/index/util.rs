@@ -12,11 +12,22 @@ pub(crate) fn index_entries_sorted_by_offset_ascending(
     let start = Instant::now();
 
     let mut v = exact_vec(idx.num_objects as usize);
-    for entry in idx.iter() {
-        v.push(entry);
-        progress.inc();
+    if let Some(entry_indices) = idx.entry_indices_from_reverse_index() {
+        for entry_index in entry_indices {
+            v.push(crate::index::Entry {
+                oid: idx.oid_at_index(entry_index).to_owned(),
+                pack_offset: idx.pack_offset_at_index(entry_index),
+                crc32: idx.crc32_at_index(entry_index),
+            });
+            progress.inc();
+        }
+    } else {
+        for entry in idx.iter() {
+            v.push(entry);
+            progress.inc();
+        }
+        v.sort_by_key(|e| e.pack_offset);
     }
-    v.sort_by_key(|e| e.pack_offset);
 
     progress.show_throughput(start);
     v
@@ -118,7 +118,7 @@ pub struct PackContext<'a, F, D = crate::MMap> {
 /// Verify and validate the content of the index file
 impl<T> index::File<T>
 where
-    T: crate::FileData + Sync,
+    T: crate::FileData,
 {
     /// Returns the trailing hash stored at the end of this index file.
     ///
@@ -134,7 +134,12 @@ where
         let from = self.data.len() - self.hash_len * 2;
         gix_hash::ObjectId::from_bytes_or_panic(&self.data[from..][..self.hash_len])
     }
+}
 
+impl<T> index::File<T>
+where
+    T: crate::FileData + Sync,
+{
     /// Validate that our [`index_checksum()`][index::File::index_checksum()] matches the actual contents
     /// of this index file, and return it if it does.
     pub fn verify_checksum(
@@ -146,4 +146,53 @@ where
             }
         })
     }
+
+    /// Return `true` if this file has a reverse index, which makes [`entry_index_at_pack_position()`](Self::entry_index_at_pack_position())
+    /// and [`lookup_pack_offset()`](Self::lookup_pack_offset()) available.
+    pub fn has_reverse_index(&self) -> bool {
+        self.reverse_index_ofs.is_some()
+    }
+
+    /// Return the entry index of the object at `pack_position` in pseudo-pack order, or `None` if there is no reverse index
+    /// or if `pack_position` is out of bounds.
+    ///
+    /// The pseudo-pack is the concatenation of all objects in the preferred pack, followed by those of all other packs in the order of
+    /// their pack index, with the objects of each pack ordered by their pack offset.
+    pub fn entry_index_at_pack_position(&self, pack_position: u32) -> Option<EntryIndex> {
+        let ofs = self.reverse_index_ofs?;
+        (pack_position < self.num_objects).then(|| crate::read_u32(&self.data[ofs + pack_position as usize * 4..][..4]))
+    }
+
+    /// Return the pack index of the preferred pack, whose objects come first in pseudo-pack order and which provides all of its objects,
+    /// or `None` if there is no reverse index.
+    pub fn preferred_pack(&self) -> Option<PackIndex> {
+        let entry_index = self.entry_index_at_pack_position(0)?;
+        (entry_index < self.num_objects).then(|| self.pack_id_and_pack_offset_at_index(entry_index).0)
+    }
+
+    /// Find the entry index of the object at `pack_offset` in the pack at `pack_index`, or `None` if there is no reverse index
+    /// or no such object.
+    ///
+    /// This is a binary search over the pseudo-pack order, without the need to sort all offsets first.
+    pub fn lookup_pack_offset(&self, pack_index: PackIndex, pack_offset: data::Offset) -> Option<EntryIndex> {
+        let preferred_pack = self.preferred_pack()?;
+        let pseudo_pack_key =
+            |pack_index: PackIndex, pack_offset: data::Offset| (pack_index != preferred_pack, pack_index, pack_offset);
+        let needle = pseudo_pack_key(pack_index, pack_offset);
+        let (mut lower, mut upper) = (0, self.num_objects);
+        while lower < upper {
+            let mid = lower + (upper - lower) / 2;
+            let entry_index = self.entry_index_at_pack_position(mid)?;
+            if entry_index >= self.num_objects {
+                return None;
+            }
+            let (mid_pack_index, mid_pack_offset) = self.pack_id_and_pack_offset_at_index(entry_index);
+            match pseudo_pack_key(mid_pack_index, mid_pack_offset).cmp(&needle) {
+                std::cmp::Ordering::Less => lower = mid + 1,
+                std::cmp::Ordering::Greater => upper = mid,
+                std::cmp::Ordering::Equal => return Some(entry_index),
+            }
+        }
+        None
+    }
 }
@@ -310,3 +310,37 @@ pub mod large_offsets {
         8 * large_offsets as u64
     }
 }
+
+/// Information about the reverse index, which lists entry indices in pseudo-pack order.
+///
+/// That order is the one of all objects in the preferred pack, followed by the objects of all other packs in the
+/// order of their pack index, with the objects of each pack sorted by their pack offset.
+pub mod reverse_index {
+    use std::ops::Range;
+
+    use crate::multi_index::EntryIndex;
+
+    /// The id uniquely identifying the reverse index table.
+    pub const ID: gix_chunk::Id = *b"RIDX";
+
+    /// Return the amount of bytes needed to store the reverse index for `entries` objects.
+    pub fn storage_size(entries: usize) -> u64 {
+        (entries * 4) as u64
+    }
+
+    pub(crate) fn write(
+        entry_indices_in_pack_order: &[EntryIndex],
+        out: &mut dyn std::io::Write,
+    ) -> std::io::Result<()> {
+        for entry_index in entry_indices_in_pack_order {
+            out.write_all(&entry_index.to_be_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Returns true if the `offset` range seems to match the size required for the untrusted `num_objects`.
+    pub fn is_valid(offset: &Range<usize>, num_objects: u32) -> bool {
+        let entry_size = 4 /* entry index */;
+        (offset.end - offset.start) == (num_objects as usize).saturating_mul(entry_size)
+    }
+}
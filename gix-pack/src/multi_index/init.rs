@@ -142,6 +142,17 @@ where
             })
             .ok()
             .transpose()?;
+        let reverse_index = chunks
+            .validated_usize_offset_by_id(chunk::reverse_index::ID, |offset| {
+                chunk::reverse_index::is_valid(&offset, num_objects)
+                    .then_some(offset)
+                    .ok_or(Error::InvalidChunkSize {
+                        id: chunk::reverse_index::ID,
+                        message: "The chunk with entries in pseudo-pack order doesn't have the correct size",
+                    })
+            })
+            .ok()
+            .transpose()?;
 
         let checksum_offset = chunks.highest_offset() as usize;
         let trailer = &data[checksum_offset..];
@@ -163,6 +174,7 @@ where
             lookup_ofs: lookup.start,
             offsets_ofs: offsets.start,
             large_offsets_ofs: large_offsets.map(|r| r.start),
+            reverse_index_ofs: reverse_index.map(|r| r.start),
             num_objects,
             num_indices,
         })
@@ -36,6 +36,7 @@ pub struct File<T = MMap> {
     lookup_ofs: usize,
     offsets_ofs: usize,
     large_offsets_ofs: Option<usize>,
+    reverse_index_ofs: Option<usize>,
 }
 
 ///
@@ -30,6 +30,11 @@ pub(crate) struct Entry {
 pub struct Options {
     /// The kind of hash to use for objects and to expect in the input files.
     pub object_hash: gix_hash::Kind,
+    /// If `true`, write a reverse index to map pack offsets to entries without sorting them first.
+    ///
+    /// Its objects are in pseudo-pack order, with the preferred pack first. The latter is the pack whose index was modified most recently
+    /// as it provides all of its objects, and the one with the lowest pack index among those with the same modification time.
+    pub write_reverse_index: bool,
 }
 
 /// The result of [`multi_index::write_from_index_paths()`].
@@ -89,7 +94,10 @@ pub(super) mod function {
         out: &mut dyn std::io::Write,
         progress: &mut dyn DynNestedProgress,
         should_interrupt: &AtomicBool,
-        Options { object_hash }: Options,
+        Options {
+            object_hash,
+            write_reverse_index,
+        }: Options,
     ) -> Result<Outcome, Error> {
         let out = gix_hash::io::Write::new(out, object_hash);
         let (index_paths_sorted, index_filenames_sorted) = {
@@ -101,6 +109,7 @@ pub(super) mod function {
             (index_paths, file_names)
         };
 
+        let mut index_mtimes = Vec::with_capacity(index_paths_sorted.len());
         let entries = {
             let mut entries = Vec::new();
             let start = Instant::now();
@@ -117,6 +126,9 @@ pub(super) mod function {
                     .and_then(|m| m.modified())
                     .unwrap_or(SystemTime::UNIX_EPOCH);
                 let index = crate::index::File::at(index, object_hash)?;
+                if index.num_objects() != 0 {
+                    index_mtimes.push((index_id as u32, mtime));
+                }
 
                 entries.reserve(index.num_objects() as usize);
                 entries.extend(index.iter().map(|e| Entry {
@@ -149,6 +161,21 @@ pub(super) mod function {
             entries
         };
 
+        let entry_indices_in_pack_order = write_reverse_index.then(|| {
+            let preferred_pack = index_mtimes
+                .iter()
+                .min_by(|(l_index, l_mtime), (r_index, r_mtime)| {
+                    l_mtime.cmp(r_mtime).reverse().then(l_index.cmp(r_index))
+                })
+                .map(|(pack_index, _)| *pack_index);
+            let mut entry_indices: Vec<_> = (0..entries.len() as u32).collect();
+            entry_indices.sort_by_key(|entry_index| {
+                let e = &entries[*entry_index as usize];
+                (Some(e.pack_index) != preferred_pack, e.pack_index, e.pack_offset)
+            });
+            entry_indices
+        });
+
         let mut cf = gix_chunk::file::Index::for_writing();
         cf.plan_chunk(
             multi_index::chunk::index_names::ID,
@@ -172,6 +199,13 @@ pub(super) mod function {
             );
         }
 
+        if entry_indices_in_pack_order.is_some() {
+            cf.plan_chunk(
+                multi_index::chunk::reverse_index::ID,
+                multi_index::chunk::reverse_index::storage_size(entries.len()),
+            );
+        }
+
         let mut write_progress =
             progress.add_child_with_id("Writing multi-index".into(), ProgressId::BytesWritten.into());
         let write_start = Instant::now();
@@ -214,6 +248,10 @@ pub(super) mod function {
                         num_large_offsets.expect("available if planned"),
                         &mut chunk_write,
                     ),
+                    multi_index::chunk::reverse_index::ID => multi_index::chunk::reverse_index::write(
+                        entry_indices_in_pack_order.as_deref().expect("available if planned"),
+                        &mut chunk_write,
+                    ),
                     unknown => unreachable!("BUG: forgot to implement chunk {:?}", std::str::from_utf8(&unknown)),
                 }
                 .map_err(gix_hash::io::Error::from)?;
@@ -0,0 +1,18 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+# each round adds a pack along with its reverse index
+for round in $(seq 4); do
+  for file_id in $(seq 10); do
+    seq "$round" $(( round * file_id * 20 )) > "$file_id"
+  done
+  git add .
+  git commit -qm "$round"
+  git -c pack.writeReverseIndex=true repack -q -d
+done
+
+# a bitmap needs a reverse index, which is stored as RIDX chunk
+git multi-pack-index write --bitmap
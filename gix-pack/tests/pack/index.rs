@@ -16,6 +16,7 @@ fn memory_backed_index(at: &str) -> gix_pack::index::File<&'static [u8]> {
 }
 
 mod fuzzed;
+mod reverse;
 
 mod version {
     mod v1 {
@@ -0,0 +1,191 @@
+use std::{path::PathBuf, sync::atomic::AtomicBool};
+
+use gix_features::progress::Discard;
+
+use gix_pack::index::{self, reverse};
+
+use crate::object_hash;
+
+fn indices_with_reverse_index() -> crate::Result<Vec<index::File>> {
+    let pack_dir = crate::scripted_fixture_read_only("make_pack_gen_repo_reverse_index.sh")?.join(".git/objects/pack");
+    let mut indices = Vec::new();
+    for entry in std::fs::read_dir(pack_dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "idx") {
+            indices.push(index::File::at(path, object_hash())?);
+        }
+    }
+    assert_eq!(indices.len(), 4, "one pack per round");
+    Ok(indices)
+}
+
+#[test]
+fn write_to_produces_the_same_file_as_git() -> crate::Result {
+    for idx in indices_with_reverse_index()? {
+        let rev = idx.reverse_index().expect("git wrote a reverse index for each pack");
+        assert_eq!(rev.num_objects(), idx.num_objects());
+        assert_eq!(rev.object_hash(), idx.object_hash());
+        assert_eq!(rev.pack_checksum(), idx.pack_checksum());
+
+        let mut out = Vec::new();
+        let checksum = reverse::write_to(&idx, &mut out)?;
+        assert_eq!(checksum, rev.checksum());
+        assert_eq!(out, std::fs::read(rev.path())?, "the output is byte-for-byte the same");
+    }
+    Ok(())
+}
+
+#[test]
+fn entries_are_in_pack_order_and_can_be_looked_up_by_offset() -> crate::Result {
+    for idx in indices_with_reverse_index()? {
+        let rev = idx.reverse_index().expect("present");
+        let mut entries: Vec<_> = idx.iter().collect();
+        entries.sort_by_key(|e| e.pack_offset);
+
+        let entry_indices: Vec<_> = rev.iter().collect();
+        assert_eq!(entry_indices, idx.entry_indices_sorted_by_pack_offset());
+        for (pack_position, (entry_index, entry)) in entry_indices.iter().zip(&entries).enumerate() {
+            assert_eq!(
+                rev.index_entry_at_pack_position(pack_position as u32),
+                Some(*entry_index)
+            );
+            assert_eq!(idx.oid_at_index(*entry_index), entry.oid);
+            assert_eq!(rev.lookup_pack_offset(&idx, entry.pack_offset), Some(*entry_index));
+            assert_eq!(
+                rev.lookup_pack_offset(&idx, entry.pack_offset + 1),
+                None,
+                "there is no object in the middle of another one"
+            );
+            assert_eq!(
+                idx.next_pack_offset(entry.pack_offset),
+                entries.get(pack_position + 1).map(|next| next.pack_offset),
+                "entries end where the next one begins, and the last one has no successor"
+            );
+        }
+        assert_eq!(rev.index_entry_at_pack_position(rev.num_objects()), None);
+    }
+    Ok(())
+}
+
+#[test]
+fn computed_pack_order_matches_the_one_read_from_disk() -> crate::Result {
+    for idx in indices_with_reverse_index()? {
+        let (data, _path) = crate::leaked_fixture_bytes(idx.path().to_owned());
+        let idx_without_reverse_index =
+            index::File::from_data(data, PathBuf::from("without-reverse-index.idx"), object_hash())?;
+        assert!(idx_without_reverse_index.reverse_index().is_none());
+        assert_eq!(
+            idx_without_reverse_index.entry_indices_sorted_by_pack_offset(),
+            idx.entry_indices_sorted_by_pack_offset()
+        );
+        for entry in idx.iter() {
+            assert_eq!(
+                idx_without_reverse_index.next_pack_offset(entry.pack_offset),
+                idx.next_pack_offset(entry.pack_offset)
+            );
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn reverse_indices_are_read_once_and_only_verified_on_demand() -> crate::Result {
+    let idx = indices_with_reverse_index()?.pop().expect("at least one");
+    let expected_order = idx.entry_indices_sorted_by_pack_offset();
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let index_path = tmp.path().join(idx.path().file_name().expect("file name"));
+    let rev_path = index_path.with_extension("rev");
+    std::fs::copy(idx.path(), &index_path)?;
+    std::fs::copy(idx.path().with_extension("rev"), &rev_path)?;
+
+    let idx = index::File::at(&index_path, object_hash())?;
+    let rev = idx.reverse_index().expect("the intact reverse index is used");
+    assert!(rev.verify_checksum(&mut Discard, &AtomicBool::default()).is_ok());
+    std::fs::remove_file(&rev_path)?;
+    assert!(
+        idx.reverse_index().is_some(),
+        "it's kept once loaded, even if the file doesn't exist anymore"
+    );
+
+    let mut data = Vec::new();
+    reverse::write_to(&idx, &mut data)?;
+    assert!(data.len() > 12 + 2 * 4, "the reverse index has at least two entries");
+    // Swap the first two entries, which still refer to valid entries, but in the wrong order.
+    let mut swapped = data.clone();
+    let (first, second) = swapped[12..20].split_at_mut(4);
+    first.swap_with_slice(second);
+    std::fs::write(&rev_path, &swapped)?;
+
+    let idx = index::File::at(&index_path, object_hash())?;
+    let rev = idx
+        .reverse_index()
+        .expect("the reverse index is used as its checksum isn't verified when opening it");
+    assert!(
+        rev.verify_checksum(&mut Discard, &AtomicBool::default()).is_err(),
+        "the corruption is detected by verifying the checksum explicitly"
+    );
+
+    // Refer to an entry that doesn't exist.
+    let mut out_of_bounds = data;
+    out_of_bounds[12..16].copy_from_slice(&idx.num_objects().to_be_bytes());
+    std::fs::write(&rev_path, &out_of_bounds)?;
+
+    let idx = index::File::at(&index_path, object_hash())?;
+    assert!(idx.reverse_index().is_some());
+    assert_eq!(
+        idx.entry_indices_sorted_by_pack_offset(),
+        expected_order,
+        "the order is computed instead of using entries that don't exist"
+    );
+    // Finding where entries end doesn't fail on entries that don't exist either.
+    for entry in idx.iter() {
+        idx.next_pack_offset(entry.pack_offset);
+    }
+    Ok(())
+}
+
+#[test]
+fn from_data_rejects_invalid_files() -> crate::Result {
+    let idx = indices_with_reverse_index()?.pop().expect("at least one");
+    let mut valid = Vec::new();
+    reverse::write_to(&idx, &mut valid)?;
+    let path = PathBuf::from("pack.rev");
+    let hash_len = object_hash().len_in_bytes();
+
+    let rev = reverse::File::from_data(valid.as_slice(), path.clone(), object_hash())?;
+    assert!(rev.is_for(&idx));
+
+    let mut wrong_signature = valid.clone();
+    wrong_signature[0] = b'X';
+    assert!(matches!(
+        reverse::File::from_data(wrong_signature, path.clone(), object_hash()),
+        Err(reverse::init::Error::Corrupt { .. })
+    ));
+
+    let mut wrong_version = valid.clone();
+    wrong_version[7] = 2;
+    assert!(matches!(
+        reverse::File::from_data(wrong_version, path.clone(), object_hash()),
+        Err(reverse::init::Error::UnsupportedVersion { version: 2 })
+    ));
+
+    let mut unknown_hash = valid.clone();
+    unknown_hash[11] = 42;
+    assert!(matches!(
+        reverse::File::from_data(unknown_hash, path.clone(), object_hash()),
+        Err(reverse::init::Error::UnsupportedObjectHash { kind: 42 })
+    ));
+
+    let mut partial_entry = valid.clone();
+    partial_entry.insert(12, 0);
+    assert!(matches!(
+        reverse::File::from_data(partial_entry, path.clone(), object_hash()),
+        Err(reverse::init::Error::Corrupt { .. })
+    ));
+
+    assert!(matches!(
+        reverse::File::from_data(&valid[..12 + hash_len], path, object_hash()),
+        Err(reverse::init::Error::Corrupt { .. })
+    ));
+    Ok(())
+}
@@ -200,3 +200,24 @@ fn from_memory() {
         Some(0)
     );
 }
+
+#[test]
+fn reverse_index_written_by_git() -> crate::Result {
+    let path = crate::scripted_fixture_read_only("make_pack_gen_repo_reverse_index.sh")?
+        .join(".git/objects/pack/multi-pack-index");
+    let file = gix_pack::multi_index::File::at(path, None)?;
+    assert!(file.has_reverse_index());
+    assert_eq!(file.num_indices(), 4);
+    super::assert_reverse_index_is_consistent(&file);
+    Ok(())
+}
+
+#[test]
+fn without_reverse_index_there_is_no_pack_order() {
+    let (file, _path) = multi_index(object_hash());
+    assert!(!file.has_reverse_index());
+    assert_eq!(file.entry_index_at_pack_position(0), None);
+    assert_eq!(file.preferred_pack(), None);
+    let entry = file.iter().next().expect("at least one entry");
+    assert_eq!(file.lookup_pack_offset(entry.pack_index, entry.pack_offset), None);
+}
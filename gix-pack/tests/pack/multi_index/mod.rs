@@ -11,6 +11,23 @@ fn multi_index(object_hash: gix_hash::Kind) -> (File, PathBuf) {
     (file, path)
 }
 
+/// Assert that the reverse index of `file` lists all entries in pseudo-pack order and can be used to find each of them.
+fn assert_reverse_index_is_consistent(file: &File) {
+    let preferred_pack = file.preferred_pack().expect("reverse index is present");
+    let mut previous_key = None;
+    for pack_position in 0..file.num_objects() {
+        let entry_index = file.entry_index_at_pack_position(pack_position).expect("in bounds");
+        let (pack_index, pack_offset) = file.pack_id_and_pack_offset_at_index(entry_index);
+        let key = (pack_index != preferred_pack, pack_index, pack_offset);
+        assert!(previous_key < Some(key), "objects are in pseudo-pack order");
+        previous_key = Some(key);
+
+        assert_eq!(file.lookup_pack_offset(pack_index, pack_offset), Some(entry_index));
+        assert_eq!(file.lookup_pack_offset(pack_index, pack_offset + 1), None);
+    }
+    assert_eq!(file.entry_index_at_pack_position(file.num_objects()), None);
+}
+
 mod access;
 mod fuzzed;
 
@@ -13,7 +13,7 @@ use crate::hex_to_id;
 #[test]
 fn from_paths() -> crate::Result {
     let pack_dir = fixture_path("objects/pack");
-    let written = write_multi_index_from_pack_dir(&pack_dir, gix_hash::Kind::Sha1)?;
+    let written = write_multi_index_from_pack_dir(&pack_dir, gix_hash::Kind::Sha1, false)?;
     assert_eq!(written.input_indices.len(), 3);
 
     assert_eq!(
@@ -43,7 +43,7 @@ fn from_paths() -> crate::Result {
 fn from_a_hash_parameterized_pack() -> crate::Result {
     let object_hash = crate::object_hash();
     let pack_dir = crate::scripted_fixture_read_only("make_pack_gen_repo_multi_index.sh")?.join(".git/objects/pack");
-    let written = write_multi_index_from_pack_dir(&pack_dir, object_hash)?;
+    let written = write_multi_index_from_pack_dir(&pack_dir, object_hash, false)?;
     assert_eq!(written.input_indices.len(), 1, "the aggressive gc leaves a single pack");
 
     assert_eq!(
@@ -59,6 +59,34 @@ fn from_a_hash_parameterized_pack() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn with_reverse_index() -> crate::Result {
+    let pack_dir = fixture_path("objects/pack");
+    let written = write_multi_index_from_pack_dir(&pack_dir, gix_hash::Kind::Sha1, true)?;
+    assert!(written.file.has_reverse_index());
+    assert_eq!(
+        written.file.num_objects(),
+        139,
+        "the reverse index doesn't affect the objects"
+    );
+    super::assert_reverse_index_is_consistent(&written.file);
+
+    let preferred_pack = written.file.preferred_pack().expect("present");
+    let num_objects_in_preferred_pack = gix_pack::index::File::at(
+        pack_dir.join(&written.file.index_names()[preferred_pack as usize]),
+        gix_hash::Kind::Sha1,
+    )?
+    .num_objects();
+    assert_eq!(
+        written.file.iter().filter(|e| e.pack_index == preferred_pack).count(),
+        num_objects_in_preferred_pack as usize,
+        "all objects of the preferred pack are selected from it"
+    );
+
+    written.verify_integrity_with_referenced_packs()?;
+    Ok(())
+}
+
 struct WrittenMultiIndex {
     file: gix_pack::multi_index::File,
     dir: gix_testtools::tempfile::TempDir,
@@ -102,7 +130,11 @@ impl WrittenMultiIndex {
     }
 }
 
-fn write_multi_index_from_pack_dir(pack_dir: &Path, object_hash: gix_hash::Kind) -> crate::Result<WrittenMultiIndex> {
+fn write_multi_index_from_pack_dir(
+    pack_dir: &Path,
+    object_hash: gix_hash::Kind,
+    write_reverse_index: bool,
+) -> crate::Result<WrittenMultiIndex> {
     let input_indices = std::fs::read_dir(pack_dir)?
         .filter_map(|r| {
             let idx_path = r.ok()?.path();
@@ -121,7 +153,10 @@ fn write_multi_index_from_pack_dir(pack_dir: &Path, object_hash: gix_hash::Kind)
         &mut out,
         &mut progress::Discard,
         &AtomicBool::new(false),
-        gix_pack::multi_index::write::Options { object_hash },
+        gix_pack::multi_index::write::Options {
+            object_hash,
+            write_reverse_index,
+        },
     )?;
     let file = gix_pack::multi_index::File::at(output_path, None)?;
 
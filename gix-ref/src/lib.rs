@@ -14,6 +14,8 @@
 //!     * one reference maps to a file on disk
 //!   * **packed**
 //!     * references are stored in a single human-readable file, along with their targets if they are symbolic.
+//! * **[reftable][reftable::Store]**
+//!   * references and their logs are stored in a stack of binary tables, with each transaction adding a new table.
 //!
 //! ## Feature Flags
 #![cfg_attr(
@@ -29,7 +31,7 @@ use gix_object::bstr::{BStr, BString};
 
 #[path = "store/mod.rs"]
 mod store_impl;
-pub use store_impl::{file, packed};
+pub use store_impl::{file, packed, reftable};
 
 mod fullname;
 ///
@@ -71,6 +73,8 @@ pub mod store {
             /// if they need to remain compatible with Windows.
             pub prohibit_windows_device_names: bool,
         }
+
+        pub use super::general::init::Error;
     }
     /// The way a file store handles the reflog
     #[derive(Default, Debug, PartialOrd, PartialEq, Ord, Eq, Hash, Clone, Copy)]
@@ -84,18 +88,40 @@ pub mod store {
         Disable,
     }
 
-    /// A thread-local handle for interacting with a [`Store`][crate::Store] to find and iterate references.
+    /// The backend used to store references, as configured by `extensions.refStorage`.
+    #[derive(Default, Debug, PartialOrd, PartialEq, Ord, Eq, Hash, Clone, Copy)]
+    pub enum Backend {
+        /// Loose references in files, along with a `packed-refs` file. This is the default.
+        #[default]
+        Files,
+        /// A stack of reftables in the `reftable` directory.
+        Reftable,
+    }
+
+    impl Backend {
+        /// Parse the value of `extensions.refStorage`, returning `None` if it's unknown.
+        pub fn from_ref_storage(value: &gix_object::bstr::BStr) -> Option<Self> {
+            let value: &[u8] = value.as_ref();
+            Some(match value {
+                b"files" => Backend::Files,
+                b"reftable" => Backend::Reftable,
+                _ => return None,
+            })
+        }
+    }
+
+    /// A handle for interacting with a [`Store`][crate::Store] to find and iterate references, with an optional namespace.
     #[derive(Clone)]
-    #[allow(dead_code)]
-    pub(crate) struct Handle {
+    pub struct Handle {
         /// A way to access shared state with the requirement that interior mutability doesn't leak or is incorporated into error types
         /// if it could. The latter can't happen if references to said internal aren't ever returned.
         state: handle::State,
     }
 
-    #[allow(dead_code)]
+    #[derive(Debug, Clone)]
     pub(crate) enum State {
         Loose { store: file::Store },
+        Reftable { store: reftable::Store },
     }
 
     pub(crate) mod general;
@@ -103,15 +129,16 @@ pub mod store {
     ///
     #[path = "general/handle/mod.rs"]
     mod handle;
-    pub use handle::find;
+    pub use handle::{find, iter, reflog, transaction};
+
+    pub use general::migrate;
 
-    use crate::file;
+    use crate::{file, reftable};
 }
 
-/// The git reference store.
-/// TODO: Figure out if handles are needed at all, which depends on the ref-table implementation.
-#[allow(dead_code)]
-pub(crate) struct Store {
+/// The git reference store, which dispatches all operations to the [backend](store::Backend) the repository is configured with.
+#[derive(Debug, Clone)]
+pub struct Store {
     inner: store::State,
 }
 
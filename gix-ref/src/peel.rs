@@ -27,9 +27,46 @@ pub mod to_object {
     pub enum Error {
         #[error("Could not follow a single level of a symbolic reference")]
         Follow(#[from] file::find::existing::Error),
+        #[error("Could not follow a single level of a symbolic reference in a reftable")]
+        FollowReftable(#[from] crate::reftable::find::existing::Error),
         #[error("Aborting due to reference cycle with first seen path being {start_absolute:?}")]
         Cycle { start_absolute: PathBuf },
         #[error("Refusing to follow more than {max_depth} levels of indirection")]
         DepthLimitExceeded { max_depth: usize },
     }
 }
+
+/// Peel `oid`, the object `reference` points to, until the first non-tag object, and make `reference` point to it.
+pub(crate) fn tags_to_id(
+    reference: &mut crate::Reference,
+    mut oid: gix_hash::ObjectId,
+    objects: &dyn gix_object::Find,
+) -> Result<gix_hash::ObjectId, to_id::Error> {
+    let mut buf = Vec::new();
+    let peeled_id = loop {
+        let gix_object::Data {
+            kind,
+            data,
+            object_hash: hash_kind,
+        } = objects
+            .try_find(&oid, &mut buf)?
+            .ok_or_else(|| to_id::Error::NotFound {
+                oid,
+                name: reference.name.0.clone(),
+            })?;
+        match kind {
+            gix_object::Kind::Tag => {
+                oid = gix_object::TagRefIter::from_bytes(data, hash_kind)
+                    .target_id()
+                    .map_err(|_err| to_id::Error::NotFound {
+                        oid,
+                        name: reference.name.0.clone(),
+                    })?;
+            }
+            _ => break oid,
+        }
+    };
+    reference.peeled = Some(peeled_id);
+    reference.target = crate::Target::Object(peeled_id);
+    Ok(peeled_id)
+}
@@ -76,8 +76,8 @@ impl file::Store {
 }
 
 impl file::Store {
-    /// Implements the logic required to transform a fully qualified refname into its log name
-    pub(crate) fn reflog_path(&self, name: &FullNameRef) -> PathBuf {
+    /// Return the path at which the reflog of the fully qualified `name` is stored, whether it exists or not.
+    pub fn reflog_path(&self, name: &FullNameRef) -> PathBuf {
         let (base, rela_path) = self.reflog_base_and_relative_path(name);
         base.join(rela_path)
     }
@@ -16,7 +16,7 @@ pub struct Store {
     common_dir: Option<PathBuf>,
     /// The kind of hash to assume in a couple of situations. Note that currently we are able to read any valid hash from files
     /// which might want to change one day.
-    object_hash: gix_hash::Kind,
+    pub(crate) object_hash: gix_hash::Kind,
     /// The amount of bytes needed for `mmap` to be used to open packed refs.
     packed_buffer_mmap_threshold: u64,
 
@@ -116,6 +116,7 @@ pub struct Transaction<'s, 'p> {
     packed_transaction: Option<crate::store_impl::packed::Transaction>,
     updates: Option<Vec<transaction::Edit>>,
     packed_refs: transaction::PackedRefs<'p>,
+    hook: Option<crate::transaction::hook::Hook<'p>>,
 }
 
 ///
@@ -156,34 +156,8 @@ impl ReferenceExt for Reference {
                 Ok(peeled)
             }
             None => {
-                let mut oid = self.follow_to_object_packed(store, packed)?;
-                let mut buf = Vec::new();
-                let peeled_id = loop {
-                    let gix_object::Data {
-                        kind,
-                        data,
-                        object_hash: hash_kind,
-                    } = objects
-                        .try_find(&oid, &mut buf)?
-                        .ok_or_else(|| peel::to_id::Error::NotFound {
-                            oid,
-                            name: self.name.0.clone(),
-                        })?;
-                    match kind {
-                        gix_object::Kind::Tag => {
-                            oid = gix_object::TagRefIter::from_bytes(data, hash_kind)
-                                .target_id()
-                                .map_err(|_err| peel::to_id::Error::NotFound {
-                                    oid,
-                                    name: self.name.0.clone(),
-                                })?;
-                        }
-                        _ => break oid,
-                    }
-                };
-                self.peeled = Some(peeled_id);
-                self.target = Target::Object(peeled_id);
-                Ok(peeled_id)
+                let oid = self.follow_to_object_packed(store, packed)?;
+                peel::tags_to_id(self, oid, objects)
             }
         }
     }
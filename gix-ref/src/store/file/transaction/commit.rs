@@ -1,6 +1,7 @@
 use crate::{
     Target,
     store_impl::file::{Transaction, transaction::PackedRefs},
+    transaction::hook,
     transaction::{Change, LogChange, RefEdit, RefLog},
 };
 
@@ -25,12 +26,36 @@ impl Transaction<'_, '_> {
     ///   along with empty parent directories
     ///
     /// Note that transactions will be prepared automatically as needed.
-    pub fn commit<'a>(self, committer: impl Into<Option<gix_actor::SignatureRef<'a>>>) -> Result<Vec<RefEdit>, Error> {
-        self.commit_inner(committer.into())
+    /// Once all edits are written, the [hook](Transaction::hook()) is called if set, or it's informed that the transaction
+    /// was aborted if the commit failed.
+    pub fn commit<'a>(
+        mut self,
+        committer: impl Into<Option<gix_actor::SignatureRef<'a>>>,
+    ) -> Result<Vec<RefEdit>, Error> {
+        let Some(mut hook) = self.hook.take() else {
+            return self.commit_inner(committer.into());
+        };
+        let prepared_edits: Vec<_> = self
+            .updates
+            .as_deref()
+            .unwrap_or_default()
+            .iter()
+            .map(|edit| edit.update.clone())
+            .collect();
+        match self.commit_inner(committer.into()) {
+            Ok(edits) => {
+                hook::call(&mut hook, hook::State::Committed, &edits).ok();
+                Ok(edits)
+            }
+            Err(err) => {
+                hook::call(&mut hook, hook::State::Aborted, &prepared_edits).ok();
+                Err(err)
+            }
+        }
     }
 
-    fn commit_inner(self, committer: Option<gix_actor::SignatureRef<'_>>) -> Result<Vec<RefEdit>, Error> {
-        let mut updates = self.updates.expect("BUG: must call prepare before commit");
+    fn commit_inner(mut self, committer: Option<gix_actor::SignatureRef<'_>>) -> Result<Vec<RefEdit>, Error> {
+        let mut updates = self.updates.take().expect("BUG: must call prepare before commit");
         let delete_loose_refs = matches!(
             self.packed_refs,
             PackedRefs::DeletionsAndNonSymbolicUpdatesRemoveLooseSourceReference(_)
@@ -143,7 +168,7 @@ impl Transaction<'_, '_> {
             }
         }
 
-        if let Some(t) = self.packed_transaction {
+        if let Some(t) = self.packed_transaction.take() {
             t.commit().map_err(Error::PackedTransactionCommit)?;
             // Always refresh ourselves right away to avoid races. We ignore errors as there may be many reasons this fails, and it's not
             // critical to be done here. In other words, the pack may be refreshed at a later time and then it might work.
@@ -73,6 +73,7 @@ impl file::Store {
             packed_transaction: None,
             updates: None,
             packed_refs: PackedRefs::default(),
+            hook: None,
         }
     }
 }
@@ -83,6 +84,22 @@ impl<'p> Transaction<'_, 'p> {
         self.packed_refs = packed_refs;
         self
     }
+
+    /// Call `hook` once the transaction was prepared, committed or aborted after it was prepared, similar to git's
+    /// `reference-transaction` hook. If it fails while the transaction is prepared, the transaction is aborted.
+    pub fn hook(mut self, hook: crate::transaction::hook::Hook<'p>) -> Self {
+        self.hook = Some(hook);
+        self
+    }
+}
+
+impl Drop for Transaction<'_, '_> {
+    fn drop(&mut self) {
+        if let (Some(updates), Some(hook)) = (self.updates.as_deref(), self.hook.as_mut()) {
+            let edits = updates.iter().map(|edit| &edit.update);
+            crate::transaction::hook::call(hook, crate::transaction::hook::State::Aborted, edits).ok();
+        }
+    }
 }
 
 impl std::fmt::Debug for Transaction<'_, '_> {
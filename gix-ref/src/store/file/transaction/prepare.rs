@@ -8,7 +8,7 @@ use crate::{
             transaction::{Edit, PackedRefs},
         },
     },
-    transaction::{Change, LogChange, PreviousValue, RefEdit, RefEditsExt, RefLog},
+    transaction::{Change, LogChange, PreviousValue, RefEdit, RefEditsExt, RefLog, hook},
 };
 
 impl Transaction<'_, '_> {
@@ -235,11 +235,19 @@ impl Transaction<'_, '_> {
         ref_files_lock_fail_mode: gix_lock::acquire::Fail,
         packed_refs_lock_fail_mode: gix_lock::acquire::Fail,
     ) -> Result<Self, Error> {
-        self.prepare_inner(
+        let mut t = self.prepare_inner(
             &mut edits.into_iter(),
             ref_files_lock_fail_mode,
             packed_refs_lock_fail_mode,
-        )
+        )?;
+        if let Some(hook) = t.hook.as_mut() {
+            let edits = t.updates.as_deref().unwrap_or_default().iter().map(|edit| &edit.update);
+            if let Err(err) = hook::call(hook, hook::State::Prepared, edits) {
+                t.rollback();
+                return Err(Error::HookRejected(err));
+            }
+        }
+        Ok(t)
     }
 
     fn prepare_inner(
@@ -439,10 +447,16 @@ impl Transaction<'_, '_> {
     /// # Note
     ///
     /// A rollback happens automatically as this instance is dropped as well.
-    pub fn rollback(self) -> Vec<RefEdit> {
-        self.updates
-            .map(|updates| updates.into_iter().map(|u| u.update).collect())
-            .unwrap_or_default()
+    /// In both cases, the [hook](Transaction::hook()) is informed that the transaction was aborted if it was prepared.
+    pub fn rollback(mut self) -> Vec<RefEdit> {
+        let Some(updates) = self.updates.take() else {
+            return Vec::new();
+        };
+        let edits: Vec<_> = updates.into_iter().map(|u| u.update).collect();
+        if let Some(mut hook) = self.hook.take() {
+            hook::call(&mut hook, hook::State::Aborted, &edits).ok();
+        }
+        edits
     }
 }
 
@@ -514,6 +528,8 @@ mod error {
         },
         #[error("Could not read reference")]
         ReferenceDecode(#[from] file::loose::reference::decode::Error),
+        #[error("The transaction was rejected by its hook")]
+        HookRejected(#[source] crate::transaction::hook::Error),
     }
 }
 
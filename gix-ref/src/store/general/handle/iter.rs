@@ -0,0 +1,96 @@
+use gix_path::RelativePath;
+
+use crate::{Reference, file, reftable, store, store::handle};
+
+/// An intermediate structure to hold shared state alive long enough for iteration to happen.
+#[must_use = "Iterators should be obtained from this platform"]
+pub enum Platform<'s> {
+    /// The platform of a loose reference store.
+    Loose(file::iter::Platform<'s>),
+    /// The platform of a reftable store.
+    Reftable(reftable::iter::Platform<'s>),
+}
+
+/// An iterator over references, sorted by their name.
+pub enum Iter<'p> {
+    /// Iterate loose references, and packed ones.
+    Loose(file::iter::LooseThenPacked<'p, 'p>),
+    /// Iterate references in a stack of reftables.
+    Reftable(reftable::iter::Iter<'p>),
+}
+
+impl Iterator for Iter<'_> {
+    type Item = Result<Reference, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Iter::Loose(iter) => iter.next().map(|res| res.map_err(Into::into)),
+            Iter::Reftable(iter) => iter.next().map(|res| res.map_err(Into::into)),
+        }
+    }
+}
+
+impl Platform<'_> {
+    /// Return an iterator over all references in `refs/`, sorted by their name.
+    pub fn all(&self) -> std::io::Result<Iter<'_>> {
+        Ok(match self {
+            Platform::Loose(platform) => Iter::Loose(platform.all()?),
+            Platform::Reftable(platform) => Iter::Reftable(platform.all()),
+        })
+    }
+
+    /// As [`all()`](Platform::all()), but filters by `prefix`, i.e. `refs/heads/` or `refs/heads/feature-`.
+    ///
+    /// Prefixes are relative paths with slash-separated components.
+    pub fn prefixed(&self, prefix: &RelativePath) -> std::io::Result<Iter<'_>> {
+        Ok(match self {
+            Platform::Loose(platform) => Iter::Loose(platform.prefixed(prefix)?),
+            Platform::Reftable(platform) => Iter::Reftable(platform.prefixed(prefix)),
+        })
+    }
+
+    /// Return an iterator over the pseudo references, like `HEAD`, sorted by name.
+    pub fn pseudo(&self) -> std::io::Result<Iter<'_>> {
+        Ok(match self {
+            Platform::Loose(platform) => Iter::Loose(platform.pseudo()?),
+            Platform::Reftable(platform) => Iter::Reftable(platform.pseudo()),
+        })
+    }
+}
+
+impl store::Handle {
+    /// Return a platform to obtain iterators over all references, or prefixed ones, sorted by their name.
+    pub fn iter(&self) -> Result<Platform<'_>, init::Error> {
+        Ok(match &self.state {
+            handle::State::Loose { store } => Platform::Loose(store.iter()?),
+            handle::State::Reftable { store } => Platform::Reftable(store.iter()?),
+        })
+    }
+}
+
+///
+pub mod init {
+    /// The error returned by [`store::Handle::iter()`][crate::store::Handle::iter()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The packed-refs file could not be opened")]
+        Packed(#[from] crate::packed::buffer::open::Error),
+        #[error("The stack of reftables could not be loaded")]
+        Reftable(#[from] crate::reftable::stack::load::Error),
+    }
+}
+
+mod error {
+    /// The error returned by [`Iter`][super::Iter] for each reference that couldn't be read.
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("A loose or packed reference could not be read")]
+        Loose(#[from] crate::file::iter::loose_then_packed::Error),
+        #[error("A reference could not be read from its table")]
+        Reftable(#[from] crate::reftable::table::decode::Error),
+    }
+}
+
+pub use error::Error;
@@ -1,19 +1,19 @@
-#![allow(dead_code)]
-use crate::{Namespace, store};
+use crate::{Namespace, store, store::WriteReflog};
 
 #[derive(Clone)]
 pub(crate) enum State {
     Loose { store: crate::file::Store },
+    Reftable { store: crate::reftable::Store },
 }
 
 impl crate::Store {
-    /// Return a new handle which sees all references if `namespace` is `None` or all read and write operations are limited
-    /// to the given `namespace` if `Some`.
+    /// Return a new handle which sees all references.
     pub fn to_handle(&self) -> store::Handle {
         Self::new_handle_inner(&self.inner, None)
     }
 
-    /// As above, but supports a namespace to be set
+    /// Return a new handle which sees all references if `namespace` is `None` or all read and write operations are limited
+    /// to the given `namespace` if `Some`.
     pub fn to_handle_namespaced(&self, namespace: Option<Namespace>) -> store::Handle {
         Self::new_handle_inner(&self.inner, namespace)
     }
@@ -21,7 +21,14 @@ impl crate::Store {
     fn new_handle_inner(state: &store::State, namespace: Option<Namespace>) -> store::Handle {
         store::Handle {
             state: match state {
-                store::State::Loose { store } => store::handle::State::Loose {
+                store::State::Loose { store } => State::Loose {
+                    store: {
+                        let mut store = store.clone();
+                        store.namespace = namespace;
+                        store
+                    },
+                },
+                store::State::Reftable { store } => State::Reftable {
                     store: {
                         let mut store = store.clone();
                         store.namespace = namespace;
@@ -33,11 +40,34 @@ impl crate::Store {
     }
 }
 
+impl store::Handle {
+    /// Limit all read and write operations to the given `namespace` if `Some`, or see all references if `None`.
+    pub fn set_namespace(&mut self, namespace: Option<Namespace>) {
+        match &mut self.state {
+            State::Loose { store } => store.namespace = namespace,
+            State::Reftable { store } => store.namespace = namespace,
+        }
+    }
+
+    /// Set how reflogs are written when editing references.
+    pub fn set_write_reflog(&mut self, write_reflog: WriteReflog) {
+        match &mut self.state {
+            State::Loose { store } => store.write_reflog = write_reflog,
+            State::Reftable { store } => store.write_reflog = write_reflog,
+        }
+    }
+}
+
 ///
 pub mod find;
 
-mod iter {
-    // impl store::Handle {
-    //     pub fn iter<'p, 's>(&'s self, packed: Option<&'p packed::Buffer>) -> std::io::Result<LooseThenPacked<'p, 's>> {
-    // }
-}
+///
+pub mod iter;
+
+mod peel;
+
+///
+pub mod reflog;
+
+///
+pub mod transaction;
@@ -0,0 +1,48 @@
+use gix_hash::ObjectId;
+
+use crate::{
+    Reference, file,
+    file::ReferenceExt,
+    peel,
+    store::{find, handle},
+};
+
+impl crate::store::Handle {
+    /// Follow `reference` a single level if it's symbolic and return the reference it points to,
+    /// or `None` if it points to an object already.
+    pub fn follow(&self, reference: &Reference) -> Option<Result<Reference, find::existing::Error>> {
+        match &self.state {
+            handle::State::Loose { store } => reference.follow(store).map(|res| res.map_err(Into::into)),
+            handle::State::Reftable { store } => store.follow(reference).map(|res| res.map_err(Into::into)),
+        }
+    }
+
+    /// Follow `reference` until it points to an object, and return the object's id.
+    /// Afterwards, `reference` is the last reference in the chain, the one pointing to the object directly.
+    pub fn follow_to_object(&self, reference: &mut Reference) -> Result<ObjectId, peel::to_object::Error> {
+        match &self.state {
+            handle::State::Loose { store } => {
+                let packed = store.cached_packed_buffer().map_err(|err| {
+                    peel::to_object::Error::Follow(file::find::existing::Error::Find(file::find::Error::PackedOpen(
+                        err,
+                    )))
+                })?;
+                reference.follow_to_object_packed(store, packed.as_ref().map(|b| &***b))
+            }
+            handle::State::Reftable { store } => store.follow_to_object(reference),
+        }
+    }
+
+    /// Follow `reference` to the object it points to, and peel annotated tags using `objects` until the first
+    /// non-tag object is found, whose id is returned. Afterwards, `reference` points to that object.
+    pub fn peel_to_id(
+        &self,
+        reference: &mut Reference,
+        objects: &dyn gix_object::Find,
+    ) -> Result<ObjectId, peel::to_id::Error> {
+        match &self.state {
+            handle::State::Loose { store } => reference.peel_to_id(store, objects),
+            handle::State::Reftable { store } => store.peel_to_id(reference, objects),
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use crate::{FullNameRef, log::Line, store, store::handle};
+
+impl store::Handle {
+    /// Return `true` if a reflog exists for the reference `name`.
+    pub fn reflog_exists(&self, name: &FullNameRef) -> Result<bool, Error> {
+        Ok(match &self.state {
+            handle::State::Loose { store } => store.reflog_exists(name)?,
+            handle::State::Reftable { store } => store.reflog_exists(name)?,
+        })
+    }
+
+    /// Return all reflog entries of the reference `name`, oldest first, or `None` if there is no reflog.
+    pub fn reflog(&self, name: &FullNameRef) -> Result<Option<Vec<Line>>, Error> {
+        Ok(match &self.state {
+            handle::State::Loose { store } => {
+                let mut buf = Vec::new();
+                match store.reflog_iter(name, &mut buf)? {
+                    Some(lines) => Some(
+                        lines
+                            .map(|line| line.map(|line| line.to_owned()))
+                            .collect::<Result<_, _>>()?,
+                    ),
+                    None => None,
+                }
+            }
+            handle::State::Reftable { store } => store.reflog_iter(name)?.map(Iterator::collect),
+        })
+    }
+}
+
+mod error {
+    use std::convert::Infallible;
+
+    /// The error returned by [`store::Handle::reflog()`][crate::store::Handle::reflog()] and
+    /// [`store::Handle::reflog_exists()`][crate::store::Handle::reflog_exists()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The reflog file could not be read")]
+        Loose(#[from] crate::file::log::Error),
+        #[error("A reflog line could not be parsed")]
+        LooseDecode(#[from] crate::file::log::iter::decode::Error),
+        #[error("The reflog could not be read from the stack of reftables")]
+        Reftable(#[from] crate::reftable::log::Error),
+    }
+
+    impl From<Infallible> for Error {
+        fn from(_: Infallible) -> Self {
+            unreachable!("this impl is needed to allow passing a known valid name as parameter")
+        }
+    }
+}
+
+pub use error::Error;
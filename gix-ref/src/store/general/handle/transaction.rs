@@ -0,0 +1,94 @@
+use crate::{file, reftable, store, store::handle, transaction::RefEdit};
+
+/// A transaction on the references of a [`Store`](crate::Store), which is atomic if the backend supports it.
+#[derive(Debug)]
+#[must_use = "transactions must be committed to take effect"]
+pub enum Transaction<'s> {
+    /// A transaction on loose references and packed references.
+    Loose(file::Transaction<'s, 's>),
+    /// A transaction on a stack of reftables.
+    Reftable(reftable::transaction::Transaction<'s>),
+}
+
+impl store::Handle {
+    /// Open a transaction to edit references.
+    ///
+    /// The transaction inherits the namespace of this handle.
+    pub fn transaction(&self) -> Transaction<'_> {
+        match &self.state {
+            handle::State::Loose { store } => Transaction::Loose(store.transaction()),
+            handle::State::Reftable { store } => Transaction::Reftable(store.transaction()),
+        }
+    }
+}
+
+impl<'s> Transaction<'s> {
+    /// Call `hook` once the transaction was prepared, committed or aborted after it was prepared, similar to git's
+    /// `reference-transaction` hook. If it fails while the transaction is prepared, the transaction is aborted.
+    pub fn hook(self, hook: crate::transaction::hook::Hook<'s>) -> Self {
+        match self {
+            Transaction::Loose(t) => Transaction::Loose(t.hook(hook)),
+            Transaction::Reftable(t) => Transaction::Reftable(t.hook(hook)),
+        }
+    }
+
+    /// Prepare for calling [`commit(…)`][Transaction::commit()] by acquiring all locks, waiting for them according to `lock_fail_mode`,
+    /// and validating `edits` against the current state of their references.
+    pub fn prepare(
+        self,
+        edits: impl IntoIterator<Item = RefEdit>,
+        lock_fail_mode: gix_lock::acquire::Fail,
+    ) -> Result<Self, prepare::Error> {
+        Ok(match self {
+            Transaction::Loose(t) => Transaction::Loose(t.prepare(edits, lock_fail_mode, lock_fail_mode)?),
+            Transaction::Reftable(t) => Transaction::Reftable(t.prepare(edits, lock_fail_mode)?),
+        })
+    }
+
+    /// Make all [prepared][Transaction::prepare()] edits permanent and return them, adjusted to know about the previous state of
+    /// their references.
+    /// `committer` is used in the reflog and only if the reflog is actually written, which is why it is optional.
+    pub fn commit<'a>(
+        self,
+        committer: impl Into<Option<gix_actor::SignatureRef<'a>>>,
+    ) -> Result<Vec<RefEdit>, commit::Error> {
+        Ok(match self {
+            Transaction::Loose(t) => t.commit(committer)?,
+            Transaction::Reftable(t) => t.commit(committer)?,
+        })
+    }
+
+    /// Rollback all intermediate state and return the `RefEdits` as we know them thus far.
+    pub fn rollback(self) -> Vec<RefEdit> {
+        match self {
+            Transaction::Loose(t) => t.rollback(),
+            Transaction::Reftable(t) => t.rollback(),
+        }
+    }
+}
+
+///
+pub mod prepare {
+    /// The error returned by [`Transaction::prepare()`][super::Transaction::prepare()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Loose(#[from] crate::file::transaction::prepare::Error),
+        #[error(transparent)]
+        Reftable(#[from] crate::reftable::transaction::prepare::Error),
+    }
+}
+
+///
+pub mod commit {
+    /// The error returned by [`Transaction::commit()`][super::Transaction::commit()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Loose(#[from] crate::file::transaction::commit::Error),
+        #[error(transparent)]
+        Reftable(#[from] crate::reftable::transaction::commit::Error),
+    }
+}
@@ -12,22 +12,33 @@ mod error {
 
 pub use error::Error;
 
-use crate::file;
+use crate::{file, reftable, store::Backend};
 
-#[allow(dead_code)]
 impl crate::Store {
-    /// Create a new store at the given location, typically the `.git/` directory.
+    /// Create a new store at the given location, typically the `.git/` directory, which uses `backend` to store references.
     /// Use [`opts`](crate::store::init::Options) to adjust settings.
     ///
     /// Note that if [`precompose_unicode`](crate::store::init::Options::precompose_unicode) is set in the options,
     /// the `git_dir` is also expected to use precomposed unicode, or else some operations that strip prefixes will fail.
-    pub fn at(git_dir: PathBuf, opts: crate::store::init::Options) -> Result<Self, Error> {
-        // for now, just try to read the directory - later we will do that naturally as we have to figure out if it's a ref-table or not.
+    pub fn at(git_dir: PathBuf, backend: Backend, opts: crate::store::init::Options) -> Result<Self, Error> {
         std::fs::read_dir(&git_dir)?;
         Ok(crate::Store {
-            inner: crate::store::State::Loose {
-                store: file::Store::at(git_dir, opts),
+            inner: match backend {
+                Backend::Files => crate::store::State::Loose {
+                    store: file::Store::at(git_dir, opts),
+                },
+                Backend::Reftable => crate::store::State::Reftable {
+                    store: reftable::Store::at(git_dir, opts),
+                },
             },
         })
     }
+
+    /// Return the backend used to store references.
+    pub fn backend(&self) -> Backend {
+        match &self.inner {
+            crate::store::State::Loose { .. } => Backend::Files,
+            crate::store::State::Reftable { .. } => Backend::Reftable,
+        }
+    }
 }
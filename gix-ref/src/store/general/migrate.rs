@@ -0,0 +1,329 @@
+use std::path::{Path, PathBuf};
+
+use crate::{
+    FullName, Reference, Target, file,
+    log::Line,
+    reftable,
+    store::{Backend, State},
+    transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+};
+
+/// The contents of `refs/heads` in repositories using reftables, which exists to keep older versions of git from
+/// considering the repository valid.
+const REFTABLE_HEADS_MARKER: &[u8] = b"this repository uses the reftable format\n";
+/// The contents of `HEAD` in repositories using reftables, for the same reason.
+const REFTABLE_HEAD_MARKER: &[u8] = b"ref: refs/heads/.invalid\n";
+
+/// Pseudo-refs which aren't references of any backend but always stored as files.
+const SPECIAL_REFS: &[&str] = &["FETCH_HEAD", "MERGE_HEAD"];
+
+/// The outcome of [`Store::migrate()`](crate::Store::migrate()).
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    /// The store using the new backend.
+    pub store: crate::Store,
+    /// The amount of references that were migrated.
+    pub refs: usize,
+    /// The amount of reflog entries that were migrated.
+    pub reflog_entries: usize,
+}
+
+mod error {
+    use std::path::PathBuf;
+
+    use crate::{file, packed, reftable, store};
+
+    /// The error returned by [`Store::migrate()`](crate::Store::migrate()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The references are already stored using the {backend:?} backend")]
+        SameBackend { backend: store::Backend },
+        #[error("Repositories with linked worktrees can't be migrated")]
+        LinkedWorktrees,
+        #[error("Refusing to migrate as {path:?} already exists")]
+        DestinationExists { path: PathBuf },
+        #[error("Could not iterate references")]
+        IterInit(#[from] store::iter::init::Error),
+        #[error("Could not read a reference")]
+        Iter(#[from] store::iter::Error),
+        #[error("Could not read a reflog")]
+        Reflog(#[from] store::reflog::Error),
+        #[error("Could not write the table with all references")]
+        WriteTable(#[from] reftable::write::Error),
+        #[error("Could not add the table with all references to the new stack")]
+        AddTable(#[from] reftable::stack::add::Error),
+        #[error("Could not load the new stack of tables")]
+        LoadStack(#[from] reftable::stack::load::Error),
+        #[error("Could not prepare writing loose references")]
+        LoosePrepare(#[from] file::transaction::prepare::Error),
+        #[error("Could not write loose references")]
+        LooseCommit(#[from] file::transaction::commit::Error),
+        #[error("Could not obtain the lock for the packed-refs file")]
+        PackedLock(#[from] file::packed::transaction::Error),
+        #[error("Could not prepare writing packed references")]
+        PackedPrepare(#[from] packed::transaction::prepare::Error),
+        #[error("Could not write packed references")]
+        PackedCommit(#[from] packed::transaction::commit::Error),
+        #[error("The configuration could not be updated to use the new backend")]
+        UpdateConfig(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
+    }
+}
+
+pub use error::Error;
+
+/// A reference and its reflog, oldest entry first.
+struct Entry {
+    reference: Reference,
+    log: Vec<Line>,
+}
+
+impl crate::Store {
+    /// Move all references and their reflogs into a store using the backend `to`, similar to `git refs migrate`, and
+    /// return a store for it.
+    ///
+    /// All data is first written into a temporary directory within the `.git` directory, and moved into place only
+    /// once it's complete. `update_config(to)` is called as soon as the new references are in place, and is expected
+    /// to set `extensions.refStorage` in the configuration of the repository accordingly. Only then the references of the
+    /// previous backend are removed, so an interruption leaves a repository that is fully usable with either backend.
+    /// `objects` are used to peel annotated tags when writing `packed-refs`.
+    ///
+    /// Note that concurrent writers must be stopped during the migration, and that repositories with linked worktrees
+    /// are not supported.
+    pub fn migrate(
+        &self,
+        to: Backend,
+        objects: &dyn gix_object::Find,
+        update_config: &mut dyn FnMut(Backend) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>>,
+    ) -> Result<Outcome, Error> {
+        let from = self.backend();
+        if from == to {
+            return Err(Error::SameBackend { backend: to });
+        }
+        let (git_dir, opts) = self.location_and_options();
+        let has_worktrees = match std::fs::read_dir(git_dir.join("worktrees")) {
+            Ok(mut entries) => entries.next().is_some(),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => false,
+            Err(err) => return Err(err.into()),
+        };
+        if has_worktrees {
+            return Err(Error::LinkedWorktrees);
+        }
+        let reftable_dir = git_dir.join("reftable");
+        if to == Backend::Reftable && reftable_dir.exists() {
+            return Err(Error::DestinationExists { path: reftable_dir });
+        }
+
+        let entries = self.read_all_entries()?;
+        let outcome = |store| Outcome {
+            store,
+            refs: entries.len(),
+            reflog_entries: entries.iter().map(|entry| entry.log.len()).sum(),
+        };
+
+        let tmp = git_dir.join(format!("ref_migration.{:08x}", reftable::stack::random_suffix()));
+        std::fs::create_dir(&tmp)?;
+        let res = match to {
+            Backend::Reftable => {
+                write_reftable(&tmp, opts, &entries)?;
+                std::fs::rename(tmp.join("reftable"), &reftable_dir)?;
+                update_config(to).map_err(Error::UpdateConfig)?;
+                remove_files_backend(&git_dir, &entries)?;
+                std::fs::write(git_dir.join("HEAD"), REFTABLE_HEAD_MARKER)?;
+                std::fs::create_dir_all(git_dir.join("refs"))?;
+                std::fs::write(git_dir.join("refs").join("heads"), REFTABLE_HEADS_MARKER)
+            }
+            Backend::Files => {
+                write_files(&tmp, opts, objects, &entries)?;
+                remove_if_present(&git_dir.join("refs"))?;
+                for name in tmp_file_names(&tmp)? {
+                    std::fs::rename(tmp.join(&name), git_dir.join(&name))?;
+                }
+                for dir in ["heads", "tags"] {
+                    std::fs::create_dir_all(git_dir.join("refs").join(dir))?;
+                }
+                update_config(to).map_err(Error::UpdateConfig)?;
+                remove_if_present(&reftable_dir)
+            }
+        };
+        res?;
+        remove_if_present(&tmp)?;
+        Ok(outcome(crate::Store::at(git_dir, to, opts).map_err(
+            |err| match err {
+                crate::store::init::Error::Io(err) => Error::Io(err),
+            },
+        )?))
+    }
+
+    fn location_and_options(&self) -> (PathBuf, crate::store::init::Options) {
+        match &self.inner {
+            State::Loose { store } => (
+                store.git_dir().to_owned(),
+                crate::store::init::Options {
+                    write_reflog: store.write_reflog,
+                    object_hash: store.object_hash,
+                    precompose_unicode: store.precompose_unicode,
+                    prohibit_windows_device_names: store.prohibit_windows_device_names,
+                },
+            ),
+            State::Reftable { store } => (
+                store.git_dir().to_owned(),
+                crate::store::init::Options {
+                    write_reflog: store.write_reflog,
+                    object_hash: store.object_hash(),
+                    ..Default::default()
+                },
+            ),
+        }
+    }
+
+    /// Read all references outside of namespaces along with their reflog, sorted by name.
+    fn read_all_entries(&self) -> Result<Vec<Entry>, Error> {
+        let handle = self.to_handle();
+        let platform = handle.iter()?;
+        let mut entries = Vec::new();
+        for reference in platform.pseudo()?.chain(platform.all()?) {
+            let reference = reference?;
+            if SPECIAL_REFS.iter().any(|name| reference.name.as_bstr() == *name) {
+                continue;
+            }
+            let log = handle.reflog(reference.name.as_ref())?.unwrap_or_default();
+            entries.push(Entry { reference, log });
+        }
+        Ok(entries)
+    }
+}
+
+/// Write all `entries` into a single table in `dir/reftable`, with each reflog entry receiving its own update index.
+fn write_reftable(dir: &Path, opts: crate::store::init::Options, entries: &[Entry]) -> Result<(), Error> {
+    let dir = dir.join("reftable");
+    let lock = reftable::Stack::lock(&dir, gix_lock::acquire::Fail::Immediately)?;
+    let stack = reftable::Stack::load(&dir, opts.object_hash, None)?;
+    let num_log_entries: usize = entries.iter().map(|entry| entry.log.len()).sum();
+    let max_update_index = num_log_entries.max(1) as u64;
+    let mut writer = reftable::write::Writer::new(
+        opts.object_hash,
+        1,
+        max_update_index,
+        reftable::write::Options::default(),
+    );
+    let mut update_index = 0;
+    for Entry { reference, log } in entries {
+        writer.add_ref(reftable::RefRecord {
+            name: reference.name.clone(),
+            update_index: max_update_index,
+            value: match (&reference.target, reference.peeled) {
+                (Target::Object(target), Some(peeled)) if *target != peeled => reftable::RefValue::Peeled {
+                    target: *target,
+                    peeled,
+                },
+                (Target::Object(target), _) => reftable::RefValue::Object(*target),
+                (Target::Symbolic(name), _) => reftable::RefValue::Symbolic(name.clone()),
+            },
+        });
+        for line in log {
+            update_index += 1;
+            writer.add_log(reftable::LogRecord {
+                name: reference.name.clone(),
+                update_index,
+                line: Some(line.clone()),
+            });
+        }
+    }
+    stack.add_table(&dir, writer.write()?, lock)?;
+    Ok(())
+}
+
+/// Write all `entries` as loose references or into `packed-refs` in `dir`, along with their reflogs.
+fn write_files(
+    dir: &Path,
+    opts: crate::store::init::Options,
+    objects: &dyn gix_object::Find,
+    entries: &[Entry],
+) -> Result<(), Error> {
+    let mut store = file::Store::at(dir.to_owned(), opts);
+    store.write_reflog = crate::store::WriteReflog::Disable;
+    let edit = |name: &FullName, new: &Target| RefEdit {
+        change: Change::Update {
+            log: LogChange {
+                mode: RefLog::AndReference,
+                force_create_reflog: false,
+                message: Default::default(),
+            },
+            expected: PreviousValue::MustNotExist,
+            new: new.clone(),
+        },
+        name: name.clone(),
+        deref: false,
+    };
+    let (packed, loose): (Vec<_>, Vec<_>) = entries.iter().partition(|entry| {
+        entry.reference.target.kind() == crate::Kind::Object && entry.reference.name.as_bstr().starts_with(b"refs/")
+    });
+    store
+        .transaction()
+        .prepare(
+            loose
+                .iter()
+                .map(|entry| edit(&entry.reference.name, &entry.reference.target)),
+            gix_lock::acquire::Fail::Immediately,
+            gix_lock::acquire::Fail::Immediately,
+        )?
+        .commit(None)?;
+    if !packed.is_empty() {
+        store
+            .packed_transaction(gix_lock::acquire::Fail::Immediately)?
+            .prepare(
+                &mut packed
+                    .iter()
+                    .map(|entry| edit(&entry.reference.name, &entry.reference.target)),
+                objects,
+            )?
+            .commit()?;
+    }
+
+    for Entry { reference, log } in entries.iter().filter(|entry| !entry.log.is_empty()) {
+        let path = store.reflog_path(reference.name.as_ref());
+        std::fs::create_dir_all(path.parent().expect("reflogs are in the logs directory"))?;
+        let mut out = Vec::new();
+        for line in log {
+            line.write_to(&mut out)?;
+        }
+        std::fs::write(path, out)?;
+    }
+    Ok(())
+}
+
+/// Remove all references of the files backend in `git_dir` which were migrated as `entries`, along with all reflogs.
+fn remove_files_backend(git_dir: &Path, entries: &[Entry]) -> std::io::Result<()> {
+    for path in ["refs", "logs", "packed-refs"] {
+        remove_if_present(&git_dir.join(path))?;
+    }
+    for entry in entries {
+        let name = entry.reference.name.as_bstr();
+        if !name.contains(&b'/') && name != "HEAD" {
+            remove_if_present(&git_dir.join(gix_path::from_bstr(name)))?;
+        }
+    }
+    Ok(())
+}
+
+/// Return the names of all files and directories in `dir`.
+fn tmp_file_names(dir: &Path) -> std::io::Result<Vec<std::ffi::OsString>> {
+    std::fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.file_name()))
+        .collect()
+}
+
+fn remove_if_present(path: &Path) -> std::io::Result<()> {
+    let res = if path.is_dir() {
+        std::fs::remove_dir_all(path)
+    } else {
+        std::fs::remove_file(path)
+    };
+    match res {
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        res => res,
+    }
+}
@@ -1 +1,3 @@
-mod init;
+pub(crate) mod init;
+///
+pub mod migrate;
@@ -0,0 +1,126 @@
+use std::path::{Path, PathBuf};
+
+use gix_features::threading::{MutableOnDemand, OwnShared, get_mut, get_ref};
+
+use crate::{
+    Reference, Target,
+    store_impl::reftable::{RefRecord, RefValue, Stack, Store, stack, write},
+};
+
+/// Initialization
+impl Store {
+    /// Create a new store at the given `git_dir`, which contains the `reftable` directory, with `opts` to adjust settings.
+    ///
+    /// Note that the store isn't accessed until it's used.
+    pub fn at(git_dir: PathBuf, opts: crate::store::init::Options) -> Self {
+        Store {
+            git_dir,
+            object_hash: opts.object_hash,
+            write_reflog: opts.write_reflog,
+            namespace: None,
+            write_options: write::Options::default(),
+            auto_compact: true,
+            stack: OwnShared::new(MutableOnDemand::new(None)),
+        }
+    }
+}
+
+/// Access
+impl Store {
+    /// Return the `.git` directory in which the `reftable` directory is located.
+    pub fn git_dir(&self) -> &Path {
+        &self.git_dir
+    }
+
+    /// Return the directory containing all tables and the `tables.list` file.
+    pub fn reftable_dir(&self) -> PathBuf {
+        self.git_dir.join("reftable")
+    }
+
+    /// The kind of hash used by all tables.
+    pub fn object_hash(&self) -> gix_hash::Kind {
+        self.object_hash
+    }
+
+    /// Return the current stack of tables, reloading it if `tables.list` changed since it was last loaded.
+    pub fn stack(&self) -> Result<OwnShared<Stack>, stack::load::Error> {
+        let dir = self.reftable_dir();
+        let names = stack::read_list(&dir)?;
+        let previous = self.cached_stack();
+        if let Some(previous) = previous.as_ref().filter(|stack| stack.has_names(&names)) {
+            return Ok(previous.clone());
+        }
+        let stack = OwnShared::new(Stack::load(&dir, self.object_hash, previous.as_deref())?);
+        self.set_stack(stack.clone());
+        Ok(stack)
+    }
+
+    /// Return the most recently loaded stack, if there is one, without checking if it's still current.
+    pub(crate) fn cached_stack(&self) -> Option<OwnShared<Stack>> {
+        get_ref(&self.stack).clone()
+    }
+
+    pub(crate) fn set_stack(&self, stack: OwnShared<Stack>) {
+        *get_mut(&self.stack) = Some(stack);
+    }
+
+    /// Turn `record` into a reference, with our namespace removed, or `None` if it's a deletion.
+    pub(crate) fn to_reference(&self, record: RefRecord) -> Option<Reference> {
+        let (target, peeled) = match record.value {
+            RefValue::Deletion => return None,
+            RefValue::Object(id) => (Target::Object(id), None),
+            RefValue::Peeled { target, peeled } => (Target::Object(target), Some(peeled)),
+            RefValue::Symbolic(name) => (Target::Symbolic(name), None),
+        };
+        let mut reference = Reference {
+            name: record.name,
+            target,
+            peeled,
+        };
+        if let Some(namespace) = &self.namespace {
+            reference.strip_namespace(namespace);
+        }
+        Some(reference)
+    }
+}
+
+/// Maintenance
+impl Store {
+    /// Merge tables as needed to keep each table at least twice as large as the next newer one, which keeps the amount of
+    /// tables logarithmic in the amount of updates.
+    /// Return `true` if tables were merged.
+    ///
+    /// Nothing is done if the stack is currently locked by another process.
+    pub fn auto_compact(&self) -> Result<bool, stack::add::Error> {
+        let dir = self.reftable_dir();
+        let lock = match Stack::lock(&dir, gix_lock::acquire::Fail::Immediately) {
+            Ok(lock) => lock,
+            Err(stack::add::Error::LockAcquire(_)) => return Ok(false),
+            Err(err) => return Err(err),
+        };
+        self.compact_locked(&dir, lock, false)
+    }
+
+    /// Merge all tables into one, dropping all deletions, and wait for the stack to be unlocked according to `lock_mode`.
+    /// Return `true` if tables were merged.
+    pub fn compact_all(&self, lock_mode: gix_lock::acquire::Fail) -> Result<bool, stack::add::Error> {
+        let dir = self.reftable_dir();
+        let lock = Stack::lock(&dir, lock_mode)?;
+        self.compact_locked(&dir, lock, true)
+    }
+
+    fn compact_locked(&self, dir: &Path, lock: gix_lock::File, all: bool) -> Result<bool, stack::add::Error> {
+        let stack = Stack::load(dir, self.object_hash, self.cached_stack().as_deref())?;
+        let range = if all {
+            Some(0..stack.tables().len()).filter(|range| !range.is_empty())
+        } else {
+            stack.suggest_compaction()
+        };
+        let Some(range) = range else {
+            return Ok(false);
+        };
+        let compacted = stack.compact(dir, range, self.write_options, lock)?;
+        self.set_stack(OwnShared::new(compacted));
+        Ok(true)
+    }
+}
@@ -0,0 +1,305 @@
+use std::borrow::Cow;
+
+use gix_hash::ObjectId;
+use gix_object::bstr::BString;
+
+use crate::{
+    FullName,
+    store_impl::reftable::{RefValue, table::decode::Error, varint},
+};
+
+pub(crate) const REF: u8 = b'r';
+pub(crate) const LOG: u8 = b'g';
+pub(crate) const INDEX: u8 = b'i';
+
+/// The size of the header in front of each block.
+pub(crate) const HEADER_LEN: usize = 4;
+
+/// A single block of records, with log blocks already being decompressed.
+pub(crate) struct Block<'a> {
+    /// The kind of records in this block.
+    pub(crate) typ: u8,
+    /// All bytes of the block starting at the beginning of the block, which for the first block is the start of the file.
+    data: Cow<'a, [u8]>,
+    /// The offset of the first record in `data`.
+    pub(crate) records_start: usize,
+    /// The offset of the restart table in `data`, which is also the end of the records.
+    pub(crate) records_end: usize,
+    restart_count: usize,
+    /// The amount of bytes the block occupies in the file, including padding, to find the next block.
+    pub(crate) file_len: usize,
+}
+
+/// The decoded value of a record, whose kind depends on the block it was read from.
+pub(crate) enum Value {
+    Ref { update_index_delta: u64, value: RefValue },
+    Log(Option<crate::log::Line>),
+    Index { position: u64 },
+}
+
+pub(crate) fn corrupt(message: &'static str) -> Error {
+    Error::Corrupt { message }
+}
+
+fn read_u24(data: &[u8]) -> usize {
+    (usize::from(data[0]) << 16) | (usize::from(data[1]) << 8) | usize::from(data[2])
+}
+
+impl<'a> Block<'a> {
+    /// Read the block at `offset` from the table `data`, where `end` is the offset of the footer and `header_len` the length of
+    /// the table header, which is part of the first block.
+    /// Return `None` if there is no block at `offset`.
+    pub(crate) fn at(
+        data: &'a [u8],
+        offset: usize,
+        header_len: usize,
+        block_size: usize,
+        end: usize,
+    ) -> Result<Option<Self>, Error> {
+        let header_offset = if offset == 0 { header_len } else { offset };
+        if header_offset + HEADER_LEN > end {
+            return Ok(None);
+        }
+        let typ = data[header_offset];
+        let block_len = read_u24(&data[header_offset + 1..]);
+        let prefix_len = header_offset - offset + HEADER_LEN;
+        if block_len < prefix_len {
+            return Err(corrupt("block is shorter than its header"));
+        }
+        let (data, file_len) = if typ == LOG {
+            let mut out = Vec::with_capacity(block_len);
+            out.extend_from_slice(&data[offset..offset + prefix_len]);
+            out.resize(block_len, 0);
+            let mut inflate = gix_zlib::Decompress::new();
+            let status = inflate.decompress(
+                &data[offset + prefix_len..end],
+                &mut out[prefix_len..],
+                gix_zlib::FlushDecompress::Finish,
+            )?;
+            if status != gix_zlib::Status::StreamEnd || inflate.total_out() as usize != block_len - prefix_len {
+                return Err(corrupt("log block didn't decompress to its expected size"));
+            }
+            let file_len = prefix_len + inflate.total_in() as usize;
+            (Cow::Owned(out), file_len)
+        } else {
+            let block_end = offset + block_len;
+            if block_end > end {
+                return Err(corrupt("block exceeds the table"));
+            }
+            // Padded blocks are followed by null bytes, while unaligned ones are directly followed by the next block.
+            let file_len = if block_end < end && data[block_end] == 0 && block_size > block_len {
+                block_size
+            } else {
+                block_len
+            };
+            (Cow::Borrowed(&data[offset..block_end]), file_len)
+        };
+
+        let len = data.len();
+        if len < prefix_len + 2 {
+            return Err(corrupt("block is too short for its restart table"));
+        }
+        let restart_count = usize::from(u16::from_be_bytes([data[len - 2], data[len - 1]]));
+        let records_end = (len - 2)
+            .checked_sub(restart_count * 3)
+            .filter(|end| *end >= prefix_len)
+            .ok_or_else(|| corrupt("restart table exceeds the block"))?;
+        let block = Block {
+            typ,
+            data,
+            records_start: prefix_len,
+            records_end,
+            restart_count,
+            file_len,
+        };
+        if (0..restart_count).any(|idx| !(block.records_start..block.records_end).contains(&block.restart(idx))) {
+            return Err(corrupt("restart offset points outside of the records"));
+        }
+        Ok(Some(block))
+    }
+
+    /// Return the offset of the record at restart point `idx`, which is always within the records once the block was read.
+    fn restart(&self, idx: usize) -> usize {
+        read_u24(&self.data[self.records_end + idx * 3..])
+    }
+
+    /// Decode the record at `pos`, whose key is computed from the `key` of the previous record, and return its value along with the
+    /// position of the next record. `key` is updated to contain the key of the decoded record.
+    pub(crate) fn decode(
+        &self,
+        pos: usize,
+        key: &mut Vec<u8>,
+        object_hash: gix_hash::Kind,
+    ) -> Result<(Value, usize), Error> {
+        let input = &self.data[pos..self.records_end];
+        let (prefix_len, input) = varint::decode(input).ok_or_else(|| corrupt("truncated key prefix"))?;
+        let (suffix_and_type, input) = varint::decode(input).ok_or_else(|| corrupt("truncated key suffix"))?;
+        let (prefix_len, suffix_len) = (prefix_len as usize, (suffix_and_type >> 3) as usize);
+        let value_type = (suffix_and_type & 0x7) as u8;
+        if prefix_len > key.len() || suffix_len > input.len() {
+            return Err(corrupt("invalid key length"));
+        }
+        key.truncate(prefix_len);
+        key.extend_from_slice(&input[..suffix_len]);
+        let input = &input[suffix_len..];
+
+        let (value, input) = match self.typ {
+            REF => {
+                let (update_index_delta, input) =
+                    varint::decode(input).ok_or_else(|| corrupt("truncated update index"))?;
+                let (value, input) = decode_ref_value(value_type, input, object_hash)?;
+                (
+                    Value::Ref {
+                        update_index_delta,
+                        value,
+                    },
+                    input,
+                )
+            }
+            LOG => {
+                if key.len() < 9 || key[key.len() - 9] != 0 {
+                    return Err(corrupt("invalid log key"));
+                }
+                match value_type {
+                    0 => (Value::Log(None), input),
+                    1 => {
+                        let (line, input) = decode_log_line(input, object_hash)?;
+                        (Value::Log(Some(line)), input)
+                    }
+                    _ => return Err(corrupt("unknown log record type")),
+                }
+            }
+            INDEX => {
+                let (position, input) = varint::decode(input).ok_or_else(|| corrupt("truncated block position"))?;
+                (Value::Index { position }, input)
+            }
+            _ => return Err(corrupt("unsupported block type")),
+        };
+        Ok((value, self.records_end - input.len()))
+    }
+
+    /// Return the position of the first record whose key is greater than or equal to `target`, along with the key of the
+    /// record before it, as needed to decode it.
+    /// The position is the end of all records if there is no such record.
+    pub(crate) fn seek(&self, target: &[u8], object_hash: gix_hash::Kind) -> Result<(usize, Vec<u8>), Error> {
+        let restart_key = |idx: usize| -> Result<&[u8], Error> {
+            let input = &self.data[self.restart(idx)..self.records_end];
+            let (prefix_len, input) = varint::decode(input).ok_or_else(|| corrupt("truncated key prefix"))?;
+            let (suffix_and_type, input) = varint::decode(input).ok_or_else(|| corrupt("truncated key suffix"))?;
+            let suffix_len = (suffix_and_type >> 3) as usize;
+            if prefix_len != 0 || suffix_len > input.len() {
+                return Err(corrupt("invalid restart record"));
+            }
+            Ok(&input[..suffix_len])
+        };
+
+        // Find the first restart point with a key past the target, and start searching at the one before it.
+        let (mut lo, mut hi) = (0, self.restart_count);
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            if restart_key(mid)? > target {
+                hi = mid;
+            } else {
+                lo = mid + 1;
+            }
+        }
+        let mut pos = match lo {
+            0 => self.records_start,
+            idx => self.restart(idx - 1),
+        };
+
+        let mut key = Vec::new();
+        let mut next_key = Vec::new();
+        while pos < self.records_end {
+            next_key.clone_from(&key);
+            let (_value, next_pos) = self.decode(pos, &mut next_key, object_hash)?;
+            if next_key.as_slice() >= target {
+                break;
+            }
+            std::mem::swap(&mut key, &mut next_key);
+            pos = next_pos;
+        }
+        Ok((pos, key))
+    }
+
+    /// Return the key of the first record in this block.
+    pub(crate) fn first_key(&self, object_hash: gix_hash::Kind) -> Result<Option<Vec<u8>>, Error> {
+        if self.records_start >= self.records_end {
+            return Ok(None);
+        }
+        let mut key = Vec::new();
+        self.decode(self.records_start, &mut key, object_hash)?;
+        Ok(Some(key))
+    }
+}
+
+fn split_id(input: &[u8], object_hash: gix_hash::Kind) -> Result<(ObjectId, &[u8]), Error> {
+    let len = object_hash.len_in_bytes();
+    if input.len() < len {
+        return Err(corrupt("truncated object id"));
+    }
+    let (id, rest) = input.split_at(len);
+    Ok((ObjectId::from_bytes_or_panic(id), rest))
+}
+
+fn split_string(input: &[u8]) -> Result<(BString, &[u8]), Error> {
+    let (len, input) = varint::decode(input).ok_or_else(|| corrupt("truncated string length"))?;
+    let len = len as usize;
+    if input.len() < len {
+        return Err(corrupt("truncated string"));
+    }
+    let (bytes, rest) = input.split_at(len);
+    Ok((bytes.into(), rest))
+}
+
+fn decode_ref_value(value_type: u8, input: &[u8], object_hash: gix_hash::Kind) -> Result<(RefValue, &[u8]), Error> {
+    Ok(match value_type {
+        0 => (RefValue::Deletion, input),
+        1 => {
+            let (id, input) = split_id(input, object_hash)?;
+            (RefValue::Object(id), input)
+        }
+        2 => {
+            let (target, input) = split_id(input, object_hash)?;
+            let (peeled, input) = split_id(input, object_hash)?;
+            (RefValue::Peeled { target, peeled }, input)
+        }
+        3 => {
+            let (name, input) = split_string(input)?;
+            (RefValue::Symbolic(FullName(name)), input)
+        }
+        _ => return Err(corrupt("unknown ref record type")),
+    })
+}
+
+fn decode_log_line(input: &[u8], object_hash: gix_hash::Kind) -> Result<(crate::log::Line, &[u8]), Error> {
+    let (previous_oid, input) = split_id(input, object_hash)?;
+    let (new_oid, input) = split_id(input, object_hash)?;
+    let (name, input) = split_string(input)?;
+    let (email, input) = split_string(input)?;
+    let (seconds, input) = varint::decode(input).ok_or_else(|| corrupt("truncated log time"))?;
+    if input.len() < 2 {
+        return Err(corrupt("truncated timezone offset"));
+    }
+    let offset_in_minutes = i16::from_be_bytes([input[0], input[1]]);
+    let (mut message, input) = split_string(&input[2..])?;
+    if message.last() == Some(&b'\n') {
+        message.pop();
+    }
+    Ok((
+        crate::log::Line {
+            previous_oid,
+            new_oid,
+            signature: gix_actor::Signature {
+                name,
+                email,
+                time: gix_actor::date::Time {
+                    seconds: seconds as gix_actor::date::SecondsSinceUnixEpoch,
+                    offset: i32::from(offset_in_minutes) * 60,
+                },
+            },
+            message,
+        },
+        input,
+    ))
+}
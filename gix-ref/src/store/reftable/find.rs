@@ -0,0 +1,138 @@
+use gix_object::bstr::BString;
+
+pub use error::Error;
+
+use crate::{
+    FullNameRef, PartialNameRef, Reference,
+    name::is_pseudo_ref,
+    store_impl::reftable::{Stack, Store},
+};
+
+impl Store {
+    /// Find a single reference by the given `path` which is required to be a valid reference name.
+    ///
+    /// Returns `Ok(None)` if no such ref exists.
+    ///
+    /// ### Note
+    ///
+    /// The lookup algorithm follows the one in [the git documentation][git-lookup-docs],
+    /// just like [`file::Store::try_find()`](crate::file::Store::try_find()).
+    ///
+    /// [git-lookup-docs]: https://github.com/git/git/blob/5d5b1473453400224ebb126bf3947e0a3276bdf5/Documentation/revisions.txt#L34-L46
+    pub fn try_find<'a, Name, E>(&self, partial: Name) -> Result<Option<Reference>, Error>
+    where
+        Name: TryInto<&'a PartialNameRef, Error = E>,
+        Error: From<E>,
+    {
+        let partial_name = partial.try_into()?;
+        let stack = self.stack()?;
+        self.find_one_with_verified_input(&stack, partial_name)
+    }
+
+    /// Find the reference with the exact `name`, ignoring all lookup rules, in the given `stack`.
+    pub(crate) fn find_full_name(&self, stack: &Stack, name: &FullNameRef) -> Result<Option<Reference>, Error> {
+        let record = match &self.namespace {
+            Some(namespace) => stack.find_ref(namespace.to_owned().into_namespaced_name(name).as_ref())?,
+            None => stack.find_ref(name)?,
+        };
+        Ok(record.and_then(|record| self.to_reference(record)))
+    }
+
+    pub(crate) fn find_one_with_verified_input(
+        &self,
+        stack: &Stack,
+        partial_name: &PartialNameRef,
+    ) -> Result<Option<Reference>, Error> {
+        let mut buf = BString::default();
+        for consider_pseudo_ref in [true, false] {
+            if !consider_pseudo_ref && !is_pseudo_ref(partial_name.as_bstr()) {
+                break;
+            }
+            for inbetween in &["", "tags", "heads", "remotes"] {
+                let full_name = partial_name.construct_full_name_ref(inbetween, &mut buf, consider_pseudo_ref);
+                if let Some(r) = self.find_full_name(stack, full_name)? {
+                    return Ok(Some(r));
+                }
+                if consider_pseudo_ref && is_pseudo_ref(partial_name.as_bstr()) {
+                    break;
+                }
+            }
+        }
+        if partial_name.as_bstr() != "HEAD" {
+            let with_head = partial_name.to_owned().join("HEAD".into()).expect("HEAD is valid name");
+            let full_name = with_head
+                .as_ref()
+                .construct_full_name_ref("remotes", &mut buf, true /* consider-pseudo-ref */);
+            self.find_full_name(stack, full_name)
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+///
+pub mod existing {
+    pub use error::Error;
+
+    use crate::{PartialNameRef, Reference, store_impl::reftable};
+
+    impl reftable::Store {
+        /// Similar to [`reftable::Store::try_find()`] but a non-existing ref is treated as error.
+        pub fn find<'a, Name, E>(&self, partial: Name) -> Result<Reference, Error>
+        where
+            Name: TryInto<&'a PartialNameRef, Error = E>,
+            crate::name::Error: From<E>,
+        {
+            let path = partial
+                .try_into()
+                .map_err(|err| Error::Find(reftable::find::Error::RefnameValidation(err.into())))?;
+            match self.try_find(path) {
+                Ok(Some(r)) => Ok(r),
+                Ok(None) => Err(Error::NotFound {
+                    name: path.to_partial_path().to_owned(),
+                }),
+                Err(err) => Err(err.into()),
+            }
+        }
+    }
+
+    mod error {
+        use std::path::PathBuf;
+
+        use crate::store_impl::reftable::find;
+
+        /// The error returned by [reftable::Store::find()][crate::reftable::Store::find()].
+        #[derive(Debug, thiserror::Error)]
+        #[allow(missing_docs)]
+        pub enum Error {
+            #[error("An error occurred while trying to find a reference")]
+            Find(#[from] find::Error),
+            #[error("The ref partially named {name:?} could not be found")]
+            NotFound { name: PathBuf },
+        }
+    }
+}
+
+mod error {
+    use std::convert::Infallible;
+
+    use crate::store_impl::reftable::{stack, table};
+
+    /// The error returned by [reftable::Store::try_find()][crate::reftable::Store::try_find()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The ref name or path is not a valid ref name")]
+        RefnameValidation(#[from] crate::name::Error),
+        #[error("The stack of tables could not be loaded")]
+        Load(#[from] stack::load::Error),
+        #[error("A reference could not be read from its table")]
+        Decode(#[from] table::decode::Error),
+    }
+
+    impl From<Infallible> for Error {
+        fn from(_: Infallible) -> Self {
+            unreachable!("this impl is needed to allow passing a known valid partial path as parameter")
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use gix_features::threading::OwnShared;
+use gix_object::bstr::{BStr, BString, ByteSlice};
+use gix_path::RelativePath;
+
+use crate::{
+    Reference,
+    store_impl::reftable::{RefRecord, Stack, Store, stack, table},
+};
+
+/// An intermediate structure to hold a snapshot of the stack alive long enough for iteration to happen.
+#[must_use = "Iterators should be obtained from this platform"]
+pub struct Platform<'s> {
+    store: &'s Store,
+    stack: OwnShared<Stack>,
+}
+
+/// An iterator over references of a [`Store`], sorted by name.
+pub struct Iter<'p> {
+    store: &'p Store,
+    inner: Box<dyn Iterator<Item = Result<RefRecord, table::decode::Error>> + 'p>,
+    /// If set, only references without a slash in their name are returned.
+    pseudo_only: bool,
+}
+
+impl Iterator for Iter<'_> {
+    type Item = Result<Reference, table::decode::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let record = match self.inner.next()? {
+                Ok(record) => record,
+                Err(err) => return Some(Err(err)),
+            };
+            let Some(reference) = self.store.to_reference(record) else {
+                continue;
+            };
+            if self.pseudo_only && reference.name.as_bstr().contains(&b'/') {
+                continue;
+            }
+            return Some(Ok(reference));
+        }
+    }
+}
+
+impl Platform<'_> {
+    /// Return an iterator over all references in `refs/`, sorted by their name.
+    pub fn all(&self) -> Iter<'_> {
+        self.iter_prefixed("refs/".into(), false)
+    }
+
+    /// As [`all()`](Platform::all()), but filters by `prefix`, i.e. `refs/heads/` or `refs/heads/feature-`.
+    ///
+    /// Prefixes are relative paths with slash-separated components.
+    pub fn prefixed(&self, prefix: &RelativePath) -> Iter<'_> {
+        self.iter_prefixed(prefix.as_ref().as_bstr(), false)
+    }
+
+    /// Return an iterator over the pseudo references, like `HEAD` or `ORIG_HEAD`, which are all references outside of `refs/`,
+    /// sorted by name.
+    pub fn pseudo(&self) -> Iter<'_> {
+        self.iter_prefixed("".into(), true)
+    }
+
+    fn iter_prefixed(&self, prefix: &BStr, pseudo_only: bool) -> Iter<'_> {
+        let prefix: BString = match &self.store.namespace {
+            Some(namespace) => {
+                let mut namespaced = namespace.as_bstr().to_owned();
+                namespaced.extend_from_slice(prefix);
+                namespaced
+            }
+            None => prefix.to_owned(),
+        };
+        Iter {
+            store: self.store,
+            inner: Box::new(self.stack.refs_with_prefix(prefix.as_ref())),
+            pseudo_only,
+        }
+    }
+}
+
+impl Store {
+    /// Return a platform to obtain iterators over all references, or prefixed ones, sorted by their name.
+    pub fn iter(&self) -> Result<Platform<'_>, stack::load::Error> {
+        Ok(Platform {
+            store: self,
+            stack: self.stack()?,
+        })
+    }
+}
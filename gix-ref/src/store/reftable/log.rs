@@ -0,0 +1,64 @@
+use crate::{
+    FullNameRef,
+    store_impl::reftable::{LogRecord, Stack, Store, table},
+};
+
+mod error {
+    use crate::store_impl::reftable::{stack, table};
+
+    /// The error returned by [reftable::Store::reflog_iter()][crate::reftable::Store::reflog_iter()] and similar methods.
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The stack of tables could not be loaded")]
+        Load(#[from] stack::load::Error),
+        #[error("A log entry could not be read from its table")]
+        Decode(#[from] table::decode::Error),
+    }
+}
+pub use error::Error;
+
+impl Store {
+    /// Return `true` if a reflog entry exists for the given reference `name`.
+    pub fn reflog_exists<'a, Name, E>(&self, name: Name) -> Result<bool, Error>
+    where
+        Name: TryInto<&'a FullNameRef, Error = E>,
+        crate::name::Error: From<E>,
+    {
+        let Ok(name) = name.try_into() else {
+            return Ok(false);
+        };
+        let stack = self.stack()?;
+        Ok(self.reflog_rev(&stack, name).next().is_some())
+    }
+
+    /// Return all reflog entries of the reference `name`, oldest first, or `None` if there is no reflog.
+    pub fn reflog_iter(&self, name: &FullNameRef) -> Result<Option<std::vec::IntoIter<crate::log::Line>>, Error> {
+        Ok(self.reflog_iter_rev(name)?.map(|lines| {
+            let mut lines: Vec<_> = lines.collect();
+            lines.reverse();
+            lines.into_iter()
+        }))
+    }
+
+    /// Return all reflog entries of the reference `name`, newest first, or `None` if there is no reflog.
+    pub fn reflog_iter_rev(&self, name: &FullNameRef) -> Result<Option<std::vec::IntoIter<crate::log::Line>>, Error> {
+        let stack = self.stack()?;
+        let lines = self
+            .reflog_rev(&stack, name)
+            .map(|record| record.map(|record| record.line.expect("deletions are filtered")))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok((!lines.is_empty()).then(|| lines.into_iter()))
+    }
+
+    fn reflog_rev<'a>(
+        &self,
+        stack: &'a Stack,
+        name: &FullNameRef,
+    ) -> impl Iterator<Item = Result<LogRecord, table::decode::Error>> + use<'a> {
+        match &self.namespace {
+            Some(namespace) => stack.logs_of(namespace.to_owned().into_namespaced_name(name).as_ref()),
+            None => stack.logs_of(name),
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use crate::store_impl::reftable::{LogRecord, RefRecord, table::decode::Error, table::log_key};
+
+/// A record that can be merged by its key.
+pub(crate) trait Keyed {
+    fn key(&self) -> Vec<u8>;
+}
+
+impl Keyed for RefRecord {
+    fn key(&self) -> Vec<u8> {
+        self.name.as_bstr().to_vec()
+    }
+}
+
+impl Keyed for LogRecord {
+    fn key(&self) -> Vec<u8> {
+        log_key(self.name.as_bstr(), self.update_index)
+    }
+}
+
+type Source<'a, T> = Box<dyn Iterator<Item = Result<T, Error>> + 'a>;
+
+/// Merge sorted records of multiple tables, ordered from oldest to newest, so that each key is returned only once
+/// with the value of the newest table that has it.
+pub(crate) struct Merged<'a, T> {
+    sources: Vec<Source<'a, T>>,
+    heads: Vec<Option<(Vec<u8>, T)>>,
+    exhausted: Vec<bool>,
+}
+
+impl<'a, T: Keyed> Merged<'a, T> {
+    pub(crate) fn new(sources: Vec<Source<'a, T>>) -> Self {
+        let len = sources.len();
+        Merged {
+            sources,
+            heads: (0..len).map(|_| None).collect(),
+            exhausted: vec![false; len],
+        }
+    }
+}
+
+impl<T: Keyed> Iterator for Merged<'_, T> {
+    type Item = Result<T, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for (idx, source) in self.sources.iter_mut().enumerate() {
+            if self.heads[idx].is_some() || self.exhausted[idx] {
+                continue;
+            }
+            match source.next() {
+                Some(Ok(record)) => self.heads[idx] = Some((record.key(), record)),
+                Some(Err(err)) => {
+                    self.exhausted[idx] = true;
+                    return Some(Err(err));
+                }
+                None => self.exhausted[idx] = true,
+            }
+        }
+
+        let min_key = self.heads.iter().flatten().map(|(key, _)| key).min()?.clone();
+        let mut newest = None;
+        for head in self.heads.iter_mut() {
+            if head.as_ref().is_some_and(|(key, _)| *key == min_key) {
+                newest = head.take();
+            }
+        }
+        newest.map(|(_, record)| Ok(record))
+    }
+}
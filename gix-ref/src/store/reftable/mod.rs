@@ -0,0 +1,119 @@
+//! A reference store backed by [reftables](https://git-scm.com/docs/reftable), as used by git if
+//! `extensions.refStorage` is set to `reftable`.
+//!
+//! References and their logs are stored in a stack of immutable tables listed in `reftable/tables.list`, with
+//! newer tables shadowing the values of older ones. Each transaction adds a new table on top of the stack, which is
+//! then compacted automatically to keep the amount of tables logarithmic in the amount of updates.
+use std::path::PathBuf;
+
+use gix_features::threading::{MutableOnDemand, OwnShared};
+use gix_hash::ObjectId;
+
+use crate::{FullName, Namespace, store::WriteReflog};
+
+/// A store for references which uses a stack of reftables.
+///
+/// Note that only the references of the main worktree are supported.
+#[derive(Debug, Clone)]
+pub struct Store {
+    /// The `.git` directory which contains the `reftable` directory.
+    git_dir: PathBuf,
+    /// The kind of hash all tables are expected to use.
+    object_hash: gix_hash::Kind,
+    /// The way to handle reflog edits
+    pub write_reflog: WriteReflog,
+    /// The namespace to use for edits and reads
+    pub namespace: Option<Namespace>,
+    /// The options to use when writing new tables.
+    pub write_options: write::Options,
+    /// If `true`, the default, tables will be compacted after each transaction to keep the amount of tables low.
+    pub auto_compact: bool,
+    /// The most recently loaded stack, shared among all clones of this instance.
+    stack: OwnShared<MutableOnDemand<Option<OwnShared<Stack>>>>,
+}
+
+/// A single immutable reftable, fully loaded into memory.
+pub struct Table {
+    /// The name of the table file within the `reftable` directory, or an empty string if it wasn't loaded from disk.
+    name: String,
+    data: Vec<u8>,
+    header: table::Header,
+    footer: table::Footer,
+}
+
+/// A snapshot of all tables as listed in `tables.list`, from oldest to newest.
+#[derive(Debug, Clone)]
+pub struct Stack {
+    tables: Vec<OwnShared<Table>>,
+}
+
+/// A reference as stored in a reftable.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+pub struct RefRecord {
+    /// The full name of the reference.
+    pub name: FullName,
+    /// The update index of the transaction that wrote this record.
+    pub update_index: u64,
+    /// The value of the reference.
+    pub value: RefValue,
+}
+
+/// The value of a [`RefRecord`].
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+pub enum RefValue {
+    /// The reference was deleted, shadowing values in older tables.
+    Deletion,
+    /// The reference points to an object.
+    Object(ObjectId),
+    /// The reference points to an annotated tag, which peels to another object.
+    Peeled {
+        /// The id of the annotated tag.
+        target: ObjectId,
+        /// The object the tag ultimately points to.
+        peeled: ObjectId,
+    },
+    /// The reference points to another reference.
+    Symbolic(FullName),
+}
+
+/// A reflog entry as stored in a reftable.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+pub struct LogRecord {
+    /// The full name of the reference the log entry belongs to.
+    pub name: FullName,
+    /// The update index of the transaction that wrote this entry.
+    pub update_index: u64,
+    /// The log entry itself, or `None` if the entry was deleted, shadowing entries with the same update index in older tables.
+    pub line: Option<crate::log::Line>,
+}
+
+mod varint;
+
+mod block;
+
+///
+pub mod table;
+
+///
+pub mod write;
+
+///
+pub mod stack;
+
+mod access;
+
+///
+pub mod find;
+
+///
+pub mod iter;
+
+mod peel;
+
+///
+pub mod log;
+
+///
+pub mod transaction;
+
+mod merge;
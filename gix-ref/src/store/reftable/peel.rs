@@ -0,0 +1,57 @@
+use std::collections::BTreeSet;
+
+use gix_hash::ObjectId;
+
+use crate::{
+    Reference, Target, peel,
+    store_impl::reftable::{Store, find},
+};
+
+impl Store {
+    /// Follow `reference` a single level if it's symbolic and return the reference it points to,
+    /// or `None` if it points to an object already.
+    pub fn follow(&self, reference: &Reference) -> Option<Result<Reference, find::existing::Error>> {
+        match &reference.target {
+            Target::Object(_) => None,
+            Target::Symbolic(full_name) => Some(self.find(full_name.as_ref())),
+        }
+    }
+
+    /// Follow `reference` until it points to an object, and return the object's id.
+    /// Afterwards, `reference` is the last reference in the chain, the one pointing to the object directly.
+    pub fn follow_to_object(&self, reference: &mut Reference) -> Result<ObjectId, peel::to_object::Error> {
+        const MAX_REF_DEPTH: usize = 5;
+        let mut seen = BTreeSet::new();
+        while let Some(next) = self.follow(reference) {
+            let next = next?;
+            if seen.contains(&next.name) {
+                return Err(peel::to_object::Error::Cycle {
+                    start_absolute: self.git_dir().join(reference.name.to_path()),
+                });
+            }
+            *reference = next;
+            seen.insert(reference.name.clone());
+            if seen.len() == MAX_REF_DEPTH {
+                return Err(peel::to_object::Error::DepthLimitExceeded {
+                    max_depth: MAX_REF_DEPTH,
+                });
+            }
+        }
+        Ok(reference.target.try_id().expect("followed to an object").to_owned())
+    }
+
+    /// Follow `reference` to the object it points to, and peel annotated tags using `objects` until the first
+    /// non-tag object is found, whose id is returned. Afterwards, `reference` points to that object.
+    pub fn peel_to_id(
+        &self,
+        reference: &mut Reference,
+        objects: &dyn gix_object::Find,
+    ) -> Result<ObjectId, peel::to_id::Error> {
+        if let Some(peeled) = reference.peeled {
+            reference.target = Target::Object(peeled);
+            return Ok(peeled);
+        }
+        let oid = self.follow_to_object(reference)?;
+        peel::tags_to_id(reference, oid, objects)
+    }
+}
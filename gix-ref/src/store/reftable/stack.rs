@@ -0,0 +1,369 @@
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use gix_features::threading::OwnShared;
+use gix_object::bstr::BStr;
+
+use crate::{
+    FullNameRef,
+    store_impl::reftable::{LogRecord, RefRecord, RefValue, Stack, Table, merge::Merged, table, write},
+};
+
+/// The name of the file listing all tables of the stack, oldest first.
+pub const TABLES_LIST: &str = "tables.list";
+
+/// The factor by which each table has to be larger than the next newer one to avoid compaction.
+const GEOMETRIC_FACTOR: u64 = 2;
+
+///
+pub mod load {
+    use std::path::PathBuf;
+
+    /// The error returned by [`Stack::at()`](crate::reftable::Stack::at()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not read the list of tables at \"{}\"", path.display())]
+        ReadList { source: std::io::Error, path: PathBuf },
+        #[error(transparent)]
+        Open(#[from] crate::reftable::table::open::Error),
+        #[error("The table {name:?} uses {actual} object ids, but {expected} was expected")]
+        ObjectHashMismatch {
+            name: String,
+            expected: gix_hash::Kind,
+            actual: gix_hash::Kind,
+        },
+    }
+}
+
+///
+pub mod add {
+    /// The error returned when adding a table to the stack.
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Load(#[from] super::load::Error),
+        #[error(transparent)]
+        Decode(#[from] crate::reftable::table::decode::Error),
+        #[error(transparent)]
+        Write(#[from] crate::reftable::write::Error),
+        #[error("The lock for the list of tables could not be obtained")]
+        LockAcquire(#[from] gix_lock::acquire::Error),
+        #[error("The list of tables could not be updated")]
+        LockCommit(#[from] gix_lock::commit::Error<gix_lock::File>),
+        #[error("An IO error occurred while writing a table")]
+        Io(#[from] std::io::Error),
+    }
+}
+
+/// Initialization
+impl Stack {
+    /// Load all tables listed in `tables.list` within the `reftable_dir`, expecting them to use `object_hash`.
+    ///
+    /// A missing `tables.list` file is treated like an empty stack.
+    pub fn at(reftable_dir: &Path, object_hash: gix_hash::Kind) -> Result<Self, load::Error> {
+        Self::load(reftable_dir, object_hash, None)
+    }
+
+    /// Load the stack in `dir`, reusing already loaded tables of `previous`.
+    pub(crate) fn load(dir: &Path, object_hash: gix_hash::Kind, previous: Option<&Stack>) -> Result<Self, load::Error> {
+        // Tables may be removed by concurrent compactions after we read the list, which is when we read it again.
+        let mut attempts = 0;
+        loop {
+            let names = read_list(dir)?;
+            match Self::load_tables(dir, &names, object_hash, previous) {
+                Err(load::Error::Open(table::open::Error::Io { source, .. }))
+                    if source.kind() == std::io::ErrorKind::NotFound && attempts < 5 =>
+                {
+                    attempts += 1;
+                }
+                res => return res,
+            }
+        }
+    }
+
+    fn load_tables(
+        dir: &Path,
+        names: &[String],
+        object_hash: gix_hash::Kind,
+        previous: Option<&Stack>,
+    ) -> Result<Self, load::Error> {
+        let mut tables = Vec::with_capacity(names.len());
+        for name in names {
+            let table = match previous.and_then(|stack| stack.tables.iter().find(|t| t.name == *name)) {
+                Some(table) => table.clone(),
+                None => OwnShared::new(Table::at(&dir.join(name))?),
+            };
+            if table.object_hash() != object_hash {
+                return Err(load::Error::ObjectHashMismatch {
+                    name: name.clone(),
+                    expected: object_hash,
+                    actual: table.object_hash(),
+                });
+            }
+            tables.push(table);
+        }
+        Ok(Stack { tables })
+    }
+}
+
+/// Read the names of all tables in the stack at `dir`.
+pub(crate) fn read_list(dir: &Path) -> Result<Vec<String>, load::Error> {
+    let path = dir.join(TABLES_LIST);
+    match std::fs::read(&path) {
+        Ok(content) => Ok(BStr::new(&content)
+            .split(|b| *b == b'\n')
+            .filter(|line| !line.is_empty())
+            .map(|line| String::from_utf8_lossy(line).into_owned())
+            .collect()),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(source) => Err(load::Error::ReadList { source, path }),
+    }
+}
+
+/// Access
+impl Stack {
+    /// Return all tables, from oldest to newest.
+    pub fn tables(&self) -> &[OwnShared<Table>] {
+        &self.tables
+    }
+
+    /// Return `true` if the names of our tables are exactly the given `names`.
+    pub(crate) fn has_names(&self, names: &[String]) -> bool {
+        self.tables.len() == names.len() && self.tables.iter().zip(names).all(|(table, name)| table.name == *name)
+    }
+
+    /// Return the update index to use for the next table added on top of the stack.
+    pub fn next_update_index(&self) -> u64 {
+        self.tables.last().map_or(1, |table| table.max_update_index() + 1)
+    }
+
+    /// Find the reference named `name`, or return `None` if it doesn't exist or was deleted.
+    pub fn find_ref(&self, name: &FullNameRef) -> Result<Option<RefRecord>, table::decode::Error> {
+        for table in self.tables.iter().rev() {
+            if let Some(record) = table.find_ref(name)? {
+                return Ok((record.value != RefValue::Deletion).then_some(record));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Return an iterator over all existing references whose name starts with `prefix`, sorted by name.
+    pub fn refs_with_prefix<'a>(
+        &'a self,
+        prefix: &BStr,
+    ) -> impl Iterator<Item = Result<RefRecord, table::decode::Error>> + use<'a> {
+        self.merged_refs(Some(prefix), false)
+    }
+
+    /// Return an iterator over all existing log entries of the reference named `name`, newest first.
+    pub fn logs_of<'a>(
+        &'a self,
+        name: &FullNameRef,
+    ) -> impl Iterator<Item = Result<LogRecord, table::decode::Error>> + use<'a> {
+        let sources = self
+            .tables
+            .iter()
+            .map(|table| match table.logs_of(name) {
+                Ok(logs) => Box::new(logs) as Box<dyn Iterator<Item = _>>,
+                Err(err) => Box::new(std::iter::once(Err(err))),
+            })
+            .collect();
+        Merged::new(sources).filter(|record| record.as_ref().map_or(true, |record: &LogRecord| record.line.is_some()))
+    }
+
+    /// Merge the references of all tables, starting at `prefix` and stopping at the first name without it.
+    fn merged_refs<'a>(
+        &'a self,
+        prefix: Option<&BStr>,
+        keep_deletions: bool,
+    ) -> impl Iterator<Item = Result<RefRecord, table::decode::Error>> + use<'a> {
+        let sources = self
+            .tables
+            .iter()
+            .map(|table| match prefix {
+                Some(prefix) => match table.refs_from(prefix) {
+                    Ok(refs) => Box::new(refs) as Box<dyn Iterator<Item = _>>,
+                    Err(err) => Box::new(std::iter::once(Err(err))),
+                },
+                None => Box::new(table.refs()),
+            })
+            .collect();
+        let prefix = prefix.map(ToOwned::to_owned);
+        Merged::new(sources)
+            .take_while(move |record| {
+                prefix.as_ref().is_none_or(|prefix| {
+                    record
+                        .as_ref()
+                        .map_or(true, |record| record.name.as_bstr().starts_with(prefix.as_slice()))
+                })
+            })
+            .filter(move |record| {
+                keep_deletions
+                    || record
+                        .as_ref()
+                        .map_or(true, |record| record.value != RefValue::Deletion)
+            })
+    }
+
+    fn merged_logs(&self, keep_deletions: bool) -> impl Iterator<Item = Result<LogRecord, table::decode::Error>> + '_ {
+        let sources = self
+            .tables
+            .iter()
+            .map(|table| Box::new(table.logs()) as Box<dyn Iterator<Item = _>>)
+            .collect();
+        Merged::new(sources).filter(move |record| {
+            keep_deletions || record.as_ref().map_or(true, |record: &LogRecord| record.line.is_some())
+        })
+    }
+}
+
+/// Modification
+impl Stack {
+    /// Obtain the lock for `tables.list` in `dir`, creating the directory if needed.
+    pub(crate) fn lock(dir: &Path, lock_mode: gix_lock::acquire::Fail) -> Result<gix_lock::File, add::Error> {
+        std::fs::create_dir_all(dir)?;
+        Ok(gix_lock::File::acquire_to_update_resource(
+            dir.join(TABLES_LIST),
+            lock_mode,
+            None,
+        )?)
+    }
+
+    /// Write `table` to a new file in `dir` and add it to the top of this stack, which must have been loaded while holding `lock`,
+    /// committing the lock. Return the stack with the new table.
+    pub(crate) fn add_table(&self, dir: &Path, table: Vec<u8>, lock: gix_lock::File) -> Result<Stack, add::Error> {
+        let table = write_table_file(dir, table)?;
+        let mut tables = self.tables.clone();
+        tables.push(OwnShared::new(table));
+        let stack = Stack { tables };
+        stack.commit_list(lock)?;
+        Ok(stack)
+    }
+
+    fn commit_list(&self, mut lock: gix_lock::File) -> Result<(), add::Error> {
+        for table in &self.tables {
+            writeln!(lock, "{}", table.name)?;
+        }
+        lock.commit()?;
+        Ok(())
+    }
+
+    /// Return the range of tables that should be merged to restore a geometric sequence of table sizes,
+    /// with each table being at least twice as large as the next newer one, or `None` if there is nothing to do.
+    pub(crate) fn suggest_compaction(&self) -> Option<std::ops::Range<usize>> {
+        let sizes: Vec<u64> = self
+            .tables
+            .iter()
+            .map(|table| (table.size() - table.header().footer_len() - table.header().len() + 1) as u64)
+            .collect();
+        let n = sizes.len();
+        if n <= 1 {
+            return None;
+        }
+        // Find the end of the segment from the newest table, then extend the segment to all older tables which are smaller
+        // than the accumulated size of the segment.
+        let mut idx = n - 1;
+        let mut bytes = 0;
+        let mut end = None;
+        while idx > 0 {
+            if sizes[idx - 1] < sizes[idx] * GEOMETRIC_FACTOR {
+                end = Some(idx + 1);
+                bytes = sizes[idx];
+                break;
+            }
+            idx -= 1;
+        }
+        let end = end?;
+        let mut start = end;
+        while idx > 0 {
+            let current = bytes;
+            bytes += sizes[idx - 1];
+            if sizes[idx - 1] < current * GEOMETRIC_FACTOR {
+                start = idx - 1;
+            }
+            idx -= 1;
+        }
+        (end - start > 1).then_some(start..end)
+    }
+
+    /// Merge the tables in `range` into a single one, which must have been loaded while holding `lock`, and commit the lock.
+    /// Deletions are dropped if the oldest table is part of the range as there is nothing left for them to shadow.
+    /// Return the compacted stack.
+    pub(crate) fn compact(
+        &self,
+        dir: &Path,
+        range: std::ops::Range<usize>,
+        options: write::Options,
+        lock: gix_lock::File,
+    ) -> Result<Stack, add::Error> {
+        let segment = &self.tables[range.clone()];
+        let (first, last) = (&segment[0], &segment[segment.len() - 1]);
+        let keep_deletions = range.start != 0;
+        let mut writer = write::Writer::new(
+            first.object_hash(),
+            first.min_update_index(),
+            last.max_update_index(),
+            options,
+        );
+        let segment_stack = Stack {
+            tables: segment.to_vec(),
+        };
+        for record in segment_stack.merged_refs(None, keep_deletions) {
+            writer.add_ref(record?);
+        }
+        for record in segment_stack.merged_logs(keep_deletions) {
+            writer.add_log(record?);
+        }
+        let table = write_table_file(dir, writer.write()?)?;
+
+        let mut tables = self.tables[..range.start].to_vec();
+        tables.push(OwnShared::new(table));
+        tables.extend_from_slice(&self.tables[range.end..]);
+        let stack = Stack { tables };
+        stack.commit_list(lock)?;
+
+        for table in segment {
+            std::fs::remove_file(dir.join(&table.name)).ok();
+        }
+        Ok(stack)
+    }
+}
+
+/// Write `data` into a new uniquely named table file in `dir` and return it as loaded table.
+fn write_table_file(dir: &Path, data: Vec<u8>) -> Result<Table, add::Error> {
+    let mut table = Table::from_bytes(data)?;
+    let mut suffix = random_suffix();
+    let (path, mut file) = loop {
+        let name = format!(
+            "0x{:012x}-0x{:012x}-{suffix:08x}.ref",
+            table.min_update_index(),
+            table.max_update_index()
+        );
+        let path: PathBuf = dir.join(&name);
+        match std::fs::OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(file) => {
+                table.name = name;
+                break (path, file);
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => suffix = suffix.wrapping_add(1),
+            Err(err) => return Err(err.into()),
+        }
+    };
+    if let Err(err) = file.write_all(&table.data).and_then(|_| file.sync_all()) {
+        std::fs::remove_file(&path).ok();
+        return Err(err.into());
+    }
+    Ok(table)
+}
+
+/// Produce a suffix that makes table names with the same update indices unlikely to collide.
+pub(crate) fn random_suffix() -> u32 {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |duration| duration.subsec_nanos());
+    nanos ^ std::process::id().rotate_left(16)
+}
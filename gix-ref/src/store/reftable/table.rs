@@ -0,0 +1,542 @@
+use std::path::Path;
+
+use gix_object::bstr::BStr;
+
+use crate::{
+    FullName, FullNameRef,
+    store_impl::reftable::{
+        LogRecord, RefRecord, Table,
+        block::{self, Block, Value, corrupt},
+    },
+};
+
+/// The magic bytes at the beginning of each table.
+pub(crate) const MAGIC: &[u8; 4] = b"REFT";
+const HASH_ID_SHA1: u32 = u32::from_be_bytes(*b"sha1");
+const HASH_ID_SHA256: u32 = u32::from_be_bytes(*b"s256");
+
+/// Return `true` if `object_hash` is SHA-1, which is the only hash supported by version 1 tables.
+pub(crate) fn is_sha1(object_hash: gix_hash::Kind) -> bool {
+    gix_hash::Kind::try_from(1).ok() == Some(object_hash)
+}
+
+/// The header of a table, which is repeated in its footer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Header {
+    /// The format version, `1` for tables using SHA-1, or `2` for tables that also record their hash.
+    pub version: u8,
+    /// The size of blocks in the table, which may be exceeded by log blocks.
+    pub block_size: u32,
+    /// The smallest update index of all records in the table.
+    pub min_update_index: u64,
+    /// The largest update index of all records in the table.
+    pub max_update_index: u64,
+    /// The kind of hash used for all object ids in the table.
+    pub object_hash: gix_hash::Kind,
+}
+
+impl Header {
+    /// The size of the encoded header.
+    pub(crate) fn len(&self) -> usize {
+        if self.version == 1 { 24 } else { 28 }
+    }
+
+    /// The size of the footer of a table with this header.
+    pub(crate) fn footer_len(&self) -> usize {
+        self.len() + 5 * 8 + 4
+    }
+
+    pub(crate) fn write_to(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(MAGIC);
+        out.push(self.version);
+        out.extend_from_slice(&self.block_size.to_be_bytes()[1..]);
+        out.extend_from_slice(&self.min_update_index.to_be_bytes());
+        out.extend_from_slice(&self.max_update_index.to_be_bytes());
+        if self.version != 1 {
+            let id = if is_sha1(self.object_hash) {
+                HASH_ID_SHA1
+            } else {
+                HASH_ID_SHA256
+            };
+            out.extend_from_slice(&id.to_be_bytes());
+        }
+    }
+
+    fn from_bytes(data: &[u8]) -> Result<Self, decode::Error> {
+        if data.len() < 24 || &data[..4] != MAGIC {
+            return Err(decode::Error::InvalidHeader);
+        }
+        let version = data[4];
+        let block_size = u32::from_be_bytes([0, data[5], data[6], data[7]]);
+        let min_update_index = u64::from_be_bytes(data[8..16].try_into().expect("8 bytes"));
+        let max_update_index = u64::from_be_bytes(data[16..24].try_into().expect("8 bytes"));
+        let id = match version {
+            1 => HASH_ID_SHA1,
+            2 => data
+                .get(24..28)
+                .map(|id| u32::from_be_bytes(id.try_into().expect("4 bytes")))
+                .ok_or(decode::Error::InvalidHeader)?,
+            version => return Err(decode::Error::UnsupportedVersion { version }),
+        };
+        let object_hash = match id {
+            HASH_ID_SHA1 => gix_hash::Kind::try_from(1),
+            HASH_ID_SHA256 => gix_hash::Kind::try_from(2),
+            _ => Err(0),
+        }
+        .map_err(|_| decode::Error::UnsupportedHash { id })?;
+        Ok(Header {
+            version,
+            block_size,
+            min_update_index,
+            max_update_index,
+            object_hash,
+        })
+    }
+}
+
+/// The offsets to the sections of a table, as stored in its footer.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Footer {
+    pub(crate) ref_index_offset: u64,
+    pub(crate) obj_offset_and_id_len: u64,
+    pub(crate) obj_index_offset: u64,
+    pub(crate) log_offset: u64,
+    pub(crate) log_index_offset: u64,
+}
+
+impl Footer {
+    pub(crate) fn write_to(&self, header: &Header, out: &mut Vec<u8>) {
+        let start = out.len();
+        header.write_to(out);
+        for offset in [
+            self.ref_index_offset,
+            self.obj_offset_and_id_len,
+            self.obj_index_offset,
+            self.log_offset,
+            self.log_index_offset,
+        ] {
+            out.extend_from_slice(&offset.to_be_bytes());
+        }
+        let crc = gix_features::hash::crc32(&out[start..]);
+        out.extend_from_slice(&crc.to_be_bytes());
+    }
+
+    fn from_bytes(footer: &[u8], header_bytes: &[u8]) -> Result<Self, decode::Error> {
+        let (footer, crc) = footer.split_at(footer.len() - 4);
+        if gix_features::hash::crc32(footer) != u32::from_be_bytes(crc.try_into().expect("4 bytes")) {
+            return Err(decode::Error::FooterChecksum);
+        }
+        let (header, offsets) = footer.split_at(header_bytes.len());
+        if header != header_bytes {
+            return Err(corrupt("footer doesn't repeat the header"));
+        }
+        let offset = |idx: usize| u64::from_be_bytes(offsets[idx * 8..][..8].try_into().expect("8 bytes"));
+        Ok(Footer {
+            ref_index_offset: offset(0),
+            obj_offset_and_id_len: offset(1),
+            obj_index_offset: offset(2),
+            log_offset: offset(3),
+            log_index_offset: offset(4),
+        })
+    }
+}
+
+///
+pub mod decode {
+    /// The error returned when reading a table or its records.
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The table doesn't start with a valid header")]
+        InvalidHeader,
+        #[error("Reftable version {version} is not supported")]
+        UnsupportedVersion { version: u8 },
+        #[error("The table uses an unknown hash with id {id:#x}")]
+        UnsupportedHash { id: u32 },
+        #[error("The checksum of the table footer didn't match")]
+        FooterChecksum,
+        #[error("The table is corrupt: {message}")]
+        Corrupt { message: &'static str },
+        #[error("A log block could not be decompressed")]
+        Inflate(#[from] gix_zlib::DecompressError),
+    }
+}
+
+///
+pub mod open {
+    use std::path::PathBuf;
+
+    /// The error returned by [`Table::at()`](crate::reftable::Table::at()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The table at \"{}\" could not be read", path.display())]
+        Io { source: std::io::Error, path: PathBuf },
+        #[error("The table at \"{}\" could not be decoded", path.display())]
+        Decode {
+            source: super::decode::Error,
+            path: PathBuf,
+        },
+    }
+}
+
+/// Initialization
+impl Table {
+    /// Read the table at `path` into memory.
+    pub fn at(path: &Path) -> Result<Self, open::Error> {
+        let data = std::fs::read(path).map_err(|source| open::Error::Io {
+            source,
+            path: path.to_owned(),
+        })?;
+        let mut table = Table::from_bytes(data).map_err(|source| open::Error::Decode {
+            source,
+            path: path.to_owned(),
+        })?;
+        table.name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        Ok(table)
+    }
+
+    /// Decode a table from `data`, validating its header and footer.
+    pub fn from_bytes(data: Vec<u8>) -> Result<Self, decode::Error> {
+        let header = Header::from_bytes(&data)?;
+        let footer_len = header.footer_len();
+        if data.len() < header.len() + footer_len {
+            return Err(corrupt("table is too short for its footer"));
+        }
+        let footer = Footer::from_bytes(&data[data.len() - footer_len..], &data[..header.len()])?;
+        Ok(Table {
+            name: String::new(),
+            data,
+            header,
+            footer,
+        })
+    }
+}
+
+/// Access
+impl Table {
+    /// The name of the file this table was loaded from, or an empty string if it was created from memory.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The header of the table.
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    /// The kind of hash used for object ids in this table.
+    pub fn object_hash(&self) -> gix_hash::Kind {
+        self.header.object_hash
+    }
+
+    /// The smallest update index of all records in this table.
+    pub fn min_update_index(&self) -> u64 {
+        self.header.min_update_index
+    }
+
+    /// The largest update index of all records in this table.
+    pub fn max_update_index(&self) -> u64 {
+        self.header.max_update_index
+    }
+
+    /// The size of the table in bytes.
+    pub fn size(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Return an iterator over all references in this table, including deletions, sorted by name.
+    pub fn refs(&self) -> Refs<'_> {
+        Refs {
+            inner: self.section(block::REF),
+        }
+    }
+
+    /// Return an iterator over all references in this table whose name is equal to or sorts after `name`.
+    pub fn refs_from(&self, name: &BStr) -> Result<Refs<'_>, decode::Error> {
+        Ok(Refs {
+            inner: self.seek(block::REF, name)?,
+        })
+    }
+
+    /// Find the record of the reference named `name`, which may be a deletion.
+    pub fn find_ref(&self, name: &FullNameRef) -> Result<Option<RefRecord>, decode::Error> {
+        Ok(match self.refs_from(name.as_bstr())?.next().transpose()? {
+            Some(record) if record.name.as_ref() == name => Some(record),
+            _ => None,
+        })
+    }
+
+    /// Return an iterator over all log records in this table, sorted by reference name and newest first.
+    pub fn logs(&self) -> Logs<'_> {
+        Logs {
+            inner: self.section(block::LOG),
+        }
+    }
+
+    /// Return an iterator over the log records of the reference named `name`, newest first.
+    pub fn logs_of(&self, name: &FullNameRef) -> Result<Logs<'_>, decode::Error> {
+        let mut key = name.as_bstr().to_vec();
+        key.push(0);
+        let mut inner = self.seek(block::LOG, &key)?;
+        inner.prefix = Some(key);
+        Ok(Logs { inner })
+    }
+}
+
+/// Block access
+impl Table {
+    fn footer_offset(&self) -> usize {
+        self.data.len() - self.header.footer_len()
+    }
+
+    pub(crate) fn block_at(&self, offset: usize) -> Result<Option<Block<'_>>, decode::Error> {
+        Block::at(
+            &self.data,
+            offset,
+            self.header.len(),
+            self.header.block_size as usize,
+            self.footer_offset(),
+        )
+    }
+
+    /// Return the offset of the first block of type `typ`, if there is such a section.
+    fn section_offset(&self, typ: u8) -> Result<Option<usize>, decode::Error> {
+        let offset = match typ {
+            block::REF => 0,
+            block::LOG => self.footer.log_offset,
+            _ => unreachable!("BUG: only refs and logs are iterated"),
+        };
+        if offset != 0 {
+            return Ok(Some(offset as usize));
+        }
+        // Without offset, the section exists only if it's the first one in the table.
+        let first_typ = self.block_at(0)?.map(|block| block.typ);
+        Ok((first_typ == Some(typ)).then_some(0))
+    }
+
+    fn section(&self, typ: u8) -> Records<'_> {
+        let mut records = Records::empty(self, typ);
+        match self.section_offset(typ) {
+            Ok(Some(offset)) => records.enter_block(offset),
+            Ok(None) => {}
+            Err(err) => records.err = Some(err),
+        }
+        records
+    }
+
+    /// Position the returned records on the first record whose key is equal to or greater than `key`.
+    fn seek(&self, typ: u8, key: &[u8]) -> Result<Records<'_>, decode::Error> {
+        let mut records = Records::empty(self, typ);
+        let Some(mut offset) = self.section_offset(typ)? else {
+            return Ok(records);
+        };
+        let index_offset = match typ {
+            block::REF => self.footer.ref_index_offset,
+            _ => self.footer.log_index_offset,
+        };
+        let object_hash = self.object_hash();
+        if index_offset != 0 {
+            // Walk down the index levels until a block of the desired type is reached.
+            let mut index_offset = index_offset as usize;
+            loop {
+                let block = self
+                    .block_at(index_offset)?
+                    .filter(|block| block.typ == block::INDEX)
+                    .ok_or_else(|| corrupt("invalid index offset"))?;
+                let (pos, mut prev_key) = block.seek(key, object_hash)?;
+                if pos >= block.records_end {
+                    // All keys are smaller than the one we look for.
+                    return Ok(records);
+                }
+                let Value::Index { position } = block.decode(pos, &mut prev_key, object_hash)?.0 else {
+                    unreachable!("index blocks contain index records")
+                };
+                let position = position as usize;
+                // Index blocks are written after the blocks they refer to, so each step must go backwards to terminate.
+                if position >= index_offset {
+                    return Err(corrupt("index points to a block that isn't before it"));
+                }
+                match self.block_at(position)?.map(|block| block.typ) {
+                    Some(block::INDEX) => index_offset = position,
+                    Some(found) if found == typ => {
+                        offset = position;
+                        break;
+                    }
+                    _ => return Err(corrupt("index points to an invalid block")),
+                }
+            }
+        } else {
+            // Skip all blocks whose successor starts with a key that isn't past the one we look for.
+            while let Some(block) = self.block_at(offset)? {
+                let next_offset = offset + block.file_len;
+                match self.block_at(next_offset)? {
+                    Some(next) if next.typ == typ => match next.first_key(object_hash)? {
+                        Some(first_key) if first_key.as_slice() <= key => offset = next_offset,
+                        _ => break,
+                    },
+                    _ => break,
+                }
+            }
+        }
+        records.enter_block(offset);
+        if let Some(block) = &records.block {
+            let (pos, prev_key) = block.seek(key, object_hash)?;
+            records.pos = pos;
+            records.key = prev_key;
+        }
+        Ok(records)
+    }
+}
+
+/// A cursor over all records of one type in a table, moving from block to block.
+pub(crate) struct Records<'a> {
+    table: &'a Table,
+    typ: u8,
+    block: Option<Block<'a>>,
+    offset: usize,
+    pos: usize,
+    key: Vec<u8>,
+    /// If set, iteration stops at the first key that doesn't start with it.
+    prefix: Option<Vec<u8>>,
+    err: Option<decode::Error>,
+}
+
+impl<'a> Records<'a> {
+    fn empty(table: &'a Table, typ: u8) -> Self {
+        Records {
+            table,
+            typ,
+            block: None,
+            offset: 0,
+            pos: 0,
+            key: Vec::new(),
+            prefix: None,
+            err: None,
+        }
+    }
+
+    /// Load the block at `offset` and position ourselves on its first record if it's of our type, or stop iteration otherwise.
+    fn enter_block(&mut self, offset: usize) {
+        self.key.clear();
+        match self.table.block_at(offset) {
+            Ok(block) => {
+                self.block = block.filter(|block| block.typ == self.typ);
+                self.offset = offset;
+                self.pos = self.block.as_ref().map_or(0, |block| block.records_start);
+            }
+            Err(err) => {
+                self.block = None;
+                self.err = Some(err);
+            }
+        }
+    }
+}
+
+impl Iterator for Records<'_> {
+    type Item = Result<(Vec<u8>, Value), decode::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(err) = self.err.take() {
+            return Some(Err(err));
+        }
+        loop {
+            let block = self.block.as_ref()?;
+            if self.pos >= block.records_end {
+                let next_offset = self.offset + block.file_len;
+                self.enter_block(next_offset);
+                if let Some(err) = self.err.take() {
+                    return Some(Err(err));
+                }
+                continue;
+            }
+            return match block.decode(self.pos, &mut self.key, self.table.object_hash()) {
+                Ok((value, next_pos)) => {
+                    if self.prefix.as_ref().is_some_and(|prefix| !self.key.starts_with(prefix)) {
+                        self.block = None;
+                        return None;
+                    }
+                    self.pos = next_pos;
+                    Some(Ok((self.key.clone(), value)))
+                }
+                Err(err) => {
+                    self.block = None;
+                    Some(Err(err))
+                }
+            };
+        }
+    }
+}
+
+/// An iterator over the reference records of a [`Table`].
+pub struct Refs<'a> {
+    inner: Records<'a>,
+}
+
+impl Iterator for Refs<'_> {
+    type Item = Result<RefRecord, decode::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let min_update_index = self.inner.table.min_update_index();
+        Some(self.inner.next()?.and_then(|(key, value)| {
+            match value {
+                Value::Ref {
+                    update_index_delta,
+                    value,
+                } => Ok(RefRecord {
+                    name: FullName(key.into()),
+                    update_index: min_update_index
+                        .checked_add(update_index_delta)
+                        .ok_or_else(|| corrupt("update index overflows"))?,
+                    value,
+                }),
+                _ => Err(corrupt("unexpected record in ref block")),
+            }
+        }))
+    }
+}
+
+/// An iterator over the log records of a [`Table`].
+pub struct Logs<'a> {
+    inner: Records<'a>,
+}
+
+impl Iterator for Logs<'_> {
+    type Item = Result<LogRecord, decode::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.inner.next()?.and_then(|(key, value)| match value {
+            Value::Log(line) => {
+                let (name, update_index) = key.split_at(key.len() - 9);
+                Ok(LogRecord {
+                    name: FullName(name.into()),
+                    update_index: !u64::from_be_bytes(update_index[1..].try_into().expect("8 bytes")),
+                    line,
+                })
+            }
+            _ => Err(corrupt("unexpected record in log block")),
+        }))
+    }
+}
+
+/// Produce the key of a log record.
+pub(crate) fn log_key(name: &BStr, update_index: u64) -> Vec<u8> {
+    let mut key = Vec::with_capacity(name.len() + 9);
+    key.extend_from_slice(name);
+    key.push(0);
+    key.extend_from_slice(&(!update_index).to_be_bytes());
+    key
+}
+
+impl std::fmt::Debug for Table {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Table")
+            .field("name", &self.name)
+            .field("header", &self.header)
+            .field("size", &self.data.len())
+            .finish_non_exhaustive()
+    }
+}
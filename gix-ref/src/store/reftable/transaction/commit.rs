@@ -0,0 +1,196 @@
+use gix_object::bstr::ByteSlice;
+
+use crate::{
+    FullName, FullNameRef, Target,
+    log::Line,
+    store::WriteReflog,
+    store_impl::reftable::{LogRecord, RefRecord, RefValue, Stack, Store, transaction::Transaction, write},
+    transaction::{Change, PreviousValue, RefEdit, RefLog, hook},
+};
+
+impl Transaction<'_> {
+    /// Make all [prepared][Transaction::prepare()] permanent by writing them into a new table on top of the stack, and return
+    /// the performed edits which represent the state of the affected refs in the ref store before the transaction.
+    /// Please note that the obtained edits may have been adjusted to contain more dependent edits or additional information.
+    /// `committer` is used in the reflog and only if the reflog is actually written, which is why it is optional.
+    ///
+    /// As all edits are written into a single table, they are either all visible or none of them is, even on error.
+    /// If [auto-compaction][Store::auto_compact] is enabled, tables will be merged afterwards, ignoring errors.
+    /// Once all edits are written, the [hook](Transaction::hook()) is called if set, or it's informed that the transaction
+    /// was aborted if the commit failed.
+    pub fn commit<'a>(
+        mut self,
+        committer: impl Into<Option<gix_actor::SignatureRef<'a>>>,
+    ) -> Result<Vec<RefEdit>, Error> {
+        let Some(mut hook) = self.hook.take() else {
+            return self.commit_inner(committer.into());
+        };
+        let prepared_edits: Vec<_> = self
+            .updates
+            .as_deref()
+            .unwrap_or_default()
+            .iter()
+            .map(|edit| edit.update.clone())
+            .collect();
+        match self.commit_inner(committer.into()) {
+            Ok(edits) => {
+                hook::call(&mut hook, hook::State::Committed, &edits).ok();
+                Ok(edits)
+            }
+            Err(err) => {
+                hook::call(&mut hook, hook::State::Aborted, &prepared_edits).ok();
+                Err(err)
+            }
+        }
+    }
+
+    fn commit_inner(mut self, committer: Option<gix_actor::SignatureRef<'_>>) -> Result<Vec<RefEdit>, Error> {
+        let updates = self.updates.take().expect("BUG: must call prepare before commit");
+        let lock = self.lock.take().expect("set during prepare");
+        let stack = self.stack.take().expect("set during prepare");
+        let store = self.store;
+
+        let update_index = stack.next_update_index();
+        let mut writer = write::Writer::new(store.object_hash, update_index, update_index, store.write_options);
+        let mut has_records = false;
+        for change in &updates {
+            assert!(!change.update.deref, "Deref mode is turned into splits and turned off");
+            let name = store.namespaced(change.update.name.as_ref());
+            match &change.update.change {
+                Change::Update { log, new, expected } => {
+                    if log.mode == RefLog::AndReference {
+                        writer.add_ref(RefRecord {
+                            name: name.clone(),
+                            update_index,
+                            value: match new {
+                                Target::Object(id) => RefValue::Object(*id),
+                                Target::Symbolic(target) => RefValue::Symbolic(target.clone()),
+                            },
+                        });
+                        has_records = true;
+                    }
+                    let log_update = match new {
+                        Target::Symbolic(_) => match expected {
+                            PreviousValue::ExistingMustMatch(Target::Object(oid)) => {
+                                Some((Some(gix_hash::ObjectId::null(oid.kind())), *oid))
+                            }
+                            _ => None,
+                        },
+                        Target::Object(new_oid) => {
+                            let previous = match expected {
+                                PreviousValue::MustExistAndMatch(Target::Object(oid)) => Some(*oid),
+                                _ => None,
+                            }
+                            .or(change.leaf_referent_previous_oid);
+                            Some((previous, *new_oid))
+                        }
+                    };
+                    let Some((previous, new_oid)) = log_update else {
+                        continue;
+                    };
+                    if previous == Some(new_oid)
+                        || !store.should_write_reflog(&stack, name.as_ref(), log.force_create_reflog)?
+                    {
+                        continue;
+                    }
+                    let committer = committer.ok_or(Error::MissingCommitter)?;
+                    writer.add_log(LogRecord {
+                        name,
+                        update_index,
+                        line: Some(Line {
+                            previous_oid: previous.unwrap_or_else(|| new_oid.kind().null()),
+                            new_oid,
+                            signature: committer.trim().to_owned()?,
+                            message: log.message.clone(),
+                        }),
+                    });
+                    has_records = true;
+                }
+                Change::Delete { log: mode, .. } => {
+                    if *mode == RefLog::AndReference {
+                        writer.add_ref(RefRecord {
+                            name: name.clone(),
+                            update_index,
+                            value: RefValue::Deletion,
+                        });
+                        has_records = true;
+                    }
+                    // Like deleting the reflog file, shadow all existing log entries.
+                    for record in stack.logs_of(name.as_ref()) {
+                        writer.add_log(LogRecord { line: None, ..record? });
+                        has_records = true;
+                    }
+                }
+            }
+        }
+
+        if has_records {
+            let table = writer.write()?;
+            let stack = stack.add_table(&store.reftable_dir(), table, lock)?;
+            store.set_stack(stack.into());
+            if store.auto_compact {
+                // Compaction is an optimization, it's not critical if it fails.
+                store.auto_compact().ok();
+            }
+        }
+        Ok(updates.into_iter().map(|edit| edit.update).collect())
+    }
+}
+
+impl Store {
+    /// Return `name` within our namespace, if there is one.
+    fn namespaced(&self, name: &FullNameRef) -> FullName {
+        match &self.namespace {
+            Some(namespace) => namespace.to_owned().into_namespaced_name(name),
+            None => name.to_owned(),
+        }
+    }
+
+    fn should_write_reflog(
+        &self,
+        stack: &Stack,
+        name: &FullNameRef,
+        force_create_reflog: bool,
+    ) -> Result<bool, crate::store_impl::reftable::table::decode::Error> {
+        Ok(match self.write_reflog {
+            WriteReflog::Disable => false,
+            WriteReflog::Always => true,
+            WriteReflog::Normal => {
+                force_create_reflog
+                    || should_autocreate_reflog(name)
+                    || stack.logs_of(name).next().transpose()?.is_some()
+            }
+        })
+    }
+}
+
+fn should_autocreate_reflog(name: &FullNameRef) -> bool {
+    let name = name.as_bstr();
+    name.starts_with_str("refs/heads/")
+        || name.starts_with_str("refs/remotes/")
+        || name.starts_with_str("refs/notes/")
+        || name.starts_with_str("refs/worktree/") // NOTE: git does not write reflogs for worktree private refs
+        || name == "HEAD"
+}
+
+mod error {
+    use crate::store_impl::reftable::{stack, table, write};
+
+    /// The error returned by [`Transaction::commit()`][super::Transaction::commit()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("reflog messages need a committer which isn't set")]
+        MissingCommitter,
+        #[error("The time of the committer could not be parsed")]
+        CommitterTime(#[from] gix_actor::date::Error),
+        #[error("The existing reflog entries of a deleted reference could not be read")]
+        Decode(#[from] table::decode::Error),
+        #[error("The table of the transaction could not be written")]
+        Write(#[from] write::Error),
+        #[error("The table of the transaction could not be added to the stack")]
+        Add(#[from] stack::add::Error),
+    }
+}
+
+pub use error::Error;
@@ -0,0 +1,99 @@
+use std::fmt::Formatter;
+
+use gix_hash::ObjectId;
+use gix_object::bstr::BString;
+
+use crate::{
+    store_impl::reftable::{Stack, Store},
+    transaction::RefEdit,
+};
+
+/// A transaction on a reftable store, which writes all of its edits into a single new table, making them atomic.
+#[must_use = "transactions must be committed to take effect"]
+pub struct Transaction<'s> {
+    store: &'s Store,
+    updates: Option<Vec<Edit>>,
+    /// The lock on `tables.list`, held from preparation until commit.
+    lock: Option<gix_lock::File>,
+    /// The stack as it was loaded while holding the lock.
+    stack: Option<Stack>,
+    hook: Option<crate::transaction::hook::Hook<'s>>,
+}
+
+#[derive(Debug)]
+pub(in crate::store_impl::reftable) struct Edit {
+    update: RefEdit,
+    /// Set if this update is coming from a symbolic reference and used to make it appear like it is the one that is handled,
+    /// instead of the referent reference.
+    parent_index: Option<usize>,
+    /// For symbolic refs, this is the previous OID to put into the reflog instead of our own previous value. It's the
+    /// peeled value of the leaf referent.
+    leaf_referent_previous_oid: Option<ObjectId>,
+}
+
+impl Edit {
+    fn name(&self) -> BString {
+        self.update.name.0.clone()
+    }
+}
+
+impl std::borrow::Borrow<RefEdit> for Edit {
+    fn borrow(&self) -> &RefEdit {
+        &self.update
+    }
+}
+
+impl std::borrow::BorrowMut<RefEdit> for Edit {
+    fn borrow_mut(&mut self) -> &mut RefEdit {
+        &mut self.update
+    }
+}
+
+/// Edits
+impl Store {
+    /// Open a transaction which will add a single table with all of its edits to the stack.
+    ///
+    /// The transaction inherits the parent namespace.
+    pub fn transaction(&self) -> Transaction<'_> {
+        Transaction {
+            store: self,
+            updates: None,
+            lock: None,
+            stack: None,
+            hook: None,
+        }
+    }
+}
+
+impl<'s> Transaction<'s> {
+    /// Call `hook` once the transaction was prepared, committed or aborted after it was prepared, similar to git's
+    /// `reference-transaction` hook. If it fails while the transaction is prepared, the transaction is aborted.
+    pub fn hook(mut self, hook: crate::transaction::hook::Hook<'s>) -> Self {
+        self.hook = Some(hook);
+        self
+    }
+}
+
+impl Drop for Transaction<'_> {
+    fn drop(&mut self) {
+        if let (Some(updates), Some(hook)) = (self.updates.as_deref(), self.hook.as_mut()) {
+            let edits = updates.iter().map(|edit| &edit.update);
+            crate::transaction::hook::call(hook, crate::transaction::hook::State::Aborted, edits).ok();
+        }
+    }
+}
+
+impl std::fmt::Debug for Transaction<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Transaction")
+            .field("store", self.store)
+            .field("edits", &self.updates.as_ref().map(Vec::len))
+            .finish_non_exhaustive()
+    }
+}
+
+///
+pub mod prepare;
+
+///
+pub mod commit;
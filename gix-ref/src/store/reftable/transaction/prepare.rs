@@ -0,0 +1,231 @@
+use crate::{
+    Target,
+    store_impl::reftable::{
+        Stack, Store,
+        transaction::{Edit, Transaction},
+    },
+    transaction::{Change, PreviousValue, RefEdit, RefEditsExt, hook},
+};
+
+impl Transaction<'_> {
+    fn check_and_apply_expectation(store: &Store, stack: &Stack, change: &mut Edit) -> Result<(), Error> {
+        let existing_ref = store.find_full_name(stack, change.update.name.as_ref())?;
+        let full_name = change.name();
+        match &mut change.update.change {
+            Change::Delete { expected, .. } => {
+                match (&expected, &existing_ref) {
+                    (PreviousValue::MustNotExist, _) => {
+                        panic!("BUG: MustNotExist constraint makes no sense if references are to be deleted")
+                    }
+                    (PreviousValue::ExistingMustMatch(_) | PreviousValue::Any, None)
+                    | (PreviousValue::MustExist | PreviousValue::Any, Some(_)) => {}
+                    (PreviousValue::MustExist | PreviousValue::MustExistAndMatch(_), None) => {
+                        return Err(Error::DeleteReferenceMustExist { full_name });
+                    }
+                    (
+                        PreviousValue::MustExistAndMatch(previous) | PreviousValue::ExistingMustMatch(previous),
+                        Some(existing),
+                    ) => {
+                        if *previous != existing.target {
+                            return Err(Error::ReferenceOutOfDate {
+                                full_name,
+                                expected: previous.clone(),
+                                actual: existing.target.clone(),
+                            });
+                        }
+                    }
+                }
+                if let Some(existing) = existing_ref {
+                    *expected = PreviousValue::MustExistAndMatch(existing.target);
+                }
+            }
+            Change::Update { expected, new, .. } => {
+                match (&expected, &existing_ref) {
+                    (PreviousValue::Any, _)
+                    | (PreviousValue::MustExist, Some(_))
+                    | (PreviousValue::MustNotExist | PreviousValue::ExistingMustMatch(_), None) => {}
+                    (PreviousValue::MustExist, None) => {
+                        return Err(Error::MustExist {
+                            full_name,
+                            expected: Target::Object(store.object_hash.null()),
+                        });
+                    }
+                    (PreviousValue::MustNotExist, Some(existing)) => {
+                        if existing.target != *new {
+                            return Err(Error::MustNotExist {
+                                full_name,
+                                actual: existing.target.clone(),
+                                new: new.clone(),
+                            });
+                        }
+                    }
+                    (
+                        PreviousValue::MustExistAndMatch(previous) | PreviousValue::ExistingMustMatch(previous),
+                        Some(existing),
+                    ) => {
+                        if *previous != existing.target {
+                            return Err(Error::ReferenceOutOfDate {
+                                full_name,
+                                expected: previous.clone(),
+                                actual: existing.target.clone(),
+                            });
+                        }
+                    }
+                    (PreviousValue::MustExistAndMatch(previous), None) => {
+                        return Err(Error::MustExist {
+                            full_name,
+                            expected: previous.clone(),
+                        });
+                    }
+                }
+                if let Some(existing) = existing_ref {
+                    *expected = PreviousValue::MustExistAndMatch(existing.target);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Prepare for calling [`commit(…)`][Transaction::commit()] by locking the stack of tables, waiting for it according
+    /// to `lock_fail_mode`, and validating all `edits` against the current values of their references.
+    ///
+    /// If the operation succeeds, the transaction can be committed or dropped to cause a rollback automatically.
+    pub fn prepare(
+        self,
+        edits: impl IntoIterator<Item = RefEdit>,
+        lock_fail_mode: gix_lock::acquire::Fail,
+    ) -> Result<Self, Error> {
+        let mut t = self.prepare_inner(&mut edits.into_iter(), lock_fail_mode)?;
+        if let Some(hook) = t.hook.as_mut() {
+            let edits = t.updates.as_deref().unwrap_or_default().iter().map(|edit| &edit.update);
+            if let Err(err) = hook::call(hook, hook::State::Prepared, edits) {
+                t.rollback();
+                return Err(Error::HookRejected(err));
+            }
+        }
+        Ok(t)
+    }
+
+    fn prepare_inner(
+        mut self,
+        edits: &mut dyn Iterator<Item = RefEdit>,
+        lock_fail_mode: gix_lock::acquire::Fail,
+    ) -> Result<Self, Error> {
+        assert!(self.updates.is_none(), "BUG: Must not call prepare(…) multiple times");
+        let store = self.store;
+        let dir = store.reftable_dir();
+        let lock = Stack::lock(&dir, lock_fail_mode)?;
+        let stack = Stack::load(&dir, store.object_hash, store.cached_stack().as_deref())?;
+
+        let mut updates: Vec<_> = edits
+            .map(|update| Edit {
+                update,
+                parent_index: None,
+                leaf_referent_previous_oid: None,
+            })
+            .collect();
+        updates
+            .pre_process(
+                &mut |name| {
+                    store
+                        .find_one_with_verified_input(&stack, name)
+                        .ok()
+                        .flatten()
+                        .map(|r| r.target)
+                },
+                &mut |idx, update| Edit {
+                    update,
+                    parent_index: Some(idx),
+                    leaf_referent_previous_oid: None,
+                },
+            )
+            .map_err(Error::PreprocessingFailed)?;
+
+        for cid in 0..updates.len() {
+            let change = &mut updates[cid];
+            Self::check_and_apply_expectation(store, &stack, change)?;
+
+            // traverse parent chain from leaf/peeled ref and set the leaf previous oid accordingly
+            // to help with their reflog entries
+            if let (Some(crate::TargetRef::Object(oid)), Some(parent_idx)) =
+                (change.update.change.previous_value(), change.parent_index)
+            {
+                let oid = oid.to_owned();
+                let mut parent_idx_cursor = Some(parent_idx);
+                while let Some(parent) = parent_idx_cursor.take().map(|idx| &mut updates[idx]) {
+                    parent_idx_cursor = parent.parent_index;
+                    parent.leaf_referent_previous_oid = Some(oid);
+                }
+            }
+        }
+        self.updates = Some(updates);
+        self.lock = Some(lock);
+        self.stack = Some(stack);
+        Ok(self)
+    }
+
+    /// Rollback all intermediate state and return the `RefEdits` as we know them thus far.
+    ///
+    /// Note that they have been altered compared to what was initially provided as they have
+    /// been split and know about their current state on disk.
+    ///
+    /// # Note
+    ///
+    /// A rollback happens automatically as this instance is dropped as well.
+    /// In both cases, the [hook](Transaction::hook()) is informed that the transaction was aborted if it was prepared.
+    pub fn rollback(mut self) -> Vec<RefEdit> {
+        let Some(updates) = self.updates.take() else {
+            return Vec::new();
+        };
+        let edits: Vec<_> = updates.into_iter().map(|u| u.update).collect();
+        if let Some(mut hook) = self.hook.take() {
+            hook::call(&mut hook, hook::State::Aborted, &edits).ok();
+        }
+        edits
+    }
+}
+
+mod error {
+    use gix_object::bstr::BString;
+
+    use crate::{
+        Target,
+        store_impl::reftable::{find, stack},
+    };
+
+    /// The error returned by [`Transaction::prepare()`][super::Transaction::prepare()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Edit preprocessing failed with an error")]
+        PreprocessingFailed(#[source] std::io::Error),
+        #[error("The stack of tables could not be locked")]
+        Lock(#[from] stack::add::Error),
+        #[error("The stack of tables could not be loaded")]
+        Load(#[from] stack::load::Error),
+        #[error("The current value of a reference could not be read")]
+        Find(#[from] find::Error),
+        #[error("The reference {full_name:?} for deletion did not exist or could not be parsed")]
+        DeleteReferenceMustExist { full_name: BString },
+        #[error(
+            "Reference {full_name:?} was not supposed to exist when writing it with value {new:?}, but actual content was {actual:?}"
+        )]
+        MustNotExist {
+            full_name: BString,
+            actual: Target,
+            new: Target,
+        },
+        #[error("Reference {full_name:?} was supposed to exist with value {expected}, but didn't.")]
+        MustExist { full_name: BString, expected: Target },
+        #[error("The reference {full_name:?} should have content {expected}, actual content was {actual}")]
+        ReferenceOutOfDate {
+            full_name: BString,
+            expected: Target,
+            actual: Target,
+        },
+        #[error("The transaction was rejected by its hook")]
+        HookRejected(#[source] crate::transaction::hook::Error),
+    }
+}
+
+pub use error::Error;
@@ -0,0 +1,30 @@
+//! The variable length integer encoding used by reftables, which is the same as the one used for offsets of `ofs-delta`
+//! objects in packs.
+
+/// Decode a varint from the beginning of `input` and return it along with the remaining bytes,
+/// or `None` if `input` is truncated or the value overflows.
+pub(crate) fn decode(input: &[u8]) -> Option<(u64, &[u8])> {
+    let (&first, mut input) = input.split_first()?;
+    let mut value = u64::from(first & 0x7f);
+    let mut byte = first;
+    while byte & 0x80 != 0 {
+        (byte, input) = input.split_first().map(|(b, rest)| (*b, rest))?;
+        value = value.checked_add(1)?.checked_mul(128)? | u64::from(byte & 0x7f);
+    }
+    Some((value, input))
+}
+
+/// Append `value` to `out` in its encoded form.
+pub(crate) fn encode(mut value: u64, out: &mut Vec<u8>) {
+    let mut buf = [0u8; 10];
+    let mut pos = buf.len() - 1;
+    buf[pos] = (value & 0x7f) as u8;
+    value >>= 7;
+    while value != 0 {
+        value -= 1;
+        pos -= 1;
+        buf[pos] = 0x80 | (value & 0x7f) as u8;
+        value >>= 7;
+    }
+    out.extend_from_slice(&buf[pos..]);
+}
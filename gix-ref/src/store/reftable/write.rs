@@ -0,0 +1,377 @@
+use std::io::Write as _;
+
+use gix_object::bstr::{BString, ByteSlice};
+
+use crate::store_impl::reftable::{
+    LogRecord, RefRecord, RefValue,
+    block::{self, HEADER_LEN},
+    table::{Footer, Header, is_sha1, log_key},
+    varint,
+};
+
+/// Options for writing tables.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Options {
+    /// The maximum size of ref and index blocks, and the size of uncompressed log blocks unless a single log record is larger.
+    pub block_size: u32,
+    /// The amount of records after which a key is written in full, to allow binary searches within a block.
+    pub restart_interval: u16,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            block_size: 4096,
+            restart_interval: 16,
+        }
+    }
+}
+
+/// The error returned by [`Writer::write()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The record for {name:?} doesn't fit into a block of {block_size} bytes")]
+    RecordTooLarge { name: BString, block_size: u32 },
+    #[error("The update index {update_index} of the record for {name:?} is outside of the table range {min}..={max}")]
+    UpdateIndexOutOfRange {
+        name: BString,
+        update_index: u64,
+        min: u64,
+        max: u64,
+    },
+    #[error("The object ids of the record for {name:?} don't use the table hash {object_hash}")]
+    ObjectHashMismatch { name: BString, object_hash: gix_hash::Kind },
+    #[error("The block size must be between 256 bytes and 16MB, got {block_size}")]
+    InvalidBlockSize { block_size: u32 },
+    #[error("Log blocks could not be compressed")]
+    Io(#[from] std::io::Error),
+}
+
+/// A utility to collect records and write them as a table.
+#[derive(Debug, Clone)]
+pub struct Writer {
+    header: Header,
+    options: Options,
+    refs: Vec<RefRecord>,
+    logs: Vec<LogRecord>,
+}
+
+impl Writer {
+    /// Create a new writer for a table whose records use `object_hash` and update indices between `min_update_index` and
+    /// `max_update_index`, inclusive.
+    pub fn new(object_hash: gix_hash::Kind, min_update_index: u64, max_update_index: u64, options: Options) -> Self {
+        Writer {
+            header: Header {
+                version: if is_sha1(object_hash) { 1 } else { 2 },
+                block_size: options.block_size,
+                min_update_index,
+                max_update_index,
+                object_hash,
+            },
+            options,
+            refs: Vec::new(),
+            logs: Vec::new(),
+        }
+    }
+
+    /// Add the reference `record`, replacing a previously added record of the same name.
+    pub fn add_ref(&mut self, record: RefRecord) -> &mut Self {
+        self.refs.push(record);
+        self
+    }
+
+    /// Add the log `record`, replacing a previously added record with the same name and update index.
+    pub fn add_log(&mut self, record: LogRecord) -> &mut Self {
+        self.logs.push(record);
+        self
+    }
+
+    /// Write all records into a new table and return its bytes.
+    pub fn write(mut self) -> Result<Vec<u8>, Error> {
+        let block_size = self.options.block_size;
+        if !(256..(1 << 24)).contains(&block_size) {
+            return Err(Error::InvalidBlockSize { block_size });
+        }
+        // Later records win, so a stable sort followed by keeping the last of each key does the trick.
+        self.refs.sort_by(|a, b| a.name.cmp(&b.name));
+        self.refs.reverse();
+        self.refs.dedup_by(|a, b| a.name == b.name);
+        self.refs.reverse();
+        let mut logs: Vec<_> = self
+            .logs
+            .into_iter()
+            .map(|record| (log_key(record.name.as_bstr(), record.update_index), record))
+            .collect();
+        logs.sort_by(|a, b| a.0.cmp(&b.0));
+        logs.reverse();
+        logs.dedup_by(|a, b| a.0 == b.0);
+        logs.reverse();
+
+        let header = self.header;
+        let mut writer = SectionWriter {
+            header,
+            options: self.options,
+            out: Vec::new(),
+        };
+        let mut value = Vec::new();
+        let mut footer = Footer::default();
+
+        let mut ref_records = Vec::with_capacity(self.refs.len());
+        for record in &self.refs {
+            value.clear();
+            let value_type = encode_ref(record, &header, &mut value)?;
+            ref_records.push((record.name.as_bstr().to_vec(), value_type, value.clone()));
+        }
+        let ref_index = writer.write_section(block::REF, ref_records)?;
+        footer.ref_index_offset = writer.write_index(ref_index)?;
+
+        let mut log_records = Vec::with_capacity(logs.len());
+        for (key, record) in &logs {
+            value.clear();
+            let value_type = encode_log(record, &header, &mut value)?;
+            log_records.push((key.clone(), value_type, value.clone()));
+        }
+        let log_offset = writer.out.len();
+        let log_index = writer.write_section(block::LOG, log_records)?;
+        if !log_index.is_empty() {
+            footer.log_offset = log_offset as u64;
+        }
+        footer.log_index_offset = writer.write_index(log_index)?;
+
+        let mut out = writer.out;
+        if out.is_empty() {
+            header.write_to(&mut out);
+        }
+        footer.write_to(&header, &mut out);
+        Ok(out)
+    }
+}
+
+fn check_update_index(name: &BString, update_index: u64, header: &Header) -> Result<(), Error> {
+    if !(header.min_update_index..=header.max_update_index).contains(&update_index) {
+        return Err(Error::UpdateIndexOutOfRange {
+            name: name.clone(),
+            update_index,
+            min: header.min_update_index,
+            max: header.max_update_index,
+        });
+    }
+    Ok(())
+}
+
+fn push_id(id: &gix_hash::oid, name: &BString, header: &Header, out: &mut Vec<u8>) -> Result<(), Error> {
+    if id.kind() != header.object_hash {
+        return Err(Error::ObjectHashMismatch {
+            name: name.clone(),
+            object_hash: header.object_hash,
+        });
+    }
+    out.extend_from_slice(id.as_bytes());
+    Ok(())
+}
+
+fn push_string(bytes: &[u8], out: &mut Vec<u8>) {
+    varint::encode(bytes.len() as u64, out);
+    out.extend_from_slice(bytes);
+}
+
+/// Encode the value of `record` into `out` and return its value type.
+fn encode_ref(record: &RefRecord, header: &Header, out: &mut Vec<u8>) -> Result<u8, Error> {
+    let name = &record.name.0;
+    check_update_index(name, record.update_index, header)?;
+    varint::encode(record.update_index - header.min_update_index, out);
+    Ok(match &record.value {
+        RefValue::Deletion => 0,
+        RefValue::Object(id) => {
+            push_id(id, name, header, out)?;
+            1
+        }
+        RefValue::Peeled { target, peeled } => {
+            push_id(target, name, header, out)?;
+            push_id(peeled, name, header, out)?;
+            2
+        }
+        RefValue::Symbolic(target) => {
+            push_string(target.as_bstr(), out);
+            3
+        }
+    })
+}
+
+/// Encode the value of `record` into `out` and return its value type.
+///
+/// Unlike references, log entries may use update indices outside of the table range, which allows deleting entries of older tables.
+fn encode_log(record: &LogRecord, header: &Header, out: &mut Vec<u8>) -> Result<u8, Error> {
+    let name = &record.name.0;
+    let Some(line) = &record.line else {
+        return Ok(0);
+    };
+    push_id(&line.previous_oid, name, header, out)?;
+    push_id(&line.new_oid, name, header, out)?;
+    push_string(&line.signature.name, out);
+    push_string(&line.signature.email, out);
+    varint::encode(line.signature.time.seconds.max(0) as u64, out);
+    let offset_in_minutes = (line.signature.time.offset / 60).clamp(i16::MIN.into(), i16::MAX.into()) as i16;
+    out.extend_from_slice(&offset_in_minutes.to_be_bytes());
+    // Messages are always stored with a trailing newline.
+    varint::encode(line.message.len() as u64 + 1, out);
+    out.extend_from_slice(line.message.as_bytes());
+    out.push(b'\n');
+    Ok(1)
+}
+
+/// Writes sequences of blocks and keeps track of the offset at which they are placed.
+struct SectionWriter {
+    header: Header,
+    options: Options,
+    /// The table written so far.
+    out: Vec<u8>,
+}
+
+impl SectionWriter {
+    /// Write all `records` as blocks of type `typ`, and return the last key and offset of each block for use in an index.
+    fn write_section(&mut self, typ: u8, records: Vec<(Vec<u8>, u8, Vec<u8>)>) -> Result<Vec<(Vec<u8>, u64)>, Error> {
+        let mut index = Vec::new();
+        let mut block: Option<BlockWriter> = None;
+        for (key, value_type, value) in records {
+            if let Some(b) = block.as_mut() {
+                if b.try_add(&key, value_type, &value, &self.options) {
+                    continue;
+                }
+            }
+            if let Some(full) = block.take() {
+                index.push(self.finish_block(full)?);
+            }
+            let mut new_block = self.new_block(typ);
+            let fits = new_block.try_add(&key, value_type, &value, &self.options);
+            if !fits {
+                return Err(Error::RecordTooLarge {
+                    name: key.into(),
+                    block_size: self.options.block_size,
+                });
+            }
+            block = Some(new_block);
+        }
+        if let Some(last) = block {
+            index.push(self.finish_block(last)?);
+        }
+        Ok(index)
+    }
+
+    /// Write index blocks for `entries` if there is more than one block to point to, and return the offset of the root index block,
+    /// or 0 if no index was written.
+    fn write_index(&mut self, mut entries: Vec<(Vec<u8>, u64)>) -> Result<u64, Error> {
+        if entries.len() <= 1 {
+            return Ok(0);
+        }
+        loop {
+            let records = entries
+                .into_iter()
+                .map(|(key, offset)| {
+                    let mut value = Vec::new();
+                    varint::encode(offset, &mut value);
+                    (key, 0, value)
+                })
+                .collect();
+            entries = self.write_section(block::INDEX, records)?;
+            if entries.len() == 1 {
+                return Ok(entries[0].1);
+            }
+        }
+    }
+
+    fn new_block(&self, typ: u8) -> BlockWriter {
+        let mut buf = Vec::with_capacity(self.options.block_size as usize);
+        // The first block of the table also contains the table header.
+        if self.out.is_empty() {
+            self.header.write_to(&mut buf);
+        }
+        let header_offset = buf.len();
+        buf.extend_from_slice(&[typ, 0, 0, 0]);
+        BlockWriter {
+            typ,
+            buf,
+            header_offset,
+            restarts: Vec::new(),
+            entries: 0,
+            last_key: Vec::new(),
+        }
+    }
+
+    fn finish_block(&mut self, block: BlockWriter) -> Result<(Vec<u8>, u64), Error> {
+        let offset = self.out.len() as u64;
+        let last_key = block.last_key.clone();
+        block.finish(&mut self.out)?;
+        Ok((last_key, offset))
+    }
+}
+
+struct BlockWriter {
+    typ: u8,
+    /// The block, starting at its beginning, which for the first block includes the table header.
+    buf: Vec<u8>,
+    /// The offset of the block header in `buf`.
+    header_offset: usize,
+    restarts: Vec<u32>,
+    entries: usize,
+    last_key: Vec<u8>,
+}
+
+impl BlockWriter {
+    /// Add the record, or return `false` if it doesn't fit into this block anymore.
+    /// The first log record is always added, even if it's larger than the block size.
+    fn try_add(&mut self, key: &[u8], value_type: u8, value: &[u8], options: &Options) -> bool {
+        let prefix_len = if self.entries % usize::from(options.restart_interval.max(1)) == 0 {
+            0
+        } else {
+            key.iter().zip(self.last_key.iter()).take_while(|(a, b)| a == b).count()
+        };
+        let is_restart = prefix_len == 0;
+
+        let record_start = self.buf.len();
+        varint::encode(prefix_len as u64, &mut self.buf);
+        varint::encode(
+            (((key.len() - prefix_len) as u64) << 3) | u64::from(value_type),
+            &mut self.buf,
+        );
+        self.buf.extend_from_slice(&key[prefix_len..]);
+        self.buf.extend_from_slice(value);
+
+        let restart_table_len = (self.restarts.len() + usize::from(is_restart)) * 3 + 2;
+        let len = self.buf.len() + restart_table_len;
+        let fits = len <= options.block_size as usize || (self.entries == 0 && self.typ == block::LOG && len < 1 << 24);
+        if !fits {
+            self.buf.truncate(record_start);
+            return false;
+        }
+        if is_restart {
+            self.restarts.push(record_start as u32);
+        }
+        self.entries += 1;
+        self.last_key.clear();
+        self.last_key.extend_from_slice(key);
+        true
+    }
+
+    /// Append the block to `out`, compressing it if it's a log block.
+    fn finish(mut self, out: &mut Vec<u8>) -> Result<(), Error> {
+        for restart in &self.restarts {
+            self.buf.extend_from_slice(&restart.to_be_bytes()[1..]);
+        }
+        self.buf.extend_from_slice(&(self.restarts.len() as u16).to_be_bytes());
+        let block_len = (self.buf.len() as u32).to_be_bytes();
+        self.buf[self.header_offset + 1..][..3].copy_from_slice(&block_len[1..]);
+
+        if self.typ == block::LOG {
+            let (uncompressed_header, records) = self.buf.split_at(self.header_offset + HEADER_LEN);
+            out.extend_from_slice(uncompressed_header);
+            let mut deflate = gix_zlib::stream::deflate::Write::new(out);
+            deflate.write_all(records)?;
+            deflate.flush()?;
+        } else {
+            out.extend_from_slice(&self.buf);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,136 @@
+//! Support for observing and vetoing transactions, similar to git's
+//! [`reference-transaction`](https://git-scm.com/docs/githooks#_reference_transaction) hook.
+//!
+//! The [`Update`] type also describes the lines that git passes to the receive-side hooks `pre-receive` and `post-receive`,
+//! and the arguments of the `update` hook, so servers can use it to drive these as well.
+use std::io::Write;
+
+use gix_object::bstr::{BString, ByteVec};
+
+use crate::{
+    FullNameRef, TargetRef,
+    transaction::{Change, LogChange, RefEdit, RefLog},
+};
+
+/// The states of a transaction a [`Hook`] is called for, in the order in which they occur.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum State {
+    /// All locks were obtained and all edits are known to be valid. Returning an error here aborts the transaction.
+    Prepared,
+    /// All edits were written successfully.
+    Committed,
+    /// The transaction was rolled back or dropped after it was prepared, possibly because the hook returned an error while it
+    /// was prepared, or committing it failed.
+    Aborted,
+}
+
+impl State {
+    /// Return the name of the state as passed as argument to the `reference-transaction` hook.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            State::Prepared => "prepared",
+            State::Committed => "committed",
+            State::Aborted => "aborted",
+        }
+    }
+}
+
+/// The error a [`Hook`] can return to reject a transaction while it's [prepared](State::Prepared).
+pub type Error = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+/// A function to be called with the [`State`] of a transaction and all of the [updates](Update) it contains.
+///
+/// Errors are only considered in the [`Prepared`](State::Prepared) state, where they abort the transaction, and are ignored otherwise.
+pub type Hook<'a> = Box<dyn FnMut(State, &[Update<'_>]) -> Result<(), Error> + 'a>;
+
+/// A change to a single reference, as seen by hooks.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Update<'a> {
+    /// The full name of the reference, including its namespace.
+    pub name: &'a FullNameRef,
+    /// The value of the reference before the change, or `None` if it didn't exist or its value wasn't checked.
+    pub previous: Option<TargetRef<'a>>,
+    /// The value of the reference after the change, or `None` if it is deleted.
+    pub new: Option<TargetRef<'a>>,
+}
+
+impl<'a> Update<'a> {
+    /// Create a new instance from `edit`, or return `None` if it only affects the reflog.
+    ///
+    /// Note that the previous value is only known once the transaction was prepared, or if it was checked by `edit`.
+    pub fn from_edit(edit: &'a RefEdit) -> Option<Self> {
+        let mode = match &edit.change {
+            Change::Update {
+                log: LogChange { mode, .. },
+                ..
+            } => *mode,
+            Change::Delete { log, .. } => *log,
+        };
+        (mode == RefLog::AndReference).then(|| Update {
+            name: edit.name.as_ref(),
+            previous: edit.change.previous_value(),
+            new: edit.change.new_value(),
+        })
+    }
+
+    /// Write this instance as line of the form `<old-value> SP <new-value> SP <ref-name> LF`, which is how git passes
+    /// updates to the `reference-transaction`, `pre-receive` and `post-receive` hooks on `stdin`.
+    ///
+    /// `object_hash` is used to write the null hash for values that are `None`.
+    pub fn write_to(&self, object_hash: gix_hash::Kind, out: &mut dyn Write) -> std::io::Result<()> {
+        out.write_all(&value(self.previous, object_hash))?;
+        out.write_all(b" ")?;
+        out.write_all(&value(self.new, object_hash))?;
+        out.write_all(b" ")?;
+        out.write_all(self.name.as_bstr())?;
+        out.write_all(b"\n")
+    }
+
+    /// Return the arguments `<ref-name> <old-value> <new-value>` as passed to the `update` hook.
+    ///
+    /// `object_hash` is used to produce the null hash for values that are `None`.
+    pub fn to_args(&self, object_hash: gix_hash::Kind) -> [BString; 3] {
+        [
+            self.name.as_bstr().to_owned(),
+            value(self.previous, object_hash),
+            value(self.new, object_hash),
+        ]
+    }
+}
+
+/// Write all `updates` in the format git uses to pass them to hooks on `stdin` into `out`.
+///
+/// See [`Update::write_to()`] for details.
+pub fn write_updates<'a>(
+    updates: impl IntoIterator<Item = &'a Update<'a>>,
+    object_hash: gix_hash::Kind,
+    out: &mut dyn Write,
+) -> std::io::Result<()> {
+    for update in updates {
+        update.write_to(object_hash, out)?;
+    }
+    Ok(())
+}
+
+/// Symbolic references are written as `ref:<target>` like git does, and missing values as the null hash.
+fn value(target: Option<TargetRef<'_>>, object_hash: gix_hash::Kind) -> BString {
+    match target {
+        Some(TargetRef::Object(id)) => id.to_string().into(),
+        Some(TargetRef::Symbolic(name)) => {
+            let mut out = BString::from("ref:");
+            out.push_str(name.as_bstr());
+            out
+        }
+        None => object_hash.null().to_string().into(),
+    }
+}
+
+/// Call `hook` with `state` and all `edits` that change references.
+pub(crate) fn call<'a>(
+    hook: &mut Hook<'_>,
+    state: State,
+    edits: impl IntoIterator<Item = &'a RefEdit>,
+) -> Result<(), Error> {
+    let updates: Vec<_> = edits.into_iter().filter_map(Update::from_edit).collect();
+    hook(state, &updates)
+}
@@ -5,6 +5,7 @@
 //!
 //! * create or update reference
 //! * delete references
+//! * let a [hook](hook::Hook) observe them, and veto them once they are prepared
 //!
 //! The following guarantees are made:
 //!
@@ -130,5 +131,8 @@ pub enum RefLog {
     Only,
 }
 
+///
+pub mod hook;
+
 mod ext;
 pub use ext::RefEditsExt;
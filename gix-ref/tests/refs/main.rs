@@ -100,5 +100,6 @@ mod partialname {
 mod namespace;
 mod packed;
 mod reference;
+mod reftable;
 mod store;
 mod transaction;
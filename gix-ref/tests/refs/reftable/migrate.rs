@@ -0,0 +1,154 @@
+use gix_object::bstr::ByteSlice;
+use gix_ref::{
+    Target,
+    store::{Backend, migrate},
+};
+
+use crate::{file::odb_at, reftable::store_options};
+
+type Snapshot = Vec<(String, Target, Vec<gix_ref::log::Line>)>;
+
+fn snapshot(store: &gix_ref::Store) -> crate::Result<Snapshot> {
+    let handle = store.to_handle();
+    let platform = handle.iter()?;
+    let mut out = Vec::new();
+    for reference in platform.pseudo()?.chain(platform.all()?) {
+        let reference = reference?;
+        let log = handle.reflog(reference.name.as_ref())?.unwrap_or_default();
+        out.push((
+            reference.name.as_bstr().to_str_lossy().into_owned(),
+            reference.target,
+            log,
+        ));
+    }
+    Ok(out)
+}
+
+fn peeled_refs(store: &gix_ref::Store) -> crate::Result<Vec<(String, gix_hash::ObjectId)>> {
+    let mut out = Vec::new();
+    for reference in store.to_handle().iter()?.all()? {
+        let reference = reference?;
+        if let Some(peeled) = reference.peeled {
+            out.push((reference.name.as_bstr().to_str_lossy().into_owned(), peeled));
+        }
+    }
+    Ok(out)
+}
+
+fn migrate(
+    store: &gix_ref::Store,
+    to: Backend,
+    objects_dir: &std::path::Path,
+) -> crate::Result<(migrate::Outcome, Vec<Backend>)> {
+    let mut configured = Vec::new();
+    let outcome = store.migrate(to, &odb_at(objects_dir)?, &mut |backend| {
+        configured.push(backend);
+        Ok(())
+    })?;
+    Ok((outcome, configured))
+}
+
+#[test]
+fn reftable_to_files_and_back() -> crate::Result {
+    let dir = crate::scripted_fixture_writable("make_reftable_repo.sh")?;
+    let git_dir = dir.path().join("reftable-clone/.git");
+    let store = gix_ref::Store::at(git_dir.clone(), Backend::Reftable, store_options())?;
+    let expected = snapshot(&store)?;
+    assert_eq!(expected.len(), 4);
+
+    let (outcome, configured) = migrate(&store, Backend::Files, &git_dir.join("objects"))?;
+    assert_eq!(
+        configured,
+        [Backend::Files],
+        "the configuration is updated exactly once"
+    );
+    assert_eq!(outcome.refs, 4);
+    assert_eq!(outcome.reflog_entries, 4, "HEAD, main and the two remote refs");
+    assert_eq!(outcome.store.backend(), Backend::Files);
+    assert_eq!(snapshot(&outcome.store)?, expected, "nothing was lost");
+    assert!(!git_dir.join("reftable").exists(), "the tables are removed");
+    assert!(git_dir.join("refs/heads").is_dir(), "the marker file was replaced");
+    assert_eq!(std::fs::read(git_dir.join("HEAD"))?, b"ref: refs/heads/main\n");
+    assert!(
+        !std::fs::read_dir(&git_dir)?
+            .any(|entry| entry.is_ok_and(|entry| entry.file_name().to_string_lossy().starts_with("ref_migration."))),
+        "temporary directories are removed"
+    );
+
+    let (outcome, configured) = migrate(&outcome.store, Backend::Reftable, &git_dir.join("objects"))?;
+    assert_eq!(configured, [Backend::Reftable]);
+    assert_eq!(outcome.store.backend(), Backend::Reftable);
+    assert_eq!(snapshot(&outcome.store)?, expected, "the round-trip is lossless");
+    assert_eq!(
+        std::fs::read(git_dir.join("refs/heads"))?,
+        b"this repository uses the reftable format\n"
+    );
+    assert_eq!(std::fs::read(git_dir.join("HEAD"))?, b"ref: refs/heads/.invalid\n");
+    assert!(!git_dir.join("logs").exists());
+    assert!(!git_dir.join("packed-refs").exists());
+    Ok(())
+}
+
+#[test]
+fn files_to_reftable_keeps_packed_and_loose_refs() -> crate::Result {
+    let dir = crate::scripted_fixture_writable("make_packed_ref_repository.sh")?;
+    let git_dir = dir.path().join(".git");
+    let store = gix_ref::Store::at(git_dir.clone(), Backend::Files, store_options())?;
+    let objects = git_dir.join("objects");
+    assert!(
+        matches!(
+            store.migrate(Backend::Reftable, &odb_at(&objects)?, &mut |_| Ok(())),
+            Err(migrate::Error::Iter(_))
+        ),
+        "unreadable references abort the migration"
+    );
+    assert!(!git_dir.join("reftable").exists(), "nothing was written");
+
+    std::fs::remove_file(git_dir.join("refs/broken"))?;
+    let expected = snapshot(&store)?;
+    let peeled = peeled_refs(&store)?;
+    assert!(!peeled.is_empty(), "annotated tags are peeled in packed-refs");
+    let (outcome, _) = migrate(&store, Backend::Reftable, &objects)?;
+    assert_eq!(outcome.refs, expected.len());
+    assert_eq!(snapshot(&outcome.store)?, expected);
+    assert_eq!(peeled_refs(&outcome.store)?, peeled, "peeled values are retained");
+    Ok(())
+}
+
+#[test]
+fn refusals() -> crate::Result {
+    let dir = crate::scripted_fixture_writable("make_reftable_repo.sh")?;
+    let git_dir = dir.path().join("reftable-clone/.git");
+    let store = gix_ref::Store::at(git_dir.clone(), Backend::Reftable, store_options())?;
+    let objects = git_dir.join("objects");
+    assert!(matches!(
+        migrate(&store, Backend::Reftable, &objects)
+            .unwrap_err()
+            .downcast_ref::<migrate::Error>(),
+        Some(migrate::Error::SameBackend {
+            backend: Backend::Reftable
+        })
+    ));
+
+    let err = store
+        .migrate(Backend::Files, &odb_at(&objects)?, &mut |_| {
+            Err("no config for you".into())
+        })
+        .unwrap_err();
+    assert!(matches!(err, migrate::Error::UpdateConfig(_)));
+    assert!(
+        git_dir.join("reftable").is_dir(),
+        "the previous backend is retained if the configuration can't be updated"
+    );
+    assert!(
+        git_dir.join("refs/heads").is_dir(),
+        "and the new one is in place as well"
+    );
+
+    std::fs::create_dir_all(git_dir.join("worktrees/wt"))?;
+    let err = store
+        .migrate(Backend::Files, &odb_at(&objects)?, &mut |_| Ok(()))
+        .unwrap_err();
+    assert!(matches!(err, migrate::Error::LinkedWorktrees));
+    Ok(())
+}
@@ -0,0 +1,22 @@
+use gix_ref::reftable;
+
+mod migrate;
+mod store;
+mod table;
+
+fn git_reftable_dir() -> crate::Result<std::path::PathBuf> {
+    Ok(crate::scripted_fixture_read_only("make_reftable_repo.sh")?.join("reftable-clone/.git/reftable"))
+}
+
+fn store_options() -> gix_ref::store::init::Options {
+    gix_ref::store::init::Options {
+        object_hash: crate::fixture_hash_kind(),
+        ..Default::default()
+    }
+}
+
+fn empty_store() -> crate::Result<(gix_testtools::tempfile::TempDir, reftable::Store)> {
+    let dir = gix_testtools::tempfile::TempDir::new()?;
+    let store = reftable::Store::at(dir.path().into(), store_options());
+    Ok((dir, store))
+}
@@ -0,0 +1,442 @@
+use gix_object::bstr::ByteSlice;
+use gix_ref::{
+    Target,
+    reftable::{self, RefValue},
+    store::Backend,
+    transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog, hook::State},
+};
+
+use crate::{
+    file::transaction::prepare_and_commit::committer,
+    hex_to_id,
+    reftable::{empty_store, store_options},
+};
+
+fn update(name: &str, expected: PreviousValue, new: Target) -> RefEdit {
+    RefEdit {
+        change: Change::Update {
+            log: LogChange {
+                mode: RefLog::AndReference,
+                force_create_reflog: false,
+                message: format!("update {name}").into(),
+            },
+            expected,
+            new,
+        },
+        name: name.try_into().expect("valid"),
+        deref: false,
+    }
+}
+
+fn delete(name: &str) -> RefEdit {
+    RefEdit {
+        change: Change::Delete {
+            expected: PreviousValue::MustExist,
+            log: RefLog::AndReference,
+        },
+        name: name.try_into().expect("valid"),
+        deref: false,
+    }
+}
+
+fn commit(store: &reftable::Store, edits: impl IntoIterator<Item = RefEdit>) -> crate::Result<Vec<RefEdit>> {
+    Ok(store
+        .transaction()
+        .prepare(edits, gix_lock::acquire::Fail::Immediately)?
+        .commit(committer().to_ref(&mut Default::default()))?)
+}
+
+fn names(
+    iter: impl Iterator<Item = Result<gix_ref::Reference, impl std::error::Error + Send + Sync + 'static>>,
+) -> crate::Result<Vec<String>> {
+    iter.map(|r| Ok(r?.name.as_bstr().to_str_lossy().into_owned()))
+        .collect()
+}
+
+#[test]
+fn backend_from_ref_storage() {
+    assert_eq!(Backend::from_ref_storage("files".into()), Some(Backend::Files));
+    assert_eq!(Backend::from_ref_storage("reftable".into()), Some(Backend::Reftable));
+    assert_eq!(Backend::from_ref_storage("other".into()), None);
+}
+
+#[test]
+fn general_store_reads_repository_written_by_git() -> crate::Result {
+    let git_dir = crate::scripted_fixture_read_only("make_reftable_repo.sh")?.join("reftable-clone/.git");
+    let store = gix_ref::Store::at(git_dir, Backend::Reftable, store_options())?;
+    assert_eq!(store.backend(), Backend::Reftable);
+    let handle = store.to_handle();
+
+    let head = handle.find("HEAD")?;
+    assert_eq!(head.target, Target::Symbolic("refs/heads/main".try_into()?));
+    let main = handle.find("main")?;
+    assert_eq!(main.name.as_bstr(), "refs/heads/main");
+    assert_eq!(
+        handle.find("origin")?.name.as_bstr(),
+        "refs/remotes/origin/HEAD",
+        "the lookup rules are the same as for loose references"
+    );
+    assert!(handle.try_find("does-not-exist")?.is_none());
+
+    let platform = handle.iter()?;
+    assert_eq!(
+        names(platform.all()?)?,
+        [
+            "refs/heads/main",
+            "refs/remotes/origin/HEAD",
+            "refs/remotes/origin/main"
+        ]
+    );
+    assert_eq!(
+        names(platform.prefixed(b"refs/remotes/".try_into()?)?)?,
+        ["refs/remotes/origin/HEAD", "refs/remotes/origin/main"]
+    );
+    assert_eq!(names(platform.pseudo()?)?, ["HEAD"]);
+
+    let main_name = main.name.as_ref();
+    assert!(handle.reflog_exists(main_name)?);
+    let log = handle.reflog(main_name)?.expect("present");
+    assert_eq!(log.len(), 1);
+    assert_eq!(log[0].new_oid, main.target.try_id().expect("peeled").to_owned());
+    assert!(handle.reflog("refs/heads/other".try_into()?)?.is_none());
+    Ok(())
+}
+
+#[test]
+fn general_store_follows_and_peels_symbolic_references() -> crate::Result {
+    let git_dir = crate::scripted_fixture_read_only("make_reftable_repo.sh")?.join("reftable-clone/.git");
+    let odb = crate::file::odb_at(git_dir.join("objects"))?;
+    let handle = gix_ref::Store::at(git_dir, Backend::Reftable, store_options())?.to_handle();
+    let main_id = handle.find("main")?.target.into_id();
+
+    let head = handle.find("HEAD")?;
+    let main = handle.follow(&head).expect("symbolic")?;
+    assert_eq!(main.name.as_bstr(), "refs/heads/main");
+    assert!(handle.follow(&main).is_none(), "there is nothing to follow");
+
+    let mut origin_head = handle.find("origin")?;
+    assert_eq!(handle.follow_to_object(&mut origin_head)?, main_id);
+    assert_eq!(
+        origin_head.name.as_bstr(),
+        "refs/remotes/origin/main",
+        "the reference is replaced with the last one in the chain"
+    );
+
+    let mut head = handle.find("HEAD")?;
+    assert_eq!(handle.peel_to_id(&mut head, &odb)?, main_id);
+    assert_eq!(head.peeled, Some(main_id));
+    Ok(())
+}
+
+#[test]
+fn transactions_update_the_repository_written_by_git() -> crate::Result {
+    let dir = crate::scripted_fixture_writable("make_reftable_repo.sh")?;
+    let git_dir = dir.path().join("reftable-clone/.git");
+    let store = gix_ref::Store::at(git_dir.clone(), Backend::Reftable, store_options())?;
+    let handle = store.to_handle();
+    let previous = handle.find("main")?.target;
+    let new_id = hex_to_id("e69de29bb2d1d6434b8b29ae775ad8c2e48c5391");
+
+    let edits = handle
+        .transaction()
+        .prepare(
+            [RefEdit {
+                deref: true,
+                ..update("HEAD", PreviousValue::MustExist, Target::Object(new_id))
+            }],
+            gix_lock::acquire::Fail::Immediately,
+        )?
+        .commit(committer().to_ref(&mut Default::default()))?;
+    assert_eq!(edits.len(), 2, "HEAD and its referent");
+    assert_eq!(
+        edits[1].change.previous_value(),
+        Some(previous.to_ref()),
+        "previous values are known"
+    );
+    assert_eq!(handle.find("main")?.target, Target::Object(new_id));
+    assert_eq!(
+        handle.find("HEAD")?.target,
+        Target::Symbolic("refs/heads/main".try_into()?),
+        "HEAD remains symbolic"
+    );
+    for name in ["HEAD", "refs/heads/main"] {
+        let log = handle.reflog(name.try_into()?)?.expect("present");
+        assert_eq!(log.len(), 2, "one entry was added to {name}");
+        let last = log.last().expect("present");
+        assert_eq!(last.previous_oid, previous.try_id().expect("object").to_owned());
+        assert_eq!(last.new_oid, new_id);
+        assert_eq!(last.message, "update HEAD");
+    }
+
+    let reftable = reftable::Store::at(git_dir, store_options());
+    let stack = reftable.stack()?;
+    assert_eq!(
+        stack.tables().len(),
+        2,
+        "the new table is much smaller than the one written by git, so there is no need to compact"
+    );
+    assert_eq!(stack.tables()[1].min_update_index(), 5);
+    Ok(())
+}
+
+#[test]
+fn create_update_and_delete() -> crate::Result {
+    let (_dir, store) = empty_store()?;
+    assert!(store.try_find("HEAD")?.is_none(), "a store without tables is empty");
+    assert_eq!(store.iter()?.all().count(), 0);
+
+    let first = hex_to_id("e69de29bb2d1d6434b8b29ae775ad8c2e48c5391");
+    let second = hex_to_id("134385f6d781b7e97062102c6a483440bfda2a03");
+    commit(
+        &store,
+        [
+            update("refs/heads/main", PreviousValue::MustNotExist, Target::Object(first)),
+            update(
+                "HEAD",
+                PreviousValue::MustNotExist,
+                Target::Symbolic("refs/heads/main".try_into()?),
+            ),
+            update("refs/tags/v1", PreviousValue::MustNotExist, Target::Object(first)),
+        ],
+    )?;
+    assert_eq!(store.find("main")?.target, Target::Object(first));
+    assert_eq!(store.find("v1")?.target, Target::Object(first));
+    assert!(store.reflog_exists("refs/heads/main")?);
+    assert!(
+        !store.reflog_exists("refs/tags/v1")?,
+        "tags don't get a reflog by default"
+    );
+    assert!(!store.reflog_exists("HEAD")?, "symbolic refs don't get a reflog");
+
+    let err = store
+        .transaction()
+        .prepare(
+            [update(
+                "refs/heads/main",
+                PreviousValue::MustExistAndMatch(Target::Object(second)),
+                Target::Object(first),
+            )],
+            gix_lock::acquire::Fail::Immediately,
+        )
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        reftable::transaction::prepare::Error::ReferenceOutOfDate { .. }
+    ));
+
+    commit(
+        &store,
+        [update(
+            "refs/heads/main",
+            PreviousValue::MustExistAndMatch(Target::Object(first)),
+            Target::Object(second),
+        )],
+    )?;
+    assert_eq!(store.find("main")?.target, Target::Object(second));
+    let log: Vec<_> = store
+        .reflog_iter("refs/heads/main".try_into()?)?
+        .expect("present")
+        .collect();
+    assert_eq!(log.len(), 2);
+    assert_eq!((log[1].previous_oid, log[1].new_oid), (first, second));
+    assert_eq!(
+        store
+            .reflog_iter_rev("refs/heads/main".try_into()?)?
+            .expect("present")
+            .next()
+            .map(|line| line.new_oid),
+        Some(second),
+        "newest first"
+    );
+
+    commit(&store, [delete("refs/heads/main")])?;
+    assert!(store.try_find("main")?.is_none());
+    assert!(
+        !store.reflog_exists("refs/heads/main")?,
+        "deleting a reference deletes its log"
+    );
+    let err = store
+        .transaction()
+        .prepare([delete("refs/heads/main")], gix_lock::acquire::Fail::Immediately)
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        reftable::transaction::prepare::Error::DeleteReferenceMustExist { .. }
+    ));
+    assert_eq!(
+        names(store.iter()?.all())?,
+        ["refs/tags/v1"],
+        "dangling symbolic refs are fine"
+    );
+    Ok(())
+}
+
+#[test]
+fn tables_are_compacted_automatically_and_on_demand() -> crate::Result {
+    let (_dir, mut store) = empty_store()?;
+    store.auto_compact = false;
+    let id = hex_to_id("e69de29bb2d1d6434b8b29ae775ad8c2e48c5391");
+    for idx in 0..8 {
+        commit(
+            &store,
+            [update(
+                &format!("refs/heads/b{idx}"),
+                PreviousValue::MustNotExist,
+                Target::Object(id),
+            )],
+        )?;
+    }
+    assert_eq!(store.stack()?.tables().len(), 8, "one table per transaction");
+    commit(&store, [delete("refs/heads/b0")])?;
+
+    assert!(store.auto_compact()?);
+    let stack = store.stack()?;
+    assert!(stack.tables().len() < 4, "{} tables left", stack.tables().len());
+    assert!(!store.auto_compact()?, "nothing left to do");
+
+    assert!(store.compact_all(gix_lock::acquire::Fail::Immediately)?);
+    let stack = store.stack()?;
+    assert_eq!(stack.tables().len(), 1);
+    let table = &stack.tables()[0];
+    assert_eq!((table.min_update_index(), table.max_update_index()), (1, 9));
+    assert!(
+        table.refs().all(|r| r.is_ok_and(|r| r.value != RefValue::Deletion)),
+        "deletions are dropped when compacting the whole stack"
+    );
+    assert_eq!(table.refs().count(), 7);
+    assert_eq!(
+        std::fs::read_dir(store.reftable_dir())?.count(),
+        2,
+        "only the compacted table and the list remain"
+    );
+
+    store.auto_compact = true;
+    for idx in 0..32 {
+        commit(
+            &store,
+            [update(
+                &format!("refs/heads/b{idx}"),
+                PreviousValue::Any,
+                Target::Object(id),
+            )],
+        )?;
+    }
+    assert!(
+        store.stack()?.tables().len() <= 6,
+        "the amount of tables stays logarithmic"
+    );
+    assert_eq!(store.iter()?.all().count(), 32);
+    Ok(())
+}
+
+#[test]
+fn namespaces_are_applied_to_reads_and_writes() -> crate::Result {
+    let (dir, _store) = empty_store()?;
+    let id = hex_to_id("e69de29bb2d1d6434b8b29ae775ad8c2e48c5391");
+    let store = gix_ref::Store::at(dir.path().into(), Backend::Reftable, store_options())?;
+    let namespaced = store.to_handle_namespaced(Some(gix_ref::namespace::expand("foo")?));
+    namespaced
+        .transaction()
+        .prepare(
+            [update(
+                "refs/heads/main",
+                PreviousValue::MustNotExist,
+                Target::Object(id),
+            )],
+            gix_lock::acquire::Fail::Immediately,
+        )?
+        .commit(committer().to_ref(&mut Default::default()))?;
+
+    assert_eq!(namespaced.find("main")?.name.as_bstr(), "refs/heads/main");
+    assert_eq!(names(namespaced.iter()?.all()?)?, ["refs/heads/main"]);
+    assert!(
+        !namespaced.reflog_exists("refs/heads/main".try_into()?)?,
+        "like git, namespaced references don't automatically get a reflog"
+    );
+
+    let plain = store.to_handle();
+    assert!(plain.try_find("main")?.is_none());
+    assert_eq!(names(plain.iter()?.all()?)?, ["refs/namespaces/foo/refs/heads/main"]);
+    Ok(())
+}
+
+#[test]
+fn hooks_observe_and_can_reject_transactions() -> crate::Result {
+    let dir = gix_testtools::tempfile::TempDir::new()?;
+    let store = gix_ref::Store::at(dir.path().into(), Backend::Reftable, store_options())?;
+    let handle = store.to_handle();
+    let id = hex_to_id("e69de29bb2d1d6434b8b29ae775ad8c2e48c5391");
+
+    let mut states = Vec::new();
+    let err = handle
+        .transaction()
+        .hook(Box::new(|state, updates| {
+            states.push(state);
+            assert_eq!(updates.len(), 1);
+            Err("not allowed".into())
+        }))
+        .prepare(
+            [update(
+                "refs/heads/main",
+                PreviousValue::MustNotExist,
+                Target::Object(id),
+            )],
+            gix_lock::acquire::Fail::Immediately,
+        )
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        gix_ref::store::transaction::prepare::Error::Reftable(reftable::transaction::prepare::Error::HookRejected(_))
+    ));
+    assert_eq!(states, [State::Prepared, State::Aborted]);
+    assert!(handle.try_find("main")?.is_none(), "nothing was written");
+
+    let states = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+    let mut with_reflog = update("refs/heads/main", PreviousValue::MustNotExist, Target::Object(id));
+    if let Change::Update { log, .. } = &mut with_reflog.change {
+        log.force_create_reflog = true;
+    }
+    let transaction = || {
+        let states = states.clone();
+        handle.transaction().hook(Box::new(move |state, _updates| {
+            states.borrow_mut().push(state);
+            Ok(())
+        }))
+    };
+    drop(transaction().prepare([with_reflog.clone()], gix_lock::acquire::Fail::Immediately)?);
+    assert!(transaction().rollback().is_empty());
+    let err = transaction()
+        .prepare([with_reflog], gix_lock::acquire::Fail::Immediately)?
+        .commit(None)
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        gix_ref::store::transaction::commit::Error::Reftable(reftable::transaction::commit::Error::MissingCommitter)
+    ));
+    assert_eq!(
+        *states.borrow(),
+        [State::Prepared, State::Aborted, State::Prepared, State::Aborted],
+        "drops and failed commits are aborted, while unprepared transactions aren't reported"
+    );
+    assert!(handle.try_find("main")?.is_none());
+
+    let mut states = Vec::new();
+    handle
+        .transaction()
+        .hook(Box::new(|state, _updates| {
+            states.push(state);
+            Ok(())
+        }))
+        .prepare(
+            [update(
+                "refs/heads/main",
+                PreviousValue::MustNotExist,
+                Target::Object(id),
+            )],
+            gix_lock::acquire::Fail::Immediately,
+        )?
+        .commit(committer().to_ref(&mut Default::default()))?;
+    assert_eq!(states, [State::Prepared, State::Committed]);
+    assert_eq!(handle.find("main")?.target, Target::Object(id));
+    Ok(())
+}
@@ -0,0 +1,330 @@
+use gix_object::bstr::ByteSlice;
+use gix_ref::{
+    FullName,
+    reftable::{LogRecord, RefRecord, RefValue, Stack, Table, write},
+};
+
+use crate::{file::transaction::prepare_and_commit::committer, hex_to_id, reftable::git_reftable_dir};
+
+fn git_stack() -> crate::Result<Stack> {
+    let stack = Stack::at(&git_reftable_dir()?, crate::fixture_hash_kind())?;
+    assert_eq!(stack.tables().len(), 1, "a clone writes a single table");
+    Ok(stack)
+}
+
+fn name(name: &str) -> FullName {
+    name.try_into().expect("valid")
+}
+
+#[test]
+fn read_refs_and_logs_written_by_git() -> crate::Result {
+    let stack = git_stack()?;
+    let table = &stack.tables()[0];
+    assert_eq!(table.object_hash(), crate::fixture_hash_kind());
+    assert_eq!(table.min_update_index(), 1);
+    assert_eq!(table.max_update_index(), 4);
+
+    let refs = table.refs().collect::<Result<Vec<_>, _>>()?;
+    let main_id = match &refs[1].value {
+        RefValue::Object(id) => *id,
+        other => unreachable!("main is a branch, got {other:?}"),
+    };
+    assert_eq!(
+        refs,
+        vec![
+            RefRecord {
+                name: name("HEAD"),
+                update_index: 3,
+                value: RefValue::Symbolic(name("refs/heads/main")),
+            },
+            RefRecord {
+                name: name("refs/heads/main"),
+                update_index: 4,
+                value: RefValue::Object(main_id),
+            },
+            RefRecord {
+                name: name("refs/remotes/origin/HEAD"),
+                update_index: 2,
+                value: RefValue::Symbolic(name("refs/remotes/origin/main")),
+            },
+            RefRecord {
+                name: name("refs/remotes/origin/main"),
+                update_index: 1,
+                value: RefValue::Object(main_id),
+            },
+        ]
+    );
+    assert_eq!(
+        table.find_ref(name("refs/remotes/origin/main").as_ref())?,
+        Some(refs[3].clone())
+    );
+    assert_eq!(table.find_ref(name("refs/heads/other").as_ref())?, None);
+
+    let logs = table.logs().collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(
+        logs.iter()
+            .map(|log| (log.name.as_bstr().to_str_lossy().into_owned(), log.update_index))
+            .collect::<Vec<_>>(),
+        [
+            ("HEAD".into(), 4),
+            ("refs/heads/main".into(), 4),
+            ("refs/remotes/origin/HEAD".into(), 2),
+            ("refs/remotes/origin/main".into(), 1),
+        ]
+    );
+    let line = logs[1].line.as_ref().expect("not deleted");
+    assert_eq!(line.previous_oid, crate::fixture_hash_kind().null());
+    assert_eq!(line.new_oid, main_id);
+    assert_eq!(line.signature.name, "committer");
+    assert_eq!(line.signature.email, "committer@example.com");
+    assert_eq!(line.signature.time.seconds, 946771200);
+    assert!(line.message.starts_with(b"clone: from "), "{}", line.message);
+    assert_eq!(
+        logs[3].line.as_ref().expect("not deleted").message,
+        "",
+        "empty messages are stored as newline, which is removed"
+    );
+    assert_eq!(
+        table
+            .logs_of(name("refs/heads/main").as_ref())?
+            .collect::<Result<Vec<_>, _>>()?,
+        vec![logs[1].clone()]
+    );
+    Ok(())
+}
+
+#[test]
+fn ref_blocks_are_written_like_git() -> crate::Result {
+    let stack = git_stack()?;
+    let table = &stack.tables()[0];
+    let mut writer = write::Writer::new(
+        table.object_hash(),
+        table.min_update_index(),
+        table.max_update_index(),
+        write::Options::default(),
+    );
+    for record in table.refs() {
+        writer.add_ref(record?);
+    }
+    let ours = writer.write()?;
+    let theirs = std::fs::read(git_reftable_dir()?.join(table.name()))?;
+    let footer_len = if table.header().version == 1 { 68 } else { 72 };
+    let refs_end = ours.len() - footer_len;
+    assert_eq!(
+        ours[..refs_end].as_bstr(),
+        theirs[..refs_end].as_bstr(),
+        "the header and the ref block are identical, log blocks differ only by compression level"
+    );
+    Ok(())
+}
+
+fn many_records(count: usize) -> (Vec<RefRecord>, Vec<LogRecord>) {
+    let id = hex_to_id("e69de29bb2d1d6434b8b29ae775ad8c2e48c5391");
+    let peeled = hex_to_id("134385f6d781b7e97062102c6a483440bfda2a03");
+    let refs: Vec<_> = (0..count)
+        .map(|idx| RefRecord {
+            name: name(&format!("refs/heads/branch-{idx:04}")),
+            update_index: 1 + (idx % 3) as u64,
+            value: match idx % 4 {
+                0 => RefValue::Object(id),
+                1 => RefValue::Peeled { target: id, peeled },
+                2 => RefValue::Symbolic(name("refs/heads/branch-0000")),
+                _ => RefValue::Deletion,
+            },
+        })
+        .collect();
+    let logs = refs
+        .iter()
+        .map(|record| LogRecord {
+            name: record.name.clone(),
+            update_index: record.update_index,
+            line: (record.update_index != 2).then(|| gix_ref::log::Line {
+                previous_oid: peeled,
+                new_oid: id,
+                signature: committer(),
+                message: format!("update {}", record.name.as_bstr()).into(),
+            }),
+        })
+        .collect();
+    (refs, logs)
+}
+
+#[test]
+fn write_and_read_many_blocks_with_indices() -> crate::Result {
+    let (refs, logs) = many_records(500);
+    let mut writer = write::Writer::new(
+        crate::fixture_hash_kind(),
+        1,
+        3,
+        write::Options {
+            block_size: 256,
+            restart_interval: 4,
+        },
+    );
+    // Records may be added in any order.
+    for record in refs.iter().rev() {
+        writer.add_ref(record.clone());
+    }
+    for record in logs.iter().rev() {
+        writer.add_log(record.clone());
+    }
+    let table = Table::from_bytes(writer.write()?)?;
+    assert!(table.size() > 256 * 50, "many blocks were written");
+
+    assert_eq!(table.refs().collect::<Result<Vec<_>, _>>()?, refs);
+    assert_eq!(
+        table.logs().collect::<Result<Vec<_>, _>>()?,
+        logs,
+        "sorted by name, newest first"
+    );
+    for record in &refs {
+        assert_eq!(table.find_ref(record.name.as_ref())?.as_ref(), Some(record));
+        assert_eq!(
+            table.logs_of(record.name.as_ref())?.collect::<Result<Vec<_>, _>>()?,
+            logs.iter()
+                .filter(|log| log.name == record.name)
+                .cloned()
+                .collect::<Vec<_>>()
+        );
+    }
+    assert_eq!(table.find_ref(name("refs/heads/branch-0000a").as_ref())?, None);
+    assert_eq!(table.find_ref(name("refs/heads/zzz").as_ref())?, None);
+    assert_eq!(table.find_ref(name("HEAD").as_ref())?, None);
+
+    let from_middle = table
+        .refs_from("refs/heads/branch-0250".into())?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(from_middle, refs[250..]);
+    Ok(())
+}
+
+#[test]
+fn write_without_records() -> crate::Result {
+    let writer = write::Writer::new(crate::fixture_hash_kind(), 5, 5, write::Options::default());
+    let table = Table::from_bytes(writer.write()?)?;
+    assert_eq!(table.refs().count(), 0);
+    assert_eq!(table.logs().count(), 0);
+    assert_eq!(table.min_update_index(), 5);
+    Ok(())
+}
+
+#[test]
+fn write_rejects_refs_outside_of_update_index_range() {
+    let mut writer = write::Writer::new(crate::fixture_hash_kind(), 2, 3, write::Options::default());
+    writer.add_ref(RefRecord {
+        name: name("HEAD"),
+        update_index: 1,
+        value: RefValue::Deletion,
+    });
+    assert!(matches!(
+        writer.write(),
+        Err(write::Error::UpdateIndexOutOfRange { update_index: 1, .. })
+    ));
+}
+
+#[test]
+fn corrupt_tables_are_detected() -> crate::Result {
+    let mut data = std::fs::read(git_reftable_dir()?.join(git_stack()?.tables()[0].name()))?;
+    let last = data.len() - 1;
+    data[last] ^= 1;
+    assert!(matches!(
+        Table::from_bytes(data),
+        Err(gix_ref::reftable::table::decode::Error::FooterChecksum)
+    ));
+    assert!(matches!(
+        Table::from_bytes(b"not a table at all, not at all".to_vec()),
+        Err(gix_ref::reftable::table::decode::Error::InvalidHeader)
+    ));
+    Ok(())
+}
+
+#[test]
+fn corrupt_blocks_are_errors_instead_of_panics() -> crate::Result {
+    let (refs, _logs) = many_records(10);
+    let mut writer = write::Writer::new(crate::fixture_hash_kind(), 1, 3, write::Options::default());
+    for record in &refs {
+        writer.add_ref(record.clone());
+    }
+    let data = writer.write()?;
+    let header_len = if data[4] == 1 { 24 } else { 28 };
+    fn is_corrupt<T>(res: Result<T, gix_ref::reftable::table::decode::Error>) -> bool {
+        matches!(res, Err(gix_ref::reftable::table::decode::Error::Corrupt { .. }))
+    }
+
+    let mut bad_restart = data.clone();
+    assert_eq!(bad_restart[header_len], b'r', "the table starts with a ref block");
+    let block_len = usize::from(bad_restart[header_len + 1]) << 16
+        | usize::from(bad_restart[header_len + 2]) << 8
+        | usize::from(bad_restart[header_len + 3]);
+    let restart_count = usize::from(u16::from_be_bytes([
+        bad_restart[block_len - 2],
+        bad_restart[block_len - 1],
+    ]));
+    let first_restart = block_len - 2 - restart_count * 3;
+    bad_restart[first_restart..][..3].copy_from_slice(&[0xff; 3]);
+    let table = Table::from_bytes(bad_restart)?;
+    assert!(is_corrupt(table.find_ref(name("refs/heads/branch-0005").as_ref())));
+
+    let mut bad_update_index = data;
+    let footer_len = bad_update_index.len() - header_len - 5 * 8 - 4;
+    for header_start in [0, footer_len] {
+        bad_update_index[header_start + 8..][..8].copy_from_slice(&(u64::MAX - 1).to_be_bytes());
+    }
+    let crc_start = bad_update_index.len() - 4;
+    let crc = gix_features::hash::crc32(&bad_update_index[footer_len..crc_start]);
+    bad_update_index[crc_start..].copy_from_slice(&crc.to_be_bytes());
+    let table = Table::from_bytes(bad_update_index)?;
+    assert!(
+        table.refs().any(is_corrupt),
+        "the update index of refs is relative to the minimum, which can't overflow"
+    );
+    Ok(())
+}
+
+#[test]
+fn self_referencing_index_blocks_are_errors_instead_of_endless_loops() -> crate::Result {
+    let mut writer = write::Writer::new(crate::fixture_hash_kind(), 1, 1, write::Options::default());
+    writer.add_ref(RefRecord {
+        name: name("refs/heads/main"),
+        update_index: 1,
+        value: RefValue::Object(hex_to_id("e69de29bb2d1d6434b8b29ae775ad8c2e48c5391")),
+    });
+    let data = writer.write()?;
+    let header_len = if data[4] == 1 { 24 } else { 28 };
+    let footer_start = data.len() - (header_len + 5 * 8 + 4);
+
+    // An index block right after the refs with a single record pointing to the index block itself.
+    let index_offset = footer_start;
+    let mut record = vec![0, (b"refs/heads/zzz".len() as u8) << 3];
+    record.extend_from_slice(b"refs/heads/zzz");
+    record.extend(varint(index_offset as u64));
+    let block_len = 4 + record.len() + 3 + 2;
+    let mut table = data[..footer_start].to_vec();
+    table.push(b'i');
+    table.extend_from_slice(&(block_len as u32).to_be_bytes()[1..]);
+    table.extend_from_slice(&record);
+    table.extend_from_slice(&[0, 0, 4, 0, 1]);
+
+    let mut footer = data[footer_start..data.len() - 4].to_vec();
+    footer[header_len..][..8].copy_from_slice(&(index_offset as u64).to_be_bytes());
+    let crc = gix_features::hash::crc32(&footer);
+    table.extend_from_slice(&footer);
+    table.extend_from_slice(&crc.to_be_bytes());
+
+    let table = Table::from_bytes(table)?;
+    assert!(matches!(
+        table.find_ref(name("refs/heads/main").as_ref()),
+        Err(gix_ref::reftable::table::decode::Error::Corrupt { .. })
+    ));
+    Ok(())
+}
+
+/// Encode `value` like reftable varints, which are big-endian with each continuation subtracting one.
+fn varint(mut value: u64) -> Vec<u8> {
+    let mut out = vec![(value & 0x7f) as u8];
+    while value >> 7 != 0 {
+        value = (value >> 7) - 1;
+        out.insert(0, 0x80 | (value & 0x7f) as u8);
+    }
+    out
+}
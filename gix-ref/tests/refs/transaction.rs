@@ -352,3 +352,203 @@ mod refedit_ext {
         }
     }
 }
+
+mod hook {
+    use std::{cell::RefCell, rc::Rc};
+
+    use gix_object::bstr::{BString, ByteSlice};
+    use gix_ref::{
+        Target,
+        transaction::{
+            Change, LogChange, PreviousValue, RefEdit, RefLog,
+            hook::{self, State, Update},
+        },
+    };
+
+    use crate::{
+        file::transaction::prepare_and_commit::{committer, create_at, empty_store},
+        hex_to_id,
+    };
+
+    type Calls = Rc<RefCell<Vec<(State, BString)>>>;
+
+    fn recording_hook<'a>(calls: &Calls, reject: Option<State>) -> hook::Hook<'a> {
+        let calls = calls.clone();
+        Box::new(move |state, updates| {
+            let mut out = Vec::new();
+            hook::write_updates(updates, crate::fixture_hash_kind(), &mut out)?;
+            calls.borrow_mut().push((state, out.into()));
+            if reject == Some(state) {
+                return Err("rejected".into());
+            }
+            Ok(())
+        })
+    }
+
+    fn null() -> String {
+        crate::fixture_hash_kind().null().to_string()
+    }
+
+    #[test]
+    fn update_formats_lines_and_args_like_git() {
+        let id = hex_to_id("e69de29bb2d1d6434b8b29ae775ad8c2e48c5391");
+        let symbolic = RefEdit {
+            change: Change::Update {
+                log: LogChange::default(),
+                expected: PreviousValue::MustExistAndMatch(Target::Object(id)),
+                new: Target::Symbolic("refs/heads/main".try_into().expect("valid")),
+            },
+            name: "HEAD".try_into().expect("valid"),
+            deref: false,
+        };
+        let update = Update::from_edit(&symbolic).expect("changes the reference");
+        let mut out = Vec::new();
+        update.write_to(crate::fixture_hash_kind(), &mut out).unwrap();
+        assert_eq!(out.as_bstr(), format!("{id} ref:refs/heads/main HEAD\n"));
+
+        let deletion = RefEdit {
+            change: Change::Delete {
+                expected: PreviousValue::Any,
+                log: RefLog::AndReference,
+            },
+            name: "refs/heads/main".try_into().expect("valid"),
+            deref: false,
+        };
+        assert_eq!(
+            Update::from_edit(&deletion)
+                .expect("changes the reference")
+                .to_args(crate::fixture_hash_kind()),
+            [BString::from("refs/heads/main"), null().into(), null().into()],
+            "unknown previous values and deletions are represented by the null hash"
+        );
+
+        let log_only = RefEdit {
+            change: Change::Delete {
+                expected: PreviousValue::Any,
+                log: RefLog::Only,
+            },
+            ..deletion
+        };
+        assert_eq!(
+            Update::from_edit(&log_only),
+            None,
+            "only reference changes are seen by hooks"
+        );
+        assert_eq!(State::Prepared.as_str(), "prepared");
+        assert_eq!(State::Committed.as_str(), "committed");
+        assert_eq!(State::Aborted.as_str(), "aborted");
+    }
+
+    #[test]
+    fn file_transactions_call_the_hook_when_prepared_and_committed() -> crate::Result {
+        let (_keep, store) = empty_store()?;
+        let calls = Calls::default();
+        store
+            .transaction()
+            .hook(recording_hook(&calls, None))
+            .prepare(
+                Some(create_at("refs/heads/main")),
+                gix_lock::acquire::Fail::Immediately,
+                gix_lock::acquire::Fail::Immediately,
+            )?
+            .commit(committer().to_ref(&mut Default::default()))?;
+
+        let line: BString = format!(
+            "{} {} refs/heads/main\n",
+            null(),
+            hex_to_id("e69de29bb2d1d6434b8b29ae775ad8c2e48c5391")
+        )
+        .into();
+        assert_eq!(
+            *calls.borrow(),
+            [(State::Prepared, line.clone()), (State::Committed, line)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn file_transactions_can_be_rejected_when_prepared() -> crate::Result {
+        let (_keep, store) = empty_store()?;
+        let calls = Calls::default();
+        let err = store
+            .transaction()
+            .hook(recording_hook(&calls, Some(State::Prepared)))
+            .prepare(
+                Some(create_at("refs/heads/main")),
+                gix_lock::acquire::Fail::Immediately,
+                gix_lock::acquire::Fail::Immediately,
+            )
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            gix_ref::file::transaction::prepare::Error::HookRejected(_)
+        ));
+        assert_eq!(
+            calls.borrow().iter().map(|(state, _)| *state).collect::<Vec<_>>(),
+            [State::Prepared, State::Aborted]
+        );
+        assert!(store.try_find("refs/heads/main")?.is_none(), "nothing was written");
+        assert!(
+            !store.git_dir().join("refs/heads/main.lock").exists(),
+            "locks were released"
+        );
+
+        calls.borrow_mut().clear();
+        store
+            .transaction()
+            .hook(recording_hook(&calls, Some(State::Committed)))
+            .prepare(
+                Some(create_at("refs/heads/main")),
+                gix_lock::acquire::Fail::Immediately,
+                gix_lock::acquire::Fail::Immediately,
+            )?
+            .rollback();
+        assert_eq!(
+            calls.borrow().iter().map(|(state, _)| *state).collect::<Vec<_>>(),
+            [State::Prepared, State::Aborted],
+            "explicit rollbacks are reported as well"
+        );
+        assert!(store.try_find("refs/heads/main")?.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn file_transactions_report_drops_and_failed_commits_as_aborted() -> crate::Result {
+        let (_keep, store) = empty_store()?;
+        let calls = Calls::default();
+        let states = || calls.borrow().iter().map(|(state, _)| *state).collect::<Vec<_>>();
+        drop(store.transaction().hook(recording_hook(&calls, None)).prepare(
+            Some(create_at("refs/heads/main")),
+            gix_lock::acquire::Fail::Immediately,
+            gix_lock::acquire::Fail::Immediately,
+        )?);
+        assert_eq!(states(), [State::Prepared, State::Aborted], "drops roll back as well");
+
+        calls.borrow_mut().clear();
+        let edits = store.transaction().hook(recording_hook(&calls, None)).rollback();
+        assert!(edits.is_empty());
+        drop(store.transaction().hook(recording_hook(&calls, None)));
+        assert_eq!(states(), [], "transactions that were never prepared are not reported");
+
+        let err = store
+            .transaction()
+            .hook(recording_hook(&calls, None))
+            .prepare(
+                Some(create_at("refs/heads/main")),
+                gix_lock::acquire::Fail::Immediately,
+                gix_lock::acquire::Fail::Immediately,
+            )?
+            .commit(None)
+            .unwrap_err();
+        assert!(
+            matches!(err, gix_ref::file::transaction::commit::Error::CreateOrUpdateRefLog(_)),
+            "the reflog needs a committer"
+        );
+        assert_eq!(
+            states(),
+            [State::Prepared, State::Aborted],
+            "failed commits are reported as aborted, instead of not at all"
+        );
+        Ok(())
+    }
+}
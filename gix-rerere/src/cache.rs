@@ -0,0 +1,210 @@
+use std::{
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use crate::{Cache, Id};
+
+/// The kind of file stored for each variant of a conflict in the [`Cache`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Image {
+    /// The [normalized](crate::normalize()) file with conflict markers.
+    Preimage,
+    /// The file after the conflicts were resolved.
+    Postimage,
+    /// A temporary file that Git uses to hold the normalized conflicts while replaying a resolution.
+    Thisimage,
+}
+
+impl Image {
+    /// Return the name of the file for the first variant.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Image::Preimage => "preimage",
+            Image::Postimage => "postimage",
+            Image::Thisimage => "thisimage",
+        }
+    }
+}
+
+/// Information about a variant of a conflict in the [`Cache`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Variant {
+    /// The id of the variant.
+    pub id: Id,
+    /// If `true`, the conflict was recorded.
+    pub has_preimage: bool,
+    /// If `true`, the resolution of the conflict was recorded.
+    pub has_postimage: bool,
+}
+
+///
+pub mod gc {
+    use std::time::Duration;
+
+    /// Configure how long entries are kept in [`Cache::gc()`](crate::Cache::gc()).
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct Options {
+        /// Remove recorded resolutions that weren't used for longer than this, or never if `None`.
+        ///
+        /// This is `gc.rerereResolved`, which defaults to 60 days.
+        pub resolved: Option<Duration>,
+        /// Remove conflicts that were never resolved and were recorded longer ago than this, or never if `None`.
+        ///
+        /// This is `gc.rerereUnresolved`, which defaults to 15 days.
+        pub unresolved: Option<Duration>,
+    }
+
+    impl Default for Options {
+        fn default() -> Self {
+            const DAY: u64 = 24 * 60 * 60;
+            Options {
+                resolved: Some(Duration::from_secs(60 * DAY)),
+                unresolved: Some(Duration::from_secs(15 * DAY)),
+            }
+        }
+    }
+
+    /// The error returned by [`Cache::gc()`](crate::Cache::gc()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not access '{}'", path.display())]
+        Io {
+            path: std::path::PathBuf,
+            source: std::io::Error,
+        },
+    }
+}
+
+/// Lifecycle
+impl Cache {
+    /// Create an instance for the `rr-cache` directory at `dir`, which doesn't have to exist.
+    pub fn at(dir: impl Into<PathBuf>) -> Self {
+        Cache { dir: dir.into() }
+    }
+}
+
+/// Access
+impl Cache {
+    /// The directory of the cache.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Return the path to the `image` of the variant identified by `id`.
+    pub fn path(&self, id: &Id, image: Image) -> PathBuf {
+        let mut path = self.dir.join(id.hash.to_string());
+        path.push(id.file_name(image));
+        path
+    }
+
+    /// Return all variants that were recorded for the conflicts with `hash`, sorted by their id.
+    pub fn variants(&self, hash: &gix_hash::oid) -> std::io::Result<Vec<Variant>> {
+        let entries = match std::fs::read_dir(self.dir.join(hash.to_string())) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err),
+        };
+        let mut variants: Vec<Variant> = Vec::new();
+        for entry in entries {
+            let name = entry?.file_name();
+            let Some(name) = name.to_str() else { continue };
+            let (image, variant) = name.split_once('.').unwrap_or((name, "0"));
+            let Ok(variant) = variant.parse::<u32>() else { continue };
+            let is_preimage = match image {
+                "preimage" => true,
+                "postimage" => false,
+                _ => continue,
+            };
+            let id = Id {
+                hash: hash.to_owned(),
+                variant,
+            };
+            let idx = match variants.iter().position(|v| v.id == id) {
+                Some(idx) => idx,
+                None => {
+                    variants.push(Variant {
+                        id,
+                        has_preimage: false,
+                        has_postimage: false,
+                    });
+                    variants.len() - 1
+                }
+            };
+            if is_preimage {
+                variants[idx].has_preimage = true;
+            } else {
+                variants[idx].has_postimage = true;
+            }
+        }
+        variants.sort_by_key(|v| v.id);
+        Ok(variants)
+    }
+
+    /// Return `true` if a resolution was recorded for the variant identified by `id`.
+    pub fn has_resolution(&self, id: &Id) -> bool {
+        self.path(id, Image::Preimage).is_file() && self.path(id, Image::Postimage).is_file()
+    }
+}
+
+/// Mutation
+impl Cache {
+    /// Remove all files of the variant identified by `id`, and the directory of its conflicts if it's empty then.
+    pub fn remove_variant(&self, id: &Id) -> std::io::Result<()> {
+        for image in [Image::Thisimage, Image::Preimage, Image::Postimage] {
+            match std::fs::remove_file(self.path(id, image)) {
+                Ok(()) => {}
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+                Err(err) => return Err(err),
+            }
+        }
+        std::fs::remove_dir(self.dir.join(id.hash.to_string())).ok();
+        Ok(())
+    }
+
+    /// Remove all variants that are older than configured in `options` relative to `now`, like `git rerere gc`, and
+    /// return their ids.
+    ///
+    /// The age of a resolved variant is determined by the time its resolution was last used, and the age of
+    /// an unresolved variant by the time its conflict was recorded.
+    pub fn gc(&self, now: SystemTime, options: gc::Options) -> Result<Vec<Id>, gc::Error> {
+        let io_err = |path: &Path| {
+            let path = path.to_owned();
+            move |source| gc::Error::Io { path, source }
+        };
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(io_err(&self.dir)(err)),
+        };
+        let is_expired = |path: &Path, max_age: Option<Duration>| -> Option<bool> {
+            let modified = path.metadata().ok()?.modified().ok()?;
+            Some(max_age.is_some_and(|max_age| now.duration_since(modified).is_ok_and(|age| age > max_age)))
+        };
+        let mut removed = Vec::new();
+        for entry in entries {
+            let entry = entry.map_err(io_err(&self.dir))?;
+            let Some(hash) = entry
+                .file_name()
+                .to_str()
+                .and_then(|name| gix_hash::ObjectId::from_hex(name.as_bytes()).ok())
+            else {
+                continue;
+            };
+            for variant in self.variants(&hash).map_err(io_err(&entry.path()))? {
+                let id = variant.id;
+                let expired = match is_expired(&self.path(&id, Image::Postimage), options.resolved) {
+                    Some(expired) => expired,
+                    None => is_expired(&self.path(&id, Image::Preimage), options.unresolved).unwrap_or(false),
+                };
+                if expired {
+                    self.remove_variant(&id).map_err(io_err(&entry.path()))?;
+                    removed.push(id);
+                }
+            }
+        }
+        removed.sort();
+        Ok(removed)
+    }
+}
@@ -0,0 +1,68 @@
+use bstr::{BStr, ByteSlice};
+
+use crate::Id;
+
+///
+pub mod decode {
+    /// The error returned by [`Id::from_bytes()`](crate::Id::from_bytes()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not decode the conflict hash in '{input}'")]
+        Hash {
+            input: bstr::BString,
+            source: gix_hash::decode::Error,
+        },
+        #[error("Could not decode the variant in '{input}'")]
+        Variant { input: bstr::BString },
+    }
+}
+
+impl Id {
+    /// Create an instance for the first variant of the conflicts with `hash`.
+    pub fn new(hash: gix_hash::ObjectId) -> Self {
+        Id { hash, variant: 0 }
+    }
+
+    /// Decode an id as written to `MERGE_RR`, i.e. the hexadecimal hash, followed by `.<variant>` unless
+    /// it's the first variant.
+    pub fn from_bytes(input: &BStr) -> Result<Self, decode::Error> {
+        let (hex, variant) = match input.find_byte(b'.') {
+            Some(pos) => (&input[..pos], Some(&input[pos + 1..])),
+            None => (input, None),
+        };
+        let hash = gix_hash::ObjectId::from_hex(hex).map_err(|source| decode::Error::Hash {
+            input: input.to_owned(),
+            source,
+        })?;
+        let variant = match variant {
+            Some(variant) => variant
+                .to_str()
+                .ok()
+                .and_then(|variant| variant.parse().ok())
+                .ok_or_else(|| decode::Error::Variant {
+                    input: input.to_owned(),
+                })?,
+            None => 0,
+        };
+        Ok(Id { hash, variant })
+    }
+
+    /// Return the name of the file for `image` of this variant, like `preimage` or `postimage.1`.
+    pub fn file_name(&self, image: crate::cache::Image) -> String {
+        match self.variant {
+            0 => image.as_str().to_owned(),
+            variant => format!("{}.{variant}", image.as_str()),
+        }
+    }
+}
+
+/// Display the id as written to `MERGE_RR`.
+impl std::fmt::Display for Id {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.variant {
+            0 => write!(f, "{}", self.hash),
+            variant => write!(f, "{}.{variant}", self.hash),
+        }
+    }
+}
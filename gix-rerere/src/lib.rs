@@ -0,0 +1,57 @@
+//! Record and reuse conflict resolutions like [`git rerere`](https://git-scm.com/docs/git-rerere) does, compatible with
+//! the files Git keeps in `$GIT_DIR/rr-cache` and `$GIT_DIR/MERGE_RR`.
+//!
+//! * [`normalize()`] turns a file with conflict markers into its *preimage* and computes the [`Id`] of its conflicts.
+//! * [`Cache`] provides access to recorded *preimages* and *postimages* in the `rr-cache` directory, and can
+//!   [remove expired entries](Cache::gc()).
+//! * [`merge_rr`] reads and writes the list of conflicted paths that are currently tracked.
+//! * [`rerere()`] records conflicts and their resolutions, and replays recorded resolutions to the worktree.
+//!
+//! ## Feature Flags
+#![cfg_attr(
+    all(doc, feature = "document-features"),
+    doc = ::document_features::document_features!()
+)]
+#![cfg_attr(all(doc, feature = "document-features"), feature(doc_cfg))]
+#![deny(missing_docs, rust_2018_idioms)]
+#![forbid(unsafe_code)]
+
+use std::path::PathBuf;
+
+use gix_hash::ObjectId;
+
+/// The identifier of a set of conflicts in a file, along with the variant of the recorded resolution.
+///
+/// The `hash` is computed from the normalized conflict hunks of a file by [`normalize()`], and is the name of a directory
+/// in the `rr-cache`. As different files may have the same conflicts but need different resolutions,
+/// each directory can hold multiple numbered variants of *preimages* and *postimages*.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Id {
+    /// The hash of all conflict hunks.
+    pub hash: ObjectId,
+    /// The variant of the recorded conflict, with `0` being the first one.
+    pub variant: u32,
+}
+
+///
+pub mod id;
+
+/// A directory like `$GIT_DIR/rr-cache` which holds recorded conflicts and their resolutions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cache {
+    dir: PathBuf,
+}
+
+///
+pub mod cache;
+
+///
+pub mod normalize;
+pub use normalize::function::normalize;
+
+///
+pub mod merge_rr;
+
+///
+pub mod rerere;
+pub use rerere::function::rerere;
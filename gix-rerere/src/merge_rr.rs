@@ -0,0 +1,73 @@
+//! Read and write `$GIT_DIR/MERGE_RR`, which associates each conflicted path with the [`Id`] of its conflicts.
+//!
+//! Each entry is written as `<id>\t<path>\0`.
+use std::path::Path;
+
+use bstr::{BString, ByteSlice};
+
+use crate::Id;
+
+/// An entry in `MERGE_RR`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// The path to the conflicted file, relative to the root of the worktree.
+    pub rela_path: BString,
+    /// The id of the conflicts in the file.
+    pub id: Id,
+}
+
+///
+pub mod read {
+    /// The error returned by [`read()`](super::read()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not read '{}'", path.display())]
+        Io {
+            path: std::path::PathBuf,
+            source: std::io::Error,
+        },
+        #[error("Entry '{entry}' is missing the tab that separates the id from the path")]
+        MissingTab { entry: bstr::BString },
+        #[error(transparent)]
+        Id(#[from] crate::id::decode::Error),
+    }
+}
+
+/// Read all entries from the `MERGE_RR` file at `path`, or return an empty list if it doesn't exist.
+pub fn read(path: &Path) -> Result<Vec<Entry>, read::Error> {
+    let data = match std::fs::read(path) {
+        Ok(data) => data,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(source) => {
+            return Err(read::Error::Io {
+                path: path.to_owned(),
+                source,
+            });
+        }
+    };
+    data.split(|b| *b == 0)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (id, rela_path) = entry
+                .split_once_str(b"\t")
+                .ok_or_else(|| read::Error::MissingTab { entry: entry.into() })?;
+            Ok(Entry {
+                rela_path: rela_path.into(),
+                id: Id::from_bytes(id.as_bstr())?,
+            })
+        })
+        .collect()
+}
+
+/// Write all `entries` to the `MERGE_RR` file at `path`, replacing it.
+pub fn write(path: &Path, entries: &[Entry]) -> std::io::Result<()> {
+    let mut out = Vec::new();
+    for entry in entries {
+        out.extend_from_slice(entry.id.to_string().as_bytes());
+        out.push(b'\t');
+        out.extend_from_slice(&entry.rela_path);
+        out.push(0);
+    }
+    std::fs::write(path, out)
+}
@@ -0,0 +1,138 @@
+/// The outcome of [`normalize()`](crate::normalize()).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The hash of all conflict hunks, or `None` if there were no conflict markers.
+    pub hash: Option<gix_hash::ObjectId>,
+    /// The input with all conflict hunks normalized, which is stored as *preimage* in the `rr-cache`.
+    ///
+    /// In each hunk, the sides are sorted, the section of the ancestor is removed and the markers have no labels.
+    pub normalized: Vec<u8>,
+}
+
+/// The error returned by [`normalize()`](crate::normalize()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Conflict markers are out of order or a conflict isn't terminated")]
+    MalformedConflict,
+    #[error(transparent)]
+    Hash(#[from] gix_hash::hasher::Error),
+}
+
+pub(super) mod function {
+    use super::{Error, Outcome};
+
+    /// Normalize all conflict hunks in `input` that are delimited by conflict markers of `marker_size`, and compute
+    /// their hash with `object_hash`, exactly like Git does to identify conflicts in its `rr-cache`.
+    ///
+    /// A conflict hunk starts with a line of `<` markers, optionally contains a line of `|` markers to start the
+    /// ancestor's section, continues with a line of `=` markers and ends with a line of `>` markers.
+    /// Conflicts nested within one side are normalized as well, and become part of that side.
+    ///
+    /// Note that Git requires the first and last marker to be followed by a label, while unlabelled markers are accepted
+    /// here as well as that's what [`gix-merge`](gix_merge) writes if no labels are set.
+    ///
+    /// The sides of each hunk are sorted so that the same conflict has the same hash no matter which side was *ours*,
+    /// and the ancestor's section and all labels are removed.
+    ///
+    /// Return an error if conflict markers are out of order or incomplete.
+    pub fn normalize(input: &[u8], marker_size: usize, object_hash: gix_hash::Kind) -> Result<Outcome, Error> {
+        let mut lines = input.split_inclusive(|b| *b == b'\n');
+        let mut hasher = gix_hash::hasher(object_hash);
+        let mut has_conflicts = false;
+        let mut normalized = Vec::with_capacity(input.len());
+        while let Some(line) = lines.next() {
+            if is_marker(line, b'<', marker_size) {
+                conflict(&mut lines, marker_size, &mut normalized, Some(&mut hasher))?;
+                has_conflicts = true;
+            } else {
+                normalized.extend_from_slice(line);
+            }
+        }
+        Ok(Outcome {
+            hash: has_conflicts.then(|| hasher.try_finalize()).transpose()?,
+            normalized,
+        })
+    }
+
+    #[derive(Copy, Clone, Eq, PartialEq)]
+    enum Section {
+        Ours,
+        Ancestor,
+        Theirs,
+    }
+
+    /// Normalize a single conflict hunk whose opening marker was already consumed from `lines` and write it to `out`.
+    /// Add it to `hasher` if it's set, which it isn't for nested conflicts.
+    fn conflict<'a>(
+        lines: &mut impl Iterator<Item = &'a [u8]>,
+        marker_size: usize,
+        out: &mut Vec<u8>,
+        hasher: Option<&mut gix_hash::Hasher>,
+    ) -> Result<(), Error> {
+        let (mut ours, mut theirs) = (Vec::new(), Vec::new());
+        let mut section = Section::Ours;
+        while let Some(line) = lines.next() {
+            if is_marker(line, b'<', marker_size) {
+                // Like Git, nested conflicts in the ancestor's section are added to their side.
+                let side = if section == Section::Ours {
+                    &mut ours
+                } else {
+                    &mut theirs
+                };
+                conflict(lines, marker_size, side, None)?;
+            } else if is_marker(line, b'|', marker_size) {
+                if section != Section::Ours {
+                    break;
+                }
+                section = Section::Ancestor;
+            } else if is_marker(line, b'=', marker_size) {
+                if section == Section::Theirs {
+                    break;
+                }
+                section = Section::Theirs;
+            } else if is_marker(line, b'>', marker_size) {
+                if section != Section::Theirs {
+                    break;
+                }
+                if ours > theirs {
+                    std::mem::swap(&mut ours, &mut theirs);
+                }
+                put_marker(out, b'<', marker_size);
+                out.extend_from_slice(&ours);
+                put_marker(out, b'=', marker_size);
+                out.extend_from_slice(&theirs);
+                put_marker(out, b'>', marker_size);
+                if let Some(hasher) = hasher {
+                    // Git hashes both sides including their terminating null-byte.
+                    hasher.update(&ours);
+                    hasher.update(&[0]);
+                    hasher.update(&theirs);
+                    hasher.update(&[0]);
+                }
+                return Ok(());
+            } else {
+                match section {
+                    Section::Ours => ours.extend_from_slice(line),
+                    Section::Ancestor => {}
+                    Section::Theirs => theirs.extend_from_slice(line),
+                }
+            }
+        }
+        Err(Error::MalformedConflict)
+    }
+
+    /// Return `true` if `line` starts with `marker_size` times `marker`, followed by whitespace.
+    fn is_marker(line: &[u8], marker: u8, marker_size: usize) -> bool {
+        if line.len() <= marker_size || line[..marker_size].iter().any(|b| *b != marker) {
+            return false;
+        }
+        let next = line[marker_size];
+        next.is_ascii_whitespace() || next == b'\x0b'
+    }
+
+    fn put_marker(out: &mut Vec<u8>, marker: u8, marker_size: usize) {
+        out.extend(std::iter::repeat_n(marker, marker_size));
+        out.push(b'\n');
+    }
+}
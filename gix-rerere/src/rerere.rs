@@ -0,0 +1,214 @@
+use bstr::BString;
+
+use crate::merge_rr;
+
+/// The outcome of [`rerere()`](crate::rerere()).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Outcome {
+    /// The entries of paths that still have conflicts, which should be written to `MERGE_RR`.
+    pub entries: Vec<merge_rr::Entry>,
+    /// The paths whose conflicts were recorded for the first time.
+    pub recorded_conflicts: Vec<BString>,
+    /// The paths whose resolution was recorded as they don't have conflict markers anymore.
+    pub recorded_resolutions: Vec<BString>,
+    /// The paths whose conflicts were resolved by replaying a recorded resolution, which was written to the worktree.
+    ///
+    /// These are still conflicted in the index, and should be added to it if `rerere.autoUpdate` is enabled.
+    pub replayed: Vec<BString>,
+}
+
+/// The error returned by [`rerere()`](crate::rerere()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not access '{}'", path.display())]
+    Io {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+    #[error(transparent)]
+    Hash(#[from] gix_hash::hasher::Error),
+}
+
+pub(super) mod function {
+    use std::path::Path;
+
+    use bstr::{BStr, BString, ByteSlice};
+    use gix_merge::blob::{Resolution, builtin_driver};
+
+    use super::{Error, Outcome};
+    use crate::{Cache, Id, cache::Image, merge_rr, normalize};
+
+    /// Record the conflicts of all `conflicted_paths` and the resolutions of previously recorded conflicts, and replay
+    /// recorded resolutions, like `git rerere` does.
+    ///
+    /// `entries` are the paths that were recorded as conflicted before, as read from `MERGE_RR`, and
+    /// `conflicted_paths` are all paths which have *our* and *their* stage in the index, relative to `worktree`.
+    /// Their files are read from and written to `worktree`, while conflicts and resolutions are stored in `cache`.
+    /// `marker_size(rela_path)` returns the size of conflict markers in the file at `rela_path`, typically `7`,
+    /// and `object_hash` is used to compute the ids of conflicts.
+    ///
+    /// For each path,
+    ///
+    /// * a resolution is recorded if it was recorded as conflicted before and has no conflict markers anymore,
+    /// * otherwise, a recorded resolution of the same conflicts is merged into the file if that works without conflicts,
+    /// * otherwise, its conflicts are recorded as a new variant.
+    ///
+    /// Files that can't be read or have malformed conflict markers are skipped.
+    pub fn rerere<'a>(
+        entries: Vec<merge_rr::Entry>,
+        conflicted_paths: impl IntoIterator<Item = &'a BStr>,
+        worktree: &Path,
+        cache: &Cache,
+        marker_size: &mut dyn FnMut(&BStr) -> usize,
+        object_hash: gix_hash::Kind,
+    ) -> Result<Outcome, Error> {
+        let mut tracked: Vec<(BString, gix_hash::ObjectId, Option<u32>)> = entries
+            .into_iter()
+            .map(|entry| (entry.rela_path, entry.id.hash, Some(entry.id.variant)))
+            .collect();
+        for rela_path in conflicted_paths {
+            if tracked.iter().any(|(path, _, _)| path == rela_path) {
+                continue;
+            }
+            let Some(content) = read(&worktree.join(gix_path::from_bstr(rela_path)))? else {
+                continue;
+            };
+            let Ok(normalize::Outcome { hash: Some(hash), .. }) =
+                crate::normalize(&content, marker_size(rela_path), object_hash)
+            else {
+                continue;
+            };
+            tracked.push((rela_path.to_owned(), hash, None));
+        }
+
+        let mut out = Outcome::default();
+        let mut buf = Vec::new();
+        for (rela_path, hash, variant) in tracked {
+            let path = worktree.join(gix_path::from_bstr(rela_path.as_bstr()));
+            let normalized = match read(&path)? {
+                Some(content) => match crate::normalize(&content, marker_size(rela_path.as_ref()), object_hash) {
+                    Ok(normalized) if normalized.hash.is_none() => {
+                        if let Some(variant) = variant {
+                            write(&cache.path(&Id { hash, variant }, Image::Postimage), &content)?;
+                            out.recorded_resolutions.push(rela_path);
+                            continue;
+                        }
+                        None
+                    }
+                    Ok(normalized) => Some(normalized.normalized),
+                    Err(_) => None,
+                },
+                None => None,
+            };
+            let Some(normalized) = normalized else {
+                if let Some(variant) = variant {
+                    out.entries.push(merge_rr::Entry {
+                        rela_path,
+                        id: Id { hash, variant },
+                    });
+                }
+                continue;
+            };
+
+            let variants = cache.variants(&hash).map_err(|source| Error::Io {
+                path: cache.dir().join(hash.to_string()),
+                source,
+            })?;
+            let mut replayed = false;
+            for candidate in variants.iter().filter(|v| v.has_preimage && v.has_postimage) {
+                let preimage_path = cache.path(&candidate.id, Image::Preimage);
+                let postimage_path = cache.path(&candidate.id, Image::Postimage);
+                let (Some(preimage), Some(postimage)) = (read(&preimage_path)?, read(&postimage_path)?) else {
+                    continue;
+                };
+                let mut input = imara_diff::InternedInput::new(&[][..], &[]);
+                let resolution = builtin_driver::text(
+                    &mut buf,
+                    &mut input,
+                    Default::default(),
+                    &normalized,
+                    &preimage,
+                    &postimage,
+                    Default::default(),
+                );
+                if resolution != Resolution::Complete {
+                    continue;
+                }
+                write(&path, &buf)?;
+                // Mark the resolution as used so it's kept during garbage collection.
+                std::fs::File::options()
+                    .append(true)
+                    .open(&postimage_path)
+                    .and_then(|file| file.set_modified(std::time::SystemTime::now()))
+                    .map_err(|source| Error::Io {
+                        path: postimage_path,
+                        source,
+                    })?;
+                if let Some(variant) = variant.filter(|variant| *variant != candidate.id.variant) {
+                    let id = Id { hash, variant };
+                    cache.remove_variant(&id).map_err(|source| Error::Io {
+                        path: cache.path(&id, Image::Preimage),
+                        source,
+                    })?;
+                }
+                replayed = true;
+                break;
+            }
+            if replayed {
+                out.replayed.push(rela_path);
+                continue;
+            }
+
+            let id = Id {
+                hash,
+                variant: variant.unwrap_or_else(|| {
+                    (0..)
+                        .take(variants.len() + 1)
+                        .find(|idx| variants.iter().all(|v| v.id.variant != *idx))
+                        .expect("there is a free variant")
+                }),
+            };
+            write(&cache.path(&id, Image::Preimage), &normalized)?;
+            let postimage_path = cache.path(&id, Image::Postimage);
+            match std::fs::remove_file(&postimage_path) {
+                Ok(()) => {}
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+                Err(source) => {
+                    return Err(Error::Io {
+                        path: postimage_path,
+                        source,
+                    });
+                }
+            }
+            if variant.is_none() {
+                out.recorded_conflicts.push(rela_path.clone());
+            }
+            out.entries.push(merge_rr::Entry { rela_path, id });
+        }
+        Ok(out)
+    }
+
+    fn read(path: &Path) -> Result<Option<Vec<u8>>, Error> {
+        match std::fs::read(path) {
+            Ok(content) => Ok(Some(content)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(source) => Err(Error::Io {
+                path: path.to_owned(),
+                source,
+            }),
+        }
+    }
+
+    /// Write `content` to `path`, creating the leading directory if needed.
+    fn write(path: &Path, content: &[u8]) -> Result<(), Error> {
+        let io_err = |source| Error::Io {
+            path: path.to_owned(),
+            source,
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(io_err)?;
+        }
+        std::fs::write(path, content).map_err(io_err)
+    }
+}
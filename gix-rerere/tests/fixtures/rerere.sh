@@ -0,0 +1,53 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+# Create a repository at `$1` where merging `topic` into `main` conflicts once in `a`, and twice in `b`.
+# The first conflict in `b` has the greater side in `main`, so its sides are swapped when normalized.
+function conflicting_branches() {
+  git init -q "$1"
+  (cd "$1"
+    git checkout -q -b main
+    seq 1 9 > a
+    seq 1 12 > b
+    git add . && git commit -q -m "base"
+
+    git checkout -q -b topic
+    sed -i.bak 's/^5$/five on topic/' a
+    sed -i.bak -e 's/^1$/a on topic/' -e 's/^12$/twelve on topic/' b
+    rm *.bak
+    git commit -q -am "topic"
+
+    git checkout -q main
+    sed -i.bak 's/^5$/five on main/' a
+    sed -i.bak -e 's/^1$/z on main/' -e 's/^12$/twelve on main/' b
+    rm *.bak
+    git commit -q -am "main"
+  )
+}
+
+# Git recorded the conflicts with the `diff3` conflict style, but there is no resolution yet.
+conflicting_branches conflicted
+(cd conflicted
+  git -c rerere.enabled=true -c merge.conflictStyle=diff3 merge topic >/dev/null || :
+)
+
+# Git recorded the conflicts and their resolutions, and the merge is conflicted again without `rerere`.
+# The resolutions are stored in `resolved-a` and `resolved-b` next to the repository.
+conflicting_branches resolved
+(cd resolved
+  git -c rerere.enabled=true merge topic >/dev/null || :
+  seq 1 9 | sed 's/^5$/five on both/' > a
+  seq 1 12 | sed -e 's/^1$/a and z/' -e 's/^12$/twelve on both/' > b
+  cp a ../resolved-a
+  cp b ../resolved-b
+  git add a b
+  git -c rerere.enabled=true commit -q --no-edit
+  git reset -q --hard HEAD~1
+  git -c rerere.enabled=false merge topic >/dev/null || :
+)
+
+# The merge is conflicted, but nothing was recorded.
+conflicting_branches unrecorded
+(cd unrecorded
+  git -c rerere.enabled=false merge topic >/dev/null || :
+)
@@ -0,0 +1,97 @@
+use std::time::{Duration, SystemTime};
+
+use gix_rerere::{
+    Cache,
+    cache::{Image, Variant, gc},
+};
+
+use crate::{git_merge_rr, writable_fixture_dir};
+
+const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+fn set_mtime(path: &std::path::Path, time: SystemTime) -> std::io::Result<()> {
+    std::fs::File::options().append(true).open(path)?.set_modified(time)
+}
+
+#[test]
+fn variants() -> crate::Result {
+    let dir = writable_fixture_dir()?;
+    let resolved = Cache::at(dir.path().join("resolved/.git/rr-cache"));
+    let conflicted = Cache::at(dir.path().join("conflicted/.git/rr-cache"));
+    for entry in git_merge_rr("conflicted")? {
+        assert_eq!(
+            resolved.variants(&entry.id.hash)?,
+            [Variant {
+                id: entry.id,
+                has_preimage: true,
+                has_postimage: true,
+            }]
+        );
+        assert!(resolved.has_resolution(&entry.id));
+        assert!(!conflicted.has_resolution(&entry.id));
+    }
+    assert!(
+        Cache::at(dir.path().join("unrecorded/.git/rr-cache"))
+            .variants(&gix_testtools::object_hash().null())?
+            .is_empty(),
+        "a missing cache has no variants"
+    );
+    Ok(())
+}
+
+#[test]
+fn remove_variant() -> crate::Result {
+    let dir = writable_fixture_dir()?;
+    let cache = Cache::at(dir.path().join("resolved/.git/rr-cache"));
+    let id = git_merge_rr("conflicted")?[0].id;
+    cache.remove_variant(&id)?;
+    assert!(cache.variants(&id.hash)?.is_empty());
+    assert!(
+        !cache.dir().join(id.hash.to_string()).exists(),
+        "empty directories are removed"
+    );
+    cache.remove_variant(&id)?;
+    Ok(())
+}
+
+#[test]
+fn gc() -> crate::Result {
+    let dir = writable_fixture_dir()?;
+    let now = SystemTime::now();
+    let entries = git_merge_rr("conflicted")?;
+    let (a, b) = (entries[0].id, entries[1].id);
+
+    let resolved = Cache::at(dir.path().join("resolved/.git/rr-cache"));
+    assert!(
+        resolved.gc(now, gc::Options::default())?.is_empty(),
+        "nothing is old enough"
+    );
+
+    set_mtime(&resolved.path(&a, Image::Preimage), now - 100 * DAY)?;
+    set_mtime(&resolved.path(&b, Image::Postimage), now - 61 * DAY)?;
+    assert_eq!(
+        resolved.gc(now, gc::Options::default())?,
+        [b],
+        "resolved variants expire when their resolution wasn't used for too long"
+    );
+    assert!(resolved.has_resolution(&a));
+
+    let conflicted = Cache::at(dir.path().join("conflicted/.git/rr-cache"));
+    set_mtime(&conflicted.path(&a, Image::Preimage), now - 16 * DAY)?;
+    let never = gc::Options {
+        resolved: None,
+        unresolved: None,
+    };
+    assert!(conflicted.gc(now, never)?.is_empty());
+    assert_eq!(
+        conflicted.gc(now, gc::Options::default())?,
+        [a],
+        "unresolved variants expire when they were recorded too long ago"
+    );
+    let now = gc::Options {
+        resolved: Some(Duration::ZERO),
+        unresolved: Some(Duration::ZERO),
+    };
+    assert_eq!(conflicted.gc(SystemTime::now() + DAY, now)?, [b]);
+    Ok(())
+}
@@ -0,0 +1,25 @@
+use std::path::PathBuf;
+
+pub use gix_testtools::Result;
+
+/// The directory with the repositories `conflicted`, `resolved` and `unrecorded`, all in the middle of a conflicting merge.
+pub fn fixture_dir() -> Result<PathBuf> {
+    gix_testtools::scripted_fixture_read_only("rerere.sh")
+}
+
+/// A writable copy of the directory of [`fixture_dir()`].
+pub fn writable_fixture_dir() -> Result<gix_testtools::tempfile::TempDir> {
+    gix_testtools::scripted_fixture_writable("rerere.sh")
+}
+
+/// The entries of `MERGE_RR` in the repository `name`, as written by Git.
+pub fn git_merge_rr(name: &str) -> Result<Vec<gix_rerere::merge_rr::Entry>> {
+    Ok(gix_rerere::merge_rr::read(
+        &fixture_dir()?.join(name).join(".git").join("MERGE_RR"),
+    )?)
+}
+
+mod cache;
+mod merge_rr;
+mod normalize;
+mod rerere;
@@ -0,0 +1,48 @@
+use gix_rerere::merge_rr;
+
+use crate::fixture_dir;
+
+#[test]
+fn round_trip() -> crate::Result {
+    let path = fixture_dir()?.join("conflicted/.git/MERGE_RR");
+    let entries = merge_rr::read(&path)?;
+    assert_eq!(
+        entries.iter().map(|e| e.rela_path.to_string()).collect::<Vec<_>>(),
+        ["a", "b"]
+    );
+
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let copy = tmp.path().join("MERGE_RR");
+    merge_rr::write(&copy, &entries)?;
+    assert_eq!(
+        std::fs::read(&copy)?,
+        std::fs::read(&path)?,
+        "the file is written like Git does"
+    );
+    assert_eq!(merge_rr::read(&copy)?, entries);
+    Ok(())
+}
+
+#[test]
+fn missing_file_has_no_entries() -> crate::Result {
+    let entries = merge_rr::read(&fixture_dir()?.join("unrecorded/.git/MERGE_RR"))?;
+    assert!(entries.is_empty());
+    Ok(())
+}
+
+#[test]
+fn variants_are_decoded() -> crate::Result {
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let path = tmp.path().join("MERGE_RR");
+    let hex = gix_testtools::object_hash().null().to_string();
+    std::fs::write(&path, format!("{hex}.2\tdir/file\0{hex}\tother\0"))?;
+    let entries = merge_rr::read(&path)?;
+    assert_eq!(entries[0].id.variant, 2);
+    assert_eq!(entries[0].rela_path, "dir/file");
+    assert_eq!(entries[1].id.variant, 0);
+    assert_eq!(entries[1].id.to_string(), hex);
+
+    std::fs::write(&path, format!("{hex}.x\tfile\0"))?;
+    assert!(matches!(merge_rr::read(&path), Err(merge_rr::read::Error::Id(_))));
+    Ok(())
+}
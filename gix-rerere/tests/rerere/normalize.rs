@@ -0,0 +1,100 @@
+use gix_rerere::{cache::Image, normalize};
+
+use crate::{fixture_dir, git_merge_rr};
+
+#[test]
+fn hash_and_preimage_match_git() -> crate::Result {
+    let dir = fixture_dir()?;
+    let entries = git_merge_rr("conflicted")?;
+    assert_eq!(entries.len(), 2, "both files are conflicted");
+    let cache = gix_rerere::Cache::at(dir.join("conflicted/.git/rr-cache"));
+    for entry in entries {
+        let expected_preimage = std::fs::read(cache.path(&entry.id, Image::Preimage))?;
+        for repo in ["conflicted", "resolved", "unrecorded"] {
+            let content = std::fs::read(dir.join(repo).join(entry.rela_path.to_string()))?;
+            let outcome = normalize(&content, 7, gix_testtools::object_hash())?;
+            assert_eq!(
+                outcome.hash,
+                Some(entry.id.hash),
+                "{repo}/{}: the ancestor and the labels don't affect the hash",
+                entry.rela_path
+            );
+            assert_eq!(outcome.normalized, expected_preimage);
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn sides_are_sorted() -> crate::Result {
+    let outcome = normalize(
+        b"<<<<<<< ours\nb\n=======\na\n>>>>>>> theirs\n",
+        7,
+        gix_testtools::object_hash(),
+    )?;
+    let swapped = normalize(
+        b"<<<<<<< ours\na\n=======\nb\n>>>>>>> theirs\n",
+        7,
+        gix_testtools::object_hash(),
+    )?;
+    assert_eq!(outcome, swapped);
+    assert_eq!(outcome.normalized, b"<<<<<<<\na\n=======\nb\n>>>>>>>\n");
+    Ok(())
+}
+
+#[test]
+fn without_conflicts() -> crate::Result {
+    let input = b"a\n=======\n>>>>>>>\n<<< not a marker\n<<<<<<<<\n";
+    let outcome = normalize(input, 7, gix_testtools::object_hash())?;
+    assert_eq!(outcome.hash, None, "a conflict must start with an opening marker");
+    assert_eq!(outcome.normalized, input);
+
+    let input = b"<<<<<<< ours\na\n=======\nb\n>>>>>>> theirs\n";
+    let outcome = normalize(input, 3, gix_testtools::object_hash())?;
+    assert_eq!(outcome.hash, None, "markers must have the configured size");
+    assert_eq!(outcome.normalized, input);
+    Ok(())
+}
+
+#[test]
+fn unlabelled_markers() -> crate::Result {
+    let labelled = normalize(
+        b"<<<<<<< ours\na\n=======\nb\n>>>>>>> theirs\n",
+        7,
+        gix_testtools::object_hash(),
+    )?;
+    let unlabelled = normalize(b"<<<<<<<\na\n=======\nb\n>>>>>>>\n", 7, gix_testtools::object_hash())?;
+    assert_eq!(labelled, unlabelled, "as written by gix-merge without labels");
+    Ok(())
+}
+
+#[test]
+fn nested_conflicts_are_part_of_their_side() -> crate::Result {
+    let outcome = normalize(
+        b"<<<<<<< ours\n<<<<<<< inner\nc\n=======\nb\n>>>>>>> inner\n=======\na\n>>>>>>> theirs\n",
+        7,
+        gix_testtools::object_hash(),
+    )?;
+    assert_eq!(
+        outcome.normalized, b"<<<<<<<\n<<<<<<<\nb\n=======\nc\n>>>>>>>\n=======\na\n>>>>>>>\n",
+        "nested conflicts are normalized, and sorted as part of their side"
+    );
+    Ok(())
+}
+
+#[test]
+fn malformed_conflicts() {
+    for input in [
+        &b"<<<<<<< ours\na\n=======\nb\n"[..],
+        b"<<<<<<< ours\na\n>>>>>>> theirs\n",
+        b"<<<<<<< ours\na\n=======\nb\n||||||| base\n>>>>>>> theirs\n",
+    ] {
+        assert!(
+            matches!(
+                normalize(input, 7, gix_testtools::object_hash()),
+                Err(normalize::Error::MalformedConflict)
+            ),
+            "{input:?}"
+        );
+    }
+}
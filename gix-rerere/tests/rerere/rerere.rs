@@ -0,0 +1,109 @@
+use bstr::{BStr, ByteSlice};
+use gix_rerere::{Cache, cache::Image, merge_rr, rerere};
+
+use crate::{git_merge_rr, writable_fixture_dir};
+
+fn paths() -> [&'static BStr; 2] {
+    ["a".into(), "b".into()]
+}
+
+fn marker_size(_rela_path: &BStr) -> usize {
+    7
+}
+
+#[test]
+fn record_conflicts_and_resolutions() -> crate::Result {
+    let dir = writable_fixture_dir()?;
+    let worktree = dir.path().join("unrecorded");
+    let cache = Cache::at(worktree.join(".git/rr-cache"));
+    let object_hash = gix_testtools::object_hash();
+
+    let out = rerere(Vec::new(), paths(), &worktree, &cache, &mut marker_size, object_hash)?;
+    assert_eq!(out.recorded_conflicts, paths());
+    assert!(out.recorded_resolutions.is_empty());
+    assert!(out.replayed.is_empty());
+    let expected = git_merge_rr("conflicted")?;
+    assert_eq!(out.entries, expected, "conflicts are recorded like Git does");
+    for entry in &expected {
+        assert_eq!(
+            std::fs::read(cache.path(&entry.id, Image::Preimage))?,
+            std::fs::read(
+                dir.path()
+                    .join("conflicted/.git/rr-cache")
+                    .join(entry.id.hash.to_string())
+                    .join("preimage")
+            )?
+        );
+    }
+
+    let out = rerere(out.entries, paths(), &worktree, &cache, &mut marker_size, object_hash)?;
+    assert!(out.recorded_conflicts.is_empty(), "nothing changed");
+    assert_eq!(out.entries, expected);
+
+    std::fs::copy(dir.path().join("resolved-a"), worktree.join("a"))?;
+    let out = rerere(out.entries, paths(), &worktree, &cache, &mut marker_size, object_hash)?;
+    assert_eq!(out.recorded_resolutions, ["a"]);
+    assert_eq!(out.entries, expected[1..], "`b` is still conflicted");
+    assert_eq!(
+        std::fs::read(cache.path(&expected[0].id, Image::Postimage))?,
+        std::fs::read(dir.path().join("resolved-a"))?
+    );
+    Ok(())
+}
+
+#[test]
+fn replay_recorded_resolutions() -> crate::Result {
+    let dir = writable_fixture_dir()?;
+    let worktree = dir.path().join("resolved");
+    let cache = Cache::at(worktree.join(".git/rr-cache"));
+    assert!(
+        std::fs::read(worktree.join("a"))?.contains_str("<<<<<<<"),
+        "the file is conflicted"
+    );
+
+    let out = rerere(
+        Vec::new(),
+        paths(),
+        &worktree,
+        &cache,
+        &mut marker_size,
+        gix_testtools::object_hash(),
+    )?;
+    assert_eq!(out.replayed, paths());
+    assert!(out.entries.is_empty(), "nothing has to be resolved anymore");
+    assert!(out.recorded_conflicts.is_empty());
+    for path in ["a", "b"] {
+        assert_eq!(
+            std::fs::read(worktree.join(path))?,
+            std::fs::read(dir.path().join(format!("resolved-{path}")))?,
+            "the recorded resolution was applied, even though the sides of the first conflict in `b` were swapped"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn replay_into_similar_conflicts() -> crate::Result {
+    let dir = writable_fixture_dir()?;
+    let worktree = dir.path().join("resolved");
+    let cache = Cache::at(worktree.join(".git/rr-cache"));
+    let a = worktree.join("a");
+    let content = std::fs::read(&a)?;
+    std::fs::write(&a, [b"0\n".as_slice(), &content].concat())?;
+
+    let out = rerere(
+        merge_rr::read(&worktree.join(".git/MERGE_RR"))?,
+        ["a".into()],
+        &worktree,
+        &cache,
+        &mut marker_size,
+        gix_testtools::object_hash(),
+    )?;
+    assert_eq!(out.replayed, ["a"]);
+    assert_eq!(
+        std::fs::read(&a)?,
+        [b"0\n".as_slice(), &std::fs::read(dir.path().join("resolved-a"))?].concat(),
+        "changes outside of the conflicts are kept"
+    );
+    Ok(())
+}
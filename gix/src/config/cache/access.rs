@@ -24,7 +24,7 @@ impl Cache {
 
     #[cfg(feature = "blob-diff")]
     pub(crate) fn diff_algorithm(&self) -> Result<gix_diff::blob::Algorithm, config::diff::algorithm::Error> {
-        use crate::config::{cache::util::ApplyLeniencyDefault, diff::algorithm::Error, tree::Diff};
+        use crate::config::{cache::util::ApplyLeniencyDefault, tree::Diff};
         self.diff_algorithm
             .get_or_try_init(|| {
                 let name = self
@@ -33,15 +33,66 @@ impl Cache {
                     .unwrap_or_else(|| Cow::Borrowed("myers".into()));
                 config::tree::Diff::ALGORITHM
                     .try_into_algorithm(name)
-                    .or_else(|err| match err {
-                        Error::Unimplemented { .. } if self.lenient_config => Ok(gix_diff::blob::Algorithm::Histogram),
-                        err => Err(err),
-                    })
                     .with_lenient_default(self.lenient_config)
             })
             .copied()
     }
 
+    #[cfg(feature = "blob-diff")]
+    pub(crate) fn diff_context_size(
+        &self,
+    ) -> Result<gix_diff::blob::unified_diff::ContextSize, config::unsigned_integer::Error> {
+        use crate::config::tree::Diff;
+        let lines = |key: &'static config::tree::keys::UnsignedInteger, default: u32| {
+            self.resolved
+                .integer(key)
+                .map(|value| key.try_into_usize(value))
+                .transpose()
+                .with_leniency(self.lenient_config)
+                .map(|value| value.map_or(default, |value| u32::try_from(value).unwrap_or(u32::MAX)))
+        };
+        Ok(
+            gix_diff::blob::unified_diff::ContextSize::symmetrical(lines(&Diff::CONTEXT, 3)?)
+                .with_inter_hunk_lines(lines(&Diff::INTER_HUNK_CONTEXT, 0)?),
+        )
+    }
+
+    /// Return the interpolated paths of all `blame.ignoreRevsFile` values in trusted configuration, in order.
+    ///
+    /// Like in `git`, an empty value clears all previously configured files.
+    #[cfg(feature = "blame")]
+    pub(crate) fn blame_ignore_revs_files(&self) -> Result<Vec<PathBuf>, gix_config::path::interpolate::Error> {
+        let Some(values) = self.resolved.strings_filter(
+            config::tree::Blame::IGNORE_REVS_FILE,
+            &mut self.filter_config_section.clone(),
+        ) else {
+            return Ok(Vec::new());
+        };
+
+        let install_dir = crate::path::install_dir().ok();
+        let home = home_dir(self.environment);
+        let ctx = config::cache::interpolate_context(install_dir.as_deref(), home.as_deref());
+        let mut paths = Vec::new();
+        for value in values {
+            if value.is_empty() {
+                paths.clear();
+                continue;
+            }
+            paths.push(gix_config::Path::from(value).interpolate(ctx)?.into_owned());
+        }
+        Ok(paths)
+    }
+
+    /// Return `(blame.markIgnoredLines, blame.markUnblamableLines)`.
+    #[cfg(feature = "blame")]
+    pub(crate) fn blame_mark_lines(&self) -> Result<(bool, bool), boolean::Error> {
+        use config::tree::Blame;
+        Ok((
+            boolean(self, "blame.markIgnoredLines", &Blame::MARK_IGNORED_LINES, false)?,
+            boolean(self, "blame.markUnblamableLines", &Blame::MARK_UNBLAMABLE_LINES, false)?,
+        ))
+    }
+
     #[cfg(feature = "blob-diff")]
     pub(crate) fn diff_drivers(&self) -> Result<Vec<gix_diff::blob::Driver>, config::diff::drivers::Error> {
         use crate::config::cache::util::ApplyLeniencyDefault;
@@ -84,15 +135,18 @@ impl Cache {
             if let Some(textconv) = section.value(config::tree::Diff::DRIVER_TEXTCONV.name) {
                 driver.binary_to_text_command = textconv.into_owned().into();
             }
+            if let Some(word_regex) = section.value(config::tree::Diff::DRIVER_WORD_REGEX.name) {
+                driver.word_regex = word_regex.into_owned().into();
+            }
+            if let Some(funcname) = section.value(config::tree::Diff::DRIVER_FUNCNAME.name) {
+                driver.funcname = Some(gix_diff::blob::funcname::Pattern::basic(funcname.into_owned()));
+            }
+            if let Some(xfuncname) = section.value(config::tree::Diff::DRIVER_XFUNCNAME.name) {
+                driver.funcname = Some(gix_diff::blob::funcname::Pattern::extended(xfuncname.into_owned()));
+            }
             if let Some(algorithm) = section.value("algorithm") {
                 driver.algorithm = config::tree::Diff::DRIVER_ALGORITHM
                     .try_into_algorithm(algorithm)
-                    .or_else(|err| match err {
-                        config::diff::algorithm::Error::Unimplemented { .. } if self.lenient_config => {
-                            Ok(gix_diff::blob::Algorithm::Histogram)
-                        }
-                        err => Err(err),
-                    })
                     .with_lenient_default(self.lenient_config)
                     .map_err(|err| config::diff::drivers::Error {
                         name: driver.name.clone(),
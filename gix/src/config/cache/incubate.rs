@@ -15,6 +15,7 @@ pub(crate) struct StageOne {
     pub is_bare: Option<bool>,
     pub lossy: bool,
     pub object_hash: gix_hash::Kind,
+    pub ref_storage: gix_ref::store::Backend,
     pub reflog: Option<gix_ref::store::WriteReflog>,
     pub precompose_unicode: bool,
     pub protect_windows: bool,
@@ -53,6 +54,12 @@ impl StageOne {
             (version, _) => return Err(Error::UnsupportedRepositoryFormatVersion { version }),
         };
 
+        let ref_storage = config
+            .string(Extensions::REF_STORAGE)
+            .map(|value| Extensions::REF_STORAGE.try_into_ref_storage(value))
+            .transpose()?
+            .unwrap_or_default();
+
         let extension_worktree = util::config_bool(
             &config,
             &Extensions::WORKTREE_CONFIG,
@@ -95,6 +102,7 @@ impl StageOne {
             is_bare,
             lossy,
             object_hash,
+            ref_storage,
             reflog,
             precompose_unicode,
             protect_windows,
@@ -156,8 +156,6 @@ pub mod diff {
         pub enum Error {
             #[error("Unknown diff algorithm named '{name}'")]
             Unknown { name: BString },
-            #[error("The '{name}' algorithm is not yet implemented")]
-            Unimplemented { name: BString },
         }
     }
 
@@ -18,6 +18,9 @@ pub(crate) mod root {
     impl Tree {
         /// The `author` section.
         pub const AUTHOR: sections::Author = sections::Author;
+        /// The `blame` section.
+        #[cfg(feature = "blame")]
+        pub const BLAME: sections::Blame = sections::Blame;
         /// The `branch` section.
         pub const BRANCH: sections::Branch = sections::Branch;
         /// The `checkout` section.
@@ -37,6 +40,8 @@ pub(crate) mod root {
         pub const EXTENSIONS: sections::Extensions = sections::Extensions;
         /// The `fetch` section.
         pub const FETCH: sections::Fetch = sections::Fetch;
+        /// The `gc` section.
+        pub const GC: sections::Gc = sections::Gc;
         /// The `gitoxide` section.
         pub const GITOXIDE: sections::Gitoxide = sections::Gitoxide;
         /// The `http` section.
@@ -57,6 +62,8 @@ pub(crate) mod root {
         pub const PUSH: sections::Push = sections::Push;
         /// The `remote` section.
         pub const REMOTE: sections::Remote = sections::Remote;
+        /// The `rerere` section.
+        pub const RERERE: sections::Rerere = sections::Rerere;
         /// The `safe` section.
         pub const SAFE: sections::Safe = sections::Safe;
         /// The `ssh` section.
@@ -73,6 +80,8 @@ pub(crate) mod root {
         pub fn sections(&self) -> &[&dyn Section] {
             &[
                 &Self::AUTHOR,
+                #[cfg(feature = "blame")]
+                &Self::BLAME,
                 &Self::BRANCH,
                 &Self::CHECKOUT,
                 &Self::CLONE,
@@ -83,6 +92,7 @@ pub(crate) mod root {
                 &Self::DIFF,
                 &Self::EXTENSIONS,
                 &Self::FETCH,
+                &Self::GC,
                 &Self::GITOXIDE,
                 &Self::HTTP,
                 &Self::INDEX,
@@ -93,6 +103,7 @@ pub(crate) mod root {
                 &Self::PROTOCOL,
                 &Self::PUSH,
                 &Self::REMOTE,
+                &Self::RERERE,
                 &Self::SAFE,
                 &Self::SSH,
                 #[cfg(feature = "status")]
@@ -105,10 +116,12 @@ pub(crate) mod root {
 }
 
 mod sections;
+#[cfg(feature = "blame")]
+pub use sections::Blame;
 pub use sections::{
-    Author, Branch, Checkout, Clone, Committer, Core, Credential, Extensions, Fetch, Gitoxide, Http, Index, Init,
-    Mailmap, Merge, Pack, Protocol, Push, Remote, Safe, Ssh, Url, User, branch, checkout, core, credential, extensions,
-    fetch, gitoxide, http, index, protocol, push, remote, ssh,
+    Author, Branch, Checkout, Clone, Committer, Core, Credential, Extensions, Fetch, Gc, Gitoxide, Http, Index, Init,
+    Mailmap, Merge, Pack, Protocol, Push, Remote, Rerere, Safe, Ssh, Url, User, branch, checkout, core, credential,
+    extensions, fetch, gc, gitoxide, http, index, protocol, push, remote, ssh,
 };
 #[cfg(feature = "blob-diff")]
 pub use sections::{Diff, diff};
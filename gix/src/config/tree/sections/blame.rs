@@ -0,0 +1,27 @@
+use crate::config::{
+    Tree,
+    tree::{Blame, Key, Section, keys},
+};
+
+impl Blame {
+    /// The `blame.ignoreRevsFile` key
+    pub const IGNORE_REVS_FILE: keys::Path = keys::Path::new_path("ignoreRevsFile", &Tree::BLAME);
+    /// The `blame.markIgnoredLines` key
+    pub const MARK_IGNORED_LINES: keys::Boolean = keys::Boolean::new_boolean("markIgnoredLines", &Tree::BLAME);
+    /// The `blame.markUnblamableLines` key
+    pub const MARK_UNBLAMABLE_LINES: keys::Boolean = keys::Boolean::new_boolean("markUnblamableLines", &Tree::BLAME);
+}
+
+impl Section for Blame {
+    fn name(&self) -> &str {
+        "blame"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[
+            &Self::IGNORE_REVS_FILE,
+            &Self::MARK_IGNORED_LINES,
+            &Self::MARK_UNBLAMABLE_LINES,
+        ]
+    }
+}
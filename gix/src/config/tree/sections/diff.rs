@@ -5,8 +5,9 @@ use crate::{
 
 impl Diff {
     /// The `diff.algorithm` key.
-    pub const ALGORITHM: Algorithm = Algorithm::new_with_validate("algorithm", &config::Tree::DIFF, validate::Algorithm)
-                                        .with_deviation("'patience' diff is not implemented and can default to 'histogram' if lenient config is used, and defaults to histogram if unset for fastest and best results");
+    pub const ALGORITHM: Algorithm =
+        Algorithm::new_with_validate("algorithm", &config::Tree::DIFF, validate::Algorithm)
+            .with_deviation("defaults to histogram if unset for fastest and best results");
     /// The `diff.renameLimit` key.
     pub const RENAME_LIMIT: keys::UnsignedInteger = keys::UnsignedInteger::new_unsigned_integer(
         "renameLimit",
@@ -16,11 +17,21 @@ impl Diff {
         "The limit is actually squared, so 1000 stands for up to 1 million diffs if fuzzy rename tracking is enabled",
     );
 
+    /// The `diff.context` key.
+    pub const CONTEXT: keys::UnsignedInteger =
+        keys::UnsignedInteger::new_unsigned_integer("context", &config::Tree::DIFF);
+    /// The `diff.interHunkContext` key.
+    pub const INTER_HUNK_CONTEXT: keys::UnsignedInteger =
+        keys::UnsignedInteger::new_unsigned_integer("interHunkContext", &config::Tree::DIFF);
+
     /// The `diff.ignoreSubmodules` key.
     pub const IGNORE_SUBMODULES: Ignore =
         Ignore::new_with_validate("ignoreSubmodules", &config::Tree::DIFF, validate::Ignore)
             .with_note("This setting affects only the submodule status, and thus the repository status in general.");
 
+    /// The `diff.wordRegex` key.
+    pub const WORD_REGEX: keys::String = keys::String::new_string("wordRegex", &config::Tree::DIFF);
+
     /// The `diff.renames` key.
     pub const RENAMES: Renames = Renames::new_renames("renames", &config::Tree::DIFF);
 
@@ -37,6 +48,16 @@ impl Diff {
     /// The `diff.<driver>.binary` key.
     pub const DRIVER_BINARY: Binary = Binary::new_with_validate("binary", &config::Tree::DIFF, validate::Binary)
         .with_subsection_requirement(Some(SubSectionRequirement::Parameter("driver")));
+    /// The `diff.<driver>.wordRegex` key.
+    pub const DRIVER_WORD_REGEX: keys::String = keys::String::new_string("wordRegex", &config::Tree::DIFF)
+        .with_subsection_requirement(Some(SubSectionRequirement::Parameter("driver")));
+    /// The `diff.<driver>.xfuncname` key.
+    pub const DRIVER_XFUNCNAME: keys::String = keys::String::new_string("xfuncname", &config::Tree::DIFF)
+        .with_subsection_requirement(Some(SubSectionRequirement::Parameter("driver")));
+    /// The `diff.<driver>.funcname` key.
+    pub const DRIVER_FUNCNAME: keys::String = keys::String::new_string("funcname", &config::Tree::DIFF)
+        .with_subsection_requirement(Some(SubSectionRequirement::Parameter("driver")))
+        .with_note("If `xfuncname` is set in the same section, it takes precedence");
 
     /// The `diff.external` key.
     pub const EXTERNAL: keys::Program =
@@ -51,13 +72,19 @@ impl Section for Diff {
     fn keys(&self) -> &[&dyn Key] {
         &[
             &Self::ALGORITHM,
+            &Self::CONTEXT,
+            &Self::INTER_HUNK_CONTEXT,
             &Self::IGNORE_SUBMODULES,
             &Self::RENAME_LIMIT,
             &Self::RENAMES,
+            &Self::WORD_REGEX,
             &Self::DRIVER_COMMAND,
             &Self::DRIVER_TEXTCONV,
             &Self::DRIVER_ALGORITHM,
             &Self::DRIVER_BINARY,
+            &Self::DRIVER_WORD_REGEX,
+            &Self::DRIVER_XFUNCNAME,
+            &Self::DRIVER_FUNCNAME,
             &Self::EXTERNAL,
         ]
     }
@@ -80,7 +107,6 @@ mod algorithm {
 
     use crate::{
         bstr::BStr,
-        config,
         config::{
             diff::algorithm,
             key,
@@ -109,9 +135,7 @@ mod algorithm {
             } else if name.eq_ignore_ascii_case(b"histogram") {
                 gix_diff::blob::Algorithm::Histogram
             } else if name.eq_ignore_ascii_case(b"patience") {
-                return Err(config::diff::algorithm::Error::Unimplemented {
-                    name: name.into_owned(),
-                });
+                gix_diff::blob::Algorithm::Patience
             } else {
                 return Err(algorithm::Error::Unknown {
                     name: name.into_owned(),
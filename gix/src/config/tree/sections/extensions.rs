@@ -6,13 +6,22 @@ use crate::{
 impl Extensions {
     /// The `extensions.worktreeConfig` key.
     pub const WORKTREE_CONFIG: keys::Boolean = keys::Boolean::new_boolean("worktreeConfig", &config::Tree::EXTENSIONS);
+    /// The `extensions.partialClone` key, naming the remote to lazily fetch missing objects from.
+    pub const PARTIAL_CLONE: keys::RemoteName =
+        keys::RemoteName::new_remote_name("partialClone", &config::Tree::EXTENSIONS);
     /// The `extensions.objectFormat` key.
     pub const OBJECT_FORMAT: ObjectFormat =
         ObjectFormat::new_with_validate("objectFormat", &config::Tree::EXTENSIONS, validate::ObjectFormat).with_note(
             "Support for SHA256 is prepared but not fully implemented yet. For now we abort when encountered",
         );
+    /// The `extensions.refStorage` key.
+    pub const REF_STORAGE: RefStorage =
+        RefStorage::new_with_validate("refStorage", &config::Tree::EXTENSIONS, validate::RefStorage);
 }
 
+/// The `extensions.refStorage` key.
+pub type RefStorage = keys::Any<validate::RefStorage>;
+
 /// The `core.checkStat` key.
 pub type ObjectFormat = keys::Any<validate::ObjectFormat>;
 
@@ -41,13 +50,35 @@ mod object_format {
     }
 }
 
+mod ref_storage {
+    use std::borrow::Cow;
+
+    use crate::{bstr::BStr, config, config::tree::sections::extensions::RefStorage};
+
+    impl RefStorage {
+        /// Convert `value` into the backend used to store references.
+        pub fn try_into_ref_storage(
+            &'static self,
+            value: Cow<'_, BStr>,
+        ) -> Result<gix_ref::store::Backend, config::key::GenericErrorWithValue> {
+            gix_ref::store::Backend::from_ref_storage(value.as_ref())
+                .ok_or_else(|| config::key::GenericErrorWithValue::from_value(self, value.into_owned()))
+        }
+    }
+}
+
 impl Section for Extensions {
     fn name(&self) -> &str {
         "extensions"
     }
 
     fn keys(&self) -> &[&dyn Key] {
-        &[&Self::OBJECT_FORMAT, &Self::WORKTREE_CONFIG]
+        &[
+            &Self::OBJECT_FORMAT,
+            &Self::PARTIAL_CLONE,
+            &Self::REF_STORAGE,
+            &Self::WORKTREE_CONFIG,
+        ]
     }
 }
 
@@ -63,4 +94,14 @@ mod validate {
             Ok(())
         }
     }
+
+    #[derive(Clone, Copy)]
+    pub struct RefStorage;
+
+    impl keys::Validate for RefStorage {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            super::Extensions::REF_STORAGE.try_into_ref_storage(value.into())?;
+            Ok(())
+        }
+    }
 }
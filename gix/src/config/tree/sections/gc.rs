@@ -0,0 +1,71 @@
+use crate::{
+    config,
+    config::tree::{Gc, Key, Section},
+};
+
+impl Gc {
+    /// The `gc.rerereResolved` key.
+    pub const RERERE_RESOLVED: ExpiryInDays =
+        ExpiryInDays::new_with_validate("rerereResolved", &config::Tree::GC, validate::ExpiryInDays);
+    /// The `gc.rerereUnresolved` key.
+    pub const RERERE_UNRESOLVED: ExpiryInDays =
+        ExpiryInDays::new_with_validate("rerereUnresolved", &config::Tree::GC, validate::ExpiryInDays);
+}
+
+impl Section for Gc {
+    fn name(&self) -> &str {
+        "gc"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[&Self::RERERE_RESOLVED, &Self::RERERE_UNRESOLVED]
+    }
+}
+
+/// A key for an expiry time in days, like `gc.rerereResolved`.
+pub type ExpiryInDays = config::tree::keys::Any<validate::ExpiryInDays>;
+
+mod expiry_in_days {
+    use std::{borrow::Cow, time::Duration};
+
+    use crate::{bstr::BStr, config, config::tree::sections::gc::ExpiryInDays};
+
+    impl ExpiryInDays {
+        /// Convert `value`, a number of days, `now` or `never`, into the time after which entries expire,
+        /// or `None` if they never expire.
+        pub fn try_into_expiry(
+            &'static self,
+            value: Cow<'_, BStr>,
+        ) -> Result<Option<Duration>, config::key::GenericErrorWithValue> {
+            const DAY: u64 = 24 * 60 * 60;
+            if value.as_ref() == "never" {
+                return Ok(None);
+            }
+            if value.as_ref() == "now" {
+                return Ok(Some(Duration::ZERO));
+            }
+            std::str::from_utf8(value.as_ref())
+                .ok()
+                .and_then(|days| days.trim().parse::<u64>().ok())
+                .and_then(|days| days.checked_mul(DAY))
+                .map(|secs| Some(Duration::from_secs(secs)))
+                .ok_or_else(|| config::key::GenericErrorWithValue::from_value(self, value.into_owned()))
+        }
+    }
+}
+
+mod validate {
+    use crate::{
+        bstr::BStr,
+        config::tree::{Gc, keys},
+    };
+
+    #[derive(Clone, Copy)]
+    pub struct ExpiryInDays;
+    impl keys::Validate for ExpiryInDays {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            Gc::RERERE_RESOLVED.try_into_expiry(value.into())?;
+            Ok(())
+        }
+    }
+}
@@ -6,6 +6,13 @@
 pub struct Author;
 mod author;
 
+/// The `blame` top-level section.
+#[derive(Copy, Clone, Default)]
+#[cfg(feature = "blame")]
+pub struct Blame;
+#[cfg(feature = "blame")]
+mod blame;
+
 /// The `branch` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct Branch;
@@ -53,6 +60,11 @@ pub mod extensions;
 pub struct Fetch;
 pub mod fetch;
 
+/// The `gc` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct Gc;
+pub mod gc;
+
 /// The `gitoxide` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct Gitoxide;
@@ -101,6 +113,11 @@ pub mod push;
 pub struct Remote;
 pub mod remote;
 
+/// The `rerere` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct Rerere;
+mod rerere;
+
 /// The `safe` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct Safe;
@@ -0,0 +1,22 @@
+use crate::{
+    config,
+    config::tree::{Key, Rerere, Section, keys},
+};
+
+impl Rerere {
+    /// The `rerere.enabled` key.
+    pub const ENABLED: keys::Boolean = keys::Boolean::new_boolean("enabled", &config::Tree::RERERE)
+        .with_note("If unset, it's enabled if the `rr-cache` directory exists");
+    /// The `rerere.autoUpdate` key.
+    pub const AUTO_UPDATE: keys::Boolean = keys::Boolean::new_boolean("autoUpdate", &config::Tree::RERERE);
+}
+
+impl Section for Rerere {
+    fn name(&self) -> &str {
+        "rerere"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[&Self::ENABLED, &Self::AUTO_UPDATE]
+    }
+}
@@ -109,10 +109,14 @@
 // This also means that their major version changes affect our major version, but that's alright as we directly expose their
 // APIs/instances anyway.
 pub use gix_actor as actor;
+#[cfg(feature = "apply")]
+pub use gix_apply as apply;
 #[cfg(feature = "attributes")]
 pub use gix_attributes as attrs;
 #[cfg(feature = "blame")]
 pub use gix_blame as blame;
+#[cfg(feature = "blocking-network-client")]
+pub use gix_bundle as bundle;
 #[cfg(feature = "command")]
 pub use gix_command as command;
 pub use gix_commitgraph as commitgraph;
@@ -149,6 +153,8 @@ pub use gix_prompt as prompt;
 pub use gix_protocol as protocol;
 pub use gix_ref as refs;
 pub use gix_refspec as refspec;
+#[cfg(feature = "merge")]
+pub use gix_rerere as rerere;
 pub use gix_revwalk as revwalk;
 pub use gix_sec as sec;
 pub use gix_tempfile as tempfile;
@@ -411,6 +417,10 @@ pub mod state;
 #[cfg(feature = "status")]
 pub mod status;
 
+/// Types for stashing changes to the index and the worktree, compatible with `git stash`.
+#[cfg(all(feature = "merge", feature = "worktree-mutation", feature = "status"))]
+pub mod stash;
+
 ///
 pub mod shallow;
 
@@ -28,7 +28,8 @@ pub mod commit {
         pub tree_merge: crate::merge::tree::Outcome<'a>,
         /// The tree id of the base commit we used. This is either…
         /// * the single merge-base we found
-        /// * the first of multiple merge-bases if [Options::with_use_first_merge_base()] was `true`.
+        /// * the first of multiple merge-bases if [Options::with_use_first_merge_base()] was `true`,
+        ///   or if the [`ours` strategy](Strategy::Ours) was used.
         /// * the merged tree of all merge-bases, which then isn't linked to an actual commit.
         /// * an empty tree, if [Options::with_allow_missing_merge_base()] is enabled.
         pub merge_base_tree_id: gix_hash::ObjectId,
@@ -42,12 +43,15 @@ pub mod commit {
         pub virtual_merge_bases: Vec<gix_hash::ObjectId>,
     }
 
+    pub use gix_merge::commit::Strategy;
+
     /// A way to configure [`Repository::merge_commits()`](crate::Repository::merge_commits()).
     #[derive(Default, Debug, Clone)]
     pub struct Options {
         allow_missing_merge_base: bool,
         tree_merge: crate::merge::tree::Options,
         use_first_merge_base: bool,
+        strategy: Strategy,
     }
 
     impl From<gix_merge::tree::Options> for Options {
@@ -56,6 +60,7 @@ pub mod commit {
                 tree_merge: value.into(),
                 use_first_merge_base: false,
                 allow_missing_merge_base: false,
+                strategy: Strategy::default(),
             }
         }
     }
@@ -66,6 +71,7 @@ pub mod commit {
                 tree_merge: value,
                 use_first_merge_base: false,
                 allow_missing_merge_base: false,
+                strategy: Strategy::default(),
             }
         }
     }
@@ -76,12 +82,14 @@ pub mod commit {
                 allow_missing_merge_base,
                 tree_merge,
                 use_first_merge_base,
+                strategy,
             }: Options,
         ) -> Self {
             gix_merge::commit::Options {
                 allow_missing_merge_base,
                 tree_merge: tree_merge.into(),
                 use_first_merge_base,
+                strategy,
             }
         }
     }
@@ -100,6 +108,95 @@ pub mod commit {
             self.use_first_merge_base = use_first_merge_base;
             self
         }
+
+        /// Choose how both commits are merged, with the [`ours` strategy](Strategy::Ours) ignoring all changes of *their* commit.
+        pub fn with_strategy(mut self, strategy: Strategy) -> Self {
+            self.strategy = strategy;
+            self
+        }
+    }
+}
+
+///
+pub mod octopus {
+    pub use gix_merge::commit::octopus::{Outcome, Step};
+
+    use crate::merge::tree::TreatAsUnresolved;
+
+    /// A way to configure [`Repository::merge_commits_octopus()`](crate::Repository::merge_commits_octopus()).
+    #[derive(Default, Debug, Clone)]
+    pub struct Options {
+        allow_missing_merge_base: bool,
+        tree_merge: crate::merge::tree::Options,
+        use_first_merge_base: bool,
+        treat_as_unresolved: TreatAsUnresolved,
+        no_fast_forward: bool,
+    }
+
+    impl From<gix_merge::tree::Options> for Options {
+        fn from(value: gix_merge::tree::Options) -> Self {
+            Options {
+                tree_merge: value.into(),
+                ..Default::default()
+            }
+        }
+    }
+
+    impl From<crate::merge::tree::Options> for Options {
+        fn from(value: crate::merge::tree::Options) -> Self {
+            Options {
+                tree_merge: value,
+                ..Default::default()
+            }
+        }
+    }
+
+    impl From<Options> for gix_merge::commit::octopus::Options {
+        fn from(
+            Options {
+                allow_missing_merge_base,
+                tree_merge,
+                use_first_merge_base,
+                treat_as_unresolved,
+                no_fast_forward,
+            }: Options,
+        ) -> Self {
+            gix_merge::commit::octopus::Options {
+                allow_missing_merge_base,
+                tree_merge: tree_merge.into(),
+                use_first_merge_base,
+                treat_as_unresolved,
+                no_fast_forward,
+            }
+        }
+    }
+
+    /// Builder
+    impl Options {
+        /// If `true`, merging unrelated commits is allowed, with the merge-base being assumed as empty tree.
+        pub fn with_allow_missing_merge_base(mut self, allow_missing_merge_base: bool) -> Self {
+            self.allow_missing_merge_base = allow_missing_merge_base;
+            self
+        }
+
+        /// If `true`, do not merge multiple merge-bases into one. Instead, just use the first one.
+        pub fn with_use_first_merge_base(mut self, use_first_merge_base: bool) -> Self {
+            self.use_first_merge_base = use_first_merge_base;
+            self
+        }
+
+        /// Determine which conflicts make the octopus merge fail, which are the ones Git considers unresolved by default.
+        pub fn with_treat_as_unresolved(mut self, treat_as_unresolved: TreatAsUnresolved) -> Self {
+            self.treat_as_unresolved = treat_as_unresolved;
+            self
+        }
+
+        /// If `true`, *our* commit is always the first parent, even if it is contained in one of *their* commits,
+        /// similar to `git merge --no-ff`.
+        pub fn with_no_fast_forward(mut self, no_fast_forward: bool) -> Self {
+            self.no_fast_forward = no_fast_forward;
+            self
+        }
     }
 }
 
@@ -107,8 +204,8 @@ pub mod commit {
 pub mod tree {
     use gix_merge::blob::builtin_driver;
     pub use gix_merge::tree::{
-        Conflict, ContentMerge, Resolution, ResolutionFailure, TreatAsUnresolved, apply_index_entries,
-        treat_as_unresolved,
+        Conflict, ContentMerge, Resolution, ResolutionFailure, StrategyOption, TreatAsUnresolved, apply_index_entries,
+        strategy_option, treat_as_unresolved,
     };
 
     /// The outcome produced by [`Repository::merge_trees()`](crate::Repository::merge_trees()).
@@ -281,5 +378,96 @@ pub mod tree {
             self.tree_favor = tree_favor;
             self
         }
+
+        /// Adjust the merge like Git would when passing `option` as `-X <option>` to `git merge`.
+        ///
+        /// Note that [`StrategyOption::Ours`] and [`StrategyOption::Theirs`] set the [file favor](Self::with_file_favor()).
+        pub fn with_strategy_option(mut self, option: StrategyOption) -> Self {
+            match option {
+                StrategyOption::Ours => self.file_favor = Some(FileFavor::Ours),
+                StrategyOption::Theirs => self.file_favor = Some(FileFavor::Theirs),
+                _ => self.inner.apply_strategy_option(option),
+            }
+            self
+        }
+    }
+}
+
+///
+#[cfg(all(feature = "worktree-mutation", feature = "status"))]
+pub mod in_progress {
+    use crate::{Id, bstr::BString, merge::tree::Conflict};
+
+    /// The state of a merge that is in progress, as stored by Git in `MERGE_HEAD`, `MERGE_MSG` and `MERGE_MODE`.
+    ///
+    /// It's obtained with [`Repository::merge_state()`](crate::Repository::merge_state()).
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct State {
+        /// The commits that are merged into `HEAD`, as listed in `MERGE_HEAD`.
+        pub heads: Vec<gix_hash::ObjectId>,
+        /// The message to use for the merge commit as stored in `MERGE_MSG`, which may contain comment lines,
+        /// or an empty string if it didn't exist.
+        pub message: BString,
+        /// If `true`, `MERGE_MODE` indicates that `HEAD` is always a parent of the merge commit,
+        /// even if it is an ancestor of one of the [`heads`](Self::heads).
+        pub no_ff: bool,
+    }
+
+    /// The outcome of [`Repository::merge_into_head()`](crate::Repository::merge_into_head()).
+    #[derive(Debug)]
+    pub struct Outcome<'repo> {
+        /// The id of the merged tree, which is also written to the worktree.
+        ///
+        /// It contains conflict markers for unresolved content merges and is stored in `AUTO_MERGE` if there are conflicts.
+        pub tree_id: Id<'repo>,
+        /// All conflicts that were encountered, resolved or not.
+        pub conflicts: Vec<Conflict>,
+        /// The paths that have conflicting stages in the index, sorted and without duplicates.
+        ///
+        /// If empty, the merge can be completed with [`Repository::merge_continue()`](crate::Repository::merge_continue())
+        /// right away.
+        pub conflicting_paths: Vec<BString>,
+        /// Information about the files that were written to the worktree.
+        pub checkout: gix_worktree_state::checkout::Outcome,
+        /// The outcome of recording conflicts and replaying recorded resolutions, or `None` if there were no conflicts
+        /// or [rerere is disabled](crate::Repository::rerere_enabled()).
+        ///
+        /// Note that [`conflicting_paths`](Self::conflicting_paths) still lists paths that were resolved with a recorded resolution.
+        pub rerere: Option<gix_rerere::rerere::Outcome>,
+    }
+
+    ///
+    pub mod checkout {
+        use crate::bstr::BString;
+
+        /// The error returned when changing the worktree and the index to match a tree while merging or aborting a merge.
+        #[derive(Debug, thiserror::Error)]
+        #[allow(missing_docs)]
+        pub enum Error {
+            #[error("Repository at \"{}\" is a bare repository and has no worktree to merge into", git_dir.display())]
+            BareRepository { git_dir: std::path::PathBuf },
+            #[error("The untracked file at '{rela_path}' would be overwritten")]
+            UntrackedFileWouldBeOverwritten { rela_path: BString },
+            #[error("Could not create index from tree at {id}")]
+            IndexFromTree {
+                id: gix_hash::ObjectId,
+                source: gix_index::init::from_tree::Error,
+            },
+            #[error("Couldn't obtain configuration for core.protect*")]
+            BooleanConfig(#[from] crate::config::boolean::Error),
+            #[error(transparent)]
+            CheckoutOptions(#[from] crate::config::checkout_options::Error),
+            #[error(transparent)]
+            IndexCheckout(#[from] gix_worktree_state::checkout::Error),
+            #[error(transparent)]
+            WriteIndex(#[from] gix_index::file::write::Error),
+            #[error("Could not remove '{}' from the worktree", path.display())]
+            RemoveFile {
+                path: std::path::PathBuf,
+                source: std::io::Error,
+            },
+            #[error("Failed to reopen object database as Arc (only if thread-safety wasn't compiled in)")]
+            OpenArcOdb(#[source] std::io::Error),
+        }
     }
 }
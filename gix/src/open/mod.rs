@@ -59,6 +59,10 @@ pub enum Error {
     EnvironmentAccessDenied(#[from] gix_sec::permission::Error<std::path::PathBuf>),
     #[error(transparent)]
     PrefixNotRelative(#[from] gix_path::relative_path::Error),
+    #[error(transparent)]
+    RefStore(#[from] gix_ref::store::init::Error),
+    #[error("The references of linked worktrees can't be read from the {backend:?} backend yet")]
+    UnsupportedRefStorageInLinkedWorktree { backend: gix_ref::store::Backend },
 }
 
 mod options;
@@ -219,23 +219,33 @@ impl ThreadSafeRepository {
             current_dir_ref.as_path()
         };
 
-        let mut refs = {
-            let reflog = repo_config.reflog.unwrap_or(gix_ref::store::WriteReflog::Disable);
-            let object_hash = repo_config.object_hash;
-            let ref_store_init_opts = gix_ref::store::init::Options {
-                write_reflog: reflog,
-                object_hash,
-                precompose_unicode: repo_config.precompose_unicode,
-                prohibit_windows_device_names: repo_config.protect_windows,
-            };
-            match &common_dir {
-                Some(common_dir) => {
-                    crate::RefStore::for_linked_worktree(git_dir.to_owned(), common_dir.into(), ref_store_init_opts)
+        let ref_store_init_opts = gix_ref::store::init::Options {
+            write_reflog: repo_config.reflog.unwrap_or(gix_ref::store::WriteReflog::Disable),
+            object_hash: repo_config.object_hash,
+            precompose_unicode: repo_config.precompose_unicode,
+            prohibit_windows_device_names: repo_config.protect_windows,
+        };
+        let mut refs = match &common_dir {
+            Some(common_dir) => {
+                crate::RefStore::for_linked_worktree(git_dir.to_owned(), common_dir.into(), ref_store_init_opts)
+            }
+            None => crate::RefStore::at(git_dir.to_owned(), ref_store_init_opts),
+        };
+        let mut ref_store = match repo_config.ref_storage {
+            gix_ref::store::Backend::Files => None,
+            backend => {
+                if common_dir.is_some() {
+                    return Err(Error::UnsupportedRefStorageInLinkedWorktree { backend });
                 }
-                None => crate::RefStore::at(git_dir.to_owned(), ref_store_init_opts),
+                Some(Box::new(
+                    gix_ref::Store::at(git_dir.to_owned(), backend, ref_store_init_opts)?.to_handle(),
+                ))
             }
         };
-        let head = refs.find("HEAD").ok();
+        let head = match &ref_store {
+            Some(ref_store) => ref_store.find("HEAD").ok(),
+            None => refs.find("HEAD").ok(),
+        };
         let git_install_dir = crate::path::install_dir().ok();
         let home = gix_path::env::home_dir().and_then(|home| env.home.check_opt(home));
 
@@ -430,6 +440,10 @@ impl ThreadSafeRepository {
 
         refs.write_reflog = config::cache::util::reflog_or_default(config.reflog, worktree_dir.is_some());
         refs.namespace.clone_from(&config.refs_namespace);
+        if let Some(ref_store) = ref_store.as_mut() {
+            ref_store.set_write_reflog(refs.write_reflog);
+            ref_store.set_namespace(refs.namespace.clone());
+        }
         let prefix = replacement_objects_refs_prefix(&config.resolved, lenient_config, filter_config_section)?;
 
         if *git_dir_trust == gix_sec::Trust::Reduced && config.alloc_limit_bytes.is_none() {
@@ -467,17 +481,28 @@ impl ThreadSafeRepository {
 
                 Some(prefix).and_then(|prefix| {
                     let _span = gix_trace::detail!("find replacement objects");
-                    let platform = refs.iter().ok()?;
-                    let iter = platform.prefixed(prefix).ok()?;
-                    let replacements = iter
-                        .filter_map(Result::ok)
-                        .filter_map(|r: gix_ref::Reference| {
-                            let target = r.target.try_id()?.to_owned();
-                            let source =
-                                gix_hash::ObjectId::from_hex(r.name.as_bstr().strip_prefix(prefix.as_ref())?).ok()?;
-                            Some((source, target))
-                        })
-                        .collect::<Vec<_>>();
+                    let to_replacement = |r: gix_ref::Reference| {
+                        let target = r.target.try_id()?.to_owned();
+                        let source =
+                            gix_hash::ObjectId::from_hex(r.name.as_bstr().strip_prefix(prefix.as_ref())?).ok()?;
+                        Some((source, target))
+                    };
+                    let replacements = match &ref_store {
+                        Some(ref_store) => {
+                            let platform = ref_store.iter().ok()?;
+                            let iter = platform.prefixed(prefix).ok()?;
+                            iter.filter_map(Result::ok)
+                                .filter_map(to_replacement)
+                                .collect::<Vec<_>>()
+                        }
+                        None => {
+                            let platform = refs.iter().ok()?;
+                            let iter = platform.prefixed(prefix).ok()?;
+                            iter.filter_map(Result::ok)
+                                .filter_map(to_replacement)
+                                .collect::<Vec<_>>()
+                        }
+                    };
                     Some(replacements)
                 })
             }
@@ -499,6 +524,7 @@ impl ThreadSafeRepository {
             )?),
             common_dir,
             refs,
+            ref_store,
             work_tree: worktree_dir,
             config,
             // used when spawning new repositories off this one when following worktrees
@@ -12,6 +12,10 @@ pub mod edit {
         #[error(transparent)]
         FileTransactionCommit(#[from] gix_ref::file::transaction::commit::Error),
         #[error(transparent)]
+        StoreTransactionPrepare(#[from] gix_ref::store::transaction::prepare::Error),
+        #[error(transparent)]
+        StoreTransactionCommit(#[from] gix_ref::store::transaction::commit::Error),
+        #[error(transparent)]
         NameValidation(#[from] gix_validate::reference::name::Error),
         #[error(
             "Could not interpret core.filesRefLockTimeout or core.packedRefsTimeout, it must be the number in milliseconds to wait for locks or negative to wait forever"
@@ -144,5 +148,7 @@ pub mod find {
     pub enum Error {
         #[error(transparent)]
         Find(#[from] gix_ref::file::find::Error),
+        #[error(transparent)]
+        FindInStore(#[from] gix_ref::store::find::Error),
     }
 }
@@ -7,23 +7,57 @@ use gix_ref::file::ReferenceExt;
 /// A platform to create iterators over references.
 #[must_use = "Iterators should be obtained from this iterator platform"]
 pub struct Platform<'r> {
-    pub(crate) platform: gix_ref::file::iter::Platform<'r>,
+    pub(crate) platform: Refs<'r>,
     /// The owning repository.
     pub repo: &'r crate::Repository,
 }
 
+/// The platform of the reference store the repository uses.
+pub(crate) enum Refs<'r> {
+    Files(gix_ref::file::iter::Platform<'r>),
+    Store(gix_ref::store::iter::Platform<'r>),
+}
+
+impl<'r> Refs<'r> {
+    fn all(&self) -> std::io::Result<Inner<'_, 'r>> {
+        Ok(match self {
+            Refs::Files(platform) => Inner::Files(platform.all()?),
+            Refs::Store(platform) => Inner::Store(platform.all()?),
+        })
+    }
+
+    fn prefixed(&self, prefix: &RelativePath) -> std::io::Result<Inner<'_, 'r>> {
+        Ok(match self {
+            Refs::Files(platform) => Inner::Files(platform.prefixed(prefix)?),
+            Refs::Store(platform) => Inner::Store(platform.prefixed(prefix)?),
+        })
+    }
+
+    fn pseudo(&self) -> std::io::Result<Inner<'_, 'r>> {
+        Ok(match self {
+            Refs::Files(platform) => Inner::Files(platform.pseudo()?),
+            Refs::Store(platform) => Inner::Store(platform.pseudo()?),
+        })
+    }
+}
+
+enum Inner<'packed, 'repo> {
+    Files(gix_ref::file::iter::LooseThenPacked<'packed, 'repo>),
+    Store(gix_ref::store::iter::Iter<'packed>),
+}
+
 /// An iterator over references, with or without filter.
 pub struct Iter<'packed, 'repo> {
-    inner: gix_ref::file::iter::LooseThenPacked<'packed, 'repo>,
+    inner: Inner<'packed, 'repo>,
     peel_with_packed: Option<gix_ref::file::packed::SharedBufferSnapshot>,
     peel: bool,
     repo: &'repo crate::Repository,
 }
 
 impl<'packed, 'repo> Iter<'packed, 'repo> {
-    fn new(repo: &'repo crate::Repository, platform: gix_ref::file::iter::LooseThenPacked<'packed, 'repo>) -> Self {
+    fn new(repo: &'repo crate::Repository, inner: Inner<'packed, 'repo>) -> Self {
         Iter {
-            inner: platform,
+            inner,
             peel_with_packed: None,
             peel: false,
             repo,
@@ -97,7 +131,9 @@ impl Iter<'_, '_> {
     /// Doing this is necessary as the packed-refs buffer is already held by the iterator, disallowing the consumer of the iterator
     /// to peel the returned references themselves.
     pub fn peeled(mut self) -> Result<Self, gix_ref::packed::buffer::open::Error> {
-        self.peel_with_packed = self.repo.refs.cached_packed_buffer()?;
+        if self.repo.ref_store.is_none() {
+            self.peel_with_packed = self.repo.refs.cached_packed_buffer()?;
+        }
         self.peel = true;
         Ok(self)
     }
@@ -107,20 +143,34 @@ impl<'r> Iterator for Iter<'_, 'r> {
     type Item = Result<crate::Reference<'r>, Box<dyn std::error::Error + Send + Sync + 'static>>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.inner.next().map(|res| {
-            res.map_err(|err| Box::new(err) as Box<dyn std::error::Error + Send + Sync + 'static>)
-                .and_then(|mut r| {
-                    if self.peel {
-                        let repo = &self.repo;
-                        r.peel_to_id_packed(&repo.refs, &repo.objects, self.peel_with_packed.as_ref().map(|p| &***p))
-                            .map_err(|err| Box::new(err) as Box<dyn std::error::Error + Send + Sync + 'static>)
-                            .map(|_| r)
-                    } else {
-                        Ok(r)
+        let res = match &mut self.inner {
+            Inner::Files(iter) => iter
+                .next()?
+                .map_err(|err| Box::new(err) as Box<dyn std::error::Error + Send + Sync + 'static>),
+            Inner::Store(iter) => iter
+                .next()?
+                .map_err(|err| Box::new(err) as Box<dyn std::error::Error + Send + Sync + 'static>),
+        };
+        Some(
+            res.and_then(|mut r| {
+                if self.peel {
+                    let repo = &self.repo;
+                    match &repo.ref_store {
+                        Some(ref_store) => ref_store.peel_to_id(&mut r, &repo.objects),
+                        None => r.peel_to_id_packed(
+                            &repo.refs,
+                            &repo.objects,
+                            self.peel_with_packed.as_ref().map(|p| &***p),
+                        ),
                     }
-                })
-                .map(|r| crate::Reference::from_ref(r, self.repo))
-        })
+                    .map_err(|err| Box::new(err) as Box<dyn std::error::Error + Send + Sync + 'static>)
+                    .map(|_| r)
+                } else {
+                    Ok(r)
+                }
+            })
+            .map(|r| crate::Reference::from_ref(r, self.repo)),
+        )
     }
 }
 
@@ -138,4 +188,4 @@ pub mod init {
 }
 
 /// The error returned by [references()][crate::Repository::references()].
-pub type Error = gix_ref::packed::buffer::open::Error;
+pub type Error = gix_ref::store::iter::init::Error;
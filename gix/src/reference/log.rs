@@ -10,13 +10,19 @@ use crate::{
 
 impl Reference<'_> {
     /// Return a platform for obtaining iterators over reference logs.
+    ///
+    /// Note that only reference logs stored in files can be read this way, so repositories using the `reftable`
+    /// backend will appear to have none.
     pub fn log_iter(&self) -> gix_ref::file::log::iter::Platform<'_, '_> {
         self.inner.log_iter(&self.repo.refs)
     }
 
     /// Return true if a reflog is present for this reference.
     pub fn log_exists(&self) -> bool {
-        self.inner.log_exists(&self.repo.refs)
+        match &self.repo.ref_store {
+            Some(ref_store) => ref_store.reflog_exists(self.inner.name.as_ref()).unwrap_or(false),
+            None => self.inner.log_exists(&self.repo.refs),
+        }
     }
 }
 
@@ -83,8 +83,7 @@ impl<'repo> Reference<'repo> {
     /// the chain of symbolic refs and annotated tags.
     #[deprecated = "Use `peel_to_id()` instead"]
     pub fn peel_to_id_in_place(&mut self) -> Result<Id<'repo>, peel::Error> {
-        let oid = self.inner.peel_to_id(&self.repo.refs, &self.repo.objects)?;
-        Ok(Id::from_id(oid, self.repo))
+        self.peel_to_id()
     }
 
     /// Follow all symbolic targets this reference might point to and peel all annotated tags
@@ -112,7 +111,10 @@ impl<'repo> Reference<'repo> {
     /// # Ok(()) }
     /// ```
     pub fn peel_to_id(&mut self) -> Result<Id<'repo>, peel::Error> {
-        let oid = self.inner.peel_to_id(&self.repo.refs, &self.repo.objects)?;
+        let oid = match &self.repo.ref_store {
+            Some(ref_store) => ref_store.peel_to_id(&mut self.inner, &self.repo.objects)?,
+            None => self.inner.peel_to_id(&self.repo.refs, &self.repo.objects)?,
+        };
         Ok(Id::from_id(oid, self.repo))
     }
 
@@ -126,10 +128,7 @@ impl<'repo> Reference<'repo> {
         &mut self,
         packed: Option<&gix_ref::packed::Buffer>,
     ) -> Result<Id<'repo>, peel::Error> {
-        let oid = self
-            .inner
-            .peel_to_id_packed(&self.repo.refs, &self.repo.objects, packed)?;
-        Ok(Id::from_id(oid, self.repo))
+        self.peel_to_id_packed(packed)
     }
 
     /// Follow all symbolic targets this reference might point to and peel all annotated tags
@@ -141,9 +140,12 @@ impl<'repo> Reference<'repo> {
     /// Note that this method mutates `self` in place if it does not already point to a
     /// non-symbolic object.
     pub fn peel_to_id_packed(&mut self, packed: Option<&gix_ref::packed::Buffer>) -> Result<Id<'repo>, peel::Error> {
-        let oid = self
-            .inner
-            .peel_to_id_packed(&self.repo.refs, &self.repo.objects, packed)?;
+        let oid = match &self.repo.ref_store {
+            Some(ref_store) => ref_store.peel_to_id(&mut self.inner, &self.repo.objects)?,
+            None => self
+                .inner
+                .peel_to_id_packed(&self.repo.refs, &self.repo.objects, packed)?,
+        };
         Ok(Id::from_id(oid, self.repo))
     }
 
@@ -162,6 +164,9 @@ impl<'repo> Reference<'repo> {
     /// instead.
     #[doc(alias = "peel", alias = "git2")]
     pub fn peel_to_kind(&mut self, kind: gix_object::Kind) -> Result<Object<'repo>, peel::to_kind::Error> {
+        if self.repo.ref_store.is_some() {
+            return self.peel_to_kind_packed(kind, None);
+        }
         let packed = self.repo.refs.cached_packed_buffer().map_err(|err| {
             peel::to_kind::Error::FollowToObject(gix_ref::peel::to_object::Error::Follow(
                 file::find::existing::Error::Find(file::find::Error::PackedOpen(err)),
@@ -219,10 +224,11 @@ impl<'repo> Reference<'repo> {
         kind: gix_object::Kind,
         packed: Option<&gix_ref::packed::Buffer>,
     ) -> Result<Object<'repo>, peel::to_kind::Error> {
-        let target = self
-            .inner
-            .follow_to_object_packed(&self.repo.refs, packed)?
-            .attach(self.repo);
+        let target = match &self.repo.ref_store {
+            Some(ref_store) => ref_store.follow_to_object(&mut self.inner)?,
+            None => self.inner.follow_to_object_packed(&self.repo.refs, packed)?,
+        }
+        .attach(self.repo);
         Ok(target.object()?.peel_to_kind(kind)?)
     }
 
@@ -232,6 +238,9 @@ impl<'repo> Reference<'repo> {
     /// a symbolic target ref was looked up from packed-refs.
     #[doc(alias = "resolve", alias = "git2")]
     pub fn follow_to_object(&mut self) -> Result<Id<'repo>, follow::to_object::Error> {
+        if self.repo.ref_store.is_some() {
+            return self.follow_to_object_packed(None);
+        }
         let packed = self.repo.refs.cached_packed_buffer().map_err(|err| {
             follow::to_object::Error::FollowToObject(gix_ref::peel::to_object::Error::Follow(
                 file::find::existing::Error::Find(file::find::Error::PackedOpen(err)),
@@ -247,10 +256,11 @@ impl<'repo> Reference<'repo> {
         &mut self,
         packed: Option<&gix_ref::packed::Buffer>,
     ) -> Result<Id<'repo>, follow::to_object::Error> {
-        Ok(self
-            .inner
-            .follow_to_object_packed(&self.repo.refs, packed)?
-            .attach(self.repo))
+        Ok(match &self.repo.ref_store {
+            Some(ref_store) => ref_store.follow_to_object(&mut self.inner)?,
+            None => self.inner.follow_to_object_packed(&self.repo.refs, packed)?,
+        }
+        .attach(self.repo))
     }
 
     /// Follow this symbolic reference one level and return the ref it refers to.
@@ -269,13 +279,15 @@ impl<'repo> Reference<'repo> {
     /// assert_eq!(branch.name().as_bstr(), "refs/heads/main");
     /// # Ok(()) }
     /// ```
-    pub fn follow(&self) -> Option<Result<Reference<'repo>, gix_ref::file::find::existing::Error>> {
-        self.inner.follow(&self.repo.refs).map(|res| {
-            res.map(|r| Reference {
-                inner: r,
-                repo: self.repo,
-            })
-        })
+    pub fn follow(&self) -> Option<Result<Reference<'repo>, gix_ref::store::find::existing::Error>> {
+        let next = match &self.repo.ref_store {
+            Some(ref_store) => ref_store.follow(&self.inner)?,
+            None => self.inner.follow(&self.repo.refs)?.map_err(Into::into),
+        };
+        Some(next.map(|r| Reference {
+            inner: r,
+            repo: self.repo,
+        }))
     }
 }
 
@@ -38,6 +38,12 @@ mod error {
             source: Box<gix_discover::is_git::Error>,
             url: gix_url::Url,
         },
+        #[cfg(feature = "blocking-network-client")]
+        #[error(transparent)]
+        Bundle(#[from] gix_bundle::file::init::Error),
+        #[cfg(feature = "blocking-network-client")]
+        #[error("The bundle can't be applied to this repository")]
+        BundlePrerequisites(#[from] gix_bundle::verify::Error),
     }
 
     impl gix_protocol::transport::IsSpuriousError for Error {
@@ -78,6 +84,9 @@ impl<'repo> Remote<'repo> {
     /// Note that the `protocol.version` configuration key affects the transport protocol used to connect,
     /// with `2` being the default.
     ///
+    /// If the url points to a bundle file, its content is served as if it was a remote repository speaking protocol `1`,
+    /// after assuring that this repository contains all of the bundle's prerequisites.
+    ///
     /// The transport used for connection can be configured via `transport_mut().configure()` assuming the actually
     /// used transport is well known. If that's not the case, the transport can be created by hand and passed to
     /// [to_connection_with_transport()][Self::to_connection_with_transport()].
@@ -89,6 +98,19 @@ impl<'repo> Remote<'repo> {
     ) -> Result<Connection<'_, 'static, 'repo, Box<dyn Transport + Send>>, Error> {
         let (url, version) = self.sanitized_url_and_version(direction)?;
         #[cfg(feature = "blocking-network-client")]
+        if let Some(bundle_path) = bundle_path(&url) {
+            let bundle = gix_bundle::File::at(bundle_path)?;
+            // Without prerequisites, a mismatching object hash is detected after the handshake like for any other remote,
+            // which also allows clones to adopt the object hash of the bundle.
+            if !bundle.header.prerequisites.is_empty() {
+                bundle
+                    .header
+                    .verify_prerequisites(&self.repo.objects, self.repo.object_hash())?;
+            }
+            let transport = gix_bundle::transport::Connection::new(bundle, self.repo.config.trace_packet());
+            return Ok(self.to_connection_with_transport(Box::new(transport)));
+        }
+        #[cfg(feature = "blocking-network-client")]
         let scheme_is_ssh = url.scheme == gix_url::Scheme::Ssh;
         let transport = connect::connect(
             url,
@@ -114,6 +136,10 @@ impl<'repo> Remote<'repo> {
         direction: crate::remote::Direction,
     ) -> Result<(gix_url::Url, gix_protocol::transport::Protocol), Error> {
         fn sanitize(mut url: gix_url::Url) -> Result<gix_url::Url, Error> {
+            #[cfg(feature = "blocking-network-client")]
+            if bundle_path(&url).is_some() {
+                return Ok(url);
+            }
             if url.scheme == gix_url::Scheme::File {
                 let mut dir = gix_path::to_native_path_on_windows(Cow::Borrowed(url.path.as_ref()));
                 let kind = gix_discover::is_git(dir.as_ref())
@@ -155,3 +181,13 @@ impl<'repo> Remote<'repo> {
         Ok((sanitize(url)?, version))
     }
 }
+
+/// Return the path to the bundle file that `url` points to, if it is a `file://` url to a bundle.
+#[cfg(feature = "blocking-network-client")]
+fn bundle_path(url: &gix_url::Url) -> Option<std::path::PathBuf> {
+    if url.scheme != gix_url::Scheme::File {
+        return None;
+    }
+    let path = gix_path::to_native_path_on_windows(Cow::Borrowed(url.path.as_ref()));
+    gix_bundle::is_bundle(&path).then(|| path.into_owned())
+}
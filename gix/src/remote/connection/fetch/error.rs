@@ -27,6 +27,13 @@ pub enum Error {
         path: std::path::PathBuf,
         source: std::io::Error,
     },
+    #[error("Failed to write .promisor file at \"{}\"", path.display())]
+    WritePromisorFile {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+    #[error(transparent)]
+    Handshake(#[from] crate::remote::ref_map::Error),
     #[error("None of the refspec(s) {} matched any of the {num_remote_refs} refs on the remote", refspecs.iter().map(|r| r.to_ref().instruction().to_bstring().to_string()).collect::<Vec<_>>().join(", "))]
     NoMapping {
         refspecs: Vec<gix_refspec::RefSpec>,
@@ -43,6 +50,7 @@ impl gix_protocol::transport::IsSpuriousError for Error {
         match self {
             Error::Fetch(err) => err.is_spurious(),
             Error::Client(err) => err.is_spurious(),
+            Error::Handshake(err) => err.is_spurious(),
             _ => false,
         }
     }
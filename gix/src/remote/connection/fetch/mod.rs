@@ -199,6 +199,7 @@ where
 }
 
 mod config;
+mod objects;
 mod receive_pack;
 ///
 #[path = "update_refs/mod.rs"]
@@ -0,0 +1,151 @@
+use std::sync::atomic::AtomicBool;
+
+use gix_protocol::fetch::{Arguments, negotiate};
+#[cfg(feature = "async-network-client")]
+use gix_transport::client::async_io::Transport;
+#[cfg(feature = "blocking-network-client")]
+use gix_transport::client::blocking_io::Transport;
+
+use crate::remote::{
+    Connection,
+    connection::fetch::{Error, config},
+};
+
+impl<T> Connection<'_, '_, '_, T>
+where
+    T: Transport,
+{
+    /// Fetch exactly the objects identified by `ids` from the remote, without listing or updating any reference, and
+    /// write them as pack into the object database, filtered by `filter` if the remote supports it.
+    ///
+    /// This is how objects promised by a *promisor remote* are obtained in partial clones, which is why the received pack
+    /// is marked with a `.promisor` file right away. Note that no `HAVE`s are sent, so the remote may send objects
+    /// which are already present locally.
+    ///
+    /// Return `None` if the remote didn't send a pack, or the outcome of writing it to disk otherwise.
+    ///
+    /// # Consumption
+    ///
+    /// Like [`ref_map()`](Self::ref_map()), it consumes the connection as it's meant to be used for a single interaction.
+    #[allow(clippy::result_large_err)]
+    #[gix_protocol::maybe_async::maybe_async]
+    pub async fn fetch_objects<P>(
+        self,
+        ids: &[gix_hash::ObjectId],
+        filter: Option<&str>,
+        mut progress: P,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Option<gix_pack::bundle::write::Outcome>, Error>
+    where
+        P: gix_features::progress::NestedProgress,
+        P::SubProgress: 'static,
+    {
+        let _span = gix_trace::coarse!("remote::Connection::fetch_objects()", num_objects = ids.len());
+        let repo = &self.remote.repo.without_lazy_fetch();
+        let mut con = self.into_detached();
+        let mut handshake = con.handshake_by_ref(repo, Vec::new(), &mut progress).await?;
+
+        let write_pack_options = gix_pack::bundle::write::Options {
+            thread_limit: config::index_threads(repo)?,
+            index_version: config::pack_index_version(repo)?,
+            iteration_mode: gix_pack::data::input::Mode::Verify,
+            object_hash: repo.object_hash(),
+            alloc_limit_bytes: repo.config.alloc_limit_bytes,
+        };
+        let mut write_pack_bundle = None;
+        let mut negotiate = Negotiate { ids, filter };
+        gix_protocol::fetch(
+            &mut negotiate,
+            |reader, progress, should_interrupt| -> Result<bool, gix_pack::bundle::write::Error> {
+                write_pack_bundle = Some(gix_pack::Bundle::write_to_directory(
+                    reader,
+                    Some(&repo.objects.store_ref().path().join("pack")),
+                    progress,
+                    should_interrupt,
+                    Some(Box::new({
+                        let repo = repo.clone();
+                        repo.objects
+                    })),
+                    write_pack_options,
+                )?);
+                Ok(true)
+            },
+            progress,
+            should_interrupt,
+            gix_protocol::fetch::Context {
+                handshake: &mut handshake,
+                transport: &mut con.transport.inner,
+                user_agent: repo.config.user_agent_tuple(),
+                trace_packetlines: con.trace,
+            },
+            gix_protocol::fetch::Options {
+                shallow_file: repo.shallow_file(),
+                shallow: &Default::default(),
+                tags: gix_protocol::fetch::Tags::None,
+                reject_shallow_remote: false,
+            },
+        )
+        .await?;
+
+        if matches!(handshake.server_protocol_version, gix_protocol::transport::Protocol::V2) {
+            gix_protocol::indicate_end_of_interaction(&mut con.transport.inner, con.trace)
+                .await
+                .ok();
+        }
+
+        if let Some(bundle) = write_pack_bundle.as_mut() {
+            if let Some(index_path) = bundle.index_path.as_ref() {
+                let promisor_path = index_path.with_extension("promisor");
+                std::fs::write(&promisor_path, b"").map_err(|err| Error::WritePromisorFile {
+                    path: promisor_path,
+                    source: err,
+                })?;
+            }
+            if let Some(path) = bundle.keep_path.take() {
+                std::fs::remove_file(&path).map_err(|err| Error::RemovePackKeepFile { path, source: err })?;
+            }
+        }
+        Ok(write_pack_bundle)
+    }
+}
+
+/// Want all `ids` without sending any `HAVE`, as done by `git` when fetching objects from a promisor remote.
+struct Negotiate<'a> {
+    ids: &'a [gix_hash::ObjectId],
+    filter: Option<&'a str>,
+}
+
+impl gix_protocol::fetch::Negotiate for Negotiate<'_> {
+    fn mark_complete_and_common_ref(&mut self) -> Result<negotiate::Action, negotiate::Error> {
+        Ok(negotiate::Action::MustNegotiate {
+            remote_ref_target_known: Vec::new(),
+        })
+    }
+
+    fn add_wants(&mut self, arguments: &mut Arguments, _remote_ref_target_known: &[bool]) -> bool {
+        for id in self.ids {
+            arguments.want(id);
+        }
+        if let Some(filter) = self.filter.filter(|_| arguments.can_use_filter()) {
+            arguments.filter(filter);
+        }
+        !self.ids.is_empty()
+    }
+
+    fn one_round(
+        &mut self,
+        _state: &mut negotiate::one_round::State,
+        _arguments: &mut Arguments,
+        _previous_response: Option<&gix_protocol::fetch::Response>,
+    ) -> Result<(negotiate::Round, bool), negotiate::Error> {
+        Ok((
+            negotiate::Round {
+                haves_sent: 0,
+                in_vain: 0,
+                haves_to_send: 0,
+                previous_response_had_at_least_one_in_common: false,
+            },
+            true,
+        ))
+    }
+}
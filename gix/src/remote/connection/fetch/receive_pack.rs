@@ -95,6 +95,7 @@ where
         P: gix_features::progress::NestedProgress,
         P::SubProgress: 'static,
     {
+        let repo = &repo.without_lazy_fetch();
         let ref_map = &self.ref_map;
         if ref_map.is_missing_required_mapping() {
             let mut specs = ref_map.refspecs.clone();
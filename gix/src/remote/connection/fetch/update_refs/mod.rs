@@ -325,7 +325,18 @@ pub(crate) fn update(
                 .config
                 .lock_timeout()
                 .map_err(crate::reference::edit::Error::from)?;
-            repo.refs
+            let committer = repo
+                .committer()
+                .transpose()
+                .map_err(|err| update::Error::EditReferences(crate::reference::edit::Error::ParseCommitterTime(err)))?;
+            match &repo.ref_store {
+                Some(ref_store) => ref_store
+                    .transaction()
+                    .prepare(edits, file_lock_fail)
+                    .map_err(crate::reference::edit::Error::from)?
+                    .commit(committer)
+                    .map_err(crate::reference::edit::Error::from)?,
+                None => repo.refs
                 .transaction()
                 .packed_refs(
                     match write_packed_refs {
@@ -336,8 +347,9 @@ pub(crate) fn update(
                 )
                 .prepare(edits, file_lock_fail, packed_refs_lock_fail)
                 .map_err(crate::reference::edit::Error::from)?
-                .commit(repo.committer().transpose().map_err(|err| update::Error::EditReferences(crate::reference::edit::Error::ParseCommitterTime(err)))?)
-                .map_err(crate::reference::edit::Error::from)?
+                .commit(committer)
+                .map_err(crate::reference::edit::Error::from)?,
+            }
         }
         fetch::DryRun::Yes => edits,
     };
@@ -362,7 +374,8 @@ fn update_needs_adjustment_as_edits_symbolic_target_is_missing(
                             if current_target_name.as_ref() == new_target_ref {
                                 return false; // no-op are always fine
                             }
-                            let current_is_unborn = repo.refs.try_find(current_target_name).ok().flatten().is_none();
+                            let current_is_unborn =
+                                repo.try_find_reference(current_target_name).ok().flatten().is_none();
                             if current_is_unborn {
                                 return false;
                             }
@@ -377,7 +390,7 @@ fn update_needs_adjustment_as_edits_symbolic_target_is_missing(
                     unreachable!("we don't ever delete here")
                 }
             }
-            let target_ref_exists_locally = repo.refs.try_find(new_target_ref).ok().flatten().is_some();
+            let target_ref_exists_locally = repo.try_find_reference(new_target_ref).ok().flatten().is_some();
             if target_ref_exists_locally {
                 return false;
             }
@@ -135,6 +135,40 @@ where
                 extra_refspecs.push(tag_spec);
             }
         }
+        let mut handshake = self.handshake_by_ref(repo, handshake_parameters, &mut progress).await?;
+
+        let context = fetch::refmap::init::Context {
+            fetch_refspecs: self.remote.fetch_specs.clone(),
+            extra_refspecs,
+        };
+
+        let fetch_refmap = handshake.prepare_lsrefs_or_extract_refmap(
+            repo.config.user_agent_tuple(),
+            prefix_from_spec_as_filter_on_remote,
+            context,
+        )?;
+
+        #[cfg(feature = "async-network-client")]
+        let ref_map = fetch_refmap
+            .fetch_async(progress, &mut self.transport.inner, self.trace)
+            .await?;
+
+        #[cfg(feature = "blocking-network-client")]
+        let ref_map = fetch_refmap.fetch_blocking(progress, &mut self.transport.inner, self.trace)?;
+
+        self.handshake = Some(handshake);
+        Ok(ref_map)
+    }
+
+    /// Configure the transport and perform the handshake with the remote, using `handshake_parameters` and `progress`.
+    #[allow(clippy::result_large_err)]
+    #[gix_protocol::maybe_async::maybe_async]
+    pub(crate) async fn handshake_by_ref(
+        &mut self,
+        repo: &crate::Repository,
+        handshake_parameters: Vec<(String, Option<String>)>,
+        progress: &mut impl Progress,
+    ) -> Result<gix_protocol::Handshake, Error> {
         let mut credentials_storage;
         let url = self.transport.inner.to_url();
         let authenticate = match self.authenticate.as_mut() {
@@ -156,35 +190,14 @@ where
         if let Some(config) = self.transport_options.as_ref() {
             self.transport.inner.configure(&**config)?;
         }
-        let mut handshake = gix_protocol::handshake(
+        let handshake = gix_protocol::handshake(
             &mut self.transport.inner,
             gix_transport::Service::UploadPack,
             authenticate,
             handshake_parameters,
-            &mut progress,
+            progress,
         )
         .await?;
-
-        let context = fetch::refmap::init::Context {
-            fetch_refspecs: self.remote.fetch_specs.clone(),
-            extra_refspecs,
-        };
-
-        let fetch_refmap = handshake.prepare_lsrefs_or_extract_refmap(
-            repo.config.user_agent_tuple(),
-            prefix_from_spec_as_filter_on_remote,
-            context,
-        )?;
-
-        #[cfg(feature = "async-network-client")]
-        let ref_map = fetch_refmap
-            .fetch_async(progress, &mut self.transport.inner, self.trace)
-            .await?;
-
-        #[cfg(feature = "blocking-network-client")]
-        let ref_map = fetch_refmap.fetch_blocking(progress, &mut self.transport.inner, self.trace)?;
-
-        self.handshake = Some(handshake);
-        Ok(ref_map)
+        Ok(handshake)
     }
 }
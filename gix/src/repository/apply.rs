@@ -0,0 +1,492 @@
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use gix_apply::{FilePatch, Operation};
+use gix_hash::ObjectId;
+use gix_object::tree::EntryKind;
+
+use crate::{
+    Id, Repository,
+    bstr::{BStr, BString, ByteSlice},
+    repository::{
+        apply_patch,
+        apply_patch::{Options, Outcome, Target},
+        apply_patch_to_tree,
+    },
+};
+
+/// The content and kind of a file that a patch is applied to.
+#[derive(Clone)]
+struct File {
+    content: Vec<u8>,
+    kind: EntryKind,
+}
+
+/// The stages of a file that was merged with conflicts.
+struct Conflict {
+    base: ObjectId,
+    ours: ObjectId,
+    theirs: ObjectId,
+}
+
+/// The result of applying patches in memory, before anything is written.
+#[derive(Default)]
+struct Applied {
+    /// All files that patches were applied to, or `None` if they were deleted.
+    files: BTreeMap<BString, Option<File>>,
+    conflicts: BTreeMap<BString, Conflict>,
+    outcomes: Vec<apply_patch::File>,
+}
+
+/// Apply patches like `git apply` does.
+impl Repository {
+    /// Apply all `patches`, as obtained by [`gix_apply::parse()`], to the worktree or the index as configured by `options`,
+    /// like `git apply` does.
+    ///
+    /// Patches are applied in order, so later patches see the changes of earlier ones. Either all patches are applied,
+    /// or nothing is changed if one of them fails to apply, unless writing to the worktree fails.
+    /// Files in the worktree are read and written as they are, without conversion by filters.
+    pub fn apply_patch(&self, patches: &[FilePatch], options: Options) -> Result<Outcome, apply_patch::Error> {
+        let target = match options.target {
+            Target::Worktree if options.three_way => Target::WorktreeAndIndex,
+            target => target,
+        };
+        let workdir = match target {
+            Target::Index => None,
+            Target::Worktree | Target::WorktreeAndIndex => {
+                Some(self.workdir().ok_or(apply_patch::Error::BareRepository)?)
+            }
+        };
+        let mut index = match target {
+            Target::Worktree => None,
+            Target::Index | Target::WorktreeAndIndex => Some(self.index_or_empty()?.into_owned_or_cloned()),
+        };
+
+        let applied = self.apply_patches(patches, options.apply, options.three_way, |path| {
+            let Some(index) = &index else {
+                return read_worktree_file(workdir.expect("set for worktree target"), path);
+            };
+            if leading_dirs(path).any(|dir| {
+                index
+                    .entry_by_path(dir)
+                    .is_some_and(|entry| entry.mode == gix_index::entry::Mode::SYMLINK)
+            }) {
+                return Err(apply_patch::Error::BeyondSymlink { path: path.to_owned() });
+            }
+            let file = match index.entry_index_by_path_and_stage(path, gix_index::entry::Stage::Unconflicted) {
+                Some(entry_index) => {
+                    let entry = &index.entries()[entry_index];
+                    let kind = entry
+                        .mode
+                        .to_tree_entry_mode()
+                        .map_or(EntryKind::Blob, |mode| mode.kind());
+                    Some(File {
+                        content: self.find_object(entry.id)?.detach().data,
+                        kind,
+                    })
+                }
+                None if index.entry_index_by_path(path).is_ok() => {
+                    return Err(apply_patch::Error::Conflicted { path: path.to_owned() });
+                }
+                None => None,
+            };
+            if let Some(workdir) = workdir {
+                let worktree_file = read_worktree_file(workdir, path)?;
+                let matches = match (&file, &worktree_file) {
+                    (Some(file), Some(worktree_file)) => file.content == worktree_file.content,
+                    (None, None) => true,
+                    _ => false,
+                };
+                if !matches {
+                    return Err(apply_patch::Error::WorktreeDiffersFromIndex { path: path.to_owned() });
+                }
+            }
+            Ok(file)
+        })?;
+
+        if let Some(workdir) = workdir {
+            for (path, file) in &applied.files {
+                match file {
+                    Some(file) => write_worktree_file(workdir, path.as_ref(), file)?,
+                    None => remove_worktree_file(workdir, path.as_ref())?,
+                }
+            }
+        }
+        if let Some(index) = &mut index {
+            index.remove_entries(|_, path, _| applied.files.contains_key(path));
+            for (path, file) in &applied.files {
+                let Some(file) = file else { continue };
+                let mode = gix_object::tree::EntryMode::from(file.kind).into();
+                if let Some(conflict) = applied.conflicts.get(path) {
+                    for (stage, id) in [
+                        (gix_index::entry::Stage::Base, conflict.base),
+                        (gix_index::entry::Stage::Ours, conflict.ours),
+                        (gix_index::entry::Stage::Theirs, conflict.theirs),
+                    ] {
+                        index.dangerously_push_entry(
+                            Default::default(),
+                            id,
+                            gix_index::entry::Flags::from_stage(stage),
+                            mode,
+                            path.as_ref(),
+                        );
+                    }
+                    continue;
+                }
+                let stat = match workdir {
+                    Some(workdir) => {
+                        let path = worktree_path(workdir, path.as_ref())?;
+                        let metadata = gix_index::fs::Metadata::from_path_no_follow(&path)
+                            .map_err(|source| apply_patch::Error::Io { path, source })?;
+                        gix_index::entry::Stat::from_fs(&metadata).unwrap_or_default()
+                    }
+                    None => Default::default(),
+                };
+                let id = self.write_blob(&file.content)?.detach();
+                index.dangerously_push_entry(stat, id, gix_index::entry::Flags::empty(), mode, path.as_ref());
+            }
+            index.sort_entries();
+            index.write(Default::default())?;
+        }
+
+        Ok(Outcome {
+            files: applied.outcomes,
+            conflicting_paths: applied.conflicts.into_keys().collect(),
+        })
+    }
+
+    /// Apply all `patches`, as obtained by [`gix_apply::parse()`], to the tree `tree_id` as configured by `options`,
+    /// and return the id of the written tree, without touching the index or the worktree.
+    ///
+    /// Patches are applied in order, so later patches see the changes of earlier ones.
+    pub fn apply_patch_to_tree(
+        &self,
+        tree_id: impl Into<ObjectId>,
+        patches: &[FilePatch],
+        options: gix_apply::apply::Options,
+    ) -> Result<Id<'_>, apply_patch_to_tree::Error> {
+        let tree = self.find_tree(tree_id)?;
+        let applied = self.apply_patches(patches, options, false, |path| {
+            for dir in leading_dirs(path) {
+                if tree
+                    .lookup_entry(dir.split_str(b"/"))?
+                    .is_some_and(|entry| entry.mode().is_link())
+                {
+                    return Err(apply_patch::Error::BeyondSymlink { path: path.to_owned() });
+                }
+            }
+            let Some(entry) = tree.lookup_entry(path.split_str(b"/"))? else {
+                return Ok(None);
+            };
+            Ok(Some(File {
+                content: self.find_object(entry.object_id())?.detach().data,
+                kind: entry.mode().kind(),
+            }))
+        })?;
+
+        let mut editor = self.edit_tree(tree.id)?;
+        for (path, file) in applied.files {
+            match file {
+                Some(file) => {
+                    let id = self.write_blob(&file.content).map_err(apply_patch::Error::from)?;
+                    editor.upsert(path.as_bstr(), file.kind, id)?;
+                }
+                None => {
+                    editor.remove(path.as_bstr())?;
+                }
+            }
+        }
+        Ok(editor.write()?)
+    }
+
+    /// Apply `patches` in memory, using `read` to obtain files that weren't touched by a previous patch yet.
+    ///
+    /// `read` must fail for paths that are beyond a symbolic link in the files it reads from.
+    fn apply_patches(
+        &self,
+        patches: &[FilePatch],
+        options: gix_apply::apply::Options,
+        three_way: bool,
+        mut read: impl FnMut(&BStr) -> Result<Option<File>, apply_patch::Error>,
+    ) -> Result<Applied, apply_patch::Error> {
+        let reverse = options.reverse;
+        let options = gix_apply::apply::Options {
+            reverse: false,
+            object_hash: Some(self.object_hash()),
+            ..options
+        };
+        let validate = self.config.protect_options()?;
+        let mut applied = Applied::default();
+        for patch in patches {
+            let patch = if reverse {
+                Cow::Owned(patch.reversed())
+            } else {
+                Cow::Borrowed(patch)
+            };
+            let path = patch.path().to_owned();
+            for (patch_path, is_link) in [
+                (&patch.old_path, patch.old_mode == Some(EntryKind::Link)),
+                (&patch.new_path, patch.new_mode == Some(EntryKind::Link)),
+            ] {
+                let Some(patch_path) = patch_path else { continue };
+                validate_path(patch_path.as_ref(), is_link, validate)?;
+                if leading_dirs(patch_path.as_ref())
+                    .any(|dir| matches!(applied.files.get(dir), Some(Some(file)) if file.kind == EntryKind::Link))
+                {
+                    return Err(apply_patch::Error::BeyondSymlink {
+                        path: patch_path.clone(),
+                    });
+                }
+            }
+            let mut current = |path: &BString| -> Result<Option<File>, apply_patch::Error> {
+                match applied.files.get(path) {
+                    Some(file) => Ok(file.clone()),
+                    None => read(path.as_ref()),
+                }
+            };
+
+            let old = match &patch.old_path {
+                Some(old_path) => Some(
+                    current(old_path)?.ok_or_else(|| apply_patch::Error::DoesNotExist { path: old_path.clone() })?,
+                ),
+                None => None,
+            };
+            if let Some(new_path) = patch.new_path.as_ref().filter(|_| patch.operation != Operation::Modify) {
+                if current(new_path)?.is_some() {
+                    return Err(apply_patch::Error::AlreadyExists { path: new_path.clone() });
+                }
+            }
+            let kind = patch
+                .new_mode
+                .or(old.as_ref().map(|old| old.kind))
+                .unwrap_or(EntryKind::Blob);
+            if kind == EntryKind::Commit || old.as_ref().is_some_and(|old| old.kind == EntryKind::Commit) {
+                return Err(apply_patch::Error::Submodule { path });
+            }
+
+            let preimage = old.as_ref().map_or(&[][..], |old| old.content.as_slice());
+            let content = match gix_apply::apply(preimage, &patch, options) {
+                Ok(outcome) => {
+                    applied.outcomes.push(apply_patch::File {
+                        path: path.clone(),
+                        hunks: outcome.hunks,
+                        whitespace_errors: outcome.whitespace_errors,
+                        merged: false,
+                    });
+                    outcome.content
+                }
+                Err(err) => {
+                    let base = match &err {
+                        gix_apply::apply::Error::HunkMismatch { .. } if three_way => self.find_patch_base(&patch)?,
+                        _ => None,
+                    };
+                    let Some(base) = base else {
+                        return Err(apply_patch::Error::Apply { path, source: err });
+                    };
+                    let outcome = gix_apply::three_way(
+                        &base,
+                        preimage,
+                        &patch,
+                        gix_apply::three_way::Options {
+                            apply: options,
+                            labels: gix_merge::blob::builtin_driver::text::Labels {
+                                ancestor: Some("base".into()),
+                                current: Some("ours".into()),
+                                other: Some("theirs".into()),
+                            },
+                            merge: Default::default(),
+                        },
+                    )
+                    .map_err(|source| apply_patch::Error::ThreeWay {
+                        path: path.clone(),
+                        source,
+                    })?;
+                    if outcome.conflict {
+                        let conflict = Conflict {
+                            base: self.write_blob(&base)?.detach(),
+                            ours: self.write_blob(preimage)?.detach(),
+                            theirs: self.write_blob(&outcome.apply.content)?.detach(),
+                        };
+                        applied.conflicts.insert(path.clone(), conflict);
+                    }
+                    applied.outcomes.push(apply_patch::File {
+                        path: path.clone(),
+                        hunks: Vec::new(),
+                        whitespace_errors: outcome.apply.whitespace_errors,
+                        merged: true,
+                    });
+                    outcome.content
+                }
+            };
+
+            if let Some(old_path) = patch
+                .old_path
+                .as_ref()
+                .filter(|_| matches!(patch.operation, Operation::Rename | Operation::Delete))
+            {
+                applied.files.insert(old_path.clone(), None);
+            }
+            if let Some(new_path) = &patch.new_path {
+                applied.files.insert(new_path.clone(), Some(File { content, kind }));
+            }
+        }
+        Ok(applied)
+    }
+
+    /// Find the blob that `patch` was created from, or `None` if it's not in the object database.
+    fn find_patch_base(&self, patch: &FilePatch) -> Result<Option<Vec<u8>>, apply_patch::Error> {
+        let Some(prefix) = patch.old_id else {
+            return Ok(None);
+        };
+        // Abbreviated ids are parsed as the shortest kind of hash they fit into, which may not be ours.
+        let mut id = ObjectId::null(self.object_hash());
+        let len = prefix.hex_len().div_ceil(2).min(id.as_slice().len());
+        id.as_mut_slice()[..len].copy_from_slice(&prefix.as_oid().as_bytes()[..len]);
+        let Ok(prefix) = gix_hash::Prefix::new(&id, prefix.hex_len()) else {
+            return Ok(None);
+        };
+        let Some(Ok(id)) = self.objects.lookup_prefix(prefix, None)? else {
+            return Ok(None);
+        };
+        let object = self.find_object(id)?;
+        Ok((object.kind == gix_object::Kind::Blob).then(|| object.detach().data))
+    }
+}
+
+/// Fail if `rela_path` from a patch isn't a path that could be checked out, similar to `verify_path()` in Git.
+///
+/// This prevents patches from reaching outside the worktree, or into the `.git` directory.
+fn validate_path(
+    rela_path: &BStr,
+    is_link: bool,
+    options: gix_validate::path::component::Options,
+) -> Result<(), apply_patch::Error> {
+    let mut components = rela_path.split_str(b"/").peekable();
+    while let Some(component) = components.next() {
+        let mode = (is_link && components.peek().is_none()).then_some(gix_validate::path::component::Mode::Symlink);
+        gix_validate::path::component(component.as_bstr(), mode, options).map_err(|source| {
+            apply_patch::Error::InvalidPath {
+                path: rela_path.to_owned(),
+                source,
+            }
+        })?;
+    }
+    Ok(())
+}
+
+/// Return all directories leading to `rela_path`, like `a` and `a/b` for `a/b/c`.
+fn leading_dirs(rela_path: &BStr) -> impl Iterator<Item = &BStr> {
+    rela_path
+        .iter()
+        .enumerate()
+        .filter(|(_, byte)| **byte == b'/')
+        .map(move |(pos, _)| rela_path[..pos].as_bstr())
+}
+
+/// Return the location of `rela_path` in `workdir`, unless one of its leading directories is a symbolic link.
+fn worktree_path(workdir: &Path, rela_path: &BStr) -> Result<PathBuf, apply_patch::Error> {
+    if leading_dirs(rela_path).any(|dir| {
+        workdir
+            .join(gix_path::from_bstr(dir))
+            .symlink_metadata()
+            .is_ok_and(|metadata| metadata.is_symlink())
+    }) {
+        return Err(apply_patch::Error::BeyondSymlink {
+            path: rela_path.to_owned(),
+        });
+    }
+    Ok(workdir.join(gix_path::from_bstr(rela_path)))
+}
+
+fn read_worktree_file(workdir: &Path, rela_path: &BStr) -> Result<Option<File>, apply_patch::Error> {
+    let path = worktree_path(workdir, rela_path)?;
+    let io_err = |path: PathBuf| move |source| apply_patch::Error::Io { path, source };
+    let metadata = match path.symlink_metadata() {
+        Ok(metadata) => metadata,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(source) => return Err(apply_patch::Error::Io { path, source }),
+    };
+    Ok(Some(if metadata.is_symlink() {
+        let target = std::fs::read_link(&path).map_err(io_err(path.clone()))?;
+        File {
+            content: gix_path::into_bstr(target).into_owned().into(),
+            kind: EntryKind::Link,
+        }
+    } else if metadata.is_dir() {
+        return Ok(None);
+    } else {
+        File {
+            content: std::fs::read(&path).map_err(io_err(path.clone()))?,
+            kind: if gix_fs::is_executable(&metadata) {
+                EntryKind::BlobExecutable
+            } else {
+                EntryKind::Blob
+            },
+        }
+    }))
+}
+
+fn write_worktree_file(workdir: &Path, rela_path: &BStr, file: &File) -> Result<(), apply_patch::Error> {
+    let path = worktree_path(workdir, rela_path)?;
+    let io_err = |source| apply_patch::Error::Io {
+        path: path.clone(),
+        source,
+    };
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(io_err)?;
+    }
+    if path.symlink_metadata().is_ok_and(|metadata| metadata.is_symlink()) || file.kind == EntryKind::Link {
+        match std::fs::remove_file(&path) {
+            Ok(()) => {}
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(err) => return Err(io_err(err)),
+        }
+    }
+    if file.kind == EntryKind::Link {
+        let target = gix_path::try_from_byte_slice(&file.content).map_err(|_| {
+            io_err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "symlink target is not a valid path",
+            ))
+        })?;
+        return gix_fs::symlink::create(target, &path).map_err(io_err);
+    }
+    std::fs::write(&path, &file.content).map_err(io_err)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mut permissions = std::fs::metadata(&path).map_err(io_err)?.permissions();
+        let mode = permissions.mode();
+        let new_mode = if file.kind == EntryKind::BlobExecutable {
+            mode | ((mode & 0o444) >> 2)
+        } else {
+            mode & !0o111
+        };
+        if new_mode != mode {
+            permissions.set_mode(new_mode);
+            std::fs::set_permissions(&path, permissions).map_err(io_err)?;
+        }
+    }
+    Ok(())
+}
+
+/// Remove the file at `rela_path` in `workdir` if it exists, along with all parent directories that are empty then.
+fn remove_worktree_file(workdir: &Path, rela_path: &BStr) -> Result<(), apply_patch::Error> {
+    let path = worktree_path(workdir, rela_path)?;
+    match std::fs::remove_file(&path) {
+        Ok(()) => {}
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(source) => return Err(apply_patch::Error::Io { path, source }),
+    }
+    let mut dir = path.parent();
+    while let Some(parent) = dir.filter(|dir| *dir != workdir) {
+        if std::fs::remove_dir(parent).is_err() {
+            break;
+        }
+        dir = parent.parent();
+    }
+    Ok(())
+}
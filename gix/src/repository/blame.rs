@@ -1,3 +1,5 @@
+use std::{io::Read, sync::atomic::AtomicBool};
+
 use gix_hash::ObjectId;
 use gix_ref::bstr::BStr;
 
@@ -17,35 +19,212 @@ impl Repository {
     ) -> Result<gix_blame::Outcome, blame_file::Error> {
         let cache = self.commit_graph_if_enabled()?;
         let mut resource_cache = self.diff_resource_cache_for_tree_diff()?;
+        let options = self.blame_options(Some(file_path), options)?;
+
+        let outcome = gix_blame::file(
+            &self.objects,
+            suspect.into(),
+            cache,
+            &mut resource_cache,
+            file_path,
+            options,
+        )?;
+
+        Ok(outcome)
+    }
+
+    /// Like [`blame_file()`](Self::blame_file()), but call `on_entry` with each [`gix_blame::BlameEntry`] as soon as it
+    /// is known, count traversed commits in `progress`, and stop once `should_interrupt` is set.
+    ///
+    /// For details, see the documentation of [`gix_blame::file_incremental()`].
+    pub fn blame_file_incremental(
+        &self,
+        file_path: &BStr,
+        suspect: impl Into<ObjectId>,
+        options: blame_file::Options,
+        on_entry: impl FnMut(gix_blame::BlameEntry),
+        progress: &mut dyn crate::progress::Progress,
+        should_interrupt: &AtomicBool,
+    ) -> Result<gix_blame::Outcome, blame_file::Error> {
+        let cache = self.commit_graph_if_enabled()?;
+        let mut resource_cache = self.diff_resource_cache_for_tree_diff()?;
+        let options = self.blame_options(Some(file_path), options)?;
+
+        let outcome = gix_blame::file_incremental(
+            &self.objects,
+            suspect.into(),
+            cache,
+            &mut resource_cache,
+            file_path,
+            options,
+            on_entry,
+            progress,
+            should_interrupt,
+        )?;
 
+        Ok(outcome)
+    }
+
+    /// Like [`blame_file()`](Self::blame_file()), but blame all files at `file_paths` in a single traversal of the
+    /// history, and return one outcome per file in the same order.
+    ///
+    /// For details, see the documentation of [`gix_blame::files()`].
+    pub fn blame_files<'a>(
+        &self,
+        file_paths: impl IntoIterator<Item = &'a BStr>,
+        suspect: impl Into<ObjectId>,
+        options: blame_file::Options,
+    ) -> Result<Vec<gix_blame::Outcome>, blame_file::Error> {
+        let cache = self.commit_graph_if_enabled()?;
+        let mut resource_cache = self.diff_resource_cache_for_tree_diff()?;
+        let options = self.blame_options(None, options)?;
+
+        let outcomes = gix_blame::files(
+            &self.objects,
+            suspect.into(),
+            cache,
+            &mut resource_cache,
+            file_paths,
+            options,
+        )?;
+
+        Ok(outcomes)
+    }
+
+    /// Turn `options` into options for `gix_blame`, filling in everything that wasn't set from configuration.
+    /// Uncommitted changes are only obtained if `file_path` is set.
+    fn blame_options(
+        &self,
+        file_path: Option<&BStr>,
+        options: blame_file::Options,
+    ) -> Result<gix_blame::Options, blame_file::Error> {
         let blame_file::Options {
             diff_algorithm,
+            whitespace,
             ranges,
             since,
             rewrites,
+            ignore_revs,
+            ignore_revs_files,
+            mark_ignored_lines,
+            mark_unblamable_lines,
+            uncommitted,
+            reverse_until,
+            moves_and_copies,
         } = options;
         let diff_algorithm = match diff_algorithm {
             Some(diff_algorithm) => diff_algorithm,
             None => self.diff_algorithm()?,
         };
+        let ignore_revs_files = match ignore_revs_files {
+            Some(files) => files,
+            None => self.config.blame_ignore_revs_files()?,
+        };
+        let mut ignore_revs: gix_hashtable::HashSet<ObjectId> = ignore_revs.into_iter().collect();
+        for path in ignore_revs_files {
+            let path = match self.workdir() {
+                Some(workdir) => workdir.join(path),
+                None => self.git_dir().join(path),
+            };
+            read_ignore_revs_file(&path, &mut ignore_revs)?;
+        }
+        let (mark_ignored_lines, mark_unblamable_lines) = match (mark_ignored_lines, mark_unblamable_lines) {
+            (Some(ignored), Some(unblamable)) => (ignored, unblamable),
+            (ignored, unblamable) => {
+                let (configured_ignored, configured_unblamable) = self.config.blame_mark_lines()?;
+                (
+                    ignored.unwrap_or(configured_ignored),
+                    unblamable.unwrap_or(configured_unblamable),
+                )
+            }
+        };
 
-        let options = gix_blame::Options {
+        Ok(gix_blame::Options {
             diff_algorithm,
+            whitespace,
             ranges,
             since,
             rewrites,
             debug_track_path: false,
-        };
+            ignore_revs,
+            mark_ignored_lines,
+            mark_unblamable_lines,
+            uncommitted_blob: match (file_path, uncommitted) {
+                (Some(file_path), Some(uncommitted)) => Some(self.uncommitted_blob(file_path, uncommitted)?),
+                _ => None,
+            },
+            reverse_until,
+            moves_and_copies,
+        })
+    }
 
-        let outcome = gix_blame::file(
-            &self.objects,
-            suspect.into(),
-            cache,
-            &mut resource_cache,
-            file_path,
-            options,
-        )?;
+    /// Obtain the content of `file_path` as described by `uncommitted`, in the form `git` would store it.
+    fn uncommitted_blob(
+        &self,
+        file_path: &BStr,
+        uncommitted: blame_file::Uncommitted,
+    ) -> Result<Vec<u8>, blame_file::Error> {
+        use gix_filter::pipeline::convert::ToGitOutcome;
 
-        Ok(outcome)
+        Ok(match uncommitted {
+            blame_file::Uncommitted::Blob(data) => data,
+            blame_file::Uncommitted::Index => {
+                let index = self.index_or_empty()?;
+                let entry = index
+                    .entry_by_path(file_path)
+                    .ok_or_else(|| blame_file::Error::MissingIndexEntry {
+                        file_path: file_path.to_owned(),
+                    })?;
+                self.find_blob(entry.id)?.take_data()
+            }
+            blame_file::Uncommitted::Worktree => {
+                let workdir = self.workdir().ok_or(blame_file::Error::MissingWorktree)?;
+                let rela_path = gix_path::from_bstr(file_path);
+                let path = workdir.join(&rela_path);
+                let file = std::fs::File::open(&path).map_err(|source| blame_file::Error::ReadWorktreeFile {
+                    path: path.clone(),
+                    source,
+                })?;
+
+                let (mut pipeline, index) = self.filter_pipeline(None)?;
+                let mut data = Vec::new();
+                let read_result = match pipeline.convert_to_git(file, &rela_path, &index)? {
+                    ToGitOutcome::Unchanged(mut file) => file.read_to_end(&mut data),
+                    ToGitOutcome::Buffer(buf) => {
+                        data.extend_from_slice(buf);
+                        Ok(buf.len())
+                    }
+                    ToGitOutcome::Process(mut read) => read.read_to_end(&mut data),
+                };
+                read_result.map_err(|source| blame_file::Error::ReadWorktreeFile { path, source })?;
+                data
+            }
+        })
+    }
+}
+
+/// Add all object ids listed in the file at `path` to `out`, skipping empty lines and comments, similar to `git`.
+fn read_ignore_revs_file(
+    path: &std::path::Path,
+    out: &mut gix_hashtable::HashSet<ObjectId>,
+) -> Result<(), blame_file::Error> {
+    use crate::bstr::ByteSlice;
+
+    let content = std::fs::read(path).map_err(|source| blame_file::Error::ReadIgnoreRevsFile {
+        path: path.to_owned(),
+        source,
+    })?;
+    for line in content.lines() {
+        let line = line.find_byte(b'#').map_or(line, |pos| &line[..pos]).trim();
+        if line.is_empty() {
+            continue;
+        }
+        let id = ObjectId::from_hex(line).map_err(|source| blame_file::Error::ParseIgnoreRevsFile {
+            path: path.to_owned(),
+            line: line.into(),
+            source,
+        })?;
+        out.insert(id);
     }
+    Ok(())
 }
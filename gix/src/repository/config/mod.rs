@@ -147,6 +147,15 @@ impl crate::Repository {
     pub fn diff_algorithm(&self) -> Result<gix_diff::blob::Algorithm, config::diff::algorithm::Error> {
         self.config.diff_algorithm()
     }
+
+    /// Return the amount of context lines around changes in patches, along with the amount of lines between changes up to which
+    /// they are shown in the same hunk, as configured by `diff.context` and `diff.interHunkContext`.
+    #[cfg(feature = "blob-diff")]
+    pub fn diff_context_size(
+        &self,
+    ) -> Result<gix_diff::blob::unified_diff::ContextSize, config::unsigned_integer::Error> {
+        self.config.diff_context_size()
+    }
 }
 
 mod branch;
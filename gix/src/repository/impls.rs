@@ -7,6 +7,7 @@ impl Clone for crate::Repository {
     fn clone(&self) -> Self {
         let mut new = crate::Repository::from_refs_and_objects(
             self.refs.clone(),
+            self.ref_store.clone(),
             self.objects.clone(),
             self.work_tree.clone(),
             self.common_dir.clone(),
@@ -22,6 +23,8 @@ impl Clone for crate::Repository {
         if self.bufs.is_none() {
             new.bufs.take();
         }
+        // Keep the way missing objects are fetched, even if it was disabled or replaced.
+        new.objects.promisor.clone_from(&self.objects.promisor);
 
         new
     }
@@ -49,6 +52,7 @@ impl From<&crate::ThreadSafeRepository> for crate::Repository {
     fn from(repo: &crate::ThreadSafeRepository) -> Self {
         crate::Repository::from_refs_and_objects(
             repo.refs.clone(),
+            repo.ref_store.clone(),
             gix_odb::memory::Proxy::from(gix_odb::Cache::from(repo.objects.to_handle())).with_write_passthrough(),
             repo.work_tree.clone(),
             repo.common_dir.clone(),
@@ -67,6 +71,7 @@ impl From<crate::ThreadSafeRepository> for crate::Repository {
     fn from(repo: crate::ThreadSafeRepository) -> Self {
         crate::Repository::from_refs_and_objects(
             repo.refs,
+            repo.ref_store,
             gix_odb::memory::Proxy::from(gix_odb::Cache::from(repo.objects.to_handle())).with_write_passthrough(),
             repo.work_tree,
             repo.common_dir,
@@ -85,6 +90,7 @@ impl From<crate::Repository> for crate::ThreadSafeRepository {
     fn from(r: crate::Repository) -> Self {
         crate::ThreadSafeRepository {
             refs: r.refs,
+            ref_store: r.ref_store,
             objects: r.objects.into_inner().store(),
             work_tree: r.work_tree,
             common_dir: r.common_dir,
@@ -4,6 +4,7 @@ impl crate::Repository {
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn from_refs_and_objects(
         refs: crate::RefStore,
+        ref_store: Option<Box<gix_ref::store::Handle>>,
         mut objects: crate::OdbHandle,
         work_tree: Option<std::path::PathBuf>,
         common_dir: Option<std::path::PathBuf>,
@@ -14,12 +15,13 @@ impl crate::Repository {
         #[cfg(feature = "attributes")] modules: crate::submodule::ModulesFileStorage,
     ) -> Self {
         setup_objects(&mut objects, &config);
-        crate::Repository {
+        let repo = crate::Repository {
             bufs: Some(RefCell::new(Vec::with_capacity(4))),
             work_tree,
             common_dir,
             objects,
             refs,
+            ref_store,
             config,
             options: linked_worktree_options,
             #[cfg(feature = "index")]
@@ -27,7 +29,19 @@ impl crate::Repository {
             shallow_commits,
             #[cfg(feature = "attributes")]
             modules,
-        }
+        };
+        #[cfg(feature = "blocking-network-client")]
+        let repo = {
+            let mut repo = repo;
+            if repo.is_partial_clone() {
+                repo.objects.promisor = Some(std::sync::Arc::new(crate::repository::promisor::LazyFetch {
+                    git_dir: repo.git_dir().to_owned(),
+                    options: repo.options.clone(),
+                }));
+            }
+            repo
+        };
+        repo
     }
 
     /// Convert this instance into a [`ThreadSafeRepository`][crate::ThreadSafeRepository] by dropping all thread-local data.
@@ -5,11 +5,12 @@ use gix_object::Write;
 
 use crate::{
     Repository,
+    bstr::BStr,
     config::{cache::util::ApplyLeniencyDefault, tree},
     prelude::ObjectIdExt,
     repository::{
-        blob_merge_options, merge_commits, merge_resource_cache, merge_trees, tree_merge_options, virtual_merge_base,
-        virtual_merge_base_with_graph,
+        blob_merge_options, merge_commits, merge_commits_octopus, merge_resource_cache, merge_trees,
+        tree_merge_options, virtual_merge_base, virtual_merge_base_with_graph,
     },
 };
 
@@ -85,6 +86,7 @@ impl Repository {
                         .unwrap_or_default(),
                     marker_size: text::Conflict::DEFAULT_MARKER_SIZE.try_into().unwrap(),
                 },
+                whitespace: Default::default(),
             },
         })
     }
@@ -112,6 +114,7 @@ impl Repository {
             marker_size_multiplier: 0,
             symlink_conflicts: None,
             tree_conflicts: None,
+            filter_mode: None,
         }
         .into())
     }
@@ -156,6 +159,7 @@ impl Repository {
             &mut Default::default(),
             &mut diff_cache,
             &mut blob_merge,
+            &mut Submodules(self),
             options.into(),
         )?;
 
@@ -215,6 +219,7 @@ impl Repository {
             &mut graph,
             &mut diff_cache,
             &mut blob_merge,
+            &mut Submodules(self),
             self,
             &mut |id| id.to_owned().attach(self).shorten_or_id().to_string(),
             options.into(),
@@ -238,6 +243,43 @@ impl Repository {
         })
     }
 
+    /// Merge all of `their_commits` into `our_commit` one after another, like `git merge -s octopus` would, to obtain
+    /// the merged tree along with the parents of the merge commit.
+    /// No change to the worktree or index is made, but the merged trees and blobs are written to the object database.
+    ///
+    /// Commits that are already contained in what was merged so far are skipped, and the merge fails as soon as
+    /// one of the merges has conflicts that would need manual resolution.
+    ///
+    /// `options` should be initialized with [`Repository::tree_merge_options().into()`](Self::tree_merge_options()).
+    ///
+    /// ### Performance
+    ///
+    /// It's highly recommended to [set an object cache](Repository::compute_object_cache_size_for_tree_diffs)
+    /// to avoid extracting the same object multiple times.
+    pub fn merge_commits_octopus(
+        &self,
+        our_commit: impl Into<gix_hash::ObjectId>,
+        their_commits: impl IntoIterator<Item = impl Into<gix_hash::ObjectId>>,
+        options: crate::merge::octopus::Options,
+    ) -> Result<crate::merge::octopus::Outcome, merge_commits_octopus::Error> {
+        let their_commits: Vec<_> = their_commits.into_iter().map(Into::into).collect();
+        let mut diff_cache = self.diff_resource_cache_for_tree_diff()?;
+        let mut blob_merge = self.merge_resource_cache(Default::default())?;
+        let commit_graph = self.commit_graph_if_enabled()?;
+        let mut graph = self.revision_graph(commit_graph.as_ref());
+        Ok(gix_merge::commit::octopus(
+            our_commit.into(),
+            &their_commits,
+            &mut graph,
+            &mut diff_cache,
+            &mut blob_merge,
+            &mut Submodules(self),
+            self,
+            &mut |id| id.to_owned().attach(self).shorten_or_id().to_string(),
+            options.into(),
+        )?)
+    }
+
     /// Create a single virtual merge-base by merging all `merge_bases` into one.
     /// If the list is empty, an error will be returned as the histories are then unrelated.
     /// If there is only one commit in the list, it is returned directly with this case clearly marked in the outcome.
@@ -293,6 +335,7 @@ impl Repository {
             graph,
             &mut diff_cache,
             &mut blob_merge,
+            &mut Submodules(self),
             self,
             &mut |id| id.to_owned().attach(self).shorten_or_id().to_string(),
             options.into(),
@@ -305,3 +348,32 @@ impl Repository {
         })
     }
 }
+
+/// Provide access to the submodules of a repository, as configured in its `.gitmodules` file, to merge them.
+struct Submodules<'repo>(&'repo Repository);
+
+impl gix_merge::tree::submodule::Find for Submodules<'_> {
+    fn find_submodule(
+        &mut self,
+        rela_path: &BStr,
+    ) -> Result<Option<gix_merge::tree::submodule::Access<'_>>, Box<dyn std::error::Error + Send + Sync + 'static>>
+    {
+        let Some(mut submodules) = self.0.submodules()? else {
+            return Ok(None);
+        };
+        let Some(submodule) = submodules.find(|sm| sm.path().is_ok_and(|path| path.as_ref() == rela_path)) else {
+            return Ok(None);
+        };
+        let Some(repo) = submodule.open()? else {
+            return Ok(None);
+        };
+        let mut tips = Vec::new();
+        for reference in repo.references()?.all()?.peeled()? {
+            tips.push(reference?.id().detach());
+        }
+        Ok(Some(gix_merge::tree::submodule::Access {
+            objects: Box::new(repo.objects.clone()),
+            tips,
+        }))
+    }
+}
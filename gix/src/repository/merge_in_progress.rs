@@ -0,0 +1,420 @@
+use std::{collections::BTreeMap, path::Path};
+
+use gix_hash::ObjectId;
+use gix_merge::tree::{TreatAsUnresolved, apply_index_entries::RemovalMode};
+use gix_ref::{
+    Target,
+    transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+};
+
+use crate::{
+    Id, Repository,
+    bstr::{BStr, BString, ByteSlice, ByteVec},
+    merge::in_progress::{Outcome, State, checkout},
+    prelude::ObjectIdExt,
+    repository::{merge_abort, merge_continue, merge_into_head, merge_state},
+};
+
+const MERGE_HEAD: &str = "MERGE_HEAD";
+const MERGE_MSG: &str = "MERGE_MSG";
+const MERGE_MODE: &str = "MERGE_MODE";
+const AUTO_MERGE: &str = "AUTO_MERGE";
+const ORIG_HEAD: &str = "ORIG_HEAD";
+const MERGE_RR: &str = "MERGE_RR";
+
+/// Merges that are left in the repository to be completed or aborted later, compatible with Git.
+impl Repository {
+    /// Merge `their_commit` into the commit `HEAD` points to and write the result to the index and the worktree,
+    /// leaving the merge in progress so it can be completed with [`merge_continue()`](Self::merge_continue())
+    /// or undone with [`merge_abort()`](Self::merge_abort()), just like `git merge --no-ff --no-commit` would.
+    ///
+    /// Unresolved conflicts are written to the index as stages, while the worktree receives the merged content
+    /// with conflict markers. `message` is stored in `MERGE_MSG` along with a list of conflicting paths,
+    /// and `labels` are used in conflict markers like in [`merge_commits()`](Self::merge_commits()), which is
+    /// also configured with `options`.
+    ///
+    /// `ORIG_HEAD` is set to the commit `HEAD` pointed to, `MERGE_HEAD` to `their_commit`, and if there are
+    /// conflicts, `AUTO_MERGE` is set to the merged tree.
+    ///
+    /// If [rerere is enabled](Self::rerere_enabled()), conflicts are recorded and recorded resolutions are replayed,
+    /// just like [`rerere()`](Self::rerere()) does.
+    ///
+    /// The operation fails if another operation is in progress, or if the index or worktree have changes.
+    pub fn merge_into_head(
+        &self,
+        their_commit: impl Into<ObjectId>,
+        labels: gix_merge::blob::builtin_driver::text::Labels<'_>,
+        message: &BStr,
+        options: crate::merge::commit::Options,
+    ) -> Result<Outcome<'_>, merge_into_head::Error> {
+        if let Some(state) = self.state() {
+            return Err(merge_into_head::Error::OperationInProgress { state });
+        }
+        if self.is_dirty()? {
+            return Err(merge_into_head::Error::Dirty);
+        }
+        let head_id = self.head_id()?.detach();
+        let their_commit = their_commit.into();
+        let mut outcome = self.merge_commits(head_id, their_commit, labels, options)?;
+        let tree_id = outcome.tree_merge.tree.write()?.detach();
+
+        let index = self.index_or_empty()?;
+        let (mut new_index, checkout) = self.checkout_tree_over_index(&index, tree_id)?;
+        outcome.tree_merge.index_changed_after_applying_conflicts(
+            &mut new_index,
+            TreatAsUnresolved::git(),
+            RemovalMode::Prune,
+        );
+        let mut conflicting_paths: Vec<BString> = new_index
+            .entries()
+            .iter()
+            .filter(|entry| entry.stage() != gix_index::entry::Stage::Unconflicted)
+            .map(|entry| entry.path(&new_index).to_owned())
+            .collect();
+        conflicting_paths.dedup();
+        gix_index::File::from_state(new_index, self.index_path())
+            .write(Default::default())
+            .map_err(checkout::Error::from)?;
+
+        self.set_pseudo_ref(ORIG_HEAD, head_id, "merge: updating ORIG_HEAD")?;
+        if !conflicting_paths.is_empty() {
+            self.set_pseudo_ref(AUTO_MERGE, tree_id, "merge: auto-merge")?;
+        }
+
+        let mut merge_msg = BString::from(message);
+        if !merge_msg.ends_with(b"\n") {
+            merge_msg.push(b'\n');
+        }
+        if !conflicting_paths.is_empty() {
+            merge_msg.push_str("\n# Conflicts:\n");
+            for path in &conflicting_paths {
+                merge_msg.push_str("#\t");
+                merge_msg.push_str(path);
+                merge_msg.push(b'\n');
+            }
+        }
+        let git_dir = self.git_dir();
+        let write = |name: &str, content: &[u8]| {
+            let path = git_dir.join(name);
+            std::fs::write(&path, content).map_err(|source| merge_into_head::Error::WriteFile { path, source })
+        };
+        write(MERGE_HEAD, format!("{their_commit}\n").as_bytes())?;
+        write(MERGE_MODE, b"no-ff")?;
+        write(MERGE_MSG, &merge_msg)?;
+        let rerere = if conflicting_paths.is_empty() {
+            None
+        } else {
+            self.rerere()?
+        };
+
+        Ok(Outcome {
+            tree_id: tree_id.attach(self),
+            conflicts: outcome.tree_merge.conflicts,
+            conflicting_paths,
+            checkout,
+            rerere,
+        })
+    }
+
+    /// Return the state of the merge in progress, or `None` if there is no `MERGE_HEAD` file.
+    ///
+    /// This works for merges started by Git as well.
+    pub fn merge_state(&self) -> Result<Option<State>, merge_state::Error> {
+        let read = |name: &str| -> Result<Option<Vec<u8>>, merge_state::Error> {
+            let path = self.git_dir().join(name);
+            match std::fs::read(&path) {
+                Ok(content) => Ok(Some(content)),
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+                Err(source) => Err(merge_state::Error::ReadFile { path, source }),
+            }
+        };
+        let Some(merge_head) = read(MERGE_HEAD)? else {
+            return Ok(None);
+        };
+        let heads = merge_head
+            .lines()
+            .map(ByteSlice::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                ObjectId::from_hex(line).map_err(|source| merge_state::Error::ParseId {
+                    line: line.into(),
+                    source,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Some(State {
+            heads,
+            message: read(MERGE_MSG)?.unwrap_or_default().into(),
+            no_ff: read(MERGE_MODE)?.is_some_and(|mode| mode.trim() == b"no-ff"),
+        }))
+    }
+
+    /// Complete the merge in progress by creating a merge commit from the index and pointing `HEAD` to it,
+    /// like `git commit` or `git merge --continue` would.
+    ///
+    /// The commit message is taken from `MERGE_MSG` without its comment lines. The parents are the commit `HEAD`
+    /// points to and all commits in `MERGE_HEAD`, but like in Git, parents that are ancestors of other parents
+    /// are removed unless `MERGE_MODE` is `no-ff`.
+    ///
+    /// The operation fails if the index still contains conflicts, and removes all merge state on success.
+    /// If [rerere is enabled](Self::rerere_enabled()), the resolutions of recorded conflicts are recorded as well,
+    /// before `HEAD` is changed so failing to do so leaves the merge in progress.
+    pub fn merge_continue(&self) -> Result<Id<'_>, merge_continue::Error> {
+        let state = self.merge_state()?.ok_or(merge_continue::Error::NotInProgress)?;
+        let index = self.index_or_empty()?;
+        if let Some(entry) = index
+            .entries()
+            .iter()
+            .find(|entry| entry.stage() != gix_index::entry::Stage::Unconflicted)
+        {
+            return Err(merge_continue::Error::UnresolvedConflicts {
+                rela_path: entry.path(&index).to_owned(),
+            });
+        }
+
+        let message = strip_comments(state.message.as_ref());
+        if message.is_empty() {
+            return Err(merge_continue::Error::EmptyMessage);
+        }
+        self.rerere()?;
+
+        let mut editor = self.edit_tree(ObjectId::empty_tree(self.object_hash()))?;
+        for entry in index.entries() {
+            let Some(mode) = entry.mode.to_tree_entry_mode() else {
+                continue;
+            };
+            editor.upsert(entry.path(&index), mode.kind(), entry.id)?;
+        }
+        let tree_id = editor.write()?;
+
+        let head_id = self.head_id()?.detach();
+        let mut parents = vec![head_id];
+        for head in state.heads {
+            if !parents.contains(&head) {
+                parents.push(head);
+            }
+        }
+        if !state.no_ff {
+            parents = self.without_ancestors(parents)?;
+        }
+
+        let commit = self.new_commit(message.to_str_lossy(), tree_id, parents.iter().copied())?;
+        self.edit_reference(RefEdit {
+            change: Change::Update {
+                log: LogChange {
+                    mode: RefLog::AndReference,
+                    force_create_reflog: false,
+                    message: crate::reference::log::message("commit", message.as_ref(), parents.len()),
+                },
+                expected: PreviousValue::MustExistAndMatch(Target::Object(head_id)),
+                new: Target::Object(commit.id),
+            },
+            name: "HEAD".try_into().expect("valid"),
+            deref: true,
+        })?;
+        self.remove_merge_state()?;
+        Ok(commit.id.attach(self))
+    }
+
+    /// Abort the merge in progress by resetting the index and the worktree to `ORIG_HEAD`,
+    /// and by removing all merge state, like `git merge --abort` would.
+    ///
+    /// If `HEAD` was moved in the meantime, it's set back to `ORIG_HEAD` as well.
+    pub fn merge_abort(&self) -> Result<(), merge_abort::Error> {
+        if self.merge_state()?.is_none() {
+            return Err(merge_abort::Error::NotInProgress);
+        }
+        let orig_head = self.find_reference(ORIG_HEAD)?.peel_to_id()?.detach();
+        let tree_id = self.find_commit(orig_head)?.tree_id()?.detach();
+
+        let index = self.index_or_empty()?;
+        let (new_index, _checkout) = self.checkout_tree_over_index(&index, tree_id)?;
+        gix_index::File::from_state(new_index, self.index_path())
+            .write(Default::default())
+            .map_err(checkout::Error::from)?;
+
+        let head_id = self.head_id()?.detach();
+        if head_id != orig_head {
+            self.edit_reference(RefEdit {
+                change: Change::Update {
+                    log: LogChange {
+                        mode: RefLog::AndReference,
+                        force_create_reflog: false,
+                        message: "reset: moving to ORIG_HEAD".into(),
+                    },
+                    expected: PreviousValue::MustExistAndMatch(Target::Object(head_id)),
+                    new: Target::Object(orig_head),
+                },
+                name: "HEAD".try_into().expect("valid"),
+                deref: true,
+            })?;
+        }
+        self.remove_merge_state()?;
+        Ok(())
+    }
+
+    fn set_pseudo_ref(&self, name: &str, id: ObjectId, message: &str) -> Result<(), crate::reference::edit::Error> {
+        self.edit_reference(RefEdit {
+            change: Change::Update {
+                log: LogChange {
+                    mode: RefLog::AndReference,
+                    force_create_reflog: false,
+                    message: message.into(),
+                },
+                expected: PreviousValue::Any,
+                new: Target::Object(id),
+            },
+            name: name.try_into().expect("valid pseudo-ref name"),
+            deref: false,
+        })?;
+        Ok(())
+    }
+
+    fn remove_merge_state(&self) -> Result<(), merge_abort::remove_state::Error> {
+        for name in [MERGE_HEAD, MERGE_MSG, MERGE_MODE, MERGE_RR] {
+            let path = self.git_dir().join(name);
+            match std::fs::remove_file(&path) {
+                Ok(()) => {}
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+                Err(source) => return Err(merge_abort::remove_state::Error::RemoveFile { path, source }),
+            }
+        }
+        if let Some(auto_merge) = self.try_find_reference(AUTO_MERGE)? {
+            auto_merge.delete()?;
+        }
+        Ok(())
+    }
+
+    /// Remove all commits in `commits` that are ancestors of other commits in the list, keeping the order.
+    fn without_ancestors(&self, commits: Vec<ObjectId>) -> Result<Vec<ObjectId>, merge_continue::Error> {
+        let commit_graph = self.commit_graph_if_enabled()?;
+        let mut graph = self.revision_graph(commit_graph.as_ref());
+        let mut out = Vec::with_capacity(commits.len());
+        for (idx, commit) in commits.iter().enumerate() {
+            let mut is_ancestor = false;
+            for (other_idx, other) in commits.iter().enumerate() {
+                if idx == other_idx {
+                    continue;
+                }
+                let bases = gix_revision::merge_base(*commit, &[*other], &mut graph)?;
+                graph.clear();
+                if bases.is_some_and(|bases| bases.contains(commit)) {
+                    is_ancestor = true;
+                    break;
+                }
+            }
+            if !is_ancestor {
+                out.push(*commit);
+            }
+        }
+        Ok(out)
+    }
+
+    /// Change the worktree so that all files tracked in `index` match `tree_id`, and return a new index
+    /// that represents `tree_id`, along with the outcome of writing files to the worktree.
+    ///
+    /// Files that aren't changed keep their stat information, and untracked files are never overwritten.
+    pub(super) fn checkout_tree_over_index(
+        &self,
+        index: &gix_index::State,
+        tree_id: ObjectId,
+    ) -> Result<(gix_index::State, gix_worktree_state::checkout::Outcome), checkout::Error> {
+        let workdir = self.workdir().ok_or_else(|| checkout::Error::BareRepository {
+            git_dir: self.git_dir().to_owned(),
+        })?;
+        let mut new_index = gix_index::State::from_tree(&tree_id, &self.objects, self.config.protect_options()?)
+            .map_err(|source| checkout::Error::IndexFromTree { id: tree_id, source })?;
+
+        let mut current = BTreeMap::<&BStr, Option<&gix_index::Entry>>::new();
+        for entry in index.entries() {
+            let path = entry.path(index);
+            let unconflicted = entry.stage() == gix_index::entry::Stage::Unconflicted;
+            current.insert(path, unconflicted.then_some(entry));
+        }
+
+        let mut to_write = gix_index::State::new(self.object_hash());
+        for entry in new_index.entries() {
+            let path = entry.path(&new_index);
+            match current.remove(path) {
+                Some(Some(existing)) if existing.id == entry.id && existing.mode == entry.mode => continue,
+                Some(_) => {}
+                None => {
+                    if workdir.join(gix_path::from_bstr(path)).symlink_metadata().is_ok() {
+                        return Err(checkout::Error::UntrackedFileWouldBeOverwritten {
+                            rela_path: path.to_owned(),
+                        });
+                    }
+                }
+            }
+            to_write.dangerously_push_entry(Default::default(), entry.id, entry.flags, entry.mode, path);
+        }
+        for removed in current.keys() {
+            remove_file_and_empty_parents(workdir, removed)?;
+        }
+
+        let mut options =
+            self.checkout_options(gix_worktree::stack::state::attributes::Source::WorktreeThenIdMapping)?;
+        options.overwrite_existing = true;
+        let outcome = gix_worktree_state::checkout(
+            &mut to_write,
+            workdir,
+            self.objects.clone().into_arc().map_err(checkout::Error::OpenArcOdb)?,
+            &gix_features::progress::Discard,
+            &gix_features::progress::Discard,
+            &std::sync::atomic::AtomicBool::default(),
+            options,
+        )?;
+
+        let (entries, path_backing) = new_index.entries_mut_and_pathbacking();
+        for entry in entries {
+            let path = entry.path_in(path_backing);
+            let source = match to_write.entry_by_path(path) {
+                Some(written) => Some(written),
+                None => index.entry_by_path(path),
+            };
+            if let Some(source) = source {
+                entry.stat = source.stat;
+            }
+        }
+        Ok((new_index, outcome))
+    }
+}
+
+/// Remove the file at `rela_path` in `workdir` if it exists, along with all parent directories that are empty then.
+pub(super) fn remove_file_and_empty_parents(workdir: &Path, rela_path: &BStr) -> Result<(), checkout::Error> {
+    let path = workdir.join(gix_path::from_bstr(rela_path));
+    match std::fs::remove_file(&path) {
+        Ok(()) => {}
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(source) => return Err(checkout::Error::RemoveFile { path, source }),
+    }
+    let mut dir = path.parent();
+    while let Some(parent) = dir.filter(|dir| *dir != workdir) {
+        if std::fs::remove_dir(parent).is_err() {
+            break;
+        }
+        dir = parent.parent();
+    }
+    Ok(())
+}
+
+/// Remove all comment lines from `message` along with leading and trailing empty lines,
+/// similar to `git commit --cleanup=strip`.
+fn strip_comments(message: &BStr) -> BString {
+    let mut out = BString::default();
+    for line in message.lines() {
+        if line.starts_with(b"#") {
+            continue;
+        }
+        let line = line.trim_end();
+        if line.is_empty() && (out.is_empty() || out.ends_with(b"\n\n")) {
+            continue;
+        }
+        out.push_str(line);
+        out.push(b'\n');
+    }
+    while out.ends_with(b"\n\n") {
+        out.pop();
+    }
+    out
+}
@@ -14,6 +14,8 @@ pub enum Kind {
     LinkedWorkTree,
 }
 
+#[cfg(feature = "apply")]
+mod apply;
 #[cfg(any(feature = "attributes", feature = "excludes"))]
 pub mod attributes;
 ///
@@ -47,13 +49,21 @@ mod mailmap;
 ///
 #[cfg(feature = "merge")]
 mod merge;
+#[cfg(all(feature = "merge", feature = "worktree-mutation", feature = "status"))]
+mod merge_in_progress;
 mod object;
 #[cfg(feature = "attributes")]
 mod pathspec;
+mod promisor;
 mod reference;
 mod remote;
+///
+#[cfg(feature = "merge")]
+pub mod rerere;
 mod revision;
 mod shallow;
+#[cfg(all(feature = "merge", feature = "worktree-mutation", feature = "status"))]
+mod stash;
 mod state;
 #[cfg(feature = "attributes")]
 mod submodule;
@@ -90,6 +100,136 @@ mod new_commit_as {
     }
 }
 
+///
+#[cfg(feature = "apply")]
+pub mod apply_patch {
+    use crate::bstr::BString;
+
+    /// Where [Repository::apply_patch()](crate::Repository::apply_patch()) applies patches.
+    #[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Target {
+        /// Apply patches to files in the worktree, like `git apply` does.
+        #[default]
+        Worktree,
+        /// Apply patches to files in the index without touching the worktree, like `git apply --cached` does.
+        Index,
+        /// Apply patches to files in the index and the worktree, which have to match, like `git apply --index` does.
+        WorktreeAndIndex,
+    }
+
+    /// Options for [Repository::apply_patch()](crate::Repository::apply_patch()).
+    #[derive(Default, Debug, Clone, Copy)]
+    pub struct Options {
+        /// Where to apply the patches.
+        pub target: Target,
+        /// How to apply the patches to the content of each file.
+        ///
+        /// [`object_hash`](gix_apply::apply::Options::object_hash) is always set to the hash of the repository.
+        pub apply: gix_apply::apply::Options,
+        /// If `true`, merge patches that don't apply cleanly into the current version of their file if the version
+        /// they were created from is in the object database, like `git apply --3way` does.
+        ///
+        /// Conflicts are written to the index as stages, and with conflict markers to the worktree unless the target is
+        /// [`Target::Index`]. Thus [`Target::Worktree`] is treated like [`Target::WorktreeAndIndex`].
+        pub three_way: bool,
+    }
+
+    /// The outcome of applying a patch to a single file.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct File {
+        /// The path of the file after the change, or before the change if it was deleted.
+        pub path: BString,
+        /// Information about how each hunk was applied, which is empty for binary patches and three-way merges.
+        pub hunks: Vec<gix_apply::apply::HunkOutcome>,
+        /// Whitespace errors on added lines.
+        pub whitespace_errors: Vec<gix_apply::apply::WhitespaceError>,
+        /// If `true`, the patch didn't apply cleanly and was merged into the file instead.
+        pub merged: bool,
+    }
+
+    /// The outcome of [Repository::apply_patch()](crate::Repository::apply_patch()).
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct Outcome {
+        /// The outcome for each patch, in order.
+        pub files: Vec<File>,
+        /// The paths of files that were merged with conflicts, which are recorded as stages in the index.
+        pub conflicting_paths: Vec<BString>,
+    }
+
+    /// The error returned by [Repository::apply_patch()](crate::Repository::apply_patch())
+    /// and [Repository::apply_patch_to_tree()](crate::Repository::apply_patch_to_tree()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Cannot apply patches to the worktree of a bare repository")]
+        BareRepository,
+        #[error("The file at '{path}' to be created already exists")]
+        AlreadyExists { path: BString },
+        #[error("The file at '{path}' to be changed does not exist")]
+        DoesNotExist { path: BString },
+        #[error("The file at '{path}' in the worktree doesn't match the index")]
+        WorktreeDiffersFromIndex { path: BString },
+        #[error("The file at '{path}' has unresolved conflicts in the index")]
+        Conflicted { path: BString },
+        #[error("Cannot apply a patch to the submodule at '{path}'")]
+        Submodule { path: BString },
+        #[error("Refusing to apply a patch to the invalid path '{path}'")]
+        InvalidPath {
+            path: BString,
+            source: gix_validate::path::component::Error,
+        },
+        #[error("Refusing to apply a patch to '{path}' as it is beyond a symbolic link")]
+        BeyondSymlink { path: BString },
+        #[error(transparent)]
+        ValidationOptions(#[from] crate::config::boolean::Error),
+        #[error("Could not apply the patch to '{path}'")]
+        Apply {
+            path: BString,
+            source: gix_apply::apply::Error,
+        },
+        #[error("Could not merge the patch into '{path}'")]
+        ThreeWay {
+            path: BString,
+            source: gix_apply::three_way::Error,
+        },
+        #[error("Could not access '{}' in the worktree", path.display())]
+        Io {
+            path: std::path::PathBuf,
+            source: std::io::Error,
+        },
+        #[error(transparent)]
+        LookupPrefix(#[from] gix_odb::store::prefix::lookup::Error),
+        #[error(transparent)]
+        FindObject(#[from] crate::object::find::existing::Error),
+        #[error(transparent)]
+        WriteBlob(#[from] crate::object::write::Error),
+        #[error(transparent)]
+        OpenIndex(#[from] crate::worktree::open_index::Error),
+        #[error(transparent)]
+        WriteIndex(#[from] gix_index::file::write::Error),
+    }
+}
+
+///
+#[cfg(feature = "apply")]
+pub mod apply_patch_to_tree {
+    /// The error returned by [Repository::apply_patch_to_tree()](crate::Repository::apply_patch_to_tree()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        ApplyPatch(#[from] super::apply_patch::Error),
+        #[error(transparent)]
+        FindTree(#[from] crate::object::find::existing::with_conversion::Error),
+        #[error(transparent)]
+        EditTree(#[from] super::edit_tree::Error),
+        #[error(transparent)]
+        Edit(#[from] gix_object::tree::editor::Error),
+        #[error(transparent)]
+        WriteTree(#[from] crate::object::tree::editor::write::Error),
+    }
+}
+
 ///
 #[cfg(feature = "blame")]
 pub mod blame_file {
@@ -98,18 +238,92 @@ pub mod blame_file {
     pub struct Options {
         /// The algorithm to use for diffing. If `None`, `diff.algorithm` will be used.
         pub diff_algorithm: Option<gix_diff::blob::Algorithm>,
+        /// Which differences in whitespace to ignore when comparing lines.
+        ///
+        /// See [`gix_blame::Options::whitespace`] for details.
+        pub whitespace: gix_diff::blob::Whitespace,
         /// The ranges to blame in the file.
         pub ranges: gix_blame::BlameRanges,
         /// Don't consider commits before the given date.
         pub since: Option<gix_date::Time>,
         /// Determine if rename tracking should be performed, and how.
         pub rewrites: Option<gix_diff::Rewrites>,
+        /// Commits to ignore, in addition to the ones listed in `ignore_revs_files`.
+        ///
+        /// See [`gix_blame::Options::ignore_revs`] for details.
+        pub ignore_revs: Vec<gix_hash::ObjectId>,
+        /// Files that list commits to ignore, one full hexadecimal object id per line, with `#` starting a comment.
+        /// Relative paths are relative to the working tree, or to the `.git` directory in bare repositories.
+        ///
+        /// If `None`, the files configured in `blame.ignoreRevsFile` will be used.
+        pub ignore_revs_files: Option<Vec<std::path::PathBuf>>,
+        /// Mark lines that were attributed to a parent of an ignored commit. If `None`, `blame.markIgnoredLines` will be used.
+        pub mark_ignored_lines: Option<bool>,
+        /// Mark lines that were changed by an ignored commit, but couldn't be attributed to a parent.
+        /// If `None`, `blame.markUnblamableLines` will be used.
+        pub mark_unblamable_lines: Option<bool>,
+        /// If set, blame this version of the file on top of the suspect, with all of its changes attributed to a
+        /// commit identified by the null object id, similar to the lines `git blame` shows as *Not Committed Yet*.
+        ///
+        /// This is ignored by [Repository::blame_files()](crate::Repository::blame_files()).
+        pub uncommitted: Option<Uncommitted>,
+        /// If set, blame in reverse with `suspect` being the oldest commit and this one being the newest.
+        ///
+        /// See [`gix_blame::Options::reverse_until`] for details.
+        pub reverse_until: Option<gix_hash::ObjectId>,
+        /// If set, attribute lines that were moved within the file or copied from other files to the commit they came from.
+        ///
+        /// See [`gix_blame::Options::moves_and_copies`] for details.
+        pub moves_and_copies: Option<gix_blame::MovesAndCopies>,
+    }
+
+    /// A version of the *Blamed File* that isn't committed yet.
+    #[derive(Debug, Clone)]
+    pub enum Uncommitted {
+        /// Use the file in the worktree, after converting it to the form `git` would store it in.
+        Worktree,
+        /// Use the file as it is staged in the index.
+        Index,
+        /// Use the given content, which is expected to be in the form `git` would store it in.
+        Blob(Vec<u8>),
     }
 
     /// The error returned by [Repository::blame_file()](crate::Repository::blame_file()).
     #[derive(Debug, thiserror::Error)]
     #[allow(missing_docs)]
     pub enum Error {
+        #[error("Could not interpolate the path of a file with commits to ignore")]
+        IgnoreRevsFilePath(#[from] gix_config::path::interpolate::Error),
+        #[error("Could not read the file with commits to ignore at '{}'", path.display())]
+        ReadIgnoreRevsFile {
+            path: std::path::PathBuf,
+            source: std::io::Error,
+        },
+        #[error("Could not parse line '{line}' of the file with commits to ignore at '{}'", path.display())]
+        ParseIgnoreRevsFile {
+            path: std::path::PathBuf,
+            line: crate::bstr::BString,
+            source: gix_hash::decode::Error,
+        },
+        #[error(transparent)]
+        MarkLines(#[from] crate::config::boolean::Error),
+        #[error("Blaming uncommitted changes in the worktree requires a repository with worktree")]
+        MissingWorktree,
+        #[error(transparent)]
+        FilterPipeline(#[from] crate::repository::filter::pipeline::Error),
+        #[error(transparent)]
+        ConvertToGit(#[from] crate::filter::pipeline::convert_to_git::Error),
+        #[error("Could not read the file at '{}' in the worktree", path.display())]
+        ReadWorktreeFile {
+            path: std::path::PathBuf,
+            source: std::io::Error,
+        },
+        #[error(transparent)]
+        OpenIndex(#[from] crate::worktree::open_index::Error),
+        #[error("The file '{file_path}' is not staged in the index")]
+        MissingIndexEntry { file_path: crate::bstr::BString },
+        #[error(transparent)]
+        FindBlob(#[from] crate::object::find::existing::with_conversion::Error),
         #[error(transparent)]
         CommitGraphIfEnabled(#[from] super::commit_graph_if_enabled::Error),
         #[error(transparent)]
@@ -213,6 +427,387 @@ pub mod merge_commits {
     }
 }
 
+///
+#[cfg(feature = "merge")]
+pub mod merge_commits_octopus {
+    /// The error returned by [Repository::merge_commits_octopus()](crate::Repository::merge_commits_octopus()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        OpenCommitGraph(#[from] super::commit_graph_if_enabled::Error),
+        #[error(transparent)]
+        MergeResourceCache(#[from] super::merge_resource_cache::Error),
+        #[error(transparent)]
+        DiffResourceCache(#[from] super::diff_resource_cache::Error),
+        #[error(transparent)]
+        OctopusMerge(#[from] gix_merge::commit::octopus::Error),
+    }
+}
+
+///
+#[cfg(all(feature = "merge", feature = "worktree-mutation", feature = "status"))]
+pub mod merge_into_head {
+    /// The error returned by [Repository::merge_into_head()](crate::Repository::merge_into_head()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Cannot merge while another operation is in progress: {state:?}")]
+        OperationInProgress { state: crate::state::InProgress },
+        #[error("Cannot merge as the index or worktree have changes")]
+        Dirty,
+        #[error(transparent)]
+        IsDirty(#[from] crate::status::is_dirty::Error),
+        #[error(transparent)]
+        HeadId(#[from] crate::reference::head_id::Error),
+        #[error(transparent)]
+        MergeCommits(#[from] super::merge_commits::Error),
+        #[error(transparent)]
+        WriteTree(#[from] crate::object::tree::editor::write::Error),
+        #[error(transparent)]
+        OpenIndex(#[from] crate::worktree::open_index::Error),
+        #[error(transparent)]
+        Checkout(#[from] crate::merge::in_progress::checkout::Error),
+        #[error(transparent)]
+        EditReference(#[from] crate::reference::edit::Error),
+        #[error("Could not write '{}'", path.display())]
+        WriteFile {
+            path: std::path::PathBuf,
+            source: std::io::Error,
+        },
+        #[error(transparent)]
+        Rerere(#[from] super::rerere::Error),
+    }
+}
+
+///
+#[cfg(all(feature = "merge", feature = "worktree-mutation", feature = "status"))]
+pub mod merge_state {
+    /// The error returned by [Repository::merge_state()](crate::Repository::merge_state()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not read '{}'", path.display())]
+        ReadFile {
+            path: std::path::PathBuf,
+            source: std::io::Error,
+        },
+        #[error("Could not parse '{line}' in MERGE_HEAD as object id")]
+        ParseId {
+            line: crate::bstr::BString,
+            source: gix_hash::decode::Error,
+        },
+    }
+}
+
+///
+#[cfg(all(feature = "merge", feature = "worktree-mutation", feature = "status"))]
+pub mod merge_continue {
+    /// The error returned by [Repository::merge_continue()](crate::Repository::merge_continue()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("There is no merge in progress")]
+        NotInProgress,
+        #[error("There are unresolved conflicts in the index, for example at '{rela_path}'")]
+        UnresolvedConflicts { rela_path: crate::bstr::BString },
+        #[error("The merge commit message is empty")]
+        EmptyMessage,
+        #[error(transparent)]
+        MergeState(#[from] super::merge_state::Error),
+        #[error(transparent)]
+        OpenIndex(#[from] crate::worktree::open_index::Error),
+        #[error(transparent)]
+        HeadId(#[from] crate::reference::head_id::Error),
+        #[error(transparent)]
+        EditTree(#[from] super::edit_tree::Error),
+        #[error(transparent)]
+        EditTreeEntry(#[from] gix_object::tree::editor::Error),
+        #[error(transparent)]
+        WriteTree(#[from] crate::object::tree::editor::write::Error),
+        #[error(transparent)]
+        OpenCommitGraph(#[from] super::commit_graph_if_enabled::Error),
+        #[error(transparent)]
+        MergeBase(#[from] gix_revision::merge_base::Error),
+        #[error(transparent)]
+        NewCommit(#[from] super::new_commit::Error),
+        #[error(transparent)]
+        EditReference(#[from] crate::reference::edit::Error),
+        #[error(transparent)]
+        Rerere(#[from] super::rerere::Error),
+        #[error(transparent)]
+        RemoveState(#[from] super::merge_abort::remove_state::Error),
+    }
+}
+
+///
+#[cfg(all(feature = "merge", feature = "worktree-mutation", feature = "status"))]
+pub mod merge_abort {
+    /// The error returned by [Repository::merge_abort()](crate::Repository::merge_abort()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("There is no merge in progress")]
+        NotInProgress,
+        #[error(transparent)]
+        MergeState(#[from] super::merge_state::Error),
+        #[error(transparent)]
+        FindOrigHead(#[from] crate::reference::find::existing::Error),
+        #[error(transparent)]
+        PeelOrigHead(#[from] crate::reference::peel::Error),
+        #[error(transparent)]
+        FindCommit(#[from] crate::object::find::existing::with_conversion::Error),
+        #[error(transparent)]
+        CommitTree(#[from] gix_object::decode::Error),
+        #[error(transparent)]
+        HeadId(#[from] crate::reference::head_id::Error),
+        #[error(transparent)]
+        OpenIndex(#[from] crate::worktree::open_index::Error),
+        #[error(transparent)]
+        Checkout(#[from] crate::merge::in_progress::checkout::Error),
+        #[error(transparent)]
+        EditReference(#[from] crate::reference::edit::Error),
+        #[error(transparent)]
+        RemoveState(#[from] remove_state::Error),
+    }
+
+    ///
+    pub mod remove_state {
+        /// The error returned when removing the files and references that keep the state of a merge in progress.
+        #[derive(Debug, thiserror::Error)]
+        #[allow(missing_docs)]
+        pub enum Error {
+            #[error("Could not remove '{}'", path.display())]
+            RemoveFile {
+                path: std::path::PathBuf,
+                source: std::io::Error,
+            },
+            #[error(transparent)]
+            FindReference(#[from] crate::reference::find::Error),
+            #[error(transparent)]
+            DeleteReference(#[from] crate::reference::edit::Error),
+        }
+    }
+}
+
+///
+#[cfg(all(feature = "merge", feature = "worktree-mutation", feature = "status"))]
+pub mod stash_create {
+    /// The error returned by [Repository::stash_create()](crate::Repository::stash_create()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Cannot stash changes in a bare repository")]
+        BareRepository,
+        #[error("Cannot stash changes while there are unresolved conflicts, for example at '{rela_path}'")]
+        UnresolvedConflicts { rela_path: crate::bstr::BString },
+        #[error(transparent)]
+        OpenIndex(#[from] crate::worktree::open_index::Error),
+        #[error(transparent)]
+        HeadCommit(#[from] crate::reference::head_commit::Error),
+        #[error(transparent)]
+        HeadName(#[from] crate::reference::find::existing::Error),
+        #[error(transparent)]
+        DecodeCommit(#[from] gix_object::decode::Error),
+        #[error(transparent)]
+        EditTree(#[from] super::edit_tree::Error),
+        #[error(transparent)]
+        EditTreeEntry(#[from] gix_object::tree::editor::Error),
+        #[error(transparent)]
+        WriteTree(#[from] crate::object::tree::editor::write::Error),
+        #[error(transparent)]
+        Status(#[from] crate::status::Error),
+        #[error(transparent)]
+        StatusIter(#[from] crate::status::into_iter::Error),
+        #[error(transparent)]
+        StatusItem(#[from] crate::status::index_worktree::Error),
+        #[error(transparent)]
+        FilterPipeline(#[from] super::filter::pipeline::Error),
+        #[error(transparent)]
+        WorktreeFileToObject(#[from] crate::filter::pipeline::worktree_file_to_object::Error),
+        #[error("Could not read directory at '{}'", path.display())]
+        ReadDirectory {
+            path: std::path::PathBuf,
+            source: std::io::Error,
+        },
+        #[error("The path at '{}' could not be converted to UTF-8", path.display())]
+        IllformedUtf8 { path: std::path::PathBuf },
+        #[error(transparent)]
+        NewCommit(#[from] super::new_commit::Error),
+    }
+}
+
+///
+#[cfg(all(feature = "merge", feature = "worktree-mutation", feature = "status"))]
+pub mod stash_store {
+    /// The error returned by [Repository::stash_store()](crate::Repository::stash_store()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Commit {id} is not a stash commit as it has less than two parents")]
+        NotAStash { id: gix_hash::ObjectId },
+        #[error(transparent)]
+        FindCommit(#[from] crate::object::find::existing::with_conversion::Error),
+        #[error(transparent)]
+        EditReference(#[from] crate::reference::edit::Error),
+    }
+}
+
+///
+#[cfg(all(feature = "merge", feature = "worktree-mutation", feature = "status"))]
+pub mod stash_push {
+    /// The error returned by [Repository::stash_push()](crate::Repository::stash_push()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Create(#[from] super::stash_create::Error),
+        #[error(transparent)]
+        Store(#[from] super::stash_store::Error),
+        #[error(transparent)]
+        HeadTreeId(#[from] crate::reference::head_tree_id::Error),
+        #[error(transparent)]
+        BooleanConfig(#[from] crate::config::boolean::Error),
+        #[error(transparent)]
+        OpenIndex(#[from] crate::worktree::open_index::Error),
+        #[error(transparent)]
+        Checkout(#[from] crate::merge::in_progress::checkout::Error),
+    }
+}
+
+///
+#[cfg(all(feature = "merge", feature = "worktree-mutation", feature = "status"))]
+pub mod stash_list {
+    /// The error returned by [Repository::stash_list()](crate::Repository::stash_list()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        FindReference(#[from] crate::reference::find::Error),
+        #[error("Could not read the reflog of refs/stash")]
+        ReadReflog(#[from] std::io::Error),
+        #[error(transparent)]
+        DecodeReflog(#[from] gix_ref::file::log::iter::decode::Error),
+    }
+}
+
+///
+#[cfg(all(feature = "merge", feature = "worktree-mutation", feature = "status"))]
+pub mod stash_drop {
+    /// The error returned by [Repository::stash_drop()](crate::Repository::stash_drop()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("There is no stash entry at index {index}")]
+        NoSuchEntry { index: usize },
+        #[error(transparent)]
+        List(#[from] super::stash_list::Error),
+        #[error(transparent)]
+        FindReference(#[from] crate::reference::find::existing::Error),
+        #[error(transparent)]
+        EditReference(#[from] crate::reference::edit::Error),
+        #[error("Could not write the reflog at '{}'", path.display())]
+        WriteReflog {
+            path: std::path::PathBuf,
+            source: std::io::Error,
+        },
+    }
+}
+
+///
+#[cfg(all(feature = "merge", feature = "worktree-mutation", feature = "status"))]
+pub mod stash_apply {
+    /// The error returned by [Repository::stash_apply()](crate::Repository::stash_apply()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Cannot apply a stash while another operation is in progress: {state:?}")]
+        OperationInProgress { state: crate::state::InProgress },
+        #[error("Cannot apply a stash as the index or worktree have changes")]
+        Dirty,
+        #[error("There is no stash entry at index {index}")]
+        NoSuchEntry { index: usize },
+        #[error("Commit {id} is not a stash commit as it doesn't have two or three parents")]
+        NotAStash { id: gix_hash::ObjectId },
+        #[error("The stashed changes to the index conflict with the changes since the stash was created")]
+        IndexConflicts,
+        #[error("The untracked file at '{rela_path}' already exists")]
+        UntrackedFileExists { rela_path: crate::bstr::BString },
+        #[error(transparent)]
+        List(#[from] super::stash_list::Error),
+        #[error(transparent)]
+        IsDirty(#[from] crate::status::is_dirty::Error),
+        #[error(transparent)]
+        FindCommit(#[from] crate::object::find::existing::with_conversion::Error),
+        #[error(transparent)]
+        FindTreeEntry(#[from] crate::object::find::existing::Error),
+        #[error(transparent)]
+        DecodeCommit(#[from] gix_object::decode::Error),
+        #[error(transparent)]
+        HeadTreeId(#[from] crate::reference::head_tree_id::Error),
+        #[error(transparent)]
+        TreeMergeOptions(#[from] super::tree_merge_options::Error),
+        #[error(transparent)]
+        MergeTrees(#[from] super::merge_trees::Error),
+        #[error(transparent)]
+        WriteTree(#[from] crate::object::tree::editor::write::Error),
+        #[error(transparent)]
+        OpenIndex(#[from] crate::worktree::open_index::Error),
+        #[error(transparent)]
+        Checkout(#[from] crate::merge::in_progress::checkout::Error),
+        #[error(transparent)]
+        Rerere(#[from] super::rerere::Error),
+    }
+}
+
+///
+#[cfg(all(feature = "merge", feature = "worktree-mutation", feature = "status"))]
+pub mod stash_pop {
+    /// The error returned by [Repository::stash_pop()](crate::Repository::stash_pop()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Apply(#[from] super::stash_apply::Error),
+        #[error(transparent)]
+        Drop(#[from] super::stash_drop::Error),
+    }
+}
+
+///
+#[cfg(all(feature = "merge", feature = "worktree-mutation", feature = "status"))]
+pub mod stash_branch {
+    /// The error returned by [Repository::stash_branch()](crate::Repository::stash_branch()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        ValidateName(#[from] gix_validate::reference::name::Error),
+        #[error(transparent)]
+        Apply(#[from] super::stash_apply::Error),
+        #[error(transparent)]
+        List(#[from] super::stash_list::Error),
+        #[error(transparent)]
+        IsDirty(#[from] crate::status::is_dirty::Error),
+        #[error(transparent)]
+        FindCommit(#[from] crate::object::find::existing::with_conversion::Error),
+        #[error(transparent)]
+        DecodeCommit(#[from] gix_object::decode::Error),
+        #[error(transparent)]
+        HeadName(#[from] crate::reference::find::existing::Error),
+        #[error(transparent)]
+        HeadId(#[from] crate::reference::head_id::Error),
+        #[error(transparent)]
+        EditReference(#[from] crate::reference::edit::Error),
+        #[error(transparent)]
+        OpenIndex(#[from] crate::worktree::open_index::Error),
+        #[error(transparent)]
+        Checkout(#[from] crate::merge::in_progress::checkout::Error),
+        #[error(transparent)]
+        Drop(#[from] super::stash_drop::Error),
+    }
+}
+
 ///
 #[cfg(feature = "merge")]
 pub mod virtual_merge_base {
@@ -339,6 +934,24 @@ pub mod edit_tree {
     }
 }
 
+///
+#[cfg(feature = "blocking-network-client")]
+pub mod fetch_promised_objects {
+    /// The error returned by [`Repository::fetch_promised_objects()`](crate::Repository::fetch_promised_objects()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The repository has no promisor remote to fetch missing objects from")]
+        NoPromisorRemote,
+        #[error(transparent)]
+        FindRemote(#[from] crate::remote::find::existing::Error),
+        #[error(transparent)]
+        Connect(#[from] crate::remote::connect::Error),
+        #[error(transparent)]
+        Fetch(#[from] crate::remote::fetch::Error),
+    }
+}
+
 ///
 #[cfg(feature = "revision")]
 pub mod merge_base {
@@ -388,6 +1001,21 @@ pub mod commit_graph_if_enabled {
     }
 }
 
+///
+pub mod migrate_ref_storage {
+    /// The error returned by [Repository::migrate_ref_storage()](crate::Repository::migrate_ref_storage()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        RefStorage(#[from] crate::config::key::GenericErrorWithValue),
+        #[error(transparent)]
+        OpenStore(#[from] gix_ref::store::init::Error),
+        #[error(transparent)]
+        Migrate(#[from] gix_ref::store::migrate::Error),
+    }
+}
+
 ///
 #[cfg(feature = "index")]
 pub mod index_from_tree {
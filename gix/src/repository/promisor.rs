@@ -0,0 +1,109 @@
+use std::borrow::Cow;
+
+use crate::{
+    Repository,
+    bstr::BStr,
+    config::tree::{Extensions, Remote, Section},
+};
+
+impl Repository {
+    /// Return `true` if the repository is a partial clone, i.e. may lack objects that a promisor remote promised to provide.
+    pub fn is_partial_clone(&self) -> bool {
+        !self.promisor_remote_names().is_empty()
+    }
+
+    /// Return the names of all remotes that promised to provide objects missing in this repository, in the order in which
+    /// they should be asked for them.
+    ///
+    /// This is the remote named by `extensions.partialClone`, followed by all remotes with `remote.<name>.promisor` set.
+    pub fn promisor_remote_names(&self) -> Vec<Cow<'_, BStr>> {
+        let mut filter = self.filter_config_section();
+        let mut names: Vec<_> = self
+            .config
+            .resolved
+            .string_filter(Extensions::PARTIAL_CLONE, &mut filter)
+            .into_iter()
+            .collect();
+        for name in self.remote_names() {
+            let is_promisor = self
+                .config
+                .resolved
+                .boolean_filter(
+                    &format!("{}.{}.{}", Remote.name(), name, Remote::PROMISOR.name),
+                    &mut filter,
+                )
+                .is_some_and(|value| value.unwrap_or(false));
+            if is_promisor && !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        names
+    }
+
+    /// Return a clone of this instance whose object database doesn't fetch missing objects from promisor remotes.
+    ///
+    /// It's used while fetching, as objects looked up then, like new commits advertised by the remote or bases of thin packs,
+    /// may be missing without that being an error, just like `git` does by setting `fetch_if_missing = 0`.
+    #[cfg(any(feature = "async-network-client", feature = "blocking-network-client"))]
+    pub(crate) fn without_lazy_fetch(&self) -> Repository {
+        let mut repo = self.clone();
+        repo.objects.promisor = None;
+        repo
+    }
+
+    /// Fetch all objects identified by `ids` from the first [promisor remote](Self::promisor_remote_names()) which
+    /// provides them, and write them into the object database as promisor pack.
+    ///
+    /// The `blob:none` filter is used so only blobs that were explicitly asked for are received, just like `git` does when
+    /// lazily fetching missing objects.
+    /// Note that this happens automatically whenever an object is missing in a partial clone, and that this method is
+    /// meant to be used to fetch many objects at once.
+    #[cfg(feature = "blocking-network-client")]
+    pub fn fetch_promised_objects(
+        &self,
+        ids: &[gix_hash::ObjectId],
+    ) -> Result<Option<gix_pack::bundle::write::Outcome>, crate::repository::fetch_promised_objects::Error> {
+        let mut last_err = None;
+        for name in self.promisor_remote_names() {
+            let res = self
+                .find_remote(name.as_ref())
+                .map_err(crate::repository::fetch_promised_objects::Error::from)
+                .and_then(|remote| {
+                    remote
+                        .connect(crate::remote::Direction::Fetch)?
+                        .fetch_objects(
+                            ids,
+                            Some("blob:none"),
+                            gix_features::progress::Discard,
+                            &std::sync::atomic::AtomicBool::default(),
+                        )
+                        .map_err(Into::into)
+                });
+            match res {
+                Ok(outcome) => return Ok(outcome),
+                Err(err) => last_err = Some(err),
+            }
+        }
+        Err(last_err.unwrap_or(crate::repository::fetch_promised_objects::Error::NoPromisorRemote))
+    }
+}
+
+/// Fetches missing objects lazily by opening a new instance of the repository at `git_dir` each time.
+///
+/// That way it doesn't keep any repository handle alive, and the repository used for fetching
+/// can't trigger fetches itself.
+#[cfg(feature = "blocking-network-client")]
+pub(crate) struct LazyFetch {
+    pub git_dir: std::path::PathBuf,
+    pub options: crate::open::Options,
+}
+
+#[cfg(feature = "blocking-network-client")]
+impl gix_odb::store::promisor::Fetch for LazyFetch {
+    fn fetch(&self, ids: &[gix_hash::ObjectId]) -> Result<(), gix_odb::store::promisor::FetchError> {
+        let mut repo = crate::open_opts(&self.git_dir, self.options.clone())?;
+        repo.objects.promisor = None;
+        repo.fetch_promised_objects(ids)?;
+        Ok(())
+    }
+}
@@ -49,6 +49,9 @@ impl crate::Repository {
 
     /// Remove the currently set reference namespace and return it, affecting only this `Easy`.
     pub fn clear_namespace(&mut self) -> Option<gix_ref::Namespace> {
+        if let Some(ref_store) = self.ref_store.as_mut() {
+            ref_store.set_namespace(None);
+        }
         self.refs.namespace.take()
     }
 
@@ -64,6 +67,9 @@ impl crate::Repository {
         gix_validate::reference::name::Error: From<E>,
     {
         let namespace = gix_ref::namespace::expand(namespace)?;
+        if let Some(ref_store) = self.ref_store.as_mut() {
+            ref_store.set_namespace(Some(namespace.clone()));
+        }
         Ok(self.refs.namespace.replace(namespace))
     }
 
@@ -160,11 +166,19 @@ impl crate::Repository {
         committer: Option<gix_actor::SignatureRef<'_>>,
     ) -> Result<Vec<RefEdit>, reference::edit::Error> {
         let (file_lock_fail, packed_refs_lock_fail) = self.config.lock_timeout()?;
-        self.refs
-            .transaction()
-            .prepare(edits, file_lock_fail, packed_refs_lock_fail)?
-            .commit(committer)
-            .map_err(Into::into)
+        match &self.ref_store {
+            Some(ref_store) => ref_store
+                .transaction()
+                .prepare(edits, file_lock_fail)?
+                .commit(committer)
+                .map_err(Into::into),
+            None => self
+                .refs
+                .transaction()
+                .prepare(edits, file_lock_fail, packed_refs_lock_fail)?
+                .commit(committer)
+                .map_err(Into::into),
+        }
     }
 
     /// Return the repository head, an abstraction to help dealing with the `HEAD` reference.
@@ -356,7 +370,10 @@ impl crate::Repository {
     /// ```
     pub fn references(&self) -> Result<reference::iter::Platform<'_>, reference::iter::Error> {
         Ok(reference::iter::Platform {
-            platform: self.refs.iter()?,
+            platform: match &self.ref_store {
+                Some(ref_store) => reference::iter::Refs::Store(ref_store.iter()?),
+                None => reference::iter::Refs::Files(self.refs.iter()?),
+            },
             repo: self,
         })
     }
@@ -370,12 +387,70 @@ impl crate::Repository {
         Name: TryInto<&'a PartialNameRef, Error = E>,
         gix_ref::file::find::Error: From<E>,
     {
-        match self.refs.try_find(name) {
-            Ok(r) => match r {
-                Some(r) => Ok(Some(Reference::from_ref(r, self))),
-                None => Ok(None),
+        let name = name.try_into().map_err(gix_ref::file::find::Error::from)?;
+        let reference = match &self.ref_store {
+            Some(ref_store) => ref_store.try_find::<_, std::convert::Infallible>(name)?,
+            None => self.refs.try_find::<_, std::convert::Infallible>(name)?,
+        };
+        Ok(reference.map(|r| Reference::from_ref(r, self)))
+    }
+
+    /// Move all references and their reflogs into a store using the `to` backend, similar to `git refs migrate --ref-format`,
+    /// and set `extensions.refStorage` in the local configuration file accordingly.
+    ///
+    /// The backend currently in use is read from `extensions.refStorage`, and the new references are only put in place
+    /// once they were fully written. Concurrent writers must be stopped while the migration is in progress.
+    ///
+    /// Note that this instance doesn't see the configuration change and keeps using the previous backend, so it should be
+    /// reopened, or the returned store should be used to access references.
+    pub fn migrate_ref_storage(
+        &self,
+        to: gix_ref::store::Backend,
+    ) -> Result<gix_ref::store::migrate::Outcome, crate::repository::migrate_ref_storage::Error> {
+        use gix_config::parse::section::ValueName;
+
+        use crate::config::tree::Extensions;
+
+        let from = self
+            .config
+            .resolved
+            .string(Extensions::REF_STORAGE)
+            .map(|value| Extensions::REF_STORAGE.try_into_ref_storage(value))
+            .transpose()?
+            .unwrap_or_default();
+        let common_dir = self.common_dir().to_owned();
+        let store = gix_ref::Store::at(
+            common_dir.clone(),
+            from,
+            gix_ref::store::init::Options {
+                write_reflog: self.refs.write_reflog,
+                object_hash: self.object_hash(),
+                precompose_unicode: self.refs.precompose_unicode,
+                prohibit_windows_device_names: self.refs.prohibit_windows_device_names,
             },
-            Err(err) => Err(err.into()),
-        }
+        )?;
+        let config_path = common_dir.join("config");
+        Ok(store.migrate(to, &self.objects, &mut |backend| {
+            let mut config = gix_config::File::from_path_no_includes(config_path.clone(), gix_config::Source::Local)?;
+            if backend == gix_ref::store::Backend::Reftable {
+                config.section_mut_or_create_new("core", None)?.set(
+                    ValueName::try_from("repositoryformatversion").expect("valid"),
+                    "1".into(),
+                );
+            }
+            config.section_mut_or_create_new("extensions", None)?.set(
+                ValueName::try_from("refstorage").expect("valid"),
+                match backend {
+                    gix_ref::store::Backend::Files => "files",
+                    gix_ref::store::Backend::Reftable => "reftable",
+                }
+                .into(),
+            );
+            let mut lock =
+                gix_lock::File::acquire_to_update_resource(&config_path, gix_lock::acquire::Fail::Immediately, None)?;
+            config.write_to_filter(&mut lock, |section| section.meta().source == gix_config::Source::Local)?;
+            lock.commit()?;
+            Ok(())
+        })?)
     }
 }
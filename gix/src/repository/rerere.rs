@@ -0,0 +1,208 @@
+use gix_merge::blob::builtin_driver::text::Conflict;
+
+use crate::{
+    Repository,
+    bstr::{BStr, BString, ByteSlice},
+    config::{
+        cache::util::{ApplyLeniency, ApplyLeniencyDefaultValue},
+        tree::{Gc, Rerere},
+    },
+};
+
+const MERGE_RR: &str = "MERGE_RR";
+
+/// Reuse recorded resolutions of conflicts, compatible with `git rerere`.
+impl Repository {
+    /// Return `true` if conflicts and their resolutions should be recorded, which is the case if `rerere.enabled`
+    /// is set, or if it's unset and the `rr-cache` directory exists.
+    pub fn rerere_enabled(&self) -> Result<bool, crate::config::boolean::Error> {
+        let enabled = self
+            .config
+            .resolved
+            .boolean(Rerere::ENABLED)
+            .map(|res| Rerere::ENABLED.enrich_error(res))
+            .transpose()
+            .with_leniency(self.config.lenient_config)?;
+        Ok(enabled.unwrap_or_else(|| self.rerere_cache().dir().is_dir()))
+    }
+
+    /// Return the cache of recorded conflicts and resolutions in `$GIT_COMMON_DIR/rr-cache`, which may not exist.
+    pub fn rerere_cache(&self) -> crate::rerere::Cache {
+        crate::rerere::Cache::at(self.common_dir().join("rr-cache"))
+    }
+
+    /// Record the conflicts of all paths that are conflicted in the index and the resolutions of previously recorded
+    /// conflicts, and write recorded resolutions into conflicted files of the worktree, like `git rerere` does.
+    ///
+    /// The paths with recorded but unresolved conflicts are kept in `MERGE_RR` for the next invocation, which typically
+    /// happens when the merge is committed. If `rerere.autoUpdate` is set, the files that were resolved with a recorded
+    /// resolution are also added to the index.
+    ///
+    /// Return `None` if [rerere is disabled](Self::rerere_enabled()) or if this is a bare repository.
+    pub fn rerere(&self) -> Result<Option<crate::rerere::rerere::Outcome>, Error> {
+        let Some(workdir) = self.workdir() else {
+            return Ok(None);
+        };
+        if !self.rerere_enabled()? {
+            return Ok(None);
+        }
+        let index = self.index_or_empty()?;
+        let conflicted_paths: Vec<BString> = index
+            .entries()
+            .iter()
+            .filter(|entry| entry.stage() == gix_index::entry::Stage::Ours)
+            .map(|entry| entry.path(&index))
+            .filter(|path| {
+                index
+                    .entry_index_by_path_and_stage(path, gix_index::entry::Stage::Theirs)
+                    .is_some()
+            })
+            .map(ToOwned::to_owned)
+            .collect();
+
+        let mut attributes = self.attributes_only(
+            &index,
+            gix_worktree::stack::state::attributes::Source::WorktreeThenIdMapping,
+        )?;
+        let mut matches = attributes.selected_attribute_matches(["conflict-marker-size"]);
+        let mut marker_size = |rela_path: &BStr| -> usize {
+            attributes
+                .at_entry(rela_path, None)
+                .ok()
+                .and_then(|platform| {
+                    platform.matching_attributes(&mut matches);
+                    let size = matches.iter_selected().next()?;
+                    match size.assignment.state {
+                        gix_attributes::StateRef::Value(value) => value.as_bstr().to_str().ok()?.parse().ok(),
+                        _ => None,
+                    }
+                })
+                .filter(|size| *size > 0)
+                .unwrap_or(Conflict::DEFAULT_MARKER_SIZE.into())
+        };
+
+        let merge_rr_path = self.git_dir().join(MERGE_RR);
+        let outcome = gix_rerere::rerere(
+            gix_rerere::merge_rr::read(&merge_rr_path)?,
+            conflicted_paths.iter().map(AsRef::as_ref),
+            workdir,
+            &self.rerere_cache(),
+            &mut marker_size,
+            self.object_hash(),
+        )?;
+        let res = if outcome.entries.is_empty() {
+            match std::fs::remove_file(&merge_rr_path) {
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+                res => res,
+            }
+        } else {
+            gix_rerere::merge_rr::write(&merge_rr_path, &outcome.entries)
+        };
+        res.map_err(|source| Error::WriteMergeRr {
+            path: merge_rr_path,
+            source,
+        })?;
+
+        let auto_update = self
+            .config
+            .resolved
+            .boolean(Rerere::AUTO_UPDATE)
+            .map(|res| Rerere::AUTO_UPDATE.enrich_error(res))
+            .transpose()
+            .with_leniency(self.config.lenient_config)?
+            .unwrap_or_default();
+        if auto_update && !outcome.replayed.is_empty() {
+            self.add_resolved_to_index(index.into_owned_or_cloned(), &outcome.replayed)?;
+        }
+        Ok(Some(outcome))
+    }
+
+    /// Remove recorded conflicts and resolutions from the [`rr-cache`](Self::rerere_cache()) that expired according
+    /// to `gc.rerereResolved` and `gc.rerereUnresolved`, like `git rerere gc` does, and return their ids.
+    pub fn rerere_gc(&self) -> Result<Vec<crate::rerere::Id>, gc::Error> {
+        let defaults = crate::rerere::cache::gc::Options::default();
+        let expiry = |key: &'static crate::config::tree::gc::ExpiryInDays, default| {
+            self.config
+                .resolved
+                .string(key)
+                .map_or(Ok(default), |value| key.try_into_expiry(value))
+                .with_lenient_default_value(self.config.lenient_config, default)
+        };
+        let options = crate::rerere::cache::gc::Options {
+            resolved: expiry(&Gc::RERERE_RESOLVED, defaults.resolved)?,
+            unresolved: expiry(&Gc::RERERE_UNRESOLVED, defaults.unresolved)?,
+        };
+        Ok(self.rerere_cache().gc(std::time::SystemTime::now(), options)?)
+    }
+
+    /// Replace the conflicting stages of all `rela_paths` in `index` with the file in the worktree, and write it.
+    fn add_resolved_to_index(&self, mut index: gix_index::File, rela_paths: &[BString]) -> Result<(), Error> {
+        let workdir = self.workdir().expect("checked by caller");
+        let (mut pipeline, _) = self.filter_pipeline(None)?;
+        let mut resolved = Vec::new();
+        for rela_path in rela_paths {
+            let Some((id, kind, _)) = pipeline.worktree_file_to_object(rela_path.as_ref(), &index)? else {
+                continue;
+            };
+            let path = workdir.join(gix_path::from_bstr(rela_path.as_bstr()));
+            let stat = gix_index::fs::Metadata::from_path_no_follow(&path)
+                .ok()
+                .and_then(|md| gix_index::entry::Stat::from_fs(&md).ok())
+                .unwrap_or_default();
+            resolved.push((rela_path, id, kind, stat));
+        }
+        index.remove_entries(|_, path, _| resolved.iter().any(|(rela_path, ..)| *rela_path == path));
+        for (rela_path, id, kind, stat) in resolved {
+            index.dangerously_push_entry(
+                stat,
+                id,
+                gix_index::entry::Flags::empty(),
+                gix_object::tree::EntryMode::from(kind).into(),
+                rela_path.as_ref(),
+            );
+        }
+        index.sort_entries();
+        index.write(Default::default())?;
+        Ok(())
+    }
+}
+
+/// The error returned by [Repository::rerere()](crate::Repository::rerere()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    BooleanConfig(#[from] crate::config::boolean::Error),
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    AttributeStack(#[from] crate::config::attribute_stack::Error),
+    #[error(transparent)]
+    ReadMergeRr(#[from] gix_rerere::merge_rr::read::Error),
+    #[error(transparent)]
+    Rerere(#[from] gix_rerere::rerere::Error),
+    #[error("Could not write '{}'", path.display())]
+    WriteMergeRr {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+    #[error(transparent)]
+    FilterPipeline(#[from] crate::repository::filter::pipeline::Error),
+    #[error(transparent)]
+    WorktreeFileToObject(#[from] crate::filter::pipeline::worktree_file_to_object::Error),
+    #[error(transparent)]
+    WriteIndex(#[from] gix_index::file::write::Error),
+}
+
+///
+pub mod gc {
+    /// The error returned by [Repository::rerere_gc()](crate::Repository::rerere_gc()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        ExpiryConfig(#[from] crate::config::key::GenericErrorWithValue),
+        #[error(transparent)]
+        Gc(#[from] gix_rerere::cache::gc::Error),
+    }
+}
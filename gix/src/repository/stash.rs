@@ -0,0 +1,649 @@
+use std::collections::BTreeSet;
+
+use gix_hash::ObjectId;
+use gix_merge::tree::{TreatAsUnresolved, apply_index_entries::RemovalMode};
+use gix_ref::{
+    FullName, Target,
+    transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+};
+use gix_status::index_as_worktree::{Change as WorktreeChange, EntryStatus};
+
+use crate::{
+    Id, Repository,
+    bstr::{BStr, BString, ByteSlice},
+    merge::in_progress::checkout,
+    prelude::ObjectIdExt,
+    repository::{
+        merge_in_progress::remove_file_and_empty_parents, stash_apply, stash_branch, stash_create, stash_drop,
+        stash_list, stash_pop, stash_push, stash_store,
+    },
+    stash::{Entry, Untracked, apply, create},
+    status::index_worktree::Item,
+};
+
+const STASH: &str = "refs/stash";
+
+/// A stash commit that was created from the index and the worktree, along with the paths it affects in the worktree.
+struct Created {
+    id: ObjectId,
+    message: BString,
+    /// Tracked files whose worktree version differs from the index.
+    changed: Vec<BString>,
+    /// Untracked files that are stored in the stash.
+    untracked: Vec<BString>,
+}
+
+/// Stash changes to the index and the worktree and restore them later, compatible with `git stash`.
+///
+/// Stash entries are commits whose tree represents the worktree, with the commit `HEAD` pointed to as first parent,
+/// a commit representing the index as second parent, and optionally a commit representing untracked files as third parent.
+/// They are listed in the reflog of `refs/stash`.
+impl Repository {
+    /// Create a stash commit from the changes in the index and the worktree as configured by `options`, without storing it
+    /// in `refs/stash` or changing the index or the worktree, like `git stash create` does.
+    ///
+    /// Return `None` if there are no changes to stash.
+    pub fn stash_create(&self, options: create::Options) -> Result<Option<Id<'_>>, stash_create::Error> {
+        Ok(self.create_stash(options)?.map(|created| created.id.attach(self)))
+    }
+
+    /// Store the stash commit `id` in `refs/stash` as newest entry with `message` in its reflog, like `git stash store` does.
+    pub fn stash_store(&self, id: impl Into<ObjectId>, message: &BStr) -> Result<(), stash_store::Error> {
+        let id = id.into();
+        if self.find_commit(id)?.parent_ids().count() < 2 {
+            return Err(stash_store::Error::NotAStash { id });
+        }
+        self.edit_reference(RefEdit {
+            change: Change::Update {
+                log: LogChange {
+                    mode: RefLog::AndReference,
+                    force_create_reflog: true,
+                    message: message.into(),
+                },
+                expected: PreviousValue::Any,
+                new: Target::Object(id),
+            },
+            name: STASH.try_into().expect("valid"),
+            deref: false,
+        })?;
+        Ok(())
+    }
+
+    /// Create a stash commit from the changes in the index and the worktree as configured by `options`, store it as newest
+    /// stash entry and reset the index and the worktree to `HEAD`, removing stashed untracked files, like `git stash push` does.
+    ///
+    /// Return `None` if there are no changes to stash, in which case nothing is changed.
+    pub fn stash_push(&self, options: create::Options) -> Result<Option<Id<'_>>, stash_push::Error> {
+        let Some(created) = self.create_stash(options)? else {
+            return Ok(None);
+        };
+        self.stash_store(created.id, created.message.as_ref())?;
+
+        let workdir = self.workdir().expect("checked when creating the stash");
+        for rela_path in &created.untracked {
+            remove_file_and_empty_parents(workdir, rela_path.as_ref())?;
+        }
+
+        // Pretend that changed files and files that were removed from the index are tracked with unknown content,
+        // so they are overwritten just like `git reset --hard` would.
+        let head_tree_id = self.head_tree_id()?.detach();
+        let head_index = gix_index::State::from_tree(&head_tree_id, &self.objects, self.config.protect_options()?)
+            .map_err(|source| checkout::Error::IndexFromTree {
+                id: head_tree_id,
+                source,
+            })?;
+        let mut index = self.index_or_empty()?.into_owned_or_cloned();
+        let (entries, path_backing) = index.entries_mut_and_pathbacking();
+        for entry in entries {
+            if created
+                .changed
+                .binary_search_by(|path| path.as_bstr().cmp(entry.path_in(path_backing)))
+                .is_ok()
+            {
+                entry.id = ObjectId::null(self.object_hash());
+            }
+        }
+        for entry in head_index.entries() {
+            let rela_path = entry.path(&head_index);
+            if index.entry_by_path(rela_path).is_none() {
+                index.dangerously_push_entry(
+                    Default::default(),
+                    ObjectId::null(self.object_hash()),
+                    entry.flags,
+                    entry.mode,
+                    rela_path,
+                );
+            }
+        }
+        index.sort_entries();
+
+        let (new_index, _checkout) = self.checkout_tree_over_index(&index, head_tree_id)?;
+        gix_index::File::from_state(new_index, self.index_path())
+            .write(Default::default())
+            .map_err(checkout::Error::from)?;
+        Ok(Some(created.id.attach(self)))
+    }
+
+    /// Return all entries of the stash, newest first, so that the entry at index `0` is `stash@{0}`.
+    ///
+    /// The list is empty if there is no stash.
+    pub fn stash_list(&self) -> Result<Vec<Entry>, stash_list::Error> {
+        Ok(self
+            .stash_reflog()?
+            .into_iter()
+            .rev()
+            .map(|line| Entry {
+                id: line.new_oid,
+                message: line.message,
+            })
+            .collect())
+    }
+
+    /// Remove the stash entry at `index`, with `0` being the newest entry, and return it, like `git stash drop` does.
+    ///
+    /// `refs/stash` is deleted along with its reflog if the last entry was dropped.
+    pub fn stash_drop(&self, index: usize) -> Result<Entry, stash_drop::Error> {
+        let mut lines = self.stash_reflog()?;
+        let pos = lines
+            .len()
+            .checked_sub(index + 1)
+            .ok_or(stash_drop::Error::NoSuchEntry { index })?;
+        let removed = lines.remove(pos);
+        if lines.is_empty() {
+            self.find_reference(STASH)?.delete()?;
+        } else {
+            let previous_oid = match pos.checked_sub(1) {
+                Some(prev) => lines[prev].new_oid,
+                None => ObjectId::null(self.object_hash()),
+            };
+            if let Some(next) = lines.get_mut(pos) {
+                next.previous_oid = previous_oid;
+            }
+            if index == 0 {
+                // This also appends to the reflog, which is rewritten entirely right after.
+                self.edit_reference(RefEdit {
+                    change: Change::Update {
+                        log: LogChange {
+                            mode: RefLog::AndReference,
+                            force_create_reflog: false,
+                            message: removed.message.clone(),
+                        },
+                        expected: PreviousValue::MustExistAndMatch(Target::Object(removed.new_oid)),
+                        new: Target::Object(lines.last().expect("not empty").new_oid),
+                    },
+                    name: STASH.try_into().expect("valid"),
+                    deref: false,
+                })?;
+            }
+            let path = self.refs.reflog_path(STASH.try_into().expect("valid"));
+            let write = || -> std::io::Result<()> {
+                let mut file =
+                    gix_lock::File::acquire_to_update_resource(&path, gix_lock::acquire::Fail::Immediately, None)
+                        .map_err(std::io::Error::other)?;
+                for line in &lines {
+                    line.write_to(&mut file)?;
+                }
+                file.commit().map_err(|err| err.error)?;
+                Ok(())
+            };
+            write().map_err(|source| stash_drop::Error::WriteReflog {
+                path: path.clone(),
+                source,
+            })?;
+        }
+        Ok(Entry {
+            id: removed.new_oid,
+            message: removed.message,
+        })
+    }
+
+    /// Apply the stash entry at `index`, with `0` being the newest entry, to the index and the worktree
+    /// as configured by `options`, like `git stash apply` does.
+    ///
+    /// The changes of the stash are merged into the tree `HEAD` points to, so conflicts are written to the index as stages
+    /// and to the worktree with conflict markers just like when merging. Files that were added in the stash are added
+    /// to the index, and stashed untracked files are restored, but no file that exists in the worktree is overwritten.
+    /// If [rerere is enabled](Self::rerere_enabled()), conflicts are recorded and recorded resolutions are replayed.
+    ///
+    /// ### Deviation
+    ///
+    /// Git allows applying a stash if the index and the worktree have changes that don't interfere with it,
+    /// but here the operation fails if the index or worktree have changes, just like [`merge_into_head()`](Self::merge_into_head()).
+    pub fn stash_apply(&self, index: usize, options: apply::Options) -> Result<apply::Outcome<'_>, stash_apply::Error> {
+        let entry = self
+            .stash_list()?
+            .into_iter()
+            .nth(index)
+            .ok_or(stash_apply::Error::NoSuchEntry { index })?;
+        self.apply_stash_commit(entry.id, options)
+    }
+
+    /// Like [`stash_apply()`](Self::stash_apply()), but also [drop](Self::stash_drop()) the stash entry at `index`
+    /// if it was applied without conflicts, like `git stash pop` does.
+    pub fn stash_pop(&self, index: usize, options: apply::Options) -> Result<apply::Outcome<'_>, stash_pop::Error> {
+        let outcome = self.stash_apply(index, options)?;
+        if outcome.conflicting_paths.is_empty() {
+            self.stash_drop(index)?;
+        }
+        Ok(outcome)
+    }
+
+    /// Create the branch `name`, like `refs/heads/topic`, at the commit the stash entry at `index` was created from,
+    /// check it out and apply the stash entry including its changes to the index, like `git stash branch` does.
+    ///
+    /// The stash entry is dropped if it was applied without conflicts.
+    /// The operation fails if the branch already exists, or if the index or worktree have changes.
+    pub fn stash_branch<Name, E>(&self, name: Name, index: usize) -> Result<apply::Outcome<'_>, stash_branch::Error>
+    where
+        Name: TryInto<FullName, Error = E>,
+        gix_validate::reference::name::Error: From<E>,
+    {
+        let name = name.try_into().map_err(gix_validate::reference::name::Error::from)?;
+        if let Some(state) = self.state() {
+            return Err(stash_apply::Error::OperationInProgress { state }.into());
+        }
+        if self.is_dirty()? {
+            return Err(stash_apply::Error::Dirty.into());
+        }
+        let entry = self
+            .stash_list()?
+            .into_iter()
+            .nth(index)
+            .ok_or(stash_apply::Error::NoSuchEntry { index })?;
+        let base = self
+            .find_commit(entry.id)?
+            .parent_ids()
+            .next()
+            .ok_or(stash_apply::Error::NotAStash { id: entry.id })?
+            .detach();
+        let previous = match self.head_name()? {
+            Some(head_name) => head_name.shorten().to_owned(),
+            None => self.head_id()?.to_string().into(),
+        };
+        self.reference::<_, std::convert::Infallible>(
+            name.clone(),
+            base,
+            PreviousValue::MustNotExist,
+            format!("branch: Created from {base}"),
+        )?;
+
+        let tree_id = self.find_commit(base)?.tree_id()?.detach();
+        let (new_index, _checkout) = self.checkout_tree_over_index(&*self.index_or_empty()?, tree_id)?;
+        gix_index::File::from_state(new_index, self.index_path())
+            .write(Default::default())
+            .map_err(checkout::Error::from)?;
+        self.edit_reference(RefEdit {
+            change: Change::Update {
+                log: LogChange {
+                    mode: RefLog::AndReference,
+                    force_create_reflog: false,
+                    message: format!("checkout: moving from {previous} to {}", name.shorten()).into(),
+                },
+                expected: PreviousValue::Any,
+                new: Target::Symbolic(name),
+            },
+            name: "HEAD".try_into().expect("valid"),
+            deref: false,
+        })?;
+
+        let outcome = self.apply_stash_commit(entry.id, apply::Options { index: true })?;
+        if outcome.conflicting_paths.is_empty() {
+            self.stash_drop(index)?;
+        }
+        Ok(outcome)
+    }
+
+    /// Return all lines of the reflog of `refs/stash`, oldest first.
+    fn stash_reflog(&self) -> Result<Vec<gix_ref::log::Line>, stash_list::Error> {
+        let Some(reference) = self.try_find_reference(STASH)? else {
+            return Ok(Vec::new());
+        };
+        let mut platform = reference.log_iter();
+        let Some(lines) = platform.all()? else {
+            return Ok(Vec::new());
+        };
+        Ok(lines
+            .map(|line| line.map(|line| line.to_owned()))
+            .collect::<Result<_, _>>()?)
+    }
+
+    fn create_stash(&self, options: create::Options) -> Result<Option<Created>, stash_create::Error> {
+        let workdir = self.workdir().ok_or(stash_create::Error::BareRepository)?;
+        let index = self.index_or_empty()?;
+        if let Some(entry) = index
+            .entries()
+            .iter()
+            .find(|entry| entry.stage() != gix_index::entry::Stage::Unconflicted)
+        {
+            return Err(stash_create::Error::UnresolvedConflicts {
+                rela_path: entry.path(&index).to_owned(),
+            });
+        }
+        let head = self.head_commit()?;
+        let head_tree_id = head.tree_id()?.detach();
+        let branch = self.head_name()?.map_or_else(
+            || "(no branch)".into(),
+            |name| name.shorten().to_str_lossy().into_owned(),
+        );
+        let description = format!(
+            "{branch}: {} {}",
+            head.id().shorten_or_id(),
+            head.message()?.summary().to_str_lossy()
+        );
+
+        let mut editor = self.edit_tree(ObjectId::empty_tree(self.object_hash()))?;
+        for entry in index.entries() {
+            if entry.flags.contains(gix_index::entry::Flags::INTENT_TO_ADD) {
+                continue;
+            }
+            let Some(mode) = entry.mode.to_tree_entry_mode() else {
+                continue;
+            };
+            editor.upsert(entry.path(&index), mode.kind(), entry.id)?;
+        }
+        let index_tree_id = editor.write()?.detach();
+
+        let mut status =
+            self.status(gix_features::progress::Discard)?
+                .index_worktree_submodules(crate::status::Submodule::Given {
+                    ignore: crate::submodule::config::Ignore::Dirty,
+                    check_dirty: false,
+                });
+        status = match options.untracked {
+            Untracked::Exclude => status.untracked_files(crate::status::UntrackedFiles::None),
+            Untracked::Include => status.untracked_files(crate::status::UntrackedFiles::Files),
+            Untracked::IncludeIgnored => status
+                .untracked_files(crate::status::UntrackedFiles::Files)
+                .dirwalk_options(|options| options.emit_ignored(Some(gix_dir::walk::EmissionMode::Matching))),
+        };
+
+        let (mut pipeline, _) = self.filter_pipeline(None)?;
+        let mut changed = Vec::new();
+        let mut untracked = Vec::new();
+        let mut editor = self.edit_tree(index_tree_id)?;
+        let mut untracked_editor = self.edit_tree(ObjectId::empty_tree(self.object_hash()))?;
+        for item in status.into_index_worktree_iter(Vec::new())? {
+            match item? {
+                Item::Modification {
+                    entry,
+                    rela_path,
+                    status,
+                    ..
+                } => match status {
+                    EntryStatus::Conflict { .. } => {
+                        return Err(stash_create::Error::UnresolvedConflicts { rela_path });
+                    }
+                    EntryStatus::NeedsUpdate(_) => {}
+                    EntryStatus::Change(WorktreeChange::SubmoduleModification(submodule)) => {
+                        if let Some(head_id) = submodule.checked_out_head_id.filter(|id| *id != entry.id) {
+                            editor.upsert(rela_path.as_bstr(), gix_object::tree::EntryKind::Commit, head_id)?;
+                        }
+                    }
+                    EntryStatus::Change(WorktreeChange::Removed) => {
+                        editor.remove(rela_path.as_bstr())?;
+                        changed.push(rela_path);
+                    }
+                    EntryStatus::Change(WorktreeChange::Type { .. } | WorktreeChange::Modification { .. })
+                    | EntryStatus::IntentToAdd => {
+                        match pipeline.worktree_file_to_object(rela_path.as_ref(), &index)? {
+                            Some((id, kind, _)) => editor.upsert(rela_path.as_bstr(), kind, id)?,
+                            None => editor.remove(rela_path.as_bstr())?,
+                        };
+                        changed.push(rela_path);
+                    }
+                },
+                Item::DirectoryContents { entry, .. } => {
+                    if !matches!(
+                        entry.status,
+                        gix_dir::entry::Status::Untracked | gix_dir::entry::Status::Ignored(_)
+                    ) {
+                        continue;
+                    }
+                    match entry.disk_kind {
+                        Some(gix_dir::entry::Kind::File | gix_dir::entry::Kind::Symlink) => {
+                            untracked.push(entry.rela_path);
+                        }
+                        Some(gix_dir::entry::Kind::Directory) => {
+                            collect_files(workdir, entry.rela_path, &mut untracked)?;
+                        }
+                        Some(gix_dir::entry::Kind::Repository | gix_dir::entry::Kind::Untrackable) | None => {}
+                    }
+                }
+                Item::Rewrite { .. } => unreachable!("rewrites are disabled by default"),
+            }
+        }
+        for rela_path in &untracked {
+            if let Some((id, kind, _)) = pipeline.worktree_file_to_object(rela_path.as_ref(), &index)? {
+                untracked_editor.upsert(rela_path.as_bstr(), kind, id)?;
+            }
+        }
+        let worktree_tree_id = editor.write()?.detach();
+        if untracked.is_empty() && worktree_tree_id == index_tree_id && index_tree_id == head_tree_id {
+            return Ok(None);
+        }
+
+        let mut parents = vec![head.id];
+        let index_commit = self.new_commit(format!("index on {description}\n"), index_tree_id, Some(head.id))?;
+        parents.push(index_commit.id);
+        if !untracked.is_empty() {
+            let untracked_tree_id = untracked_editor.write()?;
+            let untracked_commit = self.new_commit(
+                format!("untracked files on {description}\n"),
+                untracked_tree_id,
+                None::<ObjectId>,
+            )?;
+            parents.push(untracked_commit.id);
+        }
+        let message = match options.message {
+            Some(message) => format!("On {branch}: {message}"),
+            None => format!("WIP on {description}"),
+        };
+        let commit = self.new_commit(&message, worktree_tree_id, parents)?;
+
+        changed.sort();
+        changed.dedup();
+        untracked.sort();
+        Ok(Some(Created {
+            id: commit.id,
+            message: message.into(),
+            changed,
+            untracked,
+        }))
+    }
+
+    fn apply_stash_commit(
+        &self,
+        id: ObjectId,
+        options: apply::Options,
+    ) -> Result<apply::Outcome<'_>, stash_apply::Error> {
+        if let Some(state) = self.state() {
+            return Err(stash_apply::Error::OperationInProgress { state });
+        }
+        if self.is_dirty()? {
+            return Err(stash_apply::Error::Dirty);
+        }
+        let workdir = self.workdir().ok_or_else(|| checkout::Error::BareRepository {
+            git_dir: self.git_dir().to_owned(),
+        })?;
+        let stash = self.find_commit(id)?;
+        let parents: Vec<_> = stash.parent_ids().map(Id::detach).collect();
+        let (base, index_commit, untracked_commit) = match parents.as_slice() {
+            [base, index] => (*base, *index, None),
+            [base, index, untracked] => (*base, *index, Some(*untracked)),
+            _ => return Err(stash_apply::Error::NotAStash { id }),
+        };
+        let tree_of = |commit: ObjectId| -> Result<ObjectId, stash_apply::Error> {
+            Ok(self.find_commit(commit)?.tree_id()?.detach())
+        };
+        let base_tree_id = tree_of(base)?;
+        let index_tree_id = tree_of(index_commit)?;
+        let head_tree_id = self.head_tree_id()?.detach();
+        let protect_options = self.config.protect_options().map_err(checkout::Error::from)?;
+        let index_from_tree = |tree_id: &ObjectId| {
+            gix_index::State::from_tree(tree_id, &self.objects, protect_options)
+                .map_err(|source| checkout::Error::IndexFromTree { id: *tree_id, source })
+        };
+
+        let tree_merge_options = self.tree_merge_options()?;
+        let staged_tree_id = if !options.index {
+            None
+        } else if index_tree_id == base_tree_id {
+            Some(head_tree_id)
+        } else {
+            let mut outcome = self.merge_trees(
+                base_tree_id,
+                head_tree_id,
+                index_tree_id,
+                Default::default(),
+                tree_merge_options.clone(),
+            )?;
+            if outcome.has_unresolved_conflicts(TreatAsUnresolved::git()) {
+                return Err(stash_apply::Error::IndexConflicts);
+            }
+            Some(outcome.tree.write()?.detach())
+        };
+
+        let labels = gix_merge::blob::builtin_driver::text::Labels {
+            ancestor: Some("Stash base".into()),
+            current: Some("Updated upstream".into()),
+            other: Some("Stashed changes".into()),
+        };
+        let mut outcome = self.merge_trees(base_tree_id, head_tree_id, stash.tree_id()?, labels, tree_merge_options)?;
+        let tree_id = outcome.tree.write()?.detach();
+
+        let mut untracked_index = match untracked_commit {
+            Some(commit) => Some(index_from_tree(&tree_of(commit)?)?),
+            None => None,
+        };
+        if let Some(untracked_index) = &untracked_index {
+            let tree = self.find_tree(tree_id)?;
+            for entry in untracked_index.entries() {
+                let rela_path = entry.path(untracked_index);
+                let path = gix_path::from_bstr(rela_path);
+                if workdir.join(&path).symlink_metadata().is_ok() || tree.lookup_entry_by_path(&path)?.is_some() {
+                    return Err(stash_apply::Error::UntrackedFileExists {
+                        rela_path: rela_path.to_owned(),
+                    });
+                }
+            }
+        }
+
+        let (merged_index, checkout) = self.checkout_tree_over_index(&*self.index_or_empty()?, tree_id)?;
+        let has_conflicts = outcome.has_unresolved_conflicts(TreatAsUnresolved::git());
+        let mut new_index = match staged_tree_id {
+            Some(staged_tree_id) if !has_conflicts => index_from_tree(&staged_tree_id)?,
+            _ => {
+                // Like Git, only keep the changes of files that were added in the stash, and everything else unstaged.
+                let mut new_index = index_from_tree(&head_tree_id)?;
+                let added: BTreeSet<_> = merged_index
+                    .entries()
+                    .iter()
+                    .filter(|entry| new_index.entry_by_path(entry.path(&merged_index)).is_none())
+                    .map(|entry| entry.path(&merged_index))
+                    .collect();
+                for entry in merged_index.entries() {
+                    let rela_path = entry.path(&merged_index);
+                    if added.contains(rela_path) {
+                        new_index.dangerously_push_entry(
+                            Default::default(),
+                            entry.id,
+                            entry.flags,
+                            entry.mode,
+                            rela_path,
+                        );
+                    }
+                }
+                new_index.sort_entries();
+                new_index
+            }
+        };
+        let (entries, path_backing) = new_index.entries_mut_and_pathbacking();
+        for entry in entries {
+            if let Some(merged) = merged_index
+                .entry_by_path(entry.path_in(path_backing))
+                .filter(|merged| merged.id == entry.id && merged.mode == entry.mode)
+            {
+                entry.stat = merged.stat;
+            }
+        }
+        outcome.index_changed_after_applying_conflicts(&mut new_index, TreatAsUnresolved::git(), RemovalMode::Prune);
+        let mut conflicting_paths: Vec<BString> = new_index
+            .entries()
+            .iter()
+            .filter(|entry| entry.stage() != gix_index::entry::Stage::Unconflicted)
+            .map(|entry| entry.path(&new_index).to_owned())
+            .collect();
+        conflicting_paths.dedup();
+        gix_index::File::from_state(new_index, self.index_path())
+            .write(Default::default())
+            .map_err(checkout::Error::from)?;
+
+        if let Some(untracked_index) = &mut untracked_index {
+            gix_worktree_state::checkout(
+                untracked_index,
+                workdir,
+                self.objects.clone().into_arc().map_err(checkout::Error::OpenArcOdb)?,
+                &gix_features::progress::Discard,
+                &gix_features::progress::Discard,
+                &std::sync::atomic::AtomicBool::default(),
+                self.checkout_options(gix_worktree::stack::state::attributes::Source::WorktreeThenIdMapping)
+                    .map_err(checkout::Error::from)?,
+            )
+            .map_err(checkout::Error::from)?;
+        }
+        let rerere = if conflicting_paths.is_empty() {
+            None
+        } else {
+            self.rerere()?
+        };
+
+        Ok(apply::Outcome {
+            tree_id: tree_id.attach(self),
+            conflicts: outcome.conflicts,
+            conflicting_paths,
+            checkout,
+            rerere,
+        })
+    }
+}
+
+/// Add the repository-relative paths of all files and symlinks in the directory at `rela_path` to `out`,
+/// skipping nested repositories.
+fn collect_files(
+    workdir: &std::path::Path,
+    rela_path: BString,
+    out: &mut Vec<BString>,
+) -> Result<(), stash_create::Error> {
+    let dir = workdir.join(gix_path::from_bstr(rela_path.as_bstr()));
+    if dir.join(gix_discover::DOT_GIT_DIR).symlink_metadata().is_ok() {
+        return Ok(());
+    }
+    let entries = std::fs::read_dir(&dir).map_err(|source| stash_create::Error::ReadDirectory {
+        path: dir.clone(),
+        source,
+    })?;
+    for entry in entries {
+        let entry = entry.map_err(|source| stash_create::Error::ReadDirectory {
+            path: dir.clone(),
+            source,
+        })?;
+        let mut child = rela_path.clone();
+        if !child.is_empty() {
+            child.push(b'/');
+        }
+        child.extend_from_slice(
+            gix_path::os_str_into_bstr(&entry.file_name())
+                .map_err(|_| stash_create::Error::IllformedUtf8 { path: entry.path() })?,
+        );
+        let file_type = entry.file_type().map_err(|source| stash_create::Error::ReadDirectory {
+            path: entry.path(),
+            source,
+        })?;
+        if file_type.is_dir() {
+            collect_files(workdir, child, out)?;
+        } else if file_type.is_file() || file_type.is_symlink() {
+            out.push(child);
+        }
+    }
+    Ok(())
+}
@@ -20,14 +20,18 @@ impl delegate::Revision for Delegate<'_> {
         if self.has_delayed_err() && self.refs[self.idx].is_some() {
             return Err(message("Refusing call as there are delayed errors and a ref is available").raise_erased());
         }
-        match self.repo.refs.find(name) {
+        let found = match &self.repo.ref_store {
+            Some(ref_store) => ref_store.find(name).map_err(|err| err.raise_erased()),
+            None => self.repo.refs.find(name).map_err(|err| err.raise_erased()),
+        };
+        match found {
             Ok(r) => {
                 assert!(self.refs[self.idx].is_none(), "BUG: cannot set the same ref twice");
                 self.refs[self.idx] = Some(r);
                 Ok(())
             }
             Err(err) => {
-                bail!(err.raise_erased())
+                bail!(err)
             }
         }
     }
@@ -71,8 +75,12 @@ impl delegate::Revision for Delegate<'_> {
                         Ok(())
                     }
                     RefsHint::PreferRef | RefsHint::PreferObjectOnFullLengthHexShaUseRefOtherwise | RefsHint::Fail => {
-                        match self.repo.refs.find(&prefix.to_string()) {
-                            Ok(ref_) => {
+                        let found = match &self.repo.ref_store {
+                            Some(ref_store) => ref_store.find(&prefix.to_string()).ok(),
+                            None => self.repo.refs.find(&prefix.to_string()).ok(),
+                        };
+                        match found {
+                            Some(ref_) => {
                                 assert!(self.refs[self.idx].is_none(), "BUG: cannot set the same ref twice");
                                 if self.opts.refs_hint == RefsHint::Fail {
                                     self.refs[self.idx] = Some(ref_.clone());
@@ -89,7 +97,7 @@ impl delegate::Revision for Delegate<'_> {
                                     Ok(())
                                 }
                             }
-                            Err(_) => {
+                            None => {
                                 let objs = to_sorted_vec(candidates);
                                 self.ambiguous_objects[self.idx] = Some(objs.clone());
                                 self.objs[self.idx] = Some(objs);
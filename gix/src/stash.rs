@@ -0,0 +1,75 @@
+use crate::bstr::BString;
+
+/// An entry of the stash as recorded in the reflog of `refs/stash`, as obtained by
+/// [`Repository::stash_list()`](crate::Repository::stash_list()).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// The id of the stash commit, whose tree represents the worktree, and whose parents are the commit
+    /// `HEAD` pointed to, the commit representing the index and optionally the commit representing untracked files.
+    pub id: gix_hash::ObjectId,
+    /// The message of the reflog entry, like `WIP on main: 0aa1b2c subject`.
+    pub message: BString,
+}
+
+/// Determine which files that aren't tracked should be stashed.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Untracked {
+    /// Do not stash untracked files, like `git stash` does by default.
+    #[default]
+    Exclude,
+    /// Stash untracked files that aren't ignored, like `git stash --include-untracked`.
+    Include,
+    /// Stash untracked files including those that are ignored, like `git stash --all`.
+    IncludeIgnored,
+}
+
+///
+pub mod create {
+    use crate::bstr::BString;
+
+    /// Options for [`Repository::stash_create()`](crate::Repository::stash_create())
+    /// and [`Repository::stash_push()`](crate::Repository::stash_push()).
+    #[derive(Default, Debug, Clone)]
+    pub struct Options {
+        /// The message to use for the stash instead of one that is derived from the commit `HEAD` points to.
+        pub message: Option<BString>,
+        /// Which untracked files to stash along with changes to tracked files.
+        pub untracked: super::Untracked,
+    }
+}
+
+///
+pub mod apply {
+    use crate::{Id, bstr::BString, merge::tree::Conflict};
+
+    /// Options for [`Repository::stash_apply()`](crate::Repository::stash_apply())
+    /// and [`Repository::stash_pop()`](crate::Repository::stash_pop()).
+    #[derive(Default, Debug, Clone, Copy)]
+    pub struct Options {
+        /// If `true`, also restore the changes to the index instead of only restoring files that were added,
+        /// like `git stash apply --index`.
+        ///
+        /// If the stashed changes to the index can't be applied cleanly, the operation fails.
+        pub index: bool,
+    }
+
+    /// The outcome of applying a stash entry to the index and the worktree.
+    #[derive(Debug)]
+    pub struct Outcome<'repo> {
+        /// The id of the merged tree, which is also written to the worktree.
+        ///
+        /// It contains conflict markers for unresolved content merges.
+        pub tree_id: Id<'repo>,
+        /// All conflicts that were encountered, resolved or not.
+        pub conflicts: Vec<Conflict>,
+        /// The paths that have conflicting stages in the index, sorted and without duplicates.
+        ///
+        /// If not empty, the stash entry isn't dropped when popping it.
+        pub conflicting_paths: Vec<BString>,
+        /// Information about the files that were written to the worktree.
+        pub checkout: gix_worktree_state::checkout::Outcome,
+        /// The outcome of recording conflicts and replaying recorded resolutions, or `None` if there were no conflicts
+        /// or [rerere is disabled](crate::Repository::rerere_enabled()).
+        pub rerere: Option<gix_rerere::rerere::Outcome>,
+    }
+}
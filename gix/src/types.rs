@@ -158,7 +158,12 @@ pub struct Reference<'r> {
 /// The minimal feature set to activate `Send` is `features = ["parallel"]`.
 pub struct Repository {
     /// A ref store with shared ownership (or the equivalent of it).
+    ///
+    /// Note that it can only read references stored in files, and that all reference access goes through
+    /// `ref_store` instead if `extensions.refStorage` configures another backend.
     pub refs: crate::RefStore,
+    /// The reference store to use instead of `refs` if `extensions.refStorage` configures a backend other than `files`.
+    pub(crate) ref_store: Option<Box<gix_ref::store::Handle>>,
     /// A way to access objects.
     pub objects: crate::OdbHandle,
 
@@ -197,7 +202,11 @@ pub struct Repository {
 #[derive(Clone)]
 pub struct ThreadSafeRepository {
     /// A store for references to point at objects
+    ///
+    /// Note that it can only read references stored in files.
     pub refs: crate::RefStore,
+    /// The reference store to use instead of `refs` if `extensions.refStorage` configures a backend other than `files`.
+    pub(crate) ref_store: Option<Box<gix_ref::store::Handle>>,
     /// A store for objects that contain data
     pub objects: gix_features::threading::OwnShared<gix_odb::Store>,
     /// The path to the worktree at which to find checked out files
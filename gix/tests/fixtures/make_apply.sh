@@ -0,0 +1,44 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+# `changes.patch` turns the commit tagged `base` into the branch `changes`, with all kinds of changes Git can express.
+# `main` changes context of the patch in `a`, and `conflicting` changes a line the patch changes as well.
+git init -q repo
+(cd repo
+  seq 1 20 > a
+  seq 1 10 > b
+  echo gone > gone
+  echo "#!/bin/sh" > script
+  git add . && git commit -q -m "base"
+  git tag base
+
+  git checkout -q -b changes
+  sed -i.bak -e 's/^3$/three/' -e 's/^15$/fifteen/' a
+  sed -i.bak 's/^5$/five/' b && git mv b c
+  rm gone *.bak
+  chmod +x script
+  echo new > new
+  git add -A && git commit -q -m "changes"
+  git diff --binary base changes > ../changes.patch
+
+  git checkout -q main
+  sed -i.bak 's/^6$/six/' a && rm a.bak
+  git commit -q -am "diverge"
+
+  git checkout -q -b conflicting base
+  sed -i.bak 's/^3$/THREE/' a && rm a.bak
+  git commit -q -am "conflict"
+  git checkout -q main
+)
+
+cp -R repo at-base
+(cd at-base && git checkout -q base)
+
+cp -R repo diverged
+cp -R repo diverged-git
+(cd diverged-git && git update-index -q --refresh && git apply --3way ../changes.patch 2>/dev/null)
+
+cp -R repo conflicting
+(cd conflicting && git checkout -q conflicting)
+cp -R conflicting conflicting-git
+(cd conflicting-git && git update-index -q --refresh && git apply --3way ../changes.patch 2>/dev/null || true)
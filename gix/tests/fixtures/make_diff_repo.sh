@@ -19,6 +19,12 @@ cat <<EOF >>.git/config
   textconv = textconv
   algorithm = histogram
   binary = auto
+  wordRegex = "[a-z]+"
+  xfuncname = "^fn (.*)$"
+[diff "python"]
+  textconv = textconv
+[diff "rust"]
+  funcname = "^fn"
 EOF
 
 git checkout -b main
@@ -0,0 +1,70 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+function write_lines () {
+  printf "%s\n" "$@"
+}
+
+# A repository with `main` checked out and branches to merge into it.
+function init_repo () {
+  git init -q "$1"
+  (cd "$1"
+    write_lines 1 2 3 4 5 6 7 8 9 >a
+    mkdir dir && echo c >dir/c
+    echo b >b
+    git add . && git commit -q -m "init"
+
+    git checkout -q -b conflicting
+    write_lines 1 2 3 4 theirs 6 7 8 9 >a
+    echo new >new
+    git rm -q dir/c
+    git add . && git commit -q -m "conflicting: change 'a', add 'new' and remove 'dir/c'"
+
+    git checkout -q -b clean main
+    write_lines 1 2 3 4 5 6 7 8 theirs >a
+    git commit -q -am "clean: change the end of 'a'"
+
+    git checkout -q -b ahead main
+    echo ahead >b
+    git commit -q -am "ahead: change 'b'"
+
+    git checkout -q main
+    write_lines 1 2 3 4 ours 6 7 8 9 >a
+    git commit -q -am "main: change 'a'"
+  )
+}
+
+init_repo clean-state
+
+init_repo git-conflicted
+(cd git-conflicted
+  git merge -q conflicting >/dev/null 2>&1 && {
+    echo "expected merge of 'conflicting' to conflict" >&2
+    exit 1
+  }
+  test -f .git/MERGE_HEAD
+)
+
+init_repo git-resolved
+(cd git-resolved
+  git merge -q conflicting >/dev/null 2>&1 && {
+    echo "expected merge of 'conflicting' to conflict" >&2
+    exit 1
+  }
+  write_lines 1 2 3 4 resolved 6 7 8 9 >a
+  git add a
+)
+
+# The resolution of merging `conflicting` was recorded, and the merge was undone.
+init_repo git-rerere
+(cd git-rerere
+  git -c rerere.enabled=true merge -q conflicting >/dev/null 2>&1 && {
+    echo "expected merge of 'conflicting' to conflict" >&2
+    exit 1
+  }
+  write_lines 1 2 3 4 resolved 6 7 8 9 >a
+  git add a
+  git -c rerere.enabled=true commit -q --no-edit >/dev/null
+  git reset -q --hard HEAD~1
+  test -f .git/rr-cache/*/postimage
+)
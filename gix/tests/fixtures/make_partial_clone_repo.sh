@@ -0,0 +1,18 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q base
+(cd base
+  git config uploadpack.allowFilter true
+  echo "first" > a
+  mkdir dir && echo "second" > dir/b
+  git add . && git commit -q -m "initial"
+)
+
+git clone -q --no-local --no-hardlinks --no-checkout --filter=blob:none ./base blobless
+git clone -q --no-local --no-hardlinks --no-checkout ./base full
+
+(cd base
+  echo "third" > c
+  git add . && git commit -q -m "not yet fetched"
+)
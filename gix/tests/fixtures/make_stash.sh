@@ -0,0 +1,53 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+function write_lines () {
+  printf "%s\n" "$@"
+}
+
+# A repository with a single commit on `main`.
+function init_repo () {
+  git init -q "$1"
+  (cd "$1"
+    write_lines 1 2 3 4 5 6 7 8 9 >a
+    echo b >b
+    mkdir dir && echo c >dir/c
+    echo ignored >.gitignore
+    git add . && git commit -q -m "init"
+  )
+}
+
+# Changes to stash: 'a' is staged and modified again, 'b' is removed, 'new' is added and 'untracked' and 'ignored' aren't tracked.
+function make_changes () {
+  write_lines 1 2 3 4 staged 6 7 8 9 >a
+  git add a
+  write_lines 1 2 3 4 staged 6 7 8 worktree >a
+  rm b
+  echo new >new && git add new
+  echo untracked >untracked
+  mkdir -p untracked-dir && echo u >untracked-dir/u
+  echo ignored >ignored
+}
+
+init_repo clean
+
+init_repo dirty
+(cd dirty
+  make_changes
+)
+
+init_repo git-stashes
+(cd git-stashes
+  echo first >b
+  git stash push -q -m "first"
+  make_changes
+  git stash push -q --include-untracked
+)
+
+init_repo git-conflicting-stash
+(cd git-conflicting-stash
+  write_lines 1 2 3 4 stashed 6 7 8 9 >a
+  git stash -q
+  write_lines 1 2 3 4 upstream 6 7 8 9 >a
+  git commit -q -am "change 'a' upstream"
+)
@@ -0,0 +1,37 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q module
+(cd module
+  echo root > file
+  git add file && git commit -q -m "root"
+  git branch root
+
+  git checkout -q -b a root
+  echo a > file && git commit -q -am "a"
+
+  git checkout -q -b a-descendant a
+  echo a-descendant > file && git commit -q -am "a-descendant"
+
+  git checkout -q -b b root
+  echo b > b && git add b && git commit -q -m "b"
+
+  git checkout -q -b merged a
+  git merge -q --no-edit b
+)
+
+git init -q super
+(cd super
+  git submodule add ../module sub
+  git -C sub checkout -q root
+  git add sub && git commit -q -m "add submodule at 'root'"
+
+  for branch in a a-descendant b; do
+    git checkout -q -b "$branch" main
+    git -C sub checkout -q "$branch"
+    git add sub && git commit -q -m "submodule at '$branch'"
+  done
+  git checkout -q main
+  git -C sub checkout -q root
+  git -C sub rev-parse merged > .git/merge-candidate
+)
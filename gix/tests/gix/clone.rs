@@ -1020,6 +1020,80 @@ mod blocking_io {
         Ok(())
     }
 
+    #[test]
+    fn fetch_and_checkout_from_bundle() -> crate::Result {
+        let bundles = gix_testtools::scripted_fixture_read_only("make_bundles.sh")?;
+        let source = gix::open_opts(bundles.join("repo"), restricted())?;
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let mut prepare = gix::clone::PrepareFetch::new(
+            bundles.join("all.bundle"),
+            tmp.path(),
+            gix::create::Kind::WithWorktree,
+            Default::default(),
+            restricted(),
+        )?;
+        let (mut checkout, _out) = prepare.fetch_then_checkout(gix::progress::Discard, &AtomicBool::default())?;
+        let (repo, _) = checkout.main_worktree(gix::progress::Discard, &AtomicBool::default())?;
+
+        assert_eq!(
+            repo.head_name()?.expect("not detached").as_bstr(),
+            "refs/heads/main",
+            "bundles don't know symbolic refs, but HEAD can be matched with the branch it points to"
+        );
+        for (local, remote) in [
+            ("refs/remotes/origin/main", "refs/heads/main"),
+            ("refs/remotes/origin/feature", "refs/heads/feature"),
+            ("refs/tags/v1", "refs/tags/v1"),
+        ] {
+            assert_eq!(
+                repo.find_reference(local)?.id(),
+                source.find_reference(remote)?.id(),
+                "{local}"
+            );
+        }
+        assert!(
+            repo.find_remote("origin")?
+                .url(Direction::Fetch)
+                .expect("set")
+                .path
+                .ends_with(b"/all.bundle"),
+            "the bundle is remembered as remote, just like git does"
+        );
+
+        let index = repo.index()?;
+        assert_eq!(index.entries().len(), 3, "a, b and d are checked out on main");
+        assure_index_entries_on_disk(&index, repo.workdir().expect("non-bare"));
+        Ok(())
+    }
+
+    #[test]
+    fn fetch_from_bundle_with_missing_prerequisites() -> crate::Result {
+        let bundles = gix_testtools::scripted_fixture_read_only("make_bundles.sh")?;
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let err = gix::clone::PrepareFetch::new(
+            bundles.join("incremental.bundle"),
+            tmp.path(),
+            gix::create::Kind::Bare,
+            gix::create::Options {
+                object_hash: Some(gix_testtools::object_hash()),
+                ..Default::default()
+            },
+            restricted(),
+        )?
+        .fetch_only(gix::progress::Discard, &AtomicBool::default())
+        .unwrap_err();
+        assert!(
+            matches!(
+                err,
+                gix::clone::fetch::Error::Connect(gix::remote::connect::Error::BundlePrerequisites(
+                    gix::bundle::verify::Error::MissingPrerequisites { .. }
+                ))
+            ),
+            "{err:?}"
+        );
+        Ok(())
+    }
+
     #[test]
     #[cfg(feature = "sha256")]
     fn fetch_only_adopts_remote_sha256_object_format() -> crate::Result {
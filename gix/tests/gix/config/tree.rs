@@ -352,17 +352,12 @@ mod diff {
             ("Default", Algorithm::Myers),
             ("minimal", Algorithm::MyersMinimal),
             ("histogram", Algorithm::Histogram),
+            ("patience", Algorithm::Patience),
+            ("Patience", Algorithm::Patience),
         ] {
             assert_eq!(Diff::ALGORITHM.try_into_algorithm(bcow(actual))?, expected);
             assert!(Diff::ALGORITHM.validate(actual.into()).is_ok());
         }
-        assert_eq!(
-            Diff::ALGORITHM
-                .try_into_algorithm(bcow("patience"))
-                .unwrap_err()
-                .to_string(),
-            "The 'patience' algorithm is not yet implemented"
-        );
         assert_eq!(
             Diff::ALGORITHM.try_into_algorithm(bcow("foo")).unwrap_err().to_string(),
             "Unknown diff algorithm named 'foo'"
@@ -399,6 +394,35 @@ mod merge {
     }
 }
 
+mod gc {
+    use std::time::Duration;
+
+    use gix::config::tree::{Gc, Key};
+
+    use crate::config::tree::bcow;
+
+    #[test]
+    fn rerere_expiry() -> crate::Result {
+        for (actual, expected) in [
+            ("never", None),
+            ("now", Some(Duration::ZERO)),
+            ("0", Some(Duration::ZERO)),
+            ("15", Some(Duration::from_secs(15 * 24 * 60 * 60))),
+        ] {
+            assert_eq!(Gc::RERERE_RESOLVED.try_into_expiry(bcow(actual))?, expected);
+            assert!(Gc::RERERE_UNRESOLVED.validate(actual.into()).is_ok());
+        }
+        assert_eq!(
+            Gc::RERERE_UNRESOLVED
+                .try_into_expiry(bcow("-1"))
+                .unwrap_err()
+                .to_string(),
+            "The key \"gc.rerereUnresolved=-1\" was invalid"
+        );
+        Ok(())
+    }
+}
+
 mod core {
     use std::time::Duration;
 
@@ -728,6 +752,29 @@ mod extensions {
         assert!(Extensions::OBJECT_FORMAT.validate("invalid".into()).is_err());
         Ok(())
     }
+
+    #[test]
+    fn ref_storage() -> crate::Result {
+        use gix::refs::store::Backend;
+        assert_eq!(
+            Extensions::REF_STORAGE.try_into_ref_storage(bcow("files"))?,
+            Backend::Files
+        );
+        assert_eq!(
+            Extensions::REF_STORAGE.try_into_ref_storage(bcow("reftable"))?,
+            Backend::Reftable
+        );
+        assert!(Extensions::REF_STORAGE.validate("reftable".into()).is_ok());
+        assert_eq!(
+            Extensions::REF_STORAGE
+                .try_into_ref_storage(bcow("invalid"))
+                .unwrap_err()
+                .to_string(),
+            "The key \"extensions.refStorage=invalid\" was invalid"
+        );
+        assert!(Extensions::REF_STORAGE.validate("invalid".into()).is_err());
+        Ok(())
+    }
 }
 
 mod checkout {
@@ -1,4 +1,4 @@
-use gix_diff::blob::{Algorithm, Driver};
+use gix_diff::blob::{Algorithm, Driver, funcname::Pattern};
 
 use crate::util::named_repo;
 
@@ -13,15 +13,18 @@ fn resource_cache() -> crate::Result {
             .detach(),
         Default::default(),
     )?;
+    let (configured, builtin) = cache.filter.drivers().split_at(5);
     assert_eq!(
-        cache.filter.drivers(),
+        configured,
         &[
             Driver {
                 name: "all-but-binary".into(),
                 command: Some("command".into()),
                 algorithm: Some(Algorithm::Histogram),
                 binary_to_text_command: Some("textconv".into()),
-                is_binary: None
+                is_binary: None,
+                word_regex: Some("[a-z]+".into()),
+                funcname: Some(Pattern::extended("^fn (.*)$")),
             },
             Driver {
                 name: "binary-false".into(),
@@ -32,9 +35,27 @@ fn resource_cache() -> crate::Result {
                 name: "binary-true".into(),
                 is_binary: Some(true),
                 ..Default::default()
-            }
-        ]
+            },
+            Driver {
+                name: "python".into(),
+                binary_to_text_command: Some("textconv".into()),
+                funcname: Pattern::builtin("python".into()),
+                ..Default::default()
+            },
+            Driver {
+                name: "rust".into(),
+                funcname: Some(Pattern::basic("^fn")),
+                ..Default::default()
+            },
+        ],
+        "configured drivers with a built-in name use the built-in pattern unless they set their own"
+    );
+    assert_eq!(
+        builtin.len(),
+        Pattern::builtin_names().count() - 2,
+        "built-in drivers follow unless they are configured"
     );
+    assert!(builtin.iter().all(|driver| driver.funcname.is_some()));
     assert_eq!(cache.options.algorithm, Some(Algorithm::Histogram));
     assert!(
         !cache.options.skip_internal_diff_if_external_is_configured,
@@ -914,3 +914,52 @@ mod blocking_and_async_io {
         Ok(())
     }
 }
+
+#[cfg(feature = "blocking-network-client")]
+mod bundle {
+    use std::sync::atomic::AtomicBool;
+
+    use gix::remote::{Direction::Fetch, fetch::Status};
+
+    use crate::util::restricted;
+
+    #[test]
+    fn incremental_bundle_on_top_of_its_prerequisites() -> crate::Result {
+        let bundles = gix_testtools::scripted_fixture_read_only("make_bundles.sh")?;
+        let source = gix::open_opts(bundles.join("repo"), restricted())?;
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let (repo, _out) = gix::prepare_clone_bare(bundles.join("base.bundle"), tmp.path())?
+            .fetch_only(gix::progress::Discard, &AtomicBool::default())?;
+        assert_eq!(
+            repo.find_reference("refs/remotes/origin/main")?.id(),
+            source.rev_parse_single("v1^{commit}")?,
+            "the base bundle contains main as it was when v1 was tagged"
+        );
+
+        let outcome = repo
+            .remote_at(bundles.join("incremental.bundle"))?
+            .with_refspecs(Some("+refs/heads/*:refs/remotes/origin/*"), Fetch)?
+            .connect(Fetch)?
+            .prepare_fetch(gix::progress::Discard, Default::default())?
+            .receive(gix::progress::Discard, &AtomicBool::default())?;
+        assert!(matches!(outcome.status, Status::Change { .. }));
+
+        for name in ["main", "feature"] {
+            assert_eq!(
+                repo.find_reference(format!("refs/remotes/origin/{name}").as_str())?
+                    .id(),
+                source.find_reference(format!("refs/heads/{name}").as_str())?.id(),
+                "{name} was fetched along with all of its objects"
+            );
+        }
+        assert_eq!(
+            repo.find_reference("refs/remotes/origin/feature")?
+                .id()
+                .ancestors()
+                .all()?
+                .count(),
+            3
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,307 @@
+use gix::repository::{
+    apply_patch,
+    apply_patch::{Options, Target},
+};
+use gix_testtools::tempfile;
+
+fn fixture(name: &str) -> crate::Result<(gix::Repository, tempfile::TempDir)> {
+    let dir = gix_testtools::scripted_fixture_writable("make_apply.sh")?;
+    let repo = gix::open_opts(dir.path().join(name), crate::restricted())?;
+    Ok((repo, dir))
+}
+
+fn patches(dir: &tempfile::TempDir) -> crate::Result<Vec<gix::apply::FilePatch>> {
+    Ok(gix::apply::parse(
+        &std::fs::read(dir.path().join("changes.patch"))?,
+        Default::default(),
+    )?)
+}
+
+fn read(repo: &gix::Repository, rela_path: &str) -> std::io::Result<Vec<u8>> {
+    std::fs::read(repo.workdir().expect("non-bare").join(rela_path))
+}
+
+fn exists(repo: &gix::Repository, rela_path: &str) -> bool {
+    repo.workdir().expect("non-bare").join(rela_path).exists()
+}
+
+fn index_ids(repo: &gix::Repository) -> crate::Result<Vec<(String, gix_hash::ObjectId, u32)>> {
+    let index = repo.open_index()?;
+    Ok(index
+        .entries()
+        .iter()
+        .map(|entry| (entry.path(&index).to_string(), entry.id, entry.stage_raw()))
+        .collect())
+}
+
+fn tree_ids(repo: &gix::Repository, spec: &str) -> crate::Result<Vec<(String, gix_hash::ObjectId, u32)>> {
+    let tree = repo
+        .rev_parse_single(format!("{spec}^{{tree}}").as_str())?
+        .object()?
+        .into_tree();
+    Ok(tree
+        .decode()?
+        .entries
+        .iter()
+        .map(|entry| (entry.filename.to_string(), entry.oid.to_owned(), 0))
+        .collect())
+}
+
+#[test]
+fn to_tree_is_the_same_as_git() -> crate::Result {
+    let (repo, dir) = fixture("repo")?;
+    let patches = patches(&dir)?;
+    let base = repo.rev_parse_single("base^{tree}")?;
+    let changes = repo.rev_parse_single("changes^{tree}")?;
+    assert_eq!(repo.apply_patch_to_tree(base, &patches, Default::default())?, changes);
+
+    let reversed = repo.apply_patch_to_tree(
+        changes,
+        &patches,
+        gix::apply::apply::Options {
+            reverse: true,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(reversed, base, "patches can be reversed");
+
+    let main = repo.rev_parse_single("main^{tree}")?;
+    assert!(matches!(
+        repo.apply_patch_to_tree(main, &patches, Default::default()),
+        Err(gix::repository::apply_patch_to_tree::Error::ApplyPatch(
+            apply_patch::Error::Apply { path, .. }
+        )) if path == "a"
+    ));
+    Ok(())
+}
+
+#[test]
+fn to_worktree_leaves_the_index_alone() -> crate::Result {
+    let (repo, dir) = fixture("at-base")?;
+    let patches = patches(&dir)?;
+    let index_before = index_ids(&repo)?;
+    let outcome = repo.apply_patch(&patches, Default::default())?;
+    assert_eq!(outcome.files.len(), 5);
+    assert!(outcome.conflicting_paths.is_empty());
+    assert_eq!(index_ids(&repo)?, index_before);
+
+    let changes = repo.rev_parse_single("changes")?.object()?.into_commit().tree()?;
+    for path in ["a", "c", "new", "script"] {
+        let entry = changes.lookup_entry_by_path(path)?.expect("present");
+        assert_eq!(read(&repo, path)?, entry.object()?.data, "{path}");
+    }
+    assert!(!exists(&repo, "b"), "renamed");
+    assert!(!exists(&repo, "gone"), "deleted");
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(repo.workdir().expect("non-bare").join("script"))?
+            .permissions()
+            .mode();
+        assert_ne!(mode & 0o111, 0, "the mode change was applied");
+    }
+    Ok(())
+}
+
+#[test]
+fn to_index_or_both_is_the_same_as_git() -> crate::Result {
+    for target in [Target::Index, Target::WorktreeAndIndex] {
+        let (repo, dir) = fixture("at-base")?;
+        repo.apply_patch(
+            &patches(&dir)?,
+            Options {
+                target,
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(index_ids(&repo)?, tree_ids(&repo, "changes")?, "{target:?}");
+        assert_eq!(
+            exists(&repo, "c"),
+            target == Target::WorktreeAndIndex,
+            "the worktree is only changed if requested"
+        );
+        if target == Target::WorktreeAndIndex {
+            let status: Vec<_> = repo
+                .status(gix::progress::Discard)?
+                .into_index_worktree_iter(Vec::new())?
+                .collect::<Result<_, _>>()?;
+            assert!(status.is_empty(), "the index matches the worktree: {status:?}");
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn nothing_is_changed_if_a_patch_fails() -> crate::Result {
+    let (repo, dir) = fixture("at-base")?;
+    let patches = patches(&dir)?;
+    let workdir = repo.workdir().expect("non-bare").to_owned();
+    std::fs::write(workdir.join("new"), "in the way\n")?;
+    assert!(matches!(
+        repo.apply_patch(&patches, Default::default()),
+        Err(apply_patch::Error::AlreadyExists { path }) if path == "new"
+    ));
+    assert!(!exists(&repo, "c"));
+    assert_eq!(read(&repo, "a")?, repo.rev_parse_single("base:a")?.object()?.data);
+
+    std::fs::remove_file(workdir.join("new"))?;
+    std::fs::write(workdir.join("gone"), "changed\n")?;
+    assert!(matches!(
+        repo.apply_patch(
+            &patches,
+            Options {
+                target: Target::WorktreeAndIndex,
+                ..Default::default()
+            }
+        ),
+        Err(apply_patch::Error::WorktreeDiffersFromIndex { path }) if path == "gone"
+    ));
+    assert!(!exists(&repo, "c"));
+    Ok(())
+}
+
+#[test]
+fn three_way_merges_like_git() -> crate::Result {
+    let (repo, dir) = fixture("diverged")?;
+    let (git, _git_dir) = fixture("diverged-git")?;
+    let patches = patches(&dir)?;
+    assert!(matches!(
+        repo.apply_patch(&patches, Default::default()),
+        Err(apply_patch::Error::Apply { path, .. }) if path == "a"
+    ));
+
+    let outcome = repo.apply_patch(
+        &patches,
+        Options {
+            three_way: true,
+            ..Default::default()
+        },
+    )?;
+    assert!(outcome.conflicting_paths.is_empty());
+    assert!(outcome.files[0].merged);
+    assert!(outcome.files[1..].iter().all(|file| !file.merged));
+    assert_eq!(read(&repo, "a")?, read(&git, "a")?);
+    assert_eq!(
+        index_ids(&repo)?,
+        index_ids(&git)?,
+        "three-way merges also update the index"
+    );
+    Ok(())
+}
+
+#[test]
+fn three_way_conflicts_are_recorded_like_git() -> crate::Result {
+    let (repo, dir) = fixture("conflicting")?;
+    let (git, _git_dir) = fixture("conflicting-git")?;
+    let outcome = repo.apply_patch(
+        &patches(&dir)?,
+        Options {
+            three_way: true,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(outcome.conflicting_paths, ["a"]);
+    assert_eq!(read(&repo, "a")?, read(&git, "a")?);
+    assert_eq!(
+        index_ids(&repo)?,
+        index_ids(&git)?,
+        "the conflict is recorded as stages"
+    );
+    Ok(())
+}
+
+#[test]
+fn paths_outside_the_worktree_are_refused() -> crate::Result {
+    let (repo, _dir) = fixture("at-base")?;
+    let base = repo.rev_parse_single("base^{tree}")?;
+    for (patch, expected_path) in [
+        (
+            &b"diff --git a/../../x b/../../x\nnew file mode 100644\n--- /dev/null\n+++ b/../../x\n@@ -0,0 +1 @@\n+x\n"[..],
+            "../../x",
+        ),
+        (
+            b"diff --git a/a b/a\nsimilarity index 100%\nrename from a\nrename to /abs/path\n",
+            "/abs/path",
+        ),
+        (
+            b"diff --git a/.git/hooks/pre-commit b/.git/hooks/pre-commit\nnew file mode 100755\n--- /dev/null\n+++ b/.git/hooks/pre-commit\n@@ -0,0 +1 @@\n+#!/bin/sh\n",
+            ".git/hooks/pre-commit",
+        ),
+        (
+            b"diff --git a/.GIT/config b/.GIT/config\nnew file mode 100644\n--- /dev/null\n+++ b/.GIT/config\n@@ -0,0 +1 @@\n+x\n",
+            ".GIT/config",
+        ),
+    ] {
+        let patches = gix::apply::parse(patch, Default::default())?;
+        for target in [Target::Worktree, Target::Index] {
+            let err = repo
+                .apply_patch(
+                    &patches,
+                    Options {
+                        target,
+                        ..Default::default()
+                    },
+                )
+                .unwrap_err();
+            assert!(
+                matches!(&err, apply_patch::Error::InvalidPath { path, .. } if path == expected_path),
+                "{target:?}: {err:?}"
+            );
+        }
+        assert!(matches!(
+            repo.apply_patch_to_tree(base, &patches, Default::default()),
+            Err(gix::repository::apply_patch_to_tree::Error::ApplyPatch(
+                apply_patch::Error::InvalidPath { path, .. }
+            )) if path == expected_path
+        ));
+    }
+    assert_eq!(index_ids(&repo)?, tree_ids(&repo, "base")?, "nothing was changed");
+    assert!(!exists(&repo, "path"));
+    Ok(())
+}
+
+#[test]
+fn paths_beyond_symlinks_are_refused() -> crate::Result {
+    let (repo, dir) = fixture("at-base")?;
+    let patches = gix::apply::parse(
+        b"diff --git a/link b/link\nnew file mode 120000\n--- /dev/null\n+++ b/link\n@@ -0,0 +1 @@\n+..\n\\ No newline at end of file\n\
+diff --git a/link/escaped b/link/escaped\nnew file mode 100644\n--- /dev/null\n+++ b/link/escaped\n@@ -0,0 +1 @@\n+x\n",
+        Default::default(),
+    )?;
+    for target in [Target::Worktree, Target::Index] {
+        assert!(matches!(
+            repo.apply_patch(&patches, Options { target, ..Default::default() }),
+            Err(apply_patch::Error::BeyondSymlink { path }) if path == "link/escaped"
+        ));
+    }
+    let base = repo.rev_parse_single("base^{tree}")?;
+    assert!(matches!(
+        repo.apply_patch_to_tree(base, &patches, Default::default()),
+        Err(gix::repository::apply_patch_to_tree::Error::ApplyPatch(
+            apply_patch::Error::BeyondSymlink { path }
+        )) if path == "link/escaped"
+    ));
+    assert!(!exists(&repo, "link"));
+
+    #[cfg(unix)]
+    {
+        let outside = dir.path().join("outside");
+        std::fs::create_dir(&outside)?;
+        std::os::unix::fs::symlink(&outside, repo.workdir().expect("non-bare").join("link"))?;
+        let patches = gix::apply::parse(
+            b"diff --git a/link/escaped b/link/escaped\nnew file mode 100644\n--- /dev/null\n+++ b/link/escaped\n@@ -0,0 +1 @@\n+x\n",
+            Default::default(),
+        )?;
+        assert!(matches!(
+            repo.apply_patch(&patches, Default::default()),
+            Err(apply_patch::Error::BeyondSymlink { path }) if path == "link/escaped"
+        ));
+        assert!(
+            !outside.join("escaped").exists(),
+            "nothing was written through the symlink"
+        );
+    }
+    #[cfg(not(unix))]
+    let _ = dir;
+    Ok(())
+}
@@ -1,4 +1,5 @@
 use gix::bstr::BString;
+use gix_testtools::tempfile;
 use std::num::NonZero;
 
 #[test]
@@ -61,3 +62,125 @@ fn with_options() -> crate::Result {
 
     Ok(())
 }
+
+#[test]
+fn ignore_revs_from_configuration() -> crate::Result {
+    let mut repo = crate::named_repo("make_blame_repo.sh")?;
+    let suspect = repo.head_id()?.detach();
+
+    let tmp = tempfile::tempdir()?;
+    let ignore_revs_file = tmp.path().join("ignore-revs");
+    std::fs::write(
+        &ignore_revs_file,
+        format!("# a commit to ignore\n{suspect} # with comment\n\n"),
+    )?;
+    {
+        let mut config = repo.config_snapshot_mut();
+        config.set_value(
+            &gix::config::tree::Blame::IGNORE_REVS_FILE,
+            ignore_revs_file.to_str().expect("valid UTF-8"),
+        )?;
+        config.set_value(&gix::config::tree::Blame::MARK_UNBLAMABLE_LINES, "true")?;
+    }
+
+    let outcome = repo.blame_file("simple.txt".into(), suspect, Default::default())?;
+    let last_entry = outcome.entries.last().expect("4 lines");
+    assert_eq!(
+        last_entry.commit_id, suspect,
+        "added lines can't be attributed to the parent of an ignored commit"
+    );
+    assert!(last_entry.unblamable);
+    assert!(!last_entry.ignored);
+
+    let outcome = repo.blame_file(
+        "simple.txt".into(),
+        suspect,
+        gix::repository::blame_file::Options {
+            ignore_revs_files: Some(Vec::new()),
+            ..Default::default()
+        },
+    )?;
+    assert!(
+        !outcome.entries.last().expect("4 lines").unblamable,
+        "configured files are ignored if files are passed explicitly"
+    );
+    Ok(())
+}
+
+#[test]
+fn uncommitted_changes_in_worktree_and_index() -> crate::Result {
+    let (repo, _tmp) = crate::repo_rw("make_blame_repo.sh")?;
+    let suspect = repo.head_id()?.detach();
+    let workdir = repo.workdir().expect("non-bare");
+    std::fs::write(
+        workdir.join("simple.txt"),
+        "line 1\nline 2\nline 3 changed\nline 4\nline 5\n",
+    )?;
+
+    let blame = |uncommitted| {
+        repo.blame_file(
+            "simple.txt".into(),
+            suspect,
+            gix::repository::blame_file::Options {
+                uncommitted: Some(uncommitted),
+                ..Default::default()
+            },
+        )
+    };
+    let null = repo.object_hash().null();
+
+    let outcome = blame(gix::repository::blame_file::Uncommitted::Worktree)?;
+    let uncommitted_lines: Vec<_> = outcome
+        .entries
+        .iter()
+        .filter(|entry| entry.commit_id == null)
+        .map(|entry| (entry.start_in_blamed_file, entry.len.get()))
+        .collect();
+    assert_eq!(
+        uncommitted_lines,
+        [(2, 1), (4, 1)],
+        "the changed and the added line aren't committed yet"
+    );
+
+    let outcome = blame(gix::repository::blame_file::Uncommitted::Index)?;
+    assert_eq!(outcome.entries.len(), 4, "the index matches `HEAD`");
+    assert!(outcome.entries.iter().all(|entry| entry.commit_id != null));
+    Ok(())
+}
+
+#[test]
+fn incremental() -> crate::Result {
+    let repo = crate::named_repo("make_blame_repo.sh")?;
+
+    let suspect = repo.head_id()?;
+    let mut entries = Vec::new();
+    let outcome = repo.blame_file_incremental(
+        "simple.txt".into(),
+        suspect,
+        Default::default(),
+        |entry| entries.push(entry),
+        &mut gix::progress::Discard,
+        &std::sync::atomic::AtomicBool::default(),
+    )?;
+
+    assert_eq!(entries.len(), 4);
+    entries.sort_by_key(|entry| entry.start_in_blamed_file);
+    assert_eq!(entries, outcome.entries);
+
+    Ok(())
+}
+
+#[test]
+fn multiple_files() -> crate::Result {
+    let repo = crate::named_repo("make_blame_repo.sh")?;
+
+    let suspect = repo.head_id()?;
+    let outcomes = repo.blame_files(["simple.txt".into(), "simple.txt".into()], suspect, Default::default())?;
+
+    assert_eq!(outcomes.len(), 2);
+    for outcome in outcomes {
+        assert_eq!(outcome.entries.len(), 4, "the same file can be blamed more than once");
+    }
+
+    Ok(())
+}
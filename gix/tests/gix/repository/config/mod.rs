@@ -16,6 +16,30 @@ fn big_file_threshold() -> crate::Result {
     Ok(())
 }
 
+#[test]
+#[cfg(feature = "blob-diff")]
+fn diff_context_size() -> crate::Result {
+    use gix::config::tree::Diff;
+    use gix_diff::blob::unified_diff::ContextSize;
+
+    let mut repo = repo("with-hasconfig");
+    assert_eq!(
+        repo.diff_context_size()?,
+        ContextSize::symmetrical(3),
+        "three lines of context and no inter-hunk context by default, like in git"
+    );
+
+    let mut config = repo.config_snapshot_mut();
+    config.set_value(&Diff::CONTEXT, "1")?;
+    config.set_value(&Diff::INTER_HUNK_CONTEXT, "5")?;
+    config.commit()?;
+    assert_eq!(
+        repo.diff_context_size()?,
+        ContextSize::symmetrical(1).with_inter_hunk_lines(5)
+    );
+    Ok(())
+}
+
 #[cfg(feature = "blocking-network-client")]
 mod ssh_options {
     use std::ffi::OsStr;
@@ -11,3 +11,465 @@ fn tree_merge_options() -> crate::Result {
     );
     Ok(())
 }
+
+#[test]
+fn tree_merge_options_with_strategy_options() -> crate::Result {
+    use gix::merge::{
+        blob::{builtin_driver, pipeline},
+        tree::StrategyOption,
+    };
+    let repo = named_repo("make_basic_repo.sh")?;
+    let opts: gix::merge::plumbing::tree::Options = repo
+        .tree_merge_options()?
+        .with_strategy_option(StrategyOption::Theirs)
+        .with_strategy_option(StrategyOption::NoRenames)
+        .with_strategy_option(StrategyOption::Renormalize)
+        .with_strategy_option(StrategyOption::IgnoreSpaceChange)
+        .into();
+    assert_eq!(
+        opts.blob_merge.text.conflict,
+        builtin_driver::text::Conflict::ResolveWithTheirs
+    );
+    assert_eq!(
+        opts.blob_merge.resolve_binary_with,
+        Some(builtin_driver::binary::ResolveWith::Theirs)
+    );
+    assert_eq!(opts.rewrites, None);
+    assert_eq!(opts.filter_mode, Some(pipeline::Mode::Renormalize));
+    assert!(opts.blob_merge.text.whitespace.ignore_space_change);
+    Ok(())
+}
+
+#[test]
+fn merge_commits_octopus() -> crate::Result {
+    let repo = crate::named_subrepo_opts("make_merge_state.sh", "clean-state", gix::open::Options::isolated())?;
+    let id = |name: &str| -> crate::Result<gix::ObjectId> { Ok(repo.rev_parse_single(name)?.detach()) };
+    let outcome = repo.merge_commits_octopus(
+        id("main")?,
+        [id("clean")?, id("ahead")?, id("main~1")?],
+        repo.tree_merge_options()?.into(),
+    )?;
+    assert_eq!(
+        outcome.parents,
+        [id("main")?, id("clean")?, id("ahead")?],
+        "commits that are already contained aren't parents"
+    );
+    assert!(matches!(
+        outcome.steps[2],
+        gix::merge::octopus::Step::AlreadyUpToDate { .. }
+    ));
+
+    let tree = repo.find_tree(outcome.tree_id)?;
+    let blob = |path: &str| -> crate::Result<Vec<u8>> {
+        Ok(tree
+            .lookup_entry_by_path(path)?
+            .expect("present")
+            .object()?
+            .detach()
+            .data)
+    };
+    assert_eq!(blob("a")?, b"1\n2\n3\n4\nours\n6\n7\n8\ntheirs\n");
+    assert_eq!(blob("b")?, b"ahead\n");
+
+    let err = repo
+        .merge_commits_octopus(id("main")?, [id("conflicting")?], repo.tree_merge_options()?.into())
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        gix::repository::merge_commits_octopus::Error::OctopusMerge(
+            gix::merge::plumbing::commit::octopus::Error::Conflict { .. }
+        )
+    ));
+    Ok(())
+}
+
+mod submodules {
+    use gix::merge::plumbing::tree::{Resolution, ResolutionFailure, TreatAsUnresolved, submodule::Failure};
+
+    fn commit_id(repo: &gix::Repository, name: &str) -> crate::Result<gix::ObjectId> {
+        Ok(repo.rev_parse_single(name)?.detach())
+    }
+
+    #[test]
+    fn fast_forward() -> crate::Result {
+        let repo = crate::named_subrepo_opts("make_submodule_merge.sh", "super", gix::open::Options::isolated())?;
+        let mut outcome = repo.merge_commits(
+            commit_id(&repo, "a")?,
+            commit_id(&repo, "a-descendant")?,
+            Default::default(),
+            repo.tree_merge_options()?.into(),
+        )?;
+        assert!(!outcome.tree_merge.has_unresolved_conflicts(TreatAsUnresolved::git()));
+        assert!(matches!(
+            outcome.tree_merge.conflicts[0].resolution,
+            Ok(Resolution::OursModifiedTheirsModifiedThenSubmoduleFastForward { .. })
+        ));
+
+        let tree_id = outcome.tree_merge.tree.write()?;
+        let expected = repo.rev_parse_single("a-descendant^{tree}")?;
+        assert_eq!(tree_id, expected, "the submodule now points to the descendant");
+        Ok(())
+    }
+
+    #[test]
+    fn diverged_with_merge_candidate() -> crate::Result {
+        let repo = crate::named_subrepo_opts("make_submodule_merge.sh", "super", gix::open::Options::isolated())?;
+        let outcome = repo.merge_commits(
+            commit_id(&repo, "a")?,
+            commit_id(&repo, "b")?,
+            Default::default(),
+            repo.tree_merge_options()?.into(),
+        )?;
+        assert!(outcome.tree_merge.has_unresolved_conflicts(TreatAsUnresolved::git()));
+        let Err(ResolutionFailure::OursModifiedTheirsModifiedSubmodule { failure }) =
+            &outcome.tree_merge.conflicts[0].resolution
+        else {
+            panic!("unexpected resolution {:?}", outcome.tree_merge.conflicts[0].resolution)
+        };
+        let expected_candidate = std::fs::read_to_string(repo.git_dir().join("merge-candidate"))?;
+        assert_eq!(
+            *failure,
+            Failure::Diverged {
+                merge_candidates: vec![gix::ObjectId::from_hex(expected_candidate.trim().as_bytes())?]
+            }
+        );
+        Ok(())
+    }
+}
+
+#[cfg(all(feature = "worktree-mutation", feature = "status"))]
+mod in_progress {
+    use gix::bstr::ByteSlice;
+    use gix::merge::plumbing::tree::TreatAsUnresolved;
+    use gix_testtools::tempfile;
+
+    fn fixture(name: &str) -> crate::Result<(gix::Repository, tempfile::TempDir)> {
+        fixture_with_config(name, None::<&str>)
+    }
+
+    fn fixture_with_config(
+        name: &str,
+        config: impl IntoIterator<Item = impl Into<gix::bstr::BString>>,
+    ) -> crate::Result<(gix::Repository, tempfile::TempDir)> {
+        let dir = gix_testtools::scripted_fixture_writable("make_merge_state.sh")?;
+        let repo = gix::open_opts(dir.path().join(name), crate::restricted().cli_overrides(config))?;
+        Ok((repo, dir))
+    }
+
+    fn read(repo: &gix::Repository, rela_path: &str) -> std::io::Result<String> {
+        std::fs::read_to_string(repo.git_dir().join(rela_path))
+    }
+
+    fn stages(repo: &gix::Repository) -> crate::Result<Vec<(String, u32)>> {
+        let index = repo.open_index()?;
+        Ok(index
+            .entries()
+            .iter()
+            .map(|entry| (entry.path(&index).to_string(), entry.stage_raw()))
+            .collect())
+    }
+
+    #[test]
+    fn conflicting_merge_then_abort() -> crate::Result {
+        let (repo, _dir) = fixture("clean-state")?;
+        let head_id = repo.head_id()?.detach();
+        let theirs = repo.rev_parse_single("conflicting")?.detach();
+        let outcome = repo.merge_into_head(
+            theirs,
+            Default::default(),
+            "Merge branch 'conflicting'".into(),
+            repo.tree_merge_options()?.into(),
+        )?;
+        assert_eq!(outcome.conflicting_paths, ["a"]);
+        assert!(
+            outcome
+                .conflicts
+                .iter()
+                .any(|conflict| conflict.is_unresolved(TreatAsUnresolved::git()))
+        );
+
+        assert_eq!(read(&repo, "MERGE_HEAD")?, format!("{theirs}\n"));
+        assert_eq!(read(&repo, "MERGE_MODE")?, "no-ff");
+        assert_eq!(
+            read(&repo, "MERGE_MSG")?,
+            "Merge branch 'conflicting'\n\n# Conflicts:\n#\ta\n",
+            "the same format that Git uses"
+        );
+        assert_eq!(repo.find_reference("ORIG_HEAD")?.id(), head_id);
+        assert_eq!(repo.find_reference("AUTO_MERGE")?.id(), outcome.tree_id);
+        assert!(matches!(repo.state(), Some(gix::state::InProgress::Merge)));
+
+        assert_eq!(
+            stages(&repo)?,
+            [
+                ("a".into(), 1),
+                ("a".into(), 2),
+                ("a".into(), 3),
+                ("b".into(), 0),
+                ("new".into(), 0)
+            ],
+            "'dir/c' was removed and 'new' was added"
+        );
+        let workdir = repo.workdir().expect("non-bare");
+        let a = std::fs::read(workdir.join("a"))?;
+        assert!(
+            a.contains_str("<<<<<<<") && a.contains_str(">>>>>>>"),
+            "conflict markers"
+        );
+        assert_eq!(std::fs::read(workdir.join("new"))?, b"new\n");
+        assert!(!workdir.join("dir").exists(), "empty directories are removed as well");
+
+        let err = repo.merge_continue().expect_err("conflicts must be resolved first");
+        assert!(matches!(
+            err,
+            gix::repository::merge_continue::Error::UnresolvedConflicts { .. }
+        ));
+
+        repo.merge_abort()?;
+        assert_eq!(repo.merge_state()?, None);
+        assert!(repo.state().is_none());
+        assert!(repo.try_find_reference("AUTO_MERGE")?.is_none());
+        assert_eq!(repo.head_id()?, head_id);
+        assert!(!repo.is_dirty()?, "index and worktree are back at ORIG_HEAD");
+        assert_eq!(std::fs::read(workdir.join("dir/c"))?, b"c\n");
+        Ok(())
+    }
+
+    #[test]
+    fn clean_merge_then_continue() -> crate::Result {
+        let (repo, _dir) = fixture("clean-state")?;
+        let head_id = repo.head_id()?.detach();
+        let theirs = repo.rev_parse_single("clean")?.detach();
+        let outcome = repo.merge_into_head(
+            theirs,
+            Default::default(),
+            "Merge branch 'clean'".into(),
+            repo.tree_merge_options()?.into(),
+        )?;
+        assert!(outcome.conflicting_paths.is_empty());
+        assert!(
+            repo.try_find_reference("AUTO_MERGE")?.is_none(),
+            "it's only written if there are conflicts"
+        );
+        assert_eq!(
+            repo.merge_state()?,
+            Some(gix::merge::in_progress::State {
+                heads: vec![theirs],
+                message: "Merge branch 'clean'\n".into(),
+                no_ff: true,
+            })
+        );
+        assert_eq!(
+            std::fs::read(repo.workdir().expect("non-bare").join("a"))?,
+            b"1\n2\n3\n4\nours\n6\n7\n8\ntheirs\n"
+        );
+
+        let commit_id = repo.merge_continue()?;
+        let commit = commit_id.object()?.into_commit();
+        assert_eq!(commit.parent_ids().collect::<Vec<_>>(), [head_id, theirs]);
+        assert_eq!(commit.message_raw()?, "Merge branch 'clean'\n");
+        assert_eq!(commit.tree_id()?, outcome.tree_id);
+        assert_eq!(repo.head_id()?, commit_id);
+        assert_eq!(repo.merge_state()?, None);
+        assert!(!repo.is_dirty()?);
+        Ok(())
+    }
+
+    #[test]
+    fn continue_fails_without_creating_a_commit_if_rerere_fails() -> crate::Result {
+        let (repo, _dir) = fixture_with_config("clean-state", ["rerere.enabled=true"])?;
+        let head_id = repo.head_id()?.detach();
+        let theirs = repo.rev_parse_single("clean")?.detach();
+        repo.merge_into_head(
+            theirs,
+            Default::default(),
+            "Merge branch 'clean'".into(),
+            repo.tree_merge_options()?.into(),
+        )?;
+
+        let merge_rr = repo.git_dir().join("MERGE_RR");
+        std::fs::create_dir(&merge_rr)?;
+        assert!(
+            matches!(
+                repo.merge_continue(),
+                Err(gix::repository::merge_continue::Error::Rerere(_))
+            ),
+            "MERGE_RR can't be read"
+        );
+        assert_eq!(repo.head_id()?, head_id, "HEAD didn't move");
+        assert!(repo.merge_state()?.is_some(), "the merge is still in progress");
+
+        std::fs::remove_dir(&merge_rr)?;
+        let commit = repo.merge_continue()?.object()?.into_commit();
+        assert_eq!(
+            commit.parent_ids().collect::<Vec<_>>(),
+            [head_id, theirs],
+            "there is only a single merge commit"
+        );
+        assert_eq!(repo.merge_state()?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn refuses_to_merge_with_changes_or_other_merge_in_progress() -> crate::Result {
+        let (repo, _dir) = fixture("clean-state")?;
+        let theirs = repo.rev_parse_single("clean")?.detach();
+        std::fs::write(repo.workdir().expect("non-bare").join("b"), "changed")?;
+        let err = repo
+            .merge_into_head(
+                theirs,
+                Default::default(),
+                "msg".into(),
+                repo.tree_merge_options()?.into(),
+            )
+            .unwrap_err();
+        assert!(matches!(err, gix::repository::merge_into_head::Error::Dirty));
+
+        let (repo, _dir) = fixture("git-conflicted")?;
+        let err = repo
+            .merge_into_head(
+                theirs,
+                Default::default(),
+                "msg".into(),
+                repo.tree_merge_options()?.into(),
+            )
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            gix::repository::merge_into_head::Error::OperationInProgress { .. }
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn abort_merge_started_by_git() -> crate::Result {
+        let (repo, _dir) = fixture("git-conflicted")?;
+        let state = repo.merge_state()?.expect("merge in progress");
+        assert_eq!(state.heads, [repo.rev_parse_single("conflicting")?.detach()]);
+        assert!(!state.no_ff, "Git writes an empty MERGE_MODE by default");
+
+        repo.merge_abort()?;
+        assert_eq!(repo.merge_state()?, None);
+        assert!(!repo.is_dirty()?);
+        assert!(repo.state().is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn continue_merge_started_by_git() -> crate::Result {
+        let (repo, _dir) = fixture("git-resolved")?;
+        let head_id = repo.head_id()?.detach();
+        let theirs = repo.rev_parse_single("conflicting")?.detach();
+
+        let commit_id = repo.merge_continue()?;
+        let commit = commit_id.object()?.into_commit();
+        assert_eq!(commit.parent_ids().collect::<Vec<_>>(), [head_id, theirs]);
+        assert_eq!(
+            commit.message_raw()?,
+            "Merge branch 'conflicting'\n",
+            "comments are stripped"
+        );
+        assert_eq!(repo.merge_state()?, None);
+        assert!(repo.try_find_reference("AUTO_MERGE")?.is_none());
+        assert!(!repo.is_dirty()?);
+        Ok(())
+    }
+
+    #[test]
+    fn continue_removes_ancestors_from_parents_without_no_ff() -> crate::Result {
+        let (repo, _dir) = fixture("clean-state")?;
+        let ahead = repo.rev_parse_single("ahead")?.detach();
+        let head_id = repo.head_id()?.detach();
+        let git_dir = repo.git_dir();
+        // 'main' isn't an ancestor of 'ahead', but 'main~1' is, so make that the merge head.
+        let ancestor = repo.rev_parse_single("main~1")?.detach();
+        std::fs::write(git_dir.join("MERGE_HEAD"), format!("{ancestor}\n{ahead}\n"))?;
+        std::fs::write(git_dir.join("MERGE_MSG"), "octopus\n")?;
+        std::fs::write(git_dir.join("MERGE_MODE"), "")?;
+
+        let commit = repo.merge_continue()?.object()?.into_commit();
+        assert_eq!(
+            commit.parent_ids().collect::<Vec<_>>(),
+            [head_id, ahead],
+            "'main~1' is contained in both others"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn rerere_records_conflicts_and_resolutions_and_replays_them() -> crate::Result {
+        let (repo, _dir) = fixture_with_config("clean-state", ["rerere.enabled=true"])?;
+        let workdir = repo.workdir().expect("non-bare").to_owned();
+        let theirs = repo.rev_parse_single("conflicting")?.detach();
+        let options = repo.tree_merge_options()?;
+        let merge = || {
+            repo.merge_into_head(
+                theirs,
+                Default::default(),
+                "Merge branch 'conflicting'".into(),
+                options.clone().into(),
+            )
+        };
+        let rerere = merge()?.rerere.expect("rerere is enabled");
+        assert_eq!(rerere.recorded_conflicts, ["a"]);
+        assert!(rerere.replayed.is_empty());
+        assert!(repo.git_dir().join("MERGE_RR").is_file());
+
+        let resolved = "1\n2\n3\n4\nresolved\n6\n7\n8\n9\n";
+        std::fs::write(workdir.join("a"), resolved)?;
+        let rerere = repo.rerere()?.expect("rerere is enabled");
+        assert_eq!(rerere.recorded_resolutions, ["a"]);
+        assert!(
+            !repo.git_dir().join("MERGE_RR").exists(),
+            "nothing is left to be resolved"
+        );
+
+        repo.merge_abort()?;
+        let rerere = merge()?.rerere.expect("rerere is enabled");
+        assert_eq!(rerere.replayed, ["a"]);
+        assert_eq!(std::fs::read_to_string(workdir.join("a"))?, resolved);
+        assert_eq!(
+            stages(&repo)?[..3],
+            [("a".into(), 1), ("a".into(), 2), ("a".into(), 3)],
+            "the index isn't updated by default"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn rerere_replays_resolutions_recorded_by_git_and_updates_the_index() -> crate::Result {
+        let (repo, _dir) = fixture_with_config("git-rerere", ["rerere.autoUpdate=true"])?;
+        assert!(repo.rerere_enabled()?, "the rr-cache exists");
+        let outcome = repo.merge_into_head(
+            repo.rev_parse_single("conflicting")?.detach(),
+            Default::default(),
+            "Merge branch 'conflicting'".into(),
+            repo.tree_merge_options()?.into(),
+        )?;
+        assert_eq!(outcome.conflicting_paths, ["a"]);
+        assert_eq!(outcome.rerere.expect("rerere is enabled").replayed, ["a"]);
+        assert_eq!(stages(&repo)?[0], ("a".into(), 0), "conflicts were replaced");
+
+        let commit = repo.merge_continue()?.object()?.into_commit();
+        let tree = commit.tree()?;
+        let a = tree.find_entry("a").expect("present").object()?;
+        assert_eq!(a.data, b"1\n2\n3\n4\nresolved\n6\n7\n8\n9\n");
+        Ok(())
+    }
+
+    #[test]
+    fn rerere_disabled_and_gc() -> crate::Result {
+        let (repo, _dir) = fixture_with_config("git-rerere", ["rerere.enabled=false"])?;
+        assert!(!repo.rerere_enabled()?);
+        assert_eq!(repo.rerere()?, None);
+
+        let (repo, _dir) = fixture("git-rerere")?;
+        assert!(repo.rerere_gc()?.is_empty(), "the resolution was just recorded");
+
+        let (repo, _dir) = fixture_with_config("git-rerere", ["gc.rerereResolved=now"])?;
+        let removed = repo.rerere_gc()?;
+        assert_eq!(removed.len(), 1);
+        assert!(repo.rerere_cache().variants(&removed[0].hash)?.is_empty());
+        Ok(())
+    }
+}
@@ -4,6 +4,8 @@ fn blob_id(repo: &Repository, data: &[u8]) -> gix_hash::ObjectId {
     gix_object::compute_hash(repo.object_hash(), gix_object::Kind::Blob, data).expect("valid object hash")
 }
 
+#[cfg(feature = "apply")]
+mod apply;
 #[cfg(feature = "blame")]
 mod blame;
 mod config;
@@ -19,9 +21,12 @@ mod object;
 mod open;
 #[cfg(feature = "attributes")]
 mod pathspec;
+mod promisor;
 mod reference;
 mod remote;
 mod shallow;
+#[cfg(all(feature = "merge", feature = "worktree-mutation", feature = "status"))]
+mod stash;
 mod state;
 #[cfg(feature = "attributes")]
 mod submodule;
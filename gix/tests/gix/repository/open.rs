@@ -99,16 +99,90 @@ fn non_bare_reftable() -> crate::Result {
         }
         Err(err) => panic!("{err}"),
     };
-    assert!(
-        repo.head_id().is_err(),
-        "Trying to do anything with head will fail as we don't support reftables yet"
+    let mut head = repo.head()?;
+    assert_eq!(
+        head.referent_name().expect("born").as_bstr(),
+        "refs/heads/main",
+        "HEAD is read from the reftable, not from the placeholder in the HEAD file"
+    );
+    let head_id = head.peel_to_commit()?.id;
+    assert_eq!(repo.head_id()?, head_id);
+
+    let main = repo.find_reference("HEAD")?.follow().expect("symbolic")?;
+    assert_eq!(main.name().as_bstr(), "refs/heads/main");
+    assert_eq!(main.id(), head_id);
+    let mut origin_head = repo.find_reference("origin")?;
+    assert_eq!(origin_head.name().as_bstr(), "refs/remotes/origin/HEAD");
+    assert_eq!(origin_head.peel_to_id()?, head_id);
+    assert_eq!(
+        repo.rev_parse_single("main~1")?,
+        repo.find_reference("main")?
+            .peel_to_commit()?
+            .parent_ids()
+            .next()
+            .expect("parent"),
+        "revision parsing finds references in reftables as well"
+    );
+
+    let names = repo
+        .references()?
+        .all()?
+        .map(|r| r.map(|r| r.name().as_bstr().to_string()))
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(
+        names,
+        [
+            "refs/heads/main",
+            "refs/remotes/origin/HEAD",
+            "refs/remotes/origin/main"
+        ]
     );
     assert!(!repo.is_bare());
     assert_eq!(repo.kind(), gix::repository::Kind::Common);
-    assert_ne!(
-        repo.workdir(),
-        None,
-        "Otherwise it can be used, but it's hard to do without refs"
+    assert_ne!(repo.workdir(), None);
+    Ok(())
+}
+
+#[test]
+fn non_bare_reftable_can_be_written() -> crate::Result {
+    let tmp = match gix_testtools::scripted_fixture_writable("make_reftable_repo.sh") {
+        Ok(tmp) => tmp,
+        Err(_) if *gix_testtools::GIT_VERSION < (2, 44, 0) => {
+            eprintln!("Fixture script failure ignored as it looks like Git isn't recent enough.");
+            return Ok(());
+        }
+        Err(err) => panic!("{err}"),
+    };
+    let repo = gix::open_opts(tmp.path().join("reftable-clone"), crate::util::restricted())?;
+    let id = repo.head_id()?.detach();
+    repo.reference(
+        "refs/heads/new",
+        id,
+        gix::refs::transaction::PreviousValue::MustNotExist,
+        "created by test",
+    )?;
+    assert!(
+        !repo.git_dir().join("refs/heads/new").exists(),
+        "no loose reference was written"
+    );
+
+    let repo = gix::open_opts(repo.git_dir(), crate::util::restricted())?;
+    assert_eq!(repo.find_reference("new")?.id(), id);
+    Ok(())
+}
+
+#[test]
+fn unknown_ref_storage_is_an_error() -> crate::Result {
+    let tmp = gix_testtools::scripted_fixture_writable("make_basic_repo.sh")?;
+    let config_path = tmp.path().join(".git/config");
+    let mut config = std::fs::read(&config_path)?;
+    config.extend_from_slice(b"[extensions]\n\trefStorage = unknown\n");
+    std::fs::write(&config_path, config)?;
+
+    let err = gix::open_opts(tmp.path(), gix::open::Options::isolated()).unwrap_err();
+    assert!(
+        matches!(&err, gix::open::Error::Config(gix::config::Error::ConfigTypedString(_))),
+        "{err:?}"
     );
     Ok(())
 }
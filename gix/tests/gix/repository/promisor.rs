@@ -0,0 +1,130 @@
+use crate::util::named_subrepo_opts;
+
+#[test]
+fn promisor_remotes() -> crate::Result {
+    let repo = named_subrepo_opts("make_partial_clone_repo.sh", "blobless", crate::restricted())?;
+    assert!(repo.is_partial_clone());
+    let names = repo.promisor_remote_names();
+    assert_eq!(
+        names.len(),
+        1,
+        "`extensions.partialClone` and `remote.origin.promisor` name the same remote"
+    );
+    assert_eq!(names[0].as_ref(), "origin");
+
+    let repo = named_subrepo_opts("make_partial_clone_repo.sh", "full", crate::restricted())?;
+    assert!(!repo.is_partial_clone());
+    assert!(repo.promisor_remote_names().is_empty());
+    Ok(())
+}
+
+#[cfg(feature = "blocking-network-client")]
+mod fetch {
+    use std::sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+    };
+
+    use gix_testtools::scripted_fixture_writable;
+
+    fn missing_blobs(repo: &gix::Repository) -> crate::Result<Vec<gix::ObjectId>> {
+        let tree = repo.head_tree()?;
+        let mut ids: Vec<_> = tree
+            .traverse()
+            .breadthfirst
+            .files()?
+            .into_iter()
+            .filter(|entry| entry.mode.is_blob())
+            .map(|entry| entry.oid)
+            .collect();
+        ids.sort();
+        Ok(ids)
+    }
+
+    fn promisor_pack_count(repo: &gix::Repository) -> std::io::Result<usize> {
+        Ok(std::fs::read_dir(repo.objects.store_ref().path().join("pack"))?
+            .filter_map(Result::ok)
+            .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "promisor"))
+            .count())
+    }
+
+    #[test]
+    fn missing_objects_are_fetched_lazily() -> crate::Result {
+        let dir = scripted_fixture_writable("make_partial_clone_repo.sh")?;
+        let repo = gix::open_opts(dir.path().join("blobless"), crate::restricted())?;
+        let blobs = missing_blobs(&repo)?;
+        assert_eq!(blobs.len(), 2);
+        assert!(
+            blobs.iter().all(|id| !repo.has_object(id)),
+            "checking for existence doesn't trigger fetches"
+        );
+        let packs_before = promisor_pack_count(&repo)?;
+
+        let blob = repo.find_blob(blobs[0])?;
+        assert!(!blob.data.is_empty(), "the blob was fetched from the promisor remote");
+        assert_eq!(
+            promisor_pack_count(&repo)?,
+            packs_before + 1,
+            "the received pack is a promisor pack"
+        );
+        assert!(repo.objects.is_in_promisor_pack(&blobs[0]));
+        Ok(())
+    }
+
+    #[test]
+    fn fetch_promised_objects_in_one_batch() -> crate::Result {
+        let dir = scripted_fixture_writable("make_partial_clone_repo.sh")?;
+        let repo = gix::open_opts(dir.path().join("blobless"), crate::restricted())?;
+        let blobs = missing_blobs(&repo)?;
+
+        let outcome = repo.fetch_promised_objects(&blobs)?.expect("a pack was received");
+        assert_eq!(outcome.index.num_objects, 2);
+        assert!(outcome.keep_path.is_none(), "promisor packs don't need to be kept");
+        assert!(blobs.iter().all(|id| repo.has_object(id)));
+
+        let repo = gix::open_opts(dir.path().join("full"), crate::restricted())?;
+        assert!(matches!(
+            repo.fetch_promised_objects(&blobs),
+            Err(gix::repository::fetch_promised_objects::Error::NoPromisorRemote)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn missing_objects_are_not_fetched_lazily_while_fetching() -> crate::Result {
+        let dir = scripted_fixture_writable("make_partial_clone_repo.sh")?;
+        let mut repo = gix::open_opts(dir.path().join("blobless"), crate::restricted())?;
+        let lazy_fetches = Arc::new(AtomicUsize::default());
+        repo.objects.promisor = Some(Arc::new({
+            let lazy_fetches = lazy_fetches.clone();
+            move |_ids: &[gix::ObjectId]| -> Result<(), gix::odb::store::promisor::FetchError> {
+                lazy_fetches.fetch_add(1, Ordering::SeqCst);
+                Err("objects must not be fetched lazily while fetching".into())
+            }
+        }));
+
+        let outcome = repo
+            .find_remote("origin")?
+            .connect(gix::remote::Direction::Fetch)?
+            .prepare_fetch(gix::progress::Discard, Default::default())?
+            .receive(gix::progress::Discard, &AtomicBool::default())?;
+        assert!(
+            matches!(outcome.status, gix::remote::fetch::Status::Change { .. }),
+            "the commit that isn't present locally was received"
+        );
+        assert_eq!(
+            lazy_fetches.load(Ordering::SeqCst),
+            0,
+            "the new commit of the remote is looked up during negotiation, but may be missing"
+        );
+
+        let blobs = missing_blobs(&repo)?;
+        repo.fetch_promised_objects(&blobs)?.expect("a pack was received");
+        assert_eq!(
+            lazy_fetches.load(Ordering::SeqCst),
+            0,
+            "objects aren't fetched lazily while fetching promised objects either"
+        );
+        Ok(())
+    }
+}
@@ -92,6 +92,69 @@ fn try_find_reference_with_existing_ref_as_path_prefix_returns_none() -> crate::
     Ok(())
 }
 
+#[test]
+fn migrate_ref_storage_to_reftable_and_back() -> crate::Result {
+    use gix::refs::store::Backend;
+
+    let (repo, _tmp) = crate::repo_rw("make_references_repo.sh")?;
+    std::fs::remove_file(repo.git_dir().join("refs/broken"))?;
+    let expected: Vec<_> = repo
+        .references()?
+        .all()?
+        .map(|r| r.map(gix::Reference::detach))
+        .collect::<Result<_, _>>()?;
+
+    let outcome = repo.migrate_ref_storage(Backend::Reftable)?;
+    assert_eq!(outcome.refs, expected.len() + 1, "HEAD is migrated as well");
+    let reftable_repo = gix::open_opts(repo.git_dir(), crate::restricted())?;
+    let config = reftable_repo.config_snapshot();
+    assert_eq!(
+        config.string("extensions.refStorage").expect("set").as_ref(),
+        "reftable"
+    );
+    assert_eq!(config.integer("core.repositoryFormatVersion"), Some(1));
+    let handle = outcome.store.to_handle();
+    assert_eq!(
+        handle.find("HEAD")?.target.try_name().expect("symbolic").as_bstr(),
+        "refs/heads/main"
+    );
+    assert_eq!(
+        reftable_repo.head_name()?.expect("not detached").as_bstr(),
+        "refs/heads/main",
+        "the reopened repository reads references from the reftable"
+    );
+    let names = |refs: &[gix::refs::Reference]| refs.iter().map(|r| r.name.clone()).collect::<Vec<_>>();
+    let actual: Vec<_> = reftable_repo
+        .references()?
+        .all()?
+        .map(|r| r.map(gix::Reference::detach))
+        .collect::<Result<_, _>>()?;
+    assert_eq!(names(&actual), names(&expected));
+    assert!(
+        !repo.git_dir().join("packed-refs").exists(),
+        "files-backend references were removed"
+    );
+
+    let outcome = reftable_repo.migrate_ref_storage(Backend::Files)?;
+    assert_eq!(outcome.refs, expected.len() + 1);
+    let repo = gix::open_opts(repo.git_dir(), crate::restricted())?;
+    assert_eq!(
+        repo.config_snapshot()
+            .string("extensions.refStorage")
+            .expect("set")
+            .as_ref(),
+        "files"
+    );
+    let actual: Vec<_> = repo
+        .references()?
+        .all()?
+        .map(|r| r.map(gix::Reference::detach))
+        .collect::<Result<_, _>>()?;
+    assert_eq!(actual, expected, "references survive the round-trip");
+    assert_eq!(repo.head_name()?.expect("not detached").as_bstr(), "refs/heads/main");
+    Ok(())
+}
+
 mod iter_references {
     use crate::util::hex_to_id;
 
@@ -0,0 +1,265 @@
+use gix::{
+    bstr::ByteSlice,
+    stash::{Untracked, apply, create},
+};
+use gix_testtools::tempfile;
+
+fn fixture(name: &str) -> crate::Result<(gix::Repository, tempfile::TempDir)> {
+    let dir = gix_testtools::scripted_fixture_writable("make_stash.sh")?;
+    let repo = gix::open_opts(dir.path().join(name), crate::restricted())?;
+    Ok((repo, dir))
+}
+
+fn read(repo: &gix::Repository, rela_path: &str) -> std::io::Result<String> {
+    std::fs::read_to_string(repo.workdir().expect("non-bare").join(rela_path))
+}
+
+fn exists(repo: &gix::Repository, rela_path: &str) -> bool {
+    repo.workdir().expect("non-bare").join(rela_path).exists()
+}
+
+fn index_id(repo: &gix::Repository, rela_path: &str) -> crate::Result<Option<gix_hash::ObjectId>> {
+    let index = repo.open_index()?;
+    Ok(index.entry_by_path(rela_path.into()).map(|entry| entry.id))
+}
+
+fn tree_of(repo: &gix::Repository, spec: &str) -> crate::Result<gix_hash::ObjectId> {
+    Ok(repo.rev_parse_single(format!("{spec}^{{tree}}").as_str())?.detach())
+}
+
+#[test]
+fn list_entries_created_by_git() -> crate::Result {
+    let (repo, _dir) = fixture("git-stashes")?;
+    let entries = repo.stash_list()?;
+    let messages: Vec<_> = entries.iter().map(|entry| entry.message.to_string()).collect();
+    let head = repo.head_id()?.shorten()?;
+    assert_eq!(messages, [format!("WIP on main: {head} init"), "On main: first".into()]);
+    assert_eq!(entries[0].id, repo.rev_parse_single("refs/stash")?);
+
+    let (repo, _dir) = fixture("clean")?;
+    assert!(repo.stash_list()?.is_empty(), "no stash means no entries");
+    Ok(())
+}
+
+#[test]
+fn push_creates_the_same_trees_as_git_and_resets_the_worktree() -> crate::Result {
+    let (git, _git_dir) = fixture("git-stashes")?;
+    let (repo, _dir) = fixture("dirty")?;
+    let id = repo
+        .stash_push(create::Options {
+            message: None,
+            untracked: Untracked::Include,
+        })?
+        .expect("there are changes");
+
+    let stash = id.object()?.into_commit();
+    assert_eq!(stash.parent_ids().count(), 3, "HEAD, index and untracked files");
+    for spec in ["", "^2", "^3"] {
+        assert_eq!(
+            tree_of(&repo, &format!("{id}{spec}"))?,
+            tree_of(&git, &format!("refs/stash{spec}"))?,
+            "worktree, index and untracked files are stored like Git does it"
+        );
+    }
+    assert_eq!(
+        stash.message_raw()?,
+        git.find_commit(git.rev_parse_single("refs/stash")?)?.message_raw()?
+    );
+
+    assert!(!repo.is_dirty()?, "the index and tracked files are reset to HEAD");
+    assert_eq!(read(&repo, "a")?, "1\n2\n3\n4\n5\n6\n7\n8\n9\n");
+    assert_eq!(read(&repo, "b")?, "b\n");
+    for rela_path in ["new", "untracked", "untracked-dir"] {
+        assert!(!exists(&repo, rela_path), "{rela_path} was stashed and removed");
+    }
+    assert!(
+        exists(&repo, "ignored"),
+        "ignored files are left alone unless requested"
+    );
+
+    let entries = repo.stash_list()?;
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].id, id);
+    assert!(entries[0].message.starts_with_str("WIP on main: "));
+    Ok(())
+}
+
+#[test]
+fn push_without_changes_does_nothing() -> crate::Result {
+    let (repo, _dir) = fixture("clean")?;
+    assert_eq!(repo.stash_push(Default::default())?, None);
+    assert!(repo.try_find_reference("refs/stash")?.is_none());
+    Ok(())
+}
+
+#[test]
+fn push_with_message_and_ignored_files() -> crate::Result {
+    let (repo, _dir) = fixture("dirty")?;
+    let id = repo
+        .stash_push(create::Options {
+            message: Some("custom".into()),
+            untracked: Untracked::IncludeIgnored,
+        })?
+        .expect("there are changes");
+    assert_eq!(repo.stash_list()?[0].message, "On main: custom");
+    assert!(!exists(&repo, "ignored"), "ignored files were stashed as well");
+
+    let untracked_tree = repo
+        .rev_parse_single(format!("{id}^3^{{tree}}").as_str())?
+        .object()?
+        .into_tree();
+    assert!(untracked_tree.lookup_entry_by_path("ignored")?.is_some());
+    assert!(untracked_tree.lookup_entry_by_path("untracked-dir/u")?.is_some());
+    Ok(())
+}
+
+#[test]
+fn pop_restores_changes_and_untracked_files() -> crate::Result {
+    let (repo, _dir) = fixture("dirty")?;
+    let head_a = repo.rev_parse_single("HEAD:a")?.detach();
+    let head_b = repo.rev_parse_single("HEAD:b")?.detach();
+    repo.stash_push(create::Options {
+        message: None,
+        untracked: Untracked::Include,
+    })?;
+
+    let outcome = repo.stash_pop(0, Default::default())?;
+    assert!(outcome.conflicting_paths.is_empty());
+    assert!(outcome.rerere.is_none());
+
+    assert_eq!(read(&repo, "a")?, "1\n2\n3\n4\nstaged\n6\n7\n8\nworktree\n");
+    assert!(!exists(&repo, "b"));
+    assert_eq!(read(&repo, "new")?, "new\n");
+    assert_eq!(read(&repo, "untracked")?, "untracked\n");
+    assert_eq!(read(&repo, "untracked-dir/u")?, "u\n");
+
+    assert_eq!(index_id(&repo, "a")?, Some(head_a), "changes are unstaged like in Git");
+    assert_eq!(index_id(&repo, "b")?, Some(head_b), "deletions are unstaged as well");
+    assert!(index_id(&repo, "new")?.is_some(), "added files stay in the index");
+    assert_eq!(index_id(&repo, "untracked")?, None);
+
+    assert!(repo.stash_list()?.is_empty(), "the entry was dropped");
+    assert!(repo.try_find_reference("refs/stash")?.is_none());
+    Ok(())
+}
+
+#[test]
+fn apply_with_index_restores_staged_changes() -> crate::Result {
+    let (repo, _dir) = fixture("git-stashes")?;
+    let outcome = repo.stash_apply(0, apply::Options { index: true })?;
+    assert!(outcome.conflicting_paths.is_empty());
+
+    assert_eq!(
+        index_id(&repo, "a")?,
+        Some(repo.rev_parse_single("refs/stash^2:a")?.detach())
+    );
+    assert_eq!(
+        index_id(&repo, "new")?,
+        Some(repo.rev_parse_single("refs/stash^2:new")?.detach())
+    );
+    assert!(index_id(&repo, "b")?.is_some(), "the deletion wasn't staged");
+    assert!(!exists(&repo, "b"));
+    assert_eq!(read(&repo, "a")?, "1\n2\n3\n4\nstaged\n6\n7\n8\nworktree\n");
+    assert_eq!(read(&repo, "untracked")?, "untracked\n");
+    assert_eq!(repo.stash_list()?.len(), 2, "applying keeps the entry");
+    Ok(())
+}
+
+#[test]
+fn apply_refuses_changes_and_existing_untracked_files() -> crate::Result {
+    let (repo, _dir) = fixture("git-stashes")?;
+    std::fs::write(repo.workdir().expect("non-bare").join("untracked"), "in the way")?;
+    assert!(matches!(
+        repo.stash_apply(0, Default::default()),
+        Err(gix::repository::stash_apply::Error::UntrackedFileExists { rela_path }) if rela_path == "untracked"
+    ));
+
+    std::fs::write(repo.workdir().expect("non-bare").join("b"), "changed")?;
+    assert!(matches!(
+        repo.stash_apply(0, Default::default()),
+        Err(gix::repository::stash_apply::Error::Dirty)
+    ));
+    assert!(matches!(
+        repo.stash_apply(2, Default::default()),
+        Err(gix::repository::stash_apply::Error::NoSuchEntry { index: 2 })
+    ));
+    Ok(())
+}
+
+#[test]
+fn pop_with_conflicts_keeps_the_entry() -> crate::Result {
+    let (repo, _dir) = fixture("git-conflicting-stash")?;
+    let outcome = repo.stash_pop(0, Default::default())?;
+    assert_eq!(outcome.conflicting_paths, ["a"]);
+    assert_eq!(
+        read(&repo, "a")?,
+        "1\n2\n3\n4\n<<<<<<< Updated upstream\nupstream\n=======\nstashed\n>>>>>>> Stashed changes\n6\n7\n8\n9\n"
+    );
+    let index = repo.open_index()?;
+    let stages: Vec<_> = index
+        .entries()
+        .iter()
+        .filter(|entry| entry.path(&index) == "a")
+        .map(gix_index::Entry::stage_raw)
+        .collect();
+    assert_eq!(stages, [1, 2, 3], "'a' is conflicted in the index");
+    assert_eq!(repo.stash_list()?.len(), 1, "the entry is kept");
+    Ok(())
+}
+
+#[test]
+fn drop_rewrites_the_reflog() -> crate::Result {
+    let (repo, _dir) = fixture("git-stashes")?;
+    let newest = repo.rev_parse_single("refs/stash")?.detach();
+    let dropped = repo.stash_drop(1)?;
+    assert_eq!(dropped.message, "On main: first");
+    let entries = repo.stash_list()?;
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].id, newest);
+    assert_eq!(
+        repo.rev_parse_single("refs/stash")?,
+        newest,
+        "the reference is unchanged"
+    );
+    let log = std::fs::read_to_string(repo.git_dir().join("logs/refs/stash"))?;
+    assert!(
+        log.starts_with(&gix_hash::ObjectId::null(repo.object_hash()).to_string()),
+        "the previous value is rewritten to keep the log consistent"
+    );
+
+    let (repo, _dir) = fixture("git-stashes")?;
+    let oldest = repo.stash_list()?[1].id;
+    assert_eq!(repo.stash_drop(0)?.id, newest);
+    assert_eq!(
+        repo.rev_parse_single("refs/stash")?,
+        oldest,
+        "the reference points to the next entry"
+    );
+    assert_eq!(repo.stash_list()?.len(), 1, "no new reflog entry is added");
+
+    repo.stash_drop(0)?;
+    assert!(repo.try_find_reference("refs/stash")?.is_none());
+    assert!(!repo.git_dir().join("logs/refs/stash").exists());
+    assert!(matches!(
+        repo.stash_drop(0),
+        Err(gix::repository::stash_drop::Error::NoSuchEntry { index: 0 })
+    ));
+    Ok(())
+}
+
+#[test]
+fn branch_checks_out_the_base_and_applies_the_entry() -> crate::Result {
+    let (repo, _dir) = fixture("git-conflicting-stash")?;
+    let base = repo.rev_parse_single("refs/stash^1")?.detach();
+    let outcome = repo.stash_branch("refs/heads/from-stash", 0)?;
+    assert!(outcome.conflicting_paths.is_empty());
+
+    assert_eq!(
+        repo.head_name()?.expect("not detached").as_bstr(),
+        "refs/heads/from-stash"
+    );
+    assert_eq!(repo.head_id()?, base);
+    assert_eq!(read(&repo, "a")?, "1\n2\n3\n4\nstashed\n6\n7\n8\n9\n");
+    assert!(repo.stash_list()?.is_empty());
+    Ok(())
+}
@@ -197,6 +197,7 @@ pub fn main() -> Result<()> {
                         in_memory,
                         file_favor,
                         tree_favor,
+                        strategy_options,
                         debug,
                     },
                 message,
@@ -224,6 +225,7 @@ pub fn main() -> Result<()> {
                             file_favor: file_favor.map(Into::into),
                             in_memory,
                             tree_favor: tree_favor.map(Into::into),
+                            strategy_options,
                             debug,
                             message,
                             update_head,
@@ -237,8 +239,10 @@ pub fn main() -> Result<()> {
                         in_memory,
                         file_favor,
                         tree_favor,
+                        strategy_options,
                         debug,
                     },
+                strategy,
                 ours,
                 theirs,
             } => prepare_and_run(
@@ -255,10 +259,12 @@ pub fn main() -> Result<()> {
                         err,
                         ours,
                         theirs,
+                        strategy.map(Into::into).unwrap_or_default(),
                         core::repository::merge::tree::Options {
                             format,
                             file_favor: file_favor.map(Into::into),
                             tree_favor: tree_favor.map(Into::into),
+                            strategy_options,
                             in_memory,
                             debug,
                             message: None,
@@ -1705,6 +1711,10 @@ pub fn main() -> Result<()> {
             file,
             ranges,
             since,
+            ignore_revs,
+            moves,
+            copies,
+            ignore_whitespace,
         } => prepare_and_run(
             "blame",
             trace,
@@ -1712,20 +1722,45 @@ pub fn main() -> Result<()> {
             progress,
             progress_keep_open,
             None,
-            move |_progress, out, err| {
+            move |progress, out, err| {
                 let repo = repository(Mode::Lenient)?;
                 let diff_algorithm = repo.diff_algorithm()?;
+                let ignore_revs = ignore_revs
+                    .iter()
+                    .map(|rev| -> anyhow::Result<_> {
+                        Ok(repo.rev_parse_single(rev.as_str())?.object()?.peel_to_commit()?.id)
+                    })
+                    .collect::<Result<_, _>>()?;
 
                 core::repository::blame::blame_file(
                     repo,
                     &file,
                     gix::blame::Options {
                         diff_algorithm,
+                        whitespace: gix::diff::blob::Whitespace {
+                            ignore_all_space: ignore_whitespace,
+                            ..Default::default()
+                        },
                         ranges: gix::blame::BlameRanges::from_one_based_inclusive_ranges(ranges)?,
                         since,
                         rewrites: Some(gix::diff::Rewrites::default()),
                         debug_track_path: false,
+                        ignore_revs,
+                        mark_ignored_lines: false,
+                        mark_unblamable_lines: false,
+                        uncommitted_blob: None,
+                        reverse_until: None,
+                        moves_and_copies: (moves || copies > 0).then(|| gix::blame::MovesAndCopies {
+                            copies: match copies {
+                                0 => None,
+                                1 => Some(gix::blame::Copies::ModifiedFiles),
+                                2 => Some(gix::blame::Copies::AllFilesInCreatingCommit),
+                                _ => Some(gix::blame::Copies::AllFiles),
+                            },
+                            ..Default::default()
+                        }),
                     },
+                    progress,
                     out,
                     statistics.then_some(err),
                 )
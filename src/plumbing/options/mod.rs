@@ -180,6 +180,19 @@ pub enum Subcommands {
         /// Don't consider commits before the given date.
         #[clap(long,  value_parser=AsTime, value_name = "DATE")]
         since: Option<gix::date::Time>,
+        /// Attribute the lines changed by the given revision to its parents instead. Can be given multiple times.
+        #[clap(long = "ignore-rev", value_name = "REV", action=clap::ArgAction::Append)]
+        ignore_revs: Vec<String>,
+        /// Attribute lines that were moved within the file to the commit they were originally added in.
+        #[clap(short = 'M')]
+        moves: bool,
+        /// Also attribute lines that were copied from files modified in the same commit. Given twice, look in all files of
+        /// the commit that created the file, and given three times, in all files of every commit.
+        #[clap(short = 'C', action = clap::ArgAction::Count)]
+        copies: u8,
+        /// Ignore whitespace when comparing lines, so lines that only changed in whitespace keep their previous origin.
+        #[clap(short = 'w')]
+        ignore_whitespace: bool,
     },
     /// Generate shell completions to stdout or a directory.
     #[clap(visible_alias = "generate-completions", visible_alias = "shell-completions")]
@@ -476,6 +489,23 @@ pub mod merge {
         Ours,
     }
 
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, clap::ValueEnum)]
+    pub enum Strategy {
+        /// Merge both sides, recursively merging multiple merge-bases if needed.
+        Ort,
+        /// Ignore all changes of their side and use our tree.
+        Ours,
+    }
+
+    impl From<Strategy> for gix::merge::commit::Strategy {
+        fn from(value: Strategy) -> Self {
+            match value {
+                Strategy::Ort => gix::merge::commit::Strategy::Ort,
+                Strategy::Ours => gix::merge::commit::Strategy::Ours,
+            }
+        }
+    }
+
     impl From<FileFavor> for gix::merge::tree::FileFavor {
         fn from(value: FileFavor) -> Self {
             match value {
@@ -507,6 +537,9 @@ pub mod merge {
         /// Decide how to resolve conflicts in trees, i.e. modification/deletion. If unset, try to preserve both states and fail.
         #[clap(long, short = 't')]
         pub tree_favor: Option<TreeFavor>,
+        /// Adjust the merge like `git merge -X`, e.g. with `ours`, `ignore-space-change`, `renormalize` or `find-renames=<n>`.
+        #[clap(long = "strategy-option", short = 'X', value_name = "OPTION", value_parser = crate::shared::AsBString)]
+        pub strategy_options: Vec<BString>,
         /// Print additional information about conflicts for debugging.
         #[clap(long, short = 'd')]
         pub debug: bool,
@@ -564,6 +597,9 @@ pub mod merge {
         Commit {
             #[clap(flatten)]
             opts: SharedOptions,
+            /// The way to merge both commits. If unset, both sides are merged.
+            #[clap(long, short = 's')]
+            strategy: Option<Strategy>,
 
             /// A revspec to our committish.
             #[clap(value_name = "OURS", value_parser = crate::shared::AsBString)]